@@ -159,6 +159,14 @@ SECTIONS
         . = ALIGN(4);
     } > RAM
 
+    /* not cleared at startup, so it is retained across warm resets */
+    .persistent (NOLOAD) :
+    {
+        . = ALIGN(4);
+        *(.persistent*)
+        . = ALIGN(4);
+    } > RAM
+
     .stack (NOLOAD):
     {
         . = ALIGN(4);
@@ -0,0 +1,249 @@
+// Licensed under the Apache-2.0 license
+
+//! Authorize Debug Unlock Token command (0x0B)
+//!
+//! Sends the signed debug unlock token to the device for authorization. The token is
+//! larger than a single MCTP VDM message, so it is sent in chunks: each request carries
+//! the token contents starting at the given offset. Once the last chunk is received the
+//! token is authorized by Caliptra and the response reports the granted unlock level.
+
+use crate::codec::{VdmCodec, VdmCodecError};
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Size of the debug unlock token:
+/// length (4) | unique_device_identifier (32) | unlock_level (1) | reserved (3) |
+/// challenge (48) | ecc_public_key (96) | mldsa_public_key (2592) | ecc_signature (96) |
+/// mldsa_signature (4628)
+pub const DEBUG_UNLOCK_TOKEN_SIZE: usize = 7500;
+
+/// Maximum size of the token chunk sent in a single request. This is the largest
+/// dword-aligned chunk that fits in a 1024-byte MCTP VDM message.
+pub const MAX_TOKEN_CHUNK_SIZE: usize = 1008;
+
+/// Authorize Debug Unlock Token Request (fixed header part).
+///
+/// Request Payload:
+/// - Bytes 0:3 - offset (u32): Offset in bytes of the chunk into the token
+/// - Bytes 4:7 - data_size (u32): Size of the chunk in bytes
+/// - Bytes 8:N - data (u8[data_size]): Token contents
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct AuthorizeDebugUnlockTokenRequestHeader {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Offset in bytes into the token.
+    pub offset: u32,
+    /// Size of the chunk in bytes.
+    pub data_size: u32,
+}
+
+impl AuthorizeDebugUnlockTokenRequestHeader {
+    /// Create a new Authorize Debug Unlock Token request header.
+    pub fn new(offset: u32, data_size: u32) -> Self {
+        AuthorizeDebugUnlockTokenRequestHeader {
+            hdr: VdmMsgHeader::new_request(VdmCommand::AuthorizeDebugUnlockToken.into()),
+            offset,
+            data_size,
+        }
+    }
+}
+
+impl Default for AuthorizeDebugUnlockTokenRequestHeader {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+/// Authorize Debug Unlock Token Request with a variable-length token chunk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorizeDebugUnlockTokenRequest {
+    /// Request header.
+    pub header: AuthorizeDebugUnlockTokenRequestHeader,
+    /// Token chunk buffer.
+    pub data: [u8; MAX_TOKEN_CHUNK_SIZE],
+}
+
+impl AuthorizeDebugUnlockTokenRequest {
+    /// Create a new Authorize Debug Unlock Token request.
+    pub fn new(offset: u32, chunk: &[u8]) -> Self {
+        let data_size = chunk.len().min(MAX_TOKEN_CHUNK_SIZE);
+        let mut data = [0u8; MAX_TOKEN_CHUNK_SIZE];
+        data[..data_size].copy_from_slice(&chunk[..data_size]);
+
+        AuthorizeDebugUnlockTokenRequest {
+            header: AuthorizeDebugUnlockTokenRequestHeader::new(offset, data_size as u32),
+            data,
+        }
+    }
+
+    /// Get the actual chunk size.
+    pub fn data_size(&self) -> usize {
+        self.header.data_size as usize
+    }
+
+    /// Get a slice of the token chunk.
+    pub fn data(&self) -> &[u8] {
+        let size = self.data_size().min(MAX_TOKEN_CHUNK_SIZE);
+        &self.data[..size]
+    }
+}
+
+impl Default for AuthorizeDebugUnlockTokenRequest {
+    fn default() -> Self {
+        AuthorizeDebugUnlockTokenRequest {
+            header: AuthorizeDebugUnlockTokenRequestHeader::default(),
+            data: [0u8; MAX_TOKEN_CHUNK_SIZE],
+        }
+    }
+}
+
+impl VdmCodec for AuthorizeDebugUnlockTokenRequest {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, VdmCodecError> {
+        let header_size = core::mem::size_of::<AuthorizeDebugUnlockTokenRequestHeader>();
+        let data_size = self.data_size().min(MAX_TOKEN_CHUNK_SIZE);
+        let total_size = header_size + data_size;
+
+        if buffer.len() < total_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // Encode header
+        self.header.encode(buffer)?;
+
+        // Copy token chunk
+        buffer[header_size..total_size].copy_from_slice(&self.data[..data_size]);
+
+        Ok(total_size)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, VdmCodecError> {
+        let header_size = core::mem::size_of::<AuthorizeDebugUnlockTokenRequestHeader>();
+
+        if buffer.len() < header_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // A truncated chunk would corrupt the token, so a size above the maximum is
+        // rejected rather than clamped.
+        let header = AuthorizeDebugUnlockTokenRequestHeader::decode(buffer)?;
+        let data_size = header.data_size as usize;
+        if data_size > MAX_TOKEN_CHUNK_SIZE {
+            return Err(VdmCodecError::Unsupported);
+        }
+
+        if buffer.len() < header_size + data_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let mut data = [0u8; MAX_TOKEN_CHUNK_SIZE];
+        data[..data_size].copy_from_slice(&buffer[header_size..header_size + data_size]);
+
+        Ok(AuthorizeDebugUnlockTokenRequest { header, data })
+    }
+}
+
+/// Authorize Debug Unlock Token Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - unlock_level (u32): Granted debug unlock level, 0 until the last chunk
+///   of the token is authorized
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct AuthorizeDebugUnlockTokenResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// Granted debug unlock level.
+    pub unlock_level: u32,
+}
+
+impl AuthorizeDebugUnlockTokenResponse {
+    /// Create a new Authorize Debug Unlock Token response.
+    pub fn new(completion_code: u32, unlock_level: u32) -> Self {
+        AuthorizeDebugUnlockTokenResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::AuthorizeDebugUnlockToken.into()),
+            completion_code,
+            unlock_level,
+        }
+    }
+}
+
+impl Default for AuthorizeDebugUnlockTokenResponse {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_authorize_debug_unlock_token_request() {
+        let chunk = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88];
+        let req = AuthorizeDebugUnlockTokenRequest::new(0x3F0, &chunk);
+        assert!(req.header.hdr.is_request());
+        let command_code = req.header.hdr.command_code;
+        let offset = req.header.offset;
+        assert_eq!(command_code, VdmCommand::AuthorizeDebugUnlockToken as u8);
+        assert_eq!(offset, 0x3F0);
+        assert_eq!(req.data(), &chunk);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 8 + chunk.len());
+
+        let decoded = AuthorizeDebugUnlockTokenRequest::decode(&buffer[..size]).unwrap();
+        assert_eq!(req.header, decoded.header);
+        assert_eq!(req.data(), decoded.data());
+
+        // The chunk must be complete
+        assert_eq!(
+            AuthorizeDebugUnlockTokenRequest::decode(&buffer[..size - 1]),
+            Err(VdmCodecError::BufferTooShort)
+        );
+    }
+
+    #[test]
+    fn test_authorize_debug_unlock_token_request_max_chunk() {
+        let chunk = [0xA5u8; MAX_TOKEN_CHUNK_SIZE + 16];
+        let req = AuthorizeDebugUnlockTokenRequest::new(0, &chunk);
+        assert_eq!(req.data_size(), MAX_TOKEN_CHUNK_SIZE);
+
+        // A full chunk still fits in a 1024-byte message with the MCTP message type
+        let mut buffer = [0u8; 1024 - 1];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(
+            size,
+            core::mem::size_of::<AuthorizeDebugUnlockTokenRequestHeader>() + MAX_TOKEN_CHUNK_SIZE
+        );
+
+        // A chunk above the maximum is rejected
+        AuthorizeDebugUnlockTokenRequestHeader::new(0, MAX_TOKEN_CHUNK_SIZE as u32 + 1)
+            .encode(&mut buffer)
+            .unwrap();
+        assert_eq!(
+            AuthorizeDebugUnlockTokenRequest::decode(&buffer),
+            Err(VdmCodecError::Unsupported)
+        );
+    }
+
+    #[test]
+    fn test_authorize_debug_unlock_token_response() {
+        let resp = AuthorizeDebugUnlockTokenResponse::new(VdmCompletionCode::Success as u32, 4);
+        assert!(resp.hdr.is_response());
+        let unlock_level = resp.unlock_level;
+        assert_eq!(unlock_level, 4);
+
+        let mut buffer = [0u8; 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 8);
+
+        let decoded = AuthorizeDebugUnlockTokenResponse::decode(&buffer).unwrap();
+        assert_eq!(resp, decoded);
+    }
+}
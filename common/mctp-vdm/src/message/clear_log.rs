@@ -0,0 +1,104 @@
+// Licensed under the Apache-2.0 license
+
+//! Clear Log command (0x09)
+//!
+//! Clears the log information in the RoT subsystem.
+
+use crate::message::get_log::LogType;
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Clear Log Request.
+///
+/// Request Payload:
+/// - Bytes 0:3 - log_type (u32): Log Type
+///   - 0 = Debug Log
+///   - 1 = Attestation Log
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ClearLogRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Type of log to clear.
+    pub log_type: u32,
+}
+
+impl ClearLogRequest {
+    /// Create a new Clear Log request.
+    pub fn new(log_type: u32) -> Self {
+        ClearLogRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::ClearLog.into()),
+            log_type,
+        }
+    }
+}
+
+impl Default for ClearLogRequest {
+    fn default() -> Self {
+        Self::new(LogType::Debug as u32)
+    }
+}
+
+/// Clear Log Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ClearLogResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+}
+
+impl ClearLogResponse {
+    /// Create a new Clear Log response.
+    pub fn new(completion_code: u32) -> Self {
+        ClearLogResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::ClearLog.into()),
+            completion_code,
+        }
+    }
+}
+
+impl Default for ClearLogResponse {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::VdmCodec;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_clear_log_request() {
+        let req = ClearLogRequest::new(LogType::Debug as u32);
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        assert_eq!(command_code, VdmCommand::ClearLog as u8);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4);
+
+        let decoded = ClearLogRequest::decode(&buffer).unwrap();
+        assert_eq!(req, decoded);
+    }
+
+    #[test]
+    fn test_clear_log_response() {
+        let resp = ClearLogResponse::new(VdmCompletionCode::Success as u32);
+        assert!(resp.hdr.is_response());
+
+        let mut buffer = [0u8; 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4);
+
+        let decoded = ClearLogResponse::decode(&buffer).unwrap();
+        assert_eq!(resp, decoded);
+    }
+}
@@ -0,0 +1,228 @@
+// Licensed under the Apache-2.0 license
+
+//! Export CSR command (0x05)
+//!
+//! Exports the IDevID self-signed Certificate Signing Request.
+
+use crate::codec::{VdmCodec, VdmCodecError};
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Maximum size of the DER-encoded CSR.
+pub const MAX_CSR_SIZE: usize = 512;
+
+/// Export CSR index values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CsrIndex {
+    /// IDevID ECC CSR.
+    IdevEcc = 0x00,
+    /// IDevID MLDSA CSR.
+    IdevMldsa = 0x01,
+}
+
+/// Export CSR Request.
+///
+/// Request Payload:
+/// - Bytes 0:3 - index (u32): CSR Index
+///   - 0x00 = IDevID ECC CSR
+///   - 0x01 = IDevID MLDSA CSR
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ExportCsrRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Index of the CSR to export.
+    pub index: u32,
+}
+
+impl ExportCsrRequest {
+    /// Create a new Export CSR request.
+    pub fn new(index: u32) -> Self {
+        ExportCsrRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::ExportCsr.into()),
+            index,
+        }
+    }
+}
+
+impl Default for ExportCsrRequest {
+    fn default() -> Self {
+        Self::new(CsrIndex::IdevEcc as u32)
+    }
+}
+
+/// Export CSR Response (fixed header part).
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - data_size (u32): Length in bytes of the valid data in the data field
+/// - Bytes 8:N - data (u8[data_size]): DER-encoded IDevID certificate signing request
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ExportCsrResponseHeader {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// Size of the CSR in bytes.
+    pub data_size: u32,
+}
+
+impl ExportCsrResponseHeader {
+    /// Create a new Export CSR response header.
+    pub fn new(completion_code: u32, data_size: u32) -> Self {
+        ExportCsrResponseHeader {
+            hdr: VdmMsgHeader::new_response(VdmCommand::ExportCsr.into()),
+            completion_code,
+            data_size,
+        }
+    }
+}
+
+impl Default for ExportCsrResponseHeader {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+/// Export CSR Response with variable-length data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportCsrResponse {
+    /// Response header.
+    pub header: ExportCsrResponseHeader,
+    /// CSR buffer.
+    pub data: [u8; MAX_CSR_SIZE],
+}
+
+impl ExportCsrResponse {
+    /// Create a new Export CSR response.
+    pub fn new(completion_code: u32, csr: &[u8]) -> Self {
+        let data_size = csr.len().min(MAX_CSR_SIZE);
+        let mut data = [0u8; MAX_CSR_SIZE];
+        data[..data_size].copy_from_slice(&csr[..data_size]);
+
+        ExportCsrResponse {
+            header: ExportCsrResponseHeader::new(completion_code, data_size as u32),
+            data,
+        }
+    }
+
+    /// Get the actual CSR size.
+    pub fn data_size(&self) -> usize {
+        self.header.data_size as usize
+    }
+
+    /// Get a slice of the CSR.
+    pub fn data(&self) -> &[u8] {
+        let size = self.data_size().min(MAX_CSR_SIZE);
+        &self.data[..size]
+    }
+}
+
+impl Default for ExportCsrResponse {
+    fn default() -> Self {
+        ExportCsrResponse {
+            header: ExportCsrResponseHeader::default(),
+            data: [0u8; MAX_CSR_SIZE],
+        }
+    }
+}
+
+impl VdmCodec for ExportCsrResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, VdmCodecError> {
+        let header_size = core::mem::size_of::<ExportCsrResponseHeader>();
+        let data_size = self.data_size().min(MAX_CSR_SIZE);
+        let total_size = header_size + data_size;
+
+        if buffer.len() < total_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // Encode header
+        self.header.encode(buffer)?;
+
+        // Copy data
+        buffer[header_size..total_size].copy_from_slice(&self.data[..data_size]);
+
+        Ok(total_size)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, VdmCodecError> {
+        let header_size = core::mem::size_of::<ExportCsrResponseHeader>();
+
+        if buffer.len() < header_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let header = ExportCsrResponseHeader::decode(buffer)?;
+        let data_size = (header.data_size as usize).min(MAX_CSR_SIZE);
+
+        if buffer.len() < header_size + data_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let mut data = [0u8; MAX_CSR_SIZE];
+        data[..data_size].copy_from_slice(&buffer[header_size..header_size + data_size]);
+
+        Ok(ExportCsrResponse { header, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_export_csr_request() {
+        let req = ExportCsrRequest::new(CsrIndex::IdevMldsa as u32);
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        let index = req.index;
+        assert_eq!(command_code, VdmCommand::ExportCsr as u8);
+        assert_eq!(index, 1);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4);
+
+        let decoded = ExportCsrRequest::decode(&buffer).unwrap();
+        assert_eq!(req, decoded);
+    }
+
+    #[test]
+    fn test_export_csr_response() {
+        let csr = [0x30, 0x82, 0x01, 0x10, 0xAA, 0xBB];
+        let resp = ExportCsrResponse::new(VdmCompletionCode::Success as u32, &csr);
+        assert!(resp.header.hdr.is_response());
+        let data_size = resp.header.data_size;
+        assert_eq!(data_size, csr.len() as u32);
+        assert_eq!(resp.data(), &csr);
+
+        let mut buffer = [0u8; 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        let header_size = core::mem::size_of::<ExportCsrResponseHeader>();
+        assert_eq!(size, header_size + csr.len());
+
+        let decoded = ExportCsrResponse::decode(&buffer[..size]).unwrap();
+        assert_eq!(resp.header, decoded.header);
+        assert_eq!(resp.data(), decoded.data());
+
+        // The CSR must fit in the buffer
+        assert_eq!(
+            resp.encode(&mut buffer[..header_size]),
+            Err(VdmCodecError::BufferTooShort)
+        );
+    }
+
+    #[test]
+    fn test_export_csr_response_error() {
+        let resp = ExportCsrResponse::new(VdmCompletionCode::NotReady as u32, &[]);
+        assert_eq!(resp.data_size(), 0);
+
+        let mut buffer = [0u8; 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, core::mem::size_of::<ExportCsrResponseHeader>());
+    }
+}
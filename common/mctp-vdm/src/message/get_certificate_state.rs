@@ -0,0 +1,143 @@
+// Licensed under the Apache-2.0 license
+
+//! Get Certificate State command (0x07)
+//!
+//! Determines the state of the certificate chain for the signed certificates that have
+//! been sent to the device. The request contains no additional payload.
+
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Certificate chain state values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CertificateState {
+    /// A valid chain has been provisioned.
+    Provisioned = 0x00,
+    /// A valid chain has not been provisioned.
+    NotProvisioned = 0x01,
+    /// The stored chain is being validated.
+    Validating = 0x02,
+}
+
+/// Get Certificate State Request.
+///
+/// Request Payload: Empty (only header)
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetCertificateStateRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+}
+
+impl GetCertificateStateRequest {
+    /// Create a new Get Certificate State request.
+    pub fn new() -> Self {
+        GetCertificateStateRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::GetCertificateState.into()),
+        }
+    }
+}
+
+impl Default for GetCertificateStateRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get Certificate State Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - state (u32): State of the certificate chain
+///   - 0 = A valid chain has been provisioned
+///   - 1 = A valid chain has not been provisioned
+///   - 2 = The stored chain is being validated
+/// - Bytes 8:11 - error_details (u32): Error details if chain validation has failed
+/// - Bytes 12:15 - provisioned_slots (u32): Bit N is set if certificate slot N is provisioned
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetCertificateStateResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// State of the certificate chain.
+    pub state: u32,
+    /// Error details if chain validation has failed.
+    pub error_details: u32,
+    /// Bitmask of the provisioned certificate slots.
+    pub provisioned_slots: u32,
+}
+
+impl GetCertificateStateResponse {
+    /// Create a new Get Certificate State response.
+    pub fn new(
+        completion_code: u32,
+        state: u32,
+        error_details: u32,
+        provisioned_slots: u32,
+    ) -> Self {
+        GetCertificateStateResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::GetCertificateState.into()),
+            completion_code,
+            state,
+            error_details,
+            provisioned_slots,
+        }
+    }
+
+    /// Check whether the certificate slot is provisioned.
+    pub fn is_slot_provisioned(&self, slot: u32) -> bool {
+        slot < u32::BITS && self.provisioned_slots & (1 << slot) != 0
+    }
+}
+
+impl Default for GetCertificateStateResponse {
+    fn default() -> Self {
+        Self::new(0, CertificateState::NotProvisioned as u32, 0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::VdmCodec;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_get_certificate_state_request() {
+        let req = GetCertificateStateRequest::new();
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        assert_eq!(command_code, VdmCommand::GetCertificateState as u8);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN);
+
+        let decoded = GetCertificateStateRequest::decode(&buffer).unwrap();
+        assert_eq!(req, decoded);
+    }
+
+    #[test]
+    fn test_get_certificate_state_response() {
+        let resp = GetCertificateStateResponse::new(
+            VdmCompletionCode::Success as u32,
+            CertificateState::Provisioned as u32,
+            0,
+            0b01,
+        );
+        assert!(resp.hdr.is_response());
+        assert!(resp.is_slot_provisioned(0));
+        assert!(!resp.is_slot_provisioned(1));
+        assert!(!resp.is_slot_provisioned(u32::BITS));
+
+        let mut buffer = [0u8; 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 16);
+
+        let decoded = GetCertificateStateResponse::decode(&buffer).unwrap();
+        assert_eq!(resp, decoded);
+    }
+}
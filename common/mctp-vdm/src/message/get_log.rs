@@ -0,0 +1,242 @@
+// Licensed under the Apache-2.0 license
+
+//! Get Log command (0x08)
+//!
+//! Retrieves the debug log or the attestation log. The log is read in chunks: each
+//! response carries the log contents starting at the requested offset, and a response
+//! with no data marks the end of the log.
+
+use crate::codec::{VdmCodec, VdmCodecError};
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Maximum size of the log data returned in a single response. This is the largest
+/// dword-aligned chunk that fits in a 1024-byte MCTP VDM message.
+pub const MAX_LOG_DATA_SIZE: usize = 1008;
+
+/// Log type values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LogType {
+    /// Debug log.
+    Debug = 0x00,
+    /// Attestation log.
+    Attestation = 0x01,
+}
+
+/// Get Log Request.
+///
+/// Request Payload:
+/// - Bytes 0:3 - log_type (u32): Type of log to retrieve
+///   - 0 = Debug Log
+///   - 1 = Attestation Log
+/// - Bytes 4:7 - offset (u32): Offset in bytes into the log to read from
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetLogRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Type of log to retrieve.
+    pub log_type: u32,
+    /// Offset in bytes into the log.
+    pub offset: u32,
+}
+
+impl GetLogRequest {
+    /// Create a new Get Log request.
+    pub fn new(log_type: u32, offset: u32) -> Self {
+        GetLogRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::GetLog.into()),
+            log_type,
+            offset,
+        }
+    }
+}
+
+impl Default for GetLogRequest {
+    fn default() -> Self {
+        Self::new(LogType::Debug as u32, 0)
+    }
+}
+
+/// Get Log Response (fixed header part).
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - data_size (u32): Size of the log data in bytes
+/// - Bytes 8:N - data (u8[data_size]): Log contents
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct GetLogResponseHeader {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// Size of the log data in bytes.
+    pub data_size: u32,
+}
+
+impl GetLogResponseHeader {
+    /// Create a new Get Log response header.
+    pub fn new(completion_code: u32, data_size: u32) -> Self {
+        GetLogResponseHeader {
+            hdr: VdmMsgHeader::new_response(VdmCommand::GetLog.into()),
+            completion_code,
+            data_size,
+        }
+    }
+}
+
+impl Default for GetLogResponseHeader {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+/// Get Log Response with variable-length data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetLogResponse {
+    /// Response header.
+    pub header: GetLogResponseHeader,
+    /// Log data buffer.
+    pub data: [u8; MAX_LOG_DATA_SIZE],
+}
+
+impl GetLogResponse {
+    /// Create a new Get Log response.
+    pub fn new(completion_code: u32, log_data: &[u8]) -> Self {
+        let data_size = log_data.len().min(MAX_LOG_DATA_SIZE);
+        let mut data = [0u8; MAX_LOG_DATA_SIZE];
+        data[..data_size].copy_from_slice(&log_data[..data_size]);
+
+        GetLogResponse {
+            header: GetLogResponseHeader::new(completion_code, data_size as u32),
+            data,
+        }
+    }
+
+    /// Get the actual log data size.
+    pub fn data_size(&self) -> usize {
+        self.header.data_size as usize
+    }
+
+    /// Get a slice of the log data.
+    pub fn data(&self) -> &[u8] {
+        let size = self.data_size().min(MAX_LOG_DATA_SIZE);
+        &self.data[..size]
+    }
+}
+
+impl Default for GetLogResponse {
+    fn default() -> Self {
+        GetLogResponse {
+            header: GetLogResponseHeader::default(),
+            data: [0u8; MAX_LOG_DATA_SIZE],
+        }
+    }
+}
+
+impl VdmCodec for GetLogResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, VdmCodecError> {
+        let header_size = core::mem::size_of::<GetLogResponseHeader>();
+        let data_size = self.data_size().min(MAX_LOG_DATA_SIZE);
+        let total_size = header_size + data_size;
+
+        if buffer.len() < total_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // Encode header
+        self.header.encode(buffer)?;
+
+        // Copy data
+        buffer[header_size..total_size].copy_from_slice(&self.data[..data_size]);
+
+        Ok(total_size)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, VdmCodecError> {
+        let header_size = core::mem::size_of::<GetLogResponseHeader>();
+
+        if buffer.len() < header_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let header = GetLogResponseHeader::decode(buffer)?;
+        let data_size = (header.data_size as usize).min(MAX_LOG_DATA_SIZE);
+
+        if buffer.len() < header_size + data_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let mut data = [0u8; MAX_LOG_DATA_SIZE];
+        data[..data_size].copy_from_slice(&buffer[header_size..header_size + data_size]);
+
+        Ok(GetLogResponse { header, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_get_log_request() {
+        let req = GetLogRequest::new(LogType::Attestation as u32, 0x100);
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        let log_type = req.log_type;
+        let offset = req.offset;
+        assert_eq!(command_code, VdmCommand::GetLog as u8);
+        assert_eq!(log_type, 1);
+        assert_eq!(offset, 0x100);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 8);
+
+        let decoded = GetLogRequest::decode(&buffer).unwrap();
+        assert_eq!(req, decoded);
+    }
+
+    #[test]
+    fn test_get_log_response() {
+        let log_data = [0x08, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04];
+        let resp = GetLogResponse::new(VdmCompletionCode::Success as u32, &log_data);
+        assert!(resp.header.hdr.is_response());
+        let data_size = resp.header.data_size;
+        assert_eq!(data_size, log_data.len() as u32);
+        assert_eq!(resp.data(), &log_data);
+
+        let mut buffer = [0u8; 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        let header_size = core::mem::size_of::<GetLogResponseHeader>();
+        assert_eq!(size, header_size + log_data.len());
+
+        let decoded = GetLogResponse::decode(&buffer[..size]).unwrap();
+        assert_eq!(resp.header, decoded.header);
+        assert_eq!(resp.data(), decoded.data());
+
+        // The log data must be complete
+        assert_eq!(
+            GetLogResponse::decode(&buffer[..size - 1]),
+            Err(VdmCodecError::BufferTooShort)
+        );
+    }
+
+    #[test]
+    fn test_get_log_response_max_chunk() {
+        let log_data = [0xA5u8; MAX_LOG_DATA_SIZE + 16];
+        let resp = GetLogResponse::new(VdmCompletionCode::Success as u32, &log_data);
+        assert_eq!(resp.data_size(), MAX_LOG_DATA_SIZE);
+
+        // A full chunk still fits in a 1024-byte message with the MCTP message type
+        let mut buffer = [0u8; 1024 - 1];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(
+            size,
+            core::mem::size_of::<GetLogResponseHeader>() + MAX_LOG_DATA_SIZE
+        );
+    }
+}
@@ -0,0 +1,218 @@
+// Licensed under the Apache-2.0 license
+
+//! Import Certificate command (0x06)
+//!
+//! Imports the DER-encoded IDevID certificate endorsed by the CA. The certificate is
+//! added to the start of the certificate chain.
+
+use crate::codec::{VdmCodec, VdmCodecError};
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Maximum size of the DER-encoded certificate. This is the largest certificate that
+/// fits in a 1024-byte MCTP VDM message.
+pub const MAX_CERT_SIZE: usize = 1008;
+
+/// Import Certificate Request (fixed header part).
+///
+/// Request Payload:
+/// - Bytes 0:3 - cert_size (u32): Size of the DER-encoded IDevID certificate
+/// - Bytes 4:N - cert (u8[cert_size]): DER-encoded certificate
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ImportCertificateRequestHeader {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Size of the certificate in bytes.
+    pub cert_size: u32,
+}
+
+impl ImportCertificateRequestHeader {
+    /// Create a new Import Certificate request header.
+    pub fn new(cert_size: u32) -> Self {
+        ImportCertificateRequestHeader {
+            hdr: VdmMsgHeader::new_request(VdmCommand::ImportCertificate.into()),
+            cert_size,
+        }
+    }
+}
+
+impl Default for ImportCertificateRequestHeader {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Import Certificate Request with variable-length certificate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportCertificateRequest {
+    /// Request header.
+    pub header: ImportCertificateRequestHeader,
+    /// Certificate buffer.
+    pub cert: [u8; MAX_CERT_SIZE],
+}
+
+impl ImportCertificateRequest {
+    /// Create a new Import Certificate request.
+    pub fn new(cert: &[u8]) -> Self {
+        let cert_size = cert.len().min(MAX_CERT_SIZE);
+        let mut cert_data = [0u8; MAX_CERT_SIZE];
+        cert_data[..cert_size].copy_from_slice(&cert[..cert_size]);
+
+        ImportCertificateRequest {
+            header: ImportCertificateRequestHeader::new(cert_size as u32),
+            cert: cert_data,
+        }
+    }
+
+    /// Get the actual certificate size.
+    pub fn cert_size(&self) -> usize {
+        self.header.cert_size as usize
+    }
+
+    /// Get a slice of the certificate.
+    pub fn cert(&self) -> &[u8] {
+        let size = self.cert_size().min(MAX_CERT_SIZE);
+        &self.cert[..size]
+    }
+}
+
+impl Default for ImportCertificateRequest {
+    fn default() -> Self {
+        ImportCertificateRequest {
+            header: ImportCertificateRequestHeader::default(),
+            cert: [0u8; MAX_CERT_SIZE],
+        }
+    }
+}
+
+impl VdmCodec for ImportCertificateRequest {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, VdmCodecError> {
+        let header_size = core::mem::size_of::<ImportCertificateRequestHeader>();
+        let cert_size = self.cert_size().min(MAX_CERT_SIZE);
+        let total_size = header_size + cert_size;
+
+        if buffer.len() < total_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // Encode header
+        self.header.encode(buffer)?;
+
+        // Copy certificate
+        buffer[header_size..total_size].copy_from_slice(&self.cert[..cert_size]);
+
+        Ok(total_size)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, VdmCodecError> {
+        let header_size = core::mem::size_of::<ImportCertificateRequestHeader>();
+
+        if buffer.len() < header_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        // A truncated certificate is never imported, so a size above the maximum is
+        // rejected rather than clamped.
+        let header = ImportCertificateRequestHeader::decode(buffer)?;
+        let cert_size = header.cert_size as usize;
+        if cert_size > MAX_CERT_SIZE {
+            return Err(VdmCodecError::Unsupported);
+        }
+
+        if buffer.len() < header_size + cert_size {
+            return Err(VdmCodecError::BufferTooShort);
+        }
+
+        let mut cert = [0u8; MAX_CERT_SIZE];
+        cert[..cert_size].copy_from_slice(&buffer[header_size..header_size + cert_size]);
+
+        Ok(ImportCertificateRequest { header, cert })
+    }
+}
+
+/// Import Certificate Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct ImportCertificateResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+}
+
+impl ImportCertificateResponse {
+    /// Create a new Import Certificate response.
+    pub fn new(completion_code: u32) -> Self {
+        ImportCertificateResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::ImportCertificate.into()),
+            completion_code,
+        }
+    }
+}
+
+impl Default for ImportCertificateResponse {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_import_certificate_request() {
+        let cert = [0x30, 0x82, 0x02, 0x00, 0x11, 0x22, 0x33];
+        let req = ImportCertificateRequest::new(&cert);
+        assert!(req.header.hdr.is_request());
+        let command_code = req.header.hdr.command_code;
+        assert_eq!(command_code, VdmCommand::ImportCertificate as u8);
+        assert_eq!(req.cert(), &cert);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4 + cert.len());
+
+        let decoded = ImportCertificateRequest::decode(&buffer[..size]).unwrap();
+        assert_eq!(req.header, decoded.header);
+        assert_eq!(req.cert(), decoded.cert());
+
+        // The certificate must be complete
+        assert_eq!(
+            ImportCertificateRequest::decode(&buffer[..size - 1]),
+            Err(VdmCodecError::BufferTooShort)
+        );
+    }
+
+    #[test]
+    fn test_import_certificate_request_too_large() {
+        let mut buffer = [0u8; 64];
+        ImportCertificateRequestHeader::new(MAX_CERT_SIZE as u32 + 1)
+            .encode(&mut buffer)
+            .unwrap();
+        assert_eq!(
+            ImportCertificateRequest::decode(&buffer),
+            Err(VdmCodecError::Unsupported)
+        );
+    }
+
+    #[test]
+    fn test_import_certificate_response() {
+        let resp = ImportCertificateResponse::new(VdmCompletionCode::Success as u32);
+        assert!(resp.hdr.is_response());
+        let completion_code = resp.completion_code;
+        assert_eq!(completion_code, 0);
+
+        let mut buffer = [0u8; 64];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 4);
+
+        let decoded = ImportCertificateResponse::decode(&buffer).unwrap();
+        assert_eq!(resp, decoded);
+    }
+}
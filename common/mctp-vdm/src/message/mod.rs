@@ -1,11 +1,25 @@
 // Licensed under the Apache-2.0 license
 
+pub mod authorize_debug_unlock_token;
+pub mod clear_log;
 pub mod device_capabilities;
 pub mod device_id;
 pub mod device_info;
+pub mod export_csr;
 pub mod firmware_version;
+pub mod get_certificate_state;
+pub mod get_log;
+pub mod import_certificate;
+pub mod request_debug_unlock;
 
+pub use authorize_debug_unlock_token::*;
+pub use clear_log::*;
 pub use device_capabilities::*;
 pub use device_id::*;
 pub use device_info::*;
+pub use export_csr::*;
 pub use firmware_version::*;
+pub use get_certificate_state::*;
+pub use get_log::*;
+pub use import_certificate::*;
+pub use request_debug_unlock::*;
@@ -0,0 +1,160 @@
+// Licensed under the Apache-2.0 license
+
+//! Request Debug Unlock command (0x0A)
+//!
+//! Requests production debug unlock. The device returns the challenge generated by
+//! Caliptra, which is signed into the token sent with the Authorize Debug Unlock Token
+//! command.
+
+use crate::protocol::{VdmCommand, VdmMsgHeader};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Size of the device identifier returned in the challenge.
+pub const DEBUG_UNLOCK_UDI_SIZE: usize = 32;
+
+/// Size of the random number challenge.
+pub const DEBUG_UNLOCK_CHALLENGE_SIZE: usize = 48;
+
+/// Lowest debug unlock level.
+pub const MIN_DEBUG_UNLOCK_LEVEL: u8 = 1;
+
+/// Highest debug unlock level.
+pub const MAX_DEBUG_UNLOCK_LEVEL: u8 = 8;
+
+/// Length in DWORDs of the Request Debug Unlock request payload.
+pub const REQUEST_DEBUG_UNLOCK_LENGTH: u32 = 2;
+
+/// Length in DWORDs of the challenge returned in the Request Debug Unlock response.
+pub const DEBUG_UNLOCK_CHALLENGE_LENGTH: u32 =
+    ((4 + DEBUG_UNLOCK_UDI_SIZE + DEBUG_UNLOCK_CHALLENGE_SIZE) / 4) as u32;
+
+/// Request Debug Unlock Request.
+///
+/// Request Payload:
+/// - Bytes 0:3 - length (u32): Length of the message in DWORDs
+/// - Byte 4 - unlock_level (u8): Debug unlock level (1-8)
+/// - Bytes 5:7 - reserved (u8[3]): Reserved
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct RequestDebugUnlockRequest {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Length of the message in DWORDs.
+    pub length: u32,
+    /// Requested debug unlock level.
+    pub unlock_level: u8,
+    /// Reserved.
+    pub reserved: [u8; 3],
+}
+
+impl RequestDebugUnlockRequest {
+    /// Create a new Request Debug Unlock request.
+    pub fn new(unlock_level: u8) -> Self {
+        RequestDebugUnlockRequest {
+            hdr: VdmMsgHeader::new_request(VdmCommand::RequestDebugUnlock.into()),
+            length: REQUEST_DEBUG_UNLOCK_LENGTH,
+            unlock_level,
+            reserved: [0; 3],
+        }
+    }
+}
+
+impl Default for RequestDebugUnlockRequest {
+    fn default() -> Self {
+        Self::new(MIN_DEBUG_UNLOCK_LEVEL)
+    }
+}
+
+/// Request Debug Unlock Response.
+///
+/// Response Payload:
+/// - Bytes 0:3 - completion_code (u32): Command completion status
+/// - Bytes 4:7 - length (u32): Length of the challenge in DWORDs
+/// - Bytes 8:39 - unique_device_identifier (u8[32]): Device identifier of the Caliptra device
+/// - Bytes 40:87 - challenge (u8[48]): Random number challenge
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+pub struct RequestDebugUnlockResponse {
+    /// VDM message header.
+    pub hdr: VdmMsgHeader,
+    /// Command completion status.
+    pub completion_code: u32,
+    /// Length of the challenge in DWORDs.
+    pub length: u32,
+    /// Device identifier of the Caliptra device.
+    pub unique_device_identifier: [u8; DEBUG_UNLOCK_UDI_SIZE],
+    /// Random number challenge.
+    pub challenge: [u8; DEBUG_UNLOCK_CHALLENGE_SIZE],
+}
+
+impl RequestDebugUnlockResponse {
+    /// Create a new Request Debug Unlock response.
+    pub fn new(
+        completion_code: u32,
+        unique_device_identifier: &[u8; DEBUG_UNLOCK_UDI_SIZE],
+        challenge: &[u8; DEBUG_UNLOCK_CHALLENGE_SIZE],
+    ) -> Self {
+        RequestDebugUnlockResponse {
+            hdr: VdmMsgHeader::new_response(VdmCommand::RequestDebugUnlock.into()),
+            completion_code,
+            length: DEBUG_UNLOCK_CHALLENGE_LENGTH,
+            unique_device_identifier: *unique_device_identifier,
+            challenge: *challenge,
+        }
+    }
+}
+
+impl Default for RequestDebugUnlockResponse {
+    fn default() -> Self {
+        Self::new(
+            0,
+            &[0; DEBUG_UNLOCK_UDI_SIZE],
+            &[0; DEBUG_UNLOCK_CHALLENGE_SIZE],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::VdmCodec;
+    use crate::protocol::{VdmCompletionCode, VDM_MSG_HEADER_LEN};
+
+    #[test]
+    fn test_request_debug_unlock_request() {
+        let req = RequestDebugUnlockRequest::new(3);
+        assert!(req.hdr.is_request());
+        let command_code = req.hdr.command_code;
+        let length = req.length;
+        assert_eq!(command_code, VdmCommand::RequestDebugUnlock as u8);
+        assert_eq!(length, 2);
+        assert_eq!(req.unlock_level, 3);
+
+        let mut buffer = [0u8; 64];
+        let size = req.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 8);
+
+        let decoded = RequestDebugUnlockRequest::decode(&buffer).unwrap();
+        assert_eq!(req, decoded);
+    }
+
+    #[test]
+    fn test_request_debug_unlock_response() {
+        let udi = [0x5A; DEBUG_UNLOCK_UDI_SIZE];
+        let challenge = [0xC3; DEBUG_UNLOCK_CHALLENGE_SIZE];
+        let resp =
+            RequestDebugUnlockResponse::new(VdmCompletionCode::Success as u32, &udi, &challenge);
+        assert!(resp.hdr.is_response());
+        let length = resp.length;
+        assert_eq!(length, 21);
+
+        let mut buffer = [0u8; 128];
+        let size = resp.encode(&mut buffer).unwrap();
+        assert_eq!(size, VDM_MSG_HEADER_LEN + 88);
+
+        let decoded = RequestDebugUnlockResponse::decode(&buffer).unwrap();
+        assert_eq!(resp, decoded);
+        assert_eq!(decoded.unique_device_identifier, udi);
+        assert_eq!(decoded.challenge, challenge);
+    }
+}
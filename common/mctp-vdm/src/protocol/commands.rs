@@ -53,6 +53,13 @@ pub const SUPPORTED_COMMANDS: &[VdmCommand] = &[
     VdmCommand::DeviceCapabilities,
     VdmCommand::DeviceId,
     VdmCommand::DeviceInfo,
+    VdmCommand::ExportCsr,
+    VdmCommand::ImportCertificate,
+    VdmCommand::GetCertificateState,
+    VdmCommand::GetLog,
+    VdmCommand::ClearLog,
+    VdmCommand::RequestDebugUnlock,
+    VdmCommand::AuthorizeDebugUnlockToken,
 ];
 
 /// Check if a command is supported in the current implementation.
@@ -93,7 +100,12 @@ mod tests {
         assert!(is_command_supported(VdmCommand::DeviceCapabilities));
         assert!(is_command_supported(VdmCommand::DeviceId));
         assert!(is_command_supported(VdmCommand::DeviceInfo));
-        assert!(!is_command_supported(VdmCommand::GetLog));
-        assert!(!is_command_supported(VdmCommand::ClearLog));
+        assert!(is_command_supported(VdmCommand::ExportCsr));
+        assert!(is_command_supported(VdmCommand::ImportCertificate));
+        assert!(is_command_supported(VdmCommand::GetCertificateState));
+        assert!(is_command_supported(VdmCommand::GetLog));
+        assert!(is_command_supported(VdmCommand::ClearLog));
+        assert!(is_command_supported(VdmCommand::RequestDebugUnlock));
+        assert!(is_command_supported(VdmCommand::AuthorizeDebugUnlockToken));
     }
 }
@@ -11,6 +11,10 @@ pub const MCTP_VDM_MSG_TYPE: u8 = 0x7E;
 /// PCI Vendor ID for Caliptra (Microsoft).
 pub const CALIPTRA_PCI_VENDOR_ID: u16 = 0x1414;
 
+/// Version of the Caliptra VDM command set, reported in the MCTP
+/// Get Vendor Defined Message Support control response.
+pub const CALIPTRA_VDM_CMD_SET_VERSION: u16 = 0x0001;
+
 /// Length of the VDM message header in bytes.
 /// Header consists of: Vendor ID (2 bytes) + Request/Crypt byte (1 byte) + Command Code (1 byte)
 pub const VDM_MSG_HEADER_LEN: usize = 4;
@@ -31,6 +35,8 @@ pub enum VdmCompletionCode {
     NotReady = 0x04,
     /// Command is not supported.
     UnsupportedCommand = 0x05,
+    /// The target of the command is already provisioned.
+    AlreadyProvisioned = 0x06,
 }
 
 impl TryFrom<u32> for VdmCompletionCode {
@@ -44,6 +50,7 @@ impl TryFrom<u32> for VdmCompletionCode {
             0x03 => Ok(VdmCompletionCode::InvalidLength),
             0x04 => Ok(VdmCompletionCode::NotReady),
             0x05 => Ok(VdmCompletionCode::UnsupportedCommand),
+            0x06 => Ok(VdmCompletionCode::AlreadyProvisioned),
             _ => Err(VdmError::InvalidCompletionCode),
         }
     }
@@ -0,0 +1,48 @@
+// Licensed under the Apache-2.0 license
+
+//! Endpoint UUID reported in the MCTP Get Endpoint UUID control response.
+
+/// Length of an MCTP endpoint UUID in bytes.
+pub const ENDPOINT_UUID_LEN: usize = 16;
+
+/// Derives the endpoint UUID from the device unique ID.
+///
+/// The UUID is an RFC 4122 version 4 UUID whose random bits are taken from the
+/// unique ID, so it is stable across boots. Unique IDs longer than a UUID are
+/// folded in so every byte of the ID contributes.
+pub fn endpoint_uuid(unique_id: &[u8]) -> [u8; ENDPOINT_UUID_LEN] {
+    let mut uuid = [0u8; ENDPOINT_UUID_LEN];
+    for (i, byte) in unique_id.iter().enumerate() {
+        uuid[i % ENDPOINT_UUID_LEN] ^= byte;
+    }
+
+    // Version 4 in the high nibble of time_hi_and_version
+    uuid[6] = (uuid[6] & 0x0F) | 0x40;
+    // RFC 4122 variant in the two high bits of clock_seq_hi_and_reserved
+    uuid[8] = (uuid[8] & 0x3F) | 0x80;
+    uuid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_uuid_version_and_variant() {
+        let uuid = endpoint_uuid(&[0xFF; ENDPOINT_UUID_LEN]);
+        assert_eq!(uuid[6], 0x4F);
+        assert_eq!(uuid[8], 0xBF);
+        assert_eq!(&uuid[..6], &[0xFF; 6]);
+
+        let uuid = endpoint_uuid(&[]);
+        assert_eq!(uuid[6], 0x40);
+        assert_eq!(uuid[8], 0x80);
+    }
+
+    #[test]
+    fn test_endpoint_uuid_folds_long_unique_id() {
+        let mut unique_id = [0u8; 2 * ENDPOINT_UUID_LEN];
+        unique_id[ENDPOINT_UUID_LEN] = 0x5A;
+        assert_eq!(endpoint_uuid(&unique_id)[0], 0x5A);
+    }
+}
@@ -1,5 +1,7 @@
 // Licensed under the Apache-2.0 license
 
+pub mod endpoint_uuid;
 pub mod mctp_transport;
 
+pub use endpoint_uuid::*;
 pub use mctp_transport::*;
@@ -50,3 +50,22 @@ pub static TEST_DEVICE_CAPABILITIES: TestDeviceCapabilities = TestDeviceCapabili
     mcu_rom: [0x19, 0x1A, 0x1B, 0x1C],
     reserved: [0x00, 0x00, 0x00, 0x00],
 };
+
+// Number and size of the dummy debug log entries for testing purposes. Together they span
+// more than one MCTP VDM Get Log chunk.
+pub const TEST_DEBUG_LOG_ENTRY_COUNT: usize = 8;
+pub const TEST_DEBUG_LOG_ENTRY_SIZE: usize = 192;
+
+// Dummy debug log entry for testing purposes.
+pub fn test_debug_log_entry(index: usize) -> [u8; TEST_DEBUG_LOG_ENTRY_SIZE] {
+    let mut entry = [0u8; TEST_DEBUG_LOG_ENTRY_SIZE];
+    for (i, byte) in entry.iter_mut().enumerate() {
+        *byte = (index as u8).wrapping_mul(0x20).wrapping_add(i as u8);
+    }
+    entry
+}
+
+// Dummy measurement stashed for testing purposes, read back from the attestation log.
+pub const TEST_ATTESTATION_LOG_METADATA: [u8; 4] = *b"TEST";
+pub const TEST_ATTESTATION_LOG_MEASUREMENT: [u8; 48] = [0xA5; 48];
+pub const TEST_ATTESTATION_LOG_SVN: u32 = 3;
@@ -648,6 +648,7 @@ pub enum LogType {
 pub struct GetLogReq {
     pub hdr: MailboxReqHeader,
     pub log_type: u32,
+    pub offset: u32,
 }
 impl Request for GetLogReq {
     const ID: CommandId = CommandId::MC_GET_LOG;
@@ -27,6 +27,18 @@ pub enum PldmError {
     InvalidApplyResult,
     InvalidGetStatusReasonCode,
     InvalidAuxStateStatus,
+
+    InvalidRepositoryState,
+    InvalidPdrType,
+    InvalidSensorDataSize,
+    InvalidSensorOperationalState,
+    InvalidEventMessageGlobalEnable,
+    InvalidTransportProtocolType,
+    InvalidEventClass,
+
+    InvalidFruRecordType,
+    InvalidFruFieldEncoding,
+    InvalidFruFieldType,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1,5 +1,6 @@
 // Licensed under the Apache-2.0 license
 
+use crate::error::PldmError;
 use crate::protocol::base::{
     InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
 };
@@ -13,6 +14,18 @@ pub enum SelfContainedActivationRequest {
     ActivateSelfContainedComponents = 1,
 }
 
+impl TryFrom<u8> for SelfContainedActivationRequest {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(SelfContainedActivationRequest::NotActivateSelfContainedComponents),
+            1 => Ok(SelfContainedActivationRequest::ActivateSelfContainedComponents),
+            _ => Err(PldmError::InvalidData),
+        }
+    }
+}
+
 #[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
 #[repr(C, packed)]
 pub struct ActivateFirmwareRequest {
@@ -0,0 +1,143 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, TransferOperationFlag,
+    TransferRespFlag, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::firmware_update::FwUpdateCmd;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Sent by the UA to the FD to retrieve the device metadata of the FD, when the FD reported a
+/// non-zero FDMetaDataLength in the RequestUpdate response.
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetDeviceMetaDataRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub data_transfer_handle: u32,
+    pub transfer_operation_flag: u8,
+}
+
+impl GetDeviceMetaDataRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        data_transfer_handle: u32,
+        transfer_operation_flag: TransferOperationFlag,
+    ) -> Self {
+        GetDeviceMetaDataRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::FwUpdate,
+                FwUpdateCmd::GetDeviceMetaData as u8,
+            ),
+            data_transfer_handle,
+            transfer_operation_flag: transfer_operation_flag as u8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetDeviceMetaDataResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub next_data_transfer_handle: u32,
+    pub transfer_flag: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetDeviceMetaDataResponse<'a> {
+    pub fixed: GetDeviceMetaDataResponseFixed,
+    pub device_metadata: &'a [u8],
+}
+
+impl<'a> GetDeviceMetaDataResponse<'a> {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        next_data_transfer_handle: u32,
+        transfer_flag: TransferRespFlag,
+        device_metadata: &'a [u8],
+    ) -> Self {
+        GetDeviceMetaDataResponse {
+            fixed: GetDeviceMetaDataResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::FwUpdate,
+                    FwUpdateCmd::GetDeviceMetaData as u8,
+                ),
+                completion_code,
+                next_data_transfer_handle,
+                transfer_flag: transfer_flag as u8,
+            },
+            device_metadata,
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetDeviceMetaDataResponseFixed>() + self.device_metadata.len()
+    }
+}
+
+impl PldmCodec for GetDeviceMetaDataResponse<'_> {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let offset = self.fixed.encode(buffer)?;
+        buffer[offset..offset + self.device_metadata.len()].copy_from_slice(self.device_metadata);
+        Ok(offset + self.device_metadata.len())
+    }
+
+    // Decoding is not implemented for this struct. The caller should decode
+    // `GetDeviceMetaDataResponseFixed` and read the device metadata from the rest of the buffer.
+    fn decode(_buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        Err(PldmCodecError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_device_metadata_request() {
+        let request = GetDeviceMetaDataRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            0,
+            TransferOperationFlag::GetFirstPart,
+        );
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 5);
+        let decoded_request = GetDeviceMetaDataRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_device_metadata_response() {
+        let device_metadata = [0x3Cu8; 48];
+        let response = GetDeviceMetaDataResponse::new(
+            0x02,
+            0,
+            0,
+            TransferRespFlag::StartAndEnd,
+            &device_metadata,
+        );
+        let mut buffer = [0u8; 64];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, response.codec_size_in_bytes());
+
+        let fixed = GetDeviceMetaDataResponseFixed::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(fixed, response.fixed);
+        let data_offset = core::mem::size_of::<GetDeviceMetaDataResponseFixed>();
+        assert_eq!(&buffer[data_offset..bytes], &device_metadata[..]);
+        assert!(GetDeviceMetaDataResponse::decode(&buffer[..bytes]).is_err());
+        assert!(response.encode(&mut buffer[..bytes - 1]).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, TransferOperationFlag,
+    TransferRespFlag, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::firmware_update::FwUpdateCmd;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Sent by the FD to the UA to retrieve the device metadata that the UA previously obtained
+/// with GetDeviceMetaData, e.g. after the FD was reset to activate new firmware.
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetMetaDataRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub data_transfer_handle: u32,
+    pub transfer_operation_flag: u8,
+}
+
+impl GetMetaDataRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        data_transfer_handle: u32,
+        transfer_operation_flag: TransferOperationFlag,
+    ) -> Self {
+        GetMetaDataRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::FwUpdate,
+                FwUpdateCmd::GetMetaData as u8,
+            ),
+            data_transfer_handle,
+            transfer_operation_flag: transfer_operation_flag as u8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetMetaDataResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub next_data_transfer_handle: u32,
+    pub transfer_flag: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetMetaDataResponse<'a> {
+    pub fixed: GetMetaDataResponseFixed,
+    pub metadata: &'a [u8],
+}
+
+impl<'a> GetMetaDataResponse<'a> {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        next_data_transfer_handle: u32,
+        transfer_flag: TransferRespFlag,
+        metadata: &'a [u8],
+    ) -> Self {
+        GetMetaDataResponse {
+            fixed: GetMetaDataResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::FwUpdate,
+                    FwUpdateCmd::GetMetaData as u8,
+                ),
+                completion_code,
+                next_data_transfer_handle,
+                transfer_flag: transfer_flag as u8,
+            },
+            metadata,
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetMetaDataResponseFixed>() + self.metadata.len()
+    }
+}
+
+impl PldmCodec for GetMetaDataResponse<'_> {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let offset = self.fixed.encode(buffer)?;
+        buffer[offset..offset + self.metadata.len()].copy_from_slice(self.metadata);
+        Ok(offset + self.metadata.len())
+    }
+
+    // Decoding is not implemented for this struct. The caller should decode
+    // `GetMetaDataResponseFixed` and read the device metadata from the rest of the buffer.
+    fn decode(_buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        Err(PldmCodecError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_metadata_request() {
+        let request = GetMetaDataRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            64,
+            TransferOperationFlag::GetNextPart,
+        );
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 5);
+        let decoded_request = GetMetaDataRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_metadata_response() {
+        let metadata = [0x7Eu8; 96];
+        let response = GetMetaDataResponse::new(0x03, 0, 0, TransferRespFlag::End, &metadata[64..]);
+        let mut buffer = [0u8; 64];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, response.codec_size_in_bytes());
+
+        let fixed = GetMetaDataResponseFixed::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(fixed, response.fixed);
+        let data_offset = core::mem::size_of::<GetMetaDataResponseFixed>();
+        assert_eq!(&buffer[data_offset..bytes], &metadata[64..]);
+        assert!(GetMetaDataResponse::decode(&buffer[..bytes]).is_err());
+        assert!(response.encode(&mut buffer[..bytes - 1]).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, TransferOperationFlag,
+    TransferRespFlag, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::firmware_update::FwUpdateCmd;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Sent by the FD to the UA to retrieve the FirmwareDevicePackageData of the package, when it
+/// indicated in the RequestUpdate response that it will do so.
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetPackageDataRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub data_transfer_handle: u32,
+    pub transfer_operation_flag: u8,
+}
+
+impl GetPackageDataRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        data_transfer_handle: u32,
+        transfer_operation_flag: TransferOperationFlag,
+    ) -> Self {
+        GetPackageDataRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::FwUpdate,
+                FwUpdateCmd::GetPackageData as u8,
+            ),
+            data_transfer_handle,
+            transfer_operation_flag: transfer_operation_flag as u8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetPackageDataResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub next_data_transfer_handle: u32,
+    pub transfer_flag: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetPackageDataResponse<'a> {
+    pub fixed: GetPackageDataResponseFixed,
+    pub package_data: &'a [u8],
+}
+
+impl<'a> GetPackageDataResponse<'a> {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        next_data_transfer_handle: u32,
+        transfer_flag: TransferRespFlag,
+        package_data: &'a [u8],
+    ) -> Self {
+        GetPackageDataResponse {
+            fixed: GetPackageDataResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::FwUpdate,
+                    FwUpdateCmd::GetPackageData as u8,
+                ),
+                completion_code,
+                next_data_transfer_handle,
+                transfer_flag: transfer_flag as u8,
+            },
+            package_data,
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetPackageDataResponseFixed>() + self.package_data.len()
+    }
+}
+
+impl PldmCodec for GetPackageDataResponse<'_> {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let offset = self.fixed.encode(buffer)?;
+        buffer[offset..offset + self.package_data.len()].copy_from_slice(self.package_data);
+        Ok(offset + self.package_data.len())
+    }
+
+    // Decoding is not implemented for this struct. The caller should decode
+    // `GetPackageDataResponseFixed` and read the package data from the rest of the buffer.
+    fn decode(_buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        Err(PldmCodecError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_package_data_request() {
+        let request = GetPackageDataRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            0,
+            TransferOperationFlag::GetFirstPart,
+        );
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 5);
+        let decoded_request = GetPackageDataRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_package_data_response() {
+        let package_data = [0xA5u8; 64];
+        let response =
+            GetPackageDataResponse::new(0x01, 0, 32, TransferRespFlag::Start, &package_data[..32]);
+        let mut buffer = [0u8; 64];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, response.codec_size_in_bytes());
+
+        let fixed = GetPackageDataResponseFixed::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(fixed, response.fixed);
+        let data_offset = core::mem::size_of::<GetPackageDataResponseFixed>();
+        assert_eq!(&buffer[data_offset..bytes], &package_data[..32]);
+        assert!(GetPackageDataResponse::decode(&buffer[..bytes]).is_err());
+        assert!(response.encode(&mut buffer[..bytes - 1]).is_err());
+    }
+}
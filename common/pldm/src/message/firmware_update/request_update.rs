@@ -1,6 +1,7 @@
 // Licensed under the Apache-2.0 license
 
 use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
 use crate::protocol::base::{
     InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
 };
@@ -72,9 +73,8 @@ impl RequestUpdateRequest {
             str_len: self.fixed.comp_image_set_ver_str_len,
             str_data: {
                 let mut arr = [0u8; PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN];
-                arr.copy_from_slice(
-                    &self.comp_image_set_ver_str[..self.fixed.comp_image_set_ver_str_len as usize],
-                );
+                let len = self.fixed.comp_image_set_ver_str_len as usize;
+                arr[..len].copy_from_slice(&self.comp_image_set_ver_str[..len]);
                 arr
             },
         }
@@ -131,6 +131,29 @@ impl PldmCodec for RequestUpdateRequest {
     }
 }
 
+/// Value of the FDWillSendGetPackageDataCommand field of the RequestUpdate response.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum FdWillSendGetPackageData {
+    No = 0x00,
+    Yes = 0x01,
+    /// The response also carries GetPackageDataMaximumTransferSize.
+    YesWithMaxTransferSize = 0x02,
+}
+
+impl TryFrom<u8> for FdWillSendGetPackageData {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x00 => Ok(FdWillSendGetPackageData::No),
+            0x01 => Ok(FdWillSendGetPackageData::Yes),
+            0x02 => Ok(FdWillSendGetPackageData::YesWithMaxTransferSize),
+            _ => Err(PldmError::InvalidData),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, FromBytes, IntoBytes, Immutable, PartialEq, Default)]
 #[repr(C, packed)]
 pub struct RequestUpdateResponseFixed {
@@ -173,7 +196,9 @@ impl RequestUpdateResponse {
 
     pub fn codec_size_in_bytes(&self) -> usize {
         let mut bytes = core::mem::size_of::<RequestUpdateResponseFixed>();
-        if self.fixed.fd_will_send_pkg_data_cmd == 0x02 {
+        if self.fixed.fd_will_send_pkg_data_cmd
+            == FdWillSendGetPackageData::YesWithMaxTransferSize as u8
+        {
             bytes += core::mem::size_of::<u32>();
         }
         bytes
@@ -213,7 +238,9 @@ impl PldmCodec for RequestUpdateResponse {
         .unwrap();
         offset += core::mem::size_of::<RequestUpdateResponseFixed>();
 
-        let get_pkg_data_max_transfer_size = if fixed.fd_will_send_pkg_data_cmd == 0x02 {
+        let get_pkg_data_max_transfer_size = if fixed.fd_will_send_pkg_data_cmd
+            == FdWillSendGetPackageData::YesWithMaxTransferSize as u8
+        {
             Some(
                 u32::read_from_bytes(
                     buffer
@@ -256,6 +283,10 @@ mod tests {
 
         let decoded_request = RequestUpdateRequest::decode(&buffer[..encoded_size]).unwrap();
         assert_eq!(request, decoded_request);
+        assert_eq!(
+            decoded_request.get_comp_image_set_ver_str(),
+            PldmFirmwareString::new("ASCII", "mcu-1.0.0").unwrap()
+        );
     }
 
     #[test]
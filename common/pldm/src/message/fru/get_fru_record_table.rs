@@ -0,0 +1,137 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, TransferOperationFlag,
+    TransferRespFlag, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::fru::FruCmd;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetFruRecordTableRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub data_transfer_handle: u32,
+    pub transfer_operation_flag: u8,
+}
+
+impl GetFruRecordTableRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        data_transfer_handle: u32,
+        transfer_operation_flag: TransferOperationFlag,
+    ) -> Self {
+        GetFruRecordTableRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::Fru,
+                FruCmd::GetFruRecordTable as u8,
+            ),
+            data_transfer_handle,
+            transfer_operation_flag: transfer_operation_flag as u8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetFruRecordTableResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub next_data_transfer_handle: u32,
+    pub transfer_flag: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetFruRecordTableResponse<'a> {
+    pub fixed: GetFruRecordTableResponseFixed,
+    /// A portion of the FRU record table. The last portion includes the pad bytes of the table.
+    pub table_data: &'a [u8],
+}
+
+impl<'a> GetFruRecordTableResponse<'a> {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        next_data_transfer_handle: u32,
+        transfer_flag: TransferRespFlag,
+        table_data: &'a [u8],
+    ) -> Self {
+        GetFruRecordTableResponse {
+            fixed: GetFruRecordTableResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::Fru,
+                    FruCmd::GetFruRecordTable as u8,
+                ),
+                completion_code,
+                next_data_transfer_handle,
+                transfer_flag: transfer_flag as u8,
+            },
+            table_data,
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetFruRecordTableResponseFixed>() + self.table_data.len()
+    }
+}
+
+impl PldmCodec for GetFruRecordTableResponse<'_> {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let offset = self.fixed.encode(buffer)?;
+        buffer[offset..offset + self.table_data.len()].copy_from_slice(self.table_data);
+        Ok(offset + self.table_data.len())
+    }
+
+    // Decoding is not implemented for this struct. The caller should decode
+    // `GetFruRecordTableResponseFixed` and read the table data from the rest of the buffer.
+    fn decode(_buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        Err(PldmCodecError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_fru_record_table_request() {
+        let request = GetFruRecordTableRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            0,
+            TransferOperationFlag::GetFirstPart,
+        );
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 5);
+        let decoded_request = GetFruRecordTableRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_fru_record_table_response() {
+        let table = [0x5Au8; 24];
+        let response =
+            GetFruRecordTableResponse::new(0x01, 0, 16, TransferRespFlag::Start, &table[..16]);
+        let mut buffer = [0u8; 64];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, response.codec_size_in_bytes());
+
+        let fixed = GetFruRecordTableResponseFixed::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(fixed, response.fixed);
+        let data_offset = core::mem::size_of::<GetFruRecordTableResponseFixed>();
+        assert_eq!(&buffer[data_offset..bytes], &table[..16]);
+        assert!(GetFruRecordTableResponse::decode(&buffer[..bytes]).is_err());
+        assert!(response.encode(&mut buffer[..bytes - 1]).is_err());
+    }
+}
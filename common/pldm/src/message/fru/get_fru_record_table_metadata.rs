@@ -0,0 +1,116 @@
+// Licensed under the Apache-2.0 license
+
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::fru::{FruCmd, PLDM_FRU_DATA_MAJOR_VERSION, PLDM_FRU_DATA_MINOR_VERSION};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Computes the CRC-32 (ISO/IEC 8802-3) of the FRU record table, including its pad bytes,
+/// which is reported as the integrity checksum by `GetFRURecordTableMetadata`.
+pub fn fru_table_crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetFruRecordTableMetadataRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+}
+
+impl GetFruRecordTableMetadataRequest {
+    pub fn new(instance_id: InstanceId, msg_type: PldmMsgType) -> Self {
+        GetFruRecordTableMetadataRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::Fru,
+                FruCmd::GetFruRecordTableMetadata as u8,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetFruRecordTableMetadataResponse {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub fru_data_major_version: u8,
+    pub fru_data_minor_version: u8,
+    pub fru_table_maximum_size: u32,
+    pub fru_table_length: u32,
+    pub total_record_set_identifiers: u16,
+    pub total_table_records: u16,
+    pub checksum: u32,
+}
+
+impl GetFruRecordTableMetadataResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        fru_table_maximum_size: u32,
+        fru_table_length: u32,
+        total_record_set_identifiers: u16,
+        total_table_records: u16,
+        checksum: u32,
+    ) -> Self {
+        GetFruRecordTableMetadataResponse {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                PldmMsgType::Response,
+                PldmSupportedType::Fru,
+                FruCmd::GetFruRecordTableMetadata as u8,
+            ),
+            completion_code,
+            fru_data_major_version: PLDM_FRU_DATA_MAJOR_VERSION,
+            fru_data_minor_version: PLDM_FRU_DATA_MINOR_VERSION,
+            fru_table_maximum_size,
+            fru_table_length,
+            total_record_set_identifiers,
+            total_table_records,
+            checksum,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::codec::PldmCodec;
+
+    #[test]
+    fn test_fru_table_crc32() {
+        assert_eq!(fru_table_crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_get_fru_record_table_metadata_request() {
+        let request = GetFruRecordTableMetadataRequest::new(0x01, PldmMsgType::Request);
+        let mut buffer = [0u8; PLDM_MSG_HEADER_LEN];
+        request.encode(&mut buffer).unwrap();
+        let decoded_request = GetFruRecordTableMetadataRequest::decode(&buffer).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_fru_record_table_metadata_response() {
+        let response = GetFruRecordTableMetadataResponse::new(0x01, 0, 512, 120, 1, 1, 0x1234_5678);
+        let mut buffer = [0u8; 32];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 19);
+        let decoded_response = GetFruRecordTableMetadataResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+}
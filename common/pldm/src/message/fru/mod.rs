@@ -0,0 +1,4 @@
+// Licensed under the Apache-2.0 license
+
+pub mod get_fru_record_table;
+pub mod get_fru_record_table_metadata;
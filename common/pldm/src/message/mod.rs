@@ -2,3 +2,5 @@
 
 pub mod control;
 pub mod firmware_update;
+pub mod fru;
+pub mod platform;
@@ -0,0 +1,210 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, TransferOperationFlag,
+    PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::{PdrRecordHandle, PdrTransferFlag, PlatformCmd};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// Computes the CRC-8 (polynomial x^8 + x^2 + x + 1) of a PDR, which is carried in the
+/// last part of a multipart `GetPDR` transfer.
+pub fn pdr_crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetPdrRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub record_handle: PdrRecordHandle,
+    pub data_transfer_handle: u32,
+    pub transfer_operation_flag: u8,
+    pub request_count: u16,
+    pub record_change_number: u16,
+}
+
+impl GetPdrRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        record_handle: PdrRecordHandle,
+        data_transfer_handle: u32,
+        transfer_operation_flag: TransferOperationFlag,
+        request_count: u16,
+        record_change_number: u16,
+    ) -> Self {
+        GetPdrRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::Platform,
+                PlatformCmd::GetPdr as u8,
+            ),
+            record_handle,
+            data_transfer_handle,
+            transfer_operation_flag: transfer_operation_flag as u8,
+            request_count,
+            record_change_number,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetPdrResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub next_record_handle: PdrRecordHandle,
+    pub next_data_transfer_handle: u32,
+    pub transfer_flag: u8,
+    pub response_count: u16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetPdrResponse<'a> {
+    pub fixed: GetPdrResponseFixed,
+    pub record_data: &'a [u8],
+    /// Present only when the transfer flag is `End`.
+    pub transfer_crc: Option<u8>,
+}
+
+impl<'a> GetPdrResponse<'a> {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        next_record_handle: PdrRecordHandle,
+        next_data_transfer_handle: u32,
+        transfer_flag: PdrTransferFlag,
+        record_data: &'a [u8],
+        transfer_crc: Option<u8>,
+    ) -> Self {
+        GetPdrResponse {
+            fixed: GetPdrResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::Platform,
+                    PlatformCmd::GetPdr as u8,
+                ),
+                completion_code,
+                next_record_handle,
+                next_data_transfer_handle,
+                transfer_flag: transfer_flag as u8,
+                response_count: record_data.len() as u16,
+            },
+            record_data,
+            transfer_crc: if transfer_flag == PdrTransferFlag::End {
+                transfer_crc
+            } else {
+                None
+            },
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetPdrResponseFixed>()
+            + self.record_data.len()
+            + self.transfer_crc.map_or(0, |_| 1)
+    }
+}
+
+impl PldmCodec for GetPdrResponse<'_> {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = self.fixed.encode(buffer)?;
+        buffer[offset..offset + self.record_data.len()].copy_from_slice(self.record_data);
+        offset += self.record_data.len();
+        if let Some(crc) = self.transfer_crc {
+            buffer[offset] = crc;
+            offset += 1;
+        }
+        Ok(offset)
+    }
+
+    // Decoding is not implemented for this struct. The caller should decode `GetPdrResponseFixed`
+    // and use the `response_count` field to read the record data from the buffer.
+    fn decode(_buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        Err(PldmCodecError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pdr_crc8() {
+        assert_eq!(pdr_crc8(b"123456789"), 0xF4);
+    }
+
+    #[test]
+    fn test_get_pdr_request() {
+        let request = GetPdrRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            0,
+            0,
+            TransferOperationFlag::GetFirstPart,
+            64,
+            0,
+        );
+        let mut buffer = [0u8; 32];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 13);
+        let decoded_request = GetPdrRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_pdr_response() {
+        let record = [0xAAu8; 20];
+        let response = GetPdrResponse::new(
+            0x01,
+            0,
+            2,
+            0,
+            PdrTransferFlag::End,
+            &record[12..],
+            Some(pdr_crc8(&record)),
+        );
+        let mut buffer = [0u8; 64];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, response.codec_size_in_bytes());
+
+        let fixed = GetPdrResponseFixed::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(fixed, response.fixed);
+        let data_offset = core::mem::size_of::<GetPdrResponseFixed>();
+        let data_len = fixed.response_count as usize;
+        assert_eq!(&buffer[data_offset..data_offset + data_len], &record[12..]);
+        assert_eq!(buffer[bytes - 1], pdr_crc8(&record));
+        assert!(GetPdrResponse::decode(&buffer[..bytes]).is_err());
+
+        // No CRC unless the transfer flag is End
+        let response = GetPdrResponse::new(
+            0x01,
+            0,
+            2,
+            0,
+            PdrTransferFlag::StartAndEnd,
+            &record,
+            Some(0),
+        );
+        assert_eq!(response.transfer_crc, None);
+    }
+}
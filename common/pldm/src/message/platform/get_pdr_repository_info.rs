@@ -0,0 +1,121 @@
+// Licensed under the Apache-2.0 license
+
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::{PdrRepositoryState, PlatformCmd, PLDM_TIMESTAMP104_LEN};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetPdrRepositoryInfoRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+}
+
+impl GetPdrRepositoryInfoRequest {
+    pub fn new(instance_id: InstanceId, msg_type: PldmMsgType) -> Self {
+        GetPdrRepositoryInfoRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::Platform,
+                PlatformCmd::GetPdrRepositoryInfo as u8,
+            ),
+        }
+    }
+}
+
+/// Summary of the PDR repository reported by `GetPDRRepositoryInfo`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PdrRepositoryInfo {
+    pub repository_state: PdrRepositoryState,
+    pub update_time: [u8; PLDM_TIMESTAMP104_LEN],
+    pub oem_update_time: [u8; PLDM_TIMESTAMP104_LEN],
+    pub record_count: u32,
+    pub repository_size: u32,
+    pub largest_record_size: u32,
+    pub data_transfer_handle_timeout: u8,
+}
+
+impl Default for PdrRepositoryInfo {
+    fn default() -> Self {
+        PdrRepositoryInfo {
+            repository_state: PdrRepositoryState::Available,
+            update_time: [0u8; PLDM_TIMESTAMP104_LEN],
+            oem_update_time: [0u8; PLDM_TIMESTAMP104_LEN],
+            record_count: 0,
+            repository_size: 0,
+            largest_record_size: 0,
+            data_transfer_handle_timeout: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetPdrRepositoryInfoResponse {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub repository_state: u8,
+    pub update_time: [u8; PLDM_TIMESTAMP104_LEN],
+    pub oem_update_time: [u8; PLDM_TIMESTAMP104_LEN],
+    pub record_count: u32,
+    pub repository_size: u32,
+    pub largest_record_size: u32,
+    pub data_transfer_handle_timeout: u8,
+}
+
+impl GetPdrRepositoryInfoResponse {
+    pub fn new(instance_id: InstanceId, completion_code: u8, info: &PdrRepositoryInfo) -> Self {
+        GetPdrRepositoryInfoResponse {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                PldmMsgType::Response,
+                PldmSupportedType::Platform,
+                PlatformCmd::GetPdrRepositoryInfo as u8,
+            ),
+            completion_code,
+            repository_state: info.repository_state as u8,
+            update_time: info.update_time,
+            oem_update_time: info.oem_update_time,
+            record_count: info.record_count,
+            repository_size: info.repository_size,
+            largest_record_size: info.largest_record_size,
+            data_transfer_handle_timeout: info.data_transfer_handle_timeout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::codec::PldmCodec;
+
+    #[test]
+    fn test_get_pdr_repository_info_request() {
+        let request = GetPdrRepositoryInfoRequest::new(0x01, PldmMsgType::Request);
+        let mut buffer = [0u8; PLDM_MSG_HEADER_LEN];
+        request.encode(&mut buffer).unwrap();
+        let decoded_request = GetPdrRepositoryInfoRequest::decode(&buffer).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_pdr_repository_info_response() {
+        let info = PdrRepositoryInfo {
+            record_count: 3,
+            repository_size: 220,
+            largest_record_size: 105,
+            ..Default::default()
+        };
+        let response = GetPdrRepositoryInfoResponse::new(0x01, 0, &info);
+        let mut buffer = [0u8; 64];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(
+            bytes,
+            PLDM_MSG_HEADER_LEN + 2 + 2 * PLDM_TIMESTAMP104_LEN + 13
+        );
+        let decoded_response = GetPdrRepositoryInfoResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+}
@@ -0,0 +1,164 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::{
+    NumericSensorState, PlatformCmd, SensorDataSize, SensorEventMessageEnable, SensorId,
+    SensorOperationalState, SensorValue,
+};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetSensorReadingRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub sensor_id: SensorId,
+    pub rearm_event_state: u8,
+}
+
+impl GetSensorReadingRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        sensor_id: SensorId,
+        rearm_event_state: bool,
+    ) -> Self {
+        GetSensorReadingRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::Platform,
+                PlatformCmd::GetSensorReading as u8,
+            ),
+            sensor_id,
+            rearm_event_state: rearm_event_state as u8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetSensorReadingResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub sensor_data_size: u8,
+    pub sensor_operational_state: u8,
+    pub sensor_event_message_enable: u8,
+    pub present_state: u8,
+    pub previous_state: u8,
+    pub event_state: u8,
+}
+
+/// Present, previous and event state of a numeric sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericSensorStates {
+    pub present_state: NumericSensorState,
+    pub previous_state: NumericSensorState,
+    pub event_state: NumericSensorState,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetSensorReadingResponse {
+    pub fixed: GetSensorReadingResponseFixed,
+    pub present_reading: SensorValue,
+}
+
+impl GetSensorReadingResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        sensor_operational_state: SensorOperationalState,
+        sensor_event_message_enable: SensorEventMessageEnable,
+        states: NumericSensorStates,
+        present_reading: SensorValue,
+    ) -> Self {
+        GetSensorReadingResponse {
+            fixed: GetSensorReadingResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::Platform,
+                    PlatformCmd::GetSensorReading as u8,
+                ),
+                completion_code,
+                sensor_data_size: present_reading.data_size() as u8,
+                sensor_operational_state: sensor_operational_state as u8,
+                sensor_event_message_enable: sensor_event_message_enable as u8,
+                present_state: states.present_state as u8,
+                previous_state: states.previous_state as u8,
+                event_state: states.event_state as u8,
+            },
+            present_reading,
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetSensorReadingResponseFixed>()
+            + self.present_reading.codec_size_in_bytes()
+    }
+}
+
+impl PldmCodec for GetSensorReadingResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let offset = self.fixed.encode(buffer)?;
+        Ok(offset + self.present_reading.encode(&mut buffer[offset..])?)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let fixed = GetSensorReadingResponseFixed::decode(buffer)?;
+        let data_size = SensorDataSize::try_from(fixed.sensor_data_size)
+            .map_err(|_| PldmCodecError::Unsupported)?;
+        let present_reading = SensorValue::decode(
+            data_size,
+            &buffer[core::mem::size_of::<GetSensorReadingResponseFixed>()..],
+        )?;
+        Ok(GetSensorReadingResponse {
+            fixed,
+            present_reading,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_sensor_reading_request() {
+        let request = GetSensorReadingRequest::new(0x01, PldmMsgType::Request, 0x0002, true);
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 3);
+        let decoded_request = GetSensorReadingRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_sensor_reading_response() {
+        let response = GetSensorReadingResponse::new(
+            0x01,
+            0,
+            SensorOperationalState::Enabled,
+            SensorEventMessageEnable::EventsEnabled,
+            NumericSensorStates {
+                present_state: NumericSensorState::UpperWarning,
+                previous_state: NumericSensorState::Normal,
+                event_state: NumericSensorState::UpperWarning,
+            },
+            SensorValue::Uint32(85),
+        );
+        let mut buffer = [0u8; 32];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 7 + 4);
+        let decoded_response = GetSensorReadingResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+
+        assert!(GetSensorReadingResponse::decode(&buffer[..bytes - 1]).is_err());
+    }
+}
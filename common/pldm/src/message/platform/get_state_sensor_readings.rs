@@ -0,0 +1,176 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::{PlatformCmd, SensorId, PLDM_MAX_COMPOSITE_SENSOR_COUNT};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetStateSensorReadingsRequest {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub sensor_id: SensorId,
+    /// Bit N requests a rearm of the event state of composite sensor N.
+    pub sensor_rearm: u8,
+    pub reserved: u8,
+}
+
+impl GetStateSensorReadingsRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        sensor_id: SensorId,
+        sensor_rearm: u8,
+    ) -> Self {
+        GetStateSensorReadingsRequest {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                msg_type,
+                PldmSupportedType::Platform,
+                PlatformCmd::GetStateSensorReadings as u8,
+            ),
+            sensor_id,
+            sensor_rearm,
+            reserved: 0,
+        }
+    }
+}
+
+/// State of one composite sensor of a state sensor.
+#[derive(Debug, Clone, Copy, Default, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct StateField {
+    pub sensor_operational_state: u8,
+    pub present_state: u8,
+    pub previous_state: u8,
+    pub event_state: u8,
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct GetStateSensorReadingsResponseFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub composite_sensor_count: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetStateSensorReadingsResponse {
+    pub fixed: GetStateSensorReadingsResponseFixed,
+    pub state_fields: [StateField; PLDM_MAX_COMPOSITE_SENSOR_COUNT],
+}
+
+impl GetStateSensorReadingsResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        state_fields: &[StateField],
+    ) -> Result<Self, PldmError> {
+        if state_fields.is_empty() || state_fields.len() > PLDM_MAX_COMPOSITE_SENSOR_COUNT {
+            return Err(PldmError::InvalidData);
+        }
+        let mut fields = [StateField::default(); PLDM_MAX_COMPOSITE_SENSOR_COUNT];
+        fields[..state_fields.len()].copy_from_slice(state_fields);
+
+        Ok(GetStateSensorReadingsResponse {
+            fixed: GetStateSensorReadingsResponseFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    PldmMsgType::Response,
+                    PldmSupportedType::Platform,
+                    PlatformCmd::GetStateSensorReadings as u8,
+                ),
+                completion_code,
+                composite_sensor_count: state_fields.len() as u8,
+            },
+            state_fields: fields,
+        })
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<GetStateSensorReadingsResponseFixed>()
+            + self.fixed.composite_sensor_count as usize * core::mem::size_of::<StateField>()
+    }
+}
+
+impl PldmCodec for GetStateSensorReadingsResponse {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        let count = self.fixed.composite_sensor_count as usize;
+        if count > PLDM_MAX_COMPOSITE_SENSOR_COUNT {
+            return Err(PldmCodecError::Unsupported);
+        }
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = self.fixed.encode(buffer)?;
+        for field in &self.state_fields[..count] {
+            offset += field.encode(&mut buffer[offset..])?;
+        }
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let fixed = GetStateSensorReadingsResponseFixed::decode(buffer)?;
+        let count = fixed.composite_sensor_count as usize;
+        if count > PLDM_MAX_COMPOSITE_SENSOR_COUNT {
+            return Err(PldmCodecError::Unsupported);
+        }
+
+        let mut offset = core::mem::size_of::<GetStateSensorReadingsResponseFixed>();
+        let mut state_fields = [StateField::default(); PLDM_MAX_COMPOSITE_SENSOR_COUNT];
+        for field in state_fields.iter_mut().take(count) {
+            *field = StateField::decode(buffer.get(offset..).unwrap_or(&[]))?;
+            offset += core::mem::size_of::<StateField>();
+        }
+        Ok(GetStateSensorReadingsResponse {
+            fixed,
+            state_fields,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_state_sensor_readings_request() {
+        let request = GetStateSensorReadingsRequest::new(0x01, PldmMsgType::Request, 0x0003, 0x01);
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 4);
+        let decoded_request = GetStateSensorReadingsRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_get_state_sensor_readings_response() {
+        let fields = [
+            StateField {
+                sensor_operational_state: 0,
+                present_state: 2,
+                previous_state: 1,
+                event_state: 2,
+            },
+            StateField {
+                sensor_operational_state: 0,
+                present_state: 1,
+                previous_state: 1,
+                event_state: 1,
+            },
+        ];
+        let response = GetStateSensorReadingsResponse::new(0x01, 0, &fields).unwrap();
+        let mut buffer = [0u8; 64];
+        let bytes = response.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 2 + 2 * 4);
+        let decoded_response = GetStateSensorReadingsResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+
+        assert!(GetStateSensorReadingsResponse::decode(&buffer[..bytes - 1]).is_err());
+        assert!(GetStateSensorReadingsResponse::new(0x01, 0, &[]).is_err());
+    }
+}
@@ -0,0 +1,8 @@
+// Licensed under the Apache-2.0 license
+
+pub mod get_pdr;
+pub mod get_pdr_repository_info;
+pub mod get_sensor_reading;
+pub mod get_state_sensor_readings;
+pub mod platform_event_message;
+pub mod set_event_receiver;
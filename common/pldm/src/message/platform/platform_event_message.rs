@@ -0,0 +1,371 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::{
+    PlatformCmd, PlatformEventClass, PlatformEventStatus, SensorDataSize, SensorEventClass,
+    SensorId, SensorValue, PLDM_PLATFORM_EVENT_FORMAT_VERSION,
+};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+pub const PLDM_MAX_EVENT_DATA_LEN: usize = 32; // Arbitrary limit, change as needed
+pub const PLDM_HEARTBEAT_FORMAT_VERSION: u8 = 0x01;
+
+/// Class specific data of a sensor event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorEventData {
+    SensorOpState {
+        present_op_state: u8,
+        previous_op_state: u8,
+    },
+    StateSensorState {
+        sensor_offset: u8,
+        event_state: u8,
+        previous_event_state: u8,
+    },
+    NumericSensorState {
+        event_state: u8,
+        previous_event_state: u8,
+        present_reading: SensorValue,
+    },
+}
+
+/// Event data of the `SensorEvent` event class.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorEvent {
+    pub sensor_id: SensorId,
+    pub data: SensorEventData,
+}
+
+impl SensorEvent {
+    pub fn sensor_event_class(&self) -> SensorEventClass {
+        match self.data {
+            SensorEventData::SensorOpState { .. } => SensorEventClass::SensorOpState,
+            SensorEventData::StateSensorState { .. } => SensorEventClass::StateSensorState,
+            SensorEventData::NumericSensorState { .. } => SensorEventClass::NumericSensorState,
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        3 + match self.data {
+            SensorEventData::SensorOpState { .. } => 2,
+            SensorEventData::StateSensorState { .. } => 3,
+            SensorEventData::NumericSensorState {
+                present_reading, ..
+            } => 3 + present_reading.codec_size_in_bytes(),
+        }
+    }
+}
+
+impl PldmCodec for SensorEvent {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        buffer[..2].copy_from_slice(&self.sensor_id.to_le_bytes());
+        buffer[2] = self.sensor_event_class() as u8;
+        let bytes = match self.data {
+            SensorEventData::SensorOpState {
+                present_op_state,
+                previous_op_state,
+            } => {
+                buffer[3] = present_op_state;
+                buffer[4] = previous_op_state;
+                5
+            }
+            SensorEventData::StateSensorState {
+                sensor_offset,
+                event_state,
+                previous_event_state,
+            } => {
+                buffer[3] = sensor_offset;
+                buffer[4] = event_state;
+                buffer[5] = previous_event_state;
+                6
+            }
+            SensorEventData::NumericSensorState {
+                event_state,
+                previous_event_state,
+                present_reading,
+            } => {
+                buffer[3] = event_state;
+                buffer[4] = previous_event_state;
+                buffer[5] = present_reading.data_size() as u8;
+                6 + present_reading.encode(&mut buffer[6..])?
+            }
+        };
+        Ok(bytes)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let hdr = buffer.get(..3).ok_or(PldmCodecError::BufferTooShort)?;
+        let sensor_id = u16::from_le_bytes([hdr[0], hdr[1]]);
+        let class = SensorEventClass::try_from(hdr[2]).map_err(|_| PldmCodecError::Unsupported)?;
+        let data = match class {
+            SensorEventClass::SensorOpState => {
+                let data = buffer.get(3..5).ok_or(PldmCodecError::BufferTooShort)?;
+                SensorEventData::SensorOpState {
+                    present_op_state: data[0],
+                    previous_op_state: data[1],
+                }
+            }
+            SensorEventClass::StateSensorState => {
+                let data = buffer.get(3..6).ok_or(PldmCodecError::BufferTooShort)?;
+                SensorEventData::StateSensorState {
+                    sensor_offset: data[0],
+                    event_state: data[1],
+                    previous_event_state: data[2],
+                }
+            }
+            SensorEventClass::NumericSensorState => {
+                let data = buffer.get(3..6).ok_or(PldmCodecError::BufferTooShort)?;
+                let data_size =
+                    SensorDataSize::try_from(data[2]).map_err(|_| PldmCodecError::Unsupported)?;
+                SensorEventData::NumericSensorState {
+                    event_state: data[0],
+                    previous_event_state: data[1],
+                    present_reading: SensorValue::decode(data_size, &buffer[6..])?,
+                }
+            }
+        };
+        Ok(SensorEvent { sensor_id, data })
+    }
+}
+
+/// Event data of the `HeartbeatTimerElapsed` event class.
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct HeartbeatTimerElapsedEvent {
+    pub format_version: u8,
+    pub sequence_number: u8,
+}
+
+impl HeartbeatTimerElapsedEvent {
+    pub fn new(sequence_number: u8) -> Self {
+        HeartbeatTimerElapsedEvent {
+            format_version: PLDM_HEARTBEAT_FORMAT_VERSION,
+            sequence_number,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct PlatformEventMessageRequestFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub format_version: u8,
+    pub tid: u8,
+    pub event_class: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlatformEventMessageRequest {
+    pub fixed: PlatformEventMessageRequestFixed,
+    pub event_data_len: usize,
+    pub event_data: [u8; PLDM_MAX_EVENT_DATA_LEN],
+}
+
+impl PlatformEventMessageRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        tid: u8,
+        event_class: PlatformEventClass,
+        event_data: &[u8],
+    ) -> Result<Self, PldmError> {
+        if event_data.len() > PLDM_MAX_EVENT_DATA_LEN {
+            return Err(PldmError::InvalidLength);
+        }
+        let mut data = [0u8; PLDM_MAX_EVENT_DATA_LEN];
+        data[..event_data.len()].copy_from_slice(event_data);
+
+        Ok(PlatformEventMessageRequest {
+            fixed: PlatformEventMessageRequestFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    msg_type,
+                    PldmSupportedType::Platform,
+                    PlatformCmd::PlatformEventMessage as u8,
+                ),
+                format_version: PLDM_PLATFORM_EVENT_FORMAT_VERSION,
+                tid,
+                event_class: event_class as u8,
+            },
+            event_data_len: event_data.len(),
+            event_data: data,
+        })
+    }
+
+    /// Creates a request carrying a sensor event.
+    pub fn new_sensor_event(
+        instance_id: InstanceId,
+        tid: u8,
+        event: &SensorEvent,
+    ) -> Result<Self, PldmError> {
+        let mut data = [0u8; PLDM_MAX_EVENT_DATA_LEN];
+        let len = event
+            .encode(&mut data)
+            .map_err(|_| PldmError::InvalidLength)?;
+        Self::new(
+            instance_id,
+            PldmMsgType::Request,
+            tid,
+            PlatformEventClass::SensorEvent,
+            &data[..len],
+        )
+    }
+
+    pub fn event_data(&self) -> &[u8] {
+        &self.event_data[..self.event_data_len]
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<PlatformEventMessageRequestFixed>() + self.event_data_len
+    }
+}
+
+impl PldmCodec for PlatformEventMessageRequest {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if self.event_data_len > PLDM_MAX_EVENT_DATA_LEN {
+            return Err(PldmCodecError::Unsupported);
+        }
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let offset = self.fixed.encode(buffer)?;
+        buffer[offset..offset + self.event_data_len].copy_from_slice(self.event_data());
+        Ok(offset + self.event_data_len)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let fixed = PlatformEventMessageRequestFixed::decode(buffer)?;
+        let data = &buffer[core::mem::size_of::<PlatformEventMessageRequestFixed>()..];
+        if data.len() > PLDM_MAX_EVENT_DATA_LEN {
+            return Err(PldmCodecError::Unsupported);
+        }
+        let mut event_data = [0u8; PLDM_MAX_EVENT_DATA_LEN];
+        event_data[..data.len()].copy_from_slice(data);
+        Ok(PlatformEventMessageRequest {
+            fixed,
+            event_data_len: data.len(),
+            event_data,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct PlatformEventMessageResponse {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+    pub platform_event_status: u8,
+}
+
+impl PlatformEventMessageResponse {
+    pub fn new(
+        instance_id: InstanceId,
+        completion_code: u8,
+        platform_event_status: PlatformEventStatus,
+    ) -> Self {
+        PlatformEventMessageResponse {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                PldmMsgType::Response,
+                PldmSupportedType::Platform,
+                PlatformCmd::PlatformEventMessage as u8,
+            ),
+            completion_code,
+            platform_event_status: platform_event_status as u8,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sensor_event() {
+        let events = [
+            SensorEvent {
+                sensor_id: 1,
+                data: SensorEventData::SensorOpState {
+                    present_op_state: 4,
+                    previous_op_state: 0,
+                },
+            },
+            SensorEvent {
+                sensor_id: 2,
+                data: SensorEventData::StateSensorState {
+                    sensor_offset: 0,
+                    event_state: 2,
+                    previous_event_state: 1,
+                },
+            },
+            SensorEvent {
+                sensor_id: 3,
+                data: SensorEventData::NumericSensorState {
+                    event_state: 8,
+                    previous_event_state: 1,
+                    present_reading: SensorValue::Uint32(85),
+                },
+            },
+        ];
+        for event in events {
+            let mut buffer = [0u8; PLDM_MAX_EVENT_DATA_LEN];
+            let bytes = event.encode(&mut buffer).unwrap();
+            assert_eq!(bytes, event.codec_size_in_bytes());
+            let decoded_event = SensorEvent::decode(&buffer[..bytes]).unwrap();
+            assert_eq!(event, decoded_event);
+        }
+    }
+
+    #[test]
+    fn test_platform_event_message_request() {
+        let event = SensorEvent {
+            sensor_id: 2,
+            data: SensorEventData::StateSensorState {
+                sensor_offset: 0,
+                event_state: 2,
+                previous_event_state: 1,
+            },
+        };
+        let request = PlatformEventMessageRequest::new_sensor_event(0x01, 0x05, &event).unwrap();
+        let mut buffer = [0u8; 64];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 3 + 6);
+        let decoded_request = PlatformEventMessageRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+        assert_eq!(
+            SensorEvent::decode(decoded_request.event_data()).unwrap(),
+            event
+        );
+
+        let heartbeat = HeartbeatTimerElapsedEvent::new(7);
+        let request = PlatformEventMessageRequest::new(
+            0x02,
+            PldmMsgType::Request,
+            0x05,
+            PlatformEventClass::HeartbeatTimerElapsed,
+            heartbeat.as_bytes(),
+        )
+        .unwrap();
+        let bytes = request.encode(&mut buffer).unwrap();
+        let decoded_request = PlatformEventMessageRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+    }
+
+    #[test]
+    fn test_platform_event_message_response() {
+        let response = PlatformEventMessageResponse::new(0x01, 0, PlatformEventStatus::Logged);
+        let mut buffer = [0u8; 16];
+        let bytes = response.encode(&mut buffer).unwrap();
+        let decoded_response = PlatformEventMessageResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+}
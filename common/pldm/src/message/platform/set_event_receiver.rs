@@ -0,0 +1,165 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::protocol::base::{
+    InstanceId, PldmMsgHeader, PldmMsgType, PldmSupportedType, PLDM_MSG_HEADER_LEN,
+};
+use crate::protocol::platform::{EventMessageGlobalEnable, PlatformCmd, TransportProtocolType};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct SetEventReceiverRequestFixed {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub event_message_global_enable: u8,
+    pub transport_protocol_type: u8,
+    /// The EID of the event receiver when the transport protocol is MCTP.
+    pub event_receiver_address_info: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetEventReceiverRequest {
+    pub fixed: SetEventReceiverRequestFixed,
+    /// Heartbeat period in seconds. Present only when the global enable is `EnableAsyncKeepAlive`.
+    pub heartbeat_timer: Option<u16>,
+}
+
+impl SetEventReceiverRequest {
+    pub fn new(
+        instance_id: InstanceId,
+        msg_type: PldmMsgType,
+        event_message_global_enable: EventMessageGlobalEnable,
+        transport_protocol_type: TransportProtocolType,
+        event_receiver_eid: u8,
+        heartbeat_timer: u16,
+    ) -> Self {
+        SetEventReceiverRequest {
+            fixed: SetEventReceiverRequestFixed {
+                hdr: PldmMsgHeader::new(
+                    instance_id,
+                    msg_type,
+                    PldmSupportedType::Platform,
+                    PlatformCmd::SetEventReceiver as u8,
+                ),
+                event_message_global_enable: event_message_global_enable as u8,
+                transport_protocol_type: transport_protocol_type as u8,
+                event_receiver_address_info: event_receiver_eid,
+            },
+            heartbeat_timer: if event_message_global_enable
+                == EventMessageGlobalEnable::EnableAsyncKeepAlive
+            {
+                Some(heartbeat_timer)
+            } else {
+                None
+            },
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<SetEventReceiverRequestFixed>()
+            + self
+                .heartbeat_timer
+                .map_or(0, |_| core::mem::size_of::<u16>())
+    }
+}
+
+impl PldmCodec for SetEventReceiverRequest {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = self.fixed.encode(buffer)?;
+        if let Some(heartbeat_timer) = self.heartbeat_timer {
+            buffer[offset..offset + 2].copy_from_slice(&heartbeat_timer.to_le_bytes());
+            offset += 2;
+        }
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let fixed = SetEventReceiverRequestFixed::decode(buffer)?;
+        let heartbeat_timer = if fixed.event_message_global_enable
+            == EventMessageGlobalEnable::EnableAsyncKeepAlive as u8
+        {
+            let offset = core::mem::size_of::<SetEventReceiverRequestFixed>();
+            let bytes = buffer
+                .get(offset..offset + 2)
+                .ok_or(PldmCodecError::BufferTooShort)?;
+            Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+        } else {
+            None
+        };
+        Ok(SetEventReceiverRequest {
+            fixed,
+            heartbeat_timer,
+        })
+    }
+}
+
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct SetEventReceiverResponse {
+    pub hdr: PldmMsgHeader<[u8; PLDM_MSG_HEADER_LEN]>,
+    pub completion_code: u8,
+}
+
+impl SetEventReceiverResponse {
+    pub fn new(instance_id: InstanceId, completion_code: u8) -> Self {
+        SetEventReceiverResponse {
+            hdr: PldmMsgHeader::new(
+                instance_id,
+                PldmMsgType::Response,
+                PldmSupportedType::Platform,
+                PlatformCmd::SetEventReceiver as u8,
+            ),
+            completion_code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_set_event_receiver_request() {
+        let request = SetEventReceiverRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            EventMessageGlobalEnable::EnableAsync,
+            TransportProtocolType::Mctp,
+            0x08,
+            120,
+        );
+        assert_eq!(request.heartbeat_timer, None);
+        let mut buffer = [0u8; 16];
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 3);
+        let decoded_request = SetEventReceiverRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+
+        let request = SetEventReceiverRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            EventMessageGlobalEnable::EnableAsyncKeepAlive,
+            TransportProtocolType::Mctp,
+            0x08,
+            120,
+        );
+        let bytes = request.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, PLDM_MSG_HEADER_LEN + 5);
+        let decoded_request = SetEventReceiverRequest::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(request, decoded_request);
+        assert!(SetEventReceiverRequest::decode(&buffer[..bytes - 1]).is_err());
+    }
+
+    #[test]
+    fn test_set_event_receiver_response() {
+        let response = SetEventReceiverResponse::new(0x01, 0);
+        let mut buffer = [0u8; 16];
+        let bytes = response.encode(&mut buffer).unwrap();
+        let decoded_response = SetEventReceiverResponse::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(response, decoded_response);
+    }
+}
@@ -21,12 +21,15 @@ pub enum FwUpdateCmd {
     QueryDeviceIdentifiers = 0x01,
     GetFirmwareParameters = 0x02,
     RequestUpdate = 0x10,
+    GetPackageData = 0x11,
+    GetDeviceMetaData = 0x12,
     PassComponentTable = 0x13,
     UpdateComponent = 0x14,
     RequestFirmwareData = 0x15,
     TransferComplete = 0x16,
     VerifyComplete = 0x17,
     ApplyComplete = 0x18,
+    GetMetaData = 0x19,
     ActivateFirmware = 0x1A,
     GetStatus = 0x1B,
     CancelUpdateComponent = 0x1C,
@@ -41,12 +44,15 @@ impl TryFrom<u8> for FwUpdateCmd {
             0x01 => Ok(FwUpdateCmd::QueryDeviceIdentifiers),
             0x02 => Ok(FwUpdateCmd::GetFirmwareParameters),
             0x10 => Ok(FwUpdateCmd::RequestUpdate),
+            0x11 => Ok(FwUpdateCmd::GetPackageData),
+            0x12 => Ok(FwUpdateCmd::GetDeviceMetaData),
             0x13 => Ok(FwUpdateCmd::PassComponentTable),
             0x14 => Ok(FwUpdateCmd::UpdateComponent),
             0x15 => Ok(FwUpdateCmd::RequestFirmwareData),
             0x16 => Ok(FwUpdateCmd::TransferComplete),
             0x17 => Ok(FwUpdateCmd::VerifyComplete),
             0x18 => Ok(FwUpdateCmd::ApplyComplete),
+            0x19 => Ok(FwUpdateCmd::GetMetaData),
             0x1A => Ok(FwUpdateCmd::ActivateFirmware),
             0x1B => Ok(FwUpdateCmd::GetStatus),
             0x1C => Ok(FwUpdateCmd::CancelUpdateComponent),
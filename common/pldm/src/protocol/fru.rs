@@ -0,0 +1,241 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
+use core::convert::TryFrom;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+pub const PLDM_FRU_DATA_MAJOR_VERSION: u8 = 0x01;
+pub const PLDM_FRU_DATA_MINOR_VERSION: u8 = 0x00;
+pub const PLDM_FRU_FIELD_MAX_LEN: usize = 255;
+pub const PLDM_FRU_TABLE_ALIGNMENT: usize = 4;
+pub type FruRecordSetId = u16;
+
+#[repr(u8)]
+pub enum FruCmd {
+    GetFruRecordTableMetadata = 0x01,
+    GetFruRecordTable = 0x02,
+}
+
+impl TryFrom<u8> for FruCmd {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x01 => Ok(FruCmd::GetFruRecordTableMetadata),
+            0x02 => Ok(FruCmd::GetFruRecordTable),
+            _ => Err(PldmError::UnsupportedCmd),
+        }
+    }
+}
+
+/// Completion codes of the PLDM for FRU Data commands.
+#[repr(u8)]
+pub enum FruCompletionCode {
+    InvalidDataTransferHandle = 0x80,
+    InvalidTransferFlag = 0x82,
+    InvalidDataIntegrityCheck = 0x84,
+    DataStructureTableUnavailable = 0x85,
+}
+
+impl TryFrom<u8> for FruCompletionCode {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x80 => Ok(FruCompletionCode::InvalidDataTransferHandle),
+            0x82 => Ok(FruCompletionCode::InvalidTransferFlag),
+            0x84 => Ok(FruCompletionCode::InvalidDataIntegrityCheck),
+            0x85 => Ok(FruCompletionCode::DataStructureTableUnavailable),
+            _ => Err(PldmError::InvalidCompletionCode),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum FruRecordType {
+    General = 1,
+    Oem = 254,
+}
+
+impl TryFrom<u8> for FruRecordType {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            1 => Ok(FruRecordType::General),
+            254 => Ok(FruRecordType::Oem),
+            _ => Err(PldmError::InvalidFruRecordType),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum FruFieldEncoding {
+    Unspecified = 0,
+    Ascii = 1,
+    Utf8 = 2,
+    Utf16 = 3,
+    Utf16Le = 4,
+    Utf16Be = 5,
+}
+
+impl TryFrom<u8> for FruFieldEncoding {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(FruFieldEncoding::Unspecified),
+            1 => Ok(FruFieldEncoding::Ascii),
+            2 => Ok(FruFieldEncoding::Utf8),
+            3 => Ok(FruFieldEncoding::Utf16),
+            4 => Ok(FruFieldEncoding::Utf16Le),
+            5 => Ok(FruFieldEncoding::Utf16Be),
+            _ => Err(PldmError::InvalidFruFieldEncoding),
+        }
+    }
+}
+
+/// Field types of the General FRU record.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum FruGeneralFieldType {
+    ChassisType = 1,
+    Model = 2,
+    PartNumber = 3,
+    SerialNumber = 4,
+    Manufacturer = 5,
+    ManufactureDate = 6,
+    Vendor = 7,
+    Name = 8,
+    Sku = 9,
+    Version = 10,
+    AssetTag = 11,
+    Description = 12,
+    EngineeringChangeLevel = 13,
+    OtherInformation = 14,
+    VendorIana = 15,
+}
+
+impl TryFrom<u8> for FruGeneralFieldType {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            1 => Ok(FruGeneralFieldType::ChassisType),
+            2 => Ok(FruGeneralFieldType::Model),
+            3 => Ok(FruGeneralFieldType::PartNumber),
+            4 => Ok(FruGeneralFieldType::SerialNumber),
+            5 => Ok(FruGeneralFieldType::Manufacturer),
+            6 => Ok(FruGeneralFieldType::ManufactureDate),
+            7 => Ok(FruGeneralFieldType::Vendor),
+            8 => Ok(FruGeneralFieldType::Name),
+            9 => Ok(FruGeneralFieldType::Sku),
+            10 => Ok(FruGeneralFieldType::Version),
+            11 => Ok(FruGeneralFieldType::AssetTag),
+            12 => Ok(FruGeneralFieldType::Description),
+            13 => Ok(FruGeneralFieldType::EngineeringChangeLevel),
+            14 => Ok(FruGeneralFieldType::OtherInformation),
+            15 => Ok(FruGeneralFieldType::VendorIana),
+            _ => Err(PldmError::InvalidFruFieldType),
+        }
+    }
+}
+
+/// Header of a FRU record, followed by `field_count` fields.
+#[derive(Debug, Clone, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct FruRecordHeader {
+    pub record_set_id: FruRecordSetId,
+    pub record_type: u8,
+    pub field_count: u8,
+    pub encoding_type: u8,
+}
+
+impl FruRecordHeader {
+    pub fn new(
+        record_set_id: FruRecordSetId,
+        record_type: FruRecordType,
+        field_count: u8,
+        encoding_type: FruFieldEncoding,
+    ) -> Self {
+        FruRecordHeader {
+            record_set_id,
+            record_type: record_type as u8,
+            field_count,
+            encoding_type: encoding_type as u8,
+        }
+    }
+}
+
+/// A type-length-value field of a FRU record.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FruField<'a> {
+    pub field_type: u8,
+    pub value: &'a [u8],
+}
+
+impl<'a> FruField<'a> {
+    pub fn new(field_type: FruGeneralFieldType, value: &'a [u8]) -> Result<Self, PldmError> {
+        if value.len() > PLDM_FRU_FIELD_MAX_LEN {
+            return Err(PldmError::InvalidLength);
+        }
+        Ok(FruField {
+            field_type: field_type as u8,
+            value,
+        })
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        2 + self.value.len()
+    }
+}
+
+impl PldmCodec for FruField<'_> {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        let size = self.codec_size_in_bytes();
+        if buffer.len() < size {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+        buffer[0] = self.field_type;
+        buffer[1] = self.value.len() as u8;
+        buffer[2..size].copy_from_slice(self.value);
+        Ok(size)
+    }
+
+    // Decoding is not implemented for this struct. The caller should read the type and length
+    // bytes and borrow the value from the buffer.
+    fn decode(_buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        Err(PldmCodecError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fru_record_header() {
+        let hdr = FruRecordHeader::new(1, FruRecordType::General, 3, FruFieldEncoding::Ascii);
+        let mut buffer = [0u8; 8];
+        let bytes = hdr.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, 5);
+        assert_eq!(&buffer[..bytes], &[0x01, 0x00, 0x01, 0x03, 0x01]);
+        assert_eq!(FruRecordHeader::decode(&buffer[..bytes]).unwrap(), hdr);
+    }
+
+    #[test]
+    fn test_fru_field() {
+        let field = FruField::new(FruGeneralFieldType::Name, b"Caliptra").unwrap();
+        let mut buffer = [0u8; 16];
+        let bytes = field.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, field.codec_size_in_bytes());
+        assert_eq!(&buffer[..2], &[FruGeneralFieldType::Name as u8, 8]);
+        assert_eq!(&buffer[2..bytes], b"Caliptra");
+        assert!(field.encode(&mut buffer[..bytes - 1]).is_err());
+
+        assert!(FruField::new(FruGeneralFieldType::Name, &[0u8; 256]).is_err());
+    }
+}
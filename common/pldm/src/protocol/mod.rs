@@ -2,4 +2,6 @@
 
 pub mod base;
 pub mod firmware_update;
+pub mod fru;
+pub mod platform;
 pub mod version;
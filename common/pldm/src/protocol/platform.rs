@@ -0,0 +1,838 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{PldmCodec, PldmCodecError};
+use crate::error::PldmError;
+use core::convert::TryFrom;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+pub const PLDM_PDR_HEADER_VERSION: u8 = 0x01;
+pub const PLDM_PDR_HEADER_LEN: usize = 10;
+pub const PLDM_PLATFORM_EVENT_FORMAT_VERSION: u8 = 0x01;
+pub const PLDM_TIMESTAMP104_LEN: usize = 13;
+pub const PLDM_MAX_COMPOSITE_SENSOR_COUNT: usize = 8;
+pub const PLDM_MAX_POSSIBLE_STATES_SIZE: usize = 8; // Arbitrary limit, change as needed
+pub const PLDM_NO_MORE_RECORDS: u32 = 0;
+pub type SensorId = u16;
+pub type PdrRecordHandle = u32;
+
+#[repr(u8)]
+pub enum PlatformCmd {
+    SetEventReceiver = 0x04,
+    PlatformEventMessage = 0x0A,
+    GetSensorReading = 0x11,
+    GetStateSensorReadings = 0x21,
+    GetPdrRepositoryInfo = 0x50,
+    GetPdr = 0x51,
+}
+
+impl TryFrom<u8> for PlatformCmd {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x04 => Ok(PlatformCmd::SetEventReceiver),
+            0x0A => Ok(PlatformCmd::PlatformEventMessage),
+            0x11 => Ok(PlatformCmd::GetSensorReading),
+            0x21 => Ok(PlatformCmd::GetStateSensorReadings),
+            0x50 => Ok(PlatformCmd::GetPdrRepositoryInfo),
+            0x51 => Ok(PlatformCmd::GetPdr),
+            _ => Err(PldmError::UnsupportedCmd),
+        }
+    }
+}
+
+/// Completion codes of `GetSensorReading` and `GetStateSensorReadings`.
+#[repr(u8)]
+pub enum SensorCompletionCode {
+    InvalidSensorId = 0x80,
+    RearmUnavailableInPresentState = 0x81,
+}
+
+impl TryFrom<u8> for SensorCompletionCode {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x80 => Ok(SensorCompletionCode::InvalidSensorId),
+            0x81 => Ok(SensorCompletionCode::RearmUnavailableInPresentState),
+            _ => Err(PldmError::InvalidCompletionCode),
+        }
+    }
+}
+
+/// Completion codes of `GetPDR`.
+#[repr(u8)]
+pub enum GetPdrCompletionCode {
+    InvalidDataTransferHandle = 0x80,
+    InvalidTransferOperationFlag = 0x81,
+    InvalidRecordHandle = 0x82,
+    InvalidRecordChangeNumber = 0x83,
+    TransferTimeout = 0x84,
+    RepositoryUpdateInProgress = 0x85,
+}
+
+impl TryFrom<u8> for GetPdrCompletionCode {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x80 => Ok(GetPdrCompletionCode::InvalidDataTransferHandle),
+            0x81 => Ok(GetPdrCompletionCode::InvalidTransferOperationFlag),
+            0x82 => Ok(GetPdrCompletionCode::InvalidRecordHandle),
+            0x83 => Ok(GetPdrCompletionCode::InvalidRecordChangeNumber),
+            0x84 => Ok(GetPdrCompletionCode::TransferTimeout),
+            0x85 => Ok(GetPdrCompletionCode::RepositoryUpdateInProgress),
+            _ => Err(PldmError::InvalidCompletionCode),
+        }
+    }
+}
+
+/// Completion codes of `SetEventReceiver`.
+#[repr(u8)]
+pub enum SetEventReceiverCompletionCode {
+    InvalidProtocolType = 0x80,
+    EnableMethodNotSupported = 0x81,
+    HeartbeatFrequencyTooHigh = 0x82,
+}
+
+impl TryFrom<u8> for SetEventReceiverCompletionCode {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x80 => Ok(SetEventReceiverCompletionCode::InvalidProtocolType),
+            0x81 => Ok(SetEventReceiverCompletionCode::EnableMethodNotSupported),
+            0x82 => Ok(SetEventReceiverCompletionCode::HeartbeatFrequencyTooHigh),
+            _ => Err(PldmError::InvalidCompletionCode),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum PdrRepositoryState {
+    Available = 0,
+    UpdateInProgress = 1,
+    Failed = 2,
+}
+
+impl TryFrom<u8> for PdrRepositoryState {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(PdrRepositoryState::Available),
+            1 => Ok(PdrRepositoryState::UpdateInProgress),
+            2 => Ok(PdrRepositoryState::Failed),
+            _ => Err(PldmError::InvalidRepositoryState),
+        }
+    }
+}
+
+/// Transfer flag of the `GetPDR` response. The values differ from `TransferRespFlag`
+/// of the base specification.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum PdrTransferFlag {
+    Start = 0x00,
+    Middle = 0x01,
+    End = 0x04,
+    StartAndEnd = 0x05,
+}
+
+impl TryFrom<u8> for PdrTransferFlag {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x00 => Ok(PdrTransferFlag::Start),
+            0x01 => Ok(PdrTransferFlag::Middle),
+            0x04 => Ok(PdrTransferFlag::End),
+            0x05 => Ok(PdrTransferFlag::StartAndEnd),
+            _ => Err(PldmError::InvalidTransferRespFlag),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum PdrType {
+    TerminusLocator = 1,
+    NumericSensor = 2,
+    NumericSensorInitialization = 3,
+    StateSensor = 4,
+    StateSensorInitialization = 5,
+    SensorAuxiliaryNames = 6,
+    EntityAssociation = 15,
+    FruRecordSet = 20,
+}
+
+impl TryFrom<u8> for PdrType {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            1 => Ok(PdrType::TerminusLocator),
+            2 => Ok(PdrType::NumericSensor),
+            3 => Ok(PdrType::NumericSensorInitialization),
+            4 => Ok(PdrType::StateSensor),
+            5 => Ok(PdrType::StateSensorInitialization),
+            6 => Ok(PdrType::SensorAuxiliaryNames),
+            15 => Ok(PdrType::EntityAssociation),
+            20 => Ok(PdrType::FruRecordSet),
+            _ => Err(PldmError::InvalidPdrType),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum SensorDataSize {
+    Uint8 = 0,
+    Sint8 = 1,
+    Uint16 = 2,
+    Sint16 = 3,
+    Uint32 = 4,
+    Sint32 = 5,
+}
+
+impl TryFrom<u8> for SensorDataSize {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(SensorDataSize::Uint8),
+            1 => Ok(SensorDataSize::Sint8),
+            2 => Ok(SensorDataSize::Uint16),
+            3 => Ok(SensorDataSize::Sint16),
+            4 => Ok(SensorDataSize::Uint32),
+            5 => Ok(SensorDataSize::Sint32),
+            _ => Err(PldmError::InvalidSensorDataSize),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum SensorOperationalState {
+    Enabled = 0,
+    Disabled = 1,
+    Unavailable = 2,
+    StatusUnknown = 3,
+    Failed = 4,
+    Initializing = 5,
+    ShuttingDown = 6,
+    InTest = 7,
+}
+
+impl TryFrom<u8> for SensorOperationalState {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(SensorOperationalState::Enabled),
+            1 => Ok(SensorOperationalState::Disabled),
+            2 => Ok(SensorOperationalState::Unavailable),
+            3 => Ok(SensorOperationalState::StatusUnknown),
+            4 => Ok(SensorOperationalState::Failed),
+            5 => Ok(SensorOperationalState::Initializing),
+            6 => Ok(SensorOperationalState::ShuttingDown),
+            7 => Ok(SensorOperationalState::InTest),
+            _ => Err(PldmError::InvalidSensorOperationalState),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum SensorEventMessageEnable {
+    NoEventGeneration = 0,
+    EventsDisabled = 1,
+    EventsEnabled = 2,
+    OpEventsOnlyEnabled = 3,
+    StateEventsOnlyEnabled = 4,
+}
+
+/// Present, previous and event state of a numeric sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum NumericSensorState {
+    Unknown = 0,
+    Normal = 1,
+    Warning = 2,
+    Critical = 3,
+    Fatal = 4,
+    LowerWarning = 5,
+    LowerCritical = 6,
+    LowerFatal = 7,
+    UpperWarning = 8,
+    UpperCritical = 9,
+    UpperFatal = 10,
+}
+
+impl TryFrom<u8> for NumericSensorState {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(NumericSensorState::Unknown),
+            1 => Ok(NumericSensorState::Normal),
+            2 => Ok(NumericSensorState::Warning),
+            3 => Ok(NumericSensorState::Critical),
+            4 => Ok(NumericSensorState::Fatal),
+            5 => Ok(NumericSensorState::LowerWarning),
+            6 => Ok(NumericSensorState::LowerCritical),
+            7 => Ok(NumericSensorState::LowerFatal),
+            8 => Ok(NumericSensorState::UpperWarning),
+            9 => Ok(NumericSensorState::UpperCritical),
+            10 => Ok(NumericSensorState::UpperFatal),
+            _ => Err(PldmError::InvalidData),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum EventMessageGlobalEnable {
+    Disable = 0,
+    EnableAsync = 1,
+    EnablePolling = 2,
+    EnableAsyncKeepAlive = 3,
+}
+
+impl TryFrom<u8> for EventMessageGlobalEnable {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(EventMessageGlobalEnable::Disable),
+            1 => Ok(EventMessageGlobalEnable::EnableAsync),
+            2 => Ok(EventMessageGlobalEnable::EnablePolling),
+            3 => Ok(EventMessageGlobalEnable::EnableAsyncKeepAlive),
+            _ => Err(PldmError::InvalidEventMessageGlobalEnable),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum TransportProtocolType {
+    Mctp = 0,
+}
+
+impl TryFrom<u8> for TransportProtocolType {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(TransportProtocolType::Mctp),
+            _ => Err(PldmError::InvalidTransportProtocolType),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum PlatformEventClass {
+    SensorEvent = 0x00,
+    EffecterEvent = 0x01,
+    RedfishTaskExecuted = 0x02,
+    RedfishMessage = 0x03,
+    PdrRepositoryChanged = 0x04,
+    MessagePoll = 0x05,
+    HeartbeatTimerElapsed = 0x06,
+}
+
+impl TryFrom<u8> for PlatformEventClass {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x00 => Ok(PlatformEventClass::SensorEvent),
+            0x01 => Ok(PlatformEventClass::EffecterEvent),
+            0x02 => Ok(PlatformEventClass::RedfishTaskExecuted),
+            0x03 => Ok(PlatformEventClass::RedfishMessage),
+            0x04 => Ok(PlatformEventClass::PdrRepositoryChanged),
+            0x05 => Ok(PlatformEventClass::MessagePoll),
+            0x06 => Ok(PlatformEventClass::HeartbeatTimerElapsed),
+            _ => Err(PldmError::InvalidEventClass),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum SensorEventClass {
+    SensorOpState = 0x00,
+    StateSensorState = 0x01,
+    NumericSensorState = 0x02,
+}
+
+impl TryFrom<u8> for SensorEventClass {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0x00 => Ok(SensorEventClass::SensorOpState),
+            0x01 => Ok(SensorEventClass::StateSensorState),
+            0x02 => Ok(SensorEventClass::NumericSensorState),
+            _ => Err(PldmError::InvalidEventClass),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum PlatformEventStatus {
+    NoLogging = 0,
+    LoggingDisabled = 1,
+    LogFull = 2,
+    AcceptedForLogging = 3,
+    Logged = 4,
+    LoggingRejected = 5,
+}
+
+impl TryFrom<u8> for PlatformEventStatus {
+    type Error = PldmError;
+
+    fn try_from(value: u8) -> Result<Self, PldmError> {
+        match value {
+            0 => Ok(PlatformEventStatus::NoLogging),
+            1 => Ok(PlatformEventStatus::LoggingDisabled),
+            2 => Ok(PlatformEventStatus::LogFull),
+            3 => Ok(PlatformEventStatus::AcceptedForLogging),
+            4 => Ok(PlatformEventStatus::Logged),
+            5 => Ok(PlatformEventStatus::LoggingRejected),
+            _ => Err(PldmError::InvalidData),
+        }
+    }
+}
+
+/// A sensor reading, encoded on the wire with the size given by `SensorDataSize`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorValue {
+    Uint8(u8),
+    Sint8(i8),
+    Uint16(u16),
+    Sint16(i16),
+    Uint32(u32),
+    Sint32(i32),
+}
+
+impl Default for SensorValue {
+    fn default() -> Self {
+        SensorValue::Uint8(0)
+    }
+}
+
+impl SensorValue {
+    pub fn data_size(&self) -> SensorDataSize {
+        match self {
+            SensorValue::Uint8(_) => SensorDataSize::Uint8,
+            SensorValue::Sint8(_) => SensorDataSize::Sint8,
+            SensorValue::Uint16(_) => SensorDataSize::Uint16,
+            SensorValue::Sint16(_) => SensorDataSize::Sint16,
+            SensorValue::Uint32(_) => SensorDataSize::Uint32,
+            SensorValue::Sint32(_) => SensorDataSize::Sint32,
+        }
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        match self.data_size() {
+            SensorDataSize::Uint8 | SensorDataSize::Sint8 => 1,
+            SensorDataSize::Uint16 | SensorDataSize::Sint16 => 2,
+            SensorDataSize::Uint32 | SensorDataSize::Sint32 => 4,
+        }
+    }
+
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        let bytes = self.codec_size_in_bytes();
+        let dst = buffer
+            .get_mut(..bytes)
+            .ok_or(PldmCodecError::BufferTooShort)?;
+        match *self {
+            SensorValue::Uint8(v) => dst.copy_from_slice(&v.to_le_bytes()),
+            SensorValue::Sint8(v) => dst.copy_from_slice(&v.to_le_bytes()),
+            SensorValue::Uint16(v) => dst.copy_from_slice(&v.to_le_bytes()),
+            SensorValue::Sint16(v) => dst.copy_from_slice(&v.to_le_bytes()),
+            SensorValue::Uint32(v) => dst.copy_from_slice(&v.to_le_bytes()),
+            SensorValue::Sint32(v) => dst.copy_from_slice(&v.to_le_bytes()),
+        }
+        Ok(bytes)
+    }
+
+    pub fn decode(data_size: SensorDataSize, buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let short = PldmCodecError::BufferTooShort;
+        Ok(match data_size {
+            SensorDataSize::Uint8 => SensorValue::Uint8(*buffer.first().ok_or(short)?),
+            SensorDataSize::Sint8 => SensorValue::Sint8(*buffer.first().ok_or(short)? as i8),
+            SensorDataSize::Uint16 => SensorValue::Uint16(u16::from_le_bytes(
+                buffer.get(..2).ok_or(short)?.try_into().unwrap(),
+            )),
+            SensorDataSize::Sint16 => SensorValue::Sint16(i16::from_le_bytes(
+                buffer.get(..2).ok_or(short)?.try_into().unwrap(),
+            )),
+            SensorDataSize::Uint32 => SensorValue::Uint32(u32::from_le_bytes(
+                buffer.get(..4).ok_or(short)?.try_into().unwrap(),
+            )),
+            SensorDataSize::Sint32 => SensorValue::Sint32(i32::from_le_bytes(
+                buffer.get(..4).ok_or(short)?.try_into().unwrap(),
+            )),
+        })
+    }
+}
+
+/// Common header of every PDR in the repository.
+#[derive(Debug, Clone, Copy, Default, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct PdrHeader {
+    pub record_handle: PdrRecordHandle,
+    pub pdr_header_version: u8,
+    pub pdr_type: u8,
+    pub record_change_number: u16,
+    pub data_length: u16,
+}
+
+impl PdrHeader {
+    pub fn new(record_handle: PdrRecordHandle, pdr_type: PdrType, data_length: u16) -> Self {
+        PdrHeader {
+            record_handle,
+            pdr_header_version: PLDM_PDR_HEADER_VERSION,
+            pdr_type: pdr_type as u8,
+            record_change_number: 0,
+            data_length,
+        }
+    }
+}
+
+/// Numeric Sensor PDR (type 2) with 32-bit unsigned readings and range fields.
+///
+/// A sensor described by this PDR is expected to report its readings as `SensorValue::Uint32`.
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct NumericSensorPdr {
+    pub hdr: PdrHeader,
+    pub terminus_handle: u16,
+    pub sensor_id: SensorId,
+    pub entity_type: u16,
+    pub entity_instance_number: u16,
+    pub container_id: u16,
+    pub sensor_init: u8,
+    pub sensor_auxiliary_names_pdr: u8,
+    pub base_unit: u8,
+    pub unit_modifier: i8,
+    pub rate_unit: u8,
+    pub base_oem_unit_handle: u8,
+    pub aux_unit: u8,
+    pub aux_unit_modifier: i8,
+    pub aux_rate_unit: u8,
+    pub rel: u8,
+    pub aux_oem_unit_handle: u8,
+    pub is_linear: u8,
+    pub sensor_data_size: u8,
+    pub resolution: f32,
+    pub offset: f32,
+    pub accuracy: u16,
+    pub plus_tolerance: u8,
+    pub minus_tolerance: u8,
+    pub hysteresis: u32,
+    pub supported_thresholds: u8,
+    pub threshold_and_hysteresis_volatility: u8,
+    pub state_transition_interval: f32,
+    pub update_interval: f32,
+    pub max_readable: u32,
+    pub min_readable: u32,
+    pub range_field_format: u8,
+    pub range_field_support: u8,
+    pub nominal_value: u32,
+    pub normal_max: u32,
+    pub normal_min: u32,
+    pub warning_high: u32,
+    pub warning_low: u32,
+    pub critical_high: u32,
+    pub critical_low: u32,
+    pub fatal_high: u32,
+    pub fatal_low: u32,
+}
+
+impl Default for NumericSensorPdr {
+    fn default() -> Self {
+        NumericSensorPdr {
+            hdr: PdrHeader::new(
+                0,
+                PdrType::NumericSensor,
+                (core::mem::size_of::<NumericSensorPdr>() - PLDM_PDR_HEADER_LEN) as u16,
+            ),
+            terminus_handle: 0,
+            sensor_id: 0,
+            entity_type: 0,
+            entity_instance_number: 0,
+            container_id: 0,
+            sensor_init: 0,
+            sensor_auxiliary_names_pdr: 0,
+            base_unit: 0,
+            unit_modifier: 0,
+            rate_unit: 0,
+            base_oem_unit_handle: 0,
+            aux_unit: 0,
+            aux_unit_modifier: 0,
+            aux_rate_unit: 0,
+            rel: 0,
+            aux_oem_unit_handle: 0,
+            is_linear: 1,
+            sensor_data_size: SensorDataSize::Uint32 as u8,
+            resolution: 1.0,
+            offset: 0.0,
+            accuracy: 0,
+            plus_tolerance: 0,
+            minus_tolerance: 0,
+            hysteresis: 0,
+            supported_thresholds: 0,
+            threshold_and_hysteresis_volatility: 0,
+            state_transition_interval: 0.0,
+            update_interval: 0.0,
+            max_readable: u32::MAX,
+            min_readable: 0,
+            range_field_format: SensorDataSize::Uint32 as u8,
+            range_field_support: 0,
+            nominal_value: 0,
+            normal_max: 0,
+            normal_min: 0,
+            warning_high: 0,
+            warning_low: 0,
+            critical_high: 0,
+            critical_low: 0,
+            fatal_high: 0,
+            fatal_low: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, FromBytes, IntoBytes, Immutable, PartialEq)]
+#[repr(C, packed)]
+pub struct StateSensorPdrFixed {
+    pub hdr: PdrHeader,
+    pub terminus_handle: u16,
+    pub sensor_id: SensorId,
+    pub entity_type: u16,
+    pub entity_instance_number: u16,
+    pub container_id: u16,
+    pub sensor_init: u8,
+    pub sensor_auxiliary_names_pdr: u8,
+    pub composite_sensor_count: u8,
+}
+
+/// Possible states of one composite sensor in a State Sensor PDR.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PossibleStates {
+    pub state_set_id: u16,
+    pub possible_states_size: u8,
+    pub possible_states: [u8; PLDM_MAX_POSSIBLE_STATES_SIZE],
+}
+
+impl PossibleStates {
+    /// Creates the possible states of a state set from the list of supported state values.
+    pub fn new(state_set_id: u16, states: &[u8]) -> Result<Self, PldmError> {
+        let mut possible_states = [0u8; PLDM_MAX_POSSIBLE_STATES_SIZE];
+        let mut possible_states_size = 0;
+        for &state in states {
+            let index = state as usize / 8;
+            if index >= PLDM_MAX_POSSIBLE_STATES_SIZE {
+                return Err(PldmError::InvalidData);
+            }
+            possible_states[index] |= 1 << (state % 8);
+            possible_states_size = possible_states_size.max(index + 1);
+        }
+        Ok(PossibleStates {
+            state_set_id,
+            possible_states_size: possible_states_size as u8,
+            possible_states,
+        })
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        core::mem::size_of::<u16>() + 1 + self.possible_states_size as usize
+    }
+}
+
+/// State Sensor PDR (type 4).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateSensorPdr {
+    pub fixed: StateSensorPdrFixed,
+    pub possible_states: [PossibleStates; PLDM_MAX_COMPOSITE_SENSOR_COUNT],
+}
+
+impl StateSensorPdr {
+    pub fn new(
+        record_handle: PdrRecordHandle,
+        terminus_handle: u16,
+        sensor_id: SensorId,
+        entity_type: u16,
+        entity_instance_number: u16,
+        container_id: u16,
+        composite_sensors: &[PossibleStates],
+    ) -> Result<Self, PldmError> {
+        if composite_sensors.is_empty() || composite_sensors.len() > PLDM_MAX_COMPOSITE_SENSOR_COUNT
+        {
+            return Err(PldmError::InvalidData);
+        }
+        let mut possible_states = [PossibleStates::default(); PLDM_MAX_COMPOSITE_SENSOR_COUNT];
+        possible_states[..composite_sensors.len()].copy_from_slice(composite_sensors);
+
+        let mut pdr = StateSensorPdr {
+            fixed: StateSensorPdrFixed {
+                hdr: PdrHeader::new(record_handle, PdrType::StateSensor, 0),
+                terminus_handle,
+                sensor_id,
+                entity_type,
+                entity_instance_number,
+                container_id,
+                sensor_init: 0,
+                sensor_auxiliary_names_pdr: 0,
+                composite_sensor_count: composite_sensors.len() as u8,
+            },
+            possible_states,
+        };
+        pdr.fixed.hdr.data_length = (pdr.codec_size_in_bytes() - PLDM_PDR_HEADER_LEN) as u16;
+        Ok(pdr)
+    }
+
+    pub fn codec_size_in_bytes(&self) -> usize {
+        let count =
+            (self.fixed.composite_sensor_count as usize).min(PLDM_MAX_COMPOSITE_SENSOR_COUNT);
+        core::mem::size_of::<StateSensorPdrFixed>()
+            + self.possible_states[..count]
+                .iter()
+                .map(|s| s.codec_size_in_bytes())
+                .sum::<usize>()
+    }
+}
+
+impl PldmCodec for StateSensorPdr {
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, PldmCodecError> {
+        let count = self.fixed.composite_sensor_count as usize;
+        if count > PLDM_MAX_COMPOSITE_SENSOR_COUNT {
+            return Err(PldmCodecError::Unsupported);
+        }
+        if buffer.len() < self.codec_size_in_bytes() {
+            return Err(PldmCodecError::BufferTooShort);
+        }
+
+        let mut offset = self.fixed.encode(buffer)?;
+        for states in &self.possible_states[..count] {
+            let size = states.possible_states_size as usize;
+            if size > PLDM_MAX_POSSIBLE_STATES_SIZE {
+                return Err(PldmCodecError::Unsupported);
+            }
+            buffer[offset..offset + 2].copy_from_slice(&states.state_set_id.to_le_bytes());
+            buffer[offset + 2] = states.possible_states_size;
+            offset += 3;
+            buffer[offset..offset + size].copy_from_slice(&states.possible_states[..size]);
+            offset += size;
+        }
+        Ok(offset)
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, PldmCodecError> {
+        let fixed = StateSensorPdrFixed::decode(buffer)?;
+        let count = fixed.composite_sensor_count as usize;
+        if count > PLDM_MAX_COMPOSITE_SENSOR_COUNT {
+            return Err(PldmCodecError::Unsupported);
+        }
+
+        let mut offset = core::mem::size_of::<StateSensorPdrFixed>();
+        let mut possible_states = [PossibleStates::default(); PLDM_MAX_COMPOSITE_SENSOR_COUNT];
+        for states in possible_states.iter_mut().take(count) {
+            let hdr = buffer
+                .get(offset..offset + 3)
+                .ok_or(PldmCodecError::BufferTooShort)?;
+            let size = hdr[2] as usize;
+            if size > PLDM_MAX_POSSIBLE_STATES_SIZE {
+                return Err(PldmCodecError::Unsupported);
+            }
+            states.state_set_id = u16::from_le_bytes([hdr[0], hdr[1]]);
+            states.possible_states_size = hdr[2];
+            offset += 3;
+            states.possible_states[..size].copy_from_slice(
+                buffer
+                    .get(offset..offset + size)
+                    .ok_or(PldmCodecError::BufferTooShort)?,
+            );
+            offset += size;
+        }
+        Ok(StateSensorPdr {
+            fixed,
+            possible_states,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sensor_value_codec() {
+        let values = [
+            SensorValue::Uint8(0xA5),
+            SensorValue::Sint8(-3),
+            SensorValue::Uint16(0x1234),
+            SensorValue::Sint16(-1000),
+            SensorValue::Uint32(0xDEADBEEF),
+            SensorValue::Sint32(-100000),
+        ];
+        for value in values {
+            let mut buffer = [0u8; 4];
+            let bytes = value.encode(&mut buffer).unwrap();
+            assert_eq!(bytes, value.codec_size_in_bytes());
+            let decoded = SensorValue::decode(value.data_size(), &buffer[..bytes]).unwrap();
+            assert_eq!(value, decoded);
+        }
+        assert!(SensorValue::decode(SensorDataSize::Uint32, &[0u8; 3]).is_err());
+    }
+
+    #[test]
+    fn test_numeric_sensor_pdr() {
+        let pdr = NumericSensorPdr {
+            hdr: PdrHeader {
+                record_handle: 1,
+                ..NumericSensorPdr::default().hdr
+            },
+            sensor_id: 2,
+            warning_high: 80,
+            critical_high: 95,
+            ..Default::default()
+        };
+        let mut buffer = [0u8; 128];
+        let bytes = pdr.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, core::mem::size_of::<NumericSensorPdr>());
+        assert_eq!(
+            { pdr.hdr.data_length } as usize,
+            bytes - PLDM_PDR_HEADER_LEN
+        );
+        let decoded = NumericSensorPdr::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(pdr, decoded);
+    }
+
+    #[test]
+    fn test_state_sensor_pdr() {
+        let composite_sensors = [
+            PossibleStates::new(0x100, &[1, 2, 3]).unwrap(),
+            PossibleStates::new(0x101, &[0, 9]).unwrap(),
+        ];
+        let pdr = StateSensorPdr::new(3, 0, 4, 0x5, 0, 0, &composite_sensors).unwrap();
+        assert_eq!(composite_sensors[0].possible_states_size, 1);
+        assert_eq!(composite_sensors[0].possible_states[0], 0b1110);
+        assert_eq!(composite_sensors[1].possible_states_size, 2);
+
+        let mut buffer = [0u8; 64];
+        let bytes = pdr.encode(&mut buffer).unwrap();
+        assert_eq!(bytes, core::mem::size_of::<StateSensorPdrFixed>() + 4 + 5);
+        assert_eq!(
+            { pdr.fixed.hdr.data_length } as usize,
+            bytes - PLDM_PDR_HEADER_LEN
+        );
+        let decoded = StateSensorPdr::decode(&buffer[..bytes]).unwrap();
+        assert_eq!(pdr, decoded);
+    }
+}
@@ -6,10 +6,12 @@
 pub mod i3c;
 pub mod i3c_socket;
 pub mod i3c_socket_server;
+pub mod mctp_serial_transport;
 pub mod mctp_transport;
 pub mod mctp_vdm_transport;
 #[macro_use]
 pub mod mctp_util;
+pub mod uart_socket_server;
 
 pub use caliptra_api_types::DeviceLifecycle;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
@@ -0,0 +1,554 @@
+// Licensed under the Apache-2.0 license
+
+//! MCTP over serial transport implementation for testing.
+//!
+//! This module exchanges MCTP messages with the MCU over its UART, which the
+//! emulator exposes on a TCP socket (`--uart-port`), using the serial framing
+//! of DSP0253. It is an alternate path to the I3C socket for the SPDM, PLDM
+//! and VDM tests: `MctpSerialSocket::send_request` takes the MCTP message
+//! (message type followed by the payload) like `MctpUtil::wait_for_responder`,
+//! and `MctpSerialTransport` implements the PLDM transport traits.
+//!
+//! The UART is shared with the console of the MCU. The bytes received outside
+//! of a frame are printed as console output.
+
+use crate::mctp_util::base_protocol::{MCTPHdr, LOCAL_TEST_ENDPOINT_EID, MCTP_HDR_SIZE};
+use core::time::Duration;
+use pldm_common::util::mctp_transport::{MctpCommonHeader, MCTP_PLDM_MSG_TYPE};
+use pldm_ua::transport::{
+    EndpointId, Payload, PldmSocket, PldmTransport, PldmTransportError, RxPacket,
+    MAX_PLDM_PAYLOAD_SIZE,
+};
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use zerocopy::{FromBytes, IntoBytes};
+
+/// Payload size of the packets sent to the MCU (baseline transmission unit).
+pub const MCTP_SERIAL_PKT_PAYLOAD_SIZE: usize = 64;
+pub const MCTP_SERIAL_DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+const MCTP_SERIAL_FRAME_FLAG: u8 = 0x7E;
+const MCTP_SERIAL_ESCAPE: u8 = 0x7D;
+const MCTP_SERIAL_ESCAPE_XOR: u8 = 0x20;
+const MCTP_SERIAL_REVISION: u8 = 0x01;
+const MCTP_SERIAL_FCS_INIT: u16 = 0xFFFF;
+const MCTP_TAG_MASK: u8 = 0x07;
+
+/// FCS-16 (RFC1662) calculation, without the final ones' complement.
+pub fn compute_fcs(mut fcs: u16, data: &[u8]) -> u16 {
+    for byte in data {
+        fcs ^= *byte as u16;
+        for _ in 0..8 {
+            if fcs & 0x0001 != 0 {
+                fcs = (fcs >> 1) ^ 0x8408;
+            } else {
+                fcs >>= 1;
+            }
+        }
+    }
+    fcs
+}
+
+/// Encodes an MCTP packet in a serial frame (DSP0253).
+pub fn encode_frame(pkt: &[u8]) -> Vec<u8> {
+    assert!(pkt.len() <= u8::MAX as usize, "MCTP packet too large");
+    let hdr = [MCTP_SERIAL_REVISION, pkt.len() as u8];
+    let fcs = compute_fcs(compute_fcs(MCTP_SERIAL_FCS_INIT, &hdr), pkt);
+
+    let mut frame = vec![MCTP_SERIAL_FRAME_FLAG];
+    for byte in hdr.iter().chain(pkt).chain(fcs.to_be_bytes().iter()) {
+        if *byte == MCTP_SERIAL_FRAME_FLAG || *byte == MCTP_SERIAL_ESCAPE {
+            frame.push(MCTP_SERIAL_ESCAPE);
+            frame.push(*byte ^ MCTP_SERIAL_ESCAPE_XOR);
+        } else {
+            frame.push(*byte);
+        }
+    }
+    frame.push(MCTP_SERIAL_FRAME_FLAG);
+    frame
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum SerialRxState {
+    #[default]
+    Idle,
+    Revision,
+    ByteCount,
+    Data,
+    Fcs,
+    End,
+}
+
+/// Byte received over the serial link.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SerialRxEvent {
+    /// Byte received outside of a frame
+    Console(u8),
+    /// MCTP packet of a frame with a valid FCS
+    Packet(Vec<u8>),
+}
+
+/// Decoder of the serial frames received one byte at a time.
+#[derive(Debug, Default)]
+pub struct SerialFrameDecoder {
+    state: SerialRxState,
+    escape: bool,
+    pkt_len: usize,
+    pkt: Vec<u8>,
+    fcs: u16,
+    fcs_rcvd: Vec<u8>,
+}
+
+impl SerialFrameDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, byte: u8) -> Option<SerialRxEvent> {
+        if byte == MCTP_SERIAL_FRAME_FLAG {
+            // A flag closes the current frame and may open the next one
+            let fcs_rcvd = match self.fcs_rcvd[..] {
+                [hi, lo] => u16::from_be_bytes([hi, lo]),
+                _ => 0,
+            };
+            let pkt = (self.state == SerialRxState::End && self.fcs == fcs_rcvd)
+                .then(|| std::mem::take(&mut self.pkt));
+            self.reset(SerialRxState::Revision);
+            return pkt.map(SerialRxEvent::Packet);
+        }
+
+        if self.state == SerialRxState::Idle {
+            return Some(SerialRxEvent::Console(byte));
+        }
+        if byte == MCTP_SERIAL_ESCAPE {
+            self.escape = true;
+            return None;
+        }
+        let byte = if std::mem::take(&mut self.escape) {
+            byte ^ MCTP_SERIAL_ESCAPE_XOR
+        } else {
+            byte
+        };
+
+        match self.state {
+            SerialRxState::Revision if byte == MCTP_SERIAL_REVISION => {
+                self.fcs = compute_fcs(self.fcs, &[byte]);
+                self.state = SerialRxState::ByteCount;
+            }
+            SerialRxState::ByteCount if byte as usize >= MCTP_HDR_SIZE => {
+                self.fcs = compute_fcs(self.fcs, &[byte]);
+                self.pkt_len = byte as usize;
+                self.state = SerialRxState::Data;
+            }
+            SerialRxState::Data => {
+                self.fcs = compute_fcs(self.fcs, &[byte]);
+                self.pkt.push(byte);
+                if self.pkt.len() == self.pkt_len {
+                    self.state = SerialRxState::Fcs;
+                }
+            }
+            SerialRxState::Fcs => {
+                self.fcs_rcvd.push(byte);
+                if self.fcs_rcvd.len() == 2 {
+                    self.state = SerialRxState::End;
+                }
+            }
+            _ => self.reset(SerialRxState::Idle),
+        }
+        None
+    }
+
+    fn reset(&mut self, state: SerialRxState) {
+        *self = SerialFrameDecoder {
+            state,
+            fcs: MCTP_SERIAL_FCS_INIT,
+            ..Default::default()
+        };
+    }
+}
+
+/// Error types for serial transport operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MctpSerialError {
+    /// Connection was lost or could not be established.
+    Disconnected,
+    /// Timeout waiting for a message.
+    Timeout,
+}
+
+/// MCTP message received over the serial link.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MctpSerialMessage {
+    pub src_eid: u8,
+    pub dest_eid: u8,
+    pub tag_owner: u8,
+    pub msg_tag: u8,
+    /// Message type followed by the message payload
+    pub msg: Vec<u8>,
+}
+
+struct MctpSerialReader {
+    stream: TcpStream,
+    decoder: SerialFrameDecoder,
+    console: Vec<u8>,
+    message: Option<MctpSerialMessage>,
+    next_seq: u8,
+    received: VecDeque<MctpSerialMessage>,
+}
+
+impl MctpSerialReader {
+    fn print_console(&mut self, byte: u8) {
+        if byte == b'\n' {
+            println!("{}", String::from_utf8_lossy(&self.console));
+            self.console.clear();
+        } else if byte != b'\r' {
+            self.console.push(byte);
+        }
+    }
+
+    /// Adds a packet to the message being assembled.
+    /// Returns the message once its last packet has been received.
+    fn assemble(&mut self, pkt: &[u8]) -> Option<MctpSerialMessage> {
+        let hdr = MCTPHdr::<[u8; MCTP_HDR_SIZE]>::read_from_bytes(&pkt[..MCTP_HDR_SIZE]).ok()?;
+        if hdr.som() == 1 {
+            self.message = Some(MctpSerialMessage {
+                src_eid: hdr.src_eid(),
+                dest_eid: hdr.dest_eid(),
+                tag_owner: hdr.tag_owner(),
+                msg_tag: hdr.msg_tag(),
+                msg: Vec::new(),
+            });
+            self.next_seq = hdr.pkt_seq();
+        }
+
+        let in_sequence = self.message.as_ref().is_some_and(|message| {
+            message.src_eid == hdr.src_eid()
+                && message.tag_owner == hdr.tag_owner()
+                && message.msg_tag == hdr.msg_tag()
+                && self.next_seq == hdr.pkt_seq()
+        });
+        if !in_sequence {
+            // Drop the message
+            self.message = None;
+            return None;
+        }
+
+        self.next_seq = (self.next_seq + 1) % 4;
+        let message = self.message.as_mut()?;
+        message.msg.extend_from_slice(&pkt[MCTP_HDR_SIZE..]);
+        if hdr.eom() == 1 {
+            self.message.take()
+        } else {
+            None
+        }
+    }
+
+    fn receive_message(&mut self, timeout: Duration) -> Result<MctpSerialMessage, MctpSerialError> {
+        let deadline = Instant::now() + timeout;
+        let mut data = [0u8; 256];
+        loop {
+            if let Some(message) = self.received.pop_front() {
+                return Ok(message);
+            }
+            let remaining = deadline
+                .checked_duration_since(Instant::now())
+                .filter(|remaining| !remaining.is_zero())
+                .ok_or(MctpSerialError::Timeout)?;
+            self.stream
+                .set_read_timeout(Some(remaining))
+                .map_err(|_| MctpSerialError::Disconnected)?;
+            let len = match self.stream.read(&mut data) {
+                Ok(0) => return Err(MctpSerialError::Disconnected),
+                Ok(len) => len,
+                Err(ref e)
+                    if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut =>
+                {
+                    return Err(MctpSerialError::Timeout)
+                }
+                Err(_) => return Err(MctpSerialError::Disconnected),
+            };
+
+            for byte in &data[..len] {
+                match self.decoder.push(*byte) {
+                    Some(SerialRxEvent::Console(byte)) => self.print_console(byte),
+                    Some(SerialRxEvent::Packet(pkt)) => {
+                        if let Some(message) = self.assemble(&pkt) {
+                            self.received.push_back(message);
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+}
+
+/// Socket exchanging MCTP messages over the serial link.
+pub struct MctpSerialSocket {
+    src_eid: u8,
+    dest_eid: u8,
+    msg_tag: Arc<Mutex<u8>>,
+    writer: Arc<Mutex<TcpStream>>,
+    reader: Arc<Mutex<MctpSerialReader>>,
+}
+
+impl MctpSerialSocket {
+    /// Connects to the UART socket of the emulator.
+    pub fn connect(port: u16, dest_eid: u8) -> Result<Self, MctpSerialError> {
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        let stream = TcpStream::connect(addr).map_err(|_| MctpSerialError::Disconnected)?;
+        let reader = MctpSerialReader {
+            stream: stream
+                .try_clone()
+                .map_err(|_| MctpSerialError::Disconnected)?,
+            decoder: SerialFrameDecoder::new(),
+            console: Vec::new(),
+            message: None,
+            next_seq: 0,
+            received: VecDeque::new(),
+        };
+        Ok(MctpSerialSocket {
+            src_eid: LOCAL_TEST_ENDPOINT_EID,
+            dest_eid,
+            msg_tag: Arc::new(Mutex::new(0)),
+            writer: Arc::new(Mutex::new(stream)),
+            reader: Arc::new(Mutex::new(reader)),
+        })
+    }
+
+    /// Sets the EID of the endpoint the messages are sent to.
+    pub fn set_dest_eid(&mut self, dest_eid: u8) {
+        self.dest_eid = dest_eid;
+    }
+
+    /// Sends an MCTP message, split in packets of the baseline transmission unit.
+    ///
+    /// # Arguments
+    /// * `msg` - Message type followed by the message payload
+    /// * `tag_owner` - 1 for a request, 0 for a response
+    /// * `msg_tag` - Tag of the message
+    pub fn send_message(
+        &self,
+        msg: &[u8],
+        tag_owner: u8,
+        msg_tag: u8,
+    ) -> Result<(), MctpSerialError> {
+        let chunks: Vec<&[u8]> = msg.chunks(MCTP_SERIAL_PKT_PAYLOAD_SIZE).collect();
+        let mut frames = Vec::new();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let mut hdr = MCTPHdr::new();
+            hdr.prepare_header(
+                self.dest_eid,
+                self.src_eid,
+                (index == 0) as u8,
+                (index == chunks.len() - 1) as u8,
+                (index % 4) as u8,
+                tag_owner,
+                msg_tag & MCTP_TAG_MASK,
+            );
+            let mut pkt = hdr.as_bytes().to_vec();
+            pkt.extend_from_slice(chunk);
+            frames.extend(encode_frame(&pkt));
+        }
+
+        let mut writer = self.writer.lock().unwrap();
+        writer
+            .write_all(&frames)
+            .map_err(|_| MctpSerialError::Disconnected)
+    }
+
+    /// Receives the next MCTP message.
+    pub fn receive_message(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<MctpSerialMessage, MctpSerialError> {
+        let mut reader = self.reader.lock().unwrap();
+        reader.receive_message(timeout.unwrap_or(MCTP_SERIAL_DEFAULT_TIMEOUT))
+    }
+
+    /// Sends a request and waits for its response.
+    ///
+    /// The request and the response start with the message type. Messages
+    /// received meanwhile that are not the response are dropped.
+    pub fn send_request(&self, request: &[u8]) -> Result<Vec<u8>, MctpSerialError> {
+        let msg_tag = {
+            let mut msg_tag = self.msg_tag.lock().unwrap();
+            let tag = *msg_tag;
+            *msg_tag = (tag + 1) & MCTP_TAG_MASK;
+            tag
+        };
+        self.send_message(request, 1, msg_tag)?;
+
+        let deadline = Instant::now() + MCTP_SERIAL_DEFAULT_TIMEOUT;
+        loop {
+            let remaining = deadline
+                .checked_duration_since(Instant::now())
+                .ok_or(MctpSerialError::Timeout)?;
+            let message = self.receive_message(Some(remaining))?;
+            if message.tag_owner == 0 && message.msg_tag == msg_tag {
+                return Ok(message.msg);
+            }
+        }
+    }
+
+    pub fn try_clone(&self) -> Self {
+        MctpSerialSocket {
+            src_eid: self.src_eid,
+            dest_eid: self.dest_eid,
+            msg_tag: self.msg_tag.clone(),
+            writer: self.writer.clone(),
+            reader: self.reader.clone(),
+        }
+    }
+}
+
+/// PLDM socket over the serial link.
+pub struct MctpSerialPldmSocket {
+    dest: EndpointId,
+    socket: MctpSerialSocket,
+    response_msg_tag: Arc<Mutex<u8>>,
+}
+
+impl PldmSocket for MctpSerialPldmSocket {
+    fn send(&self, payload: &[u8]) -> Result<(), PldmTransportError> {
+        let mut mctp_common_header = MctpCommonHeader(0);
+        mctp_common_header.set_ic(0);
+        mctp_common_header.set_msg_type(MCTP_PLDM_MSG_TYPE);
+
+        let mut mctp_payload: Vec<u8> = Vec::new();
+        mctp_payload.push(mctp_common_header.0);
+        mctp_payload.extend_from_slice(payload);
+
+        // Requests have the Rq bit set, responses carry the tag of the request
+        let result = if payload.first().is_some_and(|byte| byte & 0x80 == 0x80) {
+            let msg_tag = {
+                let mut msg_tag = self.socket.msg_tag.lock().unwrap();
+                let tag = *msg_tag;
+                *msg_tag = (tag + 1) & MCTP_TAG_MASK;
+                tag
+            };
+            self.socket.send_message(&mctp_payload, 1, msg_tag)
+        } else {
+            let msg_tag = *self.response_msg_tag.lock().unwrap();
+            self.socket.send_message(&mctp_payload, 0, msg_tag)
+        };
+        result.map_err(|_| PldmTransportError::Disconnected)
+    }
+
+    fn receive(&self, timeout: Option<Duration>) -> Result<RxPacket, PldmTransportError> {
+        loop {
+            let message = self.socket.receive_message(timeout).map_err(|e| match e {
+                MctpSerialError::Timeout => PldmTransportError::Timeout,
+                MctpSerialError::Disconnected => PldmTransportError::Disconnected,
+            })?;
+            if message.msg.first().map(|byte| byte & 0x7F) != Some(MCTP_PLDM_MSG_TYPE) {
+                continue;
+            }
+            if message.tag_owner == 1 {
+                *self.response_msg_tag.lock().unwrap() = message.msg_tag;
+            }
+
+            // Skip the first byte containing the MCTP common header
+            // and only return the PLDM payload
+            let len = message.msg.len() - 1;
+            if len > MAX_PLDM_PAYLOAD_SIZE {
+                return Err(PldmTransportError::Underflow);
+            }
+            let mut data = [0u8; MAX_PLDM_PAYLOAD_SIZE];
+            data[..len].copy_from_slice(&message.msg[1..]);
+            return Ok(RxPacket {
+                src: self.dest,
+                payload: Payload { data, len },
+            });
+        }
+    }
+
+    fn connect(&self) -> Result<(), PldmTransportError> {
+        // Not supported
+        Ok(())
+    }
+
+    fn disconnect(&self) {
+        // Not supported
+    }
+
+    fn clone(&self) -> Self {
+        MctpSerialPldmSocket {
+            dest: self.dest,
+            socket: self.socket.try_clone(),
+            response_msg_tag: self.response_msg_tag.clone(),
+        }
+    }
+}
+
+/// PLDM transport over the serial link.
+#[derive(Clone)]
+pub struct MctpSerialTransport {
+    port: u16,
+}
+
+impl MctpSerialTransport {
+    pub fn new(port: u16) -> Self {
+        Self { port }
+    }
+}
+
+impl PldmTransport<MctpSerialPldmSocket> for MctpSerialTransport {
+    fn create_socket(
+        &self,
+        _source: EndpointId,
+        dest: EndpointId,
+    ) -> Result<MctpSerialPldmSocket, PldmTransportError> {
+        let socket = MctpSerialSocket::connect(self.port, dest.0)
+            .map_err(|_| PldmTransportError::Disconnected)?;
+        Ok(MctpSerialPldmSocket {
+            dest,
+            socket,
+            response_msg_tag: Arc::new(Mutex::new(0)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fcs() {
+        assert_eq!(0x6F91, compute_fcs(MCTP_SERIAL_FCS_INIT, b"123456789"));
+    }
+
+    #[test]
+    fn test_frame_round_trip() {
+        let pkt = [0x01, 0x00, 0x08, 0xC8, 0x7E, 0x7D, 0x00];
+        let frame = encode_frame(&pkt);
+        assert_eq!(frame[..3], [0x7E, 0x01, 0x07]);
+        assert_eq!(frame[7..11], [0x7D, 0x5E, 0x7D, 0x5D]);
+
+        let mut decoder = SerialFrameDecoder::new();
+        let mut events: Vec<SerialRxEvent> = b"ok\n"
+            .iter()
+            .chain(frame.iter())
+            .filter_map(|byte| decoder.push(*byte))
+            .collect();
+        assert_eq!(events.pop(), Some(SerialRxEvent::Packet(pkt.to_vec())));
+        assert_eq!(
+            events,
+            b"ok\n"
+                .iter()
+                .map(|byte| SerialRxEvent::Console(*byte))
+                .collect::<Vec<_>>()
+        );
+
+        // A frame with a corrupted FCS is dropped
+        let mut frame = frame;
+        let fcs_index = frame.len() - 2;
+        frame[fcs_index] ^= 0x01;
+        assert!(frame
+            .iter()
+            .all(|byte| !matches!(decoder.push(*byte), Some(SerialRxEvent::Packet(_)))));
+    }
+}
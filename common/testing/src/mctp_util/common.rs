@@ -207,6 +207,30 @@ impl MctpUtil {
         self.send_packets(pkts, stream, target_addr);
     }
 
+    /// Send a request to an endpoint reached through the target device, such as an
+    /// endpoint behind a bridge.
+    /// This function will block until the request message is sent
+    ///
+    /// # Arguments
+    /// * `dest_eid` - The EID of the endpoint the request is addressed to
+    /// * `msg_tag` - The message tag to be used for the request
+    /// * `msg` - The message to be sent
+    /// * `stream` - The TCP stream to I3C socket
+    /// * `target_addr` - The target address of the I3C device
+    pub fn send_request_to(
+        &mut self,
+        dest_eid: u8,
+        msg_tag: u8,
+        msg: &[u8],
+        stream: &mut BufferedStream,
+        target_addr: u8,
+    ) {
+        self.new_req(msg_tag);
+        self.dest_eid = dest_eid;
+        let pkts = self.packetize(msg);
+        self.send_packets(pkts, stream, target_addr);
+    }
+
     /// Send a response to the target address
     /// This function will block until the response message is sent
     ///
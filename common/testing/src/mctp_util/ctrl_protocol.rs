@@ -59,6 +59,10 @@ pub fn generate_msg_type_support_resp_bytes(cc: u8, supported_types: &[MctpMsgTy
     resp_bytes
 }
 
+pub fn get_routing_table_entries_resp_bytes(cc: u8, next_handle: u8, entries: u8) -> Vec<u8> {
+    vec![cc, next_handle, entries]
+}
+
 bitfield! {
     #[repr(C)]
     #[derive(Clone, FromBytes, IntoBytes, Immutable)]
@@ -88,8 +92,12 @@ impl MCTPCtrlMsgHdr<[u8; MCTP_CTRL_MSG_HDR_SIZE]> {
 pub enum MCTPCtrlCmd {
     SetEID = 1,
     GetEID = 2,
+    GetEndpointUUID = 3,
     GetMctpVersionSupport = 4,
     GetMsgTypeSupport = 5,
+    GetVendorDefinedMsgSupport = 6,
+    AllocateEndpointIDs = 8,
+    GetRoutingTableEntries = 0x0A,
     Unsupported,
 }
 
@@ -98,8 +106,12 @@ impl From<u8> for MCTPCtrlCmd {
         match val {
             1 => MCTPCtrlCmd::SetEID,
             2 => MCTPCtrlCmd::GetEID,
+            3 => MCTPCtrlCmd::GetEndpointUUID,
             4 => MCTPCtrlCmd::GetMctpVersionSupport,
             5 => MCTPCtrlCmd::GetMsgTypeSupport,
+            6 => MCTPCtrlCmd::GetVendorDefinedMsgSupport,
+            8 => MCTPCtrlCmd::AllocateEndpointIDs,
+            0x0A => MCTPCtrlCmd::GetRoutingTableEntries,
             _ => MCTPCtrlCmd::Unsupported,
         }
     }
@@ -182,6 +194,7 @@ pub enum SetEIDStatus {
 
 pub enum SetEIDAllocStatus {
     NoEIDPool,
+    PoolRequired,
 }
 
 // Get EID Request has no fields
@@ -7,7 +7,9 @@
 
 use crate::i3c::DynamicI3cAddress;
 use crate::i3c_socket::BufferedStream;
+use crate::mctp_util::base_protocol::MctpMsgType;
 use crate::mctp_util::common::MctpUtil;
+use crate::mctp_util::ctrl_protocol::{MCTPCtrlCmd, MCTPCtrlMsgHdr, MCTP_CTRL_MSG_HDR_SIZE};
 use mctp_vdm_common::codec::VdmCodec;
 use mctp_vdm_common::protocol::header::{
     VdmCompletionCode, VdmMsgHeader, MCTP_VDM_MSG_TYPE, VDM_MSG_HEADER_LEN,
@@ -80,14 +82,46 @@ impl MctpVdmSocket {
     /// The request should contain the full VDM payload (header + data).
     /// Returns the response payload (header + data).
     pub fn send_request(&mut self, request: &[u8]) -> Result<Vec<u8>, VdmTransportError> {
+        let mctp_common_header = MctpVdmCommonHeader::new();
+        self.exchange(mctp_common_header.0, request)
+    }
+
+    /// Send an MCTP control request and receive the response.
+    ///
+    /// Returns the response data following the control message header.
+    pub fn send_ctrl_request(
+        &mut self,
+        cmd: MCTPCtrlCmd,
+        data: &[u8],
+    ) -> Result<Vec<u8>, VdmTransportError> {
+        let cmd = cmd as u8;
+        let mut ctrl_msg_hdr = MCTPCtrlMsgHdr::new();
+        ctrl_msg_hdr.set_rq(1);
+        ctrl_msg_hdr.set_cmd(cmd);
+        let mut request = ctrl_msg_hdr.0.to_vec();
+        request.extend_from_slice(data);
+
+        let response = self.exchange(MctpMsgType::Ctrl as u8, &request)?;
+        if response.len() < MCTP_CTRL_MSG_HDR_SIZE {
+            return Err(VdmTransportError::Underflow);
+        }
+        let rsp_hdr = MCTPCtrlMsgHdr(&response[..MCTP_CTRL_MSG_HDR_SIZE]);
+        if rsp_hdr.rq() != 0 || rsp_hdr.cmd() != cmd {
+            return Err(VdmTransportError::InvalidResponse);
+        }
+        Ok(response[MCTP_CTRL_MSG_HDR_SIZE..].to_vec())
+    }
+
+    /// Send a message of the given MCTP message type and return the response body
+    /// following the MCTP common header.
+    fn exchange(&mut self, msg_type: u8, body: &[u8]) -> Result<Vec<u8>, VdmTransportError> {
         let mut mctp_util = MctpUtil::new();
         mctp_util.set_pkt_payload_size(MAX_VDM_PAYLOAD_SIZE);
 
-        // Build MCTP payload: common header + VDM request
+        // Build MCTP payload: common header + message body
         let mut mctp_payload: Vec<u8> = Vec::new();
-        let mctp_common_header = MctpVdmCommonHeader::new();
-        mctp_payload.push(mctp_common_header.0);
-        mctp_payload.extend_from_slice(request);
+        mctp_payload.push(msg_type);
+        mctp_payload.extend_from_slice(body);
 
         // Send request and wait for response
         mctp_util.new_req(self.msg_tag);
@@ -103,7 +137,7 @@ impl MctpVdmSocket {
         // Increment message tag for next request
         self.msg_tag = (self.msg_tag + 1) & 0x07;
 
-        // Skip MCTP common header and return VDM payload
+        // Skip MCTP common header and return the message body
         if response.len() <= 1 {
             return Err(VdmTransportError::Underflow);
         }
@@ -165,6 +199,15 @@ impl VdmClient {
         self.socket.send_request(request)
     }
 
+    /// Send an MCTP control request to the VDM responder endpoint.
+    pub fn send_ctrl_request(
+        &mut self,
+        cmd: MCTPCtrlCmd,
+        data: &[u8],
+    ) -> Result<Vec<u8>, VdmTransportError> {
+        self.socket.send_ctrl_request(cmd, data)
+    }
+
     /// Send a command with just the header (no payload).
     pub fn send_command(&mut self, command_code: u8) -> Result<Vec<u8>, VdmTransportError> {
         let header = VdmMsgHeader::new_request(command_code);
@@ -0,0 +1,138 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+File Name:
+
+    uart_socket_server.rs
+
+Abstract:
+
+    UART over TCP socket implementation.
+
+    The protocol is a raw byte stream. The bytes written by the MCU to the UART
+    are forwarded to the client, and the bytes written by the client are fed to
+    the UART receiver one at a time.
+
+    Only one client is served at a time. The UART output is dropped while no
+    client is connected.
+
+--*/
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Starts the UART socket server.
+///
+/// The bytes received from the client are written to `uart_rx`, the input of the
+/// emulated UART, as soon as the previous byte has been read by the MCU.
+///
+/// # Returns
+/// The sender to which the UART output is written.
+pub fn start_uart_socket(
+    running: &'static AtomicBool,
+    port: u16,
+    uart_rx: Arc<Mutex<Option<u8>>>,
+) -> Sender<u8> {
+    let listener = TcpListener::bind(format!("127.0.0.1:{}", port))
+        .expect("Failed to bind TCP socket for port");
+
+    let (uart_tx_tx, uart_tx_rx) = mpsc::channel::<u8>();
+    let (uart_rx_tx, uart_rx_rx) = mpsc::channel::<u8>();
+    std::thread::spawn(move || handle_uart_socket_loop(running, listener, uart_tx_rx, uart_rx_tx));
+    std::thread::spawn(move || handle_uart_rx(running, uart_rx_rx, uart_rx));
+
+    uart_tx_tx
+}
+
+fn handle_uart_socket_loop(
+    running: &'static AtomicBool,
+    listener: TcpListener,
+    uart_tx_rx: Receiver<u8>,
+    uart_rx_tx: Sender<u8>,
+) {
+    listener
+        .set_nonblocking(true)
+        .expect("Could not set non-blocking");
+    while running.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, addr)) => {
+                println!("Accepting UART socket connection from {:?}", addr);
+                handle_uart_socket_connection(running, stream, &uart_tx_rx, &uart_rx_tx);
+            }
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                while uart_tx_rx.try_recv().is_ok() {}
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Err(e) => panic!("Error accepting connection: {}", e),
+        }
+    }
+}
+
+fn handle_uart_socket_connection(
+    running: &'static AtomicBool,
+    mut stream: TcpStream,
+    uart_tx_rx: &Receiver<u8>,
+    uart_rx_tx: &Sender<u8>,
+) {
+    stream.set_nonblocking(true).unwrap();
+
+    let mut data = [0u8; 256];
+    while running.load(Ordering::Relaxed) {
+        match stream.read(&mut data) {
+            Ok(0) => {
+                println!("handle_uart_socket_connection: Connection closed by client");
+                break;
+            }
+            Ok(len) => {
+                for byte in &data[..len] {
+                    uart_rx_tx
+                        .send(*byte)
+                        .expect("Failed to send byte to the UART");
+                }
+            }
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(ref e) if e.kind() == ErrorKind::ConnectionReset => {
+                println!("handle_uart_socket_connection: Connection reset by client");
+                break;
+            }
+            Err(e) => panic!("Error reading from socket: {}", e),
+        }
+
+        if let Ok(byte) = uart_tx_rx.recv_timeout(Duration::from_millis(1)) {
+            let mut output = vec![byte];
+            output.extend(uart_tx_rx.try_iter());
+            if stream.write_all(&output).is_err() {
+                println!("handle_uart_socket_connection: Failed to write to socket");
+                break;
+            }
+        }
+    }
+}
+
+fn handle_uart_rx(
+    running: &'static AtomicBool,
+    uart_rx_rx: Receiver<u8>,
+    uart_rx: Arc<Mutex<Option<u8>>>,
+) {
+    while let Ok(byte) = uart_rx_rx.recv() {
+        // The UART holds a single byte until the MCU reads it
+        loop {
+            if !running.load(Ordering::Relaxed) {
+                return;
+            }
+            let mut uart_rx = uart_rx.lock().unwrap();
+            if uart_rx.is_none() {
+                *uart_rx = Some(byte);
+                break;
+            }
+            drop(uart_rx);
+            std::thread::yield_now();
+        }
+    }
+}
@@ -0,0 +1,354 @@
+// Licensed under the Apache-2.0 license
+
+//! Fields of X.509 certificates (RFC 5280) needed to validate certificate chains.
+
+use crate::der::{
+    DerError, DerReader, DerResult, DER_TAG_BIT_STRING, DER_TAG_BOOLEAN, DER_TAG_CONTEXT_0,
+    DER_TAG_CONTEXT_3, DER_TAG_INTEGER, DER_TAG_OCTET_STRING, DER_TAG_OID, DER_TAG_SEQUENCE,
+};
+
+pub const ECC_P384_PARAM_SIZE: usize = 48;
+pub const ECC_P384_SIGNATURE_SIZE: usize = 2 * ECC_P384_PARAM_SIZE;
+
+// AlgorithmIdentifier contents for ecdsa-with-SHA384 (1.2.840.10045.4.3.3)
+pub const ECDSA_WITH_SHA384_ALG_ID: &[u8] =
+    &[0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x03];
+// AlgorithmIdentifier contents for ecdsa-with-SHA512 (1.2.840.10045.4.3.4)
+pub const ECDSA_WITH_SHA512_ALG_ID: &[u8] =
+    &[0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x04];
+// AlgorithmIdentifier contents for id-ml-dsa-87 (2.16.840.1.101.3.4.3.19), used both
+// for the signature and the public key
+pub const ML_DSA_87_ALG_ID: &[u8] = &[
+    0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x03, 0x13,
+];
+// AlgorithmIdentifier contents for id-ecPublicKey (1.2.840.10045.2.1) with secp384r1 (1.3.132.0.34)
+pub const EC_P384_PUBLIC_KEY_ALG_ID: &[u8] = &[
+    0x06, 0x07, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01, 0x06, 0x05, 0x2B, 0x81, 0x04, 0x00, 0x22,
+];
+// Uncompressed EC point marker
+pub const EC_POINT_UNCOMPRESSED: u8 = 0x04;
+// OBJECT IDENTIFIER contents for id-ce-basicConstraints (2.5.29.19)
+const BASIC_CONSTRAINTS_OID: &[u8] = &[0x55, 0x1D, 0x13];
+// DER encoding of a TRUE BOOLEAN value
+const DER_TRUE: u8 = 0xFF;
+
+/// Fields of an X.509 certificate needed for chain validation
+pub struct X509Cert<'a> {
+    /// DER encoding of the TBSCertificate, covered by the signature
+    pub tbs: &'a [u8],
+    /// Contents of the signatureAlgorithm AlgorithmIdentifier
+    pub signature_alg_id: &'a [u8],
+    /// Contents of the signatureValue BIT STRING, without the unused bits octet
+    pub signature: &'a [u8],
+    /// DER encoding of the issuer Name
+    pub issuer: &'a [u8],
+    /// DER encoding of the subject Name
+    pub subject: &'a [u8],
+    /// DER encoding of the SubjectPublicKeyInfo
+    pub public_key_info: &'a [u8],
+    /// Contents of the SubjectPublicKeyInfo algorithm AlgorithmIdentifier
+    pub public_key_alg_id: &'a [u8],
+    /// Contents of the subjectPublicKey BIT STRING, without the unused bits octet
+    pub public_key: &'a [u8],
+    /// Contents of the Extensions SEQUENCE, empty if the certificate has no extensions
+    pub extensions: &'a [u8],
+}
+
+impl<'a> X509Cert<'a> {
+    /// Parses the DER encoding of a certificate.
+    ///
+    /// Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signatureValue }
+    pub fn parse(cert_der: &'a [u8]) -> DerResult<Self> {
+        let mut outer = DerReader::new(cert_der);
+        let mut body = DerReader::new(outer.expect(DER_TAG_SEQUENCE)?);
+        let (tag, tbs_body, tbs) = body.read_tlv()?;
+        if tag != DER_TAG_SEQUENCE {
+            Err(DerError::InvalidEncoding)?;
+        }
+        let signature_alg_id = body.expect(DER_TAG_SEQUENCE)?;
+        let signature = bit_string_bytes(body.expect(DER_TAG_BIT_STRING)?)?;
+
+        // TBSCertificate ::= SEQUENCE { [0] version, serialNumber, signature, issuer,
+        //                               validity, subject, subjectPublicKeyInfo, ... }
+        let mut fields = DerReader::new(tbs_body);
+        if fields.peek_tag() == Some(DER_TAG_CONTEXT_0) {
+            fields.read_tlv()?;
+        }
+        fields.expect(DER_TAG_INTEGER)?; // serialNumber
+        fields.expect(DER_TAG_SEQUENCE)?; // signature
+        let issuer = fields.expect_tlv(DER_TAG_SEQUENCE)?;
+        fields.expect(DER_TAG_SEQUENCE)?; // validity
+        let subject = fields.expect_tlv(DER_TAG_SEQUENCE)?;
+        let public_key_info = fields.expect_tlv(DER_TAG_SEQUENCE)?;
+
+        // SubjectPublicKeyInfo ::= SEQUENCE { algorithm, subjectPublicKey BIT STRING }
+        let mut spki = DerReader::new(DerReader::new(public_key_info).expect(DER_TAG_SEQUENCE)?);
+        let public_key_alg_id = spki.expect(DER_TAG_SEQUENCE)?;
+        let public_key = bit_string_bytes(spki.expect(DER_TAG_BIT_STRING)?)?;
+
+        // issuerUniqueID [1] and subjectUniqueID [2] are skipped, extensions are in [3]
+        let mut extensions: &[u8] = &[];
+        while !fields.is_empty() {
+            let (tag, value, _) = fields.read_tlv()?;
+            if tag == DER_TAG_CONTEXT_3 {
+                extensions = DerReader::new(value).expect(DER_TAG_SEQUENCE)?;
+            }
+        }
+
+        Ok(Self {
+            tbs,
+            signature_alg_id,
+            signature,
+            issuer,
+            subject,
+            public_key_info,
+            public_key_alg_id,
+            public_key,
+            extensions,
+        })
+    }
+
+    /// Returns true if the basicConstraints extension of the certificate sets cA, which
+    /// allows its key to sign certificates.
+    ///
+    /// Extension ::= SEQUENCE { extnID, critical BOOLEAN DEFAULT FALSE, extnValue OCTET STRING }
+    /// BasicConstraints ::= SEQUENCE { cA BOOLEAN DEFAULT FALSE, pathLenConstraint OPTIONAL }
+    pub fn is_ca(&self) -> DerResult<bool> {
+        let mut extensions = DerReader::new(self.extensions);
+        while !extensions.is_empty() {
+            let mut extension = DerReader::new(extensions.expect(DER_TAG_SEQUENCE)?);
+            if extension.expect(DER_TAG_OID)? != BASIC_CONSTRAINTS_OID {
+                continue;
+            }
+            if extension.peek_tag() == Some(DER_TAG_BOOLEAN) {
+                extension.read_tlv()?; // critical
+            }
+            let mut value = DerReader::new(extension.expect(DER_TAG_OCTET_STRING)?);
+            let mut constraints = DerReader::new(value.expect(DER_TAG_SEQUENCE)?);
+            if constraints.peek_tag() != Some(DER_TAG_BOOLEAN) {
+                return Ok(false);
+            }
+            return Ok(constraints.expect(DER_TAG_BOOLEAN)? == [DER_TRUE]);
+        }
+        Ok(false)
+    }
+}
+
+/// Returns the x and y coordinates of an ECPoint in the uncompressed form: 0x04 || x || y
+pub fn parse_ecc_p384_public_key(
+    key_bytes: &[u8],
+) -> DerResult<([u8; ECC_P384_PARAM_SIZE], [u8; ECC_P384_PARAM_SIZE])> {
+    match key_bytes.split_first() {
+        Some((&EC_POINT_UNCOMPRESSED, point)) if point.len() == 2 * ECC_P384_PARAM_SIZE => {
+            let (x, y) = point.split_at(ECC_P384_PARAM_SIZE);
+            Ok((x.try_into().unwrap(), y.try_into().unwrap()))
+        }
+        _ => Err(DerError::InvalidEncoding),
+    }
+}
+
+/// Returns the contents of a BIT STRING made of whole octets
+pub fn bit_string_bytes(bits: &[u8]) -> DerResult<&[u8]> {
+    match bits.split_first() {
+        Some((&0, bytes)) => Ok(bytes),
+        _ => Err(DerError::InvalidEncoding),
+    }
+}
+
+/// Returns an ECDSA P-384 signature in raw r || s format.
+///
+/// Ecdsa-Sig-Value ::= SEQUENCE { r INTEGER, s INTEGER }
+pub fn parse_ecdsa_signature(sig_der: &[u8]) -> DerResult<[u8; ECC_P384_SIGNATURE_SIZE]> {
+    let mut outer = DerReader::new(sig_der);
+    let mut sig_seq = DerReader::new(outer.expect(DER_TAG_SEQUENCE)?);
+
+    let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
+    let (r, s) = signature.split_at_mut(ECC_P384_PARAM_SIZE);
+    copy_der_integer(sig_seq.expect(DER_TAG_INTEGER)?, r)?;
+    copy_der_integer(sig_seq.expect(DER_TAG_INTEGER)?, s)?;
+    Ok(signature)
+}
+
+/// Copies an unsigned DER INTEGER into a fixed-size big-endian buffer
+pub fn copy_der_integer(int: &[u8], out: &mut [u8]) -> DerResult<()> {
+    let first_non_zero = int.iter().position(|&b| b != 0).unwrap_or(int.len());
+    let int = &int[first_non_zero..];
+    if int.len() > out.len() {
+        Err(DerError::InvalidEncoding)?;
+    }
+    let pad = out.len() - int.len();
+    out[..pad].fill(0);
+    out[pad..].copy_from_slice(int);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::der::DerWriter;
+
+    // Appends a TLV with the given tag and value
+    fn push_tlv(out: &mut Vec<u8>, tag: u8, value: &[u8]) {
+        let mut header = [0u8; 4];
+        let mut writer = DerWriter::new(&mut header);
+        writer.write_header(tag, value.len()).unwrap();
+        let len = writer.len();
+        out.extend_from_slice(&header[..len]);
+        out.extend_from_slice(value);
+    }
+
+    // Builds a certificate with the given names, SubjectPublicKeyInfo algorithm and key, and
+    // extensions if not empty
+    fn cert_der(
+        issuer: &[u8],
+        subject: &[u8],
+        alg_id: &[u8],
+        key: &[u8],
+        extensions: &[u8],
+    ) -> Vec<u8> {
+        let mut alg = Vec::new();
+        push_tlv(&mut alg, DER_TAG_SEQUENCE, alg_id);
+        let mut key_bits = vec![0];
+        key_bits.extend_from_slice(key);
+        push_tlv(&mut alg, DER_TAG_BIT_STRING, &key_bits);
+        let mut spki = Vec::new();
+        push_tlv(&mut spki, DER_TAG_SEQUENCE, &alg);
+
+        let mut tbs_body = Vec::new();
+        push_tlv(
+            &mut tbs_body,
+            DER_TAG_CONTEXT_0,
+            &[DER_TAG_INTEGER, 0x01, 0x02],
+        );
+        push_tlv(&mut tbs_body, DER_TAG_INTEGER, &[0x01]);
+        push_tlv(&mut tbs_body, DER_TAG_SEQUENCE, ECDSA_WITH_SHA384_ALG_ID);
+        push_tlv(&mut tbs_body, DER_TAG_SEQUENCE, issuer);
+        push_tlv(&mut tbs_body, DER_TAG_SEQUENCE, &[]);
+        push_tlv(&mut tbs_body, DER_TAG_SEQUENCE, subject);
+        tbs_body.extend_from_slice(&spki);
+        if !extensions.is_empty() {
+            let mut seq = Vec::new();
+            push_tlv(&mut seq, DER_TAG_SEQUENCE, extensions);
+            push_tlv(&mut tbs_body, DER_TAG_CONTEXT_3, &seq);
+        }
+
+        let mut body = Vec::new();
+        push_tlv(&mut body, DER_TAG_SEQUENCE, &tbs_body);
+        push_tlv(&mut body, DER_TAG_SEQUENCE, ECDSA_WITH_SHA384_ALG_ID);
+        push_tlv(&mut body, DER_TAG_BIT_STRING, &[0x00, 0x5A]);
+        let mut cert = Vec::new();
+        push_tlv(&mut cert, DER_TAG_SEQUENCE, &body);
+        cert
+    }
+
+    #[test]
+    fn test_parse_cert() {
+        let mut ecc_key = [0u8; 1 + 2 * ECC_P384_PARAM_SIZE];
+        ecc_key[0] = EC_POINT_UNCOMPRESSED;
+        ecc_key[1..1 + ECC_P384_PARAM_SIZE].fill(0x11);
+        ecc_key[1 + ECC_P384_PARAM_SIZE..].fill(0x22);
+        let der = cert_der(
+            &[0x31, 0x00],
+            &[0x31, 0x01, 0x00],
+            EC_P384_PUBLIC_KEY_ALG_ID,
+            &ecc_key,
+            &[],
+        );
+
+        let cert = X509Cert::parse(&der).unwrap();
+        assert_eq!(cert.issuer, &[DER_TAG_SEQUENCE, 0x02, 0x31, 0x00]);
+        assert_eq!(cert.subject, &[DER_TAG_SEQUENCE, 0x03, 0x31, 0x01, 0x00]);
+        assert_eq!(cert.signature_alg_id, ECDSA_WITH_SHA384_ALG_ID);
+        assert_eq!(cert.signature, &[0x5A]);
+        assert_eq!(cert.public_key_alg_id, EC_P384_PUBLIC_KEY_ALG_ID);
+        let (x, y) = parse_ecc_p384_public_key(cert.public_key).unwrap();
+        assert_eq!(x, [0x11; ECC_P384_PARAM_SIZE]);
+        assert_eq!(y, [0x22; ECC_P384_PARAM_SIZE]);
+        assert!(cert.extensions.is_empty());
+        assert_eq!(cert.is_ca(), Ok(false));
+
+        // Truncated certificate
+        assert_eq!(
+            X509Cert::parse(&der[..der.len() - 1]).err(),
+            Some(DerError::InvalidEncoding)
+        );
+        // Compressed point
+        assert_eq!(
+            parse_ecc_p384_public_key(&[0x02; 1 + ECC_P384_PARAM_SIZE]),
+            Err(DerError::InvalidEncoding)
+        );
+    }
+
+    // Builds an extension with the given OID, critical flag and extnValue contents
+    fn extension(oid: &[u8], critical: bool, value: &[u8]) -> Vec<u8> {
+        let mut fields = Vec::new();
+        push_tlv(&mut fields, DER_TAG_OID, oid);
+        if critical {
+            push_tlv(&mut fields, DER_TAG_BOOLEAN, &[DER_TRUE]);
+        }
+        push_tlv(&mut fields, DER_TAG_OCTET_STRING, value);
+        let mut ext = Vec::new();
+        push_tlv(&mut ext, DER_TAG_SEQUENCE, &fields);
+        ext
+    }
+
+    #[test]
+    fn test_is_ca() {
+        let is_ca = |extensions: &[u8]| {
+            let der = cert_der(&[], &[], EC_P384_PUBLIC_KEY_ALG_ID, &[], extensions);
+            X509Cert::parse(&der).unwrap().is_ca()
+        };
+        // keyUsage (2.5.29.15) with keyCertSign
+        let key_usage = extension(&[0x55, 0x1D, 0x0F], true, &[0x03, 0x02, 0x02, 0x04]);
+
+        // cA TRUE after another extension, with a pathLenConstraint
+        let mut extensions = key_usage.clone();
+        extensions.extend(extension(
+            BASIC_CONSTRAINTS_OID,
+            true,
+            &[0x30, 0x06, 0x01, 0x01, 0xFF, 0x02, 0x01, 0x00],
+        ));
+        assert_eq!(is_ca(&extensions), Ok(true));
+
+        // cA FALSE, cA omitted and basicConstraints missing
+        let extensions = extension(
+            BASIC_CONSTRAINTS_OID,
+            false,
+            &[0x30, 0x03, 0x01, 0x01, 0x00],
+        );
+        assert_eq!(is_ca(&extensions), Ok(false));
+        let extensions = extension(BASIC_CONSTRAINTS_OID, true, &[0x30, 0x00]);
+        assert_eq!(is_ca(&extensions), Ok(false));
+        assert_eq!(is_ca(&key_usage), Ok(false));
+
+        // basicConstraints without a SEQUENCE
+        let extensions = extension(BASIC_CONSTRAINTS_OID, true, &[0x01, 0x01, 0xFF]);
+        assert_eq!(is_ca(&extensions), Err(DerError::InvalidEncoding));
+    }
+
+    #[test]
+    fn test_parse_ecdsa_signature() {
+        // r with a leading sign byte, s shorter than the field size
+        let mut sig_bits = [0u8; 3 + 2 + 49 + 2 + 47];
+        sig_bits[..3].copy_from_slice(&[0x00, DER_TAG_SEQUENCE, 2 + 49 + 2 + 47]);
+        sig_bits[3..5].copy_from_slice(&[DER_TAG_INTEGER, 49]);
+        sig_bits[5] = 0x00;
+        sig_bits[6..54].fill(0xAA);
+        sig_bits[54..56].copy_from_slice(&[DER_TAG_INTEGER, 47]);
+        sig_bits[56..].fill(0x55);
+
+        let signature = parse_ecdsa_signature(bit_string_bytes(&sig_bits).unwrap()).unwrap();
+        assert_eq!(signature[..48], [0xAA; 48]);
+        assert_eq!(signature[48], 0);
+        assert_eq!(signature[49..], [0x55; 47]);
+    }
+
+    #[test]
+    fn test_copy_der_integer_too_large() {
+        let mut out = [0u8; 4];
+        assert_eq!(
+            copy_der_integer(&[0x01, 0x02, 0x03, 0x04, 0x05], &mut out),
+            Err(DerError::InvalidEncoding)
+        );
+    }
+}
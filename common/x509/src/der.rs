@@ -0,0 +1,220 @@
+// Licensed under the Apache-2.0 license
+
+//! Minimal DER reader and writer for the ASN.1 structures of certificates and CSRs.
+
+pub const DER_TAG_BOOLEAN: u8 = 0x01;
+pub const DER_TAG_INTEGER: u8 = 0x02;
+pub const DER_TAG_BIT_STRING: u8 = 0x03;
+pub const DER_TAG_OCTET_STRING: u8 = 0x04;
+pub const DER_TAG_OID: u8 = 0x06;
+pub const DER_TAG_SEQUENCE: u8 = 0x30;
+pub const DER_TAG_SET: u8 = 0x31;
+// Context-specific constructed [0], used for explicit versions and CSR attributes
+pub const DER_TAG_CONTEXT_0: u8 = 0xA0;
+// Context-specific constructed [3], used for the extensions of a certificate
+pub const DER_TAG_CONTEXT_3: u8 = 0xA3;
+
+#[derive(Debug, PartialEq)]
+pub enum DerError {
+    InvalidEncoding,
+    BufferTooSmall,
+}
+
+pub type DerResult<T> = Result<T, DerError>;
+
+/// Minimal DER TLV reader
+pub struct DerReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> DerReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn read_byte(&mut self) -> DerResult<u8> {
+        let byte = *self.data.get(self.pos).ok_or(DerError::InvalidEncoding)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    /// Reads the next TLV and returns its tag, value and complete encoding
+    pub fn read_tlv(&mut self) -> DerResult<(u8, &'a [u8], &'a [u8])> {
+        let start = self.pos;
+        let tag = self.read_byte()?;
+
+        let len_byte = self.read_byte()?;
+        let len = match len_byte {
+            0..=0x7F => len_byte as usize,
+            0x81 => self.read_byte()? as usize,
+            0x82 => {
+                let hi = self.read_byte()? as usize;
+                let lo = self.read_byte()? as usize;
+                (hi << 8) | lo
+            }
+            _ => Err(DerError::InvalidEncoding)?,
+        };
+
+        let value_start = self.pos;
+        let end = value_start
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or(DerError::InvalidEncoding)?;
+        self.pos = end;
+
+        Ok((tag, &self.data[value_start..end], &self.data[start..end]))
+    }
+
+    /// Reads the next TLV, which must have the expected tag, and returns its value
+    pub fn expect(&mut self, expected_tag: u8) -> DerResult<&'a [u8]> {
+        let (tag, value, _) = self.read_tlv()?;
+        if tag != expected_tag {
+            Err(DerError::InvalidEncoding)?;
+        }
+        Ok(value)
+    }
+
+    /// Reads the next TLV, which must have the expected tag, and returns its complete encoding
+    pub fn expect_tlv(&mut self, expected_tag: u8) -> DerResult<&'a [u8]> {
+        let (tag, _, tlv) = self.read_tlv()?;
+        if tag != expected_tag {
+            Err(DerError::InvalidEncoding)?;
+        }
+        Ok(tlv)
+    }
+}
+
+/// Returns the size of the tag and length octets of a TLV with a value of `len` bytes
+pub fn header_len(len: usize) -> usize {
+    match len {
+        0..=0x7F => 2,
+        0x80..=0xFF => 3,
+        _ => 4,
+    }
+}
+
+/// Minimal DER writer into a fixed-size buffer
+pub struct DerWriter<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> DerWriter<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos == 0
+    }
+
+    pub fn write_bytes(&mut self, data: &[u8]) -> DerResult<()> {
+        let end = self
+            .pos
+            .checked_add(data.len())
+            .filter(|&end| end <= self.buf.len())
+            .ok_or(DerError::BufferTooSmall)?;
+        self.buf[self.pos..end].copy_from_slice(data);
+        self.pos = end;
+        Ok(())
+    }
+
+    /// Writes the tag and length octets of a TLV with a value of `len` bytes
+    pub fn write_header(&mut self, tag: u8, len: usize) -> DerResult<()> {
+        match len {
+            0..=0x7F => self.write_bytes(&[tag, len as u8]),
+            0x80..=0xFF => self.write_bytes(&[tag, 0x81, len as u8]),
+            0x100..=0xFFFF => self.write_bytes(&[tag, 0x82, (len >> 8) as u8, len as u8]),
+            _ => Err(DerError::BufferTooSmall),
+        }
+    }
+
+    /// Writes an unsigned big-endian integer as a DER INTEGER
+    pub fn write_unsigned_integer(&mut self, int: &[u8]) -> DerResult<()> {
+        let first_non_zero = int.iter().position(|&b| b != 0).unwrap_or(int.len() - 1);
+        let int = &int[first_non_zero..];
+        // A leading zero octet keeps the integer positive
+        let sign_octet = int[0] & 0x80 != 0;
+        self.write_header(DER_TAG_INTEGER, int.len() + sign_octet as usize)?;
+        if sign_octet {
+            self.write_bytes(&[0])?;
+        }
+        self.write_bytes(int)
+    }
+}
+
+/// Returns the size of the DER INTEGER encoding an unsigned big-endian integer
+pub fn unsigned_integer_len(int: &[u8]) -> usize {
+    let first_non_zero = int.iter().position(|&b| b != 0).unwrap_or(int.len() - 1);
+    let int = &int[first_non_zero..];
+    let len = int.len() + (int[0] & 0x80 != 0) as usize;
+    header_len(len) + len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_der_reader_lengths() {
+        // Short form INTEGER followed by a long form SEQUENCE
+        let mut data = [0u8; 3 + 3 + 0x90];
+        data[..3].copy_from_slice(&[DER_TAG_INTEGER, 0x01, 0x05]);
+        data[3..6].copy_from_slice(&[DER_TAG_SEQUENCE, 0x81, 0x90]);
+
+        let mut reader = DerReader::new(&data);
+        assert_eq!(reader.expect(DER_TAG_INTEGER).unwrap(), &[0x05]);
+        let (tag, value, raw) = reader.read_tlv().unwrap();
+        assert_eq!(tag, DER_TAG_SEQUENCE);
+        assert_eq!(value.len(), 0x90);
+        assert_eq!(raw.len(), 3 + 0x90);
+        assert!(reader.is_empty());
+
+        // Length beyond the end of the buffer
+        let mut reader = DerReader::new(&[DER_TAG_SEQUENCE, 0x05, 0x00]);
+        assert_eq!(reader.read_tlv(), Err(DerError::InvalidEncoding));
+    }
+
+    #[test]
+    fn test_der_writer_round_trip() {
+        let mut buf = [0u8; 0x200];
+        let mut writer = DerWriter::new(&mut buf);
+        writer.write_header(DER_TAG_OCTET_STRING, 0x100).unwrap();
+        writer.write_bytes(&[0x5A; 0x100]).unwrap();
+        writer
+            .write_unsigned_integer(&[0x00, 0x00, 0x80, 0x01])
+            .unwrap();
+        writer.write_unsigned_integer(&[0x00, 0x7F]).unwrap();
+        let len = writer.len();
+        assert_eq!(len, 4 + 0x100 + 5 + 3);
+        assert_eq!(unsigned_integer_len(&[0x00, 0x00, 0x80, 0x01]), 5);
+        assert_eq!(unsigned_integer_len(&[0x00, 0x7F]), 3);
+
+        let mut reader = DerReader::new(&buf[..len]);
+        assert_eq!(reader.expect(DER_TAG_OCTET_STRING).unwrap(), &[0x5A; 0x100]);
+        assert_eq!(reader.expect(DER_TAG_INTEGER).unwrap(), &[0x00, 0x80, 0x01]);
+        assert_eq!(reader.expect(DER_TAG_INTEGER).unwrap(), &[0x7F]);
+        assert!(reader.is_empty());
+
+        // The buffer is too small for the TLV
+        let mut small = [0u8; 4];
+        let mut writer = DerWriter::new(&mut small);
+        assert_eq!(
+            writer.write_unsigned_integer(&[0xFF; 4]),
+            Err(DerError::BufferTooSmall)
+        );
+    }
+}
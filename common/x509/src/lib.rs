@@ -0,0 +1,12 @@
+// Licensed under the Apache-2.0 license
+
+//! DER encoding and X.509 certificate parsing shared by the firmware that validates
+//! certificate chains.
+
+#![cfg_attr(target_arch = "riscv32", no_std)]
+
+mod cert;
+mod der;
+
+pub use cert::*;
+pub use der::*;
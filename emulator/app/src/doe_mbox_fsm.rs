@@ -2,6 +2,7 @@
 
 use emulator_periph::DoeMboxPeriph;
 use mcu_testing_common::{sleep_emulator_ticks, wait_for_runtime_start, MCU_RUNNING};
+use std::collections::VecDeque;
 use std::process::exit;
 use std::sync::atomic::Ordering;
 use std::sync::mpsc::{Receiver, Sender};
@@ -21,11 +22,25 @@ enum DoeMboxState {
 
 pub struct DoeMboxFsm {
     doe_mbox: DoeMboxPeriph,
+    posted: bool,
 }
 
 impl DoeMboxFsm {
     pub fn new(doe_mbox: DoeMboxPeriph) -> Self {
-        Self { doe_mbox }
+        Self {
+            doe_mbox,
+            posted: false,
+        }
+    }
+
+    /// Creates a state machine for posted messages, such as PCIe VDMs, which do not each
+    /// get a response. Messages are written once the MCU has taken the previous one, and
+    /// messages from the MCU are received at any time.
+    pub fn new_posted(doe_mbox: DoeMboxPeriph) -> Self {
+        Self {
+            doe_mbox,
+            posted: true,
+        }
     }
 
     pub fn start(&mut self) -> (Receiver<Vec<u8>>, Sender<Vec<u8>>) {
@@ -33,6 +48,21 @@ impl DoeMboxFsm {
         let (fsm_to_test_tx, fsm_to_test_rx) = std::sync::mpsc::channel::<Vec<u8>>();
         let doe_mbox_clone = self.doe_mbox.clone();
 
+        if self.posted {
+            thread::spawn(move || {
+                let mut fsm = PostedMboxStateMachine::new(doe_mbox_clone, fsm_to_test_tx);
+
+                while MCU_RUNNING.load(Ordering::Relaxed) {
+                    while let Ok(message) = test_to_fsm_rx.try_recv() {
+                        fsm.outgoing.push_back(message);
+                    }
+                    fsm.on_event();
+                    sleep_emulator_ticks(1000);
+                }
+            });
+            return (fsm_to_test_rx, test_to_fsm_tx);
+        }
+
         thread::spawn(move || {
             let mut fsm = DoeMboxStateMachine::new(doe_mbox_clone, fsm_to_test_tx);
 
@@ -162,6 +192,44 @@ impl DoeMboxStateMachine {
     }
 }
 
+struct PostedMboxStateMachine {
+    doe_mbox: DoeMboxPeriph,
+    fsm_to_test_tx: Sender<Vec<u8>>,
+    outgoing: VecDeque<Vec<u8>>,
+}
+
+impl PostedMboxStateMachine {
+    fn new(doe_mbox: DoeMboxPeriph, fsm_to_test_tx: Sender<Vec<u8>>) -> Self {
+        Self {
+            doe_mbox,
+            fsm_to_test_tx,
+            outgoing: VecDeque::new(),
+        }
+    }
+
+    fn on_event(&mut self) {
+        // Take the message from the MCU first, the mailbox SRAM is shared by both directions
+        match self.doe_mbox.read_data() {
+            Ok(Some(data)) => self.fsm_to_test_tx.send(data).unwrap(),
+            Ok(None) => {}
+            Err(e) => println!("DOE_MBOX_FSM: {}", e),
+        }
+
+        if self.doe_mbox.is_write_pending() {
+            return;
+        }
+        if let Some(message) = self.outgoing.pop_front() {
+            println!(
+                "DOE_MBOX_FSM: Posting message of length {} dwords",
+                message.len() / 4
+            );
+            if let Err(e) = self.doe_mbox.write_data(message) {
+                println!("DOE_MBOX_FSM: Failed to post message: {}", e);
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DoeTestState {
     Start,
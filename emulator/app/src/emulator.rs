@@ -43,6 +43,7 @@ use mcu_testing_common::i3c_socket;
 use mcu_testing_common::i3c_socket_server::start_i3c_socket;
 use mcu_testing_common::mctp_transport::MctpTransport;
 use mcu_testing_common::mctp_util::base_protocol::LOCAL_TEST_ENDPOINT_EID;
+use mcu_testing_common::uart_socket_server::start_uart_socket;
 use mcu_testing_common::{MCU_RUNNING, MCU_RUNTIME_STARTED, MCU_TICKS, TICK_COND};
 use pldm_fw_pkg::FirmwareManifest;
 use pldm_ua::daemon::PldmDaemon;
@@ -134,6 +135,11 @@ pub struct EmulatorArgs {
     #[arg(long)]
     pub i3c_port: Option<u16>,
 
+    /// TCP port on which to expose the MCU UART, e.g. for MCTP over serial.
+    /// The UART does not take stdin nor print to the console when exposed.
+    #[arg(long)]
+    pub uart_port: Option<u16>,
+
     /// Device lifecycle value (0=Unprovisioned, 1=Manufacturing, 2=Reserved, 3=Production).
     #[arg(long, value_parser = maybe_hex::<u32>, default_value_t = DeviceLifecycle::Production as u32)]
     pub device_security_state: u32,
@@ -382,11 +388,20 @@ impl Emulator {
             None
         };
 
-        let stdin_uart = if cli.stdin_uart && std::io::stdin().is_terminal() {
+        let read_stdin =
+            cli.uart_port.is_none() && cli.stdin_uart && std::io::stdin().is_terminal();
+        let stdin_uart = if read_stdin || cli.uart_port.is_some() {
             Some(Arc::new(Mutex::new(None)))
         } else {
             None
         };
+        let uart_socket_tx = match (cli.uart_port, stdin_uart.clone()) {
+            (Some(uart_port), Some(uart_rx)) => {
+                println!("Starting UART Socket, port {}", uart_port);
+                Some(start_uart_socket(&MCU_RUNNING, uart_port, uart_rx))
+            }
+            _ => None,
+        };
         let pic = Rc::new(Pic::new());
 
         let mut mcu_root_bus_offsets = McuRootBusOffsets::default();
@@ -482,14 +497,18 @@ impl Emulator {
             auto_root_bus_offsets.lc_size = lc_size;
         }
 
+        let pcie_vdm_mbox_periph = DoeMboxPeriph::default();
+
         let bus_args = McuRootBusArgs {
             offsets: mcu_root_bus_offsets.clone(),
             rom: rom_buffer,
             log_dir: args_log_dir.clone(),
             uart_output: uart_output.clone(),
             uart_rx: stdin_uart.clone(),
+            uart_socket_tx,
             pic: pic.clone(),
             clock: clock.clone(),
+            pcie_vdm_mbox: Some(pcie_vdm_mbox_periph.clone()),
         };
         let root_bus = McuRootBus::new(bus_args).unwrap();
 
@@ -553,6 +572,37 @@ impl Emulator {
             println!("Starting DOE user loopback test thread");
             let tests = tests::doe_user_loopback::generate_tests();
             doe_mbox_fsm::run_doe_transport_tests(test_tx, test_rx, tests);
+        } else if cfg!(feature = "test-mctp-pcie-vdm") {
+            // VDMs are posted, so a message can span several VDMs in either direction
+            let (test_rx, test_tx) =
+                doe_mbox_fsm::DoeMboxFsm::new_posted(pcie_vdm_mbox_periph.clone()).start();
+            println!("Starting MCTP over PCIe VDM test thread");
+            let tests = tests::mctp_pcie_vdm::MctpPcieVdmTest::generate_tests();
+            doe_mbox_fsm::run_doe_transport_tests(test_tx, test_rx, tests);
+        } else if cfg!(feature = "test-mctp-bridge") {
+            i3c_controller_join_handle = Some(i3c_controller.start());
+            // The endpoint behind the bridge is on the PCIe VDM mailbox
+            let (endpoint_rx, endpoint_tx) =
+                doe_mbox_fsm::DoeMboxFsm::new_posted(pcie_vdm_mbox_periph.clone()).start();
+            tests::mctp_bridge::run_downstream_endpoint(endpoint_tx, endpoint_rx);
+            println!(
+                "Starting MCTP bridge test thread for testing target {:?}",
+                i3c.get_dynamic_address().unwrap()
+            );
+
+            let tests = tests::mctp_bridge::MctpBridgeTest::generate_tests();
+            i3c_socket::run_tests(
+                cli.i3c_port.unwrap(),
+                i3c.get_dynamic_address().unwrap(),
+                tests,
+                None,
+            );
+        } else if cfg!(feature = "test-mctp-serial") {
+            println!("Starting MCTP over serial test thread");
+            tests::mctp_serial::run_mctp_serial_tests(
+                cli.uart_port
+                    .expect("MCTP over serial is tested over --uart-port"),
+            );
         } else if cfg!(feature = "test-mctp-ctrl-cmds") {
             i3c_controller_join_handle = Some(i3c_controller.start());
             println!(
@@ -620,6 +670,66 @@ impl Emulator {
                 SpdmTestType::SpdmTeeIoValidator,
                 std::time::Duration::from_secs(9000), // timeout in seconds
             );
+        } else if cfg!(feature = "test-doe-spdm-pqc-requester") {
+            if std::env::var("SPDM_VALIDATOR_DIR").is_err() {
+                println!("SPDM_VALIDATOR_DIR environment variable is not set. Skipping test");
+                exit(0);
+            }
+            let (test_rx, test_tx) = doe_mbox_fsm.start();
+            crate::tests::spdm_responder_validator::doe::run_doe_spdm_conformance_test(
+                test_tx,
+                test_rx,
+                SpdmTestType::SpdmPqcRequester,
+                std::time::Duration::from_secs(9000), // timeout in seconds
+            );
+        } else if cfg!(feature = "test-doe-spdm-key-update") {
+            if std::env::var("SPDM_VALIDATOR_DIR").is_err() {
+                println!("SPDM_VALIDATOR_DIR environment variable is not set. Skipping test");
+                exit(0);
+            }
+            let (test_rx, test_tx) = doe_mbox_fsm.start();
+            crate::tests::spdm_responder_validator::doe::run_doe_spdm_conformance_test(
+                test_tx,
+                test_rx,
+                SpdmTestType::SpdmKeyUpdate,
+                std::time::Duration::from_secs(9000), // timeout in seconds
+            );
+        } else if cfg!(feature = "test-doe-spdm-psk") {
+            if std::env::var("SPDM_VALIDATOR_DIR").is_err() {
+                println!("SPDM_VALIDATOR_DIR environment variable is not set. Skipping test");
+                exit(0);
+            }
+            let (test_rx, test_tx) = doe_mbox_fsm.start();
+            crate::tests::spdm_responder_validator::doe::run_doe_spdm_conformance_test(
+                test_tx,
+                test_rx,
+                SpdmTestType::SpdmPsk,
+                std::time::Duration::from_secs(9000), // timeout in seconds
+            );
+        } else if cfg!(feature = "test-doe-spdm-mut-auth") {
+            if std::env::var("SPDM_VALIDATOR_DIR").is_err() {
+                println!("SPDM_VALIDATOR_DIR environment variable is not set. Skipping test");
+                exit(0);
+            }
+            let (test_rx, test_tx) = doe_mbox_fsm.start();
+            crate::tests::spdm_responder_validator::doe::run_doe_spdm_conformance_test(
+                test_tx,
+                test_rx,
+                SpdmTestType::SpdmMutAuth,
+                std::time::Duration::from_secs(9000), // timeout in seconds
+            );
+        } else if cfg!(feature = "test-doe-spdm-set-cert") {
+            if std::env::var("SPDM_VALIDATOR_DIR").is_err() {
+                println!("SPDM_VALIDATOR_DIR environment variable is not set. Skipping test");
+                exit(0);
+            }
+            let (test_rx, test_tx) = doe_mbox_fsm.start();
+            crate::tests::spdm_responder_validator::doe::run_doe_spdm_conformance_test(
+                test_tx,
+                test_rx,
+                SpdmTestType::SpdmSetCert,
+                std::time::Duration::from_secs(9000), // timeout in seconds
+            );
         }
 
         if cfg!(any(
@@ -636,6 +746,27 @@ impl Emulator {
             PldmRequestResponseTest::run(pldm_socket);
         }
 
+        if cfg!(feature = "test-pldm-platform") {
+            i3c_controller_join_handle = Some(i3c_controller.start());
+            let pldm_transport =
+                MctpTransport::new(cli.i3c_port.unwrap(), i3c.get_dynamic_address().unwrap());
+            // The responses to the platform events come from the event receiver EID
+            let pldm_socket = pldm_transport
+                .create_socket(EndpointId(0), EndpointId(LOCAL_TEST_ENDPOINT_EID))
+                .unwrap();
+            crate::tests::pldm_platform::run_pldm_platform_tests(pldm_socket);
+        }
+
+        if cfg!(feature = "test-pldm-fru") {
+            i3c_controller_join_handle = Some(i3c_controller.start());
+            let pldm_transport =
+                MctpTransport::new(cli.i3c_port.unwrap(), i3c.get_dynamic_address().unwrap());
+            let pldm_socket = pldm_transport
+                .create_socket(EndpointId(0), EndpointId(1))
+                .unwrap();
+            crate::tests::pldm_fru::run_pldm_fru_tests(pldm_socket);
+        }
+
         let create_flash_controller =
             |default_path: &str,
              error_irq: u8,
@@ -989,6 +1120,7 @@ impl Emulator {
             caliptra_cpu,
             instr_trace,
             stdin_uart,
+            read_stdin,
             bmc,
             sram_range,
             clock,
@@ -1007,6 +1139,7 @@ impl Emulator {
         caliptra_cpu: Cpu<CaliptraMainRootBus>,
         trace_path: Option<PathBuf>,
         stdin_uart: Option<Arc<Mutex<Option<u8>>>>,
+        read_stdin: bool,
         bmc: Option<Bmc>,
         sram_range: Range<u32>,
         clock: Rc<Clock>,
@@ -1018,7 +1151,7 @@ impl Emulator {
         i3c_controller_join_handle: Option<JoinHandle<()>>,
     ) -> Self {
         // read from the console in a separate thread to prevent blocking
-        let stdin_uart_clone = stdin_uart.clone();
+        let stdin_uart_clone = stdin_uart.clone().filter(|_| read_stdin);
         std::thread::spawn(move || read_console(stdin_uart_clone));
 
         let timer = Timer::new(&mcu_cpu.clock.clone());
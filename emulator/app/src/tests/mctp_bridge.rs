@@ -0,0 +1,252 @@
+// Licensed under the Apache-2.0 license
+
+//! Tests the MCTP bridge of the MCU. The bus owner on I3C assigns the EID of the MCU
+//! and allocates it an EID pool, from which the MCU assigns the EID of the endpoint
+//! behind it on the PCIe VDM mailbox. A message sent over I3C to that EID is then
+//! forwarded to the endpoint, which echoes it back through the bridge.
+
+use crate::tests::mctp_pcie_vdm::{
+    unwrap_mctp_pkt, wrap_mctp_msg, Reassembly, MCTP_BASELINE_TRANSMISSION_UNIT, MCTP_SOM, MCTP_TO,
+};
+use mcu_testing_common::i3c_socket::{BufferedStream, MctpTransportTest};
+use mcu_testing_common::mctp_util::common::MctpUtil;
+use mcu_testing_common::mctp_util::ctrl_protocol::{
+    set_eid_resp_bytes, CmdCompletionCode, MCTPCtrlCmd, SetEIDAllocStatus, SetEIDStatus,
+};
+use mcu_testing_common::{sleep_emulator_ticks, MCU_RUNNING};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{Receiver, Sender, TryRecvError};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+const MCU_EID: u8 = 0x0A;
+const DOWNSTREAM_EID: u8 = 0x20;
+
+// PCI ID of the endpoint behind the bridge, as added by the MCU runtime
+const DOWNSTREAM_PCI_ID: u16 = 0x0200;
+
+// Vendor defined (PCI) message type, which the downstream endpoint echoes
+const MCTP_MSG_TYPE_VDM_PCI: u8 = 0x7E;
+const ECHO_MSG_LEN: usize = 200;
+
+// The route to the downstream endpoint is only added once the endpoint has accepted
+// its EID, so a request forwarded by the bridge may need to be sent again.
+const MAX_ATTEMPTS: usize = 5;
+const RESPONSE_TIMEOUT_SECS: u32 = 2;
+
+#[derive(EnumIter, Debug)]
+pub enum MctpBridgeTest {
+    SetEid,
+    AllocateEndpointIds,
+    EchoDownstream,
+}
+
+impl MctpBridgeTest {
+    pub fn generate_tests() -> Vec<Box<dyn MctpTransportTest + Send>> {
+        MctpBridgeTest::iter()
+            .enumerate()
+            .map(|(tag, test)| {
+                Box::new(Test {
+                    name: format!("{:?}", test),
+                    dest_eid: test.dest_eid(),
+                    msg_tag: tag as u8,
+                    req_msg: test.request_message(),
+                    resp_msg: test.response_message(),
+                    mctp_util: MctpUtil::new(),
+                    passed: false,
+                }) as Box<dyn MctpTransportTest + Send>
+            })
+            .collect()
+    }
+
+    fn dest_eid(&self) -> u8 {
+        match self {
+            MctpBridgeTest::SetEid => 0,
+            MctpBridgeTest::AllocateEndpointIds => MCU_EID,
+            MctpBridgeTest::EchoDownstream => DOWNSTREAM_EID,
+        }
+    }
+
+    /// MCTP message, starting with the message type
+    fn request_message(&self) -> Vec<u8> {
+        match self {
+            // Set Endpoint ID: operation = set
+            MctpBridgeTest::SetEid => ctrl_msg(true, MCTPCtrlCmd::SetEID, &[0x00, MCU_EID]),
+            // Allocate Endpoint IDs: operation = allocate, pool size, starting EID
+            MctpBridgeTest::AllocateEndpointIds => ctrl_msg(
+                true,
+                MCTPCtrlCmd::AllocateEndpointIDs,
+                &[0x00, 0x01, DOWNSTREAM_EID],
+            ),
+            MctpBridgeTest::EchoDownstream => echo_msg(),
+        }
+    }
+
+    /// Expected MCTP response message
+    fn response_message(&self) -> Vec<u8> {
+        match self {
+            // An EID pool is required for the endpoint behind the bridge
+            MctpBridgeTest::SetEid => {
+                let mut resp = set_eid_resp_bytes(
+                    CmdCompletionCode::Success,
+                    SetEIDStatus::Accepted,
+                    SetEIDAllocStatus::PoolRequired,
+                    MCU_EID,
+                );
+                // EID pool size
+                resp[3] = 0x01;
+                ctrl_msg(false, MCTPCtrlCmd::SetEID, &resp)
+            }
+            // Completion code, allocation accepted, pool size, starting EID
+            MctpBridgeTest::AllocateEndpointIds => ctrl_msg(
+                false,
+                MCTPCtrlCmd::AllocateEndpointIDs,
+                &[0x00, 0x00, 0x01, DOWNSTREAM_EID],
+            ),
+            MctpBridgeTest::EchoDownstream => echo_msg(),
+        }
+    }
+}
+
+/// Encodes an MCTP control message
+pub(crate) fn ctrl_msg(request: bool, cmd: MCTPCtrlCmd, data: &[u8]) -> Vec<u8> {
+    let mut msg = vec![0x00, if request { 0x80 } else { 0x00 }, cmd as u8];
+    msg.extend_from_slice(data);
+    msg
+}
+
+/// Message larger than the baseline transmission unit, so that it is forwarded in
+/// several packets
+fn echo_msg() -> Vec<u8> {
+    let mut msg = vec![MCTP_MSG_TYPE_VDM_PCI];
+    msg.extend((1..ECHO_MSG_LEN).map(|i| i as u8));
+    msg
+}
+
+/// Runs the endpoint behind the bridge on the PCIe VDM mailbox. The endpoint accepts
+/// the EID assigned by the bridge and echoes the requests addressed to that EID.
+pub fn run_downstream_endpoint(tx: Sender<Vec<u8>>, rx: Receiver<Vec<u8>>) {
+    std::thread::spawn(move || {
+        let mut eid = 0;
+        let mut reassembly = Reassembly::default();
+        while MCU_RUNNING.load(Ordering::Relaxed) {
+            let vdm = match rx.try_recv() {
+                Ok(vdm) => vdm,
+                Err(TryRecvError::Empty) => {
+                    sleep_emulator_ticks(100_000);
+                    continue;
+                }
+                Err(TryRecvError::Disconnected) => break,
+            };
+            let Some((mctp_hdr, payload)) = unwrap_mctp_pkt(&vdm) else {
+                println!("MCTP_BRIDGE_TEST: Not an MCTP VDM: {:x?}", vdm);
+                continue;
+            };
+
+            let [_, dest_eid, src_eid, flags] = mctp_hdr;
+            if flags & MCTP_SOM != 0 {
+                reassembly = Reassembly::default();
+            }
+            match reassembly.add_pkt(flags, &payload) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    println!("MCTP_BRIDGE_TEST: Invalid request packet: {}", e);
+                    reassembly = Reassembly::default();
+                    continue;
+                }
+            }
+            let msg = std::mem::take(&mut reassembly).msg;
+            if flags & MCTP_TO == 0 {
+                continue;
+            }
+
+            let resp = match msg.as_slice() {
+                [0x00, rq_iid, cmd, _, new_eid, ..]
+                    if rq_iid & 0x80 != 0 && *cmd == MCTPCtrlCmd::SetEID as u8 =>
+                {
+                    eid = *new_eid;
+                    println!("MCTP_BRIDGE_TEST: Downstream endpoint assigned EID {eid:#x}");
+                    let mut resp = ctrl_msg(
+                        false,
+                        MCTPCtrlCmd::SetEID,
+                        &set_eid_resp_bytes(
+                            CmdCompletionCode::Success,
+                            SetEIDStatus::Accepted,
+                            SetEIDAllocStatus::NoEIDPool,
+                            eid,
+                        ),
+                    );
+                    // The response has the instance ID of the request
+                    resp[1] = rq_iid & 0x1F;
+                    resp
+                }
+                _ if eid != 0 && dest_eid == eid => msg,
+                _ => continue,
+            };
+            // The response is posted in back-to-back packets
+            let vdms = wrap_mctp_msg(
+                &resp,
+                MCTP_BASELINE_TRANSMISSION_UNIT,
+                DOWNSTREAM_PCI_ID,
+                src_eid,
+                eid,
+                flags & 0x07,
+            );
+            if !vdms.into_iter().all(|vdm| tx.send(vdm).is_ok()) {
+                break;
+            }
+        }
+    });
+}
+
+struct Test {
+    name: String,
+    dest_eid: u8,
+    msg_tag: u8,
+    req_msg: Vec<u8>,
+    resp_msg: Vec<u8>,
+    mctp_util: MctpUtil,
+    passed: bool,
+}
+
+impl MctpTransportTest for Test {
+    fn run_test(&mut self, stream: &mut BufferedStream, target_addr: u8) {
+        stream.set_nonblocking(true).unwrap();
+        println!("MCTP_BRIDGE_TEST: Running test: {}", self.name);
+
+        for _ in 0..MAX_ATTEMPTS {
+            if !MCU_RUNNING.load(Ordering::Relaxed) {
+                break;
+            }
+            self.mctp_util.send_request_to(
+                self.dest_eid,
+                self.msg_tag,
+                &self.req_msg,
+                stream,
+                target_addr,
+            );
+            let resp_msg =
+                self.mctp_util
+                    .receive_response(stream, target_addr, Some(RESPONSE_TIMEOUT_SECS));
+            if resp_msg == self.resp_msg {
+                self.passed = true;
+                break;
+            }
+            println!(
+                "MCTP_BRIDGE_TEST: Unexpected response: {:x?} expected {:x?}",
+                resp_msg, self.resp_msg
+            );
+        }
+
+        println!(
+            "MCTP_BRIDGE_TEST: Test {} {}",
+            self.name,
+            if self.passed { "passed!" } else { "failed!" }
+        );
+    }
+
+    fn is_passed(&self) -> bool {
+        self.passed
+    }
+}
@@ -35,6 +35,8 @@ pub(crate) enum MCTPCtrlCmdTests {
     GetMctpVersionSupportUnspecified,
     GetMctpVersionSupportUnsupported,
     GetMsgTypeSupport,
+    GetRoutingTableEntries,
+    GetRoutingTableEntriesInvalidHandle,
 }
 
 impl MCTPCtrlCmdTests {
@@ -85,6 +87,12 @@ impl MCTPCtrlCmdTests {
             MCTPCtrlCmdTests::GetMsgTypeSupport => {
                 vec![]
             }
+            MCTPCtrlCmdTests::GetRoutingTableEntries => {
+                vec![0x00]
+            }
+            MCTPCtrlCmdTests::GetRoutingTableEntriesInvalidHandle => {
+                vec![0x01]
+            }
         };
         MCTPCtrlCmdTests::generate_msg((mctp_common_msg_hdr, mctp_ctrl_msg_hdr, req_data))
     }
@@ -159,6 +167,17 @@ impl MCTPCtrlCmdTests {
                 ];
                 generate_msg_type_support_resp_bytes(CmdCompletionCode::Success as u8, &msg_types)
             }
+            MCTPCtrlCmdTests::GetRoutingTableEntries => {
+                // No routing table entries, no more entries to follow
+                get_routing_table_entries_resp_bytes(CmdCompletionCode::Success as u8, 0xFF, 0)
+            }
+            MCTPCtrlCmdTests::GetRoutingTableEntriesInvalidHandle => {
+                get_routing_table_entries_resp_bytes(
+                    CmdCompletionCode::ErrorInvalidData as u8,
+                    0xFF,
+                    0,
+                )
+            }
         };
 
         MCTPCtrlCmdTests::generate_msg((mctp_common_msg_hdr, mctp_ctrl_msg_hdr, resp_data))
@@ -201,6 +220,10 @@ impl MCTPCtrlCmdTests {
                 MCTPCtrlCmd::GetMctpVersionSupport as u8
             }
             MCTPCtrlCmdTests::GetMsgTypeSupport => MCTPCtrlCmd::GetMsgTypeSupport as u8,
+            MCTPCtrlCmdTests::GetRoutingTableEntries
+            | MCTPCtrlCmdTests::GetRoutingTableEntriesInvalidHandle => {
+                MCTPCtrlCmd::GetRoutingTableEntries as u8
+            }
         }
     }
 }
@@ -0,0 +1,184 @@
+// Licensed under the Apache-2.0 license
+
+//! Tests MCTP over serial. The messages are exchanged with the MCU over the UART socket
+//! of the emulator (`--uart-port`), which carries the frames of DSP0253 along with the
+//! console output. The bus owner assigns the EID of the MCU, then sends control
+//! messages handled by the kernel and an SPDM request handled by the user app. The
+//! PLDM discovery exchange then runs over `MctpSerialTransport`, as it does over I3C
+//! in the `test-pldm-discovery` test.
+
+use crate::tests::mctp_bridge::ctrl_msg;
+use crate::tests::pldm_request_response_test::PldmRequestResponseTest;
+use mcu_testing_common::i3c_socket::DEFAULT_TEST_TIMEOUT_TICKS;
+use mcu_testing_common::mctp_serial_transport::{MctpSerialSocket, MctpSerialTransport};
+use mcu_testing_common::mctp_util::base_protocol::MctpMsgType;
+use mcu_testing_common::mctp_util::ctrl_protocol::{
+    generate_msg_type_support_resp_bytes, get_eid_resp_bytes, set_eid_req_bytes,
+    set_eid_resp_bytes, CmdCompletionCode, MCTPCtrlCmd, SetEIDAllocStatus, SetEIDOp, SetEIDStatus,
+};
+use mcu_testing_common::{wait_emulator_ticks, wait_for_runtime_start, MCU_RUNNING};
+use pldm_ua::transport::{EndpointId, PldmTransport};
+use std::process::exit;
+use std::sync::atomic::Ordering;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+const MCU_EID: u8 = 0x0A;
+
+// SPDM GET_VERSION, as the first request of a connection
+const SPDM_VERSION_10: u8 = 0x10;
+const SPDM_GET_VERSION: u8 = 0x84;
+const SPDM_VERSION: u8 = 0x04;
+
+#[derive(EnumIter, Debug)]
+pub enum MctpSerialTest {
+    SetEid,
+    GetEid,
+    GetMsgTypeSupport,
+    SpdmGetVersion,
+}
+
+impl MctpSerialTest {
+    fn dest_eid(&self) -> u8 {
+        match self {
+            MctpSerialTest::SetEid => 0,
+            _ => MCU_EID,
+        }
+    }
+
+    /// MCTP message, starting with the message type
+    fn request_message(&self) -> Vec<u8> {
+        match self {
+            MctpSerialTest::SetEid => ctrl_msg(
+                true,
+                MCTPCtrlCmd::SetEID,
+                &set_eid_req_bytes(SetEIDOp::SetEID, MCU_EID),
+            ),
+            MctpSerialTest::GetEid => ctrl_msg(true, MCTPCtrlCmd::GetEID, &[]),
+            MctpSerialTest::GetMsgTypeSupport => {
+                ctrl_msg(true, MCTPCtrlCmd::GetMsgTypeSupport, &[])
+            }
+            MctpSerialTest::SpdmGetVersion => vec![
+                MctpMsgType::Spdm as u8,
+                SPDM_VERSION_10,
+                SPDM_GET_VERSION,
+                0x00,
+                0x00,
+            ],
+        }
+    }
+
+    fn check_response(&self, resp: &[u8]) -> bool {
+        match self {
+            MctpSerialTest::SetEid => {
+                resp == ctrl_msg(
+                    false,
+                    MCTPCtrlCmd::SetEID,
+                    &set_eid_resp_bytes(
+                        CmdCompletionCode::Success,
+                        SetEIDStatus::Accepted,
+                        SetEIDAllocStatus::NoEIDPool,
+                        MCU_EID,
+                    ),
+                )
+            }
+            MctpSerialTest::GetEid => {
+                resp == ctrl_msg(
+                    false,
+                    MCTPCtrlCmd::GetEID,
+                    &get_eid_resp_bytes(CmdCompletionCode::Success, MCU_EID),
+                )
+            }
+            MctpSerialTest::GetMsgTypeSupport => {
+                let msg_types = [
+                    MctpMsgType::Ctrl,
+                    MctpMsgType::Pldm,
+                    MctpMsgType::Spdm,
+                    MctpMsgType::SecureSpdm,
+                    MctpMsgType::Caliptra,
+                ];
+                resp == ctrl_msg(
+                    false,
+                    MCTPCtrlCmd::GetMsgTypeSupport,
+                    &generate_msg_type_support_resp_bytes(
+                        CmdCompletionCode::Success as u8,
+                        &msg_types,
+                    ),
+                )
+            }
+            // The supported versions depend on the build of the responder
+            MctpSerialTest::SpdmGetVersion => {
+                resp.starts_with(&[MctpMsgType::Spdm as u8, SPDM_VERSION_10, SPDM_VERSION])
+            }
+        }
+    }
+
+    fn run(&self, socket: &mut MctpSerialSocket) -> bool {
+        println!("MCTP_SERIAL_TEST: Running test: {:?}", self);
+        socket.set_dest_eid(self.dest_eid());
+        let passed = match socket.send_request(&self.request_message()) {
+            Ok(resp) if self.check_response(&resp) => true,
+            Ok(resp) => {
+                println!("MCTP_SERIAL_TEST: Unexpected response: {:x?}", resp);
+                false
+            }
+            Err(e) => {
+                println!("MCTP_SERIAL_TEST: No response: {:?}", e);
+                false
+            }
+        };
+        println!(
+            "MCTP_SERIAL_TEST: Test {:?} {}",
+            self,
+            if passed { "passed!" } else { "failed!" }
+        );
+        passed
+    }
+}
+
+/// Runs the tests over the UART socket once the runtime has started, and exits the
+/// emulator with the result.
+pub fn run_mctp_serial_tests(uart_port: u16) {
+    std::thread::spawn(move || {
+        if !wait_emulator_ticks(DEFAULT_TEST_TIMEOUT_TICKS) {
+            // Emulator stopped before timeout - this is normal completion
+            return;
+        }
+        println!(
+            "INTEGRATION TEST ON MCTP-SERIAL TIMED OUT AFTER {} TICKS",
+            DEFAULT_TEST_TIMEOUT_TICKS
+        );
+        exit(-1);
+    });
+    std::thread::spawn(move || {
+        wait_for_runtime_start();
+        if !MCU_RUNNING.load(Ordering::Relaxed) {
+            exit(-1);
+        }
+        let mut socket =
+            MctpSerialSocket::connect(uart_port, 0).expect("Failed to connect to the UART socket");
+        let tests: Vec<MctpSerialTest> = MctpSerialTest::iter().collect();
+        let passed = tests.iter().filter(|test| test.run(&mut socket)).count();
+        println!("Test Result: {}/{} tests passed", passed, tests.len());
+        // The UART socket serves one connection at a time
+        drop(socket);
+
+        println!("MCTP_SERIAL_TEST: Running PLDM discovery over MCTP serial");
+        let pldm_socket = MctpSerialTransport::new(uart_port)
+            .create_socket(EndpointId(0), EndpointId(MCU_EID))
+            .expect("Failed to connect to the UART socket");
+        let pldm_passed = PldmRequestResponseTest::new(pldm_socket)
+            .test_send_receive()
+            .is_ok();
+        println!(
+            "MCTP_SERIAL_TEST: PLDM discovery {}",
+            if pldm_passed { "passed!" } else { "failed!" }
+        );
+        MCU_RUNNING.store(false, Ordering::Relaxed);
+        exit(if passed == tests.len() && pldm_passed {
+            0
+        } else {
+            -1
+        });
+    });
+}
@@ -6,7 +6,12 @@ pub mod doe_transport_loopback;
 pub mod doe_user_loopback;
 pub mod doe_util;
 pub mod emulator_mcu_mailbox_test;
+pub mod mctp_bridge;
 pub mod mctp_ctrl_cmd;
+pub mod mctp_pcie_vdm;
+pub mod mctp_serial;
 pub mod mctp_user_loopback;
+pub mod pldm_fru;
+pub mod pldm_platform;
 pub mod pldm_request_response_test;
 pub mod spdm_responder_validator;
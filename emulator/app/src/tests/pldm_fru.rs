@@ -0,0 +1,246 @@
+// Licensed under the Apache-2.0 license
+
+//! Tests the PLDM for FRU Data responder of the user app. The emulator reads the FRU record
+//! table metadata and the table, checks its checksum and that the General FRU record reports
+//! the device ID, the unique chip ID and the firmware versions of the external commands.
+
+use mcu_mbox_common::config::{TEST_DEVICE_ID, TEST_FIRMWARE_VERSIONS, TEST_UID};
+use mcu_testing_common::i3c_socket::DEFAULT_TEST_TIMEOUT_TICKS;
+use mcu_testing_common::mctp_transport::MctpPldmSocket;
+use mcu_testing_common::{wait_emulator_ticks, wait_for_runtime_start, MCU_RUNNING};
+use pldm_common::codec::PldmCodec;
+use pldm_common::message::control::{GetPldmTypeRequest, GetPldmTypeResponse};
+use pldm_common::message::fru::get_fru_record_table::{
+    GetFruRecordTableRequest, GetFruRecordTableResponseFixed,
+};
+use pldm_common::message::fru::get_fru_record_table_metadata::{
+    fru_table_crc32, GetFruRecordTableMetadataRequest, GetFruRecordTableMetadataResponse,
+};
+use pldm_common::protocol::base::{
+    InstanceId, PldmBaseCompletionCode, PldmMsgType, PldmSupportedType, TransferOperationFlag,
+    TransferRespFlag,
+};
+use pldm_common::protocol::fru::{
+    FruFieldEncoding, FruGeneralFieldType, FruRecordHeader, FruRecordType, PLDM_FRU_TABLE_ALIGNMENT,
+};
+use pldm_ua::transport::PldmSocket;
+use std::process::exit;
+use std::sync::atomic::Ordering;
+
+// Product name of the General FRU record, see image_loader/pldm_fru.rs of the user app
+const PRODUCT_NAME: &str = "Caliptra MCU";
+const DEVICE_IDENTITY_RECORD_SET_ID: u16 = 1;
+const INSTANCE_ID_COUNT: u8 = 32;
+
+struct PldmFruTest {
+    socket: MctpPldmSocket,
+    instance_id: InstanceId,
+    table: Vec<u8>,
+}
+
+impl PldmFruTest {
+    fn new(socket: MctpPldmSocket) -> Self {
+        Self {
+            socket,
+            instance_id: 0,
+            table: Vec::new(),
+        }
+    }
+
+    fn next_instance_id(&mut self) -> InstanceId {
+        self.instance_id = (self.instance_id + 1) % INSTANCE_ID_COUNT;
+        self.instance_id
+    }
+
+    fn exchange<Req: PldmCodec>(&self, request: Req) -> Result<Vec<u8>, ()> {
+        let mut buffer = [0u8; 1024];
+        let len = request.encode(&mut buffer).map_err(|_| ())?;
+        self.socket.send(&buffer[..len]).map_err(|_| ())?;
+        let rx_pkt = self.socket.receive(None).map_err(|_| ())?;
+        Ok(rx_pkt.payload.data[..rx_pkt.payload.len].to_vec())
+    }
+
+    fn test_pldm_types(&mut self) -> Result<(), ()> {
+        let instance_id = self.next_instance_id();
+        let rsp = self.exchange(GetPldmTypeRequest::new(instance_id, PldmMsgType::Request))?;
+        let expected = GetPldmTypeResponse::new(
+            instance_id,
+            PldmBaseCompletionCode::Success as u8,
+            &[
+                PldmSupportedType::Base as u8,
+                PldmSupportedType::FwUpdate as u8,
+                PldmSupportedType::Fru as u8,
+            ],
+        );
+        let rsp = GetPldmTypeResponse::decode(&rsp).map_err(|_| ())?;
+        if rsp.pldm_types != expected.pldm_types {
+            println!("PLDM_FRU_TEST: Unexpected PLDM types {:x?}", rsp.pldm_types);
+            return Err(());
+        }
+        Ok(())
+    }
+
+    fn test_fru_record_table(&mut self) -> Result<(), ()> {
+        let instance_id = self.next_instance_id();
+        let rsp = self.exchange(GetFruRecordTableMetadataRequest::new(
+            instance_id,
+            PldmMsgType::Request,
+        ))?;
+        let metadata = GetFruRecordTableMetadataResponse::decode(&rsp).map_err(|_| ())?;
+        if metadata.completion_code != PldmBaseCompletionCode::Success as u8
+            || metadata.total_record_set_identifiers != 1
+            || metadata.total_table_records != 1
+        {
+            println!("PLDM_FRU_TEST: Unexpected metadata {:?}", metadata);
+            return Err(());
+        }
+
+        let mut table = Vec::new();
+        let mut data_transfer_handle = 0;
+        let mut op_flag = TransferOperationFlag::GetFirstPart;
+        loop {
+            let instance_id = self.next_instance_id();
+            let rsp = self.exchange(GetFruRecordTableRequest::new(
+                instance_id,
+                PldmMsgType::Request,
+                data_transfer_handle,
+                op_flag,
+            ))?;
+            let fixed_len = core::mem::size_of::<GetFruRecordTableResponseFixed>();
+            if rsp.len() < fixed_len {
+                return Err(());
+            }
+            let fixed = GetFruRecordTableResponseFixed::decode(&rsp).map_err(|_| ())?;
+            if fixed.completion_code != PldmBaseCompletionCode::Success as u8 {
+                println!(
+                    "PLDM_FRU_TEST: GetFRURecordTable failed with {:#x}",
+                    fixed.completion_code
+                );
+                return Err(());
+            }
+            table.extend_from_slice(&rsp[fixed_len..]);
+            if fixed.transfer_flag == TransferRespFlag::End as u8
+                || fixed.transfer_flag == TransferRespFlag::StartAndEnd as u8
+            {
+                break;
+            }
+            data_transfer_handle = fixed.next_data_transfer_handle;
+            op_flag = TransferOperationFlag::GetNextPart;
+        }
+
+        let table_length = metadata.fru_table_length;
+        let checksum = metadata.checksum;
+        if table.len() != table_length as usize
+            || table.len() % PLDM_FRU_TABLE_ALIGNMENT != 0
+            || fru_table_crc32(&table) != checksum
+        {
+            println!("PLDM_FRU_TEST: Table does not match its metadata");
+            return Err(());
+        }
+        self.table = table;
+        Ok(())
+    }
+
+    fn test_device_identity(&mut self) -> Result<(), ()> {
+        let part_number = format!(
+            "{:04x}:{:04x}:{:04x}:{:04x}",
+            TEST_DEVICE_ID.vendor_id,
+            TEST_DEVICE_ID.device_id,
+            TEST_DEVICE_ID.subsystem_vendor_id,
+            TEST_DEVICE_ID.subsystem_id
+        );
+        let serial_number: String = TEST_UID.iter().map(|b| format!("{:02x}", b)).collect();
+        let mut expected = vec![
+            (FruGeneralFieldType::Name, PRODUCT_NAME.to_string()),
+            (FruGeneralFieldType::PartNumber, part_number),
+            (FruGeneralFieldType::SerialNumber, serial_number),
+        ];
+        expected.extend(
+            TEST_FIRMWARE_VERSIONS
+                .iter()
+                .map(|version| (FruGeneralFieldType::Version, version.to_string())),
+        );
+
+        let hdr = FruRecordHeader::decode(&self.table).map_err(|_| ())?;
+        if hdr
+            != FruRecordHeader::new(
+                DEVICE_IDENTITY_RECORD_SET_ID,
+                FruRecordType::General,
+                expected.len() as u8,
+                FruFieldEncoding::Ascii,
+            )
+        {
+            println!("PLDM_FRU_TEST: Unexpected record header {:?}", hdr);
+            return Err(());
+        }
+
+        let mut offset = core::mem::size_of::<FruRecordHeader>();
+        for (field_type, value) in expected {
+            let field = self.table.get(offset..offset + 2).ok_or(())?;
+            let len = field[1] as usize;
+            let data = self.table.get(offset + 2..offset + 2 + len).ok_or(())?;
+            if field[0] != field_type as u8 || !data.eq_ignore_ascii_case(value.as_bytes()) {
+                println!(
+                    "PLDM_FRU_TEST: Expected {:?} \"{}\", got type {} \"{}\"",
+                    field_type,
+                    value,
+                    field[0],
+                    String::from_utf8_lossy(data)
+                );
+                return Err(());
+            }
+            offset += 2 + len;
+        }
+        Ok(())
+    }
+
+    fn run_test(&mut self, name: &str, test: fn(&mut PldmFruTest) -> Result<(), ()>) -> bool {
+        println!("PLDM_FRU_TEST: Running test: {}", name);
+        let passed = test(self).is_ok();
+        println!(
+            "PLDM_FRU_TEST: Test {} {}",
+            name,
+            if passed { "passed!" } else { "failed!" }
+        );
+        passed
+    }
+
+    fn run_tests(&mut self) -> bool {
+        let tests: [(&str, fn(&mut PldmFruTest) -> Result<(), ()>); 3] = [
+            ("PldmTypes", Self::test_pldm_types),
+            ("FruRecordTable", Self::test_fru_record_table),
+            ("DeviceIdentity", Self::test_device_identity),
+        ];
+        let passed = tests
+            .iter()
+            .filter(|(name, test)| self.run_test(name, *test))
+            .count();
+        println!("Test Result: {}/{} tests passed", passed, tests.len());
+        passed == tests.len()
+    }
+}
+
+/// Runs the tests once the runtime has started, and exits the emulator with the result.
+pub fn run_pldm_fru_tests(socket: MctpPldmSocket) {
+    std::thread::spawn(move || {
+        if !wait_emulator_ticks(DEFAULT_TEST_TIMEOUT_TICKS) {
+            // Emulator stopped before timeout - this is normal completion
+            return;
+        }
+        println!(
+            "INTEGRATION TEST ON PLDM FRU TIMED OUT AFTER {} TICKS",
+            DEFAULT_TEST_TIMEOUT_TICKS
+        );
+        exit(-1);
+    });
+    std::thread::spawn(move || {
+        wait_for_runtime_start();
+        if !MCU_RUNNING.load(Ordering::Relaxed) {
+            exit(-1);
+        }
+        let mut test = PldmFruTest::new(socket);
+        let passed = test.run_tests();
+        MCU_RUNNING.store(false, Ordering::Relaxed);
+        exit(if passed { 0 } else { -1 });
+    });
+}
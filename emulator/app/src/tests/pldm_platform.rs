@@ -0,0 +1,372 @@
+// Licensed under the Apache-2.0 license
+
+//! Tests the PLDM for Platform Monitoring and Control responder of the user app. The emulator
+//! reads the PDRs and the sensors of the MCU: the boot status, the state of the periodic FIPS
+//! self-test and the fill level of the flash log. It then registers as the event receiver,
+//! leaves the first event unanswered and checks that the MCU stops waiting for the response
+//! and sends the event again.
+
+use mcu_testing_common::i3c_socket::DEFAULT_TEST_TIMEOUT_TICKS;
+use mcu_testing_common::mctp_transport::MctpPldmSocket;
+use mcu_testing_common::mctp_util::base_protocol::LOCAL_TEST_ENDPOINT_EID;
+use mcu_testing_common::{wait_emulator_ticks, wait_for_runtime_start, MCU_RUNNING};
+use pldm_common::codec::PldmCodec;
+use pldm_common::message::control::{GetPldmTypeRequest, GetPldmTypeResponse};
+use pldm_common::message::platform::get_pdr::{GetPdrRequest, GetPdrResponseFixed};
+use pldm_common::message::platform::get_pdr_repository_info::{
+    GetPdrRepositoryInfoRequest, GetPdrRepositoryInfoResponse,
+};
+use pldm_common::message::platform::get_sensor_reading::{
+    GetSensorReadingRequest, GetSensorReadingResponse,
+};
+use pldm_common::message::platform::get_state_sensor_readings::{
+    GetStateSensorReadingsRequest, GetStateSensorReadingsResponse,
+};
+use pldm_common::message::platform::platform_event_message::{
+    PlatformEventMessageRequest, PlatformEventMessageResponse,
+};
+use pldm_common::message::platform::set_event_receiver::{
+    SetEventReceiverRequest, SetEventReceiverResponse,
+};
+use pldm_common::protocol::base::{
+    InstanceId, PldmBaseCompletionCode, PldmControlCmd, PldmMsgHeader, PldmMsgType,
+    PldmSupportedType, TransferOperationFlag, PLDM_MSG_HEADER_LEN,
+};
+use pldm_common::protocol::platform::{
+    EventMessageGlobalEnable, NumericSensorState, PdrHeader, PdrRepositoryState, PdrType,
+    PlatformCmd, PlatformEventStatus, SensorValue, TransportProtocolType, PLDM_NO_MORE_RECORDS,
+    PLDM_PDR_HEADER_LEN,
+};
+use pldm_ua::transport::PldmSocket;
+use std::process::exit;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+// Sensors of the user app, see image_loader/pldm_platform.rs
+const BOOT_STATUS_SENSOR_ID: u16 = 1;
+const FIPS_SELF_TEST_SENSOR_ID: u16 = 2;
+const LOG_FILL_LEVEL_SENSOR_ID: u16 = 3;
+const SENSOR_IDS: [u16; 3] = [
+    BOOT_STATUS_SENSOR_ID,
+    FIPS_SELF_TEST_SENSOR_ID,
+    LOG_FILL_LEVEL_SENSOR_ID,
+];
+const BOOT_STATUS_COLD_BOOT: u8 = 1;
+const FIPS_SELF_TEST_IN_PROGRESS: u8 = 2;
+const FIPS_SELF_TEST_PASSED: u8 = 3;
+
+// Endpoint registered as the event receiver, the EID the test socket responds from
+const EVENT_RECEIVER_EID: u8 = LOCAL_TEST_ENDPOINT_EID;
+
+const FIPS_SELF_TEST_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const FIPS_SELF_TEST_POLL_COUNT: usize = 60;
+const INSTANCE_ID_COUNT: u8 = 32;
+
+struct PldmPlatformTest {
+    socket: MctpPldmSocket,
+    instance_id: InstanceId,
+}
+
+impl PldmPlatformTest {
+    fn new(socket: MctpPldmSocket) -> Self {
+        Self {
+            socket,
+            instance_id: 0,
+        }
+    }
+
+    fn next_instance_id(&mut self) -> InstanceId {
+        self.instance_id = (self.instance_id + 1) % INSTANCE_ID_COUNT;
+        self.instance_id
+    }
+
+    fn send<Msg: PldmCodec>(&self, msg: Msg) -> Result<(), ()> {
+        let mut buffer = [0u8; 1024];
+        let len = msg.encode(&mut buffer).map_err(|_| ())?;
+        self.socket.send(&buffer[..len]).map_err(|_| ())
+    }
+
+    fn receive(&self) -> Result<Vec<u8>, ()> {
+        let rx_pkt = self.socket.receive(None).map_err(|_| ())?;
+        if rx_pkt.payload.len < PLDM_MSG_HEADER_LEN {
+            return Err(());
+        }
+        Ok(rx_pkt.payload.data[..rx_pkt.payload.len].to_vec())
+    }
+
+    fn receive_event(&self) -> Result<PlatformEventMessageRequest, ()> {
+        let msg = self.receive()?;
+        let hdr = PldmMsgHeader(msg[..PLDM_MSG_HEADER_LEN].try_into().unwrap());
+        if !hdr.is_request() || hdr.cmd_code() != PlatformCmd::PlatformEventMessage as u8 {
+            println!("PLDM_PLATFORM_TEST: Expected an event, got {:x?}", msg);
+            return Err(());
+        }
+        PlatformEventMessageRequest::decode(&msg).map_err(|_| ())
+    }
+
+    fn acknowledge_event(&self, event: &PlatformEventMessageRequest) -> Result<(), ()> {
+        self.send(PlatformEventMessageResponse::new(
+            event.fixed.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            PlatformEventStatus::NoLogging,
+        ))
+    }
+
+    /// Sends a request and returns its response. Events sent by the MCU in the meantime are
+    /// acknowledged.
+    fn exchange<Req: PldmCodec>(&self, request: Req) -> Result<Vec<u8>, ()> {
+        self.send(request)?;
+        loop {
+            let msg = self.receive()?;
+            let hdr = PldmMsgHeader(msg[..PLDM_MSG_HEADER_LEN].try_into().unwrap());
+            if !hdr.is_request() {
+                return Ok(msg);
+            }
+            let event = PlatformEventMessageRequest::decode(&msg).map_err(|_| ())?;
+            self.acknowledge_event(&event)?;
+        }
+    }
+
+    fn test_pldm_types(&mut self) -> Result<(), ()> {
+        let instance_id = self.next_instance_id();
+        let rsp = self.exchange(GetPldmTypeRequest::new(instance_id, PldmMsgType::Request))?;
+        let expected = GetPldmTypeResponse::new(
+            instance_id,
+            PldmBaseCompletionCode::Success as u8,
+            &[
+                PldmSupportedType::Base as u8,
+                PldmSupportedType::Platform as u8,
+                PldmSupportedType::FwUpdate as u8,
+            ],
+        );
+        let rsp = GetPldmTypeResponse::decode(&rsp).map_err(|_| ())?;
+        if rsp.hdr.cmd_code() != PldmControlCmd::GetPldmTypes as u8
+            || rsp.pldm_types != expected.pldm_types
+        {
+            return Err(());
+        }
+        Ok(())
+    }
+
+    fn test_pdr_repository(&mut self) -> Result<(), ()> {
+        let instance_id = self.next_instance_id();
+        let rsp = self.exchange(GetPdrRepositoryInfoRequest::new(
+            instance_id,
+            PldmMsgType::Request,
+        ))?;
+        let info = GetPdrRepositoryInfoResponse::decode(&rsp).map_err(|_| ())?;
+        if info.completion_code != PldmBaseCompletionCode::Success as u8
+            || info.repository_state != PdrRepositoryState::Available as u8
+            || info.record_count != SENSOR_IDS.len() as u32
+        {
+            return Err(());
+        }
+
+        // Walk the repository and check that each sensor is described
+        let mut record_handle = 0;
+        for sensor_id in SENSOR_IDS {
+            let instance_id = self.next_instance_id();
+            let rsp = self.exchange(GetPdrRequest::new(
+                instance_id,
+                PldmMsgType::Request,
+                record_handle,
+                0,
+                TransferOperationFlag::GetFirstPart,
+                info.largest_record_size as u16,
+                0,
+            ))?;
+            let fixed = GetPdrResponseFixed::decode(&rsp).map_err(|_| ())?;
+            let record = rsp
+                .get(core::mem::size_of::<GetPdrResponseFixed>()..)
+                .and_then(|data| data.get(..fixed.response_count as usize))
+                .ok_or(())?;
+            let hdr = PdrHeader::decode(record).map_err(|_| ())?;
+            let expected_type = if sensor_id == LOG_FILL_LEVEL_SENSOR_ID {
+                PdrType::NumericSensor
+            } else {
+                PdrType::StateSensor
+            };
+            // Both sensor PDRs start with the terminus handle, then the sensor ID
+            let record_sensor_id = record
+                .get(PLDM_PDR_HEADER_LEN + 2..PLDM_PDR_HEADER_LEN + 4)
+                .map(|id| u16::from_le_bytes(id.try_into().unwrap()))
+                .ok_or(())?;
+            if fixed.completion_code != PldmBaseCompletionCode::Success as u8
+                || hdr.pdr_type != expected_type as u8
+                || record_sensor_id != sensor_id
+            {
+                return Err(());
+            }
+            record_handle = fixed.next_record_handle;
+        }
+        if record_handle != PLDM_NO_MORE_RECORDS {
+            return Err(());
+        }
+        Ok(())
+    }
+
+    fn read_state_sensor(&mut self, sensor_id: u16) -> Result<u8, ()> {
+        let instance_id = self.next_instance_id();
+        let rsp = self.exchange(GetStateSensorReadingsRequest::new(
+            instance_id,
+            PldmMsgType::Request,
+            sensor_id,
+            0,
+        ))?;
+        let rsp = GetStateSensorReadingsResponse::decode(&rsp).map_err(|_| ())?;
+        if rsp.fixed.completion_code != PldmBaseCompletionCode::Success as u8
+            || rsp.fixed.composite_sensor_count != 1
+        {
+            return Err(());
+        }
+        Ok(rsp.state_fields[0].present_state)
+    }
+
+    fn test_boot_status(&mut self) -> Result<(), ()> {
+        // The emulator starts the MCU from a cold boot
+        match self.read_state_sensor(BOOT_STATUS_SENSOR_ID)? {
+            BOOT_STATUS_COLD_BOOT => Ok(()),
+            state => {
+                println!("PLDM_PLATFORM_TEST: Unexpected boot status {}", state);
+                Err(())
+            }
+        }
+    }
+
+    fn test_fips_self_test(&mut self) -> Result<(), ()> {
+        // The user app enables the periodic self-test before starting the PLDM service
+        for _ in 0..FIPS_SELF_TEST_POLL_COUNT {
+            match self.read_state_sensor(FIPS_SELF_TEST_SENSOR_ID)? {
+                FIPS_SELF_TEST_PASSED => return Ok(()),
+                FIPS_SELF_TEST_IN_PROGRESS => std::thread::sleep(FIPS_SELF_TEST_POLL_INTERVAL),
+                state => {
+                    println!(
+                        "PLDM_PLATFORM_TEST: Unexpected FIPS self-test state {}",
+                        state
+                    );
+                    return Err(());
+                }
+            }
+        }
+        println!("PLDM_PLATFORM_TEST: FIPS self-test did not complete");
+        Err(())
+    }
+
+    fn test_log_fill_level(&mut self) -> Result<(), ()> {
+        let instance_id = self.next_instance_id();
+        let rsp = self.exchange(GetSensorReadingRequest::new(
+            instance_id,
+            PldmMsgType::Request,
+            LOG_FILL_LEVEL_SENSOR_ID,
+            false,
+        ))?;
+        let rsp = GetSensorReadingResponse::decode(&rsp).map_err(|_| ())?;
+        match rsp.present_reading {
+            SensorValue::Uint32(percent)
+                if rsp.fixed.completion_code == PldmBaseCompletionCode::Success as u8
+                    && rsp.fixed.present_state == NumericSensorState::Normal as u8
+                    && percent <= 100 =>
+            {
+                Ok(())
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn test_event_timeout(&mut self) -> Result<(), ()> {
+        let instance_id = self.next_instance_id();
+        self.send(SetEventReceiverRequest::new(
+            instance_id,
+            PldmMsgType::Request,
+            EventMessageGlobalEnable::EnableAsync,
+            TransportProtocolType::Mctp,
+            EVENT_RECEIVER_EID,
+            0,
+        ))?;
+
+        // The sensors have not been reported yet, so the MCU sends an event, possibly before
+        // the response. Leave it unanswered: the MCU gives up on the response and sends the
+        // same event again.
+        let mut event = None;
+        loop {
+            let msg = self.receive()?;
+            let hdr = PldmMsgHeader(msg[..PLDM_MSG_HEADER_LEN].try_into().unwrap());
+            if hdr.is_request() {
+                event = Some(PlatformEventMessageRequest::decode(&msg).map_err(|_| ())?);
+                continue;
+            }
+            let rsp = SetEventReceiverResponse::decode(&msg).map_err(|_| ())?;
+            if rsp.completion_code != PldmBaseCompletionCode::Success as u8 {
+                return Err(());
+            }
+            break;
+        }
+        let event = match event {
+            Some(event) => event,
+            None => self.receive_event()?,
+        };
+        let retry = self.receive_event()?;
+        if retry.fixed.event_class != event.fixed.event_class
+            || retry.event_data() != event.event_data()
+        {
+            return Err(());
+        }
+        self.acknowledge_event(&retry)?;
+
+        // The responder keeps serving requests while the remaining events are reported
+        self.test_boot_status()
+    }
+
+    fn run_test(&mut self, name: &str, test: fn(&mut PldmPlatformTest) -> Result<(), ()>) -> bool {
+        println!("PLDM_PLATFORM_TEST: Running test: {}", name);
+        let passed = test(self).is_ok();
+        println!(
+            "PLDM_PLATFORM_TEST: Test {} {}",
+            name,
+            if passed { "passed!" } else { "failed!" }
+        );
+        passed
+    }
+
+    fn run_tests(&mut self) -> bool {
+        let tests: [(&str, fn(&mut PldmPlatformTest) -> Result<(), ()>); 6] = [
+            ("PldmTypes", Self::test_pldm_types),
+            ("PdrRepository", Self::test_pdr_repository),
+            ("BootStatus", Self::test_boot_status),
+            ("FipsSelfTest", Self::test_fips_self_test),
+            ("LogFillLevel", Self::test_log_fill_level),
+            ("EventTimeout", Self::test_event_timeout),
+        ];
+        let passed = tests
+            .iter()
+            .filter(|(name, test)| self.run_test(name, *test))
+            .count();
+        println!("Test Result: {}/{} tests passed", passed, tests.len());
+        passed == tests.len()
+    }
+}
+
+/// Runs the tests once the runtime has started, and exits the emulator with the result.
+pub fn run_pldm_platform_tests(socket: MctpPldmSocket) {
+    std::thread::spawn(move || {
+        if !wait_emulator_ticks(DEFAULT_TEST_TIMEOUT_TICKS) {
+            // Emulator stopped before timeout - this is normal completion
+            return;
+        }
+        println!(
+            "INTEGRATION TEST ON PLDM PLATFORM TIMED OUT AFTER {} TICKS",
+            DEFAULT_TEST_TIMEOUT_TICKS
+        );
+        exit(-1);
+    });
+    std::thread::spawn(move || {
+        wait_for_runtime_start();
+        if !MCU_RUNNING.load(Ordering::Relaxed) {
+            exit(-1);
+        }
+        let mut test = PldmPlatformTest::new(socket);
+        let passed = test.run_tests();
+        MCU_RUNNING.store(false, Ordering::Relaxed);
+        exit(if passed { 0 } else { -1 });
+    });
+}
@@ -2,7 +2,6 @@
 //! This module tests the PLDM request/response interaction between the emulator and the device.
 //! The emulator sends out different PLDM requests and expects a corresponding response for those requests.
 
-use mcu_testing_common::mctp_transport::MctpPldmSocket;
 use mcu_testing_common::{wait_for_runtime_start, MCU_RUNNING};
 use pldm_common::codec::PldmCodec;
 use pldm_common::message::control::*;
@@ -18,9 +17,9 @@ use pldm_ua::transport::PldmSocket;
 use std::process::exit;
 use std::sync::atomic::Ordering;
 
-pub struct PldmRequestResponseTest {
+pub struct PldmRequestResponseTest<S: PldmSocket> {
     test_messages: Vec<PldmExpectedMessagePair>,
-    socket: MctpPldmSocket,
+    socket: S,
 }
 
 pub struct PldmExpectedMessagePair {
@@ -30,8 +29,8 @@ pub struct PldmExpectedMessagePair {
     pub response: Vec<u8>,
 }
 
-impl PldmRequestResponseTest {
-    fn new(socket: MctpPldmSocket) -> Self {
+impl<S: PldmSocket + Send + 'static> PldmRequestResponseTest<S> {
+    pub fn new(socket: S) -> Self {
         let mut test_messages: Vec<PldmExpectedMessagePair> = Vec::new();
 
         if cfg!(feature = "test-pldm-request-response") {
@@ -48,7 +47,10 @@ impl PldmRequestResponseTest {
                 SetTidRequest::new(2u8, PldmMsgType::Request, 2u8),
                 SetTidResponse::new(2u8, 0u8),
             );
-        } else if cfg!(feature = "test-pldm-discovery") {
+        } else if cfg!(any(
+            feature = "test-pldm-discovery",
+            feature = "test-mctp-serial"
+        )) {
             println!("Emulator: Running PLDM discovery Test");
             Self::add_pldm_discovery_test_message(&mut test_messages);
         } else if cfg!(feature = "test-pldm-fw-update") {
@@ -89,7 +91,7 @@ impl PldmRequestResponseTest {
         Ok(())
     }
 
-    pub fn run(socket: MctpPldmSocket) {
+    pub fn run(socket: S) {
         std::thread::spawn(move || {
             wait_for_runtime_start();
             if !MCU_RUNNING.load(Ordering::Relaxed) {
@@ -213,6 +215,9 @@ impl PldmRequestResponseTest {
                     FwUpdateCmd::GetStatus as u8,
                     FwUpdateCmd::CancelUpdateComponent as u8,
                     FwUpdateCmd::CancelUpdate as u8,
+                    FwUpdateCmd::GetPackageData as u8,
+                    FwUpdateCmd::GetDeviceMetaData as u8,
+                    FwUpdateCmd::GetMetaData as u8,
                 ],
             ),
         );
@@ -6,7 +6,7 @@ use std::fs::File;
 use std::io::{self, ErrorKind, Read, Write};
 use std::net::TcpStream;
 use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
+use std::process::{exit, Child, Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use zerocopy::{transmute, FromBytes, Immutable, IntoBytes};
 
@@ -57,6 +57,10 @@ impl SpdmValidatorRunner {
     }
 
     pub fn run_test(&mut self, stream: &mut TcpStream) {
+        // The runner may serve several connections one after the other
+        self.state = SpdmServerState::Start;
+        self.passed = false;
+
         while MCU_RUNNING.load(Ordering::Relaxed) {
             match self.state {
                 SpdmServerState::Start => {
@@ -317,6 +321,172 @@ pub fn execute_spdm_responder_validator(transport: &'static str) {
     });
 }
 
+/// A single spdm_requester_emu run. The output and the capture of the run are written to
+/// `<name>_output.txt` and `<name>.pcap` in SPDM_VALIDATOR_DIR.
+pub struct SpdmRequesterRun {
+    pub name: String,
+    pub args: Vec<&'static str>,
+}
+
+/// Starts spdm_requester_emu once per run, one after the other. Each run opens a new
+/// connection to the SPDM listener. The test passes once every run has exited
+/// successfully, and fails on the first run that does not.
+pub fn execute_spdm_requester_runs(transport: &'static str, runs: Vec<SpdmRequesterRun>) {
+    std::thread::spawn(move || {
+        println!("Starting spdm_requester_emu runs. Waiting for SPDM listener to start...");
+        while !SERVER_LISTENING.load(Ordering::Relaxed) {
+            std::thread::sleep(std::time::Duration::from_millis(200));
+        }
+
+        for run in runs {
+            let passed = match start_spdm_requester(transport, &run) {
+                Ok(child) => wait_for_requester_exit(child),
+                Err(e) => {
+                    println!("Error: {:?} Failed to spawn spdm_requester_emu!!", e);
+                    false
+                }
+            };
+            if !passed {
+                println!("spdm_requester_emu run {} failed", run.name);
+                exit(-1);
+            }
+            println!("spdm_requester_emu run {} passed", run.name);
+        }
+        exit(0);
+    });
+}
+
+/// Runs that each establish a session, rotate the session keys with KEY_UPDATE and keep
+/// using the session with the rotated keys. The runs alternate between updating the
+/// requester keys only and updating the keys in both directions.
+pub fn spdm_key_update_runs(count: usize) -> Vec<SpdmRequesterRun> {
+    (0..count)
+        .map(|i| SpdmRequesterRun {
+            name: format!("spdm_key_update_requester_{}", i),
+            args: vec![
+                "--exe_conn",
+                "DIGEST,CERT",
+                "--exe_session",
+                "KEY_EX,KEY_UPDATE,HEARTBEAT,MEAS",
+                "--key_upd",
+                if i % 2 == 0 { "REQ" } else { "ALL" },
+            ],
+        })
+        .collect()
+}
+
+/// Hex encoding of the 48-byte test PSK provisioned in the emulator user app for the
+/// default PSKHint of the requester.
+const EMULATOR_TEST_PSK_HEX: &str =
+    "4d6375456d756c61746f725370646d5465737450736b2d4e6f74466f7250726f64756374696f6e446576696365732121";
+
+/// Run that establishes a session with PSK_EXCHANGE and PSK_FINISH using the emulator
+/// test PSK and the default PSKHint of the requester, and then uses the session.
+pub fn spdm_psk_run() -> SpdmRequesterRun {
+    SpdmRequesterRun {
+        name: "spdm_psk_requester".to_string(),
+        args: vec![
+            "--psk",
+            EMULATOR_TEST_PSK_HEX,
+            "--exe_conn",
+            "DIGEST,CERT",
+            "--exe_session",
+            "PSK,KEY_UPDATE,HEARTBEAT,MEAS",
+            "--key_upd",
+            "ALL",
+        ],
+    }
+}
+
+/// Run of a SPDM 1.4 requester offering ML-DSA-87, which the responder selects over
+/// ECDSA, so that every signed response is verified with the post-quantum signature.
+pub fn spdm_pqc_run() -> SpdmRequesterRun {
+    SpdmRequesterRun {
+        name: "spdm_pqc_requester".to_string(),
+        args: vec![
+            "--ver",
+            "1.4",
+            "--pqc_asym",
+            "ML-DSA-87",
+            "--exe_conn",
+            "DIGEST,CERT,CHAL,MEAS",
+            "--exe_session",
+            "KEY_EX",
+        ],
+    }
+}
+
+/// Run that establishes a session with mutual authentication. The Responder retrieves
+/// the ECC P-384 certificate chain of the requester with encapsulated GET_DIGESTS and
+/// GET_CERTIFICATE, and verifies the requester signature in FINISH.
+pub fn spdm_mut_auth_run() -> SpdmRequesterRun {
+    SpdmRequesterRun {
+        name: "spdm_mut_auth_requester".to_string(),
+        args: vec![
+            "--exe_conn",
+            "DIGEST,CERT",
+            "--exe_session",
+            "KEY_EX,HEARTBEAT,MEAS",
+            "--req_asym",
+            "ECDSA_P384",
+        ],
+    }
+}
+
+/// Run that retrieves a CSR with GET_CSR and sends SET_CERTIFICATE over a secure session,
+/// then reads the chains back with GET_DIGESTS and GET_CERTIFICATE in the session. The
+/// sample chain of the requester does not certify the device key, so the responder
+/// rejects it and the session stays usable.
+pub fn spdm_set_cert_run() -> SpdmRequesterRun {
+    SpdmRequesterRun {
+        name: "spdm_set_cert_requester".to_string(),
+        args: vec![
+            "--exe_conn",
+            "DIGEST,CERT,GET_CSR",
+            "--exe_session",
+            "KEY_EX,GET_CSR,SET_CERT,DIGEST,CERT",
+        ],
+    }
+}
+
+/// Waits for spdm_requester_emu to exit. Returns true if it exited successfully.
+fn wait_for_requester_exit(mut child: Child) -> bool {
+    while MCU_RUNNING.load(Ordering::Relaxed) {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                println!("spdm_requester_emu exited with status: {:?}", status);
+                return status.success();
+            }
+            Ok(None) => {}
+            Err(e) => {
+                println!("Error: {:?}", e);
+                break;
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    let _ = child.kill();
+    false
+}
+
+pub fn start_spdm_requester(transport: &'static str, run: &SpdmRequesterRun) -> io::Result<Child> {
+    spawn_validator_binary(
+        "spdm_requester_emu",
+        &format!("{}_output.txt", run.name),
+        |cmd| {
+            println!(
+                "Starting spdm_requester_emu run {} with transport: {}, args: {:?}",
+                run.name, transport, run.args
+            );
+            cmd.arg("--trans")
+                .arg(transport)
+                .args(&run.args)
+                .arg("--pcap")
+                .arg(format!("{}.pcap", run.name));
+        },
+    )
+}
+
 pub fn start_spdm_responder_validator(transport: &'static str) -> io::Result<Child> {
     spawn_validator_binary(
         "spdm_device_validator_sample",
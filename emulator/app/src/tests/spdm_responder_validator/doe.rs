@@ -2,8 +2,9 @@
 
 use crate::tests::doe_util::common::DoeUtil;
 use crate::tests::spdm_responder_validator::common::{
-    execute_spdm_responder_validator, execute_spdm_tee_io_validator, SpdmValidatorRunner,
-    SERVER_LISTENING,
+    execute_spdm_requester_runs, execute_spdm_responder_validator, execute_spdm_tee_io_validator,
+    spdm_key_update_runs, spdm_mut_auth_run, spdm_pqc_run, spdm_psk_run, spdm_set_cert_run,
+    SpdmValidatorRunner, SERVER_LISTENING,
 };
 use crate::tests::spdm_responder_validator::transport::{Transport, SOCKET_TRANSPORT_TYPE_PCI_DOE};
 use crate::tests::spdm_responder_validator::SpdmTestType;
@@ -17,6 +18,9 @@ use std::time::Duration;
 
 const TEST_NAME: &str = "DOE-SPDM-RESPONDER-VALIDATOR";
 
+// Number of sessions whose keys are rotated in the key update test
+const KEY_UPDATE_RUNS: usize = 4;
+
 enum TxRxState {
     Start,
     SendReq,
@@ -114,6 +118,7 @@ pub fn run_doe_spdm_conformance_test(
     test_timeout_seconds: Duration,
 ) {
     let transport = DoeTransport::new(tx, rx, 1);
+    let requester_driven = test_type.requester_driven();
     // Spawn a thread to handle the timeout for the test
     thread::spawn(move || {
         thread::sleep(test_timeout_seconds);
@@ -140,15 +145,17 @@ pub fn run_doe_spdm_conformance_test(
         println!("[{}]: Spdm Server Listening on port 2323", TEST_NAME);
         SERVER_LISTENING.store(true, Ordering::Relaxed);
 
-        if let Some(spdm_stream) = listener.incoming().next() {
+        let mut test = SpdmValidatorRunner::new(Box::new(transport), TEST_NAME);
+        for spdm_stream in listener.incoming() {
             let mut spdm_stream = spdm_stream.expect("Failed to accept connection");
 
-            let mut test = SpdmValidatorRunner::new(Box::new(transport), TEST_NAME);
             test.run_test(&mut spdm_stream);
             if !test.is_passed() {
                 println!("[{}]: Spdm Responder Conformance Test Failed", TEST_NAME);
                 exit(-1);
-            } else {
+            }
+            // Requester-driven tests end once the last requester run has exited
+            if !requester_driven {
                 println!("[{}]: Spdm Responder Conformance Test Passed", TEST_NAME);
                 exit(0);
             }
@@ -158,5 +165,18 @@ pub fn run_doe_spdm_conformance_test(
     match test_type {
         SpdmTestType::SpdmResponderConformance => execute_spdm_responder_validator("PCI_DOE"),
         SpdmTestType::SpdmTeeIoValidator => execute_spdm_tee_io_validator("PCI_DOE"),
+        SpdmTestType::SpdmPqcRequester => {
+            execute_spdm_requester_runs("PCI_DOE", vec![spdm_pqc_run()])
+        }
+        SpdmTestType::SpdmKeyUpdate => {
+            execute_spdm_requester_runs("PCI_DOE", spdm_key_update_runs(KEY_UPDATE_RUNS))
+        }
+        SpdmTestType::SpdmPsk => execute_spdm_requester_runs("PCI_DOE", vec![spdm_psk_run()]),
+        SpdmTestType::SpdmMutAuth => {
+            execute_spdm_requester_runs("PCI_DOE", vec![spdm_mut_auth_run()])
+        }
+        SpdmTestType::SpdmSetCert => {
+            execute_spdm_requester_runs("PCI_DOE", vec![spdm_set_cert_run()])
+        }
     }
 }
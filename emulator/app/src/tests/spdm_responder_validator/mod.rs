@@ -8,4 +8,24 @@ mod transport;
 pub enum SpdmTestType {
     SpdmResponderConformance,
     SpdmTeeIoValidator,
+    SpdmPqcRequester,
+    SpdmKeyUpdate,
+    SpdmPsk,
+    SpdmMutAuth,
+    SpdmSetCert,
+}
+
+impl SpdmTestType {
+    /// Returns true if the test is made of spdm_requester_emu runs. Each run connects to the
+    /// SPDM listener in turn, and the result of the test is the exit status of the runs.
+    pub fn requester_driven(&self) -> bool {
+        matches!(
+            self,
+            SpdmTestType::SpdmPqcRequester
+                | SpdmTestType::SpdmKeyUpdate
+                | SpdmTestType::SpdmPsk
+                | SpdmTestType::SpdmMutAuth
+                | SpdmTestType::SpdmSetCert
+        )
+    }
 }
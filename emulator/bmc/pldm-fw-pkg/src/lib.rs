@@ -1,4 +1,5 @@
 // Licensed under the Apache-2.0 license
 
 pub mod manifest;
+pub mod signature;
 pub use manifest::FirmwareManifest;
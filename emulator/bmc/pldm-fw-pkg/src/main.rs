@@ -3,7 +3,7 @@
 Licensed under the Apache-2.0 license.
 
 --*/
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, ArgGroup, Command};
 /// PLDM Firmware Tool
 ///
 /// This tool is designed to work with PLDM (Platform Level Data Model) firmware packages.
@@ -22,11 +22,28 @@ use clap::{Arg, Command};
 /// pldm_fw_pkg encode --manifest manifest.toml --file firmware.bin
 /// ```
 ///
+/// Encode a manifest file, with the package data retrieved by the devices with GetPackageData
+/// read from a file:
+/// ```bash
+/// pldm_fw_pkg encode --manifest manifest.toml --file firmware.bin --package-data soc_manifest.bin
+/// ```
+///
+/// Encode and sign a manifest file with an ECC P-384 key and an ML-DSA-87 key:
+/// ```bash
+/// pldm_fw_pkg encode --manifest manifest.toml --file firmware.bin --ecc-key ecc.key --mldsa-key mldsa.key
+/// ```
+///
 /// Decode a firmware package:
 /// ```bash
 /// pldm_fw_pkg decode --file firmware.bin --directory output
 /// ```
 ///
+/// Decode a firmware package, rejecting it unless it is signed with the given ECC P-384 key:
+/// ```bash
+/// pldm_fw_pkg decode --package firmware.bin --directory output --ecc-public-key ecc_pub.bin
+/// ```
+///
+use pldm_fw_pkg::signature::{PackageSigningKeys, PackageVerificationPolicy};
 use pldm_fw_pkg::FirmwareManifest;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -51,6 +68,31 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         .value_name("FILE")
                         .help("Output file for the firmware package")
                         .required(true),
+                )
+                .arg(
+                    Arg::new("package-data")
+                        .long("package-data")
+                        .value_name("PACKAGE_DATA")
+                        .help(
+                            "Binary file with the firmware device package data, \
+                             replacing the one of every device ID record",
+                        ),
+                )
+                .arg(
+                    Arg::new("ecc-key")
+                        .long("ecc-key")
+                        .value_name("ECC_KEY")
+                        .help("ECC P-384 private key to sign the package with, as 48 raw bytes"),
+                )
+                .arg(
+                    Arg::new("mldsa-key")
+                        .long("mldsa-key")
+                        .value_name("MLDSA_KEY")
+                        .requires("ecc-key")
+                        .help(
+                            "ML-DSA-87 private key to also sign the package with, \
+                             in the raw FIPS 204 encoding",
+                        ),
                 ),
         )
         .subcommand(
@@ -71,6 +113,39 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         .value_name("DIRECTORY")
                         .help("Output directory for manifest and components")
                         .required(true),
+                )
+                .arg(
+                    Arg::new("require-signed")
+                        .long("require-signed")
+                        .action(ArgAction::SetTrue)
+                        .requires("trusted-key")
+                        .help(
+                            "Reject the package if it is not signed with the given \
+                             trusted public keys",
+                        ),
+                )
+                .arg(
+                    Arg::new("ecc-public-key")
+                        .long("ecc-public-key")
+                        .value_name("ECC_PUBLIC_KEY")
+                        .help(
+                            "Reject the package unless it is signed with this ECC P-384 \
+                             public key, as the raw X and Y coordinates",
+                        ),
+                )
+                .arg(
+                    Arg::new("mldsa-public-key")
+                        .long("mldsa-public-key")
+                        .value_name("MLDSA_PUBLIC_KEY")
+                        .help(
+                            "Reject the package unless it is signed with this ML-DSA-87 \
+                             public key, in the raw FIPS 204 encoding",
+                        ),
+                )
+                .group(
+                    ArgGroup::new("trusted-key")
+                        .args(["ecc-public-key", "mldsa-public-key"])
+                        .multiple(true),
                 ),
         )
         .get_matches();
@@ -80,17 +155,62 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(("encode", sub_matches)) => {
             let manifest_path = sub_matches.get_one::<String>("manifest").unwrap();
             let output_path = sub_matches.get_one("file").unwrap();
-            let firmware_manifest: FirmwareManifest =
+            let mut firmware_manifest: FirmwareManifest =
                 FirmwareManifest::parse_manifest_file(manifest_path)
                     .expect("Failed to parse the manifest file");
-            firmware_manifest.generate_firmware_package(output_path)?;
+            if let Some(package_data_path) = sub_matches.get_one::<String>("package-data") {
+                let package_data = std::fs::read(package_data_path)?;
+                if package_data.len() > u16::MAX as usize {
+                    return Err(format!(
+                        "Package data is too large: {} bytes, maximum is {} bytes",
+                        package_data.len(),
+                        u16::MAX
+                    )
+                    .into());
+                }
+                for record in &mut firmware_manifest.firmware_device_id_records {
+                    record.firmware_device_package_data = Some(package_data.clone());
+                }
+            }
+            if let Some(ecc_key_path) = sub_matches.get_one::<String>("ecc-key") {
+                let signing_keys = PackageSigningKeys::from_files(
+                    ecc_key_path,
+                    sub_matches.get_one::<String>("mldsa-key"),
+                )?;
+                firmware_manifest.generate_signed_firmware_package(output_path, &signing_keys)?;
+            } else {
+                firmware_manifest.generate_firmware_package(output_path)?;
+            }
             println!("Encoded FirmwarePackage to binary file: {}", output_path);
         }
         Some(("decode", sub_matches)) => {
             let package_path = sub_matches.get_one("package").unwrap();
             let output_dir = sub_matches.get_one("dir").unwrap();
-            FirmwareManifest::decode_firmware_package(package_path, Some(output_dir))
-                .expect("Failed to decode the firmware package");
+            let policy = PackageVerificationPolicy {
+                require_signature: sub_matches.get_flag("require-signed"),
+                trusted_ecc_public_key: sub_matches
+                    .get_one::<String>("ecc-public-key")
+                    .map(std::fs::read)
+                    .transpose()?,
+                trusted_mldsa_public_key: sub_matches
+                    .get_one::<String>("mldsa-public-key")
+                    .map(std::fs::read)
+                    .transpose()?,
+            };
+            let (_, signature_area) = FirmwareManifest::decode_firmware_package_with_policy(
+                package_path,
+                Some(output_dir),
+                &policy,
+            )
+            .expect("Failed to decode the firmware package");
+            match signature_area {
+                Some(signature_area) => {
+                    for signature in &signature_area.signatures {
+                        println!("Verified {} signature", signature.signature_type);
+                    }
+                }
+                None => println!("The firmware package is not signed"),
+            }
             println!("Decoded FirmwarePackage to directory: {}", output_dir);
         }
         _ => {
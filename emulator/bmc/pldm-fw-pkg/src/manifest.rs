@@ -12,13 +12,15 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::fs;
 use std::fs::File;
+use std::io::BufWriter;
 use std::io::{self, Read, Write};
-use std::io::{BufReader, BufWriter};
 use std::str::FromStr;
 use uuid::Uuid;
 
 use crc::{Crc, CRC_32_ISO_HDLC};
 
+use crate::signature::{PackageSignatureArea, PackageSigningKeys, PackageVerificationPolicy};
+
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct FirmwareManifest {
     pub package_header_information: PackageHeaderInformation,
@@ -414,10 +416,28 @@ impl FirmwareManifest {
     }
 
     pub fn generate_firmware_package(&self, output_file_path: &String) -> io::Result<()> {
+        self.write_firmware_package(output_file_path, None)
+    }
+
+    /// Generates a firmware package with a signature area made with the given keys.
+    pub fn generate_signed_firmware_package(
+        &self,
+        output_file_path: &String,
+        signing_keys: &PackageSigningKeys,
+    ) -> io::Result<()> {
+        self.write_firmware_package(output_file_path, Some(signing_keys))
+    }
+
+    fn write_firmware_package(
+        &self,
+        output_file_path: &String,
+        signing_keys: Option<&PackageSigningKeys>,
+    ) -> io::Result<()> {
         println!("Generating firmware package: {}", output_file_path);
         let file = File::create(output_file_path)?;
         let mut writer = BufWriter::new(file);
         let mut buffer: Vec<u8> = Vec::new();
+        let signature_area_size = signing_keys.map_or(0, |keys| keys.signature_area_size() as u16);
 
         // Encode package_header_information
         self.package_header_information.encode(
@@ -425,6 +445,7 @@ impl FirmwareManifest {
             &self.firmware_device_id_records,
             &self.downstream_device_id_records,
             &self.component_image_information,
+            signature_area_size,
         )?;
 
         let component_bitmap_bit_length = self.component_image_information.len() as u16;
@@ -453,6 +474,7 @@ impl FirmwareManifest {
             &self.firmware_device_id_records,
             &self.downstream_device_id_records,
             &self.component_image_information,
+            signature_area_size,
         ) as u32;
         buffer.write_all(&num_components.to_le_bytes())?;
         for component in &self.component_image_information {
@@ -489,13 +511,19 @@ impl FirmwareManifest {
         // Calculate the checksum of the image data
         let pldm_fw_package_payload_checksum = crc32.checksum(&image_data);
 
+        // Append the checksums to the package header
+        buffer.write_all(&package_header_checksum.to_le_bytes())?;
+        buffer.write_all(&pldm_fw_package_payload_checksum.to_le_bytes())?;
+
+        // Sign the package header and the image data, and append the signature area
+        if let Some(signing_keys) = signing_keys {
+            let signature_area = signing_keys.sign(&[buffer.as_slice(), image_data.as_slice()])?;
+            signature_area.encode(&mut buffer)?;
+        }
+
         // Write the package header to the writer
         writer.write_all(&buffer)?;
 
-        // Write the checksums to the writer
-        writer.write_all(&package_header_checksum.to_le_bytes())?;
-        writer.write_all(&pldm_fw_package_payload_checksum.to_le_bytes())?;
-
         // Write the image data to the writer
         writer.write_all(&image_data)?;
         writer.flush()?;
@@ -517,6 +545,22 @@ impl FirmwareManifest {
         fw_package_file_path: &String,
         output_dir_path: Option<&String>,
     ) -> io::Result<Self> {
+        Self::decode_firmware_package_with_policy(
+            fw_package_file_path,
+            output_dir_path,
+            &PackageVerificationPolicy::default(),
+        )
+        .map(|(manifest, _)| manifest)
+    }
+
+    /// Decodes a firmware package and checks its signature against the policy. The signatures
+    /// of a signed package are always verified. Returns the manifest and the signature area of
+    /// the package, if it is signed.
+    pub fn decode_firmware_package_with_policy(
+        fw_package_file_path: &String,
+        output_dir_path: Option<&String>,
+        policy: &PackageVerificationPolicy,
+    ) -> io::Result<(Self, Option<PackageSignatureArea>)> {
         if let Some(output_dir_path) = output_dir_path {
             match fs::metadata(output_dir_path) {
                 Ok(metadata) => {
@@ -536,8 +580,8 @@ impl FirmwareManifest {
             }
         }
 
-        let bin_file = File::open(fw_package_file_path)?;
-        let mut reader = BufReader::new(bin_file);
+        let package_data = fs::read(fw_package_file_path)?;
+        let mut reader: &[u8] = &package_data;
 
         // Decode package_header_information
         let (package_header_information, component_bitmap_length) =
@@ -613,6 +657,47 @@ impl FirmwareManifest {
             reader.read_exact(&mut buffer)?;
         }
 
+        // The rest of the package header, if any, is the signature area
+        let signed_header_size = package_data.len() - reader.len();
+        let package_header_size = package_header_information.package_header_size as usize;
+        let signature_area =
+            if pldm_version == PldmVersion::Version13 && package_header_size > signed_header_size {
+                let signature_area_size = package_header_size - signed_header_size;
+                if reader.len() < signature_area_size {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "Truncated package signature area",
+                    ));
+                }
+                let (mut signature_area_data, image_data) = reader.split_at(signature_area_size);
+                let signature_area = PackageSignatureArea::decode(&mut signature_area_data)?;
+                if !signature_area_data.is_empty() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Invalid package signature area size",
+                    ));
+                }
+                reader = image_data;
+                Some(signature_area)
+            } else {
+                None
+            };
+
+        // Verify the signatures over the package header and the image data before using them
+        if let Some(signature_area) = &signature_area {
+            let image_size = component_image_information
+                .iter()
+                .map(|component| component.size as usize)
+                .sum::<usize>()
+                .min(reader.len());
+            signature_area
+                .verify(&[&package_data[..signed_header_size], &reader[..image_size]])
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+        policy
+            .check(signature_area.as_ref())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
         for (component_idx, component) in component_image_information.iter_mut().enumerate() {
             // Get the size of the component
             let size = component.size as usize;
@@ -646,7 +731,7 @@ impl FirmwareManifest {
             file.write_all(manifest_data.as_bytes())?;
         }
 
-        Ok(manifest)
+        Ok((manifest, signature_area))
     }
 }
 
@@ -656,6 +741,7 @@ impl PackageHeaderInformation {
         firmware_device_records: &[FirmwareDeviceIdRecord],
         downstream_device_records: &Option<Vec<DownstreamDeviceIdRecord>>,
         component_image_information: &[ComponentImageInformation],
+        signature_area_size: u16,
     ) -> u16 {
         // Calculate the size of the header
         let mut size = 0;
@@ -690,6 +776,7 @@ impl PackageHeaderInformation {
 
         size += 4; // package_header_checksum
         size += 4; // pldm_fw_package_payload_checksum
+        size += signature_area_size; // signature area of a signed package
         size
     }
 
@@ -699,6 +786,7 @@ impl PackageHeaderInformation {
         firmware_device_record: &[FirmwareDeviceIdRecord],
         downstream_device_record: &Option<Vec<DownstreamDeviceIdRecord>>,
         component_image_information: &[ComponentImageInformation],
+        signature_area_size: u16,
     ) -> io::Result<()> {
         // Always encode as version 1.3
         let version13_uuid = PldmVersion::Version13.get_uuid().unwrap();
@@ -708,6 +796,7 @@ impl PackageHeaderInformation {
             firmware_device_record,
             downstream_device_record,
             component_image_information,
+            signature_area_size,
         );
         buffer.write_all(&header_size.to_le_bytes())?; // TODO: add size for firmware_device_id_records, downstream_device_id_records, component_image_information
 
@@ -0,0 +1,383 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+--*/
+
+//! Signature area of a PLDM firmware package.
+//!
+//! A signed package carries its signature area in the package header, right after the
+//! PackageHeaderChecksum and PLDMFWPackagePayloadChecksum fields. The area is counted in the
+//! PackageHeaderSize, so the component offsets skip it and decoders unaware of it still find
+//! the component images. The area is laid out as follows:
+//!
+//! | Field              | Size                     |
+//! |--------------------|--------------------------|
+//! | SignatureCount     | 1                        |
+//! | SignatureType      | 1 (for each signature)   |
+//! | PublicKeyLength    | 2 (for each signature)   |
+//! | SignatureLength    | 2 (for each signature)   |
+//! | PublicKey          | PublicKeyLength          |
+//! | Signature          | SignatureLength          |
+//!
+//! Every signature covers the package header up to and including the checksums, followed by
+//! the component images. ECC P-384 signs the SHA-384 digest of these bytes, ML-DSA-87 signs
+//! the bytes themselves (pure ML-DSA with an empty context).
+
+use fips204::ml_dsa_87;
+use fips204::traits::{SerDes, Signer, Verifier};
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::{FromPrimitive, ToPrimitive};
+use p384::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use p384::ecdsa::{
+    Signature as EccSignature, SigningKey as EccSigningKey, VerifyingKey as EccVerifyingKey,
+};
+use p384::elliptic_curve::sec1::ToEncodedPoint;
+use sha2::{Digest, Sha384};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+
+/// Length of an ECC P-384 public key, as the X and Y coordinates in big-endian
+pub const ECC_P384_PUBLIC_KEY_LEN: usize = 96;
+/// Length of an ECC P-384 signature, as the R and S values in big-endian
+pub const ECC_P384_SIGNATURE_LEN: usize = 96;
+
+#[derive(Debug, Clone, Copy, PartialEq, FromPrimitive, ToPrimitive)]
+pub enum SignatureType {
+    /// ECDSA P-384 over the SHA-384 digest of the package
+    EccP384 = 1,
+    /// Pure ML-DSA-87 over the package
+    MlDsa87 = 2,
+}
+
+impl SignatureType {
+    fn public_key_len(&self) -> usize {
+        match self {
+            SignatureType::EccP384 => ECC_P384_PUBLIC_KEY_LEN,
+            SignatureType::MlDsa87 => ml_dsa_87::PK_LEN,
+        }
+    }
+
+    fn signature_len(&self) -> usize {
+        match self {
+            SignatureType::EccP384 => ECC_P384_SIGNATURE_LEN,
+            SignatureType::MlDsa87 => ml_dsa_87::SIG_LEN,
+        }
+    }
+}
+
+impl fmt::Display for SignatureType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SignatureType::EccP384 => write!(f, "ECC P-384"),
+            SignatureType::MlDsa87 => write!(f, "ML-DSA-87"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageSignature {
+    pub signature_type: SignatureType,
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageSignatureArea {
+    pub signatures: Vec<PackageSignature>,
+}
+
+/// Keys to sign a package with. The package is always signed with the ECC P-384 key, and also
+/// with the ML-DSA-87 key if one is given.
+pub struct PackageSigningKeys {
+    pub ecc_private_key: p384::SecretKey,
+    pub mldsa_private_key: Option<ml_dsa_87::PrivateKey>,
+}
+
+/// Requirements on the signature of a package, checked when the package is decoded.
+#[derive(Debug, Clone, Default)]
+pub struct PackageVerificationPolicy {
+    /// Rejects packages without a signature area. At least one trusted key must be set, as
+    /// a signature made with any key proves nothing about the origin of the package.
+    pub require_signature: bool,
+    /// If set, the package must be signed with this ECC P-384 public key
+    pub trusted_ecc_public_key: Option<Vec<u8>>,
+    /// If set, the package must be signed with this ML-DSA-87 public key
+    pub trusted_mldsa_public_key: Option<Vec<u8>>,
+}
+
+// Digest of the signed parts of the package
+fn package_digest<D: Digest>(signed_data: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = D::new();
+    for data in signed_data {
+        hasher.update(data);
+    }
+    hasher.finalize().to_vec()
+}
+
+// Signed parts of the package as a single message
+fn package_message(signed_data: &[&[u8]]) -> Vec<u8> {
+    signed_data.concat()
+}
+
+impl PackageSignature {
+    fn verify(&self, signed_data: &[&[u8]]) -> Result<(), String> {
+        if self.public_key.len() != self.signature_type.public_key_len() {
+            return Err(format!(
+                "Invalid {} public key length {}",
+                self.signature_type,
+                self.public_key.len()
+            ));
+        }
+        if self.signature.len() != self.signature_type.signature_len() {
+            return Err(format!(
+                "Invalid {} signature length {}",
+                self.signature_type,
+                self.signature.len()
+            ));
+        }
+
+        let verified = match self.signature_type {
+            SignatureType::EccP384 => {
+                // Uncompressed SEC1 encoding of the public key
+                let mut sec1_public_key = vec![0x04];
+                sec1_public_key.extend_from_slice(&self.public_key);
+                let public_key = EccVerifyingKey::from_sec1_bytes(&sec1_public_key)
+                    .map_err(|_| "Invalid ECC P-384 public key".to_string())?;
+                let signature = EccSignature::from_slice(&self.signature)
+                    .map_err(|_| "Invalid ECC P-384 signature".to_string())?;
+                public_key
+                    .verify_prehash(&package_digest::<Sha384>(signed_data), &signature)
+                    .is_ok()
+            }
+            SignatureType::MlDsa87 => {
+                let public_key: [u8; ml_dsa_87::PK_LEN] =
+                    self.public_key.as_slice().try_into().unwrap();
+                let public_key = ml_dsa_87::PublicKey::try_from_bytes(public_key)
+                    .map_err(|_| "Invalid ML-DSA-87 public key".to_string())?;
+                let signature: [u8; ml_dsa_87::SIG_LEN] =
+                    self.signature.as_slice().try_into().unwrap();
+                public_key.verify(&package_message(signed_data), &signature, &[])
+            }
+        };
+        if !verified {
+            return Err(format!(
+                "{} signature verification failed",
+                self.signature_type
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl PackageSignatureArea {
+    // Size of a signature area holding signatures of the given types
+    fn size_of(signature_types: &[SignatureType]) -> usize {
+        1 + signature_types
+            .iter()
+            .map(|signature_type| {
+                5 + signature_type.public_key_len() + signature_type.signature_len()
+            })
+            .sum::<usize>()
+    }
+
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> io::Result<()> {
+        buffer.push(self.signatures.len() as u8);
+        for signature in &self.signatures {
+            buffer.push(signature.signature_type.to_u8().unwrap_or(0));
+            buffer.write_all(&(signature.public_key.len() as u16).to_le_bytes())?;
+            buffer.write_all(&(signature.signature.len() as u16).to_le_bytes())?;
+            buffer.write_all(&signature.public_key)?;
+            buffer.write_all(&signature.signature)?;
+        }
+        Ok(())
+    }
+
+    pub fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut buffer1 = [0u8; 1];
+        let mut buffer2 = [0u8; 2];
+
+        reader.read_exact(&mut buffer1)?;
+        let signature_count = buffer1[0];
+
+        let mut signatures = Vec::with_capacity(signature_count as usize);
+        for _ in 0..signature_count {
+            reader.read_exact(&mut buffer1)?;
+            let signature_type = SignatureType::from_u8(buffer1[0]).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Unknown package signature type {}", buffer1[0]),
+                )
+            })?;
+
+            reader.read_exact(&mut buffer2)?;
+            let public_key_length = u16::from_le_bytes(buffer2);
+            reader.read_exact(&mut buffer2)?;
+            let signature_length = u16::from_le_bytes(buffer2);
+
+            let mut public_key = vec![0u8; public_key_length as usize];
+            reader.read_exact(&mut public_key)?;
+            let mut signature = vec![0u8; signature_length as usize];
+            reader.read_exact(&mut signature)?;
+
+            signatures.push(PackageSignature {
+                signature_type,
+                public_key,
+                signature,
+            });
+        }
+
+        Ok(PackageSignatureArea { signatures })
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        1 + self
+            .signatures
+            .iter()
+            .map(|signature| 5 + signature.public_key.len() + signature.signature.len())
+            .sum::<usize>()
+    }
+
+    /// Returns the signature of the given type, if the package has one.
+    pub fn find(&self, signature_type: SignatureType) -> Option<&PackageSignature> {
+        self.signatures
+            .iter()
+            .find(|signature| signature.signature_type == signature_type)
+    }
+
+    /// Verifies every signature of the area against the signed parts of the package.
+    pub fn verify(&self, signed_data: &[&[u8]]) -> Result<(), String> {
+        if self.signatures.is_empty() {
+            return Err("The package signature area has no signature".to_string());
+        }
+        for (index, signature) in self.signatures.iter().enumerate() {
+            if let Err(e) = signature.verify(signed_data) {
+                return Err(format!("signatures[{}]: {}", index, e));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PackageSigningKeys {
+    /// Loads the signing keys from raw key files: the ECC P-384 private key as a 48-byte
+    /// big-endian scalar, and the ML-DSA-87 private key in the FIPS 204 encoding.
+    pub fn from_files(ecc_key_path: &String, mldsa_key_path: Option<&String>) -> io::Result<Self> {
+        let ecc_private_key =
+            p384::SecretKey::from_slice(&fs::read(ecc_key_path)?).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid ECC P-384 private key: {}", ecc_key_path),
+                )
+            })?;
+
+        let mldsa_private_key = match mldsa_key_path {
+            Some(mldsa_key_path) => {
+                let invalid_key = || {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Invalid ML-DSA-87 private key: {}", mldsa_key_path),
+                    )
+                };
+                let key_bytes: [u8; ml_dsa_87::SK_LEN] = fs::read(mldsa_key_path)?
+                    .try_into()
+                    .map_err(|_| invalid_key())?;
+                Some(ml_dsa_87::PrivateKey::try_from_bytes(key_bytes).map_err(|_| invalid_key())?)
+            }
+            None => None,
+        };
+
+        Ok(PackageSigningKeys {
+            ecc_private_key,
+            mldsa_private_key,
+        })
+    }
+
+    fn signature_types(&self) -> Vec<SignatureType> {
+        let mut signature_types = vec![SignatureType::EccP384];
+        if self.mldsa_private_key.is_some() {
+            signature_types.push(SignatureType::MlDsa87);
+        }
+        signature_types
+    }
+
+    /// Returns the size of the signature area made with these keys.
+    pub fn signature_area_size(&self) -> usize {
+        PackageSignatureArea::size_of(&self.signature_types())
+    }
+
+    /// Signs the signed parts of a package.
+    pub fn sign(&self, signed_data: &[&[u8]]) -> io::Result<PackageSignatureArea> {
+        let signing_key = EccSigningKey::from(&self.ecc_private_key);
+        let ecc_signature: EccSignature = signing_key
+            .sign_prehash(&package_digest::<Sha384>(signed_data))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+        let ecc_public_key = self.ecc_private_key.public_key().to_encoded_point(false);
+        let mut signatures = vec![PackageSignature {
+            signature_type: SignatureType::EccP384,
+            // Skip the SEC1 tag of the uncompressed point
+            public_key: ecc_public_key.as_bytes()[1..].to_vec(),
+            signature: ecc_signature.to_bytes().to_vec(),
+        }];
+
+        if let Some(mldsa_private_key) = &self.mldsa_private_key {
+            let mldsa_signature = mldsa_private_key
+                .try_sign(&package_message(signed_data), &[])
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            signatures.push(PackageSignature {
+                signature_type: SignatureType::MlDsa87,
+                public_key: mldsa_private_key.get_public_key().into_bytes().to_vec(),
+                signature: mldsa_signature.to_vec(),
+            });
+        }
+
+        Ok(PackageSignatureArea { signatures })
+    }
+}
+
+impl PackageVerificationPolicy {
+    /// Checks the signature area of a package, whose signatures are already verified, against
+    /// the policy.
+    pub fn check(&self, signature_area: Option<&PackageSignatureArea>) -> Result<(), String> {
+        if self.require_signature
+            && self.trusted_ecc_public_key.is_none()
+            && self.trusted_mldsa_public_key.is_none()
+        {
+            return Err(
+                "A signed package is required but no trusted public key is given".to_string(),
+            );
+        }
+
+        let Some(signature_area) = signature_area else {
+            if self.require_signature
+                || self.trusted_ecc_public_key.is_some()
+                || self.trusted_mldsa_public_key.is_some()
+            {
+                return Err("The package is not signed".to_string());
+            }
+            return Ok(());
+        };
+
+        let trusted_keys = [
+            (SignatureType::EccP384, &self.trusted_ecc_public_key),
+            (SignatureType::MlDsa87, &self.trusted_mldsa_public_key),
+        ];
+        for (signature_type, trusted_key) in trusted_keys {
+            let Some(trusted_key) = trusted_key else {
+                continue;
+            };
+            match signature_area.find(signature_type) {
+                Some(signature) if signature.public_key == *trusted_key => {}
+                Some(_) => {
+                    return Err(format!(
+                        "The {} signature is not made with the trusted key",
+                        signature_type
+                    ))
+                }
+                None => return Err(format!("The package has no {} signature", signature_type)),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+// Licensed under the Apache-2.0 license
+
+use chrono::Utc;
+use fips204::ml_dsa_87;
+use fips204::traits::{SerDes, Signer};
+use pldm_fw_pkg::{
+    manifest::{
+        ComponentImageInformation, Descriptor, DescriptorType, FirmwareDeviceIdRecord,
+        PackageHeaderInformation, StringType,
+    },
+    signature::{PackageSigningKeys, PackageVerificationPolicy, SignatureType},
+    FirmwareManifest,
+};
+use uuid::Uuid;
+
+fn get_manifest() -> FirmwareManifest {
+    FirmwareManifest {
+        package_header_information: PackageHeaderInformation {
+            package_header_identifier: Uuid::parse_str("7B291C996DB64208801B02026E463C78").unwrap(),
+            package_header_format_revision: 1,
+            package_release_date_time: Utc::now(),
+            package_version_string_type: StringType::Utf8,
+            package_version_string: Some("1.0.0".to_string()),
+            package_header_size: 0,
+        },
+        firmware_device_id_records: vec![FirmwareDeviceIdRecord {
+            component_image_set_version_string_type: StringType::Ascii,
+            component_image_set_version_string: Some("ComponentV1".to_string()),
+            applicable_components: Some(vec![0x00, 0x01]),
+            initial_descriptor: Descriptor {
+                descriptor_type: DescriptorType::Uuid,
+                descriptor_data: vec![0xAA, 0xBB, 0xCC],
+            },
+            ..Default::default()
+        }],
+        downstream_device_id_records: None,
+        component_image_information: vec![
+            ComponentImageInformation {
+                classification: 0x0001,
+                identifier: 0x0002,
+                comparison_stamp: Some(999),
+                version_string: Some("FirmwareV1".to_string()),
+                image_data: Some(vec![0x55u8; 256]),
+                ..Default::default()
+            },
+            ComponentImageInformation {
+                classification: 0x0001,
+                identifier: 0x0003,
+                comparison_stamp: Some(999),
+                version_string: Some("FirmwareV1".to_string()),
+                image_data: Some(vec![0x66u8; 128]),
+                ..Default::default()
+            },
+        ],
+    }
+}
+
+fn get_signing_keys() -> PackageSigningKeys {
+    let (_, mldsa_private_key) = ml_dsa_87::try_keygen().unwrap();
+    PackageSigningKeys {
+        ecc_private_key: p384::SecretKey::from_slice(&[0x11u8; 48]).unwrap(),
+        mldsa_private_key: Some(mldsa_private_key),
+    }
+}
+
+#[test]
+fn test_signed_package() {
+    let manifest = get_manifest();
+    let signing_keys = get_signing_keys();
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let temp_path = temp_file.path().to_str().unwrap().to_string();
+    manifest
+        .generate_signed_firmware_package(&temp_path, &signing_keys)
+        .unwrap();
+
+    let (decoded_manifest, signature_area) = FirmwareManifest::decode_firmware_package_with_policy(
+        &temp_path,
+        None,
+        &PackageVerificationPolicy::default(),
+    )
+    .unwrap();
+    let signature_area = signature_area.unwrap();
+    assert_eq!(signature_area.signatures.len(), 2);
+    assert_eq!(
+        signature_area.total_bytes(),
+        signing_keys.signature_area_size()
+    );
+
+    // The component images follow the signature area
+    for (decoded, original) in decoded_manifest
+        .component_image_information
+        .iter()
+        .zip(manifest.component_image_information.iter())
+    {
+        assert_eq!(decoded.image_data, original.image_data);
+    }
+
+    // The package is accepted with the keys it is signed with
+    let ecc_public_key = signature_area
+        .find(SignatureType::EccP384)
+        .unwrap()
+        .public_key
+        .clone();
+    let mldsa_public_key = signing_keys
+        .mldsa_private_key
+        .as_ref()
+        .unwrap()
+        .get_public_key()
+        .into_bytes()
+        .to_vec();
+    let policy = PackageVerificationPolicy {
+        require_signature: true,
+        trusted_ecc_public_key: Some(ecc_public_key.clone()),
+        trusted_mldsa_public_key: Some(mldsa_public_key),
+    };
+    assert!(
+        FirmwareManifest::decode_firmware_package_with_policy(&temp_path, None, &policy).is_ok()
+    );
+
+    // A signature is only required along with a trusted key
+    let policy = PackageVerificationPolicy {
+        require_signature: true,
+        ..Default::default()
+    };
+    assert!(
+        FirmwareManifest::decode_firmware_package_with_policy(&temp_path, None, &policy).is_err()
+    );
+
+    // And rejected with another key
+    let mut other_ecc_public_key = ecc_public_key;
+    other_ecc_public_key[0] ^= 0xFF;
+    let policy = PackageVerificationPolicy {
+        trusted_ecc_public_key: Some(other_ecc_public_key),
+        ..Default::default()
+    };
+    assert!(
+        FirmwareManifest::decode_firmware_package_with_policy(&temp_path, None, &policy).is_err()
+    );
+}
+
+#[test]
+fn test_tampered_signed_package() {
+    let manifest = get_manifest();
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let temp_path = temp_file.path().to_str().unwrap().to_string();
+    manifest
+        .generate_signed_firmware_package(&temp_path, &get_signing_keys())
+        .unwrap();
+
+    // Modify the last byte of the last component image
+    let mut package = std::fs::read(&temp_path).unwrap();
+    *package.last_mut().unwrap() ^= 0xFF;
+    std::fs::write(&temp_path, &package).unwrap();
+
+    let result = FirmwareManifest::decode_firmware_package(&temp_path, None);
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_unsigned_package_policy() {
+    let manifest = get_manifest();
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    let temp_path = temp_file.path().to_str().unwrap().to_string();
+    manifest.generate_firmware_package(&temp_path).unwrap();
+
+    let (_, signature_area) = FirmwareManifest::decode_firmware_package_with_policy(
+        &temp_path,
+        None,
+        &PackageVerificationPolicy::default(),
+    )
+    .unwrap();
+    assert!(signature_area.is_none());
+
+    let policy = PackageVerificationPolicy {
+        require_signature: true,
+        trusted_ecc_public_key: Some(vec![0x11u8; 96]),
+        ..Default::default()
+    };
+    assert!(
+        FirmwareManifest::decode_firmware_package_with_policy(&temp_path, None, &policy).is_err()
+    );
+}
@@ -0,0 +1,390 @@
+/*++
+
+Licensed under the Apache-2.0 license.
+
+--*/
+
+//! PLDM Firmware Update Agent
+//!
+//! Runs the PLDM update agent against a device of the emulator, over the MCTP I3C socket of
+//! the emulator. This tool provides the following subcommands:
+//! - `inventory`: Discover the device and report its identifiers and firmware parameters.
+//! - `update`: Update the device with a PLDM firmware package.
+//!
+//! Progress is reported on stderr. With `--json`, the result of the session is printed as JSON
+//! on stdout. Ctrl-C aborts the session and asks the device to cancel the update.
+//!
+//! # Examples
+//!
+//! Report the inventory of the device at I3C address 0x3a:
+//! ```bash
+//! pldm-ua --port 65534 --target-addr 0x3a inventory
+//! ```
+//!
+//! Update the device with a firmware package:
+//! ```bash
+//! pldm-ua --port 65534 --target-addr 0x3a --json update --package firmware.bin
+//! ```
+//!
+//! Update the device only if the package is signed with the given ECC P-384 key:
+//! ```bash
+//! pldm-ua --port 65534 --target-addr 0x3a update --package firmware.bin --ecc-public-key ecc_pub.bin
+//! ```
+
+mod report;
+mod session;
+
+use clap::{ArgGroup, Parser, Subcommand};
+use clap_num::maybe_hex;
+use log::LevelFilter;
+use mcu_testing_common::i3c::DynamicI3cAddress;
+use mcu_testing_common::mctp_transport::MctpTransport;
+use pldm_fw_pkg::signature::PackageVerificationPolicy;
+use pldm_fw_pkg::FirmwareManifest;
+use pldm_ua::campaign::UpdateResult;
+use pldm_ua::daemon::{Options, PldmDaemon};
+use pldm_ua::discovery_sm;
+use pldm_ua::transport::{EndpointId, PldmTransport};
+use session::{CliActions, Mode, Session};
+use simple_logger::SimpleLogger;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+// Print the download progress in steps of this many percents
+const PROGRESS_STEP: u32 = 10;
+
+#[derive(Parser)]
+#[command(
+    name = "pldm-ua",
+    version,
+    about = "PLDM firmware update agent for the emulator MCTP I3C socket"
+)]
+struct Args {
+    /// Port of the emulator I3C socket
+    #[arg(long)]
+    port: u16,
+
+    /// I3C dynamic address of the device
+    #[arg(long, value_parser = maybe_hex::<u8>)]
+    target_addr: u8,
+
+    /// Endpoint ID of the update agent
+    #[arg(long, default_value_t = 8)]
+    local_eid: u8,
+
+    /// Endpoint ID of the device
+    #[arg(long, default_value_t = 0)]
+    device_eid: u8,
+
+    /// The TID to be assigned to the device
+    #[arg(long, default_value_t = 1)]
+    fd_tid: u8,
+
+    /// Maximum duration of the session, in seconds
+    #[arg(long, default_value_t = 600)]
+    timeout: u64,
+
+    /// Print the result as JSON on stdout
+    #[arg(long)]
+    json: bool,
+
+    /// Print the logs of the update agent
+    #[arg(short, long)]
+    verbose: bool,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Discovers the device and reports its identifiers and firmware parameters
+    Inventory,
+    /// Updates the device with a PLDM firmware package
+    #[command(group(
+        ArgGroup::new("trusted_key")
+            .args(["ecc_public_key", "mldsa_public_key"])
+            .multiple(true)
+    ))]
+    Update {
+        /// Path to the firmware package file
+        #[arg(short, long)]
+        package: PathBuf,
+
+        /// Reject the package if it is not signed with the given trusted public keys
+        #[arg(long, requires = "trusted_key")]
+        require_signed: bool,
+
+        /// Reject the package unless it is signed with this ECC P-384 public key, as the raw X
+        /// and Y coordinates
+        #[arg(long)]
+        ecc_public_key: Option<PathBuf>,
+
+        /// Reject the package unless it is signed with this ML-DSA-87 public key, in the raw
+        /// FIPS 204 encoding
+        #[arg(long)]
+        mldsa_public_key: Option<PathBuf>,
+    },
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let log_level = if args.verbose {
+        LevelFilter::Info
+    } else {
+        LevelFilter::Warn
+    };
+    let _ = SimpleLogger::new().with_level(log_level).init();
+
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// Runs the session and returns whether it succeeded
+fn run(args: &Args) -> Result<bool, String> {
+    let (command, mode, pldm_fw_pkg) = match &args.command {
+        // The package is not used by an inventory
+        Commands::Inventory => ("inventory", Mode::Inventory, FirmwareManifest::default()),
+        Commands::Update {
+            package,
+            require_signed,
+            ecc_public_key,
+            mldsa_public_key,
+        } => {
+            let read_key = |path: &PathBuf| {
+                std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+            };
+            let policy = PackageVerificationPolicy {
+                require_signature: *require_signed,
+                trusted_ecc_public_key: ecc_public_key.as_ref().map(read_key).transpose()?,
+                trusted_mldsa_public_key: mldsa_public_key.as_ref().map(read_key).transpose()?,
+            };
+            let (pldm_fw_pkg, signature_area) =
+                FirmwareManifest::decode_firmware_package_with_policy(
+                    &package.to_string_lossy().to_string(),
+                    None,
+                    &policy,
+                )
+                .map_err(|e| format!("Failed to decode {}: {}", package.display(), e))?;
+            if let Some(signature_area) = signature_area {
+                for signature in &signature_area.signatures {
+                    eprintln!("Verified {} package signature", signature.signature_type);
+                }
+            }
+            ("update", Mode::Update, pldm_fw_pkg)
+        }
+    };
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let cancelled_clone = cancelled.clone();
+    ctrlc::set_handler(move || cancelled_clone.store(true, Ordering::Relaxed))
+        .map_err(|e| format!("Failed to set the Ctrl-C handler: {}", e))?;
+
+    let target_addr = DynamicI3cAddress::new(args.target_addr)
+        .map_err(|_| format!("Invalid I3C address: {:#x}", args.target_addr))?;
+    let pldm_socket = MctpTransport::new(args.port, target_addr)
+        .create_socket(EndpointId(args.local_eid), EndpointId(args.device_eid))
+        .map_err(|e| format!("Failed to connect to port {}: {:?}", args.port, e))?;
+
+    let session = Arc::new(Mutex::new(Session::default()));
+    let start_time = Instant::now();
+    let mut daemon = PldmDaemon::run(
+        pldm_socket,
+        Options {
+            pldm_fw_pkg: Some(pldm_fw_pkg),
+            discovery_sm_actions: discovery_sm::DefaultActions {},
+            update_sm_actions: CliActions::new(mode, session.clone()),
+            fd_tid: args.fd_tid,
+        },
+    )
+    .map_err(|_| "Failed to start the update agent".to_string())?;
+    eprintln!(
+        "Connected to the device at I3C address {:#x}",
+        args.target_addr
+    );
+
+    let timeout = Duration::from_secs(args.timeout);
+    let mut last_state = None;
+    let mut last_progress: Option<(usize, u32)> = None;
+    let mut aborted = false;
+    loop {
+        if session.lock().unwrap().finished {
+            break;
+        }
+        if !aborted && (cancelled.load(Ordering::Relaxed) || start_time.elapsed() >= timeout) {
+            if cancelled.load(Ordering::Relaxed) {
+                eprintln!("Cancelling...");
+            } else {
+                eprintln!("Timed out after {} s", args.timeout);
+                session.lock().unwrap().finish(Some(UpdateResult::TimedOut));
+            }
+            daemon.abort_update();
+            aborted = true;
+        }
+
+        let state = daemon.get_update_sm_state();
+        if last_state.as_ref() != Some(&state) {
+            eprintln!("State: {:?}", state);
+            last_state = Some(state);
+        }
+        if let Some(progress) = session.lock().unwrap().progress {
+            let step = progress.percent() / PROGRESS_STEP * PROGRESS_STEP;
+            if last_progress != Some((progress.index, step)) {
+                eprintln!(
+                    "Component {}/{} (id {:#06x}): {}% of {} bytes",
+                    progress.index + 1,
+                    progress.count,
+                    progress.identifier,
+                    step,
+                    progress.size
+                );
+                last_progress = Some((progress.index, step));
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    let final_state = daemon.get_update_sm_state();
+    let device_id = daemon.get_device_id();
+    daemon.stop();
+
+    let session = session.lock().unwrap();
+    eprintln!(
+        "Result: {} in {:.1} s",
+        report::result_name(session.result),
+        start_time.elapsed().as_secs_f32()
+    );
+    if args.json {
+        let report = report::session_report(
+            command,
+            args.fd_tid,
+            &session,
+            device_id.as_ref(),
+            &final_state,
+            start_time.elapsed(),
+        );
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+        );
+    }
+
+    Ok(matches!(
+        session.result,
+        None | Some(UpdateResult::Updated) | Some(UpdateResult::UpToDate)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_inventory_defaults() {
+        let args = Args::try_parse_from([
+            "pldm-ua",
+            "--port",
+            "65534",
+            "--target-addr",
+            "0x3a",
+            "inventory",
+        ])
+        .unwrap();
+        assert_eq!(args.port, 65534);
+        assert_eq!(args.target_addr, 0x3a);
+        assert_eq!(args.local_eid, 8);
+        assert_eq!(args.device_eid, 0);
+        assert_eq!(args.fd_tid, 1);
+        assert_eq!(args.timeout, 600);
+        assert!(!args.json);
+        assert!(!args.verbose);
+        assert!(matches!(args.command, Commands::Inventory));
+    }
+
+    #[test]
+    fn test_parse_update() {
+        let args = Args::try_parse_from([
+            "pldm-ua",
+            "--port",
+            "1234",
+            "--target-addr",
+            "58",
+            "--device-eid",
+            "10",
+            "--json",
+            "update",
+            "--package",
+            "firmware.bin",
+            "--require-signed",
+            "--mldsa-public-key",
+            "mldsa_pub.bin",
+        ])
+        .unwrap();
+        assert_eq!(args.target_addr, 58);
+        assert_eq!(args.device_eid, 10);
+        assert!(args.json);
+        match args.command {
+            Commands::Update {
+                package,
+                require_signed,
+                ecc_public_key,
+                mldsa_public_key,
+            } => {
+                assert_eq!(package, PathBuf::from("firmware.bin"));
+                assert!(require_signed);
+                assert_eq!(ecc_public_key, None);
+                assert_eq!(mldsa_public_key, Some(PathBuf::from("mldsa_pub.bin")));
+            }
+            Commands::Inventory => panic!("Expected the update command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // A signed package is only required along with a trusted key
+        assert!(Args::try_parse_from([
+            "pldm-ua",
+            "--port",
+            "1",
+            "--target-addr",
+            "0x3a",
+            "update",
+            "--package",
+            "firmware.bin",
+            "--require-signed"
+        ])
+        .is_err());
+        // The package is required by an update
+        assert!(Args::try_parse_from([
+            "pldm-ua",
+            "--port",
+            "1",
+            "--target-addr",
+            "0x3a",
+            "update"
+        ])
+        .is_err());
+        // The target address is a byte
+        assert!(Args::try_parse_from([
+            "pldm-ua",
+            "--port",
+            "1",
+            "--target-addr",
+            "0x100",
+            "inventory"
+        ])
+        .is_err());
+        // A subcommand is required
+        assert!(Args::try_parse_from(["pldm-ua", "--port", "1", "--target-addr", "0x3a"]).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+// Licensed under the Apache-2.0 license
+
+// JSON result of a session of the command-line update agent.
+
+use crate::session::Session;
+use pldm_common::message::firmware_update::get_fw_params::GetFirmwareParametersResponse;
+use pldm_common::message::firmware_update::query_devid::QueryDeviceIdentifiersResponse;
+use pldm_common::protocol::firmware_update::Descriptor;
+use pldm_fw_pkg::manifest::FirmwareDeviceIdRecord;
+use pldm_ua::campaign::UpdateResult;
+use pldm_ua::update_sm;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+pub fn result_name(result: Option<UpdateResult>) -> &'static str {
+    match result {
+        None => "ok",
+        Some(UpdateResult::Updated) => "updated",
+        Some(UpdateResult::UpToDate) => "up_to_date",
+        Some(UpdateResult::NoMatchingPackage) => "no_matching_package",
+        Some(UpdateResult::Failed) => "failed",
+        Some(UpdateResult::TimedOut) => "timed_out",
+        Some(UpdateResult::Cancelled) => "cancelled",
+    }
+}
+
+pub fn session_report(
+    command: &str,
+    fd_tid: u8,
+    session: &Session,
+    device_id: Option<&FirmwareDeviceIdRecord>,
+    final_state: &update_sm::States,
+    duration: Duration,
+) -> Value {
+    json!({
+        "command": command,
+        "result": result_name(session.result),
+        "final_state": format!("{:?}", final_state),
+        "duration_ms": duration.as_millis() as u64,
+        "device": {
+            "tid": fd_tid,
+            "descriptors": session.device_identifiers.as_ref().map(descriptors),
+            "firmware_parameters": session.firmware_parameters.as_ref().map(firmware_parameters),
+        },
+        "matched_image_set_version": device_id
+            .and_then(|record| record.component_image_set_version_string.clone()),
+        "update_components": session.update_components,
+    })
+}
+
+fn descriptors(response: &QueryDeviceIdentifiersResponse) -> Value {
+    let mut descriptors = vec![descriptor(&response.initial_descriptor)];
+    if let Some(additional) = &response.additional_descriptors {
+        let count = (response.descriptor_count as usize).saturating_sub(1);
+        descriptors.extend(additional.iter().take(count).map(descriptor));
+    }
+    Value::Array(descriptors)
+}
+
+fn descriptor(descriptor: &Descriptor) -> Value {
+    let len = (descriptor.descriptor_length as usize).min(descriptor.descriptor_data.len());
+    json!({
+        "type": descriptor.descriptor_type,
+        "data": to_hex(&descriptor.descriptor_data[..len]),
+    })
+}
+
+fn firmware_parameters(response: &GetFirmwareParametersResponse) -> Value {
+    let params = &response.parms;
+    let fixed = &params.params_fixed;
+    let comp_count = (fixed.comp_count as usize).min(params.comp_param_table.len());
+    let components: Vec<Value> = params.comp_param_table[..comp_count]
+        .iter()
+        .map(|entry| {
+            let fixed = &entry.comp_param_entry_fixed;
+            let (classification, identifier) = (fixed.comp_classification, fixed.comp_identifier);
+            let active_stamp = fixed.active_comp_comparison_stamp;
+            let pending_stamp = fixed.pending_comp_comparison_stamp;
+            json!({
+                "classification": classification,
+                "identifier": identifier,
+                "classification_index": fixed.comp_classification_index,
+                "active_comparison_stamp": active_stamp,
+                "active_version": version_string(
+                    &entry.active_comp_ver_str,
+                    fixed.active_comp_ver_str_len,
+                ),
+                "pending_comparison_stamp": pending_stamp,
+                "pending_version": entry.pending_comp_ver_str.as_ref().map(|ver_str| {
+                    version_string(ver_str, fixed.pending_comp_ver_str_len)
+                }),
+            })
+        })
+        .collect();
+
+    json!({
+        "active_image_set_version": version_string(
+            &params.active_comp_image_set_ver_str,
+            fixed.active_comp_image_set_ver_str_len,
+        ),
+        "pending_image_set_version": params.pending_comp_image_set_ver_str.as_ref().map(
+            |ver_str| version_string(ver_str, fixed.pending_comp_image_set_ver_str_len)
+        ),
+        "components": components,
+    })
+}
+
+fn version_string(ver_str: &[u8], len: u8) -> String {
+    String::from_utf8_lossy(&ver_str[..(len as usize).min(ver_str.len())]).into_owned()
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pldm_common::message::firmware_update::get_fw_params::FirmwareParameters;
+    use pldm_common::protocol::base::PldmBaseCompletionCode;
+    use pldm_common::protocol::firmware_update::{
+        ComponentActivationMethods, ComponentClassification, ComponentParameterEntry,
+        DescriptorType, FirmwareDeviceCapability, PldmFirmwareString, PldmFirmwareVersion,
+    };
+
+    const UUID: [u8; 16] = [
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+        0x10,
+    ];
+
+    fn updated_session() -> Session {
+        let descriptor = Descriptor::new(DescriptorType::Uuid, &UUID).unwrap();
+        let active_string = PldmFirmwareString::new("UTF-8", "fw-1.0").unwrap();
+        let pending_string = PldmFirmwareString::new("UTF-8", "fw-1.1").unwrap();
+        let entry = ComponentParameterEntry::new(
+            ComponentClassification::Firmware,
+            0x0001,
+            0,
+            &PldmFirmwareVersion::new(0x10, &active_string, Some("20250210")),
+            &PldmFirmwareVersion::new(0x11, &pending_string, Some("20250213")),
+            ComponentActivationMethods(0x0001),
+            FirmwareDeviceCapability(0x0010),
+        );
+        let params = FirmwareParameters::new(
+            FirmwareDeviceCapability(0x0010),
+            1,
+            &PldmFirmwareString::new("UTF-8", "1.0.0").unwrap(),
+            &PldmFirmwareString::new("UTF-8", "").unwrap(),
+            &[entry],
+        );
+
+        let mut session = Session::default();
+        session.device_identifiers = Some(
+            QueryDeviceIdentifiersResponse::new(
+                0,
+                PldmBaseCompletionCode::Success as u8,
+                &descriptor,
+                None,
+            )
+            .unwrap(),
+        );
+        session.firmware_parameters = Some(GetFirmwareParametersResponse::new(
+            1,
+            PldmBaseCompletionCode::Success as u8,
+            &params,
+        ));
+        session.update_components = vec![0x0001];
+        session.finish(Some(UpdateResult::Updated));
+        session
+    }
+
+    #[test]
+    fn test_session_report() {
+        let device_id = FirmwareDeviceIdRecord {
+            component_image_set_version_string: Some("1.1.0".to_string()),
+            ..Default::default()
+        };
+        let report = session_report(
+            "update",
+            0x01,
+            &updated_session(),
+            Some(&device_id),
+            &update_sm::States::Done,
+            Duration::from_millis(1500),
+        );
+
+        assert_eq!(report["command"], "update");
+        assert_eq!(report["result"], "updated");
+        assert_eq!(report["final_state"], "Done");
+        assert_eq!(report["duration_ms"], 1500);
+        assert_eq!(report["matched_image_set_version"], "1.1.0");
+        assert_eq!(report["update_components"], json!([1]));
+
+        let device = &report["device"];
+        assert_eq!(device["tid"], 1);
+        assert_eq!(
+            device["descriptors"],
+            json!([{
+                "type": DescriptorType::Uuid as u16,
+                "data": "0102030405060708090a0b0c0d0e0f10",
+            }])
+        );
+        let params = &device["firmware_parameters"];
+        assert_eq!(params["active_image_set_version"], "1.0.0");
+        assert_eq!(params["pending_image_set_version"], Value::Null);
+        assert_eq!(
+            params["components"],
+            json!([{
+                "classification": ComponentClassification::Firmware as u16,
+                "identifier": 1,
+                "classification_index": 0,
+                "active_comparison_stamp": 0x10,
+                "active_version": "fw-1.0",
+                "pending_comparison_stamp": 0x11,
+                "pending_version": "fw-1.1",
+            }])
+        );
+    }
+
+    #[test]
+    fn test_inventory_report_without_device() {
+        // A session that ended before the device answered reports no device information
+        let mut session = Session::default();
+        session.finish(Some(UpdateResult::TimedOut));
+        let report = session_report(
+            "inventory",
+            0x02,
+            &session,
+            None,
+            &update_sm::States::QueryDeviceIdentifiersSent,
+            Duration::ZERO,
+        );
+
+        assert_eq!(report["result"], "timed_out");
+        assert_eq!(report["final_state"], "QueryDeviceIdentifiersSent");
+        assert_eq!(report["device"]["tid"], 2);
+        assert_eq!(report["device"]["descriptors"], Value::Null);
+        assert_eq!(report["device"]["firmware_parameters"], Value::Null);
+        assert_eq!(report["matched_image_set_version"], Value::Null);
+        assert_eq!(report["update_components"], json!([]));
+    }
+
+    #[test]
+    fn test_result_names() {
+        assert_eq!(result_name(None), "ok");
+        assert_eq!(result_name(Some(UpdateResult::UpToDate)), "up_to_date");
+        assert_eq!(
+            result_name(Some(UpdateResult::NoMatchingPackage)),
+            "no_matching_package"
+        );
+        assert_eq!(result_name(Some(UpdateResult::Cancelled)), "cancelled");
+    }
+}
@@ -0,0 +1,235 @@
+// Licensed under the Apache-2.0 license
+
+// Update state machine actions of the command-line update agent. They record the device
+// inventory, the download progress and the outcome of the session, and otherwise leave the
+// update to the default actions.
+
+use pldm_common::message::firmware_update as pldm_packet;
+use pldm_ua::campaign::UpdateResult;
+use pldm_ua::events::PldmEvents;
+use pldm_ua::transport::PldmSocket;
+use pldm_ua::update_sm::{self, StateMachineActions};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Stop once the device identifiers and firmware parameters are known
+    Inventory,
+    /// Run a full update
+    Update,
+}
+
+/// Download progress of the component being transferred.
+#[derive(Debug, Clone, Copy)]
+pub struct DownloadProgress {
+    /// Index of the component in the components to update
+    pub index: usize,
+    pub count: usize,
+    pub identifier: u16,
+    /// End offset of the last firmware data requested by the device
+    pub offset: u32,
+    pub size: u32,
+}
+
+impl DownloadProgress {
+    pub fn percent(&self) -> u32 {
+        if self.size == 0 {
+            return 100;
+        }
+        (self.offset.min(self.size) as u64 * 100 / self.size as u64) as u32
+    }
+}
+
+#[derive(Default)]
+pub struct Session {
+    pub device_identifiers: Option<pldm_packet::query_devid::QueryDeviceIdentifiersResponse>,
+    pub firmware_parameters: Option<pldm_packet::get_fw_params::GetFirmwareParametersResponse>,
+    /// Identifiers of the package components that the device needs
+    pub update_components: Vec<u16>,
+    pub progress: Option<DownloadProgress>,
+    pub finished: bool,
+    /// Outcome of the update. An inventory that completes has no result.
+    pub result: Option<UpdateResult>,
+    activation_requested: bool,
+}
+
+impl Session {
+    /// Ends the session, unless it is already over.
+    pub fn finish(&mut self, result: Option<UpdateResult>) {
+        if !self.finished {
+            self.finished = true;
+            self.result = result;
+        }
+    }
+}
+
+pub struct CliActions {
+    mode: Mode,
+    session: Arc<Mutex<Session>>,
+    default: update_sm::DefaultActions,
+}
+
+impl CliActions {
+    pub fn new(mode: Mode, session: Arc<Mutex<Session>>) -> Self {
+        Self {
+            mode,
+            session,
+            default: update_sm::DefaultActions,
+        }
+    }
+}
+
+impl StateMachineActions for CliActions {
+    fn on_query_device_identifiers_response(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+        response: pldm_packet::query_devid::QueryDeviceIdentifiersResponse,
+    ) -> Result<(), ()> {
+        self.session.lock().unwrap().device_identifiers = Some(response.clone());
+        if self.mode == Mode::Inventory {
+            // The package is not involved in an inventory
+            return ctx
+                .event_queue
+                .send(PldmEvents::Update(
+                    update_sm::Events::SendGetFirmwareParameters,
+                ))
+                .map_err(|_| ());
+        }
+
+        let result = self
+            .default
+            .on_query_device_identifiers_response(ctx, response);
+        if ctx.device_id.is_none() {
+            self.session
+                .lock()
+                .unwrap()
+                .finish(Some(UpdateResult::NoMatchingPackage));
+        }
+        result
+    }
+
+    fn on_get_firmware_parameters_response(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+        response: pldm_packet::get_fw_params::GetFirmwareParametersResponse,
+    ) -> Result<(), ()> {
+        self.session.lock().unwrap().firmware_parameters = Some(response.clone());
+        if self.mode == Mode::Inventory {
+            self.session.lock().unwrap().finish(None);
+            return ctx
+                .event_queue
+                .send(PldmEvents::Update(update_sm::Events::StopUpdate))
+                .map_err(|_| ());
+        }
+
+        let result = self
+            .default
+            .on_get_firmware_parameters_response(ctx, response);
+        let mut session = self.session.lock().unwrap();
+        session.update_components = ctx.components.iter().map(|c| c.identifier).collect();
+        if ctx.components.is_empty() {
+            session.finish(Some(UpdateResult::UpToDate));
+        }
+        result
+    }
+
+    fn on_request_firmware(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+        request: pldm_packet::request_fw_data::RequestFirmwareDataRequest,
+    ) -> Result<(), ()> {
+        if let Some(index) = ctx.current_component_index {
+            let component = &ctx.components[index];
+            self.session.lock().unwrap().progress = Some(DownloadProgress {
+                index,
+                count: ctx.components.len(),
+                identifier: component.identifier,
+                offset: request.offset.saturating_add(request.length),
+                size: component
+                    .image_data
+                    .as_ref()
+                    .map_or(component.size, |data| data.len() as u32),
+            });
+        }
+        self.default.on_request_firmware(ctx, request)
+    }
+
+    fn on_activate_firmware(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.session.lock().unwrap().activation_requested = true;
+        self.default.on_activate_firmware(ctx)
+    }
+
+    fn on_transfer_fail(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.session
+            .lock()
+            .unwrap()
+            .finish(Some(UpdateResult::Failed));
+        self.default.on_transfer_fail(ctx)
+    }
+
+    fn on_verify_fail(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.session
+            .lock()
+            .unwrap()
+            .finish(Some(UpdateResult::Failed));
+        self.default.on_verify_fail(ctx)
+    }
+
+    fn on_apply_fail(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.session
+            .lock()
+            .unwrap()
+            .finish(Some(UpdateResult::Failed));
+        self.default.on_apply_fail(ctx)
+    }
+
+    fn on_stop_update(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        {
+            let mut session = self.session.lock().unwrap();
+            let result = if session.activation_requested {
+                UpdateResult::Updated
+            } else {
+                UpdateResult::Failed
+            };
+            session.finish(Some(result));
+        }
+        self.default.on_stop_update(ctx)
+    }
+
+    fn on_stop_update_error(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.session
+            .lock()
+            .unwrap()
+            .finish(Some(UpdateResult::Failed));
+        self.default.on_stop_update_error(ctx)
+    }
+
+    fn on_abort_update(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.session
+            .lock()
+            .unwrap()
+            .finish(Some(UpdateResult::Cancelled));
+        self.default.on_abort_update(ctx)
+    }
+}
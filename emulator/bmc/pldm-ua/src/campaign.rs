@@ -0,0 +1,390 @@
+// Licensed under the Apache-2.0 license
+
+use crate::daemon::{Options, PldmDaemon};
+use crate::discovery_sm;
+use crate::transport::PldmSocket;
+use crate::update_sm::{self, StateMachineActions};
+use log::{error, info, warn};
+use pldm_common::message::firmware_update as pldm_packet;
+use pldm_fw_pkg::manifest::FirmwareDeviceIdRecord;
+use pldm_fw_pkg::FirmwareManifest;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How the devices of a campaign are updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdatePolicy {
+    /// All the devices are updated at the same time.
+    Parallel,
+    /// The devices are updated one after the other, in the order they are given.
+    Serial,
+}
+
+/// Outcome of the update of one device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateResult {
+    /// The components were transferred and the activation was requested.
+    Updated,
+    /// The device already runs the components of the matching package.
+    UpToDate,
+    /// The device descriptors do not match any package of the campaign.
+    NoMatchingPackage,
+    /// The update was rejected or stopped with an error.
+    Failed,
+    /// The update did not complete within `CampaignOptions::device_timeout`.
+    TimedOut,
+    /// The update was aborted before it completed.
+    Cancelled,
+}
+
+/// A firmware device taking part in a campaign.
+pub struct CampaignDevice<S: PldmSocket, D: discovery_sm::StateMachineActions> {
+    /// Name of the device in the report
+    pub name: String,
+    /// Socket connected to the endpoint of the device
+    pub socket: S,
+    /// The TID to be assigned to the device
+    pub fd_tid: u8,
+    pub discovery_sm_actions: D,
+}
+
+pub struct CampaignOptions {
+    /// Candidate packages. Each device is updated with the first package that has a
+    /// FirmwareDeviceIdRecord matching its descriptors.
+    pub packages: Vec<FirmwareManifest>,
+    pub policy: UpdatePolicy,
+    /// Maximum time to update a single device, from discovery to activation.
+    pub device_timeout: Duration,
+}
+
+/// Result of the update of one device.
+#[derive(Debug, Clone)]
+pub struct DeviceReport {
+    pub name: String,
+    pub fd_tid: u8,
+    pub result: UpdateResult,
+    /// Index in `CampaignOptions::packages` of the package matching the device
+    pub package_index: Option<usize>,
+    /// The FirmwareDeviceIdRecord of the package matching the device
+    pub device_id: Option<FirmwareDeviceIdRecord>,
+    /// State of the update state machine when the update ended
+    pub final_state: update_sm::States,
+    pub duration: Duration,
+}
+
+impl DeviceReport {
+    // Report of a device whose update could not run to an end
+    fn failed(name: String, fd_tid: u8, duration: Duration) -> Self {
+        Self {
+            name,
+            fd_tid,
+            result: UpdateResult::Failed,
+            package_index: None,
+            device_id: None,
+            final_state: update_sm::States::Idle,
+            duration,
+        }
+    }
+}
+
+/// Per-device results of a campaign, in the order the devices were given.
+#[derive(Debug, Clone, Default)]
+pub struct CampaignReport {
+    pub devices: Vec<DeviceReport>,
+}
+
+impl CampaignReport {
+    /// Returns true if every device was updated or is already up to date.
+    pub fn is_success(&self) -> bool {
+        self.devices.iter().all(|device| {
+            matches!(
+                device.result,
+                UpdateResult::Updated | UpdateResult::UpToDate
+            )
+        })
+    }
+
+    /// Returns the number of devices with the given result.
+    pub fn count(&self, result: UpdateResult) -> usize {
+        self.devices
+            .iter()
+            .filter(|device| device.result == result)
+            .count()
+    }
+}
+
+/// Runs a firmware update campaign over several devices.
+///
+/// A PLDM daemon is run for each device. Once the device identifiers are received, the
+/// package matching the device descriptors is selected and the update proceeds as with a
+/// single device. This function returns when every device update has ended.
+///
+/// # Arguments
+///
+/// * `devices` - The devices to update.
+/// * `opts` - Campaign options.
+///
+/// # Returns
+///
+/// Returns the report of the campaign, or an error if no package is provided. A device whose
+/// update cannot be started, or whose update thread panics, is reported as failed.
+pub fn run<
+    S: PldmSocket + Send + 'static,
+    D: discovery_sm::StateMachineActions + Send + 'static,
+>(
+    devices: Vec<CampaignDevice<S, D>>,
+    opts: CampaignOptions,
+) -> Result<CampaignReport, ()> {
+    if opts.packages.is_empty() {
+        warn!("No PLDM firmware package is provided.");
+        return Err(());
+    }
+    info!(
+        "Starting {:?} update campaign of {} devices with {} packages",
+        opts.policy,
+        devices.len(),
+        opts.packages.len()
+    );
+
+    let packages = Arc::new(opts.packages);
+    let mut report = CampaignReport::default();
+    match opts.policy {
+        UpdatePolicy::Serial => {
+            for device in devices {
+                report
+                    .devices
+                    .push(update_device(device, packages.clone(), opts.device_timeout));
+            }
+        }
+        UpdatePolicy::Parallel => {
+            let handles: Vec<_> = devices
+                .into_iter()
+                .map(|device| {
+                    let name = device.name.clone();
+                    let fd_tid = device.fd_tid;
+                    let packages = packages.clone();
+                    let start_time = Instant::now();
+                    let handle = std::thread::spawn(move || {
+                        update_device(device, packages, opts.device_timeout)
+                    });
+                    (name, fd_tid, start_time, handle)
+                })
+                .collect();
+            // Every handle is joined, so that a failed device does not hide the others
+            for (name, fd_tid, start_time, handle) in handles {
+                let device_report = handle.join().unwrap_or_else(|_| {
+                    error!("{}: update thread panicked", name);
+                    DeviceReport::failed(name, fd_tid, start_time.elapsed())
+                });
+                report.devices.push(device_report);
+            }
+        }
+    }
+
+    info!(
+        "Update campaign done: {} updated, {} up to date, {} failed out of {} devices",
+        report.count(UpdateResult::Updated),
+        report.count(UpdateResult::UpToDate),
+        report.devices.len()
+            - report.count(UpdateResult::Updated)
+            - report.count(UpdateResult::UpToDate),
+        report.devices.len()
+    );
+    Ok(report)
+}
+
+fn update_device<
+    S: PldmSocket + Send + 'static,
+    D: discovery_sm::StateMachineActions + Send + 'static,
+>(
+    device: CampaignDevice<S, D>,
+    packages: Arc<Vec<FirmwareManifest>>,
+    timeout: Duration,
+) -> DeviceReport {
+    info!("{}: starting update", device.name);
+    let progress = Arc::new(Mutex::new(DeviceProgress::default()));
+    let start_time = Instant::now();
+    let daemon = PldmDaemon::run(
+        device.socket,
+        Options {
+            discovery_sm_actions: device.discovery_sm_actions,
+            fd_tid: device.fd_tid,
+            update_sm_actions: CampaignActions {
+                packages: packages.clone(),
+                progress: progress.clone(),
+                default: update_sm::DefaultActions,
+            },
+            // Replaced by the matching package once the device identifiers are known
+            pldm_fw_pkg: Some(packages[0].clone()),
+        },
+    );
+    let Ok(mut daemon) = daemon else {
+        error!("{}: failed to start the PLDM daemon", device.name);
+        return DeviceReport::failed(device.name, device.fd_tid, start_time.elapsed());
+    };
+
+    let result = loop {
+        if let Some(result) = progress.lock().unwrap().result {
+            break result;
+        }
+        if start_time.elapsed() >= timeout {
+            error!("{}: update timed out", device.name);
+            daemon.abort_update();
+            break UpdateResult::TimedOut;
+        }
+        std::thread::sleep(PROGRESS_POLL_INTERVAL);
+    };
+
+    let final_state = daemon.get_update_sm_state();
+    let device_id = daemon.get_device_id();
+    daemon.stop();
+    info!(
+        "{}: update ended with {:?} in state {:?}",
+        device.name, result, final_state
+    );
+
+    let package_index = progress.lock().unwrap().package_index;
+    DeviceReport {
+        name: device.name,
+        fd_tid: device.fd_tid,
+        result,
+        package_index,
+        device_id,
+        final_state,
+        duration: start_time.elapsed(),
+    }
+}
+
+// Progress of a device update, shared between its update state machine and the campaign
+#[derive(Default)]
+struct DeviceProgress {
+    package_index: Option<usize>,
+    activation_requested: bool,
+    result: Option<UpdateResult>,
+}
+
+impl DeviceProgress {
+    // Records the outcome of the update, unless it is already known
+    fn finish(&mut self, result: UpdateResult) {
+        self.result.get_or_insert(result);
+    }
+}
+
+// Update state machine actions selecting the package of the device and recording the
+// outcome of the update. Everything else is left to the default actions.
+struct CampaignActions {
+    packages: Arc<Vec<FirmwareManifest>>,
+    progress: Arc<Mutex<DeviceProgress>>,
+    default: update_sm::DefaultActions,
+}
+
+impl StateMachineActions for CampaignActions {
+    fn on_query_device_identifiers_response(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+        response: pldm_packet::query_devid::QueryDeviceIdentifiersResponse,
+    ) -> Result<(), ()> {
+        let package_index = self.packages.iter().position(|pkg| {
+            pkg.firmware_device_id_records
+                .iter()
+                .any(|pkg_dev_id| update_sm::is_pkg_device_id_in_response(pkg_dev_id, &response))
+        });
+        match package_index {
+            Some(index) => {
+                info!("Device matches package {}", index);
+                ctx.pldm_fw_pkg = self.packages[index].clone();
+                self.progress.lock().unwrap().package_index = Some(index);
+            }
+            None => self
+                .progress
+                .lock()
+                .unwrap()
+                .finish(UpdateResult::NoMatchingPackage),
+        }
+        self.default
+            .on_query_device_identifiers_response(ctx, response)
+    }
+
+    fn on_get_firmware_parameters_response(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+        response: pldm_packet::get_fw_params::GetFirmwareParametersResponse,
+    ) -> Result<(), ()> {
+        let result = self
+            .default
+            .on_get_firmware_parameters_response(ctx, response);
+        if ctx.components.is_empty() {
+            self.progress.lock().unwrap().finish(UpdateResult::UpToDate);
+        }
+        result
+    }
+
+    fn on_activate_firmware(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.progress.lock().unwrap().activation_requested = true;
+        self.default.on_activate_firmware(ctx)
+    }
+
+    fn on_transfer_fail(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.progress.lock().unwrap().finish(UpdateResult::Failed);
+        self.default.on_transfer_fail(ctx)
+    }
+
+    fn on_verify_fail(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.progress.lock().unwrap().finish(UpdateResult::Failed);
+        self.default.on_verify_fail(ctx)
+    }
+
+    fn on_apply_fail(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.progress.lock().unwrap().finish(UpdateResult::Failed);
+        self.default.on_apply_fail(ctx)
+    }
+
+    fn on_stop_update(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        {
+            let mut progress = self.progress.lock().unwrap();
+            let result = if progress.activation_requested {
+                UpdateResult::Updated
+            } else {
+                UpdateResult::Failed
+            };
+            progress.finish(result);
+        }
+        self.default.on_stop_update(ctx)
+    }
+
+    fn on_stop_update_error(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.progress.lock().unwrap().finish(UpdateResult::Failed);
+        self.default.on_stop_update_error(ctx)
+    }
+
+    fn on_abort_update(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        self.progress
+            .lock()
+            .unwrap()
+            .finish(UpdateResult::Cancelled);
+        self.default.on_abort_update(ctx)
+    }
+}
@@ -119,6 +119,14 @@ impl<
             .unwrap();
     }
 
+    /// Aborts the firmware update.
+    /// The device is asked to cancel the update and the update state machine moves to `Done`.
+    pub fn abort_update(&self) {
+        if let Some(event_queue) = &self.event_queue_tx {
+            let _ = event_queue.send(PldmEvents::Update(update_sm::Events::AbortUpdate));
+        }
+    }
+
     /// This thread receives PLDM packets and enqueues the corresponding events for processing.
     fn rx_loop(socket: S, event_queue_tx: Sender<PldmEvents>) -> Result<(), ()> {
         loop {
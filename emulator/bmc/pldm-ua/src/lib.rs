@@ -1,6 +1,7 @@
 // Licensed under the Apache-2.0 license
 
 #![allow(clippy::result_unit_err)]
+pub mod campaign;
 pub mod daemon;
 pub mod discovery_sm;
 pub mod events;
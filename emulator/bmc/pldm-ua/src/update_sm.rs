@@ -7,12 +7,15 @@ use log::{debug, error, info};
 use pldm_common::codec::PldmCodec;
 use pldm_common::message::firmware_update as pldm_packet;
 use pldm_common::message::firmware_update::activate_fw::SelfContainedActivationRequest;
-use pldm_common::message::firmware_update::request_update::REQUEST_UPDATE_REQUEST_FIXED_HEADER_LEN;
+use pldm_common::message::firmware_update::get_device_metadata::GetDeviceMetaDataResponseFixed;
+use pldm_common::message::firmware_update::request_update::{
+    FdWillSendGetPackageData, REQUEST_UPDATE_REQUEST_FIXED_HEADER_LEN,
+};
 use pldm_common::message::firmware_update::transfer_complete::TransferResult;
 use pldm_common::message::firmware_update::verify_complete::VerifyResult;
 use pldm_common::protocol::base::{
     InstanceId, PldmBaseCompletionCode, PldmMsgHeader, PldmMsgType, PldmSupportedType,
-    TransferRespFlag,
+    TransferOperationFlag, TransferRespFlag,
 };
 use pldm_common::protocol::firmware_update::{
     ComponentClassification, ComponentCompatibilityResponse, ComponentParameterEntry,
@@ -49,11 +52,16 @@ statemachine! {
         GetFirmwareParametersSent + GetFirmwareParametersResponse(pldm_packet::get_fw_params::GetFirmwareParametersResponse)  / on_get_firmware_parameters_response = ReceivedFirmwareParameters,
         ReceivedFirmwareParameters + SendRequestUpdate / on_send_request_update = RequestUpdateSent,
         RequestUpdateSent + RequestUpdateResponse(pldm_packet::request_update::RequestUpdateResponse) / on_request_update_response = LearnComponents,
+        LearnComponents + GetPackageData(pldm_packet::get_package_data::GetPackageDataRequest) / on_get_package_data = LearnComponents,
+        LearnComponents + SendGetDeviceMetaData / on_send_get_device_metadata = LearnComponents,
+        LearnComponents + GetDeviceMetaDataResponse(DeviceMetaDataPortion) / on_get_device_metadata_response = LearnComponents,
+        LearnComponents + GetMetaData(pldm_packet::get_metadata::GetMetaDataRequest) / on_get_metadata = LearnComponents,
         LearnComponents + SendPassComponentRequest [!are_all_components_passed] / on_send_pass_component_request = LearnComponents,
         LearnComponents + SendPassComponentRequest [are_all_components_passed]  / on_all_components_passed = ReadyXfer,
         LearnComponents + PassComponentResponse(pldm_packet::pass_component::PassComponentTableResponse) / on_pass_component_response = LearnComponents,
         LearnComponents + CancelUpdateOrTimeout  / on_stop_update = Idle,
 
+        ReadyXfer + GetMetaData(pldm_packet::get_metadata::GetMetaDataRequest) / on_get_metadata = ReadyXfer,
         ReadyXfer + SendUpdateComponent / on_send_update_component = ReadyXfer,
         ReadyXfer + UpdateComponentResponse(pldm_packet::update_component::UpdateComponentResponse) / on_update_component_response = ReadyXfer,
         ReadyXfer + StartDownload / on_start_download = Download,
@@ -84,6 +92,7 @@ statemachine! {
         Activate + CancelUpdate  / on_stop_update = Idle,
 
         _ + CancelUpdateComponentResponse(pldm_packet::request_cancel::CancelUpdateComponentResponse) / on_cancel_update_component_response = Idle,
+        _ + AbortUpdate / on_abort_update = Done,
         _ + StopUpdateOnError / on_stop_update_error = Done,
         _ + StopUpdate / on_stop_update = Done
     }
@@ -125,6 +134,48 @@ fn send_message_helper<P: PldmCodec>(
     Ok(())
 }
 
+// Sends a response to a request initiated by the device. Unlike requests, responses are not
+// retried, so the response timer of the last request is left untouched.
+fn send_response_helper<P: PldmCodec>(
+    ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    message: &P,
+) -> Result<(), ()> {
+    let mut buffer = [0u8; MAX_PLDM_PAYLOAD_SIZE];
+    let sz = message.encode(&mut buffer).map_err(|_| ())?;
+    ctx.socket.send(&buffer[..sz]).map_err(|_| ())?;
+    debug!("Sent message: {:?}", std::any::type_name::<P>());
+    Ok(())
+}
+
+// Returns the range and transfer flag of the portion of `data_len` bytes requested by a
+// GetPackageData or GetMetaData request. The data transfer handle is the offset of the portion.
+fn get_data_portion(
+    data_len: usize,
+    data_transfer_handle: u32,
+    transfer_operation_flag: u8,
+    max_portion_len: usize,
+) -> Result<(usize, usize, TransferRespFlag), FwUpdateCompletionCode> {
+    let offset = match TransferOperationFlag::try_from(transfer_operation_flag) {
+        Ok(TransferOperationFlag::GetFirstPart) => 0,
+        Ok(TransferOperationFlag::GetNextPart) => {
+            let offset = data_transfer_handle as usize;
+            if offset == 0 || offset >= data_len {
+                return Err(FwUpdateCompletionCode::InvalidTransferHandle);
+            }
+            offset
+        }
+        Err(_) => return Err(FwUpdateCompletionCode::InvalidTransferOperationFlag),
+    };
+    let end = min(offset + max_portion_len, data_len);
+    let transfer_flag = match (offset == 0, end == data_len) {
+        (true, true) => TransferRespFlag::StartAndEnd,
+        (true, false) => TransferRespFlag::Start,
+        (false, false) => TransferRespFlag::Middle,
+        (false, true) => TransferRespFlag::End,
+    };
+    Ok((offset, end, transfer_flag))
+}
+
 fn is_pkg_descriptor_in_response_descriptor(
     pkg_descriptor: &pldm_fw_pkg::manifest::Descriptor,
     response_descriptor: &pldm_common::protocol::firmware_update::Descriptor,
@@ -143,12 +194,12 @@ fn is_pkg_descriptor_in_response_descriptor(
     true
 }
 
-fn is_pkg_device_id_in_response(
+pub(crate) fn is_pkg_device_id_in_response(
     pkg_dev_id: &FirmwareDeviceIdRecord,
     response: &pldm_packet::query_devid::QueryDeviceIdentifiersResponse,
 ) -> bool {
     if response.descriptor_count < 1 {
-        error!("No descriptors in response");
+        debug!("No descriptors in response");
         return false;
     }
 
@@ -157,14 +208,14 @@ fn is_pkg_device_id_in_response(
         &pkg_dev_id.initial_descriptor,
         &response.initial_descriptor,
     ) {
-        error!("Initial descriptor does not match");
+        debug!("Initial descriptor does not match");
         return false;
     }
 
     // Check additional descriptors
     if let Some(additional_descriptors) = &pkg_dev_id.additional_descriptors {
         if response.descriptor_count < additional_descriptors.len() as u8 + 1 {
-            error!("Not enough descriptors in response");
+            debug!("Not enough descriptors in response");
             return false;
         }
 
@@ -183,7 +234,7 @@ fn is_pkg_device_id_in_response(
             }
 
             if !additional_descriptor_in_response {
-                error!("Additional descriptor not found in response");
+                debug!("Additional descriptor not found in response");
                 return false;
             }
         }
@@ -224,10 +275,24 @@ pub trait StateMachineActions {
         ctx.instance_id = ctx.instance_id.wrapping_add(1); // Response received, increment instance id
         if response.fixed.completion_code == PldmBaseCompletionCode::Success as u8 {
             debug!("RequestUpdate response success");
-            ctx.event_queue
-                .send(PldmEvents::Update(Events::SendPassComponentRequest))
-                .map_err(|_| ())?;
-            Ok(())
+            ctx.update_mode = true;
+            ctx.fd_meta_data_len = response.fixed.fd_meta_data_len;
+            ctx.fd_will_send_pkg_data = !matches!(
+                FdWillSendGetPackageData::try_from(response.fixed.fd_will_send_pkg_data_cmd),
+                Ok(FdWillSendGetPackageData::No) | Err(_)
+            );
+            ctx.pkg_data_max_transfer_size = response
+                .get_pkg_data_max_transfer_size
+                .map_or(MAX_TRANSFER_SIZE, |size| {
+                    size.clamp(BASELINE_TRANSFER_SIZE, MAX_TRANSFER_SIZE)
+                });
+            if ctx.fd_will_send_pkg_data {
+                // Wait for the device to retrieve the package data
+                debug!("Waiting for GetPackageData from the device");
+                ctx.response_timer.cancel();
+                return Ok(());
+            }
+            self.on_learn_components(ctx)
         } else {
             error!("RequestUpdate response failed");
             ctx.event_queue
@@ -237,6 +302,190 @@ pub trait StateMachineActions {
         }
     }
 
+    /// Starts learning the components, after the device metadata is saved if the device has any.
+    fn on_learn_components(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        let event = if ctx.fd_meta_data_len > 0 {
+            ctx.pending_device_metadata.clear();
+            Events::SendGetDeviceMetaData
+        } else {
+            Events::SendPassComponentRequest
+        };
+        ctx.event_queue
+            .send(PldmEvents::Update(event))
+            .map_err(|_| ())
+    }
+
+    fn on_get_package_data(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+        request: pldm_packet::get_package_data::GetPackageDataRequest,
+    ) -> Result<(), ()> {
+        let pkg_data = ctx
+            .device_id
+            .as_ref()
+            .and_then(|device_id| device_id.firmware_device_package_data.clone())
+            .unwrap_or_default();
+        if pkg_data.is_empty() {
+            error!("GetPackageData received, but the package has no package data");
+            let response = pldm_packet::get_package_data::GetPackageDataResponse::new(
+                request.hdr.instance_id(),
+                FwUpdateCompletionCode::NoPackageData as u8,
+                0,
+                TransferRespFlag::StartAndEnd,
+                &[],
+            );
+            return send_response_helper(ctx, &response);
+        }
+
+        let (offset, end, transfer_flag) = match get_data_portion(
+            pkg_data.len(),
+            request.data_transfer_handle,
+            request.transfer_operation_flag,
+            ctx.pkg_data_max_transfer_size as usize,
+        ) {
+            Ok(portion) => portion,
+            Err(completion_code) => {
+                error!("GetPackageData request is invalid");
+                let response = pldm_packet::get_package_data::GetPackageDataResponse::new(
+                    request.hdr.instance_id(),
+                    completion_code as u8,
+                    0,
+                    TransferRespFlag::StartAndEnd,
+                    &[],
+                );
+                return send_response_helper(ctx, &response);
+            }
+        };
+        let is_last = end == pkg_data.len();
+        let response = pldm_packet::get_package_data::GetPackageDataResponse::new(
+            request.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            if is_last { 0 } else { end as u32 },
+            transfer_flag,
+            &pkg_data[offset..end],
+        );
+        send_response_helper(ctx, &response)?;
+
+        // The device may retry the last request, only move on once
+        if is_last && ctx.fd_will_send_pkg_data {
+            debug!("Package data sent");
+            ctx.fd_will_send_pkg_data = false;
+            self.on_learn_components(ctx)?;
+        }
+        Ok(())
+    }
+
+    fn on_send_get_device_metadata(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        let (data_transfer_handle, transfer_operation_flag) =
+            if ctx.pending_device_metadata.is_empty() {
+                (0, TransferOperationFlag::GetFirstPart)
+            } else {
+                (
+                    ctx.device_metadata_transfer_handle,
+                    TransferOperationFlag::GetNextPart,
+                )
+            };
+        send_message_helper(
+            ctx,
+            &pldm_packet::get_device_metadata::GetDeviceMetaDataRequest::new(
+                ctx.instance_id,
+                PldmMsgType::Request,
+                data_transfer_handle,
+                transfer_operation_flag,
+            ),
+        )
+    }
+
+    fn on_get_device_metadata_response(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+        response: DeviceMetaDataPortion,
+    ) -> Result<(), ()> {
+        ctx.instance_id = ctx.instance_id.wrapping_add(1); // Response received, increment instance id
+        if response.fixed.completion_code != PldmBaseCompletionCode::Success as u8 {
+            error!("GetDeviceMetaData response failed");
+            ctx.event_queue
+                .send(PldmEvents::Update(Events::StopUpdateOnError))
+                .map_err(|_| ())?;
+            return Err(());
+        }
+
+        ctx.pending_device_metadata
+            .extend_from_slice(&response.data);
+        match TransferRespFlag::try_from(response.fixed.transfer_flag) {
+            Ok(TransferRespFlag::End) | Ok(TransferRespFlag::StartAndEnd) => {
+                // Keep the metadata until the device asks for it with GetMetaData, which may
+                // happen in a later update after the device is reset.
+                debug!(
+                    "Device metadata saved, {} bytes",
+                    ctx.pending_device_metadata.len()
+                );
+                ctx.device_metadata = Some(std::mem::take(&mut ctx.pending_device_metadata));
+                ctx.event_queue
+                    .send(PldmEvents::Update(Events::SendPassComponentRequest))
+                    .map_err(|_| ())
+            }
+            Ok(TransferRespFlag::Start) | Ok(TransferRespFlag::Middle) => {
+                ctx.device_metadata_transfer_handle = response.fixed.next_data_transfer_handle;
+                ctx.event_queue
+                    .send(PldmEvents::Update(Events::SendGetDeviceMetaData))
+                    .map_err(|_| ())
+            }
+            Err(_) => {
+                error!("GetDeviceMetaData response has an invalid transfer flag");
+                ctx.event_queue
+                    .send(PldmEvents::Update(Events::StopUpdateOnError))
+                    .map_err(|_| ())?;
+                Err(())
+            }
+        }
+    }
+
+    fn on_get_metadata(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+        request: pldm_packet::get_metadata::GetMetaDataRequest,
+    ) -> Result<(), ()> {
+        let metadata = ctx.device_metadata.clone().unwrap_or_default();
+        let portion = if metadata.is_empty() {
+            error!("GetMetaData received, but no device metadata was saved");
+            Err(FwUpdateCompletionCode::NoDeviceMetadata)
+        } else {
+            get_data_portion(
+                metadata.len(),
+                request.data_transfer_handle,
+                request.transfer_operation_flag,
+                MAX_TRANSFER_SIZE as usize,
+            )
+        };
+
+        let response = match portion {
+            Ok((offset, end, transfer_flag)) => {
+                pldm_packet::get_metadata::GetMetaDataResponse::new(
+                    request.hdr.instance_id(),
+                    PldmBaseCompletionCode::Success as u8,
+                    if end == metadata.len() { 0 } else { end as u32 },
+                    transfer_flag,
+                    &metadata[offset..end],
+                )
+            }
+            Err(completion_code) => pldm_packet::get_metadata::GetMetaDataResponse::new(
+                request.hdr.instance_id(),
+                completion_code as u8,
+                0,
+                TransferRespFlag::StartAndEnd,
+                &[],
+            ),
+        };
+        send_response_helper(ctx, &response)
+    }
+
     fn on_send_pass_component_request(
         &mut self,
         ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
@@ -444,6 +693,10 @@ pub trait StateMachineActions {
         ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
     ) -> Result<(), ()> {
         if let Some(dev_id_record) = ctx.device_id.as_ref() {
+            let pkg_data_len = dev_id_record
+                .firmware_device_package_data
+                .as_ref()
+                .map_or(0, |data| data.len() as u16);
             let version_string: PldmFirmwareString =
                 match dev_id_record.component_image_set_version_string {
                     Some(ref version_string) => PldmFirmwareString {
@@ -469,7 +722,7 @@ pub trait StateMachineActions {
                     MAX_TRANSFER_SIZE,
                     ctx.components.len() as u16,
                     MAX_OUTSTANDING_TRANSFER_REQ,
-                    0, // pkg_data_len is optional, not supported
+                    pkg_data_len,
                     &version_string,
                 ),
             )
@@ -1029,6 +1282,8 @@ pub trait StateMachineActions {
         ctx.instance_id = ctx.instance_id.wrapping_add(1); // Response received, increment instance id
         if response.completion_code == PldmBaseCompletionCode::Success as u8 {
             info!("ActivateFirmware response success");
+            // The device leaves update mode once the components are activated
+            ctx.update_mode = false;
 
             if response.estimated_time_activation > 0 {
                 // Record the expected activation time
@@ -1074,6 +1329,28 @@ pub trait StateMachineActions {
         ctx.response_timer.cancel();
         Ok(())
     }
+    fn on_abort_update(
+        &mut self,
+        ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
+    ) -> Result<(), ()> {
+        info!("Aborting update");
+        ctx.response_timer.cancel();
+        ctx.timer.cancel();
+        if !ctx.update_mode {
+            // The device is not in update mode, there is nothing to cancel
+            return Ok(());
+        }
+        // Take the device out of update mode. The request is not retried, as the update agent
+        // is going away.
+        ctx.update_mode = false;
+        let request = pldm_packet::request_cancel::CancelUpdateRequest::new(
+            ctx.instance_id,
+            PldmMsgType::Request,
+        );
+        let mut buffer = [0u8; MAX_PLDM_PAYLOAD_SIZE];
+        let sz = request.encode(&mut buffer).map_err(|_| ())?;
+        ctx.socket.send(&buffer[..sz]).map_err(|_| ())
+    }
     fn on_cancel_update_component_response(
         &mut self,
         ctx: &mut InnerContext<impl PldmSocket + Send + 'static>,
@@ -1162,6 +1439,28 @@ pub fn process_packet(packet: &RxPacket) -> Result<PldmEvents, ()> {
             FwUpdateCmd::CancelUpdateComponent => {
                 packet_to_event(&header, packet, true, Events::CancelUpdateComponentResponse)
             }
+            FwUpdateCmd::GetPackageData => {
+                packet_to_event(&header, packet, false, Events::GetPackageData)
+            }
+            FwUpdateCmd::GetDeviceMetaData => {
+                if !(header.rq() == 0 && header.datagram() == 0) {
+                    error!("Not a response");
+                    return Err(());
+                }
+                // The response carries a variable-length portion of metadata after the fixed part
+                let data = &packet.payload.data[..packet.payload.len];
+                let fixed = GetDeviceMetaDataResponseFixed::decode(data).map_err(|_| ())?;
+                Ok(PldmEvents::Update(Events::GetDeviceMetaDataResponse(
+                    DeviceMetaDataPortion {
+                        fixed,
+                        data: data[core::mem::size_of::<GetDeviceMetaDataResponseFixed>()..]
+                            .to_vec(),
+                    },
+                )))
+            }
+            FwUpdateCmd::GetMetaData => {
+                packet_to_event(&header, packet, false, Events::GetMetaData)
+            }
             _ => {
                 debug!("Unknown firmware update command");
                 Err(())
@@ -1171,6 +1470,13 @@ pub fn process_packet(packet: &RxPacket) -> Result<PldmEvents, ()> {
     }
 }
 
+/// A portion of the device metadata returned by GetDeviceMetaData.
+#[derive(Debug, Clone)]
+pub struct DeviceMetaDataPortion {
+    pub fixed: GetDeviceMetaDataResponseFixed,
+    pub data: Vec<u8>,
+}
+
 // Implement the context struct
 pub struct DefaultActions;
 impl StateMachineActions for DefaultActions {}
@@ -1209,6 +1515,22 @@ pub struct InnerContext<S: PldmSocket> {
     response_timer: Timer,
     retry_count: Arc<Mutex<u8>>,
     is_initiator: bool,
+    // Whether the device is in update mode, from a successful RequestUpdate until the
+    // components are activated or the update is cancelled
+    update_mode: bool,
+
+    // Whether the device will retrieve the package data with GetPackageData
+    fd_will_send_pkg_data: bool,
+    // Maximum package data in a GetPackageData response
+    pkg_data_max_transfer_size: u32,
+    // Length of the device metadata reported in the RequestUpdate response
+    fd_meta_data_len: u16,
+    // Device metadata received so far with GetDeviceMetaData
+    pending_device_metadata: Vec<u8>,
+    device_metadata_transfer_handle: u32,
+    // Device metadata returned to the device with GetMetaData. It is kept across updates, as
+    // the device asks for it after it is reset to activate the new firmware.
+    pub device_metadata: Option<Vec<u8>>,
 }
 
 pub struct Context<T: StateMachineActions, S: PldmSocket> {
@@ -1241,6 +1563,13 @@ impl<T: StateMachineActions, S: PldmSocket> Context<T, S> {
                 response_timer: Timer::new(),
                 retry_count: Arc::new(Mutex::new(0)),
                 is_initiator: true,
+                update_mode: false,
+                fd_will_send_pkg_data: false,
+                pkg_data_max_transfer_size: MAX_TRANSFER_SIZE,
+                fd_meta_data_len: 0,
+                pending_device_metadata: Vec::new(),
+                device_metadata_transfer_handle: 0,
+                device_metadata: None,
             },
         }
     }
@@ -1279,6 +1608,10 @@ impl<T: StateMachineActions, S: PldmSocket + Send + 'static> StateMachineContext
         on_send_request_update() -> Result<(),()>,
         on_get_firmware_parameters_response(response : pldm_packet::get_fw_params::GetFirmwareParametersResponse) -> Result<(), ()>,
         on_request_update_response(response: pldm_packet::request_update::RequestUpdateResponse) -> Result<(),()>,
+        on_get_package_data(request: pldm_packet::get_package_data::GetPackageDataRequest) -> Result<(),()>,
+        on_send_get_device_metadata() -> Result<(),()>,
+        on_get_device_metadata_response(response: DeviceMetaDataPortion) -> Result<(),()>,
+        on_get_metadata(request: pldm_packet::get_metadata::GetMetaDataRequest) -> Result<(),()>,
         on_send_pass_component_request() -> Result<(),()>,
         on_all_components_passed() -> Result<(),()>,
         on_send_update_component() -> Result<(),()>,
@@ -1295,6 +1628,7 @@ impl<T: StateMachineActions, S: PldmSocket + Send + 'static> StateMachineContext
         on_get_status_response(response: pldm_packet::get_status::GetStatusResponse) -> Result<(),()>,
         on_stop_update() -> Result<(),()>,
         on_stop_update_error() -> Result<(),()>,
+        on_abort_update() -> Result<(),()>,
         on_cancel_update_component_response(response: pldm_packet::request_cancel::CancelUpdateComponentResponse) -> Result<(),()>,
         on_verify_success() -> Result<(),()>,
         on_verify_fail() -> Result<(),()>,
@@ -209,6 +209,7 @@ pub struct TestSetup<
     D: discovery_sm::StateMachineActions + Send + 'static,
     U: update_sm::StateMachineActions + Send + 'static,
 > {
+    pub transport: MockTransport,
     pub fd_sock: MockPldmSocket,
     pub daemon: PldmDaemon<MockPldmSocket, D, U>,
 }
@@ -240,7 +241,11 @@ pub fn setup<
     // Run the PLDM daemon
     let daemon = PldmDaemon::run(ua_sock.clone(), daemon_options).unwrap();
 
-    TestSetup { fd_sock, daemon }
+    TestSetup {
+        transport,
+        fd_sock,
+        daemon,
+    }
 }
 
 impl<
@@ -271,6 +276,24 @@ impl<
         socket.send(&buffer[..sz]).unwrap();
     }
 
+    /// Returns the command codes of the messages received by the device that were not read yet.
+    pub fn drain_received_commands(&self) -> Vec<u8> {
+        // An empty packet marks the end of the messages
+        let marker_sock = self
+            .transport
+            .create_socket(EndpointId(0xff), EndpointId(0x02))
+            .unwrap();
+        marker_sock.send(&[]).unwrap();
+
+        let mut cmd_codes = Vec::new();
+        while let Ok(packet) = self.fd_sock.receive(None) {
+            if let Ok(header) = PldmMsgHeader::decode(&packet.payload.data[..packet.payload.len]) {
+                cmd_codes.push(header.cmd_code());
+            }
+        }
+        cmd_codes
+    }
+
     pub fn receive_request<P: PldmCodec>(
         &self,
         socket: &MockPldmSocket,
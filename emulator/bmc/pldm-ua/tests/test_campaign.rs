@@ -0,0 +1,367 @@
+// Licensed under the Apache-2.0 license
+
+#[cfg(test)]
+mod common;
+
+use std::thread;
+use std::time::Duration;
+
+use common::{CustomDiscoverySm, MockPldmSocket, MockTransport};
+use log::LevelFilter;
+use pldm_common::codec::PldmCodec;
+use pldm_common::message::firmware_update::get_fw_params::{
+    FirmwareParameters, GetFirmwareParametersRequest, GetFirmwareParametersResponse,
+};
+use pldm_common::message::firmware_update::query_devid::{
+    QueryDeviceIdentifiersRequest, QueryDeviceIdentifiersResponse,
+};
+use pldm_common::message::firmware_update::request_update::{
+    FdWillSendGetPackageData, RequestUpdateRequest, RequestUpdateResponse,
+};
+use pldm_common::protocol::base::{PldmBaseCompletionCode, PldmMsgHeader};
+use pldm_common::protocol::firmware_update::{
+    ComponentActivationMethods, ComponentClassification, ComponentParameterEntry,
+    ComponentParameterEntryFixed, FirmwareDeviceCapability, FwUpdateCmd, FwUpdateCompletionCode,
+    PldmFirmwareString, VersionStringType, PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN,
+};
+use pldm_fw_pkg::manifest::{
+    ComponentImageInformation, Descriptor, DescriptorType, FirmwareDeviceIdRecord,
+};
+use pldm_fw_pkg::FirmwareManifest;
+use pldm_ua::campaign::{
+    self, CampaignDevice, CampaignOptions, CampaignReport, UpdatePolicy, UpdateResult,
+};
+use pldm_ua::transport::{EndpointId, PldmSocket, PldmTransport, PldmTransportError, RxPacket};
+use pldm_ua::update_sm;
+use simple_logger::SimpleLogger;
+
+const UUID_A: [u8; 16] = [
+    0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0,
+];
+const UUID_B: [u8; 16] = [
+    0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xFF,
+];
+const UUID_UNKNOWN: [u8; 16] = [
+    0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0x00,
+];
+
+const FW_COMP_IDENTIFIER: u16 = 0x0001;
+const ACTIVE_COMP_STAMP: u32 = 0x00010100;
+const ACTIVE_VER_STR: &str = "1.1.0";
+const PKG_B_VER_STR: &str = "2.0.0";
+
+fn get_pldm_fw_pkg(uuid: &[u8], ver_str: &str, comp_stamp: u32) -> FirmwareManifest {
+    FirmwareManifest {
+        firmware_device_id_records: vec![FirmwareDeviceIdRecord {
+            initial_descriptor: Descriptor {
+                descriptor_type: DescriptorType::Uuid,
+                descriptor_data: uuid.to_vec(),
+            },
+            component_image_set_version_string_type: pldm_fw_pkg::manifest::StringType::Utf8,
+            component_image_set_version_string: Some(ver_str.to_string()),
+            applicable_components: Some(vec![0]),
+            ..Default::default()
+        }],
+        component_image_information: vec![ComponentImageInformation {
+            classification: ComponentClassification::Firmware as u16,
+            identifier: FW_COMP_IDENTIFIER,
+            comparison_stamp: Some(comp_stamp),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+fn get_component_fw_params() -> ComponentParameterEntry {
+    ComponentParameterEntry {
+        comp_param_entry_fixed: ComponentParameterEntryFixed {
+            comp_classification: ComponentClassification::Firmware as u16,
+            comp_identifier: FW_COMP_IDENTIFIER,
+            comp_classification_index: 0u8,
+            active_comp_comparison_stamp: ACTIVE_COMP_STAMP,
+            active_comp_ver_str_type: VersionStringType::Utf8 as u8,
+            active_comp_ver_str_len: ACTIVE_VER_STR.len() as u8,
+            active_comp_release_date: *b"20250210",
+            pending_comp_comparison_stamp: 0u32,
+            pending_comp_ver_str_type: VersionStringType::Unspecified as u8,
+            pending_comp_ver_str_len: 0,
+            pending_comp_release_date: [0u8; 8],
+            comp_activation_methods: ComponentActivationMethods(0),
+            capabilities_during_update: FirmwareDeviceCapability(0),
+        },
+        active_comp_ver_str: {
+            let mut active_comp_ver_str = [0u8; PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN];
+            active_comp_ver_str[..ACTIVE_VER_STR.len()].copy_from_slice(ACTIVE_VER_STR.as_bytes());
+            active_comp_ver_str
+        },
+        pending_comp_ver_str: None,
+    }
+}
+
+fn send_message<P: PldmCodec>(socket: &MockPldmSocket, message: &P) {
+    let mut buffer = [0u8; 512];
+    let sz = message.encode(&mut buffer).unwrap();
+    socket.send(&buffer[..sz]).unwrap();
+}
+
+fn receive_request<P: PldmCodec>(socket: &MockPldmSocket, cmd_code: u8) -> P {
+    let request = socket.receive(None).unwrap();
+    let data = &request.payload.data[..request.payload.len];
+    let header = PldmMsgHeader::decode(data).unwrap();
+    assert_eq!(header.cmd_code(), cmd_code);
+    P::decode(data).unwrap()
+}
+
+// Answers QueryDeviceIdentifiers with the UUID of the device
+fn fd_query_device_identifiers(socket: &MockPldmSocket, uuid: &[u8; 16]) {
+    let request: QueryDeviceIdentifiersRequest =
+        receive_request(socket, FwUpdateCmd::QueryDeviceIdentifiers as u8);
+    let mut descriptor_data = [0u8; 64];
+    descriptor_data[..uuid.len()].copy_from_slice(uuid);
+    let descriptor = pldm_common::protocol::firmware_update::Descriptor {
+        descriptor_type: DescriptorType::Uuid as u16,
+        descriptor_length: uuid.len() as u16,
+        descriptor_data,
+    };
+    let response = QueryDeviceIdentifiersResponse::new(
+        request.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        &descriptor,
+        None,
+    )
+    .unwrap();
+    send_message(socket, &response);
+}
+
+// Answers GetFirmwareParameters with the active component
+fn fd_get_firmware_parameters(socket: &MockPldmSocket) {
+    let request: GetFirmwareParametersRequest =
+        receive_request(socket, FwUpdateCmd::GetFirmwareParameters as u8);
+    let params = FirmwareParameters::new(
+        FirmwareDeviceCapability(0x0010),
+        1,
+        &PldmFirmwareString::new("UTF-8", ACTIVE_VER_STR).unwrap(),
+        &PldmFirmwareString::new("UTF-8", "").unwrap(),
+        &[get_component_fw_params()],
+    );
+    let response = GetFirmwareParametersResponse::new(
+        request.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        &params,
+    );
+    send_message(socket, &response);
+}
+
+/* Device A is up to date, device B rejects the update and the last device is unknown to the packages */
+fn run_campaign(policy: UpdatePolicy) -> CampaignReport {
+    let _ = SimpleLogger::new().with_level(LevelFilter::Debug).init();
+    let transport = MockTransport::new();
+    let uuids = [UUID_A, UUID_B, UUID_UNKNOWN];
+
+    let mut devices = Vec::new();
+    let mut fd_threads = Vec::new();
+    for (i, uuid) in uuids.into_iter().enumerate() {
+        let ua_sid = EndpointId(0x10 + i as u8);
+        let fd_sid = EndpointId(0x20 + i as u8);
+        devices.push(CampaignDevice {
+            name: format!("fd{}", i),
+            socket: transport.create_socket(ua_sid, fd_sid).unwrap(),
+            fd_tid: 0x01 + i as u8,
+            discovery_sm_actions: CustomDiscoverySm {},
+        });
+
+        let fd_sock = transport.create_socket(fd_sid, ua_sid).unwrap();
+        fd_threads.push(thread::spawn(move || {
+            fd_query_device_identifiers(&fd_sock, &uuid);
+            if uuid == UUID_UNKNOWN {
+                return;
+            }
+            fd_get_firmware_parameters(&fd_sock);
+            if uuid == UUID_A {
+                return;
+            }
+
+            // The update is requested with the image set of the package of device B
+            let request: RequestUpdateRequest =
+                receive_request(&fd_sock, FwUpdateCmd::RequestUpdate as u8);
+            let ver_str = request.get_comp_image_set_ver_str();
+            assert_eq!(
+                &ver_str.str_data[..ver_str.str_len as usize],
+                PKG_B_VER_STR.as_bytes()
+            );
+            let response = RequestUpdateResponse::new(
+                request.fixed.hdr.instance_id(),
+                FwUpdateCompletionCode::UnableToInitiateUpdate as u8,
+                0,
+                FdWillSendGetPackageData::No as u8,
+                None,
+            );
+            send_message(&fd_sock, &response);
+        }));
+    }
+
+    let report = campaign::run(
+        devices,
+        CampaignOptions {
+            packages: vec![
+                get_pldm_fw_pkg(&UUID_A, ACTIVE_VER_STR, ACTIVE_COMP_STAMP),
+                get_pldm_fw_pkg(&UUID_B, PKG_B_VER_STR, ACTIVE_COMP_STAMP + 1),
+            ],
+            policy,
+            device_timeout: Duration::from_secs(10),
+        },
+    )
+    .unwrap();
+
+    for fd_thread in fd_threads {
+        fd_thread.join().unwrap();
+    }
+    report
+}
+
+fn check_report(report: &CampaignReport) {
+    assert_eq!(report.devices.len(), 3);
+    assert!(!report.is_success());
+
+    let device_a = &report.devices[0];
+    assert_eq!(device_a.name, "fd0");
+    assert_eq!(device_a.result, UpdateResult::UpToDate);
+    assert_eq!(device_a.package_index, Some(0));
+    assert_eq!(
+        device_a
+            .device_id
+            .as_ref()
+            .unwrap()
+            .initial_descriptor
+            .descriptor_data,
+        UUID_A.to_vec()
+    );
+    assert_eq!(device_a.final_state, update_sm::States::Done);
+
+    let device_b = &report.devices[1];
+    assert_eq!(device_b.result, UpdateResult::Failed);
+    assert_eq!(device_b.package_index, Some(1));
+    assert_eq!(
+        device_b
+            .device_id
+            .as_ref()
+            .unwrap()
+            .initial_descriptor
+            .descriptor_data,
+        UUID_B.to_vec()
+    );
+    assert_eq!(device_b.final_state, update_sm::States::Done);
+
+    let device_unknown = &report.devices[2];
+    assert_eq!(device_unknown.result, UpdateResult::NoMatchingPackage);
+    assert_eq!(device_unknown.package_index, None);
+    assert!(device_unknown.device_id.is_none());
+    assert_eq!(device_unknown.final_state, update_sm::States::Done);
+}
+
+#[test]
+fn test_parallel_campaign() {
+    let report = run_campaign(UpdatePolicy::Parallel);
+    check_report(&report);
+}
+
+#[test]
+fn test_serial_campaign() {
+    let report = run_campaign(UpdatePolicy::Serial);
+    check_report(&report);
+}
+
+#[test]
+fn test_campaign_without_package() {
+    let devices: Vec<CampaignDevice<MockPldmSocket, CustomDiscoverySm>> = Vec::new();
+    assert!(campaign::run(
+        devices,
+        CampaignOptions {
+            packages: Vec::new(),
+            policy: UpdatePolicy::Serial,
+            device_timeout: Duration::from_secs(1),
+        },
+    )
+    .is_err());
+}
+
+// Socket of a device whose update thread panics when the PLDM daemon clones the socket
+struct FaultySocket(Option<MockPldmSocket>);
+
+impl FaultySocket {
+    fn inner(&self) -> &MockPldmSocket {
+        self.0.as_ref().expect("faulty socket")
+    }
+}
+
+impl PldmSocket for FaultySocket {
+    fn send(&self, payload: &[u8]) -> Result<(), PldmTransportError> {
+        self.inner().send(payload)
+    }
+
+    fn receive(&self, timeout: Option<Duration>) -> Result<RxPacket, PldmTransportError> {
+        self.inner().receive(timeout)
+    }
+
+    fn connect(&self) -> Result<(), PldmTransportError> {
+        self.inner().connect()
+    }
+
+    fn disconnect(&self) {
+        if let Some(socket) = &self.0 {
+            socket.disconnect();
+        }
+    }
+
+    fn clone(&self) -> Self {
+        Self(Some(self.inner().clone()))
+    }
+}
+
+#[test]
+fn test_parallel_campaign_with_panicking_device() {
+    let _ = SimpleLogger::new().with_level(LevelFilter::Debug).init();
+    let transport = MockTransport::new();
+    let ua_sid = EndpointId(0x10);
+    let fd_sid = EndpointId(0x20);
+    let fd_sock = transport.create_socket(fd_sid, ua_sid).unwrap();
+    let fd_thread = thread::spawn(move || {
+        fd_query_device_identifiers(&fd_sock, &UUID_A);
+        fd_get_firmware_parameters(&fd_sock);
+    });
+
+    let devices = vec![
+        CampaignDevice {
+            name: "fd0".to_string(),
+            socket: FaultySocket(Some(transport.create_socket(ua_sid, fd_sid).unwrap())),
+            fd_tid: 0x01,
+            discovery_sm_actions: CustomDiscoverySm {},
+        },
+        CampaignDevice {
+            name: "fd1".to_string(),
+            socket: FaultySocket(None),
+            fd_tid: 0x02,
+            discovery_sm_actions: CustomDiscoverySm {},
+        },
+    ];
+    let report = campaign::run(
+        devices,
+        CampaignOptions {
+            packages: vec![get_pldm_fw_pkg(&UUID_A, ACTIVE_VER_STR, ACTIVE_COMP_STAMP)],
+            policy: UpdatePolicy::Parallel,
+            device_timeout: Duration::from_secs(10),
+        },
+    )
+    .unwrap();
+    fd_thread.join().unwrap();
+
+    // The panic of one device is reported as a failure, and the other device is still reported
+    assert_eq!(report.devices.len(), 2);
+    assert!(!report.is_success());
+    assert_eq!(report.devices[0].name, "fd0");
+    assert_eq!(report.devices[0].result, UpdateResult::UpToDate);
+    assert_eq!(report.devices[1].name, "fd1");
+    assert_eq!(report.devices[1].fd_tid, 0x02);
+    assert_eq!(report.devices[1].result, UpdateResult::Failed);
+    assert_eq!(report.devices[1].package_index, None);
+}
@@ -0,0 +1,274 @@
+// Licensed under the Apache-2.0 license
+
+#[cfg(test)]
+mod common;
+
+use common::CustomDiscoverySm;
+use pldm_common::codec::PldmCodec;
+use pldm_common::message::firmware_update::get_device_metadata::{
+    GetDeviceMetaDataRequest, GetDeviceMetaDataResponse,
+};
+use pldm_common::message::firmware_update::get_fw_params::GetFirmwareParametersResponse;
+use pldm_common::message::firmware_update::get_metadata::{
+    GetMetaDataRequest, GetMetaDataResponseFixed,
+};
+use pldm_common::message::firmware_update::get_package_data::{
+    GetPackageDataRequest, GetPackageDataResponseFixed,
+};
+use pldm_common::message::firmware_update::pass_component::PassComponentTableRequest;
+use pldm_common::message::firmware_update::query_devid::QueryDeviceIdentifiersResponse;
+use pldm_common::message::firmware_update::request_update::{
+    FdWillSendGetPackageData, RequestUpdateRequest, RequestUpdateResponse,
+};
+use pldm_common::protocol::base::{
+    PldmBaseCompletionCode, PldmMsgType, TransferOperationFlag, TransferRespFlag,
+};
+use pldm_common::protocol::firmware_update::{
+    ComponentClassification, FwUpdateCmd, FwUpdateCompletionCode,
+};
+use pldm_fw_pkg::manifest::{
+    ComponentImageInformation, Descriptor, DescriptorType, FirmwareDeviceIdRecord,
+};
+use pldm_fw_pkg::FirmwareManifest;
+use pldm_ua::daemon::Options;
+use pldm_ua::events::PldmEvents;
+use pldm_ua::transport::PldmSocket;
+use pldm_ua::update_sm;
+
+// Test UUID
+pub const TEST_UUID: [u8; 16] = [
+    0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0,
+];
+const PKG_DATA_MAX_TRANSFER_SIZE: usize = 32;
+
+/* Override the Update SM, bypass QueryDeviceIdentifiers and GetFirmwareParameters */
+struct UpdateSmBypassed {}
+impl update_sm::StateMachineActions for UpdateSmBypassed {
+    fn on_start_update(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket>,
+    ) -> Result<(), ()> {
+        ctx.device_id = Some(ctx.pldm_fw_pkg.firmware_device_id_records[0].clone());
+        ctx.components = ctx.pldm_fw_pkg.component_image_information.clone();
+        ctx.event_queue
+            .send(PldmEvents::Update(
+                update_sm::Events::QueryDeviceIdentifiersResponse(QueryDeviceIdentifiersResponse {
+                    ..Default::default()
+                }),
+            ))
+            .map_err(|_| ())?;
+        Ok(())
+    }
+    fn on_query_device_identifiers_response(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket>,
+        _response: QueryDeviceIdentifiersResponse,
+    ) -> Result<(), ()> {
+        ctx.event_queue
+            .send(PldmEvents::Update(
+                update_sm::Events::SendGetFirmwareParameters,
+            ))
+            .map_err(|_| ())?;
+        Ok(())
+    }
+    fn on_send_get_firmware_parameters(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket>,
+    ) -> Result<(), ()> {
+        ctx.event_queue
+            .send(PldmEvents::Update(
+                update_sm::Events::GetFirmwareParametersResponse(GetFirmwareParametersResponse {
+                    ..Default::default()
+                }),
+            ))
+            .map_err(|_| ())
+    }
+    fn on_get_firmware_parameters_response(
+        &mut self,
+        ctx: &mut update_sm::InnerContext<impl PldmSocket>,
+        _response: GetFirmwareParametersResponse,
+    ) -> Result<(), ()> {
+        ctx.event_queue
+            .send(PldmEvents::Update(update_sm::Events::SendRequestUpdate))
+            .map_err(|_| ())
+    }
+}
+
+fn get_pldm_fw_pkg_with_package_data(package_data: Option<Vec<u8>>) -> FirmwareManifest {
+    FirmwareManifest {
+        firmware_device_id_records: vec![FirmwareDeviceIdRecord {
+            initial_descriptor: Descriptor {
+                descriptor_type: DescriptorType::Uuid,
+                descriptor_data: TEST_UUID.to_vec(),
+            },
+            component_image_set_version_string_type: pldm_fw_pkg::manifest::StringType::Utf8,
+            component_image_set_version_string: Some("1.1.0".to_string()),
+            applicable_components: Some(vec![0]),
+            firmware_device_package_data: package_data,
+            ..Default::default()
+        }],
+        component_image_information: vec![ComponentImageInformation {
+            classification: ComponentClassification::Firmware as u16,
+            identifier: 0x0001,
+            comparison_stamp: Some(0x00010106),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_package_data_and_device_metadata() {
+    let package_data: Vec<u8> = (0..80).collect();
+    let device_metadata: Vec<u8> = (0..40).map(|i| 0xFF - i).collect();
+    let pldm_fw_pkg = get_pldm_fw_pkg_with_package_data(Some(package_data.clone()));
+
+    let mut setup = common::setup(Options {
+        pldm_fw_pkg: Some(pldm_fw_pkg),
+        discovery_sm_actions: CustomDiscoverySm {},
+        update_sm_actions: UpdateSmBypassed {},
+        fd_tid: 0x01,
+    });
+
+    // The UA advertises the package data in the RequestUpdate request
+    let request: RequestUpdateRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::RequestUpdate as u8)
+        .unwrap();
+    assert_eq!({ request.fixed.pkg_data_len }, package_data.len() as u16);
+
+    let response = RequestUpdateResponse::new(
+        request.fixed.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        device_metadata.len() as u16,
+        FdWillSendGetPackageData::YesWithMaxTransferSize as u8,
+        Some(PKG_DATA_MAX_TRANSFER_SIZE as u32),
+    );
+    setup.send_response(&setup.fd_sock, &response);
+    setup.wait_for_state_transition(update_sm::States::LearnComponents);
+
+    // Retrieve the package data from the UA
+    let mut received = Vec::new();
+    let mut handle = 0;
+    let mut op_flag = TransferOperationFlag::GetFirstPart;
+    loop {
+        let request = GetPackageDataRequest::new(0x01, PldmMsgType::Request, handle, op_flag);
+        setup.send_response(&setup.fd_sock, &request);
+
+        let packet = setup.fd_sock.receive(None).unwrap();
+        let data = &packet.payload.data[..packet.payload.len];
+        let fixed = GetPackageDataResponseFixed::decode(data).unwrap();
+        assert_eq!(fixed.completion_code, PldmBaseCompletionCode::Success as u8);
+        let portion = &data[core::mem::size_of::<GetPackageDataResponseFixed>()..];
+        assert!(portion.len() <= PKG_DATA_MAX_TRANSFER_SIZE);
+        received.extend_from_slice(portion);
+        if fixed.transfer_flag == TransferRespFlag::End as u8 {
+            break;
+        }
+        handle = fixed.next_data_transfer_handle;
+        op_flag = TransferOperationFlag::GetNextPart;
+    }
+    assert_eq!(received, package_data);
+
+    // The UA then saves the device metadata, in two portions
+    let request: GetDeviceMetaDataRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::GetDeviceMetaData as u8)
+        .unwrap();
+    assert_eq!(
+        request.transfer_operation_flag,
+        TransferOperationFlag::GetFirstPart as u8
+    );
+    let response = GetDeviceMetaDataResponse::new(
+        request.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        24,
+        TransferRespFlag::Start,
+        &device_metadata[..24],
+    );
+    setup.send_response(&setup.fd_sock, &response);
+
+    let request: GetDeviceMetaDataRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::GetDeviceMetaData as u8)
+        .unwrap();
+    assert_eq!({ request.data_transfer_handle }, 24);
+    assert_eq!(
+        request.transfer_operation_flag,
+        TransferOperationFlag::GetNextPart as u8
+    );
+    let response = GetDeviceMetaDataResponse::new(
+        request.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        0,
+        TransferRespFlag::End,
+        &device_metadata[24..],
+    );
+    setup.send_response(&setup.fd_sock, &response);
+
+    // The components are passed once the metadata is saved
+    let _request: PassComponentTableRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::PassComponentTable as u8)
+        .unwrap();
+
+    // The device gets its metadata back
+    let request = GetMetaDataRequest::new(
+        0x02,
+        PldmMsgType::Request,
+        0,
+        TransferOperationFlag::GetFirstPart,
+    );
+    setup.send_response(&setup.fd_sock, &request);
+    let packet = setup.fd_sock.receive(None).unwrap();
+    let data = &packet.payload.data[..packet.payload.len];
+    let fixed = GetMetaDataResponseFixed::decode(data).unwrap();
+    assert_eq!(fixed.completion_code, PldmBaseCompletionCode::Success as u8);
+    assert_eq!(fixed.transfer_flag, TransferRespFlag::StartAndEnd as u8);
+    assert_eq!(
+        &data[core::mem::size_of::<GetMetaDataResponseFixed>()..],
+        &device_metadata[..]
+    );
+
+    setup.daemon.stop();
+}
+
+#[test]
+fn test_get_package_data_without_package_data() {
+    let pldm_fw_pkg = get_pldm_fw_pkg_with_package_data(None);
+
+    let mut setup = common::setup(Options {
+        pldm_fw_pkg: Some(pldm_fw_pkg),
+        discovery_sm_actions: CustomDiscoverySm {},
+        update_sm_actions: UpdateSmBypassed {},
+        fd_tid: 0x01,
+    });
+
+    let request: RequestUpdateRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::RequestUpdate as u8)
+        .unwrap();
+    assert_eq!({ request.fixed.pkg_data_len }, 0);
+
+    let response = RequestUpdateResponse::new(
+        request.fixed.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        0,
+        FdWillSendGetPackageData::YesWithMaxTransferSize as u8,
+        Some(PKG_DATA_MAX_TRANSFER_SIZE as u32),
+    );
+    setup.send_response(&setup.fd_sock, &response);
+    setup.wait_for_state_transition(update_sm::States::LearnComponents);
+
+    let request = GetPackageDataRequest::new(
+        0x01,
+        PldmMsgType::Request,
+        0,
+        TransferOperationFlag::GetFirstPart,
+    );
+    setup.send_response(&setup.fd_sock, &request);
+    let packet = setup.fd_sock.receive(None).unwrap();
+    let fixed =
+        GetPackageDataResponseFixed::decode(&packet.payload.data[..packet.payload.len]).unwrap();
+    assert_eq!(
+        fixed.completion_code,
+        FwUpdateCompletionCode::NoPackageData as u8
+    );
+
+    setup.daemon.stop();
+}
@@ -191,3 +191,63 @@ fn test_request_update_receive_fail() {
 
     setup.daemon.stop();
 }
+
+#[test]
+fn test_abort_update_in_update_mode() {
+    let pldm_fw_pkg = get_pldm_fw_pkg_caliptra_and_manifest(
+        Some(CALIPTRA_FW_ACTIVE_COMP_STAMP + 1),
+        Some(SOC_MANIFEST_ACTIVE_COMP_STAMP + 1),
+    );
+    let mut setup = common::setup(Options {
+        pldm_fw_pkg: Some(pldm_fw_pkg.clone()),
+        discovery_sm_actions: CustomDiscoverySm {},
+        update_sm_actions: UpdateSmBypassed {},
+        fd_tid: 0x01,
+    });
+
+    let request: RequestUpdateRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::RequestUpdate as u8)
+        .unwrap();
+    let response = RequestUpdateResponse::new(
+        request.fixed.hdr.instance_id(),
+        PldmBaseCompletionCode::Success as u8,
+        0,
+        0,
+        None,
+    );
+    setup.send_response(&setup.fd_sock, &response);
+    setup.wait_for_state_transition(update_sm::States::LearnComponents);
+
+    // The device is in update mode, so it is asked to cancel the update
+    setup.daemon.abort_update();
+    setup.wait_for_state_transition(update_sm::States::Done);
+    setup.daemon.stop();
+    assert!(setup
+        .drain_received_commands()
+        .contains(&(FwUpdateCmd::CancelUpdate as u8)));
+}
+
+#[test]
+fn test_abort_update_not_in_update_mode() {
+    let pldm_fw_pkg = get_pldm_fw_pkg_caliptra_and_manifest(
+        Some(CALIPTRA_FW_ACTIVE_COMP_STAMP + 1),
+        Some(SOC_MANIFEST_ACTIVE_COMP_STAMP + 1),
+    );
+    let mut setup = common::setup(Options {
+        pldm_fw_pkg: Some(pldm_fw_pkg.clone()),
+        discovery_sm_actions: CustomDiscoverySm {},
+        update_sm_actions: UpdateSmBypassed {},
+        fd_tid: 0x01,
+    });
+
+    // Abort before the device answers RequestUpdate: it never entered update mode
+    let _: RequestUpdateRequest = setup
+        .receive_request(&setup.fd_sock, FwUpdateCmd::RequestUpdate as u8)
+        .unwrap();
+    setup.daemon.abort_update();
+    setup.wait_for_state_transition(update_sm::States::Done);
+    setup.daemon.stop();
+    assert!(!setup
+        .drain_received_commands()
+        .contains(&(FwUpdateCmd::CancelUpdate as u8)));
+}
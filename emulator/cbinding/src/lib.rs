@@ -311,6 +311,7 @@ pub unsafe extern "C" fn emulator_init(
         } else {
             Some(config.i3c_port as u16)
         },
+        uart_port: None,
         device_security_state: DeviceLifecycle::try_from(config.device_security_state)
             .unwrap_or(DeviceLifecycle::Production) as u32,
         vendor_pk_hash: convert_optional_c_string(config.vendor_pk_hash),
@@ -47,6 +47,7 @@ fn test_emulator_args_creation() {
         _no_stdin_uart: false,
         flash_based_boot: false,
         i3c_port: None,
+        uart_port: None,
         device_security_state: DeviceLifecycle::Production as u32,
         vendor_pk_hash: None,
         vendor_pqc_type: FwVerificationPqcKeyType::LMS,
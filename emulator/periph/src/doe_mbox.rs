@@ -176,6 +176,12 @@ impl DoeMboxPeriph {
         Ok(())
     }
 
+    /// Returns true while the MCU has not taken the last message written to the mailbox.
+    pub fn is_write_pending(&self) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.mbox_event.reg.get() & DoeMboxEvent::DataReady::SET.value != 0
+    }
+
     pub fn request_reset(&mut self) {
         let mut inner = self.inner.lock().unwrap();
         // PERIPHERAL LOGIC: Set EVENT.RESET_REQ bit
@@ -13,7 +13,7 @@ Abstract:
 --*/
 
 use crate::McuMailbox0Internal;
-use crate::{EmuCtrl, Uart};
+use crate::{DoeMboxPeriph, DummyDoeMbox, EmuCtrl, Uart};
 use caliptra_emu_bus::{Bus, BusError, Clock, Ram, Rom};
 use caliptra_emu_bus::{Device, Event, EventData};
 use caliptra_emu_cpu::{Irq, Pic, PicMmioRegisters};
@@ -23,6 +23,7 @@ use emulator_consts::{
     MCU_MAILBOX0_SRAM_SIZE, MCU_MAILBOX1_SRAM_SIZE, RAM_SIZE, ROM_DEDICATED_RAM_ORG,
     ROM_DEDICATED_RAM_SIZE,
 };
+use emulator_registers_generated::doe_mbox::DoeMboxBus;
 use std::{
     cell::RefCell,
     path::PathBuf,
@@ -49,6 +50,8 @@ pub struct McuRootBusOffsets {
     pub direct_read_flash_size: u32,
     pub dot_flash_offset: u32,
     pub dot_flash_size: u32,
+    pub pcie_vdm_mbox_offset: u32,
+    pub pcie_vdm_mbox_size: u32,
 }
 
 impl Default for McuRootBusOffsets {
@@ -71,6 +74,8 @@ impl Default for McuRootBusOffsets {
             direct_read_flash_size: DIRECT_READ_FLASH_SIZE,
             dot_flash_offset: 0x8100_0000,
             dot_flash_size: DOT_FLASH_SIZE,
+            pcie_vdm_mbox_offset: 0x2f20_0000,
+            pcie_vdm_mbox_size: 0x10_1000,
         }
     }
 }
@@ -86,7 +91,11 @@ pub struct McuRootBusArgs {
     pub log_dir: PathBuf,
     pub uart_output: Option<Rc<RefCell<Vec<u8>>>>,
     pub uart_rx: Option<Arc<Mutex<Option<u8>>>>,
+    /// Socket receiving the UART output, for MCTP over serial.
+    pub uart_socket_tx: Option<mpsc::Sender<u8>>,
     pub offsets: McuRootBusOffsets,
+    /// Mailbox modeling the PCIe link that carries MCTP over PCIe VDM.
+    pub pcie_vdm_mbox: Option<DoeMboxPeriph>,
 }
 
 pub struct McuRootBus {
@@ -102,6 +111,7 @@ pub struct McuRootBus {
     pub direct_read_flash: Rc<RefCell<Ram>>,
     pub dot_flash: Rc<RefCell<Ram>>,
     pub mci_irq: Rc<RefCell<Irq>>,
+    pub pcie_vdm_mbox: Option<DoeMboxBus>,
     event_sender: Option<mpsc::Sender<Event>>,
     offsets: McuRootBusOffsets,
 }
@@ -117,6 +127,7 @@ impl McuRootBus {
     pub const DMA_ERROR_IRQ: u8 = 23;
     pub const DMA_EVENT_IRQ: u8 = 24;
     pub const DOE_MBOX_EVENT_IRQ: u8 = 25;
+    pub const PCIE_VDM_MBOX_EVENT_IRQ: u8 = 26;
 
     pub fn new(mut args: McuRootBusArgs) -> Result<Self, std::io::Error> {
         let clock = args.clock;
@@ -131,12 +142,22 @@ impl McuRootBus {
         let mci_irq = pic.register_irq(McuRootBus::MCI_IRQ);
         let mcu_mailbox0 = McuMailbox0Internal::new(&clock.clone());
         let mcu_mailbox1 = McuMailbox0Internal::new(&clock.clone());
+        let mut uart = Uart::new(args.uart_output, args.uart_rx, uart_irq, &clock.clone());
+        if let Some(uart_socket_tx) = args.uart_socket_tx {
+            uart.set_socket_tx(uart_socket_tx);
+        }
+        let pcie_vdm_mbox = args.pcie_vdm_mbox.map(|periph| {
+            let irq = pic.register_irq(Self::PCIE_VDM_MBOX_EVENT_IRQ);
+            DoeMboxBus {
+                periph: Box::new(DummyDoeMbox::new(&clock.clone(), irq, periph)),
+            }
+        });
 
         Ok(Self {
             rom,
             ram: Rc::new(RefCell::new(ram)),
             rom_sram: Rc::new(RefCell::new(rom_sram)),
-            uart: Uart::new(args.uart_output, args.uart_rx, uart_irq, &clock.clone()),
+            uart,
             ctrl: EmuCtrl::new(),
             pic_regs: pic.mmio_regs(clock.clone()),
             event_sender: None,
@@ -147,6 +168,7 @@ impl McuRootBus {
             mci_irq: Rc::new(RefCell::new(mci_irq)),
             mcu_mailbox0,
             mcu_mailbox1,
+            pcie_vdm_mbox,
         })
     }
 
@@ -223,6 +245,13 @@ impl Bus for McuRootBus {
                 .borrow_mut()
                 .read(size, addr - self.offsets.dot_flash_offset);
         }
+        if addr >= self.offsets.pcie_vdm_mbox_offset
+            && addr < self.offsets.pcie_vdm_mbox_offset + self.offsets.pcie_vdm_mbox_size
+        {
+            if let Some(pcie_vdm_mbox) = self.pcie_vdm_mbox.as_mut() {
+                return pcie_vdm_mbox.read(size, addr - self.offsets.pcie_vdm_mbox_offset);
+            }
+        }
         Err(BusError::LoadAccessFault)
     }
 
@@ -280,6 +309,13 @@ impl Bus for McuRootBus {
                 val,
             );
         }
+        if addr >= self.offsets.pcie_vdm_mbox_offset
+            && addr < self.offsets.pcie_vdm_mbox_offset + self.offsets.pcie_vdm_mbox_size
+        {
+            if let Some(pcie_vdm_mbox) = self.pcie_vdm_mbox.as_mut() {
+                return pcie_vdm_mbox.write(size, addr - self.offsets.pcie_vdm_mbox_offset, val);
+            }
+        }
         Err(BusError::StoreAccessFault)
     }
 
@@ -292,6 +328,9 @@ impl Bus for McuRootBus {
         self.pic_regs.poll();
         self.external_test_sram.borrow_mut().poll();
         self.direct_read_flash.borrow_mut().poll();
+        if let Some(pcie_vdm_mbox) = self.pcie_vdm_mbox.as_mut() {
+            pcie_vdm_mbox.poll();
+        }
     }
 
     fn warm_reset(&mut self) {
@@ -17,6 +17,7 @@ use caliptra_emu_cpu::Irq;
 use caliptra_emu_types::{RvAddr, RvData, RvSize};
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
 
 pub struct Uart {
@@ -25,6 +26,7 @@ pub struct Uart {
     stop_bits: u8,
     output: Option<Rc<RefCell<Vec<u8>>>>,
     input: Option<Arc<Mutex<Option<u8>>>>,
+    socket_tx: Option<Sender<u8>>,
     bytes_read: Cell<u64>,
     byte_last_irq_triggered: Cell<u64>,
     irq: Irq,
@@ -48,6 +50,9 @@ impl Uart {
     /// Transmit Data Register
     const ADDR_TX_DATA: RvAddr = 0x00000041;
 
+    /// Receive status Register
+    const ADDR_RX_STATUS: RvAddr = 0x00000042;
+
     pub fn new(
         output: Option<Rc<RefCell<Vec<u8>>>>,
         input: Option<Arc<Mutex<Option<u8>>>>,
@@ -60,6 +65,7 @@ impl Uart {
             stop_bits: 1,
             output,
             input,
+            socket_tx: None,
             irq,
             bytes_read: Cell::new(0),
             byte_last_irq_triggered: Cell::new(u64::MAX),
@@ -67,6 +73,28 @@ impl Uart {
             char_buffer: Cell::new(PartialUtf8::new()),
         }
     }
+
+    /// Sends the transmitted bytes to a socket instead of the console.
+    pub fn set_socket_tx(&mut self, socket_tx: Sender<u8>) {
+        self.socket_tx = Some(socket_tx);
+    }
+
+    fn print_console(&self, ch: u8) {
+        match ch {
+            // normal ASCII
+            0x02..=0x7f => eprint!("{}", ch as char),
+            // UTF-8 multi-byte sequences
+            0x80..=0xf4 => {
+                let mut partial = self.char_buffer.take();
+                partial.push(ch);
+                while let Some(c) = partial.next() {
+                    eprint!("{}", c);
+                }
+                self.char_buffer.set(partial);
+            }
+            _ => (), // ignore test result characters
+        }
+    }
 }
 
 impl Bus for Uart {
@@ -103,6 +131,10 @@ impl Bus for Uart {
             (RvSize::Byte, Uart::ADDR_DATA_BITS) => Ok(self.data_bits as RvData),
             (RvSize::Byte, Uart::ADDR_STOP_BITS) => Ok(self.stop_bits as RvData),
             (RvSize::Byte, Uart::ADDR_TX_STATUS) => Ok(1),
+            (RvSize::Byte, Uart::ADDR_RX_STATUS) => match &self.input {
+                Some(input) => Ok(input.lock().unwrap().is_some() as RvData),
+                None => Ok(0),
+            },
             (RvSize::Byte, Uart::ADDR_TX_DATA) => match &self.input {
                 Some(input) => {
                     let mut input = input.lock().unwrap();
@@ -137,28 +169,18 @@ impl Bus for Uart {
             (RvSize::Byte, Uart::ADDR_BIT_RATE) => self.bit_rate = value as u8,
             (RvSize::Byte, Uart::ADDR_DATA_BITS) => self.data_bits = value as u8,
             (RvSize::Byte, Uart::ADDR_STOP_BITS) => self.stop_bits = value as u8,
-            (RvSize::Byte, Uart::ADDR_TX_DATA) => match &self.output {
-                Some(output) => {
-                    let mut out = output.borrow_mut();
-                    out.push(value as u8);
+            (RvSize::Byte, Uart::ADDR_TX_DATA) => {
+                if let Some(output) = &self.output {
+                    output.borrow_mut().push(value as u8);
                 }
-                None => {
-                    match value as u8 {
-                        // normal ASCII
-                        0x02..=0x7f => eprint!("{}", value as u8 as char),
-                        // UTF-8 multi-byte sequences
-                        0x80..=0xf4 => {
-                            let mut partial = self.char_buffer.take();
-                            partial.push(value as u8);
-                            while let Some(c) = partial.next() {
-                                eprint!("{}", c);
-                            }
-                            self.char_buffer.set(partial);
-                        }
-                        _ => (), // ignore test result characters
+                match &self.socket_tx {
+                    Some(socket_tx) => {
+                        let _ = socket_tx.send(value as u8);
                     }
+                    None if self.output.is_none() => self.print_console(value as u8),
+                    None => (),
                 }
-            },
+            }
             _ => Err(BusError::StoreAccessFault)?,
         }
         Ok(())
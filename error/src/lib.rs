@@ -168,6 +168,21 @@ impl McuError {
             0x1_0017,
             "DOT recovery transport error"
         ),
+        (
+            ROM_WARM_BOOT_DEBUG_UNLOCK_TIMEOUT,
+            0x1_0018,
+            "Warm boot timed out waiting for Caliptra to start production debug unlock"
+        ),
+        (
+            ROM_WARM_BOOT_DEBUG_UNLOCK_NOT_ALLOWED,
+            0x1_0019,
+            "Warm boot production debug unlock requested outside production with DEBUG_INTENT"
+        ),
+        (
+            ROM_WARM_BOOT_DEBUG_UNLOCK_IMAGE_MISMATCH,
+            0x1_001a,
+            "Warm boot MCU image digest mismatch before production debug unlock"
+        ),
         (
             ROM_LC_TRANSITION_ERROR,
             0x2_0000,
@@ -5,11 +5,11 @@ use mcu_config::boot::{PartitionId, PartitionStatus, RollbackEnable};
 use mcu_config::flash::FlashPartition;
 use zerocopy::{FromBytes, Immutable, IntoBytes};
 
-pub const FLASH_PARTITIONS_COUNT: usize = 4; // Number of flash partitions
+pub const FLASH_PARTITIONS_COUNT: usize = 5; // Number of flash partitions
 
 // Allocate driver numbers for flash partitions
 pub const DRIVER_NUM_START: usize = 0x7000_0006; // Base driver number for flash partitions
-pub const DRIVER_NUM_END: usize = 0x7000_0009; // End driver number for flash partitions
+pub const DRIVER_NUM_END: usize = 0x7000_000A; // End driver number for flash partitions
 
 pub const BLOCK_SIZE: usize = 64 * 1024; // Block size for flash partitions
 
@@ -41,11 +41,28 @@ pub const STAGING_PARTITION: FlashPartition = FlashPartition {
     driver_num: 0x7000_0009,
 };
 
+// Persistent storage for the SPDM certificate chains installed with SET_CERTIFICATE,
+// followed by the IDevID certificates imported over MCTP VDM
+pub const CERT_STORE_PARTITION: FlashPartition = FlashPartition {
+    name: "cert_store",
+    offset: IMAGE_A_PARTITION.offset + IMAGE_A_PARTITION.size,
+    size: (BLOCK_SIZE * 2),
+    driver_num: 0x7000_000A,
+};
+
+// Regions of the cert store partition, as offsets within the partition
+pub const SPDM_CERT_CHAINS_REGION_OFFSET: usize = 0;
+pub const SPDM_CERT_CHAINS_REGION_SIZE: usize = BLOCK_SIZE;
+pub const IDEV_CERTS_REGION_OFFSET: usize =
+    SPDM_CERT_CHAINS_REGION_OFFSET + SPDM_CERT_CHAINS_REGION_SIZE;
+pub const IDEV_CERTS_REGION_SIZE: usize = BLOCK_SIZE;
+
 #[macro_export]
 macro_rules! flash_partition_list_primary {
     ($macro:ident) => {{
         $macro!(0, image_a, IMAGE_A_PARTITION);
         $macro!(1, partition_table, PARTITION_TABLE);
+        $macro!(4, cert_store, CERT_STORE_PARTITION);
     }};
 }
 
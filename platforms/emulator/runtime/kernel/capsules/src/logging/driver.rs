@@ -41,6 +41,7 @@ mod logging_cmd {
     pub const SYNC: u32 = 4;
     pub const ERASE: u32 = 5;
     pub const GET_CAP: u32 = 6;
+    pub const GET_USED: u32 = 7;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -346,6 +347,12 @@ impl SyscallDriver for LoggingFlashDriver<'_> {
             0 => CommandReturn::success(),
 
             logging_cmd::GET_CAP => CommandReturn::success_u32(self.driver.get_size() as u32),
+            // Entry IDs are positions in the log, so this includes the headers stored with entries
+            logging_cmd::GET_USED => CommandReturn::success_u32(
+                self.driver
+                    .log_end()
+                    .saturating_sub(self.driver.log_start()) as u32,
+            ),
             logging_cmd::READ => {
                 match self.enqueue_command(LoggingOps::Read, Some(processid), arg1, _arg2) {
                     Ok(()) => CommandReturn::success(),
@@ -0,0 +1,310 @@
+// Licensed under the Apache-2.0 license
+
+#![cfg_attr(target_arch = "riscv32", no_std)]
+
+//! Emulated PCIe VDM target device.
+//!
+//! The emulator models the PCIe link as a mailbox with the same register layout as the
+//! DOE mailbox. Each VDM (TLP header and data payload) is exchanged through the mailbox SRAM
+//! with its length in DWORDs in the DLEN register. A transmitted VDM is complete once the
+//! host has read it and cleared STATUS.DATA_READY, so that the packets of a message are not
+//! overwritten in the SRAM before the host takes them.
+
+use pcie_vdm_transport::hil::{PcieVdmRxClient, PcieVdmTarget, PcieVdmTargetInfo, PcieVdmTxClient};
+
+use capsules_core::virtualizers::virtual_alarm::{MuxAlarm, VirtualMuxAlarm};
+use core::cell::Cell;
+use kernel::hil::time::{Alarm, AlarmClient, Time};
+use kernel::utilities::cells::{OptionalCell, TakeCell};
+use kernel::utilities::registers::interfaces::{ReadWriteable, Readable, Writeable};
+use kernel::utilities::StaticRef;
+use kernel::{debug, ErrorCode};
+use registers_generated::doe_mbox::bits::{DoeMboxEvent, DoeMboxStatus};
+use registers_generated::doe_mbox::regs::DoeMbox;
+
+pub const PCIE_VDM_MBOX_ADDR: u32 = 0x2f20_0000;
+
+pub const PCIE_VDM_MBOX_BASE: StaticRef<DoeMbox> =
+    unsafe { StaticRef::new(PCIE_VDM_MBOX_ADDR as *const DoeMbox) };
+
+const PCIE_VDM_MBOX_SRAM_ADDR: u32 = PCIE_VDM_MBOX_ADDR + 0x1000; // SRAM offset from mailbox base address
+
+// Largest VDM exchanged through the mailbox: 4 DWORD TLP header and up to 1024 DWORDs of data.
+const PCIE_VDM_MAX_LEN: usize = (4 + 1024) * 4;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum PcieVdmMboxState {
+    Idle,
+    RxWait,       // Driver waiting for a VDM to be received from the host.
+    TxInProgress, // Transmit is in progress. Need to wait for send_done.
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum TimerMode {
+    NoTimer,
+    ReceiveRetry,
+    SendDoneDefer,
+}
+
+pub struct EmulatedPcieVdm<'a, A: Alarm<'a>> {
+    registers: StaticRef<DoeMbox>,
+    tx_client: OptionalCell<&'a dyn PcieVdmTxClient>,
+    rx_client: OptionalCell<&'a dyn PcieVdmRxClient>,
+
+    // Mailbox SRAM holding the VDM being sent or received
+    sram: TakeCell<'static, [u32]>,
+    // Client buffer to receive the VDM
+    rx_buffer: TakeCell<'static, [u8]>,
+    // Client buffer held until send_done
+    tx_buffer: TakeCell<'static, [u8]>,
+
+    state: Cell<PcieVdmMboxState>,
+    timer_mode: Cell<TimerMode>,
+    alarm: VirtualMuxAlarm<'a, A>,
+}
+
+fn pcie_vdm_mbox_sram_static_ref(len: usize) -> &'static mut [u32] {
+    // SAFETY: We assume the SRAM is initialized and the address is valid.
+    // The length is provided by the caller and should match the actual SRAM size.
+    unsafe { core::slice::from_raw_parts_mut(PCIE_VDM_MBOX_SRAM_ADDR as *mut u32, len) }
+}
+
+impl<'a, A: Alarm<'a>> EmulatedPcieVdm<'a, A> {
+    // Number of ticks between checks that the host has read the transmitted VDM
+    const DEFER_SEND_DONE_TICKS: u32 = 1000;
+
+    const RECEIVE_RETRY_TICKS: u32 = 1000;
+
+    pub fn new(base: StaticRef<DoeMbox>, alarm: &'a MuxAlarm<'a, A>) -> EmulatedPcieVdm<'a, A> {
+        let len = base.doe_mbox_sram.len().min(PCIE_VDM_MAX_LEN / 4);
+
+        EmulatedPcieVdm {
+            registers: base,
+            tx_client: OptionalCell::empty(),
+            rx_client: OptionalCell::empty(),
+            sram: TakeCell::new(pcie_vdm_mbox_sram_static_ref(len)),
+            rx_buffer: TakeCell::empty(),
+            tx_buffer: TakeCell::empty(),
+            state: Cell::new(PcieVdmMboxState::Idle),
+            timer_mode: Cell::new(TimerMode::NoTimer),
+            alarm: VirtualMuxAlarm::new(alarm),
+        }
+    }
+
+    pub fn init(&'static self) {
+        self.alarm.setup();
+        self.alarm.set_alarm_client(self);
+    }
+
+    fn max_vdm_len(&self) -> usize {
+        self.sram.map_or(0, |sram| sram.len() * 4)
+    }
+
+    fn schedule_send_done(&self) {
+        self.timer_mode.set(TimerMode::SendDoneDefer);
+        let now = self.alarm.now();
+        self.alarm
+            .set_alarm(now, (Self::DEFER_SEND_DONE_TICKS).into());
+    }
+
+    fn schedule_receive_retry(&self) {
+        self.timer_mode.set(TimerMode::ReceiveRetry);
+        let now = self.alarm.now();
+        self.alarm
+            .set_alarm(now, (Self::RECEIVE_RETRY_TICKS).into());
+    }
+
+    pub fn handle_interrupt(&self) {
+        let event = self.registers.doe_mbox_event.extract();
+
+        // Clear the status register, unless it signals a transmitted VDM the host has not read
+        if self.state.get() != PcieVdmMboxState::TxInProgress {
+            self.registers.doe_mbox_status.set(0);
+        }
+
+        if event.is_set(DoeMboxEvent::ResetReq) {
+            // Write 1 to clear the RESET_REQ event. There is no state to reset
+            // beyond a pending receive, since each VDM is handled on its own.
+            self.registers
+                .doe_mbox_event
+                .modify(DoeMboxEvent::ResetReq::SET);
+            self.registers
+                .doe_mbox_status
+                .write(DoeMboxStatus::ResetAck::SET);
+        }
+
+        if event.is_set(DoeMboxEvent::DataReady) {
+            self.handle_receive_data();
+        }
+    }
+
+    fn handle_receive_data(&self) {
+        if self.state.get() != PcieVdmMboxState::RxWait {
+            // Not currently waiting for a VDM. It is picked up once the transmit completes.
+            return;
+        }
+
+        let len = self.registers.doe_mbox_dlen.get() as usize * 4;
+        if len > self.max_vdm_len() {
+            self.registers
+                .doe_mbox_event
+                .modify(DoeMboxEvent::DataReady::SET);
+            self.registers
+                .doe_mbox_status
+                .write(DoeMboxStatus::Error::SET);
+            return;
+        }
+
+        if self.rx_buffer.is_none() {
+            // Ask the client for a buffer
+            self.rx_client.map(|client| client.write_expected());
+        }
+
+        let rx_buffer = match self.rx_buffer.take() {
+            Some(buf) => buf,
+            None => {
+                // The client has not restored the rx buffer. Try receiving again later.
+                self.schedule_receive_retry();
+                return;
+            }
+        };
+
+        // Clear the DATA_READY event, writing 1 to the event register
+        self.registers
+            .doe_mbox_event
+            .modify(DoeMboxEvent::DataReady::SET);
+
+        if len > rx_buffer.len() {
+            debug!(
+                "PCIE_VDM_MBOX_DRIVER: VDM of {} bytes does not fit the rx buffer",
+                len
+            );
+            self.rx_buffer.replace(rx_buffer);
+            self.registers
+                .doe_mbox_status
+                .write(DoeMboxStatus::Error::SET);
+            return;
+        }
+
+        self.sram.map(|sram| {
+            for (dst, word) in rx_buffer[..len].chunks_exact_mut(4).zip(sram.iter()) {
+                dst.copy_from_slice(&word.to_le_bytes());
+            }
+        });
+
+        match self.rx_client.get() {
+            Some(client) => {
+                // It is expected that the client restores the buffer with set_rx_buffer().
+                client.receive(rx_buffer, len);
+            }
+            None => {
+                debug!("PCIE_VDM_MBOX_DRIVER: No RX client available to receive data");
+                self.rx_buffer.replace(rx_buffer);
+            }
+        }
+    }
+}
+
+impl<'a, A: Alarm<'a>> AlarmClient for EmulatedPcieVdm<'a, A> {
+    fn alarm(&self) {
+        let timer_mode = self.timer_mode.get();
+        // Clear timer mode before handling, as the handlers may schedule it again
+        self.timer_mode.set(TimerMode::NoTimer);
+        match timer_mode {
+            TimerMode::NoTimer => {
+                // Spurious alarm, nothing to do.
+            }
+            TimerMode::ReceiveRetry => {
+                self.handle_receive_data();
+            }
+            TimerMode::SendDoneDefer => {
+                if self
+                    .registers
+                    .doe_mbox_status
+                    .is_set(DoeMboxStatus::DataReady)
+                {
+                    // The host has not read the VDM yet
+                    self.schedule_send_done();
+                    return;
+                }
+                self.state.set(PcieVdmMboxState::RxWait);
+                if let Some(tx_buffer) = self.tx_buffer.take() {
+                    self.tx_client.map(|client| {
+                        client.send_done(tx_buffer, Ok(()));
+                    });
+                }
+                // Pick up a VDM that arrived while transmitting
+                if self
+                    .registers
+                    .doe_mbox_event
+                    .is_set(DoeMboxEvent::DataReady)
+                {
+                    self.handle_receive_data();
+                }
+            }
+        }
+    }
+}
+
+impl<'a, A: Alarm<'a>> PcieVdmTarget<'a> for EmulatedPcieVdm<'a, A> {
+    fn set_tx_client(&self, client: &'a dyn PcieVdmTxClient) {
+        self.tx_client.set(client);
+    }
+
+    fn set_rx_client(&self, client: &'a dyn PcieVdmRxClient) {
+        self.rx_client.set(client);
+    }
+
+    fn set_rx_buffer(&self, rx_buf: &'static mut [u8]) {
+        self.rx_buffer.replace(rx_buf);
+    }
+
+    fn transmit(
+        &self,
+        tx_buf: &'static mut [u8],
+        len: usize,
+    ) -> Result<(), (ErrorCode, &'static mut [u8])> {
+        if len % 4 != 0 || len > self.max_vdm_len() || len > tx_buf.len() {
+            return Err((ErrorCode::SIZE, tx_buf));
+        }
+        if self.state.get() == PcieVdmMboxState::TxInProgress {
+            return Err((ErrorCode::BUSY, tx_buf));
+        }
+
+        self.sram.map(|sram| {
+            for (word, src) in sram.iter_mut().zip(tx_buf[..len].chunks_exact(4)) {
+                *word = u32::from_le_bytes([src[0], src[1], src[2], src[3]]);
+            }
+        });
+
+        // Set data len in DWORDs and signal the host that the VDM is ready.
+        self.registers.doe_mbox_dlen.set((len / 4) as u32);
+        self.registers
+            .doe_mbox_status
+            .write(DoeMboxStatus::DataReady::SET);
+
+        // hold on to the client buffer until send_done is called
+        self.tx_buffer.replace(tx_buf);
+        self.state.set(PcieVdmMboxState::TxInProgress);
+        // send_done is called once the host has read the VDM, which is polled via an alarm.
+        self.schedule_send_done();
+
+        Ok(())
+    }
+
+    fn enable(&self) {
+        self.state.set(PcieVdmMboxState::RxWait);
+    }
+
+    fn disable(&self) {
+        self.state.set(PcieVdmMboxState::Idle);
+    }
+
+    fn get_device_info(&self) -> PcieVdmTargetInfo {
+        PcieVdmTargetInfo {
+            // The PCI ID is learned from the VDMs routed to this device.
+            pci_id: None,
+            max_vdm_len: self.max_vdm_len(),
+        }
+    }
+}
@@ -26,14 +26,21 @@ use kernel::storage_volume;
 use kernel::syscall;
 use kernel::utilities::registers::interfaces::ReadWriteable;
 use kernel::{create_capability, debug, static_init};
+#[cfg(feature = "mctp-bridge")]
+use mcu_components::mctp_bridge_component_static;
+#[cfg(not(any(feature = "mctp-pcie-vdm", feature = "mctp-serial")))]
 use mcu_components::mctp_mux_component_static;
+#[cfg(feature = "mctp-pcie-vdm")]
+use mcu_components::mctp_pcie_vdm_mux_component_static;
+#[cfg(feature = "mctp-serial")]
+use mcu_components::mctp_serial_mux_component_static;
 use mcu_components::{
     doe_component_static, flash_partition_component_static, instantiate_flash_partitions,
     mailbox_component_static, mbox_sram_component_static, mctp_driver_component_static,
     mcu_mbox_component_static,
 };
 use mcu_config_emulator::flash::{
-    IMAGE_A_PARTITION, IMAGE_B_PARTITION, PARTITION_TABLE, STAGING_PARTITION,
+    CERT_STORE_PARTITION, IMAGE_A_PARTITION, IMAGE_B_PARTITION, PARTITION_TABLE, STAGING_PARTITION,
 };
 use mcu_config_emulator::{flash_partition_list_primary, flash_partition_list_secondary};
 use mcu_platforms_common::pmp_config::{PlatformPMPConfig, PlatformRegion};
@@ -85,6 +92,29 @@ pub static mut PROCESSES: [Option<&'static dyn kernel::process::Process>; NUM_PR
 
 pub type VeeRChip = mcu_tock_veer::chip::VeeR<'static, VeeRDefaultPeripherals<'static>>;
 
+// The bridge forwards between the I3C port and the PCIe VDM mailbox.
+#[cfg(all(
+    feature = "mctp-bridge",
+    any(feature = "mctp-pcie-vdm", feature = "mctp-serial")
+))]
+compile_error!("feature \"mctp-bridge\" requires MCTP over I3C");
+
+// Each feature selects the binding of the MCTP mux.
+#[cfg(all(feature = "mctp-pcie-vdm", feature = "mctp-serial"))]
+compile_error!("features \"mctp-pcie-vdm\" and \"mctp-serial\" are mutually exclusive");
+
+/// PCI ID of the endpoint behind the MCTP bridge.
+#[cfg(feature = "mctp-bridge")]
+const MCTP_BRIDGE_DOWNSTREAM_PCI_ID: u16 = 0x0200;
+
+/// MCTP transport binding selected for the board.
+#[cfg(not(any(feature = "mctp-pcie-vdm", feature = "mctp-serial")))]
+pub type MCTPBinding = capsules_runtime::mctp::transport_binding::MCTPI3CBinding<'static>;
+#[cfg(feature = "mctp-pcie-vdm")]
+pub type MCTPBinding = capsules_runtime::mctp::transport_binding::MCTPPcieVdmBinding<'static>;
+#[cfg(feature = "mctp-serial")]
+pub type MCTPBinding = capsules_runtime::mctp::transport_binding::MCTPSerialBinding<'static>;
+
 // Reference to the chip and peripherals for panic dumps and tests.
 pub static mut CHIP: Option<&'static VeeRChip> = None;
 pub static mut EMULATOR_PERIPHERALS: Option<&'static EmulatorPeripherals> = None;
@@ -387,6 +417,18 @@ pub unsafe fn main() {
         execute: false,
     });
 
+    // Dummy PCIe VDM mailbox peripheral region
+    #[cfg(any(feature = "mctp-pcie-vdm", feature = "mctp-bridge"))]
+    platform_regions.push(PlatformRegion {
+        start_addr: 0x2f20_0000 as *const u8,
+        size: 0x10_1000,
+        is_mmio: true,
+        user_accessible: false,
+        read: true,
+        write: true,
+        execute: false,
+    });
+
     // AXICDMA
     platform_regions.push(PlatformRegion {
         start_addr: registers_generated::axicdma::AXICDMA_ADDR as *const u8,
@@ -555,8 +597,63 @@ pub unsafe fn main() {
         let _ = process_console.start();
     }
 
+    // MCTP is carried over I3C unless the board is set up for PCIe VDM or serial.
+    #[cfg(not(any(feature = "mctp-pcie-vdm", feature = "mctp-serial")))]
     let mux_mctp = mcu_components::mux_mctp::MCTPMuxComponent::new(&peripherals.i3c, mux_alarm)
         .finalize(mctp_mux_component_static!(InternalTimers, MCTPI3CBinding));
+    #[cfg(feature = "mctp-pcie-vdm")]
+    let mux_mctp = mcu_components::mux_mctp::MCTPPcieVdmMuxComponent::new(
+        &emulator_peripherals.pcie_vdm,
+        mux_alarm,
+    )
+    .finalize(mctp_pcie_vdm_mux_component_static!(InternalTimers));
+    // MCTP over serial shares the UART with the console.
+    #[cfg(feature = "mctp-serial")]
+    let mux_mctp = mcu_components::mux_mctp::MCTPSerialMuxComponent::new(uart_mux, mux_alarm)
+        .finalize(mctp_serial_mux_component_static!(InternalTimers));
+
+    // The MCTP bridge forwards the packets from I3C to the endpoint on the PCIe VDM mailbox.
+    #[cfg(feature = "mctp-bridge")]
+    {
+        use capsules_runtime::mctp::routing::{
+            MCTPBridgePortInfo, MCTP_BINDING_TYPE_I3C, MCTP_BINDING_TYPE_PCIE_VDM,
+        };
+        use capsules_runtime::mctp::transport_binding::MCTPPcieVdmBinding;
+        use pcie_vdm_transport::hil::PcieVdmTarget;
+
+        let downstream = static_init!(
+            MCTPPcieVdmBinding<'static>,
+            MCTPPcieVdmBinding::new(&emulator_peripherals.pcie_vdm)
+        );
+        downstream.setup_mctp_pcie_vdm();
+        emulator_peripherals.pcie_vdm.set_tx_client(downstream);
+        emulator_peripherals.pcie_vdm.set_rx_client(downstream);
+
+        let mctp_bridge = mcu_components::mctp_bridge::MCTPBridgeComponent::new(
+            mux_mctp,
+            downstream,
+            MCTPBridgePortInfo {
+                binding_type: MCTP_BINDING_TYPE_I3C,
+                media_type: 0,
+                phys_addr_len: 1,
+            },
+            MCTPBridgePortInfo {
+                binding_type: MCTP_BINDING_TYPE_PCIE_VDM,
+                media_type: 0,
+                phys_addr_len: 2,
+            },
+            mux_alarm,
+        )
+        .finalize(mctp_bridge_component_static!(InternalTimers));
+        if let Err(err) =
+            mctp_bridge.add_downstream_endpoint(&MCTP_BRIDGE_DOWNSTREAM_PCI_ID.to_be_bytes())
+        {
+            romtime::println!(
+                "[mcu-runtime] Failed to add MCTP bridge endpoint: {:?}",
+                err
+            );
+        }
+    }
 
     let mctp_spdm = mcu_components::mctp_driver::MCTPDriverComponent::new(
         board_kernel,
@@ -564,7 +661,7 @@ pub unsafe fn main() {
         mux_mctp,
         MessageType::Spdm,
     )
-    .finalize(mctp_driver_component_static!(InternalTimers));
+    .finalize(mctp_driver_component_static!(InternalTimers, MCTPBinding));
 
     // let mctp_secure_spdm = mcu_components::mctp_driver::MCTPDriverComponent::new(
     //     board_kernel,
@@ -580,7 +677,7 @@ pub unsafe fn main() {
         mux_mctp,
         MessageType::Pldm,
     )
-    .finalize(mctp_driver_component_static!(InternalTimers));
+    .finalize(mctp_driver_component_static!(InternalTimers, MCTPBinding));
 
     // Enable MCTP Caliptra VDM driver
     let mctp_caliptra = mcu_components::mctp_driver::MCTPDriverComponent::new(
@@ -589,7 +686,7 @@ pub unsafe fn main() {
         mux_mctp,
         MessageType::Caliptra,
     )
-    .finalize(mctp_driver_component_static!(InternalTimers));
+    .finalize(mctp_driver_component_static!(InternalTimers, MCTPBinding));
     romtime::println!("[mcu-runtime] MCTP Caliptra driver component initialized");
 
     // Set up a SPDM over DOE capsule.
@@ -13,6 +13,7 @@ pub const RECOVERY_FLASH_CTRL_ERROR_IRQ: u8 = 0x16;
 pub const DMA_EVENT_IRQ: u8 = 0x17;
 pub const DMA_ERROR_IRQ: u8 = 0x18;
 pub const DOE_MBOX_EVENT_IRQ: u8 = 0x19;
+pub const PCIE_VDM_MBOX_EVENT_IRQ: u8 = 0x1A;
 
 pub struct EmulatorPeripherals<'a> {
     pub uart: SemihostUart<'a>,
@@ -20,6 +21,7 @@ pub struct EmulatorPeripherals<'a> {
     pub secondary_flash_ctrl: flash_ctrl_emulator::EmulatedFlashCtrl<'a>,
     pub dma: dma_driver::axicdma::AxiCDMA<'a, InternalTimers<'a>>,
     pub doe_transport: doe_mbox_driver::EmulatedDoeTransport<'a, InternalTimers<'a>>,
+    pub pcie_vdm: pcie_vdm_mbox_driver::EmulatedPcieVdm<'a, InternalTimers<'a>>,
 }
 
 impl<'a> EmulatorPeripherals<'a> {
@@ -41,6 +43,10 @@ impl<'a> EmulatorPeripherals<'a> {
                 doe_mbox_driver::DOE_MBOX_BASE,
                 alarm,
             ),
+            pcie_vdm: pcie_vdm_mbox_driver::EmulatedPcieVdm::new(
+                pcie_vdm_mbox_driver::PCIE_VDM_MBOX_BASE,
+                alarm,
+            ),
         }
     }
 
@@ -51,6 +57,7 @@ impl<'a> EmulatorPeripherals<'a> {
         self.secondary_flash_ctrl.init();
         self.dma.init();
         self.doe_transport.init();
+        self.pcie_vdm.init();
     }
 }
 
@@ -75,6 +82,9 @@ impl<'a> InterruptService for EmulatorPeripherals<'a> {
         } else if interrupt == DOE_MBOX_EVENT_IRQ as u32 {
             self.doe_transport.handle_interrupt();
             return true;
+        } else if interrupt == PCIE_VDM_MBOX_EVENT_IRQ as u32 {
+            self.pcie_vdm.handle_interrupt();
+            return true;
         }
         false
     }
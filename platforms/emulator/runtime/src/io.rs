@@ -77,6 +77,10 @@ fn read_byte() -> u8 {
     unsafe { read_volatile(0x1000_1041 as *mut u8) }
 }
 
+fn rx_ready() -> bool {
+    unsafe { read_volatile(0x1000_1042 as *mut u8) != 0 }
+}
+
 pub struct SemihostUart<'a> {
     rx_client: OptionalCell<&'a dyn hil::uart::ReceiveClient>,
     rx_buffer: TakeCell<'static, [u8]>,
@@ -105,8 +109,9 @@ impl<'a> SemihostUart<'a> {
     pub fn init(&'static self) {}
 
     pub fn handle_interrupt(&self) {
-        let mut b = read_byte();
-        while b != 0 {
+        // Binary data such as MCTP over serial frames may contain zero bytes
+        while rx_ready() {
+            let b = read_byte();
             if let Some(rx_buffer) = self.rx_buffer.take() {
                 let len = self.rx_len.get();
                 let mut index = self.rx_index.get();
@@ -124,7 +129,6 @@ impl<'a> SemihostUart<'a> {
                     self.rx_buffer.replace(rx_buffer);
                 }
             }
-            b = read_byte();
         }
     }
 }
@@ -190,6 +194,7 @@ impl<'a> hil::uart::Receive<'a> for SemihostUart<'a> {
         // generic receive routine here, as the client callback needs to be
         // called from another call stack.
         self.rx_buffer.replace(rx_buffer);
+        self.rx_index.set(0);
         self.rx_len.set(rx_len);
         Ok(())
     }
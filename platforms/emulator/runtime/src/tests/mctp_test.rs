@@ -1,8 +1,8 @@
 // Licensed under the Apache-2.0 license
 
+use crate::board::MCTPBinding;
 use capsules_core::virtualizers::virtual_alarm::VirtualMuxAlarm;
 use capsules_runtime::mctp::mux::MuxMCTPDriver;
-use capsules_runtime::mctp::transport_binding::MCTPI3CBinding;
 use capsules_runtime::test::mctp::MockMctp;
 use capsules_runtime::test::mctp::TestClient;
 use core::fmt::Write;
@@ -18,12 +18,13 @@ pub fn test_mctp_capsule_loopback(
     mux_mctp: &'static MuxMCTPDriver<
         'static,
         VirtualMuxAlarm<'static, InternalTimers>,
-        MCTPI3CBinding<'static>,
+        MCTPBinding,
     >,
 ) -> Option<u32> {
     // set local EID here if needed.
     let mock_mctp = unsafe {
-        MockMctpComponent::new(mux_mctp).finalize(mock_mctp_component_static!(InternalTimers))
+        MockMctpComponent::new(mux_mctp)
+            .finalize(mock_mctp_component_static!(InternalTimers, MCTPBinding))
     };
     let mctp_tester = unsafe { static_init!(TestMctp<'static>, TestMctp::new(mock_mctp)) };
     mock_mctp.set_test_client(mctp_tester);
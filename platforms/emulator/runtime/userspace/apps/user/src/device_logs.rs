@@ -0,0 +1,246 @@
+// Licensed under the Apache-2.0 license
+
+//! Debug and attestation log access shared by the MCU mailbox and MCTP VDM command handlers.
+//!
+//! Both logs are reported as a stream of records:
+//! Entry length (u32, little-endian) | Entry
+//! The debug log entries are the entries of the flash logging capsule. The attestation log
+//! entries are the SoC measurements stashed in Caliptra:
+//! Metadata (4 bytes) | Measurement (48 bytes) | SVN (u32, little-endian)
+//! Logs are read at a byte offset into this stream, so both transports return identical
+//! records regardless of the chunk size they use.
+//!
+//! The flash log can only be read sequentially from its beginning. Each transport keeps a
+//! cursor on the last record it read, so that a chunked read continues from there instead of
+//! walking the log again, as long as the other transport has not moved the read position.
+
+use core::mem::size_of;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use external_cmds_common::{CommandError, LogType};
+use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use libapi_caliptra::evidence::measurement_log::MeasurementLog;
+use libsyscall_caliptra::logging::LoggingSyscall;
+use libtock_platform::ErrorCode;
+use mcu_mbox_common::config;
+
+// Largest entry the flash logging capsule reads back, the size of its kernel buffer
+const MAX_DEBUG_LOG_ENTRY_SIZE: usize = 256;
+const ATTESTATION_LOG_ENTRY_SIZE: usize = 4 + SHA384_HASH_SIZE + size_of::<u32>();
+
+/// Transport reading the logs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogReader {
+    McuMbox = 0,
+    Vdm = 1,
+}
+
+const LOG_READER_COUNT: usize = 2;
+
+/// Last debug log record read by a transport. A read may end inside this record, so it is
+/// kept to serve the next read, which starts after the read position of the flash log.
+#[derive(Clone, Copy)]
+struct DebugLogCursor {
+    valid: bool,
+    record_offset: usize,
+    entry: [u8; MAX_DEBUG_LOG_ENTRY_SIZE],
+    entry_len: usize,
+}
+
+impl DebugLogCursor {
+    const fn new() -> Self {
+        Self {
+            valid: false,
+            record_offset: 0,
+            entry: [0; MAX_DEBUG_LOG_ENTRY_SIZE],
+            entry_len: 0,
+        }
+    }
+}
+
+struct DebugLogState {
+    // Transport whose cursor matches the read position of the flash log
+    owner: Option<LogReader>,
+    cursors: [DebugLogCursor; LOG_READER_COUNT],
+}
+
+// The flash log has a single read position, so accesses from both transports are serialized
+static DEBUG_LOG_LOCK: Mutex<CriticalSectionRawMutex, DebugLogState> = Mutex::new(DebugLogState {
+    owner: None,
+    cursors: [DebugLogCursor::new(); LOG_READER_COUNT],
+});
+
+/// Window of the serialized log that is copied out for a read.
+struct LogChunk<'a> {
+    data: &'a mut [u8],
+    offset: usize,
+    pos: usize,
+    len: usize,
+}
+
+impl<'a> LogChunk<'a> {
+    fn new(data: &'a mut [u8], offset: usize) -> Self {
+        Self {
+            data,
+            offset,
+            pos: 0,
+            len: 0,
+        }
+    }
+
+    fn window_end(&self) -> usize {
+        self.offset.saturating_add(self.data.len())
+    }
+
+    /// Returns true once the log has been serialized past the end of the window.
+    fn is_full(&self) -> bool {
+        self.pos >= self.window_end()
+    }
+
+    /// Serializes the next bytes of the log, copying those that fall in the window.
+    fn push(&mut self, bytes: &[u8]) {
+        let start = self.pos;
+        self.pos += bytes.len();
+
+        let copy_start = start.max(self.offset);
+        let copy_end = self.pos.min(self.window_end());
+        if copy_start < copy_end {
+            self.data[copy_start - self.offset..copy_end - self.offset]
+                .copy_from_slice(&bytes[copy_start - start..copy_end - start]);
+            self.len = copy_end - self.offset;
+        }
+    }
+
+    fn push_record(&mut self, entry: &[u8]) {
+        self.push(&(entry.len() as u32).to_le_bytes());
+        self.push(entry);
+    }
+}
+
+fn debug_log() -> Result<LoggingSyscall, CommandError> {
+    let log: LoggingSyscall = LoggingSyscall::new();
+    log.exists().map_err(|_| CommandError::NotSupported)?;
+    Ok(log)
+}
+
+async fn read_debug_log(reader: LogReader, chunk: &mut LogChunk<'_>) -> Result<(), CommandError> {
+    let log = debug_log()?;
+    let mut state = DEBUG_LOG_LOCK.lock().await;
+    let DebugLogState { owner, cursors } = &mut *state;
+    let cursor = &mut cursors[reader as usize];
+
+    // Entries are only read sequentially: continue from the last record read by this
+    // transport if the read starts at or after it, otherwise walk the log from its start.
+    if *owner == Some(reader) && cursor.valid && chunk.offset >= cursor.record_offset {
+        chunk.pos = cursor.record_offset;
+        chunk.push_record(&cursor.entry[..cursor.entry_len]);
+    } else {
+        *owner = None;
+        cursor.valid = false;
+        log.seek_beginning()
+            .await
+            .map_err(|_| CommandError::InternalError)?;
+        *owner = Some(reader);
+    }
+
+    while !chunk.is_full() {
+        match log.read_entry(&mut cursor.entry).await {
+            Ok(len) => {
+                cursor.valid = true;
+                cursor.record_offset = chunk.pos;
+                cursor.entry_len = len;
+                chunk.push_record(&cursor.entry[..len]);
+            }
+            // The capsule fails the read once the end of the log is reached
+            Err(ErrorCode::Fail) => break,
+            Err(_) => {
+                *owner = None;
+                return Err(CommandError::InternalError);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_attestation_log(chunk: &mut LogChunk<'_>) {
+    let mut index = 0;
+    while !chunk.is_full() {
+        let Some(measurement) = MeasurementLog::entry(index) else {
+            break;
+        };
+
+        let mut entry = [0u8; ATTESTATION_LOG_ENTRY_SIZE];
+        let (metadata, rest) = entry.split_at_mut(measurement.metadata.len());
+        let (digest, svn) = rest.split_at_mut(SHA384_HASH_SIZE);
+        metadata.copy_from_slice(&measurement.metadata);
+        digest.copy_from_slice(&measurement.measurement);
+        svn.copy_from_slice(&measurement.svn.to_le_bytes());
+        chunk.push_record(&entry);
+        index += 1;
+    }
+}
+
+/// Reads the serialized log starting at `offset` into `data` for the given transport.
+///
+/// Returns the number of bytes read, which is 0 once `offset` is past the end of the log.
+pub async fn get_log(
+    reader: LogReader,
+    log_type: LogType,
+    offset: usize,
+    data: &mut [u8],
+) -> Result<usize, CommandError> {
+    let mut chunk = LogChunk::new(data, offset);
+    match log_type {
+        LogType::Debug => read_debug_log(reader, &mut chunk).await?,
+        LogType::Attestation => read_attestation_log(&mut chunk),
+    }
+    Ok(chunk.len)
+}
+
+/// Clears the log. Only the debug log can be cleared: the stashed measurements stay extended
+/// into PCR31 until reset, so dropping them from the attestation log would make it unverifiable.
+pub async fn clear_log(log_type: LogType) -> Result<(), CommandError> {
+    match log_type {
+        LogType::Debug => {
+            let log = debug_log()?;
+            let mut state = DEBUG_LOG_LOCK.lock().await;
+            // The read position of the flash log is reset with its entries
+            state.owner = None;
+            log.clear().await.map_err(|_| CommandError::InternalError)
+        }
+        LogType::Attestation => Err(CommandError::InvalidParams),
+    }
+}
+
+/// Appends an entry to the debug log.
+async fn append_debug_entry(entry: &[u8]) -> Result<(), CommandError> {
+    if entry.is_empty() || entry.len() > MAX_DEBUG_LOG_ENTRY_SIZE {
+        return Err(CommandError::InvalidParams);
+    }
+
+    let log = debug_log()?;
+    let _guard = DEBUG_LOG_LOCK.lock().await;
+    log.append_entry(entry)
+        .await
+        .map_err(|_| CommandError::InternalError)
+}
+
+/// Records the dummy debug log entries read back by the integration tests.
+pub async fn record_test_debug_log() -> Result<(), CommandError> {
+    for index in 0..config::TEST_DEBUG_LOG_ENTRY_COUNT {
+        append_debug_entry(&config::test_debug_log_entry(index)).await?;
+    }
+    Ok(())
+}
+
+/// Stashes the dummy measurement read back from the attestation log by the integration tests.
+pub async fn record_test_attestation_log() -> Result<(), CommandError> {
+    MeasurementLog::stash_measurement(
+        config::TEST_ATTESTATION_LOG_METADATA,
+        &config::TEST_ATTESTATION_LOG_MEASUREMENT,
+        &[0; SHA384_HASH_SIZE],
+        config::TEST_ATTESTATION_LOG_SVN,
+    )
+    .await
+    .map_err(|_| CommandError::InternalError)
+}
@@ -9,8 +9,17 @@ pub mod fw_update_consts {
             PldmFirmwareVersion,
         },
     };
+    use pldm_lib::firmware_device::fd_ops::PackageDataPolicy;
 
     pub const FD_FW_COMPONENTS_COUNT: usize = 1;
+    // Packages without package data are accepted, in which case the SoC manifest of the image
+    // is used. Set require_authenticated_package_data to only accept packages whose package data
+    // is a SoC manifest signed with the keys provisioned in Caliptra. The signature area of the
+    // package is not checked by the device either way.
+    #[allow(unused)]
+    pub const PACKAGE_DATA_POLICY: PackageDataPolicy = PackageDataPolicy {
+        require_authenticated_package_data: false,
+    };
     #[allow(unused)]
     pub static FIRMWARE_PARAMS: LazyLock<FirmwareParameters> = LazyLock::new(|| {
         let active_firmware_string = PldmFirmwareString::new("UTF-8", "soc-fw-1.0").unwrap();
@@ -19,7 +28,8 @@ pub mod fw_update_consts {
         let pending_firmware_string = PldmFirmwareString::new("UTF-8", "soc-fw-1.1").unwrap();
         let pending_firmware_version =
             PldmFirmwareVersion::new(0x87654321, &pending_firmware_string, Some("20250213"));
-        let comp_activation_methods = ComponentActivationMethods(0x0001);
+        // Activated by ActivateFirmware with self-contained activation, or else at the next reset
+        let comp_activation_methods = ComponentActivationMethods(0x000A);
         let capabilities_during_update = FirmwareDeviceCapability(0x0010);
         let component_parameter_entry = ComponentParameterEntry::new(
             ComponentClassification::Firmware,
@@ -40,6 +40,7 @@ pub async fn firmware_update<D: DMAMapping>(dma_mapping: &D) -> Result<(), Error
         let fw_params = PldmFirmwareDeviceParams {
             descriptors: &config::fw_update_consts::DESCRIPTOR.get()[..],
             fw_params: config::fw_update_consts::FIRMWARE_PARAMS.get(),
+            package_data_policy: config::fw_update_consts::PACKAGE_DATA_POLICY,
         };
         let mut staging_memory = dummy_flash::ExternalFlash::new().await?;
         let staging_memory: &'static dummy_flash::ExternalFlash =
@@ -50,7 +51,7 @@ pub async fn firmware_update<D: DMAMapping>(dma_mapping: &D) -> Result<(), Error
             dma_mapping,
             EXECUTOR.get().spawner(),
         );
-        updater.start().await?;
+        run_updater(&mut updater).await?;
     }
 
     #[cfg(feature = "test-firmware-update-flash")]
@@ -58,6 +59,7 @@ pub async fn firmware_update<D: DMAMapping>(dma_mapping: &D) -> Result<(), Error
         let fw_params = PldmFirmwareDeviceParams {
             descriptors: &config::fw_update_consts::DESCRIPTOR.get()[..],
             fw_params: config::fw_update_consts::FIRMWARE_PARAMS.get(),
+            package_data_policy: config::fw_update_consts::PACKAGE_DATA_POLICY,
         };
         let mut staging_memory = flash_memory::ExternalFlash::new().await?;
         let staging_memory: &'static flash_memory::ExternalFlash =
@@ -68,7 +70,7 @@ pub async fn firmware_update<D: DMAMapping>(dma_mapping: &D) -> Result<(), Error
             dma_mapping,
             EXECUTOR.get().spawner(),
         );
-        updater.start().await?;
+        run_updater(&mut updater).await?;
     }
 
     // Trigger MCU warm reset to boot into new firmware
@@ -79,6 +81,25 @@ pub async fn firmware_update<D: DMAMapping>(dma_mapping: &D) -> Result<(), Error
     Ok(())
 }
 
+/// Runs firmware updates until one is activated. An update the UA did not ask to activate
+/// stays pending until the next reset while the PLDM service keeps accepting new updates.
+#[cfg(any(
+    feature = "test-firmware-update-streaming",
+    feature = "test-firmware-update-flash"
+))]
+async fn run_updater<D: DMAMapping>(updater: &mut FirmwareUpdater<'_, D>) -> Result<(), ErrorCode> {
+    updater.start().await?;
+    while updater.is_activation_deferred() {
+        writeln!(
+            Console::<DefaultSyscalls>::writer(),
+            "[FW Upd] Activation deferred to the next reset"
+        )
+        .unwrap();
+        updater.start().await?;
+    }
+    Ok(())
+}
+
 fn get_reset_reason() -> Result<u32, ErrorCode> {
     let mci = MciSyscall::<DefaultSyscalls>::new();
     let reason = mci.read(RESET_REASON, 0)?;
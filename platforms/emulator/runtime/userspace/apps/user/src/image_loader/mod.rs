@@ -3,9 +3,17 @@
 #[cfg(any(
     feature = "test-pldm-discovery",
     feature = "test-pldm-fw-update",
-    feature = "test-pldm-fw-update-e2e"
+    feature = "test-pldm-fw-update-e2e",
+    feature = "test-mctp-pcie-vdm",
+    feature = "test-mctp-serial",
+    feature = "test-pldm-platform",
+    feature = "test-pldm-fru"
 ))]
 mod pldm_fdops_mock;
+#[cfg(feature = "test-pldm-fru")]
+mod pldm_fru;
+#[cfg(feature = "test-pldm-platform")]
+mod pldm_platform;
 
 mod config;
 
@@ -74,6 +82,10 @@ pub async fn image_loading_task() {
         feature = "test-pldm-discovery",
         feature = "test-pldm-fw-update",
         feature = "test-pldm-fw-update-e2e",
+        feature = "test-mctp-pcie-vdm",
+        feature = "test-mctp-serial",
+        feature = "test-pldm-platform",
+        feature = "test-pldm-fru",
     ))]
     {
         // Release SRAM lock, in case previous session hasn't released it
@@ -208,11 +220,33 @@ async fn image_loading<D: DMAMapping>(dma_mapping: &'static D) -> Result<(), Err
     #[cfg(any(
         feature = "test-pldm-discovery",
         feature = "test-pldm-fw-update",
-        feature = "test-pldm-fw-update-e2e"
+        feature = "test-pldm-fw-update-e2e",
+        feature = "test-mctp-pcie-vdm",
+        feature = "test-mctp-serial",
+        feature = "test-pldm-platform",
+        feature = "test-pldm-fru"
     ))]
     {
         let fdops = pldm_fdops_mock::FdOpsObject::new();
+        #[cfg(not(any(feature = "test-pldm-platform", feature = "test-pldm-fru")))]
         let mut pldm_service = PldmService::init(&fdops, EXECUTOR.get().spawner());
+        #[cfg(all(feature = "test-pldm-platform", not(feature = "test-pldm-fru")))]
+        let mut pldm_service = {
+            // Run the FIPS self-test reported by the platform sensors
+            mcu_mbox_lib::fips_periodic::set_enabled(true);
+            PldmService::init_with_platform(
+                &fdops,
+                pldm_platform::platform_context(),
+                EXECUTOR.get().spawner(),
+            )
+        };
+        #[cfg(feature = "test-pldm-fru")]
+        let mut pldm_service = PldmService::init_with_fru(
+            &fdops,
+            None,
+            pldm_fru::fru_context(),
+            EXECUTOR.get().spawner(),
+        );
         writeln!(
             console_writer,
             "PLDM_APP: Starting PLDM service for testing..."
@@ -8,6 +8,7 @@ use core::cell::RefCell;
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::lazy_lock::LazyLock;
 use embassy_sync::signal::Signal;
+use pldm_common::message::firmware_update::activate_fw::SelfContainedActivationRequest;
 use pldm_common::message::firmware_update::apply_complete::ApplyResult;
 use pldm_common::message::firmware_update::get_fw_params::FirmwareParameters;
 use pldm_common::message::firmware_update::get_status::ProgressPercent;
@@ -243,13 +244,16 @@ impl FdOps for FdOpsObject {
 
     fn activate(
         &self,
-        self_contained_activation: u8,
+        self_contained_activation: SelfContainedActivationRequest,
         estimated_time: &mut u16,
     ) -> Result<u8, FdOpsError> {
-        if self_contained_activation == 1 {
+        if self_contained_activation
+            == SelfContainedActivationRequest::ActivateSelfContainedComponents
+        {
             *estimated_time = TEST_SELF_ACTIVATION_MAX_TIME_IN_SECONDS;
+            PLDM_DONE_SIGNAL.signal(());
         }
-        PLDM_DONE_SIGNAL.signal(());
+        // Otherwise, keep the PLDM service running so that the UA can read the pending image
         Ok(0) // PLDM completion code for success
     }
 
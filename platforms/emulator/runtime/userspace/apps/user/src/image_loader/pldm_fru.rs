@@ -0,0 +1,51 @@
+// Licensed under the Apache-2.0 license
+
+//! FRU record table of the MCU reported through PLDM for FRU Data.
+//!
+//! The table holds a single General FRU record built from the device ID, the firmware
+//! versions and the unique chip ID, the same values as reported by the external commands.
+
+use embassy_sync::lazy_lock::LazyLock;
+use external_cmds_common::{DeviceId, FirmwareVersion, Uid, MAX_FW_VERSION_LEN, MAX_UID_LEN};
+use mcu_mbox_common::config;
+use pldm_lib::fru::fru_context::FruContext;
+use pldm_lib::fru::fru_table::{DeviceIdentity, FruTable};
+
+/// Product name reported in the Name field of the General FRU record.
+pub const PRODUCT_NAME: &str = "Caliptra MCU";
+
+static FRU_TABLE: LazyLock<FruTable> = LazyLock::new(|| {
+    let device_id = DeviceId {
+        vendor_id: config::TEST_DEVICE_ID.vendor_id,
+        device_id: config::TEST_DEVICE_ID.device_id,
+        subsystem_vendor_id: config::TEST_DEVICE_ID.subsystem_vendor_id,
+        subsystem_id: config::TEST_DEVICE_ID.subsystem_id,
+    };
+    let firmware_versions = config::TEST_FIRMWARE_VERSIONS.map(firmware_version);
+    let mut uid = Uid {
+        len: config::TEST_UID.len().min(MAX_UID_LEN),
+        ..Default::default()
+    };
+    uid.unique_chip_id[..uid.len].copy_from_slice(&config::TEST_UID[..uid.len]);
+
+    FruTable::from_device_identity(&DeviceIdentity {
+        name: PRODUCT_NAME,
+        device_id: &device_id,
+        firmware_versions: &firmware_versions,
+        unique_chip_id: Some(&uid),
+    })
+    .unwrap()
+});
+
+/// Creates the FRU context serving the FRU record table of the MCU.
+pub fn fru_context() -> FruContext<'static> {
+    FruContext::new(FRU_TABLE.get())
+}
+
+fn firmware_version(version: &str) -> FirmwareVersion {
+    let mut firmware_version = FirmwareVersion::default();
+    let len = version.len().min(MAX_FW_VERSION_LEN);
+    firmware_version.ver_str[..len].copy_from_slice(&version.as_bytes()[..len]);
+    firmware_version.len = len;
+    firmware_version
+}
@@ -0,0 +1,243 @@
+// Licensed under the Apache-2.0 license
+
+//! Sensors of the MCU reported through PLDM for Platform Monitoring and Control.
+//!
+//! - Boot status: a state sensor reporting the reason of the last reset, read from MCI.
+//! - FIPS self-test: a state sensor reporting the result of the periodic FIPS self-test.
+//! - Log fill level: a numeric sensor reporting the percentage of the flash log in use.
+//!
+//! The state sets are OEM state sets, as DSP0249 has none for these states.
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use embassy_sync::lazy_lock::LazyLock;
+use libsyscall_caliptra::logging::LoggingSyscall;
+use libsyscall_caliptra::mci::{mci_reg::RESET_REASON, Mci};
+use libsyscall_caliptra::DefaultSyscalls;
+use mcu_mbox_lib::fips_periodic;
+use pldm_common::codec::PldmCodec;
+use pldm_common::protocol::platform::{
+    NumericSensorPdr, NumericSensorState, PdrHeader, PdrType, PossibleStates, SensorId,
+    SensorOperationalState, SensorValue, StateSensorPdr, PLDM_PDR_HEADER_LEN,
+};
+use pldm_lib::platform::pdr_repo::StaticPdrRepository;
+use pldm_lib::platform::platform_context::PlatformContext;
+use pldm_lib::platform::sensor_ops::{
+    NumericSensor, NumericSensorReading, SensorOpsError, StateSensor, StateSensorReading,
+};
+
+pub const BOOT_STATUS_SENSOR_ID: SensorId = 1;
+pub const FIPS_SELF_TEST_SENSOR_ID: SensorId = 2;
+pub const LOG_FILL_LEVEL_SENSOR_ID: SensorId = 3;
+
+// State set IDs from 0x8000 are OEM state sets
+pub const BOOT_STATUS_STATE_SET_ID: u16 = 0x8000;
+pub const FIPS_SELF_TEST_STATE_SET_ID: u16 = 0x8001;
+
+/// Percentage of the log in use above which the log fill level is reported as a warning.
+pub const LOG_FILL_LEVEL_WARNING_PERCENT: u32 = 90;
+
+const PDR_REPOSITORY_SIZE: usize = 256;
+
+const RESET_REASON_FW_HITLESS_UPD_RESET_MASK: u32 = 0x1;
+const RESET_REASON_FW_BOOT_UPD_RESET_MASK: u32 = 0x2;
+const RESET_REASON_WARM_RESET_MASK: u32 = 0x4;
+
+// Bit of the upper warning threshold in the supportedThresholds field of the Numeric Sensor PDR
+const UPPER_THRESHOLD_WARNING: u8 = 0x1;
+
+/// States of the boot status state set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum BootStatus {
+    ColdBoot = 1,
+    WarmReset = 2,
+    FirmwareBootUpdate = 3,
+    FirmwareHitlessUpdate = 4,
+}
+
+/// States of the FIPS self-test state set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum FipsSelfTestState {
+    NotRun = 1,
+    InProgress = 2,
+    Passed = 3,
+    Failed = 4,
+}
+
+static PDR_RECORDS: LazyLock<([u8; PDR_REPOSITORY_SIZE], usize)> = LazyLock::new(|| {
+    let mut records = [0u8; PDR_REPOSITORY_SIZE];
+    let len = encode_pdrs(&mut records);
+    (records, len)
+});
+
+static PDR_REPOSITORY: LazyLock<StaticPdrRepository<'static>> = LazyLock::new(|| {
+    let (records, len) = PDR_RECORDS.get();
+    StaticPdrRepository::new(&records[..*len]).unwrap()
+});
+
+/// Creates the platform context serving the PDRs and the sensors of the MCU.
+pub fn platform_context() -> PlatformContext<'static> {
+    PlatformContext::new(
+        PDR_REPOSITORY.get(),
+        &[&LogFillLevelSensor],
+        &[&BootStatusSensor, &FipsSelfTestSensor],
+    )
+    .unwrap()
+}
+
+/// Encodes the PDRs of the sensors into `records` and returns their size.
+fn encode_pdrs(records: &mut [u8]) -> usize {
+    let boot_status = StateSensorPdr::new(
+        1,
+        0,
+        BOOT_STATUS_SENSOR_ID,
+        0,
+        0,
+        0,
+        &[PossibleStates::new(
+            BOOT_STATUS_STATE_SET_ID,
+            &[
+                BootStatus::ColdBoot as u8,
+                BootStatus::WarmReset as u8,
+                BootStatus::FirmwareBootUpdate as u8,
+                BootStatus::FirmwareHitlessUpdate as u8,
+            ],
+        )
+        .unwrap()],
+    )
+    .unwrap();
+    let fips_self_test = StateSensorPdr::new(
+        2,
+        0,
+        FIPS_SELF_TEST_SENSOR_ID,
+        0,
+        0,
+        0,
+        &[PossibleStates::new(
+            FIPS_SELF_TEST_STATE_SET_ID,
+            &[
+                FipsSelfTestState::NotRun as u8,
+                FipsSelfTestState::InProgress as u8,
+                FipsSelfTestState::Passed as u8,
+                FipsSelfTestState::Failed as u8,
+            ],
+        )
+        .unwrap()],
+    )
+    .unwrap();
+    let log_fill_level = NumericSensorPdr {
+        hdr: PdrHeader::new(
+            3,
+            PdrType::NumericSensor,
+            (core::mem::size_of::<NumericSensorPdr>() - PLDM_PDR_HEADER_LEN) as u16,
+        ),
+        sensor_id: LOG_FILL_LEVEL_SENSOR_ID,
+        supported_thresholds: UPPER_THRESHOLD_WARNING,
+        max_readable: 100,
+        warning_high: LOG_FILL_LEVEL_WARNING_PERCENT,
+        ..Default::default()
+    };
+
+    let mut len = boot_status.encode(records).unwrap();
+    len += fips_self_test.encode(&mut records[len..]).unwrap();
+    len += log_fill_level.encode(&mut records[len..]).unwrap();
+    len
+}
+
+/// Reports why the MCU was last reset.
+pub struct BootStatusSensor;
+
+#[async_trait(?Send)]
+impl StateSensor for BootStatusSensor {
+    fn sensor_id(&self) -> SensorId {
+        BOOT_STATUS_SENSOR_ID
+    }
+
+    fn composite_sensor_count(&self) -> usize {
+        1
+    }
+
+    async fn read(&self, readings: &mut [StateSensorReading]) -> Result<(), SensorOpsError> {
+        let reset_reason = Mci::<DefaultSyscalls>::new()
+            .read(RESET_REASON, 0)
+            .map_err(|_| SensorOpsError::ReadError)?;
+        let status = if reset_reason & RESET_REASON_FW_HITLESS_UPD_RESET_MASK != 0 {
+            BootStatus::FirmwareHitlessUpdate
+        } else if reset_reason & RESET_REASON_FW_BOOT_UPD_RESET_MASK != 0 {
+            BootStatus::FirmwareBootUpdate
+        } else if reset_reason & RESET_REASON_WARM_RESET_MASK != 0 {
+            BootStatus::WarmReset
+        } else {
+            BootStatus::ColdBoot
+        };
+        readings[0] = StateSensorReading {
+            operational_state: SensorOperationalState::Enabled,
+            present_state: status as u8,
+        };
+        Ok(())
+    }
+}
+
+/// Reports the result of the periodic FIPS self-test of Caliptra.
+pub struct FipsSelfTestSensor;
+
+#[async_trait(?Send)]
+impl StateSensor for FipsSelfTestSensor {
+    fn sensor_id(&self) -> SensorId {
+        FIPS_SELF_TEST_SENSOR_ID
+    }
+
+    fn composite_sensor_count(&self) -> usize {
+        1
+    }
+
+    async fn read(&self, readings: &mut [StateSensorReading]) -> Result<(), SensorOpsError> {
+        let (enabled, _iterations, last_result) = fips_periodic::get_status();
+        let state = match last_result {
+            fips_periodic::RESULT_PASS => FipsSelfTestState::Passed,
+            fips_periodic::RESULT_FAIL => FipsSelfTestState::Failed,
+            _ if enabled => FipsSelfTestState::InProgress,
+            _ => FipsSelfTestState::NotRun,
+        };
+        readings[0] = StateSensorReading {
+            operational_state: SensorOperationalState::Enabled,
+            present_state: state as u8,
+        };
+        Ok(())
+    }
+}
+
+/// Reports the percentage of the flash log in use.
+pub struct LogFillLevelSensor;
+
+#[async_trait(?Send)]
+impl NumericSensor for LogFillLevelSensor {
+    fn sensor_id(&self) -> SensorId {
+        LOG_FILL_LEVEL_SENSOR_ID
+    }
+
+    async fn read(&self) -> Result<NumericSensorReading, SensorOpsError> {
+        let log: LoggingSyscall = LoggingSyscall::new();
+        let capacity = log.get_capacity().map_err(|_| SensorOpsError::ReadError)?;
+        let used = log.get_used_size().map_err(|_| SensorOpsError::ReadError)?;
+        if capacity == 0 {
+            return Err(SensorOpsError::ReadError);
+        }
+
+        let percent = (used.min(capacity) as u64 * 100 / capacity as u64) as u32;
+        let present_state = if percent >= LOG_FILL_LEVEL_WARNING_PERCENT {
+            NumericSensorState::UpperWarning
+        } else {
+            NumericSensorState::Normal
+        };
+        Ok(NumericSensorReading {
+            operational_state: SensorOperationalState::Enabled,
+            present_state,
+            value: SensorValue::Uint32(percent),
+        })
+    }
+}
@@ -11,6 +11,14 @@ use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 #[allow(unused)]
 use embassy_sync::{lazy_lock::LazyLock, signal::Signal};
 use libtockasync::TockExecutor;
+#[cfg(any(
+    feature = "test-mcu-mbox-cmds",
+    feature = "test-mcu-mbox-fips-self-test",
+    feature = "test-mcu-mbox-fips-periodic",
+    feature = "test-caliptra-util-host-validator",
+    feature = "test-mctp-vdm-cmds"
+))]
+mod device_logs;
 #[cfg(any(
     feature = "test-firmware-update-streaming",
     feature = "test-firmware-update-flash"
@@ -101,7 +109,10 @@ pub(crate) async fn async_main() {
         .spawn(mcu_mbox::mcu_mbox_task())
         .unwrap();
 
-    #[cfg(feature = "test-mcu-mbox-fips-periodic")]
+    #[cfg(any(
+        feature = "test-mcu-mbox-fips-periodic",
+        feature = "test-pldm-platform"
+    ))]
     EXECUTOR
         .get()
         .spawner()
@@ -2,11 +2,13 @@
 
 extern crate alloc;
 
+use crate::device_logs::{self, LogReader};
 use alloc::boxed::Box;
 use async_trait::async_trait;
 use external_cmds_common::{
-    CommandError, DeviceCapabilities, DeviceId, DeviceInfo, FirmwareVersion, Uid,
-    UnifiedCommandHandler, MAX_FW_VERSION_LEN, MAX_UID_LEN,
+    CertificateStatus, CommandError, DebugUnlockChallenge, DeviceCapabilities, DeviceId,
+    DeviceInfo, FirmwareVersion, LogType, Uid, UnifiedCommandHandler, MAX_FW_VERSION_LEN,
+    MAX_UID_LEN,
 };
 use mcu_mbox_common::config;
 
@@ -16,8 +18,9 @@ pub struct NonCryptoCmdHandlerMock;
 /// Mock implementation of the `UnifiedCommandHandler` trait.
 ///
 /// This handler provides mock responses for firmware version queries,
-/// device ID, device information, and device capabilities. Intended to use for
-/// integration testing on the emulator platform.
+/// device ID, device information, and device capabilities. The log commands access
+/// the flash debug log and the measurement log shared with the MCTP VDM handler.
+/// Intended to use for integration testing on the emulator platform.
 #[async_trait]
 impl UnifiedCommandHandler for NonCryptoCmdHandlerMock {
     async fn get_firmware_version(
@@ -84,4 +87,47 @@ impl UnifiedCommandHandler for NonCryptoCmdHandlerMock {
         capabilities.reserved = test_capabilities.reserved;
         Ok(())
     }
+
+    // The IDevID certificate commands are only provisioned over MCTP VDM
+    async fn export_idev_csr(&self, _index: u32, _csr: &mut [u8]) -> Result<usize, CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn import_idev_cert(&self, _cert: &[u8]) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn get_cert_state(&self, _status: &mut CertificateStatus) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn get_log(
+        &self,
+        log_type: LogType,
+        offset: usize,
+        data: &mut [u8],
+    ) -> Result<usize, CommandError> {
+        device_logs::get_log(LogReader::McuMbox, log_type, offset, data).await
+    }
+
+    async fn clear_log(&self, log_type: LogType) -> Result<(), CommandError> {
+        device_logs::clear_log(log_type).await
+    }
+
+    // Production debug unlock is only requested over MCTP VDM
+    async fn request_debug_unlock(
+        &self,
+        _unlock_level: u8,
+        _challenge: &mut DebugUnlockChallenge,
+    ) -> Result<(), CommandError> {
+        Err(CommandError::NotSupported)
+    }
+
+    async fn authorize_debug_unlock_token(
+        &self,
+        _offset: usize,
+        _data: &[u8],
+    ) -> Result<Option<u8>, CommandError> {
+        Err(CommandError::NotSupported)
+    }
 }
@@ -39,6 +39,15 @@ async fn start_mcu_mbox_service() -> Result<(), ErrorCode> {
         feature = "test-caliptra-util-host-validator"
     ))]
     {
+        // Record the debug log entries read back by the MC_GET_LOG tests
+        if crate::device_logs::record_test_debug_log().await.is_err() {
+            writeln!(
+                console_writer,
+                "USER_APP: Failed to record the test debug log"
+            )
+            .unwrap();
+        }
+
         let handler = cmd_handler_mock::NonCryptoCmdHandlerMock::default();
         let mut transport = mcu_mbox_lib::transport::McuMboxTransport::new(
             libsyscall_caliptra::mcu_mbox::MCU_MBOX0_DRIVER_NUM,
@@ -3,12 +3,22 @@
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::mutex::Mutex;
 use libapi_caliptra::certificate::CertContext;
-use libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
-use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use libapi_caliptra::crypto::asym::{
+    AsymAlgo, ECC_P384_PARAM_X_SIZE, ECC_P384_PARAM_Y_SIZE, ECC_P384_SIGNATURE_SIZE,
+};
+use libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
+use libapi_caliptra::error::CaliptraApiError;
 use spdm_lib::cert_store::{CertStoreError, CertStoreResult};
+use spdm_lib::csr::{
+    csr_subject_public_key_info, encode_certification_request_info,
+    encode_ecdsa_p384_certification_request, signed_data_content, CsrError, RequesterInfo,
+};
 
 const DPE_LEAF_CERT_SIZE: usize = 2048; // Size of the DPE leaf certificate buffer.
 
+// Size of the SubjectPublicKeyInfo buffer, enough for an ECC P-384 key
+const MAX_SPKI_SIZE: usize = 128;
+
 pub const DPE_LEAF_CERT_LABEL: [u8; SHA384_HASH_SIZE] = [
     0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
     0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
@@ -57,24 +67,108 @@ impl DpeLeafCert {
         &self,
         asym_algo: AsymAlgo,
         hash: &[u8; SHA384_HASH_SIZE],
-        signature: &mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &mut [u8],
     ) -> CertStoreResult<()> {
         let dpe_leaf = SHARED_DPE_LEAF_CERT.lock().await;
         dpe_leaf.sign(asym_algo, hash, signature).await
     }
+
+    /// Returns the leaf public key as the affine coordinates X || Y.
+    pub async fn public_key(
+        &self,
+        asym_algo: AsymAlgo,
+        public_key: &mut [u8],
+    ) -> CertStoreResult<usize> {
+        let mut dpe_leaf = SHARED_DPE_LEAF_CERT.lock().await;
+        if dpe_leaf.size().is_none() {
+            dpe_leaf.fetch_cert(asym_algo).await?;
+        }
+        let key = dpe_leaf.public_key();
+        public_key
+            .get_mut(..key.len())
+            .ok_or(CertStoreError::BufferTooSmall)?
+            .copy_from_slice(key);
+        Ok(key.len())
+    }
+
+    /// Generates a PKCS#10 CSR for the leaf key so that a certificate for it can be
+    /// issued and installed into a writable slot. The subject and attributes requested
+    /// in `requester_info` replace the ones chosen by DPE.
+    pub async fn csr(
+        &self,
+        asym_algo: AsymAlgo,
+        requester_info: &[u8],
+        csr: &mut [u8],
+    ) -> CertStoreResult<usize> {
+        if asym_algo != AsymAlgo::EccP384 {
+            return Err(CertStoreError::UnsupportedAsymAlgo);
+        }
+        let mut cert_ctx = CertContext::new();
+        let cms_len = cert_ctx
+            .certify_key_csr(csr, Some(&DPE_LEAF_CERT_LABEL))
+            .await
+            .map_err(CertStoreError::CaliptraApi)?;
+
+        // DPE returns the CSR wrapped in a CMS SignedData
+        let dpe_csr = signed_data_content(&csr[..cms_len]).map_err(csr_error)?;
+        if requester_info.is_empty() {
+            let csr_len = dpe_csr.len();
+            csr.copy_within(dpe_csr, 0);
+            return Ok(csr_len);
+        }
+
+        let spki = csr_subject_public_key_info(&csr[dpe_csr]).map_err(csr_error)?;
+        let mut spki_buf = [0u8; MAX_SPKI_SIZE];
+        spki_buf
+            .get_mut(..spki.len())
+            .ok_or(CertStoreError::BufferTooSmall)?
+            .copy_from_slice(spki);
+        let spki = &spki_buf[..spki.len()];
+
+        // The CSR is re-signed with the leaf key over the requested subject and attributes
+        let requester_info = RequesterInfo::parse(requester_info).map_err(csr_error)?;
+        let info_len =
+            encode_certification_request_info(&requester_info, spki, csr).map_err(csr_error)?;
+
+        let mut info_hash = [0u8; SHA384_HASH_SIZE];
+        let mut hash_ctx = HashContext::new();
+        hash_ctx
+            .init(HashAlgoType::SHA384, None)
+            .await
+            .map_err(CertStoreError::CaliptraApi)?;
+        hash_ctx
+            .update(&csr[..info_len])
+            .await
+            .map_err(CertStoreError::CaliptraApi)?;
+        hash_ctx
+            .finalize(&mut info_hash)
+            .await
+            .map_err(CertStoreError::CaliptraApi)?;
+
+        let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
+        self.sign(asym_algo, &info_hash, &mut signature).await?;
+
+        encode_ecdsa_p384_certification_request(csr, info_len, &signature).map_err(csr_error)
+    }
+}
+
+fn csr_error(e: CsrError) -> CertStoreError {
+    match e {
+        CsrError::BufferTooSmall => CertStoreError::BufferTooSmall,
+        CsrError::InvalidFormat => CertStoreError::CaliptraApi(CaliptraApiError::InvalidResponse),
+    }
 }
 
 struct DpeLeafCertBuf {
     buffer: [u8; DPE_LEAF_CERT_SIZE],
+    // Public key certified by the leaf certificate: X || Y
+    public_key: [u8; ECC_P384_PARAM_X_SIZE + ECC_P384_PARAM_Y_SIZE],
     size: Option<usize>,
 }
 
 impl Default for DpeLeafCertBuf {
     fn default() -> Self {
-        Self {
-            buffer: [0; DPE_LEAF_CERT_SIZE],
-            size: None,
-        }
+        Self::new()
     }
 }
 
@@ -82,12 +176,14 @@ impl DpeLeafCertBuf {
     const fn new() -> Self {
         Self {
             buffer: [0; DPE_LEAF_CERT_SIZE],
+            public_key: [0; ECC_P384_PARAM_X_SIZE + ECC_P384_PARAM_Y_SIZE],
             size: None,
         }
     }
 
     fn reset(&mut self) {
         self.buffer.fill(0);
+        self.public_key.fill(0);
         self.size = None;
     }
 
@@ -100,8 +196,14 @@ impl DpeLeafCertBuf {
 
         let mut cert_ctx = CertContext::new();
 
+        let (x, y) = self.public_key.split_at_mut(ECC_P384_PARAM_X_SIZE);
         let size = cert_ctx
-            .certify_key(&mut self.buffer, Some(&DPE_LEAF_CERT_LABEL), None, None)
+            .certify_key(
+                &mut self.buffer,
+                Some(&DPE_LEAF_CERT_LABEL),
+                Some(x),
+                Some(y),
+            )
             .await
             .map_err(CertStoreError::CaliptraApi)?;
 
@@ -118,6 +220,10 @@ impl DpeLeafCertBuf {
         self.size
     }
 
+    fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
     fn read(&self, offset: usize, buf: &mut [u8]) -> CertStoreResult<usize> {
         if offset >= self.size.unwrap_or(0) {
             return Err(CertStoreError::InvalidOffset);
@@ -131,7 +237,7 @@ impl DpeLeafCertBuf {
         &self,
         asym_algo: AsymAlgo,
         hash: &[u8; SHA384_HASH_SIZE],
-        signature: &mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &mut [u8],
     ) -> CertStoreResult<()> {
         if asym_algo != AsymAlgo::EccP384 {
             return Err(CertStoreError::UnsupportedAsymAlgo);
@@ -0,0 +1,159 @@
+// Licensed under the Apache-2.0 license
+
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use libapi_caliptra::certificate::{CertContext, MAX_MLDSA87_CERT_SIZE};
+use libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
+use libapi_caliptra::error::CaliptraApiError;
+use spdm_lib::cert_store::{CertStoreError, CertStoreResult};
+
+// LDevID, FMC alias and RT alias certificates, from the root to the signing key.
+const MLDSA_CERT_COUNT: usize = 3;
+
+static SHARED_MLDSA_CERT_CHAIN: Mutex<CriticalSectionRawMutex, MldsaCertChainBuf> =
+    Mutex::new(MldsaCertChainBuf::new());
+
+/// ML-DSA-87 certificate chain of the Caliptra RT alias key.
+///
+/// The certificates are too large to be cached together, so only their lengths and
+/// the root certificate hash are kept. Each certificate is fetched from Caliptra
+/// into a single scratch buffer when its content is read.
+pub(crate) struct MldsaCertChain;
+
+impl MldsaCertChain {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn refresh(&self) {
+        let mut chain = SHARED_MLDSA_CERT_CHAIN.lock().await;
+        chain.reset();
+    }
+
+    pub async fn size(&self) -> CertStoreResult<usize> {
+        let mut chain = SHARED_MLDSA_CERT_CHAIN.lock().await;
+        let cert_lens = chain.cert_lens().await?;
+        Ok(cert_lens.iter().sum())
+    }
+
+    pub async fn read(&self, offset: usize, buf: &mut [u8]) -> CertStoreResult<usize> {
+        let mut chain = SHARED_MLDSA_CERT_CHAIN.lock().await;
+        chain.read(offset, buf).await
+    }
+
+    pub async fn root_cert_hash(
+        &self,
+        cert_hash: &mut [u8; SHA384_HASH_SIZE],
+    ) -> CertStoreResult<()> {
+        let mut chain = SHARED_MLDSA_CERT_CHAIN.lock().await;
+        chain.cert_lens().await?;
+        cert_hash.copy_from_slice(&chain.root_hash);
+        Ok(())
+    }
+
+    pub async fn sign(
+        &self,
+        hash: &[u8; SHA384_HASH_SIZE],
+        signature: &mut [u8],
+    ) -> CertStoreResult<()> {
+        let mut cert_ctx = CertContext::new();
+        cert_ctx
+            .sign_mldsa87(hash, signature)
+            .await
+            .map_err(CertStoreError::CaliptraApi)?;
+        Ok(())
+    }
+}
+
+struct MldsaCertChainBuf {
+    buffer: [u8; MAX_MLDSA87_CERT_SIZE],
+    cert_lens: Option<[usize; MLDSA_CERT_COUNT]>,
+    root_hash: [u8; SHA384_HASH_SIZE],
+}
+
+impl MldsaCertChainBuf {
+    const fn new() -> Self {
+        Self {
+            buffer: [0; MAX_MLDSA87_CERT_SIZE],
+            cert_lens: None,
+            root_hash: [0; SHA384_HASH_SIZE],
+        }
+    }
+
+    fn reset(&mut self) {
+        self.buffer.fill(0);
+        self.cert_lens = None;
+        self.root_hash.fill(0);
+    }
+
+    async fn fetch_cert(&mut self, index: usize) -> CertStoreResult<usize> {
+        let mut cert_ctx = CertContext::new();
+        let result = match index {
+            0 => cert_ctx.get_ldev_mldsa87_cert(&mut self.buffer).await,
+            1 => cert_ctx.get_fmc_alias_mldsa87_cert(&mut self.buffer).await,
+            _ => cert_ctx.get_rt_alias_mldsa87_cert(&mut self.buffer).await,
+        };
+        result.map_err(CertStoreError::CaliptraApi)
+    }
+
+    /// Returns the length of each certificate, fetching the chain on first use.
+    async fn cert_lens(&mut self) -> CertStoreResult<[usize; MLDSA_CERT_COUNT]> {
+        if let Some(cert_lens) = self.cert_lens {
+            return Ok(cert_lens);
+        }
+
+        let mut cert_lens = [0; MLDSA_CERT_COUNT];
+        // Fetch the root certificate last so that it is left in the buffer for hashing
+        for index in (0..MLDSA_CERT_COUNT).rev() {
+            cert_lens[index] = self.fetch_cert(index).await?;
+        }
+
+        let mut root_hash = [0; SHA384_HASH_SIZE];
+        while let Err(e) = HashContext::hash_all(
+            HashAlgoType::SHA384,
+            &self.buffer[..cert_lens[0]],
+            &mut root_hash,
+        )
+        .await
+        {
+            match e {
+                CaliptraApiError::MailboxBusy => continue, // Retry if the mailbox is busy
+                _ => Err(CertStoreError::CaliptraApi(e))?,
+            }
+        }
+
+        self.root_hash = root_hash;
+        self.cert_lens = Some(cert_lens);
+        Ok(cert_lens)
+    }
+
+    async fn read(&mut self, offset: usize, buf: &mut [u8]) -> CertStoreResult<usize> {
+        let cert_lens = self.cert_lens().await?;
+        let total_len: usize = cert_lens.iter().sum();
+        if offset >= total_len {
+            return Err(CertStoreError::InvalidOffset);
+        }
+
+        let mut to_read = buf.len().min(total_len - offset);
+        let mut cert_offset = offset;
+        let mut pos = 0;
+
+        for (index, cert_len) in cert_lens.iter().enumerate() {
+            if to_read == 0 {
+                break;
+            }
+            if cert_offset >= *cert_len {
+                cert_offset -= cert_len;
+                continue;
+            }
+
+            self.fetch_cert(index).await?;
+            let len = (cert_len - cert_offset).min(to_read);
+            buf[pos..pos + len].copy_from_slice(&self.buffer[cert_offset..cert_offset + len]);
+            to_read -= len;
+            pos += len;
+            cert_offset = 0;
+        }
+        Ok(pos)
+    }
+}
@@ -6,6 +6,9 @@
 //! - Endorsement component (trait)
 //! - Device certificate chain component (standard implementation)
 //! - Leaf certificate component (standard implementation and shared between all slots)
+//!
+//! The ML-DSA-87 certificate chain of the Caliptra RT alias key is served instead
+//! when ML-DSA-87 is the negotiated asymmetric algorithm.
 
 // Endorsement certchain portion
 pub mod endorsement;
@@ -13,12 +16,15 @@ pub mod endorsement;
 pub(crate) mod device;
 // Leaf certchain portion
 pub(crate) mod leaf;
+// ML-DSA-87 certchain
+pub(crate) mod mldsa;
 
 // Re-export all the public types from submodules
 use crate::spdm::cert_store::cert_chain::device::{DeviceCertIndex, DpeCertChain};
 pub use crate::spdm::cert_store::cert_chain::endorsement::EndorsementCertChainTrait;
 use crate::spdm::cert_store::cert_chain::leaf::DpeLeafCert;
-use libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
+use crate::spdm::cert_store::cert_chain::mldsa::MldsaCertChain;
+use libapi_caliptra::crypto::asym::AsymAlgo;
 use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use spdm_lib::cert_store::CertStoreError;
 use spdm_lib::cert_store::CertStoreResult;
@@ -28,6 +34,7 @@ pub struct CertChain {
     endorsement_cert_chain: &'static mut dyn EndorsementCertChainTrait,
     dpe_cert_chain: DpeCertChain,
     leaf_cert: DpeLeafCert,
+    mldsa_cert_chain: MldsaCertChain,
 }
 
 impl CertChain {
@@ -39,6 +46,7 @@ impl CertChain {
             endorsement_cert_chain,
             dpe_cert_chain: DpeCertChain::new(device_cert_id),
             leaf_cert: DpeLeafCert::new(),
+            mldsa_cert_chain: MldsaCertChain::new(),
         }
     }
 
@@ -47,9 +55,14 @@ impl CertChain {
         self.endorsement_cert_chain.refresh().await;
         self.dpe_cert_chain.refresh();
         self.leaf_cert.refresh().await;
+        self.mldsa_cert_chain.refresh().await;
     }
 
     pub async fn size(&mut self, asym_algo: AsymAlgo) -> CertStoreResult<usize> {
+        if asym_algo == AsymAlgo::MlDsa87 {
+            return self.mldsa_cert_chain.size().await;
+        }
+
         let endorsement_len = self.endorsement_cert_chain.size(asym_algo).await?;
         let dpe_len = self.dpe_cert_chain.size(asym_algo).await?;
         let leaf_len = self.leaf_cert.size(asym_algo).await?;
@@ -64,6 +77,10 @@ impl CertChain {
         offset: usize,
         buf: &mut [u8],
     ) -> CertStoreResult<usize> {
+        if asym_algo == AsymAlgo::MlDsa87 {
+            return self.mldsa_cert_chain.read(offset, buf).await;
+        }
+
         let root_cert_chain_len = self.endorsement_cert_chain.size(asym_algo).await?;
         let dpe_cert_chain_len = self.dpe_cert_chain.size(asym_algo).await?;
         let leaf_cert_len = self.leaf_cert.size(asym_algo).await?;
@@ -115,6 +132,10 @@ impl CertChain {
         asym_algo: AsymAlgo,
         cert_hash: &mut [u8; SHA384_HASH_SIZE],
     ) -> CertStoreResult<()> {
+        if asym_algo == AsymAlgo::MlDsa87 {
+            return self.mldsa_cert_chain.root_cert_hash(cert_hash).await;
+        }
+
         self.endorsement_cert_chain
             .root_cert_hash(asym_algo, cert_hash)
             .await
@@ -124,8 +145,12 @@ impl CertChain {
         &self,
         asym_algo: AsymAlgo,
         hash: &'a [u8; SHA384_HASH_SIZE],
-        signature: &'a mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &'a mut [u8],
     ) -> CertStoreResult<()> {
+        if asym_algo == AsymAlgo::MlDsa87 {
+            return self.mldsa_cert_chain.sign(hash, signature).await;
+        }
+
         self.leaf_cert.sign(asym_algo, hash, signature).await
     }
 }
@@ -0,0 +1,174 @@
+// Licensed under the Apache-2.0 license
+
+//! Certificate chains installed in the field with SET_CERTIFICATE.
+//!
+//! Each writable slot owns a fixed region of the cert store flash partition:
+//! InstalledCertChainHeader | Certificates (DER)
+//! The header is written last so an interrupted install leaves the slot unprovisioned.
+
+use crate::spdm::cert_store::cert_chain::leaf::DpeLeafCert;
+use core::mem::size_of;
+use libapi_caliptra::crypto::asym::AsymAlgo;
+use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use libsyscall_caliptra::flash::SpiFlash;
+use mcu_config_emulator::flash::{
+    CERT_STORE_PARTITION, SPDM_CERT_CHAINS_REGION_OFFSET, SPDM_CERT_CHAINS_REGION_SIZE,
+};
+use spdm_lib::cert_store::{CertStoreError, CertStoreResult, MAX_CERT_SLOTS_SUPPORTED};
+use zerocopy::{FromBytes, FromZeros, Immutable, IntoBytes};
+
+const SLOT_REGION_SIZE: usize = SPDM_CERT_CHAINS_REGION_SIZE / MAX_CERT_SLOTS_SUPPORTED as usize;
+const INSTALLED_CERT_CHAIN_MAGIC: u32 = 0x5350_4343; // "SPCC"
+const HEADER_SIZE: usize = size_of::<InstalledCertChainHeader>();
+pub const MAX_INSTALLED_CERT_CHAIN_SIZE: usize = SLOT_REGION_SIZE - HEADER_SIZE;
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct InstalledCertChainHeader {
+    magic: u32,
+    cert_chain_len: u32,
+    root_hash: [u8; SHA384_HASH_SIZE],
+}
+
+/// Certificate chain stored in the cert store flash partition.
+/// The leaf certificate certifies the DPE leaf key, which is used for signing.
+pub(crate) struct InstalledCertChain {
+    slot_id: u8,
+    cert_chain_len: usize,
+    root_hash: [u8; SHA384_HASH_SIZE],
+    leaf_key: DpeLeafCert,
+}
+
+fn slot_region_offset(slot_id: u8) -> usize {
+    SPDM_CERT_CHAINS_REGION_OFFSET + slot_id as usize * SLOT_REGION_SIZE
+}
+
+fn cert_store_flash() -> SpiFlash {
+    SpiFlash::new(CERT_STORE_PARTITION.driver_num)
+}
+
+impl InstalledCertChain {
+    /// Loads the certificate chain installed in the slot, if any.
+    pub async fn load(slot_id: u8) -> CertStoreResult<Option<Self>> {
+        let mut header = InstalledCertChainHeader::new_zeroed();
+        cert_store_flash()
+            .read(
+                slot_region_offset(slot_id),
+                HEADER_SIZE,
+                header.as_mut_bytes(),
+            )
+            .await
+            .map_err(|_| CertStoreError::CertReadError)?;
+
+        let cert_chain_len = header.cert_chain_len as usize;
+        if header.magic != INSTALLED_CERT_CHAIN_MAGIC
+            || cert_chain_len == 0
+            || cert_chain_len > MAX_INSTALLED_CERT_CHAIN_SIZE
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            slot_id,
+            cert_chain_len,
+            root_hash: header.root_hash,
+            leaf_key: DpeLeafCert::new(),
+        }))
+    }
+
+    /// Writes the certificate chain to the slot region, replacing any previous chain.
+    pub async fn install(
+        slot_id: u8,
+        root_hash: &[u8; SHA384_HASH_SIZE],
+        cert_chain: &[u8],
+    ) -> CertStoreResult<Self> {
+        if cert_chain.is_empty() || cert_chain.len() > MAX_INSTALLED_CERT_CHAIN_SIZE {
+            return Err(CertStoreError::BufferTooSmall);
+        }
+
+        Self::erase(slot_id).await?;
+
+        let flash = cert_store_flash();
+        let region_offset = slot_region_offset(slot_id);
+        flash
+            .write(region_offset + HEADER_SIZE, cert_chain.len(), cert_chain)
+            .await
+            .map_err(|_| CertStoreError::CertWriteError)?;
+
+        let header = InstalledCertChainHeader {
+            magic: INSTALLED_CERT_CHAIN_MAGIC,
+            cert_chain_len: cert_chain.len() as u32,
+            root_hash: *root_hash,
+        };
+        flash
+            .write(region_offset, HEADER_SIZE, header.as_bytes())
+            .await
+            .map_err(|_| CertStoreError::CertWriteError)?;
+
+        Ok(Self {
+            slot_id,
+            cert_chain_len: cert_chain.len(),
+            root_hash: *root_hash,
+            leaf_key: DpeLeafCert::new(),
+        })
+    }
+
+    /// Erases the slot region.
+    pub async fn erase(slot_id: u8) -> CertStoreResult<()> {
+        cert_store_flash()
+            .erase(slot_region_offset(slot_id), SLOT_REGION_SIZE)
+            .await
+            .map_err(|_| CertStoreError::CertWriteError)
+    }
+
+    pub fn size(&self, asym_algo: AsymAlgo) -> CertStoreResult<usize> {
+        if asym_algo != AsymAlgo::EccP384 {
+            return Err(CertStoreError::UnsupportedAsymAlgo);
+        }
+        Ok(self.cert_chain_len)
+    }
+
+    pub async fn read(
+        &self,
+        asym_algo: AsymAlgo,
+        offset: usize,
+        buf: &mut [u8],
+    ) -> CertStoreResult<usize> {
+        let cert_chain_len = self.size(asym_algo)?;
+        if offset >= cert_chain_len {
+            return Err(CertStoreError::InvalidOffset);
+        }
+
+        let read_len = (cert_chain_len - offset).min(buf.len());
+        cert_store_flash()
+            .read(
+                slot_region_offset(self.slot_id) + HEADER_SIZE + offset,
+                read_len,
+                &mut buf[..read_len],
+            )
+            .await
+            .map_err(|_| CertStoreError::CertReadError)?;
+        Ok(read_len)
+    }
+
+    pub fn root_cert_hash(
+        &self,
+        asym_algo: AsymAlgo,
+        cert_hash: &mut [u8; SHA384_HASH_SIZE],
+    ) -> CertStoreResult<()> {
+        if asym_algo != AsymAlgo::EccP384 {
+            return Err(CertStoreError::UnsupportedAsymAlgo);
+        }
+        cert_hash.copy_from_slice(&self.root_hash);
+        Ok(())
+    }
+
+    pub async fn sign(
+        &self,
+        asym_algo: AsymAlgo,
+        hash: &[u8; SHA384_HASH_SIZE],
+        signature: &mut [u8],
+    ) -> CertStoreResult<()> {
+        self.leaf_key.sign(asym_algo, hash, signature).await
+    }
+}
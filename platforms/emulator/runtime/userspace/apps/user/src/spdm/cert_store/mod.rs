@@ -1,23 +1,95 @@
 // Licensed under the Apache-2.0 license
 
 pub(crate) mod cert_chain;
+pub(crate) mod installed;
 
+use crate::spdm::cert_store::cert_chain::leaf::DpeLeafCert;
 use crate::spdm::cert_store::cert_chain::CertChain;
-use libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
+use crate::spdm::cert_store::installed::InstalledCertChain;
+use libapi_caliptra::crypto::asym::AsymAlgo;
 use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use spdm_lib::cert_store::{CertStoreError, CertStoreResult, MAX_CERT_SLOTS_SUPPORTED};
 
+/// Certificate chains of the device. Slots set with `set_cert_chain` are backed by the
+/// DPE certificate chain and are read-only. The remaining slots can be provisioned in
+/// the field with SET_CERTIFICATE and are persisted in flash.
 pub struct DeviceCertStore {
     cert_chains: [Option<CertChain>; MAX_CERT_SLOTS_SUPPORTED as usize],
+    installed_cert_chains: [Option<InstalledCertChain>; MAX_CERT_SLOTS_SUPPORTED as usize],
 }
 
 impl DeviceCertStore {
     pub fn new() -> Self {
         Self {
             cert_chains: Default::default(),
+            installed_cert_chains: Default::default(),
         }
     }
 
+    /// Loads the certificate chains previously installed in the writable slots.
+    pub async fn load_installed_cert_chains(&mut self) -> CertStoreResult<()> {
+        for slot in 0..MAX_CERT_SLOTS_SUPPORTED {
+            if self.cert_chains[slot as usize].is_none() {
+                self.installed_cert_chains[slot as usize] = InstalledCertChain::load(slot).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn writable_slot(&self, slot: u8) -> CertStoreResult<usize> {
+        if slot >= MAX_CERT_SLOTS_SUPPORTED {
+            return Err(CertStoreError::InvalidSlotId);
+        }
+        if self.cert_chains[slot as usize].is_some() {
+            return Err(CertStoreError::ReadOnlySlot);
+        }
+        Ok(slot as usize)
+    }
+
+    pub async fn install_cert_chain(
+        &mut self,
+        slot: u8,
+        asym_algo: AsymAlgo,
+        root_hash: &[u8; SHA384_HASH_SIZE],
+        cert_chain: &[u8],
+    ) -> CertStoreResult<()> {
+        let slot = self.writable_slot(slot)?;
+        if asym_algo != AsymAlgo::EccP384 {
+            return Err(CertStoreError::UnsupportedAsymAlgo);
+        }
+
+        // Drop the cached chain first so a failed install never leaves a stale slot behind
+        self.installed_cert_chains[slot] = None;
+        self.installed_cert_chains[slot] =
+            Some(InstalledCertChain::install(slot as u8, root_hash, cert_chain).await?);
+        Ok(())
+    }
+
+    pub async fn erase_cert_chain(&mut self, slot: u8) -> CertStoreResult<()> {
+        let slot = self.writable_slot(slot)?;
+        self.installed_cert_chains[slot] = None;
+        InstalledCertChain::erase(slot as u8).await
+    }
+
+    /// Returns the public key certified by the chains installed in writable slots.
+    pub async fn device_public_key(
+        &self,
+        asym_algo: AsymAlgo,
+        public_key: &mut [u8],
+    ) -> CertStoreResult<usize> {
+        DpeLeafCert::new().public_key(asym_algo, public_key).await
+    }
+
+    /// Generates a CSR for the key certified by the chains installed in writable slots.
+    pub async fn csr(
+        &self,
+        asym_algo: AsymAlgo,
+        requester_info: &[u8],
+        csr: &mut [u8],
+    ) -> CertStoreResult<usize> {
+        DpeLeafCert::new().csr(asym_algo, requester_info, csr).await
+    }
+
     pub fn set_cert_chain(&mut self, slot: u8, cert_chain: CertChain) -> CertStoreResult<()> {
         if slot >= MAX_CERT_SLOTS_SUPPORTED {
             return Err(CertStoreError::InvalidSlotId);
@@ -53,8 +125,23 @@ impl DeviceCertStore {
         MAX_CERT_SLOTS_SUPPORTED
     }
 
-    pub fn is_provisioned(&self, slot: u8) -> bool {
+    /// Slots backed by the DPE certificate chain serve both the ECC P-384 and the ML-DSA-87
+    /// chains. Installed chains certify the ECC P-384 DPE leaf key only.
+    pub fn is_provisioned(&self, slot: u8, asym_algo: AsymAlgo) -> bool {
         self.cert_chain(slot).is_ok()
+            || self
+                .installed_cert_chain(slot)
+                .is_ok_and(|installed| installed.size(asym_algo).is_ok())
+    }
+
+    fn installed_cert_chain(&self, slot: u8) -> CertStoreResult<&InstalledCertChain> {
+        if slot >= MAX_CERT_SLOTS_SUPPORTED {
+            return Err(CertStoreError::InvalidSlotId);
+        }
+
+        self.installed_cert_chains[slot as usize]
+            .as_ref()
+            .ok_or(CertStoreError::UnprovisionedSlot)
     }
 
     pub async fn cert_chain_len(
@@ -62,6 +149,9 @@ impl DeviceCertStore {
         asym_algo: AsymAlgo,
         slot_id: u8,
     ) -> CertStoreResult<usize> {
+        if let Ok(installed) = self.installed_cert_chain(slot_id) {
+            return installed.size(asym_algo);
+        }
         let cert_chain = self.cert_chain_mut(slot_id)?;
         cert_chain.size(asym_algo).await
     }
@@ -73,6 +163,9 @@ impl DeviceCertStore {
         offset: usize,
         cert_portion: &mut [u8],
     ) -> CertStoreResult<usize> {
+        if let Ok(installed) = self.installed_cert_chain(slot_id) {
+            return installed.read(asym_algo, offset, cert_portion).await;
+        }
         let cert_chain = self.cert_chain_mut(slot_id)?;
         cert_chain.read(asym_algo, offset, cert_portion).await
     }
@@ -83,6 +176,9 @@ impl DeviceCertStore {
         asym_algo: AsymAlgo,
         cert_hash: &mut [u8; SHA384_HASH_SIZE],
     ) -> CertStoreResult<()> {
+        if let Ok(installed) = self.installed_cert_chain(slot_id) {
+            return installed.root_cert_hash(asym_algo, cert_hash);
+        }
         let cert_chain = self.cert_chain(slot_id)?;
         cert_chain.root_cert_hash(asym_algo, cert_hash).await
     }
@@ -92,8 +188,11 @@ impl DeviceCertStore {
         asym_algo: AsymAlgo,
         slot_id: u8,
         hash: &'a [u8; SHA384_HASH_SIZE],
-        signature: &'a mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &'a mut [u8],
     ) -> CertStoreResult<()> {
+        if let Ok(installed) = self.installed_cert_chain(slot_id) {
+            return installed.sign(asym_algo, hash, signature).await;
+        }
         let cert_chain = self.cert_chain(slot_id)?;
         cert_chain.sign(asym_algo, hash, signature).await
     }
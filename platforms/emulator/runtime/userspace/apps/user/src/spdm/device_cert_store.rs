@@ -12,7 +12,7 @@ use core::mem::MaybeUninit;
 use core::sync::atomic::{AtomicBool, Ordering};
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::mutex::Mutex;
-use libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
+use libapi_caliptra::crypto::asym::AsymAlgo;
 use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use spdm_lib::cert_store::{CertStoreError, CertStoreResult, SpdmCertStore};
 use spdm_lib::protocol::{CertificateInfo, KeyUsageMask};
@@ -87,6 +87,9 @@ pub async fn initialize_cert_store() -> CertStoreResult<()> {
     let mut cert_store = DeviceCertStore::new();
     cert_store.set_cert_chain(0, slot0_cert_chain)?;
 
+    // Restore the certificate chains provisioned with SET_CERTIFICATE
+    cert_store.load_installed_cert_chains().await?;
+
     initialize_shared_cert_store(cert_store).await?;
     Ok(())
 }
@@ -112,10 +115,10 @@ impl SpdmCertStore for SharedCertStore {
         }
     }
 
-    async fn is_provisioned(&self, slot: u8) -> bool {
+    async fn is_provisioned(&self, slot: u8, asym_algo: AsymAlgo) -> bool {
         let cert_store = SHARED_CERT_STORE.lock().await;
         if let Some(cert_store) = cert_store.as_ref() {
-            cert_store.is_provisioned(slot)
+            cert_store.is_provisioned(slot, asym_algo)
         } else {
             false
         }
@@ -168,7 +171,7 @@ impl SpdmCertStore for SharedCertStore {
         slot_id: u8,
         asym_algo: AsymAlgo,
         hash: &'a [u8; SHA384_HASH_SIZE],
-        signature: &'a mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &'a mut [u8],
     ) -> CertStoreResult<()> {
         let cert_store = SHARED_CERT_STORE.lock().await;
         if let Some(cert_store) = cert_store.as_ref() {
@@ -191,4 +194,57 @@ impl SpdmCertStore for SharedCertStore {
     async fn key_usage_mask(&self, _slot_id: u8) -> Option<KeyUsageMask> {
         None
     }
+
+    async fn get_csr<'a>(
+        &self,
+        asym_algo: AsymAlgo,
+        requester_info: &'a [u8],
+        csr: &'a mut [u8],
+    ) -> CertStoreResult<usize> {
+        let cert_store = SHARED_CERT_STORE.lock().await;
+        if let Some(cert_store) = cert_store.as_ref() {
+            cert_store.csr(asym_algo, requester_info, csr).await
+        } else {
+            Err(CertStoreError::NotInitialized)
+        }
+    }
+
+    async fn device_public_key<'a>(
+        &self,
+        asym_algo: AsymAlgo,
+        public_key: &'a mut [u8],
+    ) -> CertStoreResult<usize> {
+        let cert_store = SHARED_CERT_STORE.lock().await;
+        if let Some(cert_store) = cert_store.as_ref() {
+            cert_store.device_public_key(asym_algo, public_key).await
+        } else {
+            Err(CertStoreError::NotInitialized)
+        }
+    }
+
+    async fn set_cert_chain<'a>(
+        &self,
+        slot_id: u8,
+        asym_algo: AsymAlgo,
+        root_hash: &'a [u8; SHA384_HASH_SIZE],
+        cert_chain: &'a [u8],
+    ) -> CertStoreResult<()> {
+        let mut cert_store = SHARED_CERT_STORE.lock().await;
+        if let Some(cert_store) = cert_store.as_mut() {
+            cert_store
+                .install_cert_chain(slot_id, asym_algo, root_hash, cert_chain)
+                .await
+        } else {
+            Err(CertStoreError::NotInitialized)
+        }
+    }
+
+    async fn erase_cert_chain(&self, slot_id: u8) -> CertStoreResult<()> {
+        let mut cert_store = SHARED_CERT_STORE.lock().await;
+        if let Some(cert_store) = cert_store.as_mut() {
+            cert_store.erase_cert_chain(slot_id).await
+        } else {
+            Err(CertStoreError::NotInitialized)
+        }
+    }
 }
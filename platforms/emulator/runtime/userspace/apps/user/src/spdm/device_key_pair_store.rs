@@ -0,0 +1,117 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use core::sync::atomic::{AtomicU16, Ordering};
+use spdm_lib::cert_store::MAX_CERT_SLOTS_SUPPORTED;
+use spdm_lib::key_pair_store::{
+    KeyPairInfo, KeyPairStoreError, KeyPairStoreResult, KeyPairUpdate, SetKeyPairInfoOperation,
+    SpdmKeyPairStore, ECC_P384_PUBLIC_KEY_INFO, MLDSA87_PUBLIC_KEY_INFO,
+};
+use spdm_lib::protocol::KeyUsageMask;
+
+// KeyPairID of the ECC P-384 DPE leaf key
+const DPE_LEAF_KEY_PAIR_ID: u8 = 1;
+// KeyPairID of the ML-DSA-87 Caliptra RT alias key
+const RT_ALIAS_MLDSA_KEY_PAIR_ID: u8 = 2;
+const TOTAL_KEY_PAIRS: usize = 2;
+
+// The ECC P-384 DPE leaf key signs for slot 0 and for the chains installed in the
+// writable slots, which certify the DPE leaf key.
+const DPE_LEAF_SLOT_MASK: u8 = ((1u16 << MAX_CERT_SLOTS_SUPPORTED) - 1) as u8;
+// The ML-DSA-87 chain of the RT alias key is only served from slot 0
+const RT_ALIAS_MLDSA_SLOT_MASK: u8 = 0x01;
+
+/// Key pairs of the device, one per supported asymmetric algorithm. The key usage
+/// of each key pair can be restricted by the host.
+pub struct DeviceKeyPairStore {
+    current_key_usage: [AtomicU16; TOTAL_KEY_PAIRS],
+}
+
+fn supported_key_usage() -> KeyUsageMask {
+    let mut key_usage = KeyUsageMask::default();
+    key_usage.set_key_exch_usage(1);
+    key_usage.set_challenge_usage(1);
+    key_usage.set_measurement_usage(1);
+    key_usage
+}
+
+fn key_pair_index(key_pair_id: u8) -> KeyPairStoreResult<usize> {
+    match key_pair_id {
+        DPE_LEAF_KEY_PAIR_ID | RT_ALIAS_MLDSA_KEY_PAIR_ID => Ok(key_pair_id as usize - 1),
+        _ => Err(KeyPairStoreError::InvalidKeyPairId),
+    }
+}
+
+impl DeviceKeyPairStore {
+    pub fn new() -> Self {
+        Self {
+            current_key_usage: [
+                AtomicU16::new(supported_key_usage().0),
+                AtomicU16::new(supported_key_usage().0),
+            ],
+        }
+    }
+}
+
+#[async_trait]
+impl SpdmKeyPairStore for DeviceKeyPairStore {
+    fn total_key_pairs(&self) -> u8 {
+        TOTAL_KEY_PAIRS as u8
+    }
+
+    async fn key_pair_info<'a>(
+        &self,
+        key_pair_id: u8,
+        public_key_info: &'a mut [u8],
+    ) -> KeyPairStoreResult<(KeyPairInfo, usize)> {
+        let index = key_pair_index(key_pair_id)?;
+
+        let mut info = KeyPairInfo {
+            key_usage_capabilities: supported_key_usage(),
+            current_key_usage: KeyUsageMask(self.current_key_usage[index].load(Ordering::Acquire)),
+            ..Default::default()
+        };
+        info.capabilities.set_key_usage_cap(1);
+        info.capabilities.set_shareable_cap(1);
+
+        let key_info = if key_pair_id == DPE_LEAF_KEY_PAIR_ID {
+            info.asym_algo_capabilities.set_ecc384(1);
+            info.current_asym_algo.set_ecc384(1);
+            info.assoc_cert_slot_mask = DPE_LEAF_SLOT_MASK;
+            ECC_P384_PUBLIC_KEY_INFO
+        } else {
+            info.pqc_asym_algo_capabilities.set_ml_dsa_87(1);
+            info.current_pqc_asym_algo.set_ml_dsa_87(1);
+            info.assoc_cert_slot_mask = RT_ALIAS_MLDSA_SLOT_MASK;
+            MLDSA87_PUBLIC_KEY_INFO
+        };
+
+        if public_key_info.len() < key_info.len() {
+            return Err(KeyPairStoreError::BufferTooSmall);
+        }
+        public_key_info[..key_info.len()].copy_from_slice(key_info);
+        Ok((info, key_info.len()))
+    }
+
+    async fn set_key_pair_info(
+        &self,
+        key_pair_id: u8,
+        operation: SetKeyPairInfoOperation,
+        update: KeyPairUpdate,
+    ) -> KeyPairStoreResult<()> {
+        let index = key_pair_index(key_pair_id)?;
+        // Both keys are derived and can neither be erased nor regenerated
+        if operation != SetKeyPairInfoOperation::Change {
+            return Err(KeyPairStoreError::UnsupportedOperation);
+        }
+        // A key pair without any usage cannot sign for any slot
+        if update.key_usage.0 == 0 {
+            return Err(KeyPairStoreError::InvalidParam);
+        }
+        self.current_key_usage[index].store(update.key_usage.0, Ordering::Release);
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use libapi_caliptra::evidence::measurement_log::{MeasurementLog, MAX_STASHED_MEASUREMENTS};
+use spdm_lib::measurement_log::{
+    DmtfMelBuilder, MeasurementLogError, MeasurementLogResult, SpdmMeasurementLog,
+};
+use spdm_lib::protocol::{
+    MeasurementValueType, DMTF_MEL_ENTRY_HEADER_SIZE, DMTF_MEL_HEADER_SIZE,
+    SPDM_MEASUREMENT_MANIFEST_INDEX,
+};
+
+const MEL_ENTRY_SIZE: usize = DMTF_MEL_ENTRY_HEADER_SIZE + SHA384_HASH_SIZE;
+const MAX_MEL_SIZE: usize = DMTF_MEL_HEADER_SIZE + MAX_STASHED_MEASUREMENTS * MEL_ENTRY_SIZE;
+
+static SHARED_MEL: Mutex<CriticalSectionRawMutex, MelBuf> = Mutex::new(MelBuf::new());
+
+/// Measurement extension log of the PCR quote manifest.
+///
+/// Each entry is a SoC measurement stashed in Caliptra, in the order it was extended
+/// into PCR31, so that a verifier can replay the extensions against PCR31 in the quote.
+/// The PCRs extended by the Caliptra boot flow are not logged, as their extensions are
+/// not visible to MCU. The log is captured when a retrieval starts.
+pub struct DeviceMeasurementLog;
+
+impl DeviceMeasurementLog {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl SpdmMeasurementLog for DeviceMeasurementLog {
+    async fn snapshot_log(&self) -> MeasurementLogResult<()> {
+        let mut mel = SHARED_MEL.lock().await;
+        mel.build()?;
+        Ok(())
+    }
+
+    async fn log_size(&self) -> MeasurementLogResult<usize> {
+        let mut mel = SHARED_MEL.lock().await;
+        if mel.len == 0 {
+            mel.build()?;
+        }
+        Ok(mel.len)
+    }
+
+    async fn read_log<'a>(
+        &self,
+        offset: usize,
+        log_portion: &'a mut [u8],
+    ) -> MeasurementLogResult<usize> {
+        let mut mel = SHARED_MEL.lock().await;
+        if mel.len == 0 {
+            mel.build()?;
+        }
+        if offset >= mel.len {
+            return Err(MeasurementLogError::InvalidOffset);
+        }
+
+        let len = log_portion.len().min(mel.len - offset);
+        log_portion[..len].copy_from_slice(&mel.buffer[offset..offset + len]);
+        Ok(len)
+    }
+}
+
+struct MelBuf {
+    buffer: [u8; MAX_MEL_SIZE],
+    len: usize,
+}
+
+impl MelBuf {
+    const fn new() -> Self {
+        Self {
+            buffer: [0; MAX_MEL_SIZE],
+            len: 0,
+        }
+    }
+
+    fn build(&mut self) -> MeasurementLogResult<()> {
+        self.len = 0;
+        let mut builder = DmtfMelBuilder::new(&mut self.buffer)?;
+        for index in 0..MeasurementLog::count() {
+            if let Some(entry) = MeasurementLog::entry(index) {
+                builder.add_entry(
+                    SPDM_MEASUREMENT_MANIFEST_INDEX,
+                    MeasurementValueType::MutableFirmware,
+                    true,
+                    &entry.measurement,
+                )?;
+            }
+        }
+
+        self.len = builder.finish();
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 // Licensed under the Apache-2.0 license
 
+pub mod measurement_log;
 #[cfg(feature = "test-mctp-spdm-responder-conformance")]
 pub mod ocp_eat;
 pub mod pcr_quote;
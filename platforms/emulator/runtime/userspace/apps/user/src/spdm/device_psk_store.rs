@@ -0,0 +1,75 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use caliptra_api::mailbox::Cmk;
+use libapi_caliptra::crypto::import::{CmKeyUsage, Import};
+use spdm_lib::psk_store::{PskStoreError, PskStoreResult, SpdmPskStore};
+
+// Size of the PSKs. Caliptra only imports HMAC keys of the SHA-384 key size.
+const PSK_SIZE: usize = 48;
+
+/// Pre-shared key provisioned for a PSKHint
+#[cfg_attr(not(feature = "test-doe-spdm-psk"), allow(dead_code))]
+struct PskEntry {
+    hint: &'static [u8],
+    psk: [u8; PSK_SIZE],
+}
+
+/// Test PSKs used by the emulator. The PSK is the HKDF-Extract input keying material,
+/// so it must be byte-for-byte the PSK of the requester. The emulator tests pass the
+/// same value to the libspdm sample requester with `--psk`. These must never be used
+/// on a production device.
+#[cfg(feature = "test-doe-spdm-psk")]
+const EMULATOR_PSKS: &[PskEntry] = &[PskEntry {
+    hint: b"TestPskHint\0",
+    psk: *b"McuEmulatorSpdmTestPsk-NotForProductionDevices!!",
+}];
+
+#[cfg(not(feature = "test-doe-spdm-psk"))]
+const EMULATOR_PSKS: &[PskEntry] = &[];
+
+/// PSK store backed by Caliptra cryptographic mailbox keys.
+/// Raw PSKs are imported into Caliptra on use so the SPDM key schedule only
+/// ever handles the resulting key handles.
+pub struct DevicePskStore {
+    entries: &'static [PskEntry],
+}
+
+impl DevicePskStore {
+    pub fn new() -> Self {
+        Self {
+            entries: EMULATOR_PSKS,
+        }
+    }
+}
+
+#[async_trait]
+impl SpdmPskStore for DevicePskStore {
+    fn is_provisioned(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    async fn psk(&self, psk_hint: &[u8]) -> PskStoreResult<Cmk> {
+        if self.entries.is_empty() {
+            return Err(PskStoreError::NotProvisioned);
+        }
+
+        // An empty hint selects the first provisioned PSK
+        let entry = if psk_hint.is_empty() {
+            &self.entries[0]
+        } else {
+            self.entries
+                .iter()
+                .find(|entry| entry.hint == psk_hint)
+                .ok_or(PskStoreError::UnknownPskHint)?
+        };
+
+        let import_rsp = Import::import(CmKeyUsage::Hmac, &entry.psk)
+            .await
+            .map_err(PskStoreError::CaliptraApi)?;
+        Ok(import_rsp.cmk)
+    }
+}
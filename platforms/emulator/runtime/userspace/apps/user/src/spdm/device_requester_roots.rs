@@ -0,0 +1,55 @@
+// Licensed under the Apache-2.0 license
+
+use libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
+use libapi_caliptra::error::CaliptraApiResult;
+
+// Maximum number of root certificates trusted for mutual authentication
+const MAX_REQUESTER_ROOTS: usize = 1;
+
+/// Root certificate of the libspdm sample requester certificate chain. This is a test
+/// certificate and must never be trusted on a production device.
+#[cfg(feature = "test-doe-spdm-mut-auth")]
+const REQUESTER_ROOT_CERTS: &[&[u8]] = &[include_bytes!(concat!(
+    env!("SPDM_VALIDATOR_DIR"),
+    "/ecp384/ca.cert.der"
+))];
+
+#[cfg(not(feature = "test-doe-spdm-mut-auth"))]
+const REQUESTER_ROOT_CERTS: &[&[u8]] = &[];
+
+const _: () = assert!(REQUESTER_ROOT_CERTS.len() <= MAX_REQUESTER_ROOTS);
+
+/// SHA-384 hashes of the root certificates trusted to anchor a Requester certificate
+/// chain. Mutual authentication is only requested when a root is provisioned.
+pub struct DeviceRequesterRoots {
+    hashes: [[u8; SHA384_HASH_SIZE]; MAX_REQUESTER_ROOTS],
+    count: usize,
+}
+
+impl DeviceRequesterRoots {
+    pub async fn new() -> CaliptraApiResult<Self> {
+        let mut roots = Self {
+            hashes: [[0; SHA384_HASH_SIZE]; MAX_REQUESTER_ROOTS],
+            count: 0,
+        };
+
+        for (cert, hash) in REQUESTER_ROOT_CERTS.iter().zip(roots.hashes.iter_mut()) {
+            // Root certificates may not fit in a single mailbox request
+            let mut hash_ctx = HashContext::new();
+            hash_ctx.init(HashAlgoType::SHA384, None).await?;
+            hash_ctx.update(cert).await?;
+            hash_ctx.finalize(hash).await?;
+            roots.count += 1;
+        }
+
+        Ok(roots)
+    }
+
+    pub fn is_provisioned(&self) -> bool {
+        self.count > 0
+    }
+
+    pub fn hashes(&self) -> &[[u8; SHA384_HASH_SIZE]] {
+        &self.hashes[..self.count]
+    }
+}
@@ -2,7 +2,10 @@
 
 mod cert_store;
 mod device_cert_store;
+mod device_key_pair_store;
 mod device_measurements;
+mod device_psk_store;
+mod device_requester_roots;
 mod endorsement_certs;
 #[cfg(feature = "test-doe-spdm-tdisp-ide-validator")]
 mod integration_example;
@@ -11,29 +14,53 @@ mod integration_example;
 use crate::spdm::device_measurements::ocp_eat::init_target_env_claims;
 use core::fmt::Write;
 use device_cert_store::{initialize_cert_store, SharedCertStore};
+use device_key_pair_store::DeviceKeyPairStore;
+use device_measurements::measurement_log::DeviceMeasurementLog;
+use device_psk_store::DevicePskStore;
+use device_requester_roots::DeviceRequesterRoots;
 use embassy_executor::Spawner;
 use libsyscall_caliptra::doe;
 use libsyscall_caliptra::mctp;
 use libsyscall_caliptra::DefaultSyscalls;
+use libtock::alarm::Milliseconds;
 use libtock_console::Console;
 use libtock_platform::ErrorCode;
+use pldm_lib::timer::AsyncAlarm;
 use spdm_lib::codec::MessageBuf;
 use spdm_lib::context::{SpdmContext, MAX_SPDM_RESPONDER_BUF_SIZE};
 use spdm_lib::error::SpdmError;
 use spdm_lib::measurements::SpdmMeasurements;
 use spdm_lib::protocol::*;
+use spdm_lib::psk_store::SpdmPskStore;
+use spdm_lib::session::SessionExpirySignal;
 use spdm_lib::transport::common::SpdmTransport;
 use spdm_lib::transport::common::TransportError;
 use spdm_lib::transport::doe::DoeTransport;
 use spdm_lib::transport::mctp::MctpTransport;
 
 // Caliptra supported SPDM and Secure SPDM versions
+#[cfg(not(feature = "spdm-v14"))]
 const SPDM_VERSIONS: &[SpdmVersion] = &[SpdmVersion::V12, SpdmVersion::V13];
+// SPDM 1.4 is offered when enabled for the product, as it is needed to negotiate ML-DSA-87
+#[cfg(feature = "spdm-v14")]
+const SPDM_VERSIONS: &[SpdmVersion] = &[SpdmVersion::V12, SpdmVersion::V13, SpdmVersion::V14];
 const SECURE_SPDM_VERSIONS: &[SpdmVersion] = &[SpdmVersion::V12];
 
 // Caliptra Crypto timeout exponent (2^20 us)
 const CALIPTRA_SPDM_CT_EXPONENT: u8 = 20;
 
+// Maximum size of a large request received in chunks with CHUNK_SEND
+const MAX_SPDM_LARGE_REQUEST_SIZE: usize = 4096;
+
+// Maximum size of a large response sent in chunks with CHUNK_GET.
+// Holds a response signed with ML-DSA-87.
+const MAX_SPDM_LARGE_RESPONSE_SIZE: usize = 8192;
+
+// Interval at which DOE sessions whose heartbeat window has expired are torn down
+const SESSION_EXPIRY_INTERVAL_MS: u32 = 1000;
+
+static DOE_SESSION_EXPIRY: SessionExpirySignal = SessionExpirySignal::new();
+
 #[embassy_executor::task]
 pub(crate) async fn spdm_task(spawner: Spawner) {
     let mut console_writer = Console::<DefaultSyscalls>::writer();
@@ -54,7 +81,12 @@ pub(crate) async fn spdm_task(spawner: Spawner) {
     #[cfg(feature = "test-mctp-spdm-responder-conformance")]
     init_target_env_claims();
 
-    #[cfg(feature = "test-mctp-spdm-responder-conformance")]
+    // The MCTP PCIe VDM and serial tests also exchange SPDM messages over MCTP
+    #[cfg(any(
+        feature = "test-mctp-spdm-responder-conformance",
+        feature = "test-mctp-pcie-vdm",
+        feature = "test-mctp-serial"
+    ))]
     if let Err(e) = spawner.spawn(spdm_mctp_responder()) {
         writeln!(
             console_writer,
@@ -71,6 +103,22 @@ pub(crate) async fn spdm_task(spawner: Spawner) {
         )
         .unwrap();
     }
+    if let Err(e) = spawner.spawn(spdm_session_expiry_timer()) {
+        writeln!(
+            console_writer,
+            "SPDM_TASK: Failed to spawn spdm_session_expiry_timer: {:?}",
+            e
+        )
+        .unwrap();
+    }
+}
+
+#[embassy_executor::task]
+async fn spdm_session_expiry_timer() {
+    loop {
+        AsyncAlarm::<DefaultSyscalls>::sleep(Milliseconds(SESSION_EXPIRY_INTERVAL_MS)).await;
+        DOE_SESSION_EXPIRY.signal(());
+    }
 }
 
 #[embassy_executor::task]
@@ -114,6 +162,7 @@ async fn spdm_mctp_responder() {
         local_capabilities,
         local_algorithms,
         &shared_cert_store,
+        None, // PSK sessions are not supported for MCTP transport in this configuration
         device_measurements,
         None, // VDM handlers are not supported for MCTP transport in this configuration
     ) {
@@ -146,6 +195,8 @@ async fn spdm_mctp_responder() {
 #[embassy_executor::task]
 async fn spdm_doe_responder() {
     let mut raw_buffer = [0; MAX_SPDM_RESPONDER_BUF_SIZE];
+    let mut large_req_buffer = [0; MAX_SPDM_LARGE_REQUEST_SIZE];
+    let mut large_rsp_buffer = [0; MAX_SPDM_LARGE_RESPONSE_SIZE];
     let mut cw = Console::<DefaultSyscalls>::writer();
     let mut doe_spdm_transport: DoeTransport = DoeTransport::new(doe::driver_num::DOE_SPDM);
 
@@ -156,12 +207,45 @@ async fn spdm_doe_responder() {
     doe_capability_flags.set_key_ex_cap(1);
     doe_capability_flags.set_mac_cap(1);
     doe_capability_flags.set_encrypt_cap(1);
+    doe_capability_flags.set_key_upd_cap(1);
+    doe_capability_flags.set_hbeat_cap(1);
+    // Slots 1-7 can be provisioned in the field over a secure session
+    doe_capability_flags.set_csr_cap(1);
+    doe_capability_flags.set_set_certificate_cap(1);
+    doe_capability_flags.set_get_key_pair_info_cap(1);
+    doe_capability_flags.set_set_key_pair_info_cap(1);
+    // Measurement extension log of the PCRs reported in the PCR quote manifest
+    doe_capability_flags.set_mel_cap(1);
+
+    // Pre-shared keys for PSK_EXCHANGE sessions
+    let psk_store = DevicePskStore::new();
+    if psk_store.is_provisioned() {
+        doe_capability_flags.set_psk_cap(PskCapability::PskWithContext as u8);
+    }
+
+    // Root certificates trusted to authenticate the Requester with its certificate
+    let requester_roots = match DeviceRequesterRoots::new().await {
+        Ok(requester_roots) => requester_roots,
+        Err(e) => {
+            writeln!(
+                cw,
+                "SPDM_DOE_RESPONDER: Failed to hash requester roots: {:?}",
+                e
+            )
+            .unwrap();
+            return;
+        }
+    };
+    if requester_roots.is_provisioned() {
+        doe_capability_flags.set_mut_auth_cap(1);
+        doe_capability_flags.set_encap_cap(1);
+    }
 
     let local_capabilities = DeviceCapabilities {
         ct_exponent: CALIPTRA_SPDM_CT_EXPONENT,
         flags: doe_capability_flags,
         data_transfer_size: max_doe_spdm_msg_size,
-        max_spdm_msg_size: max_doe_spdm_msg_size,
+        max_spdm_msg_size: MAX_SPDM_LARGE_REQUEST_SIZE as u32,
     };
 
     let mut device_doe_algorithms = DeviceAlgorithms::default();
@@ -169,16 +253,24 @@ async fn spdm_doe_responder() {
     device_doe_algorithms.set_aead_cipher_suite();
     device_doe_algorithms.set_spdm_key_schedule();
     device_doe_algorithms.set_other_param_support();
+    // ML-DSA-87 is preferred over ECC P-384 when offered by a SPDM 1.4 Requester
+    device_doe_algorithms.set_pqc_asym_algo();
+    // Requester certificates are verified with ECC P-384
+    device_doe_algorithms.set_req_base_asym_algo();
 
     let local_algorithms = LocalDeviceAlgorithms::new(device_doe_algorithms);
 
     // Create a wrapper for the global certificate store
     let shared_cert_store = SharedCertStore::new();
 
+    // Key pairs backing the certificate slots
+    let key_pair_store = DeviceKeyPairStore::new();
+
     // Measurements in PCR Quote format
     let (mut device_pcr_quote, meas_value_info) =
         device_measurements::pcr_quote::create_manifest_with_pcr_quote();
     let device_measurements = SpdmMeasurements::new(&meas_value_info, &mut device_pcr_quote);
+    let measurement_log = DeviceMeasurementLog::new();
 
     // Create test drivers and VDM handlers locally for integration testing
     #[cfg(feature = "test-doe-spdm-tdisp-ide-validator")]
@@ -228,6 +320,7 @@ async fn spdm_doe_responder() {
         local_capabilities,
         local_algorithms,
         &shared_cert_store,
+        Some(&psk_store),
         device_measurements,
         vdm_handlers,
     ) {
@@ -242,6 +335,12 @@ async fn spdm_doe_responder() {
             return;
         }
     };
+    ctx.set_key_pair_store(&key_pair_store);
+    ctx.set_measurement_log(&measurement_log);
+    ctx.set_session_expiry_signal(&DOE_SESSION_EXPIRY);
+    ctx.set_requester_root_hashes(requester_roots.hashes());
+    ctx.set_large_request_buffer(&mut large_req_buffer);
+    ctx.set_large_response_buffer(&mut large_rsp_buffer);
 
     let mut msg_buffer = MessageBuf::new(&mut raw_buffer);
     loop {
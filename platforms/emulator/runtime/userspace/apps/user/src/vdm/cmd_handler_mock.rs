@@ -2,12 +2,17 @@
 
 extern crate alloc;
 
+use crate::device_logs::{self, LogReader};
+use crate::vdm::{debug_unlock, idev_cert_store};
 use alloc::boxed::Box;
 use async_trait::async_trait;
 use external_cmds_common::{
-    CommandError, DeviceCapabilities, DeviceId, DeviceInfo, FirmwareVersion, Uid,
-    UnifiedCommandHandler, MAX_FW_VERSION_LEN, MAX_UID_LEN,
+    CertificateStatus, CommandError, DebugUnlockChallenge, DeviceCapabilities, DeviceId,
+    DeviceInfo, FirmwareVersion, LogType, Uid, UnifiedCommandHandler, IDEV_ECC_CERT_SLOT,
+    MAX_FW_VERSION_LEN, MAX_UID_LEN,
 };
+use libapi_caliptra::certificate::{CertContext, IDEV_ECC_CSR_MAX_SIZE};
+use libapi_caliptra::error::CaliptraApiError;
 use mcu_mbox_common::config;
 
 #[derive(Default)]
@@ -16,8 +21,12 @@ pub struct NonCryptoCmdHandlerMock;
 /// Mock implementation of the `UnifiedCommandHandler` trait.
 ///
 /// This handler provides mock responses for firmware version queries,
-/// device ID, device information, and device capabilities. Intended to use for
-/// integration testing on the emulator platform.
+/// device ID, device information, and device capabilities. The IDevID CSR and
+/// certificate commands are forwarded to Caliptra and the flash-backed IDevID
+/// certificate slots, the log commands access the flash debug log and the
+/// measurement log shared with the MCU mailbox handler, and the debug unlock commands
+/// are forwarded to Caliptra. Intended to use for integration testing on the emulator
+/// platform.
 #[async_trait]
 impl UnifiedCommandHandler for NonCryptoCmdHandlerMock {
     async fn get_firmware_version(
@@ -84,4 +93,62 @@ impl UnifiedCommandHandler for NonCryptoCmdHandlerMock {
         capabilities.reserved = test_capabilities.reserved;
         Ok(())
     }
+
+    async fn export_idev_csr(&self, index: u32, csr: &mut [u8]) -> Result<usize, CommandError> {
+        // Caliptra only exports the ECC IDevID CSR
+        if index != IDEV_ECC_CERT_SLOT {
+            return Err(CommandError::InvalidParams);
+        }
+
+        let mut csr_der = [0u8; IDEV_ECC_CSR_MAX_SIZE];
+        let len = CertContext::new()
+            .get_idev_csr(&mut csr_der)
+            .await
+            .map_err(|e| match e {
+                CaliptraApiError::UnprovisionedCsr => CommandError::Busy,
+                _ => CommandError::InternalError,
+            })?;
+        if len > csr.len() {
+            return Err(CommandError::RespLengthTooLarge);
+        }
+        csr[..len].copy_from_slice(&csr_der[..len]);
+        Ok(len)
+    }
+
+    async fn import_idev_cert(&self, cert: &[u8]) -> Result<(), CommandError> {
+        idev_cert_store::import_idev_ecc_cert(cert).await
+    }
+
+    async fn get_cert_state(&self, status: &mut CertificateStatus) -> Result<(), CommandError> {
+        idev_cert_store::cert_status(status).await
+    }
+
+    async fn get_log(
+        &self,
+        log_type: LogType,
+        offset: usize,
+        data: &mut [u8],
+    ) -> Result<usize, CommandError> {
+        device_logs::get_log(LogReader::Vdm, log_type, offset, data).await
+    }
+
+    async fn clear_log(&self, log_type: LogType) -> Result<(), CommandError> {
+        device_logs::clear_log(log_type).await
+    }
+
+    async fn request_debug_unlock(
+        &self,
+        unlock_level: u8,
+        challenge: &mut DebugUnlockChallenge,
+    ) -> Result<(), CommandError> {
+        debug_unlock::request_challenge(unlock_level, challenge).await
+    }
+
+    async fn authorize_debug_unlock_token(
+        &self,
+        offset: usize,
+        data: &[u8],
+    ) -> Result<Option<u8>, CommandError> {
+        debug_unlock::authorize_token(offset, data).await
+    }
 }
@@ -0,0 +1,156 @@
+// Licensed under the Apache-2.0 license
+
+//! Production debug unlock requested with the MCTP VDM debug unlock commands.
+//!
+//! Caliptra ROM only handles production debug unlock on boot. The first challenge request
+//! sets the request in the debug service register, completes with `NotReady` and resets
+//! into ROM once the response has been sent. While Caliptra ROM
+//! waits for the unlock, the MCU ROM starts this firmware again and the repeated challenge
+//! request is forwarded to Caliptra. The signed token is received in chunks and assembled
+//! in a static buffer, laid out as the Caliptra token request:
+//! MailboxReqHeader | Token
+//! Once the last chunk is received the token is forwarded to Caliptra for authorization,
+//! and the level granted by Caliptra is reported.
+
+use caliptra_api::mailbox::{MailboxReqHeader, ProductionAuthDebugUnlockToken};
+use core::mem::size_of;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use external_cmds_common::{CommandError, DebugUnlockChallenge};
+use libapi_caliptra::debug_unlock::DebugUnlock;
+use libapi_caliptra::error::CaliptraApiError;
+use libsyscall_caliptra::mci::Mci;
+use libsyscall_caliptra::DefaultSyscalls;
+use libtock::alarm::{Convert, Milliseconds};
+use libtock_platform::ErrorCode;
+use mctp_vdm_common::message::DEBUG_UNLOCK_TOKEN_SIZE;
+use pldm_lib::timer::AsyncAlarm;
+use zerocopy::{FromBytes, IntoBytes};
+
+const TOKEN_HEADER_SIZE: usize = size_of::<MailboxReqHeader>();
+const TOKEN_WORDS: usize = size_of::<ProductionAuthDebugUnlockToken>() / size_of::<u32>();
+const _: () = assert!(TOKEN_HEADER_SIZE + DEBUG_UNLOCK_TOKEN_SIZE == TOKEN_WORDS * 4);
+const UNLOCK_POLL_INTERVAL_MS: u32 = 10;
+// Time given to Caliptra ROM to end the unlock once it has received the token
+const UNLOCK_COMPLETE_TIMEOUT_MS: u32 = 5000;
+// Time given to the responder to send the NotReady response before resetting into ROM
+const UNLOCK_RESET_DELAY_MS: u32 = 100;
+
+struct TokenBuffer {
+    // Word-aligned so the token can be viewed in place
+    token: [u32; TOKEN_WORDS],
+    received: usize,
+}
+
+// The token is too large for the task stack, so it is assembled in place
+static TOKEN_BUFFER: Mutex<CriticalSectionRawMutex, TokenBuffer> = Mutex::new(TokenBuffer {
+    token: [0; TOKEN_WORDS],
+    received: 0,
+});
+
+/// Requests the debug unlock challenge from Caliptra. Any partially received token is
+/// dropped, as it was signed over a previous challenge.
+///
+/// Unless Caliptra ROM is already waiting for the unlock, the unlock is requested, the
+/// request completes with `Busy` and the device resets into ROM shortly after the response
+/// is sent. The request is then repeated by the caller to get the challenge.
+pub async fn request_challenge(
+    unlock_level: u8,
+    challenge: &mut DebugUnlockChallenge,
+) -> Result<(), CommandError> {
+    TOKEN_BUFFER.lock().await.received = 0;
+
+    let debug_unlock = DebugUnlock::new();
+    if !debug_unlock
+        .in_progress()
+        .map_err(|_| CommandError::InternalError)?
+    {
+        // The request is only latched in the production lifecycle with DEBUG_INTENT set
+        debug_unlock.request_unlock().map_err(|err| match err {
+            CaliptraApiError::Syscall(ErrorCode::NoSupport) => CommandError::NotSupported,
+            _ => CommandError::InternalError,
+        })?;
+        // A failed spawn means the reset is already pending from a previous request
+        let _ = crate::EXECUTOR.get().spawner().spawn(unlock_reset_task());
+        return Err(CommandError::Busy);
+    }
+
+    let resp = debug_unlock
+        .request_challenge(unlock_level)
+        .await
+        .map_err(|_| CommandError::InternalError)?;
+    challenge.unique_device_identifier = resp.unique_device_identifier;
+    challenge.challenge = resp.challenge;
+    Ok(())
+}
+
+/// Resets into ROM once the response to the challenge request has been sent.
+#[embassy_executor::task]
+async fn unlock_reset_task() {
+    AsyncAlarm::<DefaultSyscalls>::sleep(Milliseconds(UNLOCK_RESET_DELAY_MS)).await;
+    // The device stays up without the reset, and the next challenge request retries it
+    let _ = Mci::<DefaultSyscalls>::new().trigger_warm_reset();
+}
+
+/// Appends the chunk to the token and authorizes the token once it is complete.
+///
+/// Returns the granted unlock level once the token is authorized, or None while more
+/// chunks are expected.
+pub async fn authorize_token(offset: usize, data: &[u8]) -> Result<Option<u8>, CommandError> {
+    let mut buffer = TOKEN_BUFFER.lock().await;
+
+    // Chunks are only accepted in order, and a chunk at offset 0 starts a new token
+    if offset == 0 {
+        buffer.received = 0;
+    }
+    let end = offset.saturating_add(data.len());
+    if data.is_empty() || offset != buffer.received || end > DEBUG_UNLOCK_TOKEN_SIZE {
+        buffer.received = 0;
+        return Err(CommandError::InvalidParams);
+    }
+
+    buffer.token.as_mut_bytes()[TOKEN_HEADER_SIZE + offset..TOKEN_HEADER_SIZE + end]
+        .copy_from_slice(data);
+    buffer.received = end;
+    if end < DEBUG_UNLOCK_TOKEN_SIZE {
+        return Ok(None);
+    }
+
+    buffer.received = 0;
+    let debug_unlock = DebugUnlock::new();
+    if !debug_unlock
+        .in_progress()
+        .map_err(|_| CommandError::InternalError)?
+    {
+        // No challenge was issued for the token
+        return Err(CommandError::InvalidParams);
+    }
+    let token = ProductionAuthDebugUnlockToken::mut_from_bytes(buffer.token.as_mut_bytes())
+        .map_err(|_| CommandError::InternalError)?;
+    let result = debug_unlock.authorize_token(token).await;
+
+    // Caliptra ROM ends the unlock once it has checked the token
+    let freq =
+        AsyncAlarm::<DefaultSyscalls>::get_frequency().map_err(|_| CommandError::InternalError)?;
+    let timeout_ticks = Milliseconds(UNLOCK_COMPLETE_TIMEOUT_MS).to_ticks(freq).0;
+    let start =
+        AsyncAlarm::<DefaultSyscalls>::get_ticks().map_err(|_| CommandError::InternalError)?;
+    while debug_unlock
+        .in_progress()
+        .map_err(|_| CommandError::InternalError)?
+    {
+        let now =
+            AsyncAlarm::<DefaultSyscalls>::get_ticks().map_err(|_| CommandError::InternalError)?;
+        if now.wrapping_sub(start) >= timeout_ticks {
+            return Err(CommandError::InternalError);
+        }
+        AsyncAlarm::<DefaultSyscalls>::sleep(Milliseconds(UNLOCK_POLL_INTERVAL_MS)).await;
+    }
+    let unlock_level = debug_unlock
+        .unlock_level()
+        .map_err(|_| CommandError::InternalError)?;
+    if result.is_err() || unlock_level == 0 {
+        return Err(CommandError::InvalidParams);
+    }
+    Ok(Some(unlock_level))
+}
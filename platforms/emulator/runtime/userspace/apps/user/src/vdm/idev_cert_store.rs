@@ -0,0 +1,258 @@
+// Licensed under the Apache-2.0 license
+
+//! IDevID certificate chains imported with the MCTP VDM Import Certificate command.
+//!
+//! The imported chain is the endorsed IDevID certificate followed by the certificates of
+//! its issuers, as concatenated DER certificates. The IDevID certificate must certify the
+//! key of the IDevID CSR exported by Caliptra, each certificate must be issued by the next
+//! one with ecdsa-with-SHA384, and the last certificate must be a self-signed root,
+//! otherwise the chain is rejected.
+//!
+//! A slot is provisioned once: further imports are rejected.
+//!
+//! Each certificate slot owns a fixed region of the IDevID region of the cert store flash
+//! partition:
+//! IdevCertHeader | Certificate chain (DER)
+//! The chain is only stored once Caliptra accepted its IDevID certificate, and the header is
+//! written last so an interrupted import leaves the slot unprovisioned.
+//! Caliptra only keeps the IDevID certificate until reset, so the certificate of the ECC
+//! slot is populated again from flash on every boot.
+
+use core::mem::size_of;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use external_cmds_common::{
+    CertificateState, CertificateStatus, CommandError, IDEV_ECC_CERT_SLOT, IDEV_MLDSA_CERT_SLOT,
+};
+use libapi_caliptra::certificate::{CertContext, IDEV_ECC_CSR_MAX_SIZE};
+use libapi_caliptra::crypto::asym::ecdsa::Ecdsa;
+use libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
+use libapi_caliptra::error::CaliptraApiError;
+use libsyscall_caliptra::flash::SpiFlash;
+use mctp_vdm_common::message::MAX_CERT_SIZE;
+use mcu_config_emulator::flash::{
+    CERT_STORE_PARTITION, IDEV_CERTS_REGION_OFFSET, IDEV_CERTS_REGION_SIZE,
+};
+use spdm_lib::csr::csr_subject_public_key_info;
+use x509_common::{
+    parse_ecc_p384_public_key, parse_ecdsa_signature, DerReader, X509Cert, DER_TAG_SEQUENCE,
+    ECDSA_WITH_SHA384_ALG_ID, EC_P384_PUBLIC_KEY_ALG_ID,
+};
+use zerocopy::{FromBytes, FromZeros, Immutable, IntoBytes};
+
+const IDEV_CERT_SLOT_COUNT: u32 = IDEV_MLDSA_CERT_SLOT + 1;
+const SLOT_REGION_SIZE: usize = IDEV_CERTS_REGION_SIZE / IDEV_CERT_SLOT_COUNT as usize;
+const IDEV_CERT_MAGIC: u32 = 0x4944_4556; // "IDEV"
+const HEADER_SIZE: usize = size_of::<IdevCertHeader>();
+const _: () = assert!(HEADER_SIZE + MAX_CERT_SIZE <= SLOT_REGION_SIZE);
+
+// Error details reported when the ECC certificate could not be populated into Caliptra
+const POPULATE_ERROR: u32 = 0x0000_0001;
+
+// Set when the ECC certificate is populated in Caliptra, cleared before a new import
+static ECC_CERT_POPULATED: AtomicBool = AtomicBool::new(false);
+static ERROR_DETAILS: AtomicU32 = AtomicU32::new(0);
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct IdevCertHeader {
+    magic: u32,
+    cert_len: u32,
+}
+
+/// Checks that `cert` is issued by `issuer`: its issuer name is the subject of `issuer`, and
+/// its ecdsa-with-SHA384 signature verifies with the ECC P-384 key of `issuer`.
+async fn check_issued_by(cert: &X509Cert<'_>, issuer: &X509Cert<'_>) -> Result<(), CommandError> {
+    if cert.issuer != issuer.subject
+        || cert.signature_alg_id != ECDSA_WITH_SHA384_ALG_ID
+        || issuer.public_key_alg_id != EC_P384_PUBLIC_KEY_ALG_ID
+    {
+        return Err(CommandError::InvalidParams);
+    }
+    let signature =
+        parse_ecdsa_signature(cert.signature).map_err(|_| CommandError::InvalidParams)?;
+    let (x, y) =
+        parse_ecc_p384_public_key(issuer.public_key).map_err(|_| CommandError::InvalidParams)?;
+
+    let mut tbs_hash = [0u8; SHA384_HASH_SIZE];
+    HashContext::hash_all(HashAlgoType::SHA384, cert.tbs, &mut tbs_hash)
+        .await
+        .map_err(|_| CommandError::InternalError)?;
+    Ecdsa::ecdsa_verify(x, y, &signature, tbs_hash)
+        .await
+        .map_err(|_| CommandError::InvalidParams)
+}
+
+/// Parses the next certificate of the chain.
+fn next_cert<'a>(certs: &mut DerReader<'a>) -> Result<X509Cert<'a>, CommandError> {
+    certs
+        .expect_tlv(DER_TAG_SEQUENCE)
+        .and_then(X509Cert::parse)
+        .map_err(|_| CommandError::InvalidParams)
+}
+
+/// Checks that the chain is a sequence of DER certificates whose first certificate certifies
+/// the key of the IDevID CSR, each certificate being issued by the next one and the last
+/// one being a self-signed root, and returns the length of the IDevID certificate.
+async fn check_idev_ecc_chain(chain: &[u8]) -> Result<usize, CommandError> {
+    let mut certs = DerReader::new(chain);
+    let idev_cert = next_cert(&mut certs)?;
+    let idev_cert_len = idev_cert_len(chain)?;
+    let public_key_info = idev_cert.public_key_info;
+
+    let mut cert = idev_cert;
+    while !certs.is_empty() {
+        let issuer = next_cert(&mut certs)?;
+        check_issued_by(&cert, &issuer).await?;
+        cert = issuer;
+    }
+    check_issued_by(&cert, &cert).await?;
+
+    let mut csr = [0u8; IDEV_ECC_CSR_MAX_SIZE];
+    let csr_len = CertContext::new()
+        .get_idev_csr(&mut csr)
+        .await
+        .map_err(|e| match e {
+            CaliptraApiError::UnprovisionedCsr => CommandError::Busy,
+            _ => CommandError::InternalError,
+        })?;
+    let csr_public_key_info =
+        csr_subject_public_key_info(&csr[..csr_len]).map_err(|_| CommandError::InternalError)?;
+    if csr_public_key_info != public_key_info {
+        return Err(CommandError::InvalidParams);
+    }
+    Ok(idev_cert_len)
+}
+
+/// Returns the length of the IDevID certificate, the first certificate of the chain.
+fn idev_cert_len(chain: &[u8]) -> Result<usize, CommandError> {
+    let (_, _, idev_cert) = DerReader::new(chain)
+        .read_tlv()
+        .map_err(|_| CommandError::InvalidParams)?;
+    Ok(idev_cert.len())
+}
+
+fn slot_region_offset(slot: u32) -> usize {
+    IDEV_CERTS_REGION_OFFSET + slot as usize * SLOT_REGION_SIZE
+}
+
+fn cert_store_flash() -> SpiFlash {
+    SpiFlash::new(CERT_STORE_PARTITION.driver_num)
+}
+
+/// Returns the length of the certificate chain stored in the slot, if any.
+async fn stored_cert_len(slot: u32) -> Result<Option<usize>, CommandError> {
+    let mut header = IdevCertHeader::new_zeroed();
+    cert_store_flash()
+        .read(slot_region_offset(slot), HEADER_SIZE, header.as_mut_bytes())
+        .await
+        .map_err(|_| CommandError::InternalError)?;
+
+    let cert_len = header.cert_len as usize;
+    if header.magic != IDEV_CERT_MAGIC || cert_len == 0 || cert_len > MAX_CERT_SIZE {
+        return Ok(None);
+    }
+    Ok(Some(cert_len))
+}
+
+/// Writes the certificate chain to the slot region, replacing any previous chain.
+async fn store_cert(slot: u32, cert: &[u8]) -> Result<(), CommandError> {
+    if cert.is_empty() || cert.len() > MAX_CERT_SIZE {
+        return Err(CommandError::InvalidParams);
+    }
+
+    let flash = cert_store_flash();
+    let region_offset = slot_region_offset(slot);
+    flash
+        .erase(region_offset, SLOT_REGION_SIZE)
+        .await
+        .map_err(|_| CommandError::InternalError)?;
+    flash
+        .write(region_offset + HEADER_SIZE, cert.len(), cert)
+        .await
+        .map_err(|_| CommandError::InternalError)?;
+
+    let header = IdevCertHeader {
+        magic: IDEV_CERT_MAGIC,
+        cert_len: cert.len() as u32,
+    };
+    flash
+        .write(region_offset, HEADER_SIZE, header.as_bytes())
+        .await
+        .map_err(|_| CommandError::InternalError)
+}
+
+/// Populates the IDevID ECC certificate stored in flash into the Caliptra certificate chain.
+/// Called once at boot, before the VDM responder starts.
+pub async fn restore_idev_ecc_cert() -> Result<(), CommandError> {
+    let Some(cert_len) = stored_cert_len(IDEV_ECC_CERT_SLOT).await? else {
+        return Ok(());
+    };
+
+    let mut cert = [0u8; MAX_CERT_SIZE];
+    cert_store_flash()
+        .read(
+            slot_region_offset(IDEV_ECC_CERT_SLOT) + HEADER_SIZE,
+            cert_len,
+            &mut cert[..cert_len],
+        )
+        .await
+        .map_err(|_| CommandError::InternalError)?;
+
+    let idev_cert_len = idev_cert_len(&cert[..cert_len])?;
+    match CertContext::new()
+        .populate_idev_ecc384_cert(&cert[..idev_cert_len])
+        .await
+    {
+        Ok(()) => {
+            ECC_CERT_POPULATED.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+        Err(_) => {
+            ERROR_DETAILS.store(POPULATE_ERROR, Ordering::Relaxed);
+            Err(CommandError::InternalError)
+        }
+    }
+}
+
+/// Checks the endorsed IDevID ECC certificate chain against the IDevID CSR, populates the
+/// IDevID certificate into the Caliptra certificate chain and stores the chain in flash once
+/// Caliptra accepted it. The import is rejected if the slot already holds a chain.
+pub async fn import_idev_ecc_cert(chain: &[u8]) -> Result<(), CommandError> {
+    if stored_cert_len(IDEV_ECC_CERT_SLOT).await?.is_some() {
+        return Err(CommandError::AlreadyProvisioned);
+    }
+    let idev_cert_len = check_idev_ecc_chain(chain).await?;
+
+    ERROR_DETAILS.store(0, Ordering::Relaxed);
+    if CertContext::new()
+        .populate_idev_ecc384_cert(&chain[..idev_cert_len])
+        .await
+        .is_err()
+    {
+        ERROR_DETAILS.store(POPULATE_ERROR, Ordering::Relaxed);
+        return Err(CommandError::InternalError);
+    }
+    ECC_CERT_POPULATED.store(true, Ordering::Relaxed);
+    store_cert(IDEV_ECC_CERT_SLOT, chain).await
+}
+
+/// Reports the provisioned slots. The chain is provisioned once the ECC certificate is
+/// both stored and populated into Caliptra.
+pub async fn cert_status(status: &mut CertificateStatus) -> Result<(), CommandError> {
+    let mut provisioned_slots = 0;
+    for slot in 0..IDEV_CERT_SLOT_COUNT {
+        if stored_cert_len(slot).await?.is_some() {
+            provisioned_slots |= 1 << slot;
+        }
+    }
+
+    let ecc_stored = provisioned_slots & (1 << IDEV_ECC_CERT_SLOT) != 0;
+    status.state = if ecc_stored && ECC_CERT_POPULATED.load(Ordering::Relaxed) {
+        CertificateState::Provisioned
+    } else {
+        CertificateState::NotProvisioned
+    };
+    status.error_details = ERROR_DETAILS.load(Ordering::Relaxed);
+    status.provisioned_slots = provisioned_slots;
+    Ok(())
+}
@@ -2,6 +2,10 @@
 
 #[cfg(feature = "test-mctp-vdm-cmds")]
 mod cmd_handler_mock;
+#[cfg(feature = "test-mctp-vdm-cmds")]
+mod debug_unlock;
+#[cfg(feature = "test-mctp-vdm-cmds")]
+mod idev_cert_store;
 
 use core::fmt::Write;
 #[allow(unused)]
@@ -52,6 +56,38 @@ async fn start_vdm_service() -> Result<(), ErrorCode> {
             return Ok(());
         }
 
+        // Populate the IDevID certificate imported on a previous boot. This is skipped while
+        // Caliptra ROM waits for the debug unlock, as it only accepts the unlock commands.
+        let unlock_in_progress = libapi_caliptra::debug_unlock::DebugUnlock::new()
+            .in_progress()
+            .unwrap_or(false);
+        if !unlock_in_progress && idev_cert_store::restore_idev_ecc_cert().await.is_err() {
+            writeln!(
+                console_writer,
+                "USER_APP: Failed to restore the IDevID certificate"
+            )
+            .unwrap();
+        }
+
+        // Record the debug and attestation log entries read back by the Get Log tests
+        if crate::device_logs::record_test_debug_log().await.is_err() {
+            writeln!(
+                console_writer,
+                "USER_APP: Failed to record the test debug log"
+            )
+            .unwrap();
+        }
+        if crate::device_logs::record_test_attestation_log()
+            .await
+            .is_err()
+        {
+            writeln!(
+                console_writer,
+                "USER_APP: Failed to record the test attestation log"
+            )
+            .unwrap();
+        }
+
         // Create the command interface with static storage
         let cmd_interface: &'static mut mctp_vdm_lib::cmd_interface::CmdInterface<'static> =
             CMD_INTERFACE.init(mctp_vdm_lib::cmd_interface::CmdInterface::new(
@@ -34,6 +34,10 @@ pub static MCU_MEMORY_MAP: McuMemoryMap = mcu_config_fpga::FPGA_MEMORY_MAP;
 #[used]
 pub static MCU_STRAPS: McuStraps = mcu_config_fpga::FPGA_MCU_STRAPS;
 
+/// Bytes at the start of MCU SRAM whose digest is checked before a production debug unlock
+/// starts the runtime again. The runtime kernel code, which it never writes, is larger.
+const PROD_DEBUG_UNLOCK_IMAGE_SIZE: usize = 32 * 1024;
+
 pub extern "C" fn rom_entry() -> ! {
     print_to_console("FPGA MCU ROM\n");
     unsafe {
@@ -138,6 +142,7 @@ pub extern "C" fn rom_entry() -> ! {
         cptra_dma_axi_user: axi_user0,
         mci_mbox0_axi_users: mbox_axi_users,
         mci_mbox1_axi_users: mbox_axi_users,
+        prod_debug_unlock_image_size: PROD_DEBUG_UNLOCK_IMAGE_SIZE,
         ..Default::default()
     });
 
@@ -13,8 +13,8 @@ Abstract:
 --*/
 
 use crate::{
-    fatal_error, BootFlow, McuBootMilestones, McuRomBootStatus, RomEnv, RomParameters,
-    MCU_MEMORY_MAP,
+    fatal_error, image_digest, warm_boot::prod_debug_unlock_allowed, BootFlow, McuBootMilestones,
+    McuRomBootStatus, RomEnv, RomParameters, MCU_MEMORY_MAP,
 };
 use core::fmt::Write;
 use mcu_error::McuError;
@@ -37,6 +37,12 @@ impl BootFlow for FwBoot {
             fatal_error(McuError::ROM_FW_BOOT_INVALID_FIRMWARE);
         }
 
+        // A production debug unlock starts this firmware again after a warm reset without
+        // Caliptra authenticating it, so record its digest to check it against then
+        if params.prod_debug_unlock_image_size != 0 && prod_debug_unlock_allowed(&env.mci) {
+            image_digest::record_sram_image_digest(params.prod_debug_unlock_image_size);
+        }
+
         // Jump to firmware
         romtime::println!("[mcu-rom] Jumping to firmware");
         env.mci
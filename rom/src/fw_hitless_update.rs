@@ -16,7 +16,10 @@ Abstract:
 
 #[cfg(target_arch = "riscv32")]
 use crate::MCU_MEMORY_MAP;
-use crate::{fatal_error, BootFlow, RomEnv, RomParameters};
+use crate::{
+    fatal_error, image_digest, warm_boot::prod_debug_unlock_allowed, BootFlow, RomEnv,
+    RomParameters,
+};
 use caliptra_api::{mailbox::MailboxRespHeader, CaliptraApiError};
 use core::fmt::Write;
 use mcu_error::McuError;
@@ -53,6 +56,11 @@ impl BootFlow for FwHitlessUpdate {
 
         while !soc.fw_ready() {}
 
+        // Record the digest of the new firmware for a later production debug unlock
+        if _params.prod_debug_unlock_image_size != 0 && prod_debug_unlock_allowed(&env.mci) {
+            image_digest::record_sram_image_digest(_params.prod_debug_unlock_image_size);
+        }
+
         // Jump to firmware
         romtime::println!("[mcu-rom] Jumping to firmware");
 
@@ -0,0 +1,74 @@
+// Licensed under the Apache-2.0 license
+
+//! Digest of the MCU image in SRAM, recorded when the ROM starts an image Caliptra has
+//! authenticated and checked before the ROM starts it again for a production debug unlock,
+//! when Caliptra is not running its firmware and cannot authenticate it.
+
+use crate::MCU_MEMORY_MAP;
+use mcu_error::McuError;
+use sha2::{Digest, Sha384};
+
+const IMAGE_DIGEST_SIZE: usize = 48;
+
+/// The `.persistent` section of the ROM RAM is not cleared at startup, so the digest is
+/// retained across warm resets. Unlike MCU SRAM, it is not reachable over AXI.
+#[cfg_attr(target_arch = "riscv32", link_section = ".persistent")]
+static mut RECORDED_IMAGE_DIGEST: [u8; IMAGE_DIGEST_SIZE] = [0; IMAGE_DIGEST_SIZE];
+
+/// Returns the first `size` bytes of MCU SRAM, starting with the image header.
+fn sram_image(size: usize) -> &'static [u8] {
+    // Safety: the platform sets a size within MCU SRAM
+    unsafe { core::slice::from_raw_parts(MCU_MEMORY_MAP.sram_offset as *const u8, size) }
+}
+
+fn image_digest(image: &[u8]) -> [u8; IMAGE_DIGEST_SIZE] {
+    let mut digest = [0u8; IMAGE_DIGEST_SIZE];
+    digest.copy_from_slice(&Sha384::digest(image));
+    digest
+}
+
+fn check_image_digest(image: &[u8], recorded: &[u8; IMAGE_DIGEST_SIZE]) -> Result<(), McuError> {
+    if !constant_time_eq::constant_time_eq(&image_digest(image), recorded) {
+        return Err(McuError::ROM_WARM_BOOT_DEBUG_UNLOCK_IMAGE_MISMATCH);
+    }
+    Ok(())
+}
+
+/// Record the digest of the first `size` bytes of MCU SRAM.
+pub(crate) fn record_sram_image_digest(size: usize) {
+    let digest = image_digest(sram_image(size));
+    // Safety: the ROM is single threaded
+    unsafe {
+        core::ptr::write_volatile(core::ptr::addr_of_mut!(RECORDED_IMAGE_DIGEST), digest);
+    }
+}
+
+/// Check the first `size` bytes of MCU SRAM against the recorded digest.
+pub(crate) fn verify_sram_image_digest(size: usize) -> Result<(), McuError> {
+    // Safety: the ROM is single threaded
+    let recorded = unsafe { core::ptr::read_volatile(core::ptr::addr_of!(RECORDED_IMAGE_DIGEST)) };
+    check_image_digest(sram_image(size), &recorded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unmodified_image_accepted() {
+        let image = [0x5au8; 256];
+        let recorded = image_digest(&image);
+        assert!(check_image_digest(&image, &recorded).is_ok());
+    }
+
+    #[test]
+    fn test_modified_image_refused() {
+        let mut image = [0x5au8; 256];
+        let recorded = image_digest(&image);
+        image[128] ^= 1;
+        assert_eq!(
+            check_image_digest(&image, &recorded),
+            Err(McuError::ROM_WARM_BOOT_DEBUG_UNLOCK_IMAGE_MISMATCH)
+        );
+    }
+}
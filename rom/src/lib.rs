@@ -37,6 +37,7 @@ pub use rom::*;
 mod rom_env;
 pub use rom_env::*;
 mod i3c;
+mod image_digest;
 mod recovery;
 
 // Boot flow modules
@@ -99,6 +99,24 @@ impl Soc {
             .is_set(soc::bits::CptraFlowStatus::ReadyForFuses)
     }
 
+    pub fn prod_debug_unlock_requested(&self) -> bool {
+        self.registers
+            .ss_dbg_manuf_service_reg_req
+            .is_set(soc::bits::SsDbgManufServiceRegReq::ProdDbgUnlockReq)
+    }
+
+    pub fn prod_debug_unlock_in_progress(&self) -> bool {
+        self.registers
+            .ss_dbg_manuf_service_reg_rsp
+            .is_set(soc::bits::SsDbgManufServiceRegRsp::ProdDbgUnlockInProgress)
+    }
+
+    pub fn prod_debug_unlock_complete(&self) -> bool {
+        let rsp = &self.registers.ss_dbg_manuf_service_reg_rsp;
+        rsp.is_set(soc::bits::SsDbgManufServiceRegRsp::ProdDbgUnlockSuccess)
+            || rsp.is_set(soc::bits::SsDbgManufServiceRegRsp::ProdDbgUnlockFail)
+    }
+
     pub fn cptra_fw_fatal_error(&self) -> bool {
         self.registers.cptra_fw_error_fatal.get() != 0
     }
@@ -649,6 +667,14 @@ pub struct RomParameters<'a> {
     pub otp_enable_integrity_check: bool,
     pub otp_enable_consistency_check: bool,
     pub otp_check_timeout_override: Option<u32>,
+    /// Number of bytes at the start of MCU SRAM, including the image header, whose digest is
+    /// recorded before starting the firmware and checked before starting it again for a
+    /// production debug unlock. It must only cover memory the firmware does not write, such as
+    /// its code. 0 = production debug unlock is refused.
+    pub prod_debug_unlock_image_size: usize,
+    /// Time in MCU timer ticks to wait for Caliptra to start a requested production debug
+    /// unlock. Defaults to one second with a 20 MHz timer.
+    pub prod_debug_unlock_timeout_override: Option<u64>,
     /// Request flash boot (AXI recovery bypass).
     pub request_flash_boot: bool,
     /// By default, we will set recovery status as successful after loading MCU firmware.
@@ -15,13 +15,24 @@ Abstract:
 #![allow(clippy::empty_loop)]
 
 use crate::{
-    configure_mcu_mbox_axi_users, fatal_error, verify_mcu_mbox_axi_users, AxiUsers, BootFlow,
-    McuBootMilestones, McuRomBootStatus, RomEnv, RomParameters, MCU_MEMORY_MAP,
+    configure_mcu_mbox_axi_users, fatal_error, image_digest, verify_mcu_mbox_axi_users, AxiUsers,
+    BootFlow, FwBoot, McuBootMilestones, McuRomBootStatus, RomEnv, RomParameters, MCU_MEMORY_MAP,
 };
 use caliptra_api_types::{DeviceLifecycle, SecurityState};
 use core::{fmt::Write, ops::Deref};
 use mcu_error::McuError;
 
+/// Default time in MCU timer ticks to wait for Caliptra ROM to start a production debug
+/// unlock requested by the runtime: one second with a 20 MHz timer.
+const PROD_DEBUG_UNLOCK_START_TIMEOUT: u64 = 20_000_000;
+
+/// Returns true if a production debug unlock may start the MCU firmware without Caliptra
+/// authenticating it, which is only allowed in the production lifecycle with DEBUG_INTENT set.
+pub(crate) fn prod_debug_unlock_allowed(mci: &romtime::Mci) -> bool {
+    let state = SecurityState::from(mci.security_state());
+    matches!(state.device_lifecycle(), DeviceLifecycle::Production) && mci.debug_intent()
+}
+
 pub struct WarmBoot {}
 
 impl BootFlow for WarmBoot {
@@ -125,6 +136,49 @@ impl BootFlow for WarmBoot {
         mci.set_flow_checkpoint(McuRomBootStatus::FuseWriteComplete.into());
         mci.set_flow_milestone(McuBootMilestones::CPTRA_FUSES_WRITTEN.into());
 
+        // The runtime requests production debug unlock and resets into ROM. Caliptra ROM then
+        // waits for the challenge request and token before booting its firmware, so it never
+        // reports firmware ready and cannot authenticate the MCU firmware again. MCU SRAM is
+        // retained across the warm reset, so the firmware Caliptra authenticated before the
+        // reset is started to send them instead, once its digest matches the one recorded when
+        // it was first started. This is only allowed in the production lifecycle with
+        // DEBUG_INTENT set, where the hardware latches the request.
+        if soc.prod_debug_unlock_requested() {
+            if !prod_debug_unlock_allowed(mci) || params.prod_debug_unlock_image_size == 0 {
+                romtime::println!("[mcu-rom] Production debug unlock not allowed");
+                fatal_error(McuError::ROM_WARM_BOOT_DEBUG_UNLOCK_NOT_ALLOWED);
+            }
+
+            romtime::println!("[mcu-rom] Waiting for Caliptra to start production debug unlock");
+            let timeout = params
+                .prod_debug_unlock_timeout_override
+                .unwrap_or(PROD_DEBUG_UNLOCK_START_TIMEOUT);
+            let start = mci.mtime();
+            while mci.mtime().wrapping_sub(start) < timeout {
+                if soc.prod_debug_unlock_in_progress() {
+                    if let Err(err) =
+                        image_digest::verify_sram_image_digest(params.prod_debug_unlock_image_size)
+                    {
+                        romtime::println!("[mcu-rom] MCU image changed since it was started");
+                        fatal_error(err);
+                    }
+                    FwBoot::run(env, params);
+                }
+                if soc.cptra_fw_fatal_error() {
+                    romtime::println!("[mcu-rom] Caliptra reported a fatal error");
+                    fatal_error(McuError::ROM_SOC_CALIPTRA_FATAL_ERROR_BEFORE_FW_READY);
+                }
+            }
+
+            // Caliptra ROM may have already ended the unlock, in which case it boots its
+            // firmware and authenticates the MCU firmware as on any other warm reset
+            if !soc.prod_debug_unlock_complete() {
+                romtime::println!("[mcu-rom] Caliptra did not start production debug unlock");
+                fatal_error(McuError::ROM_WARM_BOOT_DEBUG_UNLOCK_TIMEOUT);
+            }
+            romtime::println!("[mcu-rom] Production debug unlock already completed");
+        }
+
         romtime::println!("[mcu-rom] Waiting for MCU firmware to be ready");
         soc.wait_for_firmware_ready(mci);
         romtime::println!("[mcu-rom] Firmware is ready");
@@ -236,6 +236,24 @@ impl Mci {
             .map(|reg| reg.get())
     }
 
+    /// Returns true if the DEBUG_INTENT strap is set
+    pub fn debug_intent(&self) -> bool {
+        self.registers
+            .mci_reg_ss_debug_intent
+            .is_set(mci::bits::SsDebugIntent::DebugIntent)
+    }
+
+    /// Read the 64-bit MCU machine timer, re-reading if the high word changes in between
+    pub fn mtime(&self) -> u64 {
+        loop {
+            let high = self.registers.mci_reg_mcu_rv_mtime_h.get();
+            let low = self.registers.mci_reg_mcu_rv_mtime_l.get();
+            if self.registers.mci_reg_mcu_rv_mtime_h.get() == high {
+                return ((high as u64) << 32) | low as u64;
+            }
+        }
+    }
+
     /// Get the length of the production debug unlock PK hash register array
     pub fn prod_debug_unlock_pk_hash_len(&self) -> usize {
         self.registers.mci_reg_prod_debug_unlock_pk_hash_reg.len()
@@ -2,12 +2,15 @@
 
 use core::mem;
 
+use crate::static_ref::StaticRef;
 use caliptra_api::{
     calc_checksum,
     mailbox::{MailboxReqHeader, MailboxRespHeader},
     CaliptraApiError, SocManager,
 };
+use registers_generated::soc::bits::{SsDbgManufServiceRegReq, SsDbgManufServiceRegRsp};
 use registers_generated::{mbox, soc};
+use tock_registers::interfaces::{ReadWriteable, Readable};
 use ureg::RealMmioMut;
 use zerocopy::{FromBytes, IntoBytes};
 
@@ -91,6 +94,41 @@ impl CaliptraSoC {
         }
     }
 
+    fn soc_regs(&self) -> StaticRef<soc::regs::Soc> {
+        // Safety: soc_ifc_addr is the address of the Caliptra SoC interface registers
+        unsafe { StaticRef::new(self.soc_ifc_addr as *const soc::regs::Soc) }
+    }
+
+    /// Requests production debug unlock through the debug service register. Caliptra ROM
+    /// handles the request on its next boot. Returns false if the request was not latched,
+    /// which happens unless the device is in the production lifecycle with DEBUG_INTENT set.
+    pub fn request_prod_debug_unlock(&mut self) -> bool {
+        let req = &self.soc_regs().ss_dbg_manuf_service_reg_req;
+        req.modify(SsDbgManufServiceRegReq::ProdDbgUnlockReq::SET);
+        req.is_set(SsDbgManufServiceRegReq::ProdDbgUnlockReq)
+    }
+
+    /// Returns true while Caliptra ROM waits for the production debug unlock request and token.
+    pub fn prod_debug_unlock_in_progress(&mut self) -> bool {
+        self.soc_regs()
+            .ss_dbg_manuf_service_reg_rsp
+            .is_set(SsDbgManufServiceRegRsp::ProdDbgUnlockInProgress)
+    }
+
+    /// Returns the production debug unlock level granted by Caliptra, or 0 if none was granted.
+    pub fn prod_debug_unlock_level(&mut self) -> u8 {
+        let regs = self.soc_regs();
+        if !regs
+            .ss_dbg_manuf_service_reg_rsp
+            .is_set(SsDbgManufServiceRegRsp::ProdDbgUnlockSuccess)
+        {
+            return 0;
+        }
+        // Caliptra sets bit (level - 1) of SS_SOC_DBG_UNLOCK_LEVEL for the granted level
+        let level = regs.ss_soc_dbg_unlock_level[0].get();
+        (u32::BITS - level.leading_zeros()) as u8
+    }
+
     pub fn is_mailbox_busy(&mut self) -> bool {
         self.soc_mbox().status().read().status().cmd_busy()
     }
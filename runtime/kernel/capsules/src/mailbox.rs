@@ -345,6 +345,12 @@ impl<'a, A: Alarm<'a>> SyscallDriver for Mailbox<'a, A> {
     ///
     /// - `0`: Return Ok(()) if this driver is included on the platform.
     /// - `1`: Enqueue a mailbox command
+    /// - `2`: Initiate a chunked mailbox command
+    /// - `3`: Send the next chunk of a chunked mailbox command
+    /// - `4`: Execute a chunked mailbox command
+    /// - `5`: Request production debug unlock on the next Caliptra boot
+    /// - `6`: Return 1 if production debug unlock is in progress, otherwise 0
+    /// - `7`: Return the granted production debug unlock level
     fn command(
         &self,
         syscall_command_num: usize,
@@ -392,6 +398,34 @@ impl<'a, A: Alarm<'a>> SyscallDriver for Mailbox<'a, A> {
                 }
             }
 
+            5 => {
+                // Request production debug unlock
+                match self.driver.map(|driver| driver.request_prod_debug_unlock()) {
+                    Some(true) => CommandReturn::success(),
+                    Some(false) => CommandReturn::failure(ErrorCode::NOSUPPORT),
+                    None => CommandReturn::failure(ErrorCode::RESERVE),
+                }
+            }
+
+            6 => {
+                // Production debug unlock status
+                match self
+                    .driver
+                    .map(|driver| driver.prod_debug_unlock_in_progress())
+                {
+                    Some(in_progress) => CommandReturn::success_u32(in_progress as u32),
+                    None => CommandReturn::failure(ErrorCode::RESERVE),
+                }
+            }
+
+            7 => {
+                // Granted production debug unlock level
+                match self.driver.map(|driver| driver.prod_debug_unlock_level()) {
+                    Some(level) => CommandReturn::success_u32(level as u32),
+                    None => CommandReturn::failure(ErrorCode::RESERVE),
+                }
+            }
+
             _ => CommandReturn::failure(ErrorCode::NOSUPPORT),
         }
     }
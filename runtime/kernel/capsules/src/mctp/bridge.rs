@@ -0,0 +1,631 @@
+// Licensed under the Apache-2.0 license
+
+use crate::mctp::base_protocol::{
+    valid_eid, MCTPHeader, MessageType, MCTP_BASELINE_TRANSMISSION_UNIT, MCTP_HDR_SIZE,
+    MCTP_PROTOCOL_VERSION_1,
+};
+use crate::mctp::control_msg::{
+    CmdCompletionCode, EidPool, MCTPCtrlCmd, MCTPCtrlMsgHdr, SetEIDOp, SetEIDResp, SetEIDStatus,
+    MCTP_CTRL_MSG_HEADER_LEN,
+};
+use crate::mctp::driver::MCTP_MAX_MESSAGE_SIZE;
+use crate::mctp::routing::{
+    MCTPBridgePort, MCTPBridgePortInfo, MCTPRoutingTable, PhysAddr, RoutingEntryType,
+    RoutingTableEntry,
+};
+use crate::mctp::transport_binding::{MCTPTransportBinding, TransportRxClient, TransportTxClient};
+use core::cell::Cell;
+use core::fmt::Write;
+use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks};
+use kernel::utilities::cells::{OptionalCell, TakeCell};
+use kernel::ErrorCode;
+use romtime::println;
+use zerocopy::FromBytes;
+
+pub const MCTP_MAX_DOWNSTREAM_ENDPOINTS: usize = 4;
+
+/// Time allowed for a downstream endpoint to respond to a Set Endpoint ID request
+/// before the request is sent again.
+pub const MCTP_SET_EID_TIMEOUT_MS: u32 = 200;
+/// Number of Set Endpoint ID requests sent to a downstream endpoint before it is
+/// left without an EID: the first request and the two retries of MN1 in DSP0236.
+pub const MCTP_SET_EID_MAX_ATTEMPTS: u8 = 3;
+
+// Set EID request data: operation and EID
+const SET_EID_REQ_LEN: usize = 2;
+const SET_EID_RESP_LEN: usize = 4;
+
+// Length prefix of the packets in the packet queues
+const PKT_LEN_SIZE: usize = 2;
+
+/// Size of the packet queue of each port of the bridge. The queue holds a message of
+/// `MCTP_MAX_MESSAGE_SIZE` bytes split into packets of the baseline transmission unit.
+pub const MCTP_BRIDGE_QUEUE_SIZE: usize = MCTP_MAX_MESSAGE_SIZE
+    + MCTP_MAX_MESSAGE_SIZE.div_ceil(MCTP_BASELINE_TRANSMISSION_UNIT)
+        * (MCTP_HDR_SIZE + PKT_LEN_SIZE);
+
+/// Interface of the MCTP mux used by the bridge.
+pub trait MCTPBridgeClient {
+    /// Returns the EID of the bridge, used as the source EID of the messages it originates.
+    fn local_eid(&self) -> u8;
+
+    /// Called when a packet received on the downstream port is ready to be
+    /// forwarded on the upstream port.
+    fn upstream_packet_ready(&self);
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct DownstreamEndpoint {
+    phys_addr: PhysAddr,
+    /// EID assigned from the EID pool, once accepted by the endpoint
+    eid: Option<u8>,
+    /// Message tag of the Set Endpoint ID request waiting for a response
+    set_eid_tag: Option<u8>,
+}
+
+/// FIFO of the packets waiting to be transmitted on a port. The packets are stored
+/// back to back in a ring buffer, each preceded by its length.
+struct PacketQueue {
+    buf: TakeCell<'static, [u8]>,
+    // Offset of the first packet in the buffer
+    head: Cell<usize>,
+    // Number of bytes used in the buffer
+    len: Cell<usize>,
+}
+
+impl PacketQueue {
+    fn new(buf: &'static mut [u8]) -> PacketQueue {
+        PacketQueue {
+            buf: TakeCell::new(buf),
+            head: Cell::new(0),
+            len: Cell::new(0),
+        }
+    }
+
+    /// Appends a packet to the queue.
+    fn push(&self, pkt: &[u8]) -> Result<(), ErrorCode> {
+        self.buf.map_or(Err(ErrorCode::FAIL), |buf| {
+            let used = self.len.get();
+            if pkt.len() > u16::MAX as usize || used + PKT_LEN_SIZE + pkt.len() > buf.len() {
+                return Err(ErrorCode::NOMEM);
+            }
+            let start = (self.head.get() + used) % buf.len();
+            let pkt_len = (pkt.len() as u16).to_le_bytes();
+            for (i, byte) in pkt_len.iter().chain(pkt.iter()).enumerate() {
+                buf[(start + i) % buf.len()] = *byte;
+            }
+            self.len.set(used + PKT_LEN_SIZE + pkt.len());
+            Ok(())
+        })
+    }
+
+    /// Removes the first packet from the queue and copies it into `out`.
+    /// The packets that don't fit in `out` are dropped.
+    ///
+    /// # Returns
+    /// The length of the packet, or None if the queue is empty.
+    fn pop(&self, out: &mut [u8]) -> Option<usize> {
+        self.buf.map_or(None, |buf| {
+            while self.len.get() > 0 {
+                let head = self.head.get();
+                let pkt_len = u16::from_le_bytes([buf[head], buf[(head + 1) % buf.len()]]) as usize;
+                let start = head + PKT_LEN_SIZE;
+                self.head.set((start + pkt_len) % buf.len());
+                self.len.set(self.len.get() - PKT_LEN_SIZE - pkt_len);
+                if pkt_len <= out.len() {
+                    for (i, byte) in out[..pkt_len].iter_mut().enumerate() {
+                        *byte = buf[(start + i) % buf.len()];
+                    }
+                    return Some(pkt_len);
+                }
+                println!("MCTPBridge: Packet too large for the port. Dropping packet.");
+            }
+            None
+        })
+    }
+}
+
+/// MCTP bridge between the upstream binding of the MCTP mux and a downstream binding.
+///
+/// The bus owner on the upstream port allocates an EID pool to the bridge with the
+/// Allocate Endpoint IDs command. The bridge assigns EIDs from the pool to the endpoints
+/// behind it with the Set Endpoint ID command and routes the packets addressed to them
+/// on the downstream port. The packets received on the downstream port that are not
+/// addressed to the bridge are forwarded on the upstream port.
+///
+/// The packets are queued on each port while the port is busy, so that a whole
+/// message can be received before its first packet is forwarded.
+pub struct MCTPBridge<'a, A: Alarm<'a>> {
+    downstream: &'a dyn MCTPTransportBinding<'a>,
+    client: OptionalCell<&'a dyn MCTPBridgeClient>,
+    upstream_port: MCTPBridgePortInfo,
+    downstream_port: MCTPBridgePortInfo,
+    routing_table: MCTPRoutingTable,
+    endpoints: Cell<[DownstreamEndpoint; MCTP_MAX_DOWNSTREAM_ENDPOINTS]>,
+    endpoint_count: Cell<usize>,
+    eid_pool: Cell<Option<EidPool>>,
+    // Index of the next downstream endpoint to assign an EID to
+    next_set_eid: Cell<usize>,
+    // Set Endpoint ID requests sent to that endpoint
+    set_eid_attempts: Cell<u8>,
+    // The alarm sends the Set Endpoint ID request again when the response times out.
+    alarm: &'a A,
+    next_msg_tag: Cell<u8>,
+    tx_pkt_buffer: TakeCell<'static, [u8]>,
+    rx_pkt_buffer: TakeCell<'static, [u8]>,
+    // Packets received on the upstream port to be forwarded downstream
+    downstream_queue: PacketQueue,
+    // Packets received on the downstream port to be forwarded upstream
+    upstream_queue: PacketQueue,
+}
+
+impl<'a, A: Alarm<'a>> MCTPBridge<'a, A> {
+    pub fn new(
+        downstream: &'a dyn MCTPTransportBinding<'a>,
+        upstream_port: MCTPBridgePortInfo,
+        downstream_port: MCTPBridgePortInfo,
+        tx_pkt_buf: &'static mut [u8],
+        rx_pkt_buf: &'static mut [u8],
+        downstream_queue_buf: &'static mut [u8],
+        upstream_queue_buf: &'static mut [u8],
+        alarm: &'a A,
+    ) -> MCTPBridge<'a, A> {
+        MCTPBridge {
+            downstream,
+            client: OptionalCell::empty(),
+            upstream_port,
+            downstream_port,
+            routing_table: MCTPRoutingTable::new(),
+            endpoints: Cell::new([DownstreamEndpoint::default(); MCTP_MAX_DOWNSTREAM_ENDPOINTS]),
+            endpoint_count: Cell::new(0),
+            eid_pool: Cell::new(None),
+            next_set_eid: Cell::new(0),
+            set_eid_attempts: Cell::new(0),
+            alarm,
+            next_msg_tag: Cell::new(0),
+            tx_pkt_buffer: TakeCell::new(tx_pkt_buf),
+            rx_pkt_buffer: TakeCell::new(rx_pkt_buf),
+            downstream_queue: PacketQueue::new(downstream_queue_buf),
+            upstream_queue: PacketQueue::new(upstream_queue_buf),
+        }
+    }
+
+    pub fn set_client(&self, client: &'a dyn MCTPBridgeClient) {
+        self.client.set(client);
+    }
+
+    pub fn enable(&self) {
+        self.downstream.enable();
+    }
+
+    /// Adds an endpoint behind the bridge. The endpoints are assigned EIDs from the
+    /// EID pool in the order they are added.
+    ///
+    /// # Arguments
+    /// 'phys_addr' - Physical address of the endpoint on the downstream port.
+    pub fn add_downstream_endpoint(&self, phys_addr: &[u8]) -> Result<(), ErrorCode> {
+        let count = self.endpoint_count.get();
+        if count >= MCTP_MAX_DOWNSTREAM_ENDPOINTS {
+            return Err(ErrorCode::NOMEM);
+        }
+        let mut endpoints = self.endpoints.get();
+        endpoints[count] = DownstreamEndpoint {
+            phys_addr: PhysAddr::new(phys_addr)?,
+            eid: None,
+            set_eid_tag: None,
+        };
+        self.endpoints.set(endpoints);
+        self.endpoint_count.set(count + 1);
+        Ok(())
+    }
+
+    /// Number of EIDs required for the endpoints behind the bridge.
+    pub fn eid_pool_size(&self) -> u8 {
+        self.endpoint_count.get() as u8
+    }
+
+    pub fn eid_pool(&self) -> Option<EidPool> {
+        self.eid_pool.get()
+    }
+
+    pub fn routing_table(&self) -> &MCTPRoutingTable {
+        &self.routing_table
+    }
+
+    pub fn upstream_port_info(&self) -> &MCTPBridgePortInfo {
+        &self.upstream_port
+    }
+
+    /// Sets the EID pool allocated by the bus owner and assigns the EIDs to the
+    /// endpoints behind the bridge. The routes of a previous pool are dropped.
+    pub fn set_eid_pool(&self, eid_pool: EidPool) {
+        self.eid_pool.set(Some(eid_pool));
+        self.routing_table.remove_port(MCTPBridgePort::Downstream);
+
+        let mut endpoints = self.endpoints.get();
+        endpoints.iter_mut().for_each(|endpoint| {
+            endpoint.eid = None;
+            endpoint.set_eid_tag = None;
+        });
+        self.endpoints.set(endpoints);
+
+        self.next_set_eid.set(0);
+        self.set_eid_attempts.set(0);
+        let _ = self.alarm.disarm();
+        self.transmit_next_downstream();
+    }
+
+    /// Checks if the EID is routed on the downstream port.
+    pub fn routes_downstream(&self, eid: u8) -> bool {
+        self.downstream_route(eid).is_some()
+    }
+
+    fn downstream_route(&self, eid: u8) -> Option<RoutingTableEntry> {
+        self.routing_table
+            .lookup(eid)
+            .filter(|entry| entry.port == MCTPBridgePort::Downstream)
+    }
+
+    /// Forwards a packet received on the upstream port to the downstream endpoint
+    /// it is addressed to. The packet is queued while the downstream port is busy.
+    ///
+    /// # Arguments
+    /// 'packet' - The MCTP packet, starting with the MCTP header.
+    pub fn forward_downstream(&self, packet: &[u8]) -> Result<(), ErrorCode> {
+        if packet.len() < MCTP_HDR_SIZE {
+            return Err(ErrorCode::INVAL);
+        }
+        let mctp_hdr = MCTPHeader(u32::from_le_bytes(
+            packet[..MCTP_HDR_SIZE].try_into().unwrap_or([0u8; 4]),
+        ));
+        if !self.routes_downstream(mctp_hdr.dest_eid()) {
+            return Err(ErrorCode::INVAL);
+        }
+
+        self.downstream_queue.push(packet)?;
+        self.transmit_next_downstream();
+        Ok(())
+    }
+
+    /// Copies the next packet waiting to be forwarded upstream into the buffer.
+    ///
+    /// # Returns
+    /// The length of the packet, or None if there is no packet to forward.
+    pub fn take_upstream_packet(&self, buf: &mut [u8]) -> Option<usize> {
+        self.upstream_queue.pop(buf)
+    }
+
+    /// Transmits the next packet on the downstream port if it is not busy. The Set
+    /// Endpoint ID requests of the bridge are sent before the forwarded packets.
+    fn transmit_next_downstream(&self) {
+        if self.send_next_set_eid() {
+            return;
+        }
+        while let Some(tx_pkt) = self.tx_pkt_buffer.take() {
+            let offset = self.downstream.get_hdr_size();
+            let Some(len) = self.downstream_queue.pop(&mut tx_pkt[offset..]) else {
+                self.tx_pkt_buffer.replace(tx_pkt);
+                return;
+            };
+            let mctp_hdr = MCTPHeader(u32::from_le_bytes(
+                tx_pkt[offset..offset + MCTP_HDR_SIZE]
+                    .try_into()
+                    .unwrap_or([0u8; 4]),
+            ));
+            // The route may have been dropped while the packet was queued
+            let Some(entry) = self.downstream_route(mctp_hdr.dest_eid()) else {
+                self.tx_pkt_buffer.replace(tx_pkt);
+                continue;
+            };
+            match self.transmit_downstream(tx_pkt, offset + len, &entry.phys_addr) {
+                Ok(()) => return,
+                Err(err) => println!("MCTPBridge: Failed to forward packet {:?}", err),
+            }
+        }
+    }
+
+    fn transmit_downstream(
+        &self,
+        tx_pkt: &'static mut [u8],
+        len: usize,
+        phys_addr: &PhysAddr,
+    ) -> Result<(), ErrorCode> {
+        if let Err(err) = self.downstream.set_dest_phys_addr(phys_addr.as_slice()) {
+            self.tx_pkt_buffer.replace(tx_pkt);
+            return Err(err);
+        }
+        self.downstream
+            .transmit(tx_pkt, len)
+            .map_err(|(err, tx_pkt)| {
+                self.tx_pkt_buffer.replace(tx_pkt);
+                err
+            })
+    }
+
+    /// Sends a Set Endpoint ID request to the next endpoint that has no EID from the
+    /// pool. The requests are sent one at a time: the bridge moves on to the next
+    /// endpoint once the endpoint has responded, or after `MCTP_SET_EID_MAX_ATTEMPTS`
+    /// requests without an accepted response.
+    ///
+    /// # Returns
+    /// True if a request is in flight, false otherwise.
+    fn send_next_set_eid(&self) -> bool {
+        let Some(eid_pool) = self.eid_pool.get() else {
+            return false;
+        };
+        let index = self.next_set_eid.get();
+        if index >= self.endpoint_count.get().min(eid_pool.size as usize) {
+            return false;
+        }
+        let mut endpoints = self.endpoints.get();
+        // Waiting for the response to the previous request
+        if endpoints[index].set_eid_tag.is_some() {
+            return false;
+        }
+        let Some(tx_pkt) = self.tx_pkt_buffer.take() else {
+            return false;
+        };
+
+        let offset = self.downstream.get_hdr_size();
+        let len = MCTP_HDR_SIZE + MCTP_CTRL_MSG_HEADER_LEN + SET_EID_REQ_LEN;
+        if offset + len > tx_pkt.len() {
+            self.tx_pkt_buffer.replace(tx_pkt);
+            return false;
+        }
+
+        let msg_tag = self.next_msg_tag.get();
+        self.next_msg_tag.set((msg_tag + 1) % 8);
+        let local_eid = self.client.map_or(0, |client| client.local_eid());
+        // The endpoint is addressed with the null EID until it has been assigned one
+        let mctp_hdr = MCTPHeader::new(0, local_eid, 1, 1, 0, 1, msg_tag);
+        let mut ctrl_hdr = MCTPCtrlMsgHdr::new();
+        ctrl_hdr.prepare_header(1, 0, index as u8, MCTPCtrlCmd::SetEID as u8);
+
+        let pkt = &mut tx_pkt[offset..offset + len];
+        pkt[..MCTP_HDR_SIZE].copy_from_slice(&mctp_hdr.0.to_le_bytes());
+        pkt[MCTP_HDR_SIZE..MCTP_HDR_SIZE + MCTP_CTRL_MSG_HEADER_LEN]
+            .copy_from_slice(&ctrl_hdr.0.to_le_bytes()[..MCTP_CTRL_MSG_HEADER_LEN]);
+        pkt[MCTP_HDR_SIZE + MCTP_CTRL_MSG_HEADER_LEN] = SetEIDOp::SetEID as u8;
+        pkt[MCTP_HDR_SIZE + MCTP_CTRL_MSG_HEADER_LEN + 1] = eid_pool.first_eid + index as u8;
+
+        endpoints[index].set_eid_tag = Some(msg_tag);
+        self.endpoints.set(endpoints);
+        self.set_eid_attempts.set(self.set_eid_attempts.get() + 1);
+        // A request that fails to transmit is sent again when the response times out
+        let now = self.alarm.now();
+        self.alarm
+            .set_alarm(now, self.alarm.ticks_from_ms(MCTP_SET_EID_TIMEOUT_MS));
+        match self.transmit_downstream(tx_pkt, offset + len, &endpoints[index].phys_addr) {
+            Ok(()) => true,
+            Err(err) => {
+                println!(
+                    "MCTPBridge: Failed to send Set EID to downstream endpoint {}: {:?}",
+                    index, err
+                );
+                false
+            }
+        }
+    }
+
+    /// Processes the response of a downstream endpoint to a Set Endpoint ID request
+    /// and adds the route to the endpoint if it accepted the EID.
+    ///
+    /// # Arguments
+    /// 'msg_tag' - The message tag of the response, which must be the one of the request.
+    /// 'msg' - The MCTP control message.
+    fn process_set_eid_resp(&self, msg_tag: u8, msg: &[u8]) {
+        if msg.len() < MCTP_CTRL_MSG_HEADER_LEN + SET_EID_RESP_LEN {
+            return;
+        }
+        let mut hdr = [0; 4];
+        hdr[..MCTP_CTRL_MSG_HEADER_LEN].copy_from_slice(&msg[..MCTP_CTRL_MSG_HEADER_LEN]);
+        let ctrl_hdr = MCTPCtrlMsgHdr(u32::from_le_bytes(hdr));
+        if ctrl_hdr.rq() != 0 || ctrl_hdr.cmd() != MCTPCtrlCmd::SetEID as u8 {
+            return;
+        }
+
+        let Ok(resp) = SetEIDResp::<[u8; SET_EID_RESP_LEN]>::read_from_bytes(
+            &msg[MCTP_CTRL_MSG_HEADER_LEN..MCTP_CTRL_MSG_HEADER_LEN + SET_EID_RESP_LEN],
+        ) else {
+            return;
+        };
+        let index = ctrl_hdr.instance_id() as usize;
+        let mut endpoints = self.endpoints.get();
+        let (Some(eid_pool), Some(endpoint)) = (
+            self.eid_pool.get(),
+            endpoints[..self.endpoint_count.get()].get_mut(index),
+        ) else {
+            return;
+        };
+        if endpoint.set_eid_tag != Some(msg_tag) {
+            println!(
+                "MCTPBridge: Unexpected Set EID response from downstream endpoint {}",
+                index
+            );
+            return;
+        }
+        endpoint.set_eid_tag = None;
+        self.endpoints.set(endpoints);
+        let _ = self.alarm.disarm();
+
+        let eid = resp.assigned_eid();
+        if resp.completion_code() != CmdCompletionCode::Success as u8
+            || resp.eid_assign_status() != SetEIDStatus::Accepted as u8
+            || eid != eid_pool.first_eid + index as u8
+        {
+            println!(
+                "MCTPBridge: Downstream endpoint {} rejected EID {}",
+                index,
+                eid_pool.first_eid + index as u8
+            );
+            self.retry_set_eid(index);
+            return;
+        }
+
+        endpoints[index].eid = Some(eid);
+        let entry = RoutingTableEntry {
+            starting_eid: eid,
+            eid_range_size: 1,
+            entry_type: RoutingEntryType::SingleEndpoint,
+            port: MCTPBridgePort::Downstream,
+            port_info: self.downstream_port,
+            phys_addr: endpoints[index].phys_addr,
+        };
+        self.endpoints.set(endpoints);
+        if let Err(err) = self.routing_table.update(entry) {
+            println!("MCTPBridge: Failed to add route to EID {}: {:?}", eid, err);
+        }
+        self.next_set_eid.set(index + 1);
+        self.set_eid_attempts.set(0);
+        self.transmit_next_downstream();
+    }
+
+    /// Sends the Set Endpoint ID request to the endpoint again after a failed attempt,
+    /// or moves on to the next endpoint once all the attempts have been made.
+    fn retry_set_eid(&self, index: usize) {
+        if self.set_eid_attempts.get() >= MCTP_SET_EID_MAX_ATTEMPTS {
+            println!(
+                "MCTPBridge: No EID assigned to downstream endpoint {}",
+                index
+            );
+            self.next_set_eid.set(index + 1);
+            self.set_eid_attempts.set(0);
+        }
+        self.transmit_next_downstream();
+    }
+}
+
+impl<'a, A: Alarm<'a>> AlarmClient for MCTPBridge<'a, A> {
+    fn alarm(&self) {
+        let index = self.next_set_eid.get();
+        let mut endpoints = self.endpoints.get();
+        let Some(endpoint) = endpoints[..self.endpoint_count.get()].get_mut(index) else {
+            return;
+        };
+        if endpoint.set_eid_tag.take().is_none() {
+            return;
+        }
+        self.endpoints.set(endpoints);
+        println!(
+            "MCTPBridge: Set EID to downstream endpoint {} timed out",
+            index
+        );
+        self.retry_set_eid(index);
+    }
+}
+
+impl<'a, A: Alarm<'a>> TransportTxClient for MCTPBridge<'a, A> {
+    fn send_done(&self, tx_buffer: &'static mut [u8], result: Result<(), ErrorCode>) {
+        self.tx_pkt_buffer.replace(tx_buffer);
+        if let Err(err) = result {
+            println!("MCTPBridge: Failed to transmit downstream {:?}", err);
+        }
+        self.transmit_next_downstream();
+    }
+}
+
+impl<'a, A: Alarm<'a>> TransportRxClient for MCTPBridge<'a, A> {
+    fn receive(&self, rx_buffer: &'static mut [u8], len: usize) {
+        if len < MCTP_HDR_SIZE + 1 || len > rx_buffer.len() {
+            println!("MCTPBridge: Invalid packet length. Dropping packet.");
+            self.rx_pkt_buffer.replace(rx_buffer);
+            return;
+        }
+
+        let packet = &rx_buffer[..len];
+        let mctp_hdr = MCTPHeader(u32::from_le_bytes(
+            packet[..MCTP_HDR_SIZE].try_into().unwrap_or([0u8; 4]),
+        ));
+        let dest_eid = mctp_hdr.dest_eid();
+        let local_eid = self.client.map_or(0, |client| client.local_eid());
+
+        if mctp_hdr.hdr_version() != MCTP_PROTOCOL_VERSION_1 {
+            println!("MCTPBridge: Invalid header version. Dropping packet.");
+        } else if dest_eid == local_eid {
+            // Only the responses to the requests of the bridge are handled locally
+            if mctp_hdr.som() == 1
+                && mctp_hdr.eom() == 1
+                && mctp_hdr.tag_owner() == 0
+                && (packet[MCTP_HDR_SIZE] & 0x7F) == MessageType::MctpControl as u8
+            {
+                self.process_set_eid_resp(mctp_hdr.msg_tag(), &packet[MCTP_HDR_SIZE..]);
+            } else {
+                println!("MCTPBridge: Unexpected packet for the bridge. Dropping packet.");
+            }
+        } else if dest_eid == 0 || !valid_eid(dest_eid) {
+            println!("MCTPBridge: Invalid destination EID. Dropping packet.");
+        } else if self.upstream_queue.push(packet).is_ok() {
+            self.client.map(|client| client.upstream_packet_ready());
+        } else {
+            println!("MCTPBridge: Upstream queue full. Dropping packet.");
+        }
+        self.rx_pkt_buffer.replace(rx_buffer);
+    }
+
+    fn write_expected(&self) {
+        if let Some(rx_buf) = self.rx_pkt_buffer.take() {
+            self.downstream.set_rx_buffer(rx_buf);
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(size: usize) -> PacketQueue {
+        PacketQueue::new(Box::leak(vec![0u8; size].into_boxed_slice()))
+    }
+
+    #[test]
+    fn test_packet_queue_fifo() {
+        let queue = queue(32);
+        let mut out = [0u8; 16];
+        assert_eq!(queue.pop(&mut out), None);
+
+        queue.push(&[1, 2, 3]).unwrap();
+        queue.push(&[4, 5]).unwrap();
+        assert_eq!(queue.pop(&mut out), Some(3));
+        assert_eq!(&out[..3], &[1, 2, 3]);
+        assert_eq!(queue.pop(&mut out), Some(2));
+        assert_eq!(&out[..2], &[4, 5]);
+        assert_eq!(queue.pop(&mut out), None);
+    }
+
+    #[test]
+    fn test_packet_queue_full() {
+        let queue = queue(16);
+        queue.push(&[0xAA; 8]).unwrap();
+        assert_eq!(queue.push(&[0xBB; 5]), Err(ErrorCode::NOMEM));
+        queue.push(&[0xBB; 4]).unwrap();
+
+        let mut out = [0u8; 16];
+        assert_eq!(queue.pop(&mut out), Some(8));
+        queue.push(&[0xCC; 8]).unwrap();
+    }
+
+    #[test]
+    fn test_packet_queue_wrap_around() {
+        let queue = queue(16);
+        let mut out = [0u8; 16];
+        queue.push(&[0xAA; 10]).unwrap();
+        assert_eq!(queue.pop(&mut out), Some(10));
+
+        // The length and the data of the packet wrap around the end of the buffer
+        let pkt: Vec<u8> = (0..12).collect();
+        queue.push(&pkt).unwrap();
+        assert_eq!(queue.pop(&mut out), Some(12));
+        assert_eq!(&out[..12], &pkt[..]);
+        assert!(queue.pop(&mut out).is_none());
+    }
+
+    #[test]
+    fn test_packet_queue_drops_oversized_packet() {
+        let queue = queue(32);
+        let mut out = [0u8; 4];
+        queue.push(&[0xAA; 8]).unwrap();
+        queue.push(&[0xBB; 4]).unwrap();
+        assert_eq!(queue.pop(&mut out), Some(4));
+        assert_eq!(out, [0xBB; 4]);
+    }
+}
@@ -1,11 +1,30 @@
 // Licensed under the Apache-2.0 license
 
-use crate::mctp::base_protocol::{valid_eid, MessageType, MCTP_NUM_MSG_TYPES_SUPPORTED};
+use crate::mctp::base_protocol::{
+    valid_eid, MessageType, MCTP_BASELINE_TRANSMISSION_UNIT, MCTP_NUM_MSG_TYPES_SUPPORTED,
+};
+use crate::mctp::routing::{
+    MCTPBridgePort, MCTPBridgePortInfo, MCTPRoutingTable, PhysAddr, RoutingEntryType,
+    RoutingTableEntry,
+};
 use bitfield::bitfield;
 use kernel::ErrorCode;
 use zerocopy::{FromBytes, Immutable, IntoBytes};
 
 pub const MCTP_CTRL_MSG_HEADER_LEN: usize = 3;
+pub const MCTP_UUID_LEN: usize = 16;
+pub const MAX_VENDOR_DEFINED_MSG_SUPPORT: usize = 4;
+
+// Vendor ID Set Selector value indicating that there are no more capability sets
+const VDM_SUPPORT_NO_MORE_SETS: u8 = 0xFF;
+// Vendor ID format for a PCI vendor ID
+const VDM_VENDOR_ID_FORMAT_PCI: u8 = 0x00;
+// Entry handle value indicating that there are no more routing table entries
+const ROUTING_TABLE_NO_MORE_ENTRIES: u8 = 0xFF;
+// Size of a routing table entry in Get Routing Table Entries, without the physical address
+const ROUTING_TABLE_ENTRY_HDR_LEN: usize = 6;
+// Size of a Routing Information Update entry, without the physical address
+const ROUTING_INFO_UPDATE_ENTRY_HDR_LEN: usize = 3;
 
 bitfield! {
     #[derive(Default)]
@@ -36,8 +55,13 @@ impl MCTPCtrlMsgHdr {
 pub enum MCTPCtrlCmd {
     SetEID = 1,
     GetEID = 2,
+    GetEndpointUUID = 3,
     GetMsgTypeSupport = 5,
     GetVersionSupport = 4,
+    GetVendorDefinedMsgSupport = 6,
+    AllocateEndpointIDs = 8,
+    RoutingInformationUpdate = 9,
+    GetRoutingTableEntries = 0x0A,
     Unsupported = 0xFF,
 }
 
@@ -46,8 +70,13 @@ impl From<u8> for MCTPCtrlCmd {
         match val {
             1 => MCTPCtrlCmd::SetEID,
             2 => MCTPCtrlCmd::GetEID,
+            3 => MCTPCtrlCmd::GetEndpointUUID,
             4 => MCTPCtrlCmd::GetVersionSupport,
             5 => MCTPCtrlCmd::GetMsgTypeSupport,
+            6 => MCTPCtrlCmd::GetVendorDefinedMsgSupport,
+            8 => MCTPCtrlCmd::AllocateEndpointIDs,
+            9 => MCTPCtrlCmd::RoutingInformationUpdate,
+            0x0A => MCTPCtrlCmd::GetRoutingTableEntries,
             _ => MCTPCtrlCmd::Unsupported,
         }
     }
@@ -58,8 +87,13 @@ impl MCTPCtrlCmd {
         match self {
             MCTPCtrlCmd::SetEID => 2,
             MCTPCtrlCmd::GetEID => 0,
+            MCTPCtrlCmd::GetEndpointUUID => 0,
             MCTPCtrlCmd::GetVersionSupport => 1,
             MCTPCtrlCmd::GetMsgTypeSupport => 0,
+            MCTPCtrlCmd::GetVendorDefinedMsgSupport => 1,
+            MCTPCtrlCmd::AllocateEndpointIDs => 3,
+            MCTPCtrlCmd::RoutingInformationUpdate => 1,
+            MCTPCtrlCmd::GetRoutingTableEntries => 1,
             MCTPCtrlCmd::Unsupported => 0,
         }
     }
@@ -68,8 +102,13 @@ impl MCTPCtrlCmd {
         match self {
             MCTPCtrlCmd::SetEID => 4,
             MCTPCtrlCmd::GetEID => 4,
+            MCTPCtrlCmd::GetEndpointUUID => 1 + MCTP_UUID_LEN, // 1 byte for completion code + UUID
             MCTPCtrlCmd::GetVersionSupport => 18, // 2 bytes header + 4 entries * 4 bytes each
             MCTPCtrlCmd::GetMsgTypeSupport => 2 + MCTP_NUM_MSG_TYPES_SUPPORTED, // 1 byte for completion code + 1 byte for count + supported message types
+            MCTPCtrlCmd::GetVendorDefinedMsgSupport => 7, // 3 bytes header + 2 bytes PCI vendor ID + 2 bytes command set version
+            MCTPCtrlCmd::AllocateEndpointIDs => 4, // 1 byte for completion code + 1 byte for allocation status + 1 byte for pool size + 1 byte for first EID
+            MCTPCtrlCmd::RoutingInformationUpdate => 1, // 1 byte for completion code
+            MCTPCtrlCmd::GetRoutingTableEntries => 3, // 1 byte for completion code + 1 byte for next entry handle + 1 byte for count
             MCTPCtrlCmd::Unsupported => 0,
         }
     }
@@ -77,6 +116,8 @@ impl MCTPCtrlCmd {
     pub fn process_set_endpoint_id(
         &self,
         req: &[u8],
+        eid_pool_size: u8,
+        eid_pool: Option<EidPool>,
         rsp_buf: &mut [u8],
     ) -> Result<Option<u8>, ErrorCode> {
         if req.len() < self.req_data_len() || rsp_buf.len() < self.resp_data_len() {
@@ -98,9 +139,14 @@ impl MCTPCtrlCmd {
                 } else {
                     // TODO: Check if rejected case needs to be handled
                     set_status = SetEIDStatus::Accepted;
-                    resp.set_eid_alloc_status(SetEIDAllocStatus::NoEIDPool as u8);
+                    let alloc_status = match (eid_pool_size, eid_pool) {
+                        (0, _) => SetEIDAllocStatus::NoEIDPool,
+                        (_, None) => SetEIDAllocStatus::PoolRequired,
+                        (_, Some(_)) => SetEIDAllocStatus::PoolAllocated,
+                    };
+                    resp.set_eid_alloc_status(alloc_status as u8);
                     resp.set_assigned_eid(eid);
-                    resp.set_eid_pool_size(0);
+                    resp.set_eid_pool_size(eid_pool_size);
                 }
             }
             SetEIDOp::ResetEID | SetEIDOp::SetDiscoveredFlag => {
@@ -124,6 +170,7 @@ impl MCTPCtrlCmd {
     pub fn process_get_endpoint_id(
         &self,
         local_eid: u8,
+        endpoint_type: EndpointType,
         rsp_buf: &mut [u8],
     ) -> Result<(), ErrorCode> {
         if rsp_buf.len() < self.resp_data_len() {
@@ -133,6 +180,7 @@ impl MCTPCtrlCmd {
 
         resp.set_completion_code(CmdCompletionCode::Success as u8);
         resp.set_eid(local_eid);
+        resp.set_endpoint_type(endpoint_type as u8);
         resp.set_eid_type(EIDType::DynamicOnly as u8);
 
         resp.write_to(&mut rsp_buf[..self.resp_data_len()])
@@ -234,6 +282,263 @@ impl MCTPCtrlCmd {
 
         Ok(())
     }
+
+    pub fn process_get_endpoint_uuid(
+        &self,
+        uuid: Option<[u8; MCTP_UUID_LEN]>,
+        rsp_buf: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        if rsp_buf.len() < self.resp_data_len() {
+            return Err(ErrorCode::NOMEM);
+        }
+        let rsp_buf = &mut rsp_buf[..self.resp_data_len()];
+        rsp_buf.fill(0);
+
+        match uuid {
+            Some(uuid) => {
+                rsp_buf[0] = CmdCompletionCode::Success as u8;
+                rsp_buf[1..].copy_from_slice(&uuid);
+            }
+            // The UUID is set once the device unique ID is known
+            None => rsp_buf[0] = CmdCompletionCode::ErrorNotReady as u8,
+        }
+        Ok(())
+    }
+
+    pub fn process_get_vendor_defined_msg_support(
+        &self,
+        req: &[u8],
+        vdm_support: &[VendorDefinedMsgSupport],
+        rsp_buf: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        if req.len() < self.req_data_len() || rsp_buf.len() < self.resp_data_len() {
+            return Err(ErrorCode::NOMEM);
+        }
+        let rsp_buf = &mut rsp_buf[..self.resp_data_len()];
+        rsp_buf.fill(0);
+
+        let selector = req[0] as usize;
+        match vdm_support.get(selector) {
+            Some(support) => {
+                let next_selector = if selector + 1 < vdm_support.len() {
+                    (selector + 1) as u8
+                } else {
+                    VDM_SUPPORT_NO_MORE_SETS
+                };
+                rsp_buf[0] = CmdCompletionCode::Success as u8;
+                rsp_buf[1] = next_selector;
+                rsp_buf[2] = VDM_VENDOR_ID_FORMAT_PCI;
+                rsp_buf[3..5].copy_from_slice(&support.vendor_id.to_be_bytes());
+                rsp_buf[5..7].copy_from_slice(&support.cmd_set_version.to_be_bytes());
+            }
+            None => rsp_buf[0] = CmdCompletionCode::ErrorInvalidData as u8,
+        }
+        Ok(())
+    }
+
+    pub fn process_allocate_endpoint_ids(
+        &self,
+        req: &[u8],
+        eid_pool_size: u8,
+        eid_pool: Option<EidPool>,
+        rsp_buf: &mut [u8],
+    ) -> Result<Option<EidPool>, ErrorCode> {
+        if req.len() < self.req_data_len() || rsp_buf.len() < self.resp_data_len() {
+            return Err(ErrorCode::NOMEM);
+        }
+        let rsp_buf = &mut rsp_buf[..self.resp_data_len()];
+        rsp_buf.fill(0);
+
+        let requested_pool = EidPool {
+            first_eid: req[2],
+            size: req[1],
+        };
+        let (completion_code, alloc_status, allocated_pool) = match AllocateEIDsOp::from(req[0]) {
+            AllocateEIDsOp::AllocateEIDs if eid_pool.is_some() => (
+                CmdCompletionCode::Success,
+                AllocateEIDsStatus::Rejected,
+                None,
+            ),
+            AllocateEIDsOp::AllocateEIDs | AllocateEIDsOp::ForceAllocation => {
+                if requested_pool.is_valid(eid_pool_size) {
+                    (
+                        CmdCompletionCode::Success,
+                        AllocateEIDsStatus::Accepted,
+                        Some(requested_pool),
+                    )
+                } else {
+                    (
+                        CmdCompletionCode::ErrorInvalidData,
+                        AllocateEIDsStatus::Rejected,
+                        None,
+                    )
+                }
+            }
+            AllocateEIDsOp::GetAllocationInfo => (
+                CmdCompletionCode::Success,
+                AllocateEIDsStatus::Accepted,
+                None,
+            ),
+            AllocateEIDsOp::Reserved => (
+                CmdCompletionCode::ErrorInvalidData,
+                AllocateEIDsStatus::Rejected,
+                None,
+            ),
+        };
+
+        // Report the pool in use after this request
+        let pool = allocated_pool.or(eid_pool).unwrap_or_default();
+        rsp_buf[0] = completion_code as u8;
+        rsp_buf[1] = alloc_status as u8;
+        rsp_buf[2] = pool.size;
+        rsp_buf[3] = pool.first_eid;
+        Ok(allocated_pool)
+    }
+
+    pub fn process_routing_information_update(
+        &self,
+        req: &[u8],
+        port_info: &MCTPBridgePortInfo,
+        routing_table: &MCTPRoutingTable,
+        rsp_buf: &mut [u8],
+    ) -> Result<(), ErrorCode> {
+        if req.len() < self.req_data_len() || rsp_buf.len() < self.resp_data_len() {
+            return Err(ErrorCode::NOMEM);
+        }
+
+        let entry_len = ROUTING_INFO_UPDATE_ENTRY_HDR_LEN + port_info.phys_addr_len;
+        let num_entries = req[0] as usize;
+        let entries = &req[self.req_data_len()..];
+        if entries.len() < num_entries * entry_len {
+            rsp_buf[0] = CmdCompletionCode::ErrorInvalidLength as u8;
+            return Ok(());
+        }
+
+        let mut completion_code = CmdCompletionCode::Success;
+        for entry in entries.chunks_exact(entry_len).take(num_entries) {
+            let result =
+                PhysAddr::new(&entry[ROUTING_INFO_UPDATE_ENTRY_HDR_LEN..]).and_then(|phys_addr| {
+                    // The entries are learned from the bus owner on the upstream port
+                    routing_table.update(RoutingTableEntry {
+                        starting_eid: entry[2],
+                        eid_range_size: entry[1],
+                        entry_type: RoutingEntryType::from(entry[0]),
+                        port: MCTPBridgePort::Upstream,
+                        port_info: *port_info,
+                        phys_addr,
+                    })
+                });
+            match result {
+                Ok(()) => {}
+                Err(ErrorCode::NOMEM) => completion_code = CmdCompletionCode::Error,
+                Err(_) => completion_code = CmdCompletionCode::ErrorInvalidData,
+            }
+        }
+        rsp_buf[0] = completion_code as u8;
+        Ok(())
+    }
+
+    /// Returns the length of the response, which depends on the number of entries
+    /// that fit in a single packet.
+    pub fn process_get_routing_table_entries(
+        &self,
+        req: &[u8],
+        entries: &[RoutingTableEntry],
+        rsp_buf: &mut [u8],
+    ) -> Result<usize, ErrorCode> {
+        if req.len() < self.req_data_len() || rsp_buf.len() < self.resp_data_len() {
+            return Err(ErrorCode::NOMEM);
+        }
+        // The response is sent in a single packet
+        let max_rsp_len = rsp_buf
+            .len()
+            .min(MCTP_BASELINE_TRANSMISSION_UNIT - MCTP_CTRL_MSG_HEADER_LEN);
+
+        let handle = req[0] as usize;
+        rsp_buf[1] = ROUTING_TABLE_NO_MORE_ENTRIES;
+        rsp_buf[2] = 0;
+        if handle >= entries.len() {
+            // An endpoint without routes only has the first entry handle
+            rsp_buf[0] = if handle == 0 {
+                CmdCompletionCode::Success as u8
+            } else {
+                CmdCompletionCode::ErrorInvalidData as u8
+            };
+            return Ok(self.resp_data_len());
+        }
+
+        let mut rsp_len = self.resp_data_len();
+        let mut count = 0;
+        for (index, entry) in entries.iter().enumerate().skip(handle) {
+            let phys_addr = entry.phys_addr.as_slice();
+            let entry_len = ROUTING_TABLE_ENTRY_HDR_LEN + phys_addr.len();
+            if rsp_len + entry_len > max_rsp_len {
+                rsp_buf[1] = index as u8;
+                break;
+            }
+            let entry_buf = &mut rsp_buf[rsp_len..rsp_len + entry_len];
+            entry_buf[0] = entry.eid_range_size;
+            entry_buf[1] = entry.starting_eid;
+            // All the entries are dynamic
+            entry_buf[2] = ((entry.entry_type as u8) << 6) | (entry.port as u8 & 0x1F);
+            entry_buf[3] = entry.port_info.binding_type;
+            entry_buf[4] = entry.port_info.media_type;
+            entry_buf[5] = phys_addr.len() as u8;
+            entry_buf[ROUTING_TABLE_ENTRY_HDR_LEN..].copy_from_slice(phys_addr);
+            rsp_len += entry_len;
+            count += 1;
+        }
+        rsp_buf[0] = CmdCompletionCode::Success as u8;
+        rsp_buf[2] = count;
+        Ok(rsp_len)
+    }
+}
+
+/// EID pool allocated to an MCTP bridge by the bus owner for the endpoints behind it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EidPool {
+    pub first_eid: u8,
+    pub size: u8,
+}
+
+impl EidPool {
+    fn is_valid(&self, max_size: u8) -> bool {
+        self.size > 0
+            && self.size <= max_size
+            && self.first_eid != 0
+            && valid_eid(self.first_eid)
+            && self
+                .first_eid
+                .checked_add(self.size - 1)
+                .is_some_and(valid_eid)
+    }
+
+    pub fn contains(&self, eid: u8) -> bool {
+        eid >= self.first_eid && (eid - self.first_eid) < self.size
+    }
+}
+
+/// Vendor defined message capability set reported in Get Vendor Defined Message Support.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VendorDefinedMsgSupport {
+    /// PCI vendor ID of the vendor defined messages
+    pub vendor_id: u16,
+    /// Version of the vendor defined command set
+    pub cmd_set_version: u16,
+}
+
+/// Endpoint information reported in the MCTP control messages that is provided
+/// by the MCTP message type drivers.
+pub trait MCTPEndpointInfo {
+    /// Sets the UUID reported in Get Endpoint UUID.
+    fn set_endpoint_uuid(&self, uuid: [u8; MCTP_UUID_LEN]);
+
+    /// Adds a vendor defined message capability set reported in Get Vendor Defined
+    /// Message Support. Registering a vendor ID again updates its command set version.
+    fn register_vendor_defined_msg_support(
+        &self,
+        support: VendorDefinedMsgSupport,
+    ) -> Result<(), ErrorCode>;
 }
 
 pub enum CmdCompletionCode {
@@ -324,6 +629,8 @@ pub enum SetEIDStatus {
 
 pub enum SetEIDAllocStatus {
     NoEIDPool,
+    PoolRequired,
+    PoolAllocated,
 }
 
 // Get EID Request has no fields
@@ -337,7 +644,7 @@ bitfield! {
     pub completion_code, set_completion_code: 7, 0;
     pub eid, set_eid: 15, 8;
     rsvd1, _: 17, 16;
-    pub endpoint_type, _: 19, 18;
+    pub endpoint_type, set_endpoint_type: 19, 18;
     rsvd2, _: 21, 20;
     pub eid_type, set_eid_type: 23, 22;
     pub medium_spec_info, _: 31, 24;
@@ -360,6 +667,30 @@ pub enum EndpointType {
     BusOwnerBridge,
 }
 
+// Allocate Endpoint IDs Request operation
+pub enum AllocateEIDsOp {
+    AllocateEIDs,
+    ForceAllocation,
+    GetAllocationInfo,
+    Reserved,
+}
+
+impl From<u8> for AllocateEIDsOp {
+    fn from(val: u8) -> AllocateEIDsOp {
+        match val & 0x03 {
+            0 => AllocateEIDsOp::AllocateEIDs,
+            1 => AllocateEIDsOp::ForceAllocation,
+            2 => AllocateEIDsOp::GetAllocationInfo,
+            _ => AllocateEIDsOp::Reserved,
+        }
+    }
+}
+
+pub enum AllocateEIDsStatus {
+    Accepted = 0,
+    Rejected = 1,
+}
+
 impl From<u8> for EndpointType {
     fn from(val: u8) -> EndpointType {
         match val {
@@ -491,7 +822,7 @@ mod tests {
 
         let rsp_buf = &mut [0; 4];
         let eid = MCTPCtrlCmd::SetEID
-            .process_set_endpoint_id(&msg_req, rsp_buf)
+            .process_set_endpoint_id(&msg_req, 0, None, rsp_buf)
             .unwrap();
         assert!(eid.is_some());
         assert_eq!(eid.unwrap(), 0x0A);
@@ -510,7 +841,7 @@ mod tests {
 
         let rsp_buf = &mut [0; 4];
         let eid = MCTPCtrlCmd::SetEID
-            .process_set_endpoint_id(&msg_req, rsp_buf)
+            .process_set_endpoint_id(&msg_req, 0, None, rsp_buf)
             .unwrap();
         assert!(eid.is_none());
 
@@ -527,7 +858,7 @@ mod tests {
 
         let rsp_buf = &mut [0; 4];
         let eid = MCTPCtrlCmd::SetEID
-            .process_set_endpoint_id(&msg_req, rsp_buf)
+            .process_set_endpoint_id(&msg_req, 0, None, rsp_buf)
             .unwrap();
         assert!(eid.is_none());
 
@@ -542,7 +873,7 @@ mod tests {
     fn test_get_endpoint_id() {
         let rsp_buf = &mut [0; 4];
         MCTPCtrlCmd::GetEID
-            .process_get_endpoint_id(0x0A, rsp_buf)
+            .process_get_endpoint_id(0x0A, EndpointType::Simple, rsp_buf)
             .unwrap();
 
         let rsp: GetEIDResp<[u8; 4]> = GetEIDResp::read_from_bytes(rsp_buf).unwrap();
@@ -629,4 +960,147 @@ mod tests {
             assert_eq!(rsp_buf[2 + i], MessageType::supported()[i] as u8);
         }
     }
+
+    #[test]
+    fn test_get_endpoint_uuid() {
+        let uuid = [0xA5; MCTP_UUID_LEN];
+        let rsp_buf = &mut [0; 1 + MCTP_UUID_LEN];
+        MCTPCtrlCmd::GetEndpointUUID
+            .process_get_endpoint_uuid(Some(uuid), rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_buf[0], CmdCompletionCode::Success as u8);
+        assert_eq!(&rsp_buf[1..], &uuid);
+
+        MCTPCtrlCmd::GetEndpointUUID
+            .process_get_endpoint_uuid(None, rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_buf[0], CmdCompletionCode::ErrorNotReady as u8);
+    }
+
+    #[test]
+    fn test_get_vendor_defined_msg_support() {
+        let vdm_support = [
+            VendorDefinedMsgSupport {
+                vendor_id: 0x1414,
+                cmd_set_version: 0x0001,
+            },
+            VendorDefinedMsgSupport {
+                vendor_id: 0x8086,
+                cmd_set_version: 0x0102,
+            },
+        ];
+        let rsp_buf = &mut [0; 7];
+
+        MCTPCtrlCmd::GetVendorDefinedMsgSupport
+            .process_get_vendor_defined_msg_support(&[0], &vdm_support, rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_buf, &[0x00, 0x01, 0x00, 0x14, 0x14, 0x00, 0x01]);
+
+        MCTPCtrlCmd::GetVendorDefinedMsgSupport
+            .process_get_vendor_defined_msg_support(&[1], &vdm_support, rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_buf, &[0x00, 0xFF, 0x00, 0x80, 0x86, 0x01, 0x02]);
+
+        MCTPCtrlCmd::GetVendorDefinedMsgSupport
+            .process_get_vendor_defined_msg_support(&[2], &vdm_support, rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_buf[0], CmdCompletionCode::ErrorInvalidData as u8);
+    }
+
+    #[test]
+    fn test_get_routing_table_entries() {
+        let rsp_buf = &mut [0; 3];
+        let rsp_len = MCTPCtrlCmd::GetRoutingTableEntries
+            .process_get_routing_table_entries(&[0], &[], rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_len, 3);
+        assert_eq!(rsp_buf, &[0x00, 0xFF, 0x00]);
+    }
+
+    #[test]
+    fn test_set_endpoint_id_bridge() {
+        let rsp_buf = &mut [0; 4];
+        MCTPCtrlCmd::SetEID
+            .process_set_endpoint_id(&[0x00, 0x0A], 2, None, rsp_buf)
+            .unwrap();
+        let rsp: SetEIDResp<[u8; 4]> = SetEIDResp::read_from_bytes(rsp_buf).unwrap();
+        assert_eq!(
+            rsp.eid_alloc_status(),
+            SetEIDAllocStatus::PoolRequired as u8
+        );
+        assert_eq!(rsp.eid_pool_size(), 2);
+    }
+
+    #[test]
+    fn test_allocate_endpoint_ids() {
+        let rsp_buf = &mut [0; 4];
+        let pool = MCTPCtrlCmd::AllocateEndpointIDs
+            .process_allocate_endpoint_ids(&[0x00, 0x02, 0x20], 2, None, rsp_buf)
+            .unwrap();
+        let pool = pool.unwrap();
+        assert_eq!(pool.first_eid, 0x20);
+        assert_eq!(pool.size, 2);
+        assert_eq!(rsp_buf, &[0x00, 0x00, 0x02, 0x20]);
+
+        // A second allocation is rejected unless forced
+        let new_pool = MCTPCtrlCmd::AllocateEndpointIDs
+            .process_allocate_endpoint_ids(&[0x00, 0x02, 0x30], 2, Some(pool), rsp_buf)
+            .unwrap();
+        assert!(new_pool.is_none());
+        assert_eq!(rsp_buf, &[0x00, 0x01, 0x02, 0x20]);
+
+        let new_pool = MCTPCtrlCmd::AllocateEndpointIDs
+            .process_allocate_endpoint_ids(&[0x01, 0x01, 0x30], 2, Some(pool), rsp_buf)
+            .unwrap();
+        assert_eq!(new_pool.map(|pool| pool.first_eid), Some(0x30));
+        assert_eq!(rsp_buf, &[0x00, 0x00, 0x01, 0x30]);
+
+        // The pool can't be larger than the number of endpoints behind the bridge
+        let new_pool = MCTPCtrlCmd::AllocateEndpointIDs
+            .process_allocate_endpoint_ids(&[0x01, 0x03, 0x30], 2, Some(pool), rsp_buf)
+            .unwrap();
+        assert!(new_pool.is_none());
+        assert_eq!(rsp_buf[0], CmdCompletionCode::ErrorInvalidData as u8);
+    }
+
+    #[test]
+    fn test_routing_information_update() {
+        let port_info = MCTPBridgePortInfo {
+            binding_type: 0x06,
+            media_type: 0x00,
+            phys_addr_len: 1,
+        };
+        let routing_table = MCTPRoutingTable::new();
+        let rsp_buf = &mut [0; 1];
+
+        // Two entries: a single endpoint and the EID range of another bridge
+        let req = [0x02, 0x00, 0x01, 0x30, 0x08, 0x03, 0x04, 0x40, 0x09];
+        MCTPCtrlCmd::RoutingInformationUpdate
+            .process_routing_information_update(&req, &port_info, &routing_table, rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_buf[0], CmdCompletionCode::Success as u8);
+
+        let entry = routing_table.lookup(0x42).unwrap();
+        assert_eq!(entry.starting_eid, 0x40);
+        assert_eq!(entry.entry_type, RoutingEntryType::DownstreamEids);
+        assert_eq!(entry.port, MCTPBridgePort::Upstream);
+        assert_eq!(entry.phys_addr.as_slice(), &[0x09]);
+
+        let rsp_buf = &mut [0; 61];
+        let rsp_len = MCTPCtrlCmd::GetRoutingTableEntries
+            .process_get_routing_table_entries(&[1], &routing_table.entries().0[..2], rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_len, 10);
+        assert_eq!(
+            &rsp_buf[..rsp_len],
+            &[0x00, 0xFF, 0x01, 0x04, 0x40, 0xC0, 0x06, 0x00, 0x01, 0x09]
+        );
+
+        // Truncated request
+        let rsp_buf = &mut [0; 1];
+        MCTPCtrlCmd::RoutingInformationUpdate
+            .process_routing_information_update(&req[..5], &port_info, &routing_table, rsp_buf)
+            .unwrap();
+        assert_eq!(rsp_buf[0], CmdCompletionCode::ErrorInvalidLength as u8);
+    }
 }
@@ -3,6 +3,7 @@
 use crate::mctp::base_protocol::{
     valid_eid, valid_msg_tag, MessageType, MCTP_TAG_MASK, MCTP_TAG_OWNER,
 };
+use crate::mctp::control_msg::{MCTPEndpointInfo, VendorDefinedMsgSupport, MCTP_UUID_LEN};
 use crate::mctp::recv::MCTPRxClient;
 use crate::mctp::send::{MCTPSender, MCTPTxClient};
 use core::cell::Cell;
@@ -128,6 +129,7 @@ pub struct App {
 
 pub struct MCTPDriver<'a> {
     sender: &'a dyn MCTPSender<'a>,
+    endpoint_info: &'a dyn MCTPEndpointInfo,
     apps: Grant<
         App,
         UpcallCount<{ upcall::COUNT }>,
@@ -146,6 +148,7 @@ pub struct MCTPDriver<'a> {
 impl<'a> MCTPDriver<'a> {
     pub fn new(
         sender: &'a dyn MCTPSender<'a>,
+        endpoint_info: &'a dyn MCTPEndpointInfo,
         grant: Grant<
             App,
             UpcallCount<{ upcall::COUNT }>,
@@ -159,6 +162,7 @@ impl<'a> MCTPDriver<'a> {
     ) -> MCTPDriver<'a> {
         MCTPDriver {
             sender,
+            endpoint_info,
             apps: grant,
             current_app: Cell::new(None),
             msg_type,
@@ -170,6 +174,23 @@ impl<'a> MCTPDriver<'a> {
         }
     }
 
+    fn set_endpoint_uuid(&self, kernel_data: &GrantKernelData) -> Result<(), ErrorCode> {
+        kernel_data
+            .get_readonly_processbuffer(ro_allow::MESSAGE_WRITE)
+            .and_then(|uuid_buf| {
+                uuid_buf.enter(|uuid_buf| {
+                    if uuid_buf.len() != MCTP_UUID_LEN {
+                        return Err(ErrorCode::SIZE);
+                    }
+                    let mut uuid = [0; MCTP_UUID_LEN];
+                    uuid_buf.copy_to_slice(&mut uuid);
+                    self.endpoint_info.set_endpoint_uuid(uuid);
+                    Ok(())
+                })
+            })
+            .unwrap_or(Err(ErrorCode::RESERVE))
+    }
+
     fn parse_args(
         &self,
         command_num: usize,
@@ -477,6 +498,20 @@ impl SyscallDriver for MCTPDriver<'_> {
     ///   will return Ok(()) and the pending tx operation context is updated. Otherwise, the result is returned immediately.
     ///
     /// - `5`: Get the maximum message size supported by the MCTP driver.
+    ///
+    /// - `6`: Set Endpoint UUID.
+    ///   Sets the UUID reported in the Get Endpoint UUID control response from the read-only buffer.
+    ///   Returns SIZE if the buffer is not MCTP_UUID_LEN bytes long.
+    ///
+    /// - `7`: Register Vendor Defined Message Support.
+    ///   Adds the vendor ID (arg1) and command set version (arg2) to the Get Vendor Defined
+    ///   Message Support control response.
+    ///   Returns NOSUPPORT if the driver does not handle vendor defined messages.
+    ///   Returns NOMEM if no more vendor defined message sets can be registered.
+    ///
+    /// - `8`: Cancel Receive Response Message.
+    ///   Drops the pending response rx operation context. If one was pending, the response
+    ///   upcall is scheduled with a message length of 0 so that the waiting process can resume.
     fn command(
         &self,
         command_num: usize,
@@ -560,6 +595,55 @@ impl SyscallDriver for MCTPDriver<'_> {
                 }
             }
             5 => CommandReturn::success_u32(self.max_msg_size as u32),
+            // 6: Set Endpoint UUID
+            6 => {
+                // Only the vendor defined message driver of the VDM service sets the UUID
+                if self.msg_type != MessageType::Caliptra {
+                    return CommandReturn::failure(ErrorCode::NOSUPPORT);
+                }
+                let result = self
+                    .apps
+                    .enter(process_id, |_, kernel_data| {
+                        self.set_endpoint_uuid(kernel_data)
+                    })
+                    .unwrap_or_else(|err| Err(err.into()));
+                match result {
+                    Ok(()) => CommandReturn::success(),
+                    Err(e) => CommandReturn::failure(e),
+                }
+            }
+            // 7: Register Vendor Defined Message Support
+            7 => {
+                // Only the vendor defined message drivers advertise a command set
+                if self.msg_type != MessageType::Caliptra {
+                    return CommandReturn::failure(ErrorCode::NOSUPPORT);
+                }
+                let support = VendorDefinedMsgSupport {
+                    vendor_id: arg1 as u16,
+                    cmd_set_version: arg2 as u16,
+                };
+                match self
+                    .endpoint_info
+                    .register_vendor_defined_msg_support(support)
+                {
+                    Ok(()) => CommandReturn::success(),
+                    Err(e) => CommandReturn::failure(e),
+                }
+            }
+            // 8: Cancel Receive Response Message
+            8 => self
+                .apps
+                .enter(process_id, |app, kernel_data| {
+                    if app.pending_rx_response.take().is_some() {
+                        if let Err(e) =
+                            kernel_data.schedule_upcall(upcall::RECEIVED_RESPONSE, (0, 0, 0))
+                        {
+                            println!("[MCTP-CAPSULE]::cancel upcall schedule failed: {:?}", e);
+                        }
+                    }
+                    CommandReturn::success()
+                })
+                .unwrap_or_else(|err| CommandReturn::failure(err.into())),
             _ => CommandReturn::failure(ErrorCode::NOSUPPORT),
         }
     }
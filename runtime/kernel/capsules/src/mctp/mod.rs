@@ -1,9 +1,11 @@
 // Licensed under the Apache-2.0 license
 
 pub mod base_protocol;
+pub mod bridge;
 pub mod control_msg;
 pub mod driver;
 pub mod mux;
 pub mod recv;
+pub mod routing;
 pub mod send;
 pub mod transport_binding;
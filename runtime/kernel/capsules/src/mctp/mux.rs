@@ -1,18 +1,22 @@
 // Licensed under the Apache-2.0 license
 
 use crate::mctp::base_protocol::{
-    MCTPHeader, MessageType, MCTP_BASELINE_TRANSMISSION_UNIT, MCTP_HDR_SIZE,
+    valid_eid, MCTPHeader, MessageType, MCTP_BASELINE_TRANSMISSION_UNIT, MCTP_HDR_SIZE,
 };
-use crate::mctp::control_msg::{MCTPCtrlCmd, MCTPCtrlMsgHdr, MCTP_CTRL_MSG_HEADER_LEN};
-use crate::mctp::recv::MCTPRxState;
+use crate::mctp::bridge::{MCTPBridge, MCTPBridgeClient};
+use crate::mctp::control_msg::{
+    EndpointType, MCTPCtrlCmd, MCTPCtrlMsgHdr, MCTPEndpointInfo, VendorDefinedMsgSupport,
+    MAX_VENDOR_DEFINED_MSG_SUPPORT, MCTP_CTRL_MSG_HEADER_LEN, MCTP_UUID_LEN,
+};
+use crate::mctp::recv::{MCTPRxCounters, MCTPRxError, MCTPRxState, MCTP_REASSEMBLY_TIMEOUT_MS};
 use crate::mctp::send::MCTPTxState;
 use crate::mctp::transport_binding::{MCTPTransportBinding, TransportRxClient, TransportTxClient};
 use core::cell::Cell;
 use core::fmt::Write;
 use kernel::collections::list::List;
 use kernel::deferred_call::{DeferredCall, DeferredCallClient};
-use kernel::hil::time::{Alarm, Ticks};
-use kernel::utilities::cells::TakeCell;
+use kernel::hil::time::{Alarm, AlarmClient, ConvertTicks, Ticks};
+use kernel::utilities::cells::{OptionalCell, TakeCell};
 use kernel::utilities::leasable_buffer::SubSliceMut;
 use kernel::ErrorCode;
 use romtime::println;
@@ -30,11 +34,23 @@ pub struct MuxMCTPDriver<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> {
     next_msg_tag: Cell<u8>, //global msg tag. increment by 1 for next tag upto 7 and wrap around.
     local_eid: Cell<u8>,
     mtu: Cell<usize>,
+    // Endpoint information reported in the MCTP control messages
+    endpoint_uuid: OptionalCell<[u8; MCTP_UUID_LEN]>,
+    vdm_support: Cell<[VendorDefinedMsgSupport; MAX_VENDOR_DEFINED_MSG_SUPPORT]>,
+    vdm_support_count: Cell<usize>,
     // List of outstanding send requests
     sender_list: List<'a, MCTPTxState<'a, A, M>>,
     receiver_list: List<'a, MCTPRxState<'a>>,
+    // Time allowed between two packets of a message before it is dropped.
+    // The alarm of the clock drops the partial messages that time out.
+    reassembly_timeout_ms: Cell<u32>,
+    rx_counters: Cell<MCTPRxCounters>,
     tx_pkt_buffer: TakeCell<'static, [u8]>, // Static buffer for tx packet.
     rx_pkt_buffer: TakeCell<'static, [u8]>, //Static buffer for rx packet
+    // Bridge to the endpoints behind this endpoint, in bridge mode
+    bridge: OptionalCell<&'a MCTPBridge<'a, A>>,
+    // Set while a packet forwarded from the bridge is transmitted
+    forwarding: Cell<bool>,
     clock: &'a A,
     deferred_call: DeferredCall,
 }
@@ -53,10 +69,19 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
             next_msg_tag: Cell::new(0),
             local_eid: Cell::new(local_eid),
             mtu: Cell::new(mtu),
+            endpoint_uuid: OptionalCell::empty(),
+            vdm_support: Cell::new(
+                [VendorDefinedMsgSupport::default(); MAX_VENDOR_DEFINED_MSG_SUPPORT],
+            ),
+            vdm_support_count: Cell::new(0),
             sender_list: List::new(),
             receiver_list: List::new(),
+            reassembly_timeout_ms: Cell::new(MCTP_REASSEMBLY_TIMEOUT_MS),
+            rx_counters: Cell::new(MCTPRxCounters::default()),
             tx_pkt_buffer: TakeCell::new(tx_pkt_buf),
             rx_pkt_buffer: TakeCell::new(rx_pkt_buf),
+            bridge: OptionalCell::empty(),
+            forwarding: Cell::new(false),
             clock,
             deferred_call: DeferredCall::new(),
         }
@@ -64,6 +89,13 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
 
     pub fn enable(&self) {
         self.mctp_device.enable();
+        self.bridge.map(|bridge| bridge.enable());
+    }
+
+    /// Enables the bridge mode, in which the packets addressed to the endpoints
+    /// behind the bridge are forwarded to them.
+    pub fn set_bridge(&self, bridge: &'a MCTPBridge<'a, A>) {
+        self.bridge.set(bridge);
     }
 
     pub fn add_sender(&self, sender: &'a MCTPTxState<'a, A, M>) {
@@ -77,6 +109,10 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
     }
 
     fn deferred_send(&self) {
+        // A packet in flight resumes the send requests when it is done
+        if self.tx_pkt_buffer.is_none() {
+            return;
+        }
         if let Some(sender) = self.sender_list.head() {
             self.send_next_packet(sender);
         }
@@ -102,6 +138,23 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
         self.mtu.get()
     }
 
+    pub fn set_reassembly_timeout_ms(&self, timeout_ms: u32) {
+        self.reassembly_timeout_ms.set(timeout_ms);
+    }
+
+    pub fn get_reassembly_timeout_ms(&self) -> u32 {
+        self.reassembly_timeout_ms.get()
+    }
+
+    /// Returns the counters of the packets and partial messages dropped during reassembly.
+    pub fn get_rx_counters(&self) -> MCTPRxCounters {
+        self.rx_counters.get()
+    }
+
+    pub fn reset_rx_counters(&self) {
+        self.rx_counters.set(MCTPRxCounters::default());
+    }
+
     pub fn get_next_msg_tag(&self) -> u8 {
         let msg_tag = self.next_msg_tag.get();
         self.next_msg_tag.set((msg_tag + 1) % 8);
@@ -199,7 +252,7 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
 
         let req_buf = &msg_buf[MCTP_CTRL_MSG_HEADER_LEN..];
         let mctp_ctrl_cmd: MCTPCtrlCmd = mctp_ctrl_msg_hdr.cmd().into();
-        let resp_len = MCTP_CTRL_MSG_HEADER_LEN + MCTP_HDR_SIZE + mctp_ctrl_cmd.resp_data_len();
+        let mut resp_data_len = mctp_ctrl_cmd.resp_data_len();
 
         if req_buf.len() < mctp_ctrl_cmd.req_data_len() {
             println!(
@@ -215,7 +268,12 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
             .map_or(Err(ErrorCode::NOMEM), |resp_buf| {
                 let result = match mctp_ctrl_cmd {
                     MCTPCtrlCmd::SetEID => mctp_ctrl_cmd
-                        .process_set_endpoint_id(req_buf, &mut resp_buf[msg_payload_start..])
+                        .process_set_endpoint_id(
+                            req_buf,
+                            self.bridge.map_or(0, |bridge| bridge.eid_pool_size()),
+                            self.bridge.get().and_then(|bridge| bridge.eid_pool()),
+                            &mut resp_buf[msg_payload_start..],
+                        )
                         .map(|eid| {
                             if let Some(eid) = eid {
                                 self.set_local_eid(eid);
@@ -224,6 +282,11 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
 
                     MCTPCtrlCmd::GetEID => mctp_ctrl_cmd.process_get_endpoint_id(
                         self.get_local_eid(),
+                        if self.bridge.is_some() {
+                            EndpointType::BusOwnerBridge
+                        } else {
+                            EndpointType::Simple
+                        },
                         &mut resp_buf[msg_payload_start..],
                     ),
 
@@ -232,8 +295,63 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
 
                     MCTPCtrlCmd::GetMsgTypeSupport => mctp_ctrl_cmd
                         .process_get_msg_type_support(req_buf, &mut resp_buf[msg_payload_start..]),
-                    _ => return Err(ErrorCode::NOSUPPORT),
+
+                    MCTPCtrlCmd::GetEndpointUUID => mctp_ctrl_cmd.process_get_endpoint_uuid(
+                        self.endpoint_uuid.get(),
+                        &mut resp_buf[msg_payload_start..],
+                    ),
+
+                    MCTPCtrlCmd::GetVendorDefinedMsgSupport => {
+                        let vdm_support = self.vdm_support.get();
+                        mctp_ctrl_cmd.process_get_vendor_defined_msg_support(
+                            req_buf,
+                            &vdm_support[..self.vdm_support_count.get()],
+                            &mut resp_buf[msg_payload_start..],
+                        )
+                    }
+
+                    MCTPCtrlCmd::AllocateEndpointIDs => match self.bridge.get() {
+                        Some(bridge) => mctp_ctrl_cmd
+                            .process_allocate_endpoint_ids(
+                                req_buf,
+                                bridge.eid_pool_size(),
+                                bridge.eid_pool(),
+                                &mut resp_buf[msg_payload_start..],
+                            )
+                            .map(|eid_pool| {
+                                if let Some(eid_pool) = eid_pool {
+                                    bridge.set_eid_pool(eid_pool);
+                                }
+                            }),
+                        None => Err(ErrorCode::NOSUPPORT),
+                    },
+
+                    MCTPCtrlCmd::RoutingInformationUpdate => match self.bridge.get() {
+                        Some(bridge) => mctp_ctrl_cmd.process_routing_information_update(
+                            req_buf,
+                            bridge.upstream_port_info(),
+                            bridge.routing_table(),
+                            &mut resp_buf[msg_payload_start..],
+                        ),
+                        None => Err(ErrorCode::NOSUPPORT),
+                    },
+
+                    MCTPCtrlCmd::GetRoutingTableEntries => {
+                        let (entries, count) = self
+                            .bridge
+                            .map(|bridge| bridge.routing_table().entries())
+                            .unwrap_or_default();
+                        mctp_ctrl_cmd
+                            .process_get_routing_table_entries(
+                                req_buf,
+                                &entries[..count],
+                                &mut resp_buf[msg_payload_start..],
+                            )
+                            .map(|len| resp_data_len = len)
+                    }
+                    _ => Err(ErrorCode::NOSUPPORT),
                 };
+                let resp_len = msg_payload_start + resp_data_len;
 
                 match result {
                     Ok(_) => {
@@ -313,19 +431,23 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
             println!(
                 "MuxMCTPDriver: Received first packet with less than 64 bytes. Dropping packet."
             );
+            self.update_rx_counters(Err(MCTPRxError::InvalidPacket));
             return;
         }
 
+        let recv_time = self.expire_stale_msgs();
         let rx_state = self
             .receiver_list
             .iter()
             .find(|rx_state| rx_state.is_receive_expected(msg_type));
 
         if let Some(rx_state) = rx_state {
-            let recv_time = self.clock.now().into_u32();
-            rx_state.start_receive(mctp_hdr, msg_type, pkt_payload, recv_time);
+            let result = rx_state.start_receive(mctp_hdr, msg_type, pkt_payload, recv_time);
+            self.update_rx_counters(result);
+            self.schedule_expiry();
         } else {
             println!("MuxMCTPDriver: No matching receive request found. Dropping packet.");
+            self.update_rx_counters(Err(MCTPRxError::NoContext));
         }
     }
 
@@ -333,33 +455,187 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MuxMCTPDriver<'a, A, M> {
     fn process_packet(&self, mctp_hdr: MCTPHeader, pkt_payload: &[u8]) {
         if self.local_eid != mctp_hdr.dest_eid().into() {
             println!("MuxMCTPDriver: Packet not for this Endpoint. Dropping packet.");
+            self.update_rx_counters(Err(MCTPRxError::OtherEndpoint));
             return;
         }
 
         if mctp_hdr.eom() != 1 && pkt_payload.len() < MCTP_BASELINE_TRANSMISSION_UNIT {
             println!("MuxMCTPDriver: Received first or middle packet with less than 64 bytes. Dropping packet.");
+            self.update_rx_counters(Err(MCTPRxError::InvalidPacket));
             return;
         }
 
+        let recv_time = self.expire_stale_msgs();
         let rx_state = self
             .receiver_list
             .iter()
-            .find(|rx_state| rx_state.is_next_packet(mctp_hdr, pkt_payload.len()));
+            .find(|rx_state| rx_state.is_assembling(mctp_hdr));
 
         match rx_state {
             Some(rx_state) => {
-                let recv_time = self.clock.now().into_u32();
-                rx_state.receive_next(mctp_hdr, pkt_payload, recv_time);
+                let result = rx_state.receive_next(mctp_hdr, pkt_payload, recv_time);
+                self.update_rx_counters(result);
+                self.schedule_expiry();
             }
             None => {
                 println!("MuxMCTPDriver: No matching receive request found. Dropping packet.");
+                self.update_rx_counters(Err(MCTPRxError::NoContext));
+            }
+        }
+    }
+
+    /// Drops the partial messages that have not received a packet within the
+    /// reassembly timeout.
+    ///
+    /// # Returns
+    /// The current time, used as the receive time of the packet being processed.
+    fn expire_stale_msgs(&self) -> u32 {
+        let now = self.clock.now().into_u32();
+        let timeout = self
+            .clock
+            .ticks_from_ms(self.reassembly_timeout_ms.get())
+            .into_u32();
+        let timed_out_msgs = self
+            .receiver_list
+            .iter()
+            .map(|rx_state| rx_state.expire_stale(now, timeout))
+            .sum::<u32>();
+        if timed_out_msgs > 0 {
+            println!(
+                "MuxMCTPDriver: Dropped {} partial messages after reassembly timeout.",
+                timed_out_msgs
+            );
+            let mut counters = self.rx_counters.get();
+            counters.timed_out_msgs = counters.timed_out_msgs.wrapping_add(timed_out_msgs);
+            self.rx_counters.set(counters);
+        }
+        now
+    }
+
+    /// Arms the alarm for the oldest partial message to time out, so that it is
+    /// dropped even if no other packet is received. The alarm is disarmed when
+    /// no message is being assembled.
+    fn schedule_expiry(&self) {
+        let now = self.clock.now();
+        let timeout = self
+            .clock
+            .ticks_from_ms(self.reassembly_timeout_ms.get())
+            .into_u32();
+        let next_expiry = self
+            .receiver_list
+            .iter()
+            .filter_map(|rx_state| rx_state.next_expiry(now.into_u32(), timeout))
+            .min();
+        match next_expiry {
+            Some(ticks) => self.clock.set_alarm(now, A::Ticks::from(ticks)),
+            None => {
+                let _ = self.clock.disarm();
+            }
+        }
+    }
+
+    fn update_rx_counters(&self, result: Result<(), MCTPRxError>) {
+        let mut counters = self.rx_counters.get();
+        match result {
+            Ok(()) => return,
+            Err(MCTPRxError::OutOfSequence) => {
+                counters.out_of_seq_pkts = counters.out_of_seq_pkts.wrapping_add(1)
+            }
+            Err(MCTPRxError::OtherEndpoint) => {
+                counters.other_eid_pkts = counters.other_eid_pkts.wrapping_add(1)
             }
+            Err(_) => counters.dropped_pkts = counters.dropped_pkts.wrapping_add(1),
         }
+        self.rx_counters.set(counters);
     }
 
     fn mctp_hdr_offset(&self) -> usize {
         self.mctp_device.get_hdr_size()
     }
+
+    /// Checks if the packet is addressed to an endpoint behind the bridge.
+    fn is_routed_downstream(&self, mctp_hdr: MCTPHeader) -> bool {
+        let dest_eid = mctp_hdr.dest_eid();
+        mctp_hdr.hdr_version() == 1
+            && dest_eid != 0
+            && valid_eid(dest_eid)
+            && dest_eid != self.local_eid.get()
+            && self
+                .bridge
+                .map_or(false, |bridge| bridge.routes_downstream(dest_eid))
+    }
+
+    /// Transmits the packet received from the bridge on the upstream port.
+    ///
+    /// # Returns
+    /// True if a packet is in flight, false otherwise.
+    fn transmit_upstream_packet(&self) -> bool {
+        let Some(bridge) = self.bridge.get() else {
+            return false;
+        };
+        let Some(tx_pkt) = self.tx_pkt_buffer.take() else {
+            return false;
+        };
+
+        let mctp_hdr_offset = self.mctp_hdr_offset();
+        let pkt_end_offset = self.get_mtu().min(tx_pkt.len());
+        match bridge.take_upstream_packet(&mut tx_pkt[mctp_hdr_offset..pkt_end_offset]) {
+            Some(len) => match self.mctp_device.transmit(tx_pkt, mctp_hdr_offset + len) {
+                Ok(()) => {
+                    self.forwarding.set(true);
+                    true
+                }
+                Err((err, tx_pkt)) => {
+                    println!("MuxMCTPDriver: Failed to forward packet {:?}", err);
+                    self.tx_pkt_buffer.replace(tx_pkt);
+                    false
+                }
+            },
+            None => {
+                self.tx_pkt_buffer.replace(tx_pkt);
+                false
+            }
+        }
+    }
+}
+
+impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MCTPEndpointInfo for MuxMCTPDriver<'a, A, M> {
+    fn set_endpoint_uuid(&self, uuid: [u8; MCTP_UUID_LEN]) {
+        self.endpoint_uuid.set(uuid);
+    }
+
+    fn register_vendor_defined_msg_support(
+        &self,
+        support: VendorDefinedMsgSupport,
+    ) -> Result<(), ErrorCode> {
+        let mut vdm_support = self.vdm_support.get();
+        let count = self.vdm_support_count.get();
+
+        match vdm_support[..count]
+            .iter()
+            .position(|entry| entry.vendor_id == support.vendor_id)
+        {
+            Some(index) => vdm_support[index] = support,
+            None if count < MAX_VENDOR_DEFINED_MSG_SUPPORT => {
+                vdm_support[count] = support;
+                self.vdm_support_count.set(count + 1);
+            }
+            None => Err(ErrorCode::NOMEM)?,
+        }
+        self.vdm_support.set(vdm_support);
+        Ok(())
+    }
+}
+
+impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> MCTPBridgeClient for MuxMCTPDriver<'a, A, M> {
+    fn local_eid(&self) -> u8 {
+        self.get_local_eid()
+    }
+
+    fn upstream_packet_ready(&self) {
+        // Otherwise the packet is forwarded when the packet in flight is done
+        self.transmit_upstream_packet();
+    }
 }
 
 impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> TransportTxClient for MuxMCTPDriver<'a, A, M> {
@@ -367,7 +643,11 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> TransportTxClient for MuxMCT
         self.tx_pkt_buffer.replace(tx_buffer);
 
         let mut cur_sender = self.sender_list.head();
-        if let Some(sender) = cur_sender {
+        if self.forwarding.take() {
+            if let Err(err) = result {
+                println!("MuxMCTPDriver: Failed to forward packet {:?}", err);
+            }
+        } else if let Some(sender) = cur_sender {
             if sender.is_eom() || result.is_err() {
                 sender.send_done(result);
                 self.sender_list.pop_head();
@@ -375,6 +655,11 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> TransportTxClient for MuxMCT
             }
         }
 
+        // Forwarded packets are interleaved with the packets of the send requests
+        if self.transmit_upstream_packet() {
+            return;
+        }
+
         if let Some(cur_sender) = cur_sender {
             self.send_next_packet(cur_sender);
         };
@@ -390,6 +675,16 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> TransportRxClient for MuxMCT
         }
 
         let (mctp_header, msg_type, payload_offset) = self.interpret_packet(&rx_buffer[0..len]);
+        if self.is_routed_downstream(mctp_header) {
+            if let Some(Err(err)) = self
+                .bridge
+                .map(|bridge| bridge.forward_downstream(&rx_buffer[0..len]))
+            {
+                println!("MuxMCTPDriver: Failed to forward packet {:?}", err);
+            }
+            self.rx_pkt_buffer.replace(rx_buffer);
+            return;
+        }
         if let Some(msg_type) = msg_type {
             match msg_type {
                 MessageType::MctpControl => {
@@ -431,6 +726,13 @@ impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> TransportRxClient for MuxMCT
     }
 }
 
+impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> AlarmClient for MuxMCTPDriver<'a, A, M> {
+    fn alarm(&self) {
+        self.expire_stale_msgs();
+        self.schedule_expiry();
+    }
+}
+
 impl<'a, A: Alarm<'a>, M: MCTPTransportBinding<'a>> DeferredCallClient for MuxMCTPDriver<'a, A, M> {
     fn handle_deferred_call(&self) {
         self.deferred_send();
@@ -20,16 +20,55 @@ pub trait MCTPRxClient {
     );
 }
 
+/// Number of messages of a message type that can be assembled at the same time.
+/// Each message is identified by its source EID, message tag and tag owner bit.
+pub const MCTP_MAX_REASSEMBLY_CONTEXTS: usize = 2;
+
+/// Default time allowed between two packets of a message, in milliseconds.
+/// The partial message is dropped if the next packet is not received in time.
+/// This is a conservative default; platforms can tighten it to the DSP0236
+/// MT1/MT2 timing of their transport.
+pub const MCTP_REASSEMBLY_TIMEOUT_MS: u32 = 1000;
+
+/// Reason a received packet was not accepted for reassembly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MCTPRxError {
+    /// The packet does not belong to any message being assembled.
+    NoContext,
+    /// All the reassembly contexts are in use by other messages.
+    ContextsFull,
+    /// The packet sequence number is not the expected one.
+    /// The message being assembled is dropped.
+    OutOfSequence,
+    /// The packet is malformed or the message does not fit in the buffer.
+    /// The message being assembled is dropped.
+    InvalidPacket,
+    /// The packet is addressed to another endpoint.
+    OtherEndpoint,
+}
+
+/// Counters of the packets and partial messages dropped during reassembly.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MCTPRxCounters {
+    /// Partial messages dropped because the next packet was not received
+    /// within the reassembly timeout.
+    pub timed_out_msgs: u32,
+    /// Packets dropped because their sequence number was not the expected one.
+    pub out_of_seq_pkts: u32,
+    /// Packets dropped because they are addressed to another endpoint.
+    pub other_eid_pkts: u32,
+    /// Packets dropped for any other reason.
+    pub dropped_pkts: u32,
+}
+
 /// Receive state
 pub struct MCTPRxState<'a> {
-    /// Message assembly context
-    msg_terminus: MapCell<MsgTerminus>,
+    /// Message assembly contexts, one per in-flight message
+    contexts: [ReassemblyContext; MCTP_MAX_REASSEMBLY_CONTEXTS],
     /// Expected message types
     msg_type: MessageType,
     /// Client (implements the MCTPRxClient trait)
     client: OptionalCell<&'a dyn MCTPRxClient>,
-    /// Message buffer
-    msg_payload: TakeCell<'static, [u8]>,
     /// next MCTPRxState node
     next: ListLink<'a, MCTPRxState<'a>>,
 }
@@ -40,6 +79,29 @@ impl<'a> ListNode<'a, MCTPRxState<'a>> for MCTPRxState<'a> {
     }
 }
 
+struct ReassemblyContext {
+    /// Assembly state of the message, if one is in flight
+    msg_terminus: MapCell<MsgTerminus>,
+    /// Message buffer
+    msg_payload: TakeCell<'static, [u8]>,
+}
+
+impl ReassemblyContext {
+    fn is_assembling(&self, mctp_hdr: &MCTPHeader) -> bool {
+        self.msg_terminus
+            .map(|msg_terminus| {
+                msg_terminus.tag_owner == mctp_hdr.tag_owner()
+                    && msg_terminus.msg_tag == mctp_hdr.msg_tag()
+                    && msg_terminus.source_eid == mctp_hdr.src_eid()
+            })
+            .unwrap_or(false)
+    }
+
+    fn buf_len(&self) -> usize {
+        self.msg_payload.map_or(0, |msg_payload| msg_payload.len())
+    }
+}
+
 #[derive(Debug)]
 struct MsgTerminus {
     msg_type: u8,
@@ -49,15 +111,21 @@ struct MsgTerminus {
     start_payload_len: usize,
     pkt_seq: u8,
     msg_size: usize,
+    last_pkt_time: u32,
 }
 
 impl<'a> MCTPRxState<'a> {
-    pub fn new(rx_msg_buf: &'static mut [u8], msg_type: MessageType) -> MCTPRxState<'static> {
+    pub fn new(
+        rx_msg_bufs: [&'static mut [u8]; MCTP_MAX_REASSEMBLY_CONTEXTS],
+        msg_type: MessageType,
+    ) -> MCTPRxState<'static> {
         MCTPRxState {
-            msg_terminus: MapCell::empty(),
+            contexts: rx_msg_bufs.map(|rx_msg_buf| ReassemblyContext {
+                msg_terminus: MapCell::empty(),
+                msg_payload: TakeCell::new(rx_msg_buf),
+            }),
             msg_type,
             client: OptionalCell::empty(),
-            msg_payload: TakeCell::new(rx_msg_buf),
             next: ListLink::empty(),
         }
     }
@@ -77,154 +145,354 @@ impl<'a> MCTPRxState<'a> {
         self.msg_type == msg_type
     }
 
-    /// Checks from the received MCTP header if the next packet belongs to
-    /// the current message being assembled.
+    /// Checks from the received MCTP header if the packet belongs to
+    /// one of the messages being assembled.
     ///
     /// # Arguments
     /// 'mctp_hdr' - The MCTP header of the received packet.
-    /// 'pkt_payload_len' - The length of the payload of the received packet.
     ///
     /// # Returns
-    /// True if the next packet belongs to the current message, false otherwise.
-    pub fn is_next_packet(&self, mctp_hdr: MCTPHeader, pkt_payload_len: usize) -> bool {
-        self.msg_terminus
-            .map(|msg_terminus| {
-                // Check if the received packet belongs to the current message
-                let next_pkt = msg_terminus.tag_owner == mctp_hdr.tag_owner()
-                    && msg_terminus.msg_tag == mctp_hdr.msg_tag()
-                    && msg_terminus.source_eid == mctp_hdr.src_eid()
-                    && msg_terminus.pkt_seq == mctp_hdr.pkt_seq();
-
-                // Check if the payload length of the middle packet is the same as the first packet
-                if mctp_hdr.middle_pkt() {
-                    next_pkt && msg_terminus.start_payload_len == pkt_payload_len
-                } else {
-                    next_pkt
-                }
+    /// True if a message from the same source EID, with the same tag and tag owner
+    /// is being assembled, false otherwise.
+    pub fn is_assembling(&self, mctp_hdr: MCTPHeader) -> bool {
+        self.contexts
+            .iter()
+            .any(|context| context.is_assembling(&mctp_hdr))
+    }
+
+    /// Drops the partial messages whose last packet was received more than
+    /// `timeout` ticks before `now`.
+    ///
+    /// # Returns
+    /// The number of partial messages dropped.
+    pub fn expire_stale(&self, now: u32, timeout: u32) -> u32 {
+        let mut expired = 0;
+        for context in self.contexts.iter() {
+            let stale = context
+                .msg_terminus
+                .map(|msg_terminus| now.wrapping_sub(msg_terminus.last_pkt_time) > timeout)
+                .unwrap_or(false);
+            if stale {
+                context.msg_terminus.take();
+                expired += 1;
+            }
+        }
+        expired
+    }
+
+    /// Returns the number of ticks after `now` at which the oldest partial message
+    /// times out, or None if no message is being assembled.
+    pub fn next_expiry(&self, now: u32, timeout: u32) -> Option<u32> {
+        self.contexts
+            .iter()
+            .filter_map(|context| {
+                context.msg_terminus.map(|msg_terminus| {
+                    timeout.saturating_sub(now.wrapping_sub(msg_terminus.last_pkt_time)) + 1
+                })
             })
-            .unwrap_or(false)
+            .min()
     }
 
     /// Receives the next packet of the message being assembled.
     /// If the packet is the last one, the message is delivered to the client
     /// by calling the `receive` method of the client.
+    /// The message is dropped if the packet is out of sequence or malformed.
     ///
     /// # Arguments
     /// 'mctp_hdr' - The MCTP header of the received packet.
     /// 'pkt_payload' - The payload of the received packet.
-    pub fn receive_next(&self, mctp_hdr: MCTPHeader, pkt_payload: &[u8], recv_time: u32) {
-        if let Some(mut msg_terminus) = self.msg_terminus.take() {
-            let offset = msg_terminus.msg_size;
-            let end_offset = offset + pkt_payload.len();
-            if end_offset > self.msg_payload.map_or(0, |msg_payload| msg_payload.len()) {
-                println!("MuxMCTPDriver - Received packet with payload length greater than buffer size. Reset assembly.");
-                return;
-            }
+    /// 'recv_time' - The time the packet was received.
+    pub fn receive_next(
+        &self,
+        mctp_hdr: MCTPHeader,
+        pkt_payload: &[u8],
+        recv_time: u32,
+    ) -> Result<(), MCTPRxError> {
+        let context = self
+            .contexts
+            .iter()
+            .find(|context| context.is_assembling(&mctp_hdr))
+            .ok_or(MCTPRxError::NoContext)?;
+        let mut msg_terminus = context.msg_terminus.take().ok_or(MCTPRxError::NoContext)?;
 
-            self.msg_payload
-                .map(|msg_payload| {
-                    msg_payload[offset..end_offset].copy_from_slice(pkt_payload);
-                    msg_terminus.msg_size = end_offset;
-                    msg_terminus.pkt_seq = mctp_hdr.next_pkt_seq();
-                    self.msg_terminus.replace(msg_terminus);
-                })
-                .unwrap_or_else(|| {
-                    // This should never happen
-                    panic!(
-                        "MuxMCTPDriver - No msg buffer in receive next. This should never happen."
-                    );
-                });
+        if msg_terminus.pkt_seq != mctp_hdr.pkt_seq() {
+            println!("MuxMCTPDriver - Received out of sequence packet. Dropping message.");
+            Err(MCTPRxError::OutOfSequence)?;
+        }
 
-            if mctp_hdr.eom() == 1 {
-                self.end_receive(recv_time);
-            }
+        // The payload length of the middle packets is the same as the first packet
+        let offset = msg_terminus.msg_size;
+        let end_offset = offset + pkt_payload.len();
+        if (mctp_hdr.middle_pkt() && msg_terminus.start_payload_len != pkt_payload.len())
+            || end_offset > context.buf_len()
+        {
+            println!(
+                "MuxMCTPDriver - Received packet with invalid payload length. Dropping message."
+            );
+            Err(MCTPRxError::InvalidPacket)?;
         }
+
+        context
+            .msg_payload
+            .map(|msg_payload| {
+                msg_payload[offset..end_offset].copy_from_slice(pkt_payload);
+            })
+            .unwrap_or_else(|| {
+                // This should never happen
+                panic!("MuxMCTPDriver - No msg buffer in receive next. This should never happen.");
+            });
+        msg_terminus.msg_size = end_offset;
+        msg_terminus.pkt_seq = mctp_hdr.next_pkt_seq();
+        msg_terminus.last_pkt_time = recv_time;
+
+        if mctp_hdr.eom() == 1 {
+            self.end_receive(context, msg_terminus, recv_time);
+        } else {
+            context.msg_terminus.replace(msg_terminus);
+        }
+        Ok(())
     }
 
     /// Called at the end of the message assembly to deliver the message to the client.
-    /// The message terminus state is set to None after the message is delivered.
-    pub fn end_receive(&self, recv_time: u32) {
-        if let Some(msg_terminus) = self.msg_terminus.take() {
-            let msg_tag = if msg_terminus.tag_owner == 1 {
-                (msg_terminus.msg_tag & MCTP_TAG_MASK) | MCTP_TAG_OWNER
-            } else {
-                msg_terminus.msg_tag & MCTP_TAG_MASK
-            };
-            self.client
-                .map(|client| {
-                    self.msg_payload.map(|msg_payload| {
-                        client.receive(
-                            msg_terminus.source_eid,
-                            msg_terminus.msg_type,
-                            msg_tag,
-                            msg_payload,
-                            msg_terminus.msg_size,
-                            recv_time,
-                        );
-                    });
-                })
-                .unwrap_or_else(|| {
-                    // This should never happen
-                    panic!(
-                        "MuxMCTPDriver - No msg buffer in end receive. This should never happen."
+    /// The reassembly context is free once the message is delivered.
+    fn end_receive(&self, context: &ReassemblyContext, msg_terminus: MsgTerminus, recv_time: u32) {
+        let msg_tag = if msg_terminus.tag_owner == 1 {
+            (msg_terminus.msg_tag & MCTP_TAG_MASK) | MCTP_TAG_OWNER
+        } else {
+            msg_terminus.msg_tag & MCTP_TAG_MASK
+        };
+        self.client
+            .map(|client| {
+                context.msg_payload.map(|msg_payload| {
+                    client.receive(
+                        msg_terminus.source_eid,
+                        msg_terminus.msg_type,
+                        msg_tag,
+                        msg_payload,
+                        msg_terminus.msg_size,
+                        recv_time,
                     );
                 });
-        }
+            })
+            .unwrap_or_else(|| {
+                // This should never happen
+                panic!("MuxMCTPDriver - No msg buffer in end receive. This should never happen.");
+            });
     }
 
     /// Called when the first packet of a message is received.
-    /// The message terminus state is initialized with the current context.
-    /// The previous message assembly state will be lost and a new message assembly
-    /// will be started.
+    /// The message is assembled in the context of a previous message from the same
+    /// source EID with the same tag and tag owner, which is dropped, or else in a free context.
     ///
     /// # Arguments
     /// 'mctp_hdr' - The MCTP header of the received packet.
     /// 'msg_type' - The message type of the received packet.
     /// 'pkt_payload' - The payload of the received packet.
+    /// 'recv_time' - The time the packet was received.
     pub fn start_receive(
         &self,
         mctp_hdr: MCTPHeader,
         msg_type: MessageType,
         pkt_payload: &[u8],
         recv_time: u32,
-    ) {
+    ) -> Result<(), MCTPRxError> {
         if mctp_hdr.som() != 1 {
             println!("MuxMCTPDriver - Received first packet without SOM. Dropping packet.");
-            return;
+            Err(MCTPRxError::InvalidPacket)?;
         }
 
-        let pkt_payload_len = pkt_payload.len();
+        let context = self
+            .contexts
+            .iter()
+            .find(|context| context.is_assembling(&mctp_hdr))
+            .or_else(|| {
+                self.contexts
+                    .iter()
+                    .find(|context| context.msg_terminus.is_none())
+            })
+            .ok_or(MCTPRxError::ContextsFull)?;
+        context.msg_terminus.take();
 
-        if pkt_payload_len == 0
-            || pkt_payload_len > self.msg_payload.map_or(0, |msg_payload| msg_payload.len())
-        {
+        let pkt_payload_len = pkt_payload.len();
+        if pkt_payload_len == 0 || pkt_payload_len > context.buf_len() {
             println!("MuxMCTPDriver - Received bad packet length. Dropping packet.");
-            return;
+            Err(MCTPRxError::InvalidPacket)?;
         }
 
-        self.msg_payload
+        context
+            .msg_payload
             .map(|msg_payload| {
-                msg_payload[..pkt_payload.len()].copy_from_slice(pkt_payload);
-
-                let msg_terminus = MsgTerminus {
-                    msg_type: msg_type as u8,
-                    msg_tag: mctp_hdr.msg_tag(),
-                    source_eid: mctp_hdr.src_eid(),
-                    tag_owner: mctp_hdr.tag_owner(),
-                    start_payload_len: pkt_payload_len,
-                    pkt_seq: mctp_hdr.next_pkt_seq(),
-                    msg_size: pkt_payload_len,
-                };
-                self.msg_terminus.replace(msg_terminus);
+                msg_payload[..pkt_payload_len].copy_from_slice(pkt_payload);
             })
             .unwrap_or_else(|| {
                 // This should never happen
                 panic!("MuxMCTPDriver - Received first packet without buffer. This should never happen.");
             });
 
+        let msg_terminus = MsgTerminus {
+            msg_type: msg_type as u8,
+            msg_tag: mctp_hdr.msg_tag(),
+            source_eid: mctp_hdr.src_eid(),
+            tag_owner: mctp_hdr.tag_owner(),
+            start_payload_len: pkt_payload_len,
+            pkt_seq: mctp_hdr.next_pkt_seq(),
+            msg_size: pkt_payload_len,
+            last_pkt_time: recv_time,
+        };
+
         // Single packet message
         if mctp_hdr.eom() == 1 {
-            self.end_receive(recv_time);
+            self.end_receive(context, msg_terminus, recv_time);
+        } else {
+            context.msg_terminus.replace(msg_terminus);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::{Cell, RefCell};
+
+    const TEST_BUF_SIZE: usize = 256;
+
+    #[derive(Default)]
+    struct TestClient {
+        msgs: RefCell<Vec<(u8, Vec<u8>)>>,
+        count: Cell<usize>,
+    }
+
+    impl MCTPRxClient for TestClient {
+        fn receive(
+            &self,
+            src_eid: u8,
+            _msg_type: u8,
+            _msg_tag: u8,
+            msg_payload: &[u8],
+            msg_len: usize,
+            _recv_time: u32,
+        ) {
+            self.msgs
+                .borrow_mut()
+                .push((src_eid, msg_payload[..msg_len].to_vec()));
+            self.count.set(self.count.get() + 1);
         }
     }
+
+    fn rx_state() -> MCTPRxState<'static> {
+        MCTPRxState::new(
+            core::array::from_fn(|_| Box::leak(Box::new([0u8; TEST_BUF_SIZE])) as &mut [u8]),
+            MessageType::Pldm,
+        )
+    }
+
+    fn hdr(src_eid: u8, som: u8, eom: u8, pkt_seq: u8) -> MCTPHeader {
+        MCTPHeader::new(0x0A, src_eid, som, eom, pkt_seq, 1, 2)
+    }
+
+    #[test]
+    fn test_interleaved_messages() {
+        let client: &'static TestClient = Box::leak(Box::default());
+        let rx_state = rx_state();
+        rx_state.set_client(client);
+
+        let pkt = [0x11u8; 64];
+        assert!(rx_state
+            .start_receive(hdr(0x10, 1, 0, 0), MessageType::Pldm, &pkt, 0)
+            .is_ok());
+        assert!(rx_state
+            .start_receive(hdr(0x20, 1, 0, 0), MessageType::Pldm, &pkt, 0)
+            .is_ok());
+        // No free context for a third source
+        assert_eq!(
+            rx_state.start_receive(hdr(0x30, 1, 0, 0), MessageType::Pldm, &pkt, 0),
+            Err(MCTPRxError::ContextsFull)
+        );
+
+        assert!(rx_state.is_assembling(hdr(0x20, 0, 1, 1)));
+        assert!(rx_state
+            .receive_next(hdr(0x20, 0, 1, 1), &[0x22; 4], 1)
+            .is_ok());
+        assert!(rx_state
+            .receive_next(hdr(0x10, 0, 1, 1), &[0x33; 4], 1)
+            .is_ok());
+
+        let msgs = client.msgs.borrow();
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0].0, 0x20);
+        assert_eq!(msgs[0].1[64..], [0x22; 4]);
+        assert_eq!(msgs[1].0, 0x10);
+        assert_eq!(msgs[1].1[64..], [0x33; 4]);
+        assert!(!rx_state.is_assembling(hdr(0x10, 0, 1, 2)));
+    }
+
+    #[test]
+    fn test_out_of_sequence_packet() {
+        let client: &'static TestClient = Box::leak(Box::default());
+        let rx_state = rx_state();
+        rx_state.set_client(client);
+
+        let pkt = [0x11u8; 64];
+        assert!(rx_state
+            .start_receive(hdr(0x10, 1, 0, 0), MessageType::Pldm, &pkt, 0)
+            .is_ok());
+        assert_eq!(
+            rx_state.receive_next(hdr(0x10, 0, 1, 2), &pkt, 1),
+            Err(MCTPRxError::OutOfSequence)
+        );
+        // The partial message is dropped
+        assert!(!rx_state.is_assembling(hdr(0x10, 0, 1, 1)));
+        assert_eq!(client.count.get(), 0);
+    }
+
+    #[test]
+    fn test_expire_stale() {
+        let client: &'static TestClient = Box::leak(Box::default());
+        let rx_state = rx_state();
+        rx_state.set_client(client);
+
+        let pkt = [0x11u8; 64];
+        assert!(rx_state
+            .start_receive(hdr(0x10, 1, 0, 0), MessageType::Pldm, &pkt, 100)
+            .is_ok());
+        assert!(rx_state
+            .start_receive(hdr(0x20, 1, 0, 0), MessageType::Pldm, &pkt, 150)
+            .is_ok());
+
+        assert_eq!(rx_state.expire_stale(200, 50), 1);
+        assert!(!rx_state.is_assembling(hdr(0x10, 0, 1, 1)));
+        assert!(rx_state.is_assembling(hdr(0x20, 0, 1, 1)));
+        assert_eq!(rx_state.next_expiry(200, 50), Some(1));
+
+        // The freed context can be used by a new message
+        assert!(rx_state
+            .start_receive(hdr(0x30, 1, 1, 0), MessageType::Pldm, &[0x44; 4], 200)
+            .is_ok());
+        assert_eq!(client.count.get(), 1);
+    }
+
+    #[test]
+    fn test_next_expiry() {
+        let client: &'static TestClient = Box::leak(Box::default());
+        let rx_state = rx_state();
+        rx_state.set_client(client);
+        assert_eq!(rx_state.next_expiry(0, 50), None);
+
+        let pkt = [0x11u8; 64];
+        assert!(rx_state
+            .start_receive(hdr(0x10, 1, 0, 0), MessageType::Pldm, &pkt, 100)
+            .is_ok());
+        assert!(rx_state
+            .start_receive(hdr(0x20, 1, 0, 0), MessageType::Pldm, &pkt, 120)
+            .is_ok());
+        // The oldest message times out first
+        assert_eq!(rx_state.next_expiry(130, 50), Some(21));
+        assert_eq!(rx_state.expire_stale(130 + 20, 50), 0);
+        assert_eq!(rx_state.expire_stale(130 + 21, 50), 1);
+        assert_eq!(rx_state.next_expiry(151, 50), Some(20));
+
+        // A message already past its timeout is expired on the next tick
+        assert_eq!(rx_state.next_expiry(500, 50), Some(1));
+    }
 }
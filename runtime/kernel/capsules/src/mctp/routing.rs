@@ -0,0 +1,278 @@
+// Licensed under the Apache-2.0 license
+
+use crate::mctp::base_protocol::valid_eid;
+use core::cell::Cell;
+use kernel::ErrorCode;
+
+pub const MCTP_MAX_ROUTING_TABLE_ENTRIES: usize = 8;
+pub const MCTP_MAX_PHYS_ADDR_LEN: usize = 2;
+
+// Physical transport binding identifiers (DSP0239)
+pub const MCTP_BINDING_TYPE_SMBUS: u8 = 0x01;
+pub const MCTP_BINDING_TYPE_PCIE_VDM: u8 = 0x02;
+pub const MCTP_BINDING_TYPE_I3C: u8 = 0x06;
+
+/// Port of an MCTP bridge.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MCTPBridgePort {
+    /// Port towards the bus owner, on which the local endpoint is reached.
+    #[default]
+    Upstream = 0,
+    /// Port towards the endpoints behind the bridge.
+    Downstream = 1,
+}
+
+/// Type of a routing table entry, as encoded in Routing Information Update
+/// and Get Routing Table Entries.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RoutingEntryType {
+    /// Single endpoint that does not route messages
+    #[default]
+    SingleEndpoint = 0,
+    /// EID range of a bridge that starts with the EID of the bridge itself
+    BridgeAndDownstreamEids = 1,
+    /// Single endpoint that is a bridge
+    SingleBridge = 2,
+    /// EID range of a bridge that does not include the EID of the bridge itself
+    DownstreamEids = 3,
+}
+
+impl From<u8> for RoutingEntryType {
+    fn from(val: u8) -> RoutingEntryType {
+        match val & 0x03 {
+            0 => RoutingEntryType::SingleEndpoint,
+            1 => RoutingEntryType::BridgeAndDownstreamEids,
+            2 => RoutingEntryType::SingleBridge,
+            _ => RoutingEntryType::DownstreamEids,
+        }
+    }
+}
+
+/// Physical transport binding information of a bridge port.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MCTPBridgePortInfo {
+    /// Physical transport binding identifier (DSP0239)
+    pub binding_type: u8,
+    /// Physical media identifier (DSP0239)
+    pub media_type: u8,
+    /// Size of the physical addresses on this port
+    pub phys_addr_len: usize,
+}
+
+/// Physical address of an endpoint on a bridge port.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PhysAddr {
+    addr: [u8; MCTP_MAX_PHYS_ADDR_LEN],
+    len: usize,
+}
+
+impl PhysAddr {
+    pub fn new(addr: &[u8]) -> Result<Self, ErrorCode> {
+        if addr.len() > MCTP_MAX_PHYS_ADDR_LEN {
+            return Err(ErrorCode::SIZE);
+        }
+        let mut phys_addr = PhysAddr {
+            addr: [0; MCTP_MAX_PHYS_ADDR_LEN],
+            len: addr.len(),
+        };
+        phys_addr.addr[..addr.len()].copy_from_slice(addr);
+        Ok(phys_addr)
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.addr[..self.len]
+    }
+}
+
+/// Entry of the routing table of an MCTP bridge.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RoutingTableEntry {
+    pub starting_eid: u8,
+    pub eid_range_size: u8,
+    pub entry_type: RoutingEntryType,
+    pub port: MCTPBridgePort,
+    /// Physical transport binding of the port
+    pub port_info: MCTPBridgePortInfo,
+    /// Physical address of the endpoint or bridge that handles the EID range
+    pub phys_addr: PhysAddr,
+}
+
+impl RoutingTableEntry {
+    pub fn contains(&self, eid: u8) -> bool {
+        eid >= self.starting_eid && (eid - self.starting_eid) < self.eid_range_size
+    }
+
+    fn last_eid(&self) -> u8 {
+        self.starting_eid + (self.eid_range_size - 1)
+    }
+
+    fn overlaps(&self, other: &RoutingTableEntry) -> bool {
+        self.starting_eid <= other.last_eid() && other.starting_eid <= self.last_eid()
+    }
+
+    fn is_valid(&self) -> bool {
+        self.eid_range_size > 0
+            && self.starting_eid != 0
+            && valid_eid(self.starting_eid)
+            && self
+                .starting_eid
+                .checked_add(self.eid_range_size - 1)
+                .is_some_and(valid_eid)
+    }
+}
+
+/// Routing table of an MCTP bridge.
+///
+/// The entries of a port are owned by the port they were learned on. An entry
+/// replaces the entries of the same port whose EID ranges overlap with it, and is
+/// rejected if it overlaps with an entry of the other port.
+pub struct MCTPRoutingTable {
+    entries: Cell<[RoutingTableEntry; MCTP_MAX_ROUTING_TABLE_ENTRIES]>,
+    count: Cell<usize>,
+}
+
+impl Default for MCTPRoutingTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MCTPRoutingTable {
+    pub fn new() -> Self {
+        MCTPRoutingTable {
+            entries: Cell::new([RoutingTableEntry::default(); MCTP_MAX_ROUTING_TABLE_ENTRIES]),
+            count: Cell::new(0),
+        }
+    }
+
+    /// Returns the entries of the routing table and the number of valid entries.
+    pub fn entries(&self) -> ([RoutingTableEntry; MCTP_MAX_ROUTING_TABLE_ENTRIES], usize) {
+        (self.entries.get(), self.count.get())
+    }
+
+    /// Returns the entry whose EID range contains the given EID.
+    pub fn lookup(&self, eid: u8) -> Option<RoutingTableEntry> {
+        let entries = self.entries.get();
+        entries[..self.count.get()]
+            .iter()
+            .find(|entry| entry.contains(eid))
+            .copied()
+    }
+
+    /// Adds an entry to the routing table.
+    ///
+    /// # Returns
+    /// `ErrorCode::INVAL` if the EID range is invalid, `ErrorCode::ALREADY` if it overlaps
+    /// with an entry of the other port and `ErrorCode::NOMEM` if the table is full.
+    pub fn update(&self, entry: RoutingTableEntry) -> Result<(), ErrorCode> {
+        if !entry.is_valid() {
+            return Err(ErrorCode::INVAL);
+        }
+
+        let (entries, count) = self.entries();
+        if entries[..count]
+            .iter()
+            .any(|other| other.port != entry.port && other.overlaps(&entry))
+        {
+            return Err(ErrorCode::ALREADY);
+        }
+
+        // Drop the stale entries of the port covering the same EIDs
+        self.retain(|other| !other.overlaps(&entry));
+        let count = self.count.get();
+        if count >= MCTP_MAX_ROUTING_TABLE_ENTRIES {
+            return Err(ErrorCode::NOMEM);
+        }
+        let mut entries = self.entries.get();
+        entries[count] = entry;
+        self.entries.set(entries);
+        self.count.set(count + 1);
+        Ok(())
+    }
+
+    /// Removes all the entries learned on the given port.
+    pub fn remove_port(&self, port: MCTPBridgePort) {
+        self.retain(|entry| entry.port != port);
+    }
+
+    fn retain(&self, keep: impl Fn(&RoutingTableEntry) -> bool) {
+        let (entries, count) = self.entries();
+        let mut retained = [RoutingTableEntry::default(); MCTP_MAX_ROUTING_TABLE_ENTRIES];
+        let mut retained_count = 0;
+        for entry in entries[..count].iter().filter(|entry| keep(entry)) {
+            retained[retained_count] = *entry;
+            retained_count += 1;
+        }
+        self.entries.set(retained);
+        self.count.set(retained_count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(starting_eid: u8, eid_range_size: u8, port: MCTPBridgePort) -> RoutingTableEntry {
+        RoutingTableEntry {
+            starting_eid,
+            eid_range_size,
+            port,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_routing_table_update() {
+        let table = MCTPRoutingTable::new();
+        table
+            .update(entry(0x20, 4, MCTPBridgePort::Downstream))
+            .unwrap();
+        table
+            .update(entry(0x30, 1, MCTPBridgePort::Upstream))
+            .unwrap();
+
+        assert_eq!(
+            table.lookup(0x23).map(|entry| entry.port),
+            Some(MCTPBridgePort::Downstream)
+        );
+        assert_eq!(
+            table.lookup(0x30).map(|entry| entry.port),
+            Some(MCTPBridgePort::Upstream)
+        );
+        assert!(table.lookup(0x24).is_none());
+
+        // Overlapping entries of the other port are rejected
+        assert_eq!(
+            table.update(entry(0x22, 2, MCTPBridgePort::Upstream)),
+            Err(ErrorCode::ALREADY)
+        );
+
+        // Overlapping entries of the same port are replaced
+        table
+            .update(entry(0x21, 1, MCTPBridgePort::Downstream))
+            .unwrap();
+        assert_eq!(table.entries().1, 2);
+        assert!(table.lookup(0x20).is_none());
+
+        table.remove_port(MCTPBridgePort::Downstream);
+        assert_eq!(table.entries().1, 1);
+        assert!(table.lookup(0x21).is_none());
+    }
+
+    #[test]
+    fn test_routing_table_invalid_entry() {
+        let table = MCTPRoutingTable::new();
+        assert_eq!(
+            table.update(entry(0x20, 0, MCTPBridgePort::Upstream)),
+            Err(ErrorCode::INVAL)
+        );
+        assert_eq!(
+            table.update(entry(0xFE, 2, MCTPBridgePort::Upstream)),
+            Err(ErrorCode::INVAL)
+        );
+        assert_eq!(
+            table.update(entry(0x00, 1, MCTPBridgePort::Upstream)),
+            Err(ErrorCode::INVAL)
+        );
+    }
+}
@@ -4,9 +4,11 @@ use crate::mctp::base_protocol::{MCTP_BASELINE_TRANSMISSION_UNIT, MCTP_HDR_SIZE}
 use core::cell::Cell;
 use core::fmt::Write;
 use i3c_driver::hil::{I3CTarget, RxClient, TxClient};
+use kernel::hil::uart::{self, UartData};
 use kernel::utilities::cells::OptionalCell;
 use kernel::utilities::cells::TakeCell;
 use kernel::ErrorCode;
+use pcie_vdm_transport::hil::{PcieVdmRxClient, PcieVdmTarget, PcieVdmTxClient};
 use romtime::println;
 
 // TODO: Set the correct value for MCTP_I3C_MAXBUF.
@@ -15,6 +17,33 @@ pub const MCTP_I3C_MAXBUF: usize = MCTP_HDR_SIZE + MCTP_BASELINE_TRANSMISSION_UN
 pub const MCTP_I3C_MAXMTU: usize = MCTP_I3C_MAXBUF - 1; // 68 bytes
 pub const MCTP_I3C_MINMTU: usize = MCTP_HDR_SIZE + MCTP_BASELINE_TRANSMISSION_UNIT;
 
+/// Size of the PCIe VDM header preceding the MCTP header (DSP0238).
+/// The MCTP header occupies the last DWORD of the 4 DWORD TLP header.
+pub const MCTP_PCIE_VDM_HDR_SIZE: usize = 12;
+pub const MCTP_PCIE_VDM_MAXMTU: usize =
+    MCTP_PCIE_VDM_HDR_SIZE + MCTP_HDR_SIZE + MCTP_BASELINE_TRANSMISSION_UNIT; // 80 bytes
+
+// TLP header fields for MCTP over PCIe VDM
+const PCIE_VDM_TLP_HDR_SIZE: usize = MCTP_PCIE_VDM_HDR_SIZE + MCTP_HDR_SIZE;
+const PCIE_VDM_FMT_TYPE: u8 = 0x70; // Fmt = 4 DW header with data, Type = Message
+const PCIE_VDM_FMT_TYPE_MASK: u8 = 0xF8;
+const PCIE_VDM_ROUTING_MASK: u8 = 0x07;
+const PCIE_VDM_MSG_CODE: u8 = 0x7F; // Vendor Defined Type 1
+const PCIE_VDM_MCTP_VDM_CODE: u8 = 0x00;
+const PCIE_VDM_DMTF_VENDOR_ID: u16 = 0x1AB4;
+const PCIE_VDM_MAX_LENGTH_DW: usize = 0x3FF;
+
+pub const MCTP_SERIAL_MAXMTU: usize = MCTP_HDR_SIZE + MCTP_BASELINE_TRANSMISSION_UNIT; // 68 bytes
+/// Size of a serial frame carrying an MTU sized packet when every byte between the flags is escaped.
+pub const MCTP_SERIAL_MAX_FRAME_SIZE: usize = 2 + 2 * (2 + MCTP_SERIAL_MAXMTU + 2);
+
+// Framing fields for MCTP over serial (DSP0253)
+const MCTP_SERIAL_FRAME_FLAG: u8 = 0x7E;
+const MCTP_SERIAL_ESCAPE: u8 = 0x7D;
+const MCTP_SERIAL_ESCAPE_XOR: u8 = 0x20;
+const MCTP_SERIAL_REVISION: u8 = 0x01;
+const MCTP_SERIAL_FCS_INIT: u16 = 0xFFFF;
+
 /// This trait contains the interface definition
 /// for sending the MCTP packet through MCTP transport binding layer.
 pub trait MCTPTransportBinding<'a> {
@@ -42,6 +71,12 @@ pub trait MCTPTransportBinding<'a> {
 
     /// Get hdr size of transport binding layer
     fn get_hdr_size(&self) -> usize;
+
+    /// Set the physical address of the endpoint the next packets are transmitted to.
+    /// Bindings that only communicate with a single peer ignore it.
+    fn set_dest_phys_addr(&self, _phys_addr: &[u8]) -> Result<(), ErrorCode> {
+        Ok(())
+    }
 }
 
 pub trait TransportTxClient {
@@ -225,6 +260,581 @@ impl RxClient for MCTPI3CBinding<'_> {
     }
 }
 
+/// Routing of a PCIe VDM carrying an MCTP packet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+enum PcieVdmRouting {
+    ToRootComplex = 0b000,
+    ById = 0b010,
+    BroadcastFromRootComplex = 0b011,
+}
+
+/// PCIe VDM header fields carried with each MCTP packet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct PcieVdmHeader {
+    routing: PcieVdmRouting,
+    /// Length of the data payload following the TLP header in DWORDs.
+    length_dw: usize,
+    /// Number of bytes padding the MCTP packet to a DWORD boundary.
+    pad_len: usize,
+    requester_id: u16,
+    target_id: u16,
+}
+
+impl PcieVdmHeader {
+    /// Builds the header for an MCTP packet of `pkt_len` bytes (MCTP header included).
+    fn new(routing: PcieVdmRouting, requester_id: u16, target_id: u16, pkt_len: usize) -> Self {
+        let payload_len = pkt_len - MCTP_HDR_SIZE;
+        PcieVdmHeader {
+            routing,
+            length_dw: payload_len.div_ceil(4),
+            pad_len: (4 - payload_len % 4) % 4,
+            requester_id,
+            target_id,
+        }
+    }
+
+    /// Length of the MCTP packet carried in the VDM, MCTP header included.
+    fn mctp_pkt_len(&self) -> usize {
+        MCTP_HDR_SIZE + self.length_dw * 4 - self.pad_len
+    }
+
+    /// Length of the VDM, TLP header included.
+    fn vdm_len(&self) -> usize {
+        PCIE_VDM_TLP_HDR_SIZE + self.length_dw * 4
+    }
+
+    fn encode(&self, buf: &mut [u8]) {
+        buf[0] = PCIE_VDM_FMT_TYPE | self.routing as u8;
+        buf[1] = 0;
+        buf[2] = ((self.length_dw >> 8) & 0x03) as u8;
+        buf[3] = self.length_dw as u8;
+        buf[4..6].copy_from_slice(&self.requester_id.to_be_bytes());
+        buf[6] = ((self.pad_len as u8) << 4) | PCIE_VDM_MCTP_VDM_CODE;
+        buf[7] = PCIE_VDM_MSG_CODE;
+        buf[8..10].copy_from_slice(&self.target_id.to_be_bytes());
+        buf[10..12].copy_from_slice(&PCIE_VDM_DMTF_VENDOR_ID.to_be_bytes());
+    }
+
+    /// Decodes the header of a received VDM. Returns None if the VDM does not carry an MCTP packet.
+    fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < PCIE_VDM_TLP_HDR_SIZE
+            || buf[0] & PCIE_VDM_FMT_TYPE_MASK != PCIE_VDM_FMT_TYPE
+            || buf[6] & 0x0F != PCIE_VDM_MCTP_VDM_CODE
+            || buf[7] != PCIE_VDM_MSG_CODE
+            || u16::from_be_bytes([buf[10], buf[11]]) != PCIE_VDM_DMTF_VENDOR_ID
+        {
+            return None;
+        }
+
+        let routing = match buf[0] & PCIE_VDM_ROUTING_MASK {
+            0b000 => PcieVdmRouting::ToRootComplex,
+            0b010 => PcieVdmRouting::ById,
+            0b011 => PcieVdmRouting::BroadcastFromRootComplex,
+            _ => return None,
+        };
+        let length_dw = (((buf[2] & 0x03) as usize) << 8) | buf[3] as usize;
+        let pad_len = ((buf[6] >> 4) & 0x03) as usize;
+        if length_dw == 0 && pad_len > 0 {
+            return None;
+        }
+
+        Some(PcieVdmHeader {
+            routing,
+            length_dw,
+            pad_len,
+            requester_id: u16::from_be_bytes([buf[4], buf[5]]),
+            target_id: u16::from_be_bytes([buf[8], buf[9]]),
+        })
+    }
+}
+
+/// MCTP over PCIe VDM transport binding (DSP0238).
+///
+/// Each MCTP packet is carried in a single PCIe Vendor Defined Message.
+/// Packets are sent to the PCI ID set as the destination physical address, or
+/// back to the PCI ID of the last endpoint heard from. They are routed to the
+/// root complex until the peer is known.
+pub struct MCTPPcieVdmBinding<'a> {
+    /// Reference to the PCIe VDM target device driver.
+    pcie_vdm: &'a dyn PcieVdmTarget<'a>,
+    rx_client: OptionalCell<&'a dyn TransportRxClient>,
+    tx_client: OptionalCell<&'a dyn TransportTxClient>,
+    /// PCI ID of this device, used as the Requester ID.
+    pci_id: Cell<u16>,
+    /// PCI ID of the peer the packets are sent to.
+    peer_pci_id: OptionalCell<u16>,
+    /// Max VDM length supported by the PCIe VDM target device.
+    max_vdm_len: Cell<usize>,
+}
+
+impl<'a> MCTPPcieVdmBinding<'a> {
+    pub fn new(pcie_vdm: &'a dyn PcieVdmTarget<'a>) -> MCTPPcieVdmBinding<'a> {
+        MCTPPcieVdmBinding {
+            pcie_vdm,
+            rx_client: OptionalCell::empty(),
+            tx_client: OptionalCell::empty(),
+            pci_id: Cell::new(0),
+            peer_pci_id: OptionalCell::empty(),
+            max_vdm_len: Cell::new(0),
+        }
+    }
+
+    pub fn setup_mctp_pcie_vdm(&self) {
+        let device_info = self.pcie_vdm.get_device_info();
+        self.max_vdm_len.set(device_info.max_vdm_len);
+        self.pci_id.set(device_info.pci_id.unwrap_or(0));
+    }
+}
+
+impl<'a> MCTPTransportBinding<'a> for MCTPPcieVdmBinding<'a> {
+    fn set_tx_client(&self, tx_client: &'a dyn TransportTxClient) {
+        self.tx_client.set(tx_client);
+    }
+
+    fn set_rx_client(&self, rx_client: &'a dyn TransportRxClient) {
+        self.rx_client.set(rx_client);
+    }
+
+    fn set_rx_buffer(&self, rx_buf: &'static mut [u8]) {
+        self.pcie_vdm.set_rx_buffer(rx_buf);
+    }
+
+    fn transmit(
+        &self,
+        tx_buffer: &'static mut [u8],
+        len: usize,
+    ) -> Result<(), (ErrorCode, &'static mut [u8])> {
+        // The buffer holds the PCIe VDM header space followed by the MCTP packet
+        if len < PCIE_VDM_TLP_HDR_SIZE {
+            println!("MCTPPcieVdmBinding: Invalid length {}", len);
+            return Err((ErrorCode::SIZE, tx_buffer));
+        }
+
+        let hdr = match self.peer_pci_id.get() {
+            Some(peer_pci_id) => PcieVdmHeader::new(
+                PcieVdmRouting::ById,
+                self.pci_id.get(),
+                peer_pci_id,
+                len - MCTP_PCIE_VDM_HDR_SIZE,
+            ),
+            None => PcieVdmHeader::new(
+                PcieVdmRouting::ToRootComplex,
+                self.pci_id.get(),
+                0,
+                len - MCTP_PCIE_VDM_HDR_SIZE,
+            ),
+        };
+
+        // Make sure there's enough space to pad the packet to a DWORD boundary
+        let vdm_len = hdr.vdm_len();
+        if hdr.length_dw > PCIE_VDM_MAX_LENGTH_DW
+            || vdm_len > tx_buffer.len()
+            || vdm_len > self.max_vdm_len.get()
+        {
+            println!(
+                "MCTPPcieVdmBinding: Invalid length. Expected: {}",
+                self.max_vdm_len.get()
+            );
+            return Err((ErrorCode::SIZE, tx_buffer));
+        }
+
+        hdr.encode(&mut tx_buffer[..MCTP_PCIE_VDM_HDR_SIZE]);
+        tx_buffer[len..vdm_len].fill(0);
+        self.pcie_vdm.transmit(tx_buffer, vdm_len)
+    }
+
+    fn enable(&self) {
+        self.pcie_vdm.enable();
+    }
+
+    fn disable(&self) {
+        self.pcie_vdm.disable();
+    }
+
+    fn get_mtu_size(&self) -> usize {
+        MCTP_PCIE_VDM_MAXMTU
+    }
+
+    fn get_hdr_size(&self) -> usize {
+        MCTP_PCIE_VDM_HDR_SIZE
+    }
+
+    fn set_dest_phys_addr(&self, phys_addr: &[u8]) -> Result<(), ErrorCode> {
+        // The physical address of a PCIe endpoint is its big-endian PCI ID
+        let pci_id: [u8; 2] = phys_addr.try_into().map_err(|_| ErrorCode::INVAL)?;
+        self.peer_pci_id.set(u16::from_be_bytes(pci_id));
+        Ok(())
+    }
+}
+
+impl PcieVdmTxClient for MCTPPcieVdmBinding<'_> {
+    fn send_done(&self, tx_buffer: &'static mut [u8], result: Result<(), ErrorCode>) {
+        self.tx_client.map(|client| {
+            client.send_done(tx_buffer, result);
+        });
+    }
+}
+
+impl PcieVdmRxClient for MCTPPcieVdmBinding<'_> {
+    fn receive(&self, rx_buffer: &'static mut [u8], len: usize) {
+        // check if the VDM carries an MCTP packet and is no longer than the received data
+        // if yes, strip the PCIe VDM header and call the client's receive function
+        // if no, drop the packet and set_rx_buffer on pcie_vdm to receive the next packet
+        let hdr = match PcieVdmHeader::decode(&rx_buffer[..len.min(rx_buffer.len())]) {
+            Some(hdr) if hdr.vdm_len() <= len && len <= rx_buffer.len() => hdr,
+            _ => {
+                println!("MCTPPcieVdmBinding: Invalid MCTP VDM. Dropping packet.");
+                self.pcie_vdm.set_rx_buffer(rx_buffer);
+                return;
+            }
+        };
+
+        // Responses are routed back to the sender. A VDM routed by ID is addressed to this device.
+        self.peer_pci_id.set(hdr.requester_id);
+        if hdr.routing == PcieVdmRouting::ById {
+            self.pci_id.set(hdr.target_id);
+        }
+
+        // Move the MCTP packet to the start of the buffer
+        let pkt_len = hdr.mctp_pkt_len();
+        rx_buffer.copy_within(MCTP_PCIE_VDM_HDR_SIZE..MCTP_PCIE_VDM_HDR_SIZE + pkt_len, 0);
+        self.rx_client.map(|client| {
+            client.receive(rx_buffer, pkt_len);
+        });
+    }
+
+    fn write_expected(&self) {
+        self.rx_client.map(|client| {
+            client.write_expected();
+        });
+    }
+}
+
+/// FCS-16 (RFC1662) calculation.
+fn compute_fcs(mut fcs: u16, buf: &[u8]) -> u16 {
+    for byte in buf {
+        fcs ^= *byte as u16;
+        for _ in 0..8 {
+            if fcs & 0x0001 != 0 {
+                fcs = (fcs >> 1) ^ 0x8408;
+            } else {
+                fcs >>= 1;
+            }
+        }
+    }
+    fcs
+}
+
+/// Encodes an MCTP packet in a serial frame (DSP0253).
+///
+/// The frame is made of the revision, the byte count, the packet and the FCS,
+/// surrounded by flags. The bytes between the flags are escaped.
+///
+/// # Returns
+/// The length of the frame, or `None` if it does not fit in `frame`.
+fn encode_serial_frame(pkt: &[u8], frame: &mut [u8]) -> Option<usize> {
+    let byte_count = u8::try_from(pkt.len()).ok()?;
+    let hdr = [MCTP_SERIAL_REVISION, byte_count];
+    let fcs = compute_fcs(compute_fcs(MCTP_SERIAL_FCS_INIT, &hdr), pkt);
+
+    let mut len = 0;
+    let mut push = |byte: u8| -> Option<()> {
+        *frame.get_mut(len)? = byte;
+        len += 1;
+        Some(())
+    };
+    push(MCTP_SERIAL_FRAME_FLAG)?;
+    for byte in hdr.iter().chain(pkt).chain(fcs.to_be_bytes().iter()) {
+        if *byte == MCTP_SERIAL_FRAME_FLAG || *byte == MCTP_SERIAL_ESCAPE {
+            push(MCTP_SERIAL_ESCAPE)?;
+            push(*byte ^ MCTP_SERIAL_ESCAPE_XOR)?;
+        } else {
+            push(*byte)?;
+        }
+    }
+    push(MCTP_SERIAL_FRAME_FLAG)?;
+    Some(len)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SerialRxState {
+    /// Waiting for the flag opening a frame
+    Idle,
+    Revision,
+    ByteCount,
+    Data,
+    Fcs,
+    /// Waiting for the flag closing the frame
+    End,
+}
+
+/// Decoder of the serial frames received one byte at a time.
+#[derive(Clone, Copy, Debug)]
+struct SerialFrameDecoder {
+    state: SerialRxState,
+    escape: bool,
+    pkt_len: usize,
+    index: usize,
+    fcs: u16,
+    fcs_rcvd: u16,
+}
+
+impl SerialFrameDecoder {
+    const fn new() -> Self {
+        SerialFrameDecoder {
+            state: SerialRxState::Idle,
+            escape: false,
+            pkt_len: 0,
+            index: 0,
+            fcs: MCTP_SERIAL_FCS_INIT,
+            fcs_rcvd: 0,
+        }
+    }
+
+    /// Processes a received byte and stores the packet bytes in `pkt_buf`.
+    /// Frames whose packet does not fit in `pkt_buf` are dropped.
+    ///
+    /// # Returns
+    /// The length of the packet once a frame with a valid FCS has been closed.
+    fn push(&mut self, byte: u8, pkt_buf: &mut [u8]) -> Option<usize> {
+        if byte == MCTP_SERIAL_FRAME_FLAG {
+            // A flag closes the current frame and may open the next one
+            let pkt_len = (self.state == SerialRxState::End && self.fcs == self.fcs_rcvd)
+                .then_some(self.pkt_len);
+            *self = SerialFrameDecoder::new();
+            self.state = SerialRxState::Revision;
+            return pkt_len;
+        }
+
+        if self.state == SerialRxState::Idle {
+            return None;
+        }
+        if byte == MCTP_SERIAL_ESCAPE {
+            self.escape = true;
+            return None;
+        }
+        let byte = if self.escape {
+            self.escape = false;
+            byte ^ MCTP_SERIAL_ESCAPE_XOR
+        } else {
+            byte
+        };
+
+        match self.state {
+            SerialRxState::Revision if byte == MCTP_SERIAL_REVISION => {
+                self.fcs = compute_fcs(self.fcs, &[byte]);
+                self.state = SerialRxState::ByteCount;
+            }
+            SerialRxState::ByteCount if byte > 0 && byte as usize <= pkt_buf.len() => {
+                self.fcs = compute_fcs(self.fcs, &[byte]);
+                self.pkt_len = byte as usize;
+                self.state = SerialRxState::Data;
+            }
+            SerialRxState::Data => {
+                pkt_buf[self.index] = byte;
+                self.fcs = compute_fcs(self.fcs, &[byte]);
+                self.index += 1;
+                if self.index == self.pkt_len {
+                    self.index = 0;
+                    self.state = SerialRxState::Fcs;
+                }
+            }
+            SerialRxState::Fcs => {
+                self.fcs_rcvd = (self.fcs_rcvd << 8) | byte as u16;
+                self.index += 1;
+                if self.index == 2 {
+                    self.state = SerialRxState::End;
+                }
+            }
+            _ => self.state = SerialRxState::Idle,
+        }
+        None
+    }
+}
+
+/// MCTP over serial transport binding (DSP0253).
+///
+/// Each MCTP packet is carried in an HDLC-like frame protected by an FCS.
+/// The UART may be shared with the console: the bytes received outside of
+/// a frame are ignored, and frames with an invalid FCS are dropped.
+pub struct MCTPSerialBinding<'a> {
+    /// Reference to the UART carrying the frames.
+    uart: &'a dyn UartData<'a>,
+    rx_client: OptionalCell<&'a dyn TransportRxClient>,
+    tx_client: OptionalCell<&'a dyn TransportTxClient>,
+    /// Buffer to store the transmitted packet while its frame is sent.
+    tx_buffer: TakeCell<'static, [u8]>,
+    /// Buffer holding the encoded frame.
+    frame_buffer: TakeCell<'static, [u8]>,
+    /// Buffer to store the received packet.
+    rx_buffer: TakeCell<'static, [u8]>,
+    /// Single byte buffer the UART receives into.
+    uart_rx_buffer: TakeCell<'static, [u8]>,
+    rx_decoder: Cell<SerialFrameDecoder>,
+    enabled: Cell<bool>,
+}
+
+impl<'a> MCTPSerialBinding<'a> {
+    pub fn new(
+        uart: &'a dyn UartData<'a>,
+        frame_buffer: &'static mut [u8],
+        uart_rx_buffer: &'static mut [u8],
+    ) -> MCTPSerialBinding<'a> {
+        MCTPSerialBinding {
+            uart,
+            rx_client: OptionalCell::empty(),
+            tx_client: OptionalCell::empty(),
+            tx_buffer: TakeCell::empty(),
+            frame_buffer: TakeCell::new(frame_buffer),
+            rx_buffer: TakeCell::empty(),
+            uart_rx_buffer: TakeCell::new(uart_rx_buffer),
+            rx_decoder: Cell::new(SerialFrameDecoder::new()),
+            enabled: Cell::new(false),
+        }
+    }
+
+    fn receive_next_byte(&self) {
+        if let Some(buf) = self.uart_rx_buffer.take() {
+            if let Err((e, buf)) = self.uart.receive_buffer(buf, 1) {
+                println!("MCTPSerialBinding: Failed to receive. Error: {:?}", e);
+                self.uart_rx_buffer.replace(buf);
+            }
+        }
+    }
+
+    fn process_rx_byte(&self, byte: u8) {
+        // Ask for a buffer when a frame may start
+        if byte == MCTP_SERIAL_FRAME_FLAG && self.rx_buffer.is_none() {
+            self.rx_client.map(|client| {
+                client.write_expected();
+            });
+        }
+
+        let mut decoder = self.rx_decoder.get();
+        let pkt_len = self
+            .rx_buffer
+            .map(|rx_buffer| decoder.push(byte, rx_buffer))
+            .unwrap_or_else(|| decoder.push(byte, &mut []));
+        self.rx_decoder.set(decoder);
+
+        if let Some(pkt_len) = pkt_len {
+            if let Some(rx_buffer) = self.rx_buffer.take() {
+                self.rx_client.map(|client| {
+                    client.receive(rx_buffer, pkt_len);
+                });
+            }
+        }
+    }
+}
+
+impl<'a> MCTPTransportBinding<'a> for MCTPSerialBinding<'a> {
+    fn set_tx_client(&self, tx_client: &'a dyn TransportTxClient) {
+        self.tx_client.set(tx_client);
+    }
+
+    fn set_rx_client(&self, rx_client: &'a dyn TransportRxClient) {
+        self.rx_client.set(rx_client);
+    }
+
+    fn set_rx_buffer(&self, rx_buf: &'static mut [u8]) {
+        self.rx_buffer.replace(rx_buf);
+    }
+
+    fn transmit(
+        &self,
+        tx_buffer: &'static mut [u8],
+        len: usize,
+    ) -> Result<(), (ErrorCode, &'static mut [u8])> {
+        if len == 0 || len > MCTP_SERIAL_MAXMTU || len > tx_buffer.len() {
+            println!(
+                "MCTPSerialBinding: Invalid length. Expected: {}",
+                MCTP_SERIAL_MAXMTU
+            );
+            return Err((ErrorCode::SIZE, tx_buffer));
+        }
+
+        let frame_buffer = match self.frame_buffer.take() {
+            Some(frame_buffer) => frame_buffer,
+            None => return Err((ErrorCode::BUSY, tx_buffer)),
+        };
+        let frame_len = match encode_serial_frame(&tx_buffer[..len], frame_buffer) {
+            Some(frame_len) => frame_len,
+            None => {
+                self.frame_buffer.replace(frame_buffer);
+                return Err((ErrorCode::SIZE, tx_buffer));
+            }
+        };
+
+        match self.uart.transmit_buffer(frame_buffer, frame_len) {
+            Ok(()) => {
+                self.tx_buffer.replace(tx_buffer);
+                Ok(())
+            }
+            Err((e, frame_buffer)) => {
+                self.frame_buffer.replace(frame_buffer);
+                Err((e, tx_buffer))
+            }
+        }
+    }
+
+    fn enable(&self) {
+        if !self.enabled.replace(true) {
+            self.receive_next_byte();
+        }
+    }
+
+    fn disable(&self) {
+        if self.enabled.replace(false) {
+            let _ = self.uart.receive_abort();
+        }
+    }
+
+    fn get_mtu_size(&self) -> usize {
+        MCTP_SERIAL_MAXMTU
+    }
+
+    fn get_hdr_size(&self) -> usize {
+        0
+    }
+}
+
+impl uart::TransmitClient for MCTPSerialBinding<'_> {
+    fn transmitted_buffer(
+        &self,
+        tx_buffer: &'static mut [u8],
+        _tx_len: usize,
+        rval: Result<(), ErrorCode>,
+    ) {
+        self.frame_buffer.replace(tx_buffer);
+        if let Some(tx_buffer) = self.tx_buffer.take() {
+            self.tx_client.map(|client| {
+                client.send_done(tx_buffer, rval);
+            });
+        }
+    }
+}
+
+impl uart::ReceiveClient for MCTPSerialBinding<'_> {
+    fn received_buffer(
+        &self,
+        rx_buffer: &'static mut [u8],
+        rx_len: usize,
+        rval: Result<(), ErrorCode>,
+        _error: uart::Error,
+    ) {
+        let byte = rx_buffer[0];
+        self.uart_rx_buffer.replace(rx_buffer);
+        if !self.enabled.get() {
+            return;
+        }
+        if rval.is_ok() && rx_len == 1 {
+            self.process_rx_byte(byte);
+        }
+        self.receive_next_byte();
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -299,4 +909,92 @@ mod tests {
         let exp_pec = calculate_crc8(&pkt[..pkt_buf_len]);
         assert_eq!(exp_pec, computed_pec);
     }
+
+    #[test]
+    fn test_pcie_vdm_hdr_encode() {
+        // MCTP header + 3 bytes payload, padded to 1 DWORD
+        let hdr = PcieVdmHeader::new(PcieVdmRouting::ById, 0x0100, 0x0008, MCTP_HDR_SIZE + 3);
+        assert_eq!(hdr.length_dw, 1);
+        assert_eq!(hdr.pad_len, 1);
+        assert_eq!(hdr.vdm_len(), 20);
+
+        let mut vdm = [0u8; PCIE_VDM_TLP_HDR_SIZE + 4];
+        hdr.encode(&mut vdm[..MCTP_PCIE_VDM_HDR_SIZE]);
+        assert_eq!(
+            vdm[..MCTP_PCIE_VDM_HDR_SIZE],
+            [0x72, 0x00, 0x00, 0x01, 0x01, 0x00, 0x10, 0x7F, 0x00, 0x08, 0x1A, 0xB4]
+        );
+        assert_eq!(PcieVdmHeader::decode(&vdm), Some(hdr));
+    }
+
+    #[test]
+    fn test_pcie_vdm_hdr_decode() {
+        // Route to root complex, 2 DWORDs of payload with 2 bytes of padding
+        let mut vdm = [0u8; PCIE_VDM_TLP_HDR_SIZE + 8];
+        vdm[..MCTP_PCIE_VDM_HDR_SIZE].copy_from_slice(&[
+            0x70, 0x00, 0x00, 0x02, 0x00, 0x08, 0x20, 0x7F, 0x00, 0x00, 0x1A, 0xB4,
+        ]);
+        let hdr = PcieVdmHeader::decode(&vdm).unwrap();
+        assert_eq!(hdr.routing, PcieVdmRouting::ToRootComplex);
+        assert_eq!(hdr.requester_id, 0x0008);
+        assert_eq!(hdr.mctp_pkt_len(), MCTP_HDR_SIZE + 6);
+        assert_eq!(hdr.vdm_len(), vdm.len());
+
+        // Not a DMTF VDM
+        vdm[11] = 0x14;
+        assert_eq!(PcieVdmHeader::decode(&vdm), None);
+    }
+
+    #[test]
+    fn test_fcs() {
+        // RFC1662 check value, before the ones' complement
+        assert_eq!(0x6F91, compute_fcs(MCTP_SERIAL_FCS_INIT, b"123456789"));
+    }
+
+    #[test]
+    fn test_serial_frame_encode() {
+        let pkt = [0x01, 0x00, 0x08, 0xC8, 0x7E, 0x7D];
+        let mut frame = [0u8; MCTP_SERIAL_MAX_FRAME_SIZE];
+        let len = encode_serial_frame(&pkt, &mut frame).unwrap();
+        assert_eq!(
+            frame[..len],
+            [0x7E, 0x01, 0x06, 0x01, 0x00, 0x08, 0xC8, 0x7D, 0x5E, 0x7D, 0x5D, 0x4A, 0xE3, 0x7E]
+        );
+
+        // The escaped frame does not fit in a buffer sized for the unescaped one
+        assert_eq!(encode_serial_frame(&pkt, &mut frame[..pkt.len() + 6]), None);
+    }
+
+    #[test]
+    fn test_serial_frame_decode() {
+        let pkt = [0x01, 0x08, 0x00, 0xC0, 0x00, 0x81, 0x02];
+        let mut frame = [0u8; MCTP_SERIAL_MAX_FRAME_SIZE];
+        let len = encode_serial_frame(&pkt, &mut frame).unwrap();
+
+        // Console output before the frame is ignored
+        let mut decoder = SerialFrameDecoder::new();
+        let mut pkt_buf = [0u8; MCTP_SERIAL_MAXMTU];
+        for byte in b"console\n" {
+            assert_eq!(decoder.push(*byte, &mut pkt_buf), None);
+        }
+        let pkt_len = frame[..len]
+            .iter()
+            .fold(None, |_, byte| decoder.push(*byte, &mut pkt_buf));
+        assert_eq!(pkt_len, Some(pkt.len()));
+        assert_eq!(pkt_buf[..pkt.len()], pkt);
+
+        // A frame with a corrupted FCS is dropped
+        frame[len - 2] ^= 0x01;
+        let pkt_len = frame[..len]
+            .iter()
+            .fold(None, |_, byte| decoder.push(*byte, &mut pkt_buf));
+        assert_eq!(pkt_len, None);
+
+        // A frame whose packet does not fit in the buffer is dropped
+        frame[len - 2] ^= 0x01;
+        let pkt_len = frame[..len]
+            .iter()
+            .fold(None, |_, byte| decoder.push(*byte, &mut pkt_buf[..4]));
+        assert_eq!(pkt_len, None);
+    }
 }
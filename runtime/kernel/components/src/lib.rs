@@ -8,6 +8,7 @@ pub mod flash_partition;
 pub mod mailbox;
 pub mod mbox_sram;
 pub mod mci;
+pub mod mctp_bridge;
 pub mod mctp_driver;
 pub mod mcu_mbox;
 pub mod mock_mctp;
@@ -0,0 +1,127 @@
+// Licensed under the Apache-2.0 license
+
+//! Component for initializing the MCTP bridge mode of the MCTP mux.
+//!
+//! The bridge forwards the packets between the MCTP mux binding (upstream port)
+//! and a second MCTP transport binding (downstream port).
+//!
+//! Usage
+//! -----
+//! ```ignore
+//! use mcu_components::mctp_bridge_component_static;
+//! use kernel::component::Component;
+//! use mcu_tock_veer::timers::InternalTimers;
+//! let bridge = mcu_components::mctp_bridge::MCTPBridgeComponent::new(
+//!    mux_mctp,
+//!    downstream_binding,
+//!    upstream_port_info,
+//!    downstream_port_info,
+//!    mux_alarm)
+//! .finalize(mctp_bridge_component_static!(InternalTimers));
+//! bridge.add_downstream_endpoint(&[downstream_device_addr]).unwrap();
+//! ```
+//!
+
+use capsules_core::virtualizers::virtual_alarm::{MuxAlarm, VirtualMuxAlarm};
+use capsules_runtime::mctp::bridge::{MCTPBridge, MCTP_BRIDGE_QUEUE_SIZE};
+use capsules_runtime::mctp::mux::MuxMCTPDriver;
+use capsules_runtime::mctp::routing::MCTPBridgePortInfo;
+use capsules_runtime::mctp::transport_binding::{MCTPI3CBinding, MCTPTransportBinding};
+use core::mem::MaybeUninit;
+use i3c_driver::core::MAX_READ_WRITE_SIZE;
+use kernel::component::Component;
+use kernel::hil::time::Alarm;
+
+// Setup static space for the objects.
+#[macro_export]
+macro_rules! mctp_bridge_component_static {
+    ($A:ty $(,)?) => {{
+        use capsules_core::virtualizers::virtual_alarm::VirtualMuxAlarm;
+        use capsules_runtime::mctp::bridge::{MCTPBridge, MCTP_BRIDGE_QUEUE_SIZE};
+        use i3c_driver::core::MAX_READ_WRITE_SIZE;
+
+        let alarm = kernel::static_buf!(VirtualMuxAlarm<'static, $A>);
+        let tx_buffer = kernel::static_buf!([u8; MAX_READ_WRITE_SIZE]);
+        let rx_buffer = kernel::static_buf!([u8; MAX_READ_WRITE_SIZE]);
+        let downstream_queue_buffer = kernel::static_buf!([u8; MCTP_BRIDGE_QUEUE_SIZE]);
+        let upstream_queue_buffer = kernel::static_buf!([u8; MCTP_BRIDGE_QUEUE_SIZE]);
+        let bridge = kernel::static_buf!(MCTPBridge<'static, VirtualMuxAlarm<'static, $A>>);
+        (
+            alarm,
+            tx_buffer,
+            rx_buffer,
+            downstream_queue_buffer,
+            upstream_queue_buffer,
+            bridge,
+        )
+    }};
+}
+
+pub struct MCTPBridgeComponent<
+    A: Alarm<'static> + 'static,
+    M: MCTPTransportBinding<'static> + 'static = MCTPI3CBinding<'static>,
+> {
+    mux_mctp: &'static MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, M>,
+    downstream: &'static dyn MCTPTransportBinding<'static>,
+    upstream_port: MCTPBridgePortInfo,
+    downstream_port: MCTPBridgePortInfo,
+    mux_alarm: &'static MuxAlarm<'static, A>,
+}
+
+impl<A: Alarm<'static>, M: MCTPTransportBinding<'static>> MCTPBridgeComponent<A, M> {
+    pub fn new(
+        mux_mctp: &'static MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, M>,
+        downstream: &'static dyn MCTPTransportBinding<'static>,
+        upstream_port: MCTPBridgePortInfo,
+        downstream_port: MCTPBridgePortInfo,
+        mux_alarm: &'static MuxAlarm<'static, A>,
+    ) -> Self {
+        Self {
+            mux_mctp,
+            downstream,
+            upstream_port,
+            downstream_port,
+            mux_alarm,
+        }
+    }
+}
+
+impl<A: Alarm<'static>, M: MCTPTransportBinding<'static>> Component for MCTPBridgeComponent<A, M> {
+    type StaticInput = (
+        &'static mut MaybeUninit<VirtualMuxAlarm<'static, A>>,
+        &'static mut MaybeUninit<[u8; MAX_READ_WRITE_SIZE]>,
+        &'static mut MaybeUninit<[u8; MAX_READ_WRITE_SIZE]>,
+        &'static mut MaybeUninit<[u8; MCTP_BRIDGE_QUEUE_SIZE]>,
+        &'static mut MaybeUninit<[u8; MCTP_BRIDGE_QUEUE_SIZE]>,
+        &'static mut MaybeUninit<MCTPBridge<'static, VirtualMuxAlarm<'static, A>>>,
+    );
+    type Output = &'static MCTPBridge<'static, VirtualMuxAlarm<'static, A>>;
+
+    fn finalize(self, static_buffer: Self::StaticInput) -> Self::Output {
+        let tx_pkt_buffer = static_buffer.1.write([0; MAX_READ_WRITE_SIZE]);
+        let rx_pkt_buffer = static_buffer.2.write([0; MAX_READ_WRITE_SIZE]);
+        let downstream_queue_buffer = static_buffer.3.write([0; MCTP_BRIDGE_QUEUE_SIZE]);
+        let upstream_queue_buffer = static_buffer.4.write([0; MCTP_BRIDGE_QUEUE_SIZE]);
+
+        let bridge_alarm = static_buffer.0.write(VirtualMuxAlarm::new(self.mux_alarm));
+        bridge_alarm.setup();
+
+        let bridge = static_buffer.5.write(MCTPBridge::new(
+            self.downstream,
+            self.upstream_port,
+            self.downstream_port,
+            tx_pkt_buffer,
+            rx_pkt_buffer,
+            downstream_queue_buffer,
+            upstream_queue_buffer,
+            bridge_alarm,
+        ));
+
+        self.downstream.set_tx_client(bridge);
+        self.downstream.set_rx_client(bridge);
+        bridge_alarm.set_alarm_client(bridge);
+        bridge.set_client(self.mux_mctp);
+        self.mux_mctp.set_bridge(bridge);
+        bridge
+    }
+}
@@ -27,10 +27,10 @@ use capsules_core::virtualizers::virtual_alarm::VirtualMuxAlarm;
 use capsules_runtime::mctp::base_protocol::MessageType;
 use capsules_runtime::mctp::driver::{MCTPDriver, MCTP_MAX_MESSAGE_SIZE};
 use capsules_runtime::mctp::mux::MuxMCTPDriver;
-use capsules_runtime::mctp::recv::MCTPRxState;
+use capsules_runtime::mctp::recv::{MCTPRxState, MCTP_MAX_REASSEMBLY_CONTEXTS};
 use capsules_runtime::mctp::send::MCTPSender;
 use capsules_runtime::mctp::send::MCTPTxState;
-use capsules_runtime::mctp::transport_binding::MCTPI3CBinding;
+use capsules_runtime::mctp::transport_binding::{MCTPI3CBinding, MCTPTransportBinding};
 use core::mem::MaybeUninit;
 use kernel::capabilities;
 use kernel::component::Component;
@@ -41,26 +41,30 @@ use kernel::utilities::leasable_buffer::SubSliceMut;
 // Setup static space for the objects.
 #[macro_export]
 macro_rules! mctp_driver_component_static {
-    ($A:ty  $(,)?) => {{
+    ($A:ty $(,)?) => {{
+        $crate::mctp_driver_component_static!(
+            $A,
+            capsules_runtime::mctp::transport_binding::MCTPI3CBinding<'static>
+        )
+    }};
+    ($A:ty, $M:ty $(,)?) => {{
         use capsules_core::virtualizers::virtual_alarm::VirtualMuxAlarm;
         use capsules_runtime::mctp::driver::MCTPDriver;
         use capsules_runtime::mctp::driver::MCTP_MAX_MESSAGE_SIZE;
-        use capsules_runtime::mctp::recv::MCTPRxState;
+        use capsules_runtime::mctp::recv::{MCTPRxState, MCTP_MAX_REASSEMBLY_CONTEXTS};
         use capsules_runtime::mctp::send::MCTPTxState;
-        use capsules_runtime::mctp::transport_binding::MCTPI3CBinding;
 
-        let tx_state = kernel::static_buf!(
-            MCTPTxState<'static, VirtualMuxAlarm<'static, $A>, MCTPI3CBinding<'static>>
-        );
+        let tx_state = kernel::static_buf!(MCTPTxState<'static, VirtualMuxAlarm<'static, $A>, $M>);
         let rx_state = kernel::static_buf!(MCTPRxState<'static>);
-        let rx_msg_buf = kernel::static_buf!([u8; MCTP_MAX_MESSAGE_SIZE]);
+        let rx_msg_bufs =
+            kernel::static_buf!([[u8; MCTP_MAX_MESSAGE_SIZE]; MCTP_MAX_REASSEMBLY_CONTEXTS]);
         let tx_msg_buf = kernel::static_buf!([u8; MCTP_MAX_MESSAGE_SIZE]);
         let mctp_driver = kernel::static_buf!(MCTPDriver<'static>);
         let buffered_rx_msg = kernel::static_buf!([u8; MCTP_MAX_MESSAGE_SIZE]);
         (
             tx_state,
             rx_state,
-            rx_msg_buf,
+            rx_msg_bufs,
             tx_msg_buf,
             mctp_driver,
             buffered_rx_msg,
@@ -68,22 +72,21 @@ macro_rules! mctp_driver_component_static {
     }};
 }
 
-pub struct MCTPDriverComponent<A: Alarm<'static> + 'static> {
+pub struct MCTPDriverComponent<
+    A: Alarm<'static> + 'static,
+    M: MCTPTransportBinding<'static> + 'static = MCTPI3CBinding<'static>,
+> {
     board_kernel: &'static kernel::Kernel,
     driver_num: usize,
-    mux_mctp: &'static MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, MCTPI3CBinding<'static>>,
+    mux_mctp: &'static MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, M>,
     msg_type: MessageType,
 }
 
-impl<A: Alarm<'static>> MCTPDriverComponent<A> {
+impl<A: Alarm<'static>, M: MCTPTransportBinding<'static>> MCTPDriverComponent<A, M> {
     pub fn new(
         board_kernel: &'static kernel::Kernel,
         driver_num: usize,
-        mux_mctp: &'static MuxMCTPDriver<
-            'static,
-            VirtualMuxAlarm<'static, A>,
-            MCTPI3CBinding<'static>,
-        >,
+        mux_mctp: &'static MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, M>,
         msg_type: MessageType,
     ) -> Self {
         Self {
@@ -95,13 +98,11 @@ impl<A: Alarm<'static>> MCTPDriverComponent<A> {
     }
 }
 
-impl<A: Alarm<'static>> Component for MCTPDriverComponent<A> {
+impl<A: Alarm<'static>, M: MCTPTransportBinding<'static>> Component for MCTPDriverComponent<A, M> {
     type StaticInput = (
-        &'static mut MaybeUninit<
-            MCTPTxState<'static, VirtualMuxAlarm<'static, A>, MCTPI3CBinding<'static>>,
-        >,
+        &'static mut MaybeUninit<MCTPTxState<'static, VirtualMuxAlarm<'static, A>, M>>,
         &'static mut MaybeUninit<MCTPRxState<'static>>,
-        &'static mut MaybeUninit<[u8; MCTP_MAX_MESSAGE_SIZE]>,
+        &'static mut MaybeUninit<[[u8; MCTP_MAX_MESSAGE_SIZE]; MCTP_MAX_REASSEMBLY_CONTEXTS]>,
         &'static mut MaybeUninit<[u8; MCTP_MAX_MESSAGE_SIZE]>,
         &'static mut MaybeUninit<MCTPDriver<'static>>,
         &'static mut MaybeUninit<[u8; MCTP_MAX_MESSAGE_SIZE]>,
@@ -111,18 +112,22 @@ impl<A: Alarm<'static>> Component for MCTPDriverComponent<A> {
     fn finalize(self, static_buffer: Self::StaticInput) -> Self::Output {
         let grant_cap = kernel::create_capability!(capabilities::MemoryAllocationCapability);
 
-        let rx_msg_buf = static_buffer.2.write([0; MCTP_MAX_MESSAGE_SIZE]);
+        let rx_msg_bufs = static_buffer
+            .2
+            .write([[0; MCTP_MAX_MESSAGE_SIZE]; MCTP_MAX_REASSEMBLY_CONTEXTS]);
         let tx_msg_buf = static_buffer.3.write([0; MCTP_MAX_MESSAGE_SIZE]);
         let buffered_rx_msg = static_buffer.5.write([0; MCTP_MAX_MESSAGE_SIZE]);
 
         let tx_state = static_buffer.0.write(MCTPTxState::new(self.mux_mctp));
 
-        let rx_state = static_buffer
-            .1
-            .write(MCTPRxState::new(rx_msg_buf, self.msg_type));
+        let rx_state = static_buffer.1.write(MCTPRxState::new(
+            rx_msg_bufs.each_mut().map(|buf| buf.as_mut_slice()),
+            self.msg_type,
+        ));
 
         let mctp_driver = static_buffer.4.write(MCTPDriver::new(
             tx_state,
+            self.mux_mctp,
             self.board_kernel.create_grant(self.driver_num, &grant_cap),
             self.msg_type,
             MCTP_MAX_MESSAGE_SIZE,
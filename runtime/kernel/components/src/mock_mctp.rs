@@ -4,9 +4,9 @@ use capsules_core::virtualizers::virtual_alarm::VirtualMuxAlarm;
 use capsules_runtime::mctp::base_protocol::MessageType;
 use capsules_runtime::mctp::driver::MCTP_MAX_MESSAGE_SIZE;
 use capsules_runtime::mctp::mux::MuxMCTPDriver;
-use capsules_runtime::mctp::recv::MCTPRxState;
+use capsules_runtime::mctp::recv::{MCTPRxState, MCTP_MAX_REASSEMBLY_CONTEXTS};
 use capsules_runtime::mctp::send::{MCTPSender, MCTPTxState};
-use capsules_runtime::mctp::transport_binding::MCTPI3CBinding;
+use capsules_runtime::mctp::transport_binding::{MCTPI3CBinding, MCTPTransportBinding};
 use capsules_runtime::test::mctp::MockMctp;
 use core::mem::MaybeUninit;
 use kernel::component::Component;
@@ -15,62 +15,64 @@ use kernel::utilities::leasable_buffer::SubSliceMut;
 
 #[macro_export]
 macro_rules! mock_mctp_component_static {
-    ($A:ty  $(,)?) => {{
+    ($A:ty $(,)?) => {{
+        $crate::mock_mctp_component_static!(
+            $A,
+            capsules_runtime::mctp::transport_binding::MCTPI3CBinding<'static>
+        )
+    }};
+    ($A:ty, $M:ty $(,)?) => {{
         use capsules_runtime::mctp::base_protocol::MessageType;
         use capsules_runtime::mctp::driver::MCTP_MAX_MESSAGE_SIZE;
-        use capsules_runtime::mctp::recv::MCTPRxState;
+        use capsules_runtime::mctp::recv::{MCTPRxState, MCTP_MAX_REASSEMBLY_CONTEXTS};
         use capsules_runtime::mctp::send::MCTPTxState;
-        use capsules_runtime::mctp::transport_binding::MCTPI3CBinding;
         use capsules_runtime::test::mctp::MockMctp;
 
-        let tx_state = kernel::static_buf!(
-            MCTPTxState<'static, VirtualMuxAlarm<'static, $A>, MCTPI3CBinding<'static>>
-        );
+        let tx_state = kernel::static_buf!(MCTPTxState<'static, VirtualMuxAlarm<'static, $A>, $M>);
         let rx_state = kernel::static_buf!(MCTPRxState<'static>);
-        let rx_msg_buf = kernel::static_buf!([u8; MCTP_MAX_MESSAGE_SIZE]);
+        let rx_msg_bufs =
+            kernel::static_buf!([[u8; MCTP_MAX_MESSAGE_SIZE]; MCTP_MAX_REASSEMBLY_CONTEXTS]);
         let tx_msg_buf = kernel::static_buf!([u8; MCTP_MAX_MESSAGE_SIZE]);
         let mock_mctp = kernel::static_buf!(MockMctp<'static>);
-        (tx_state, rx_state, rx_msg_buf, tx_msg_buf, mock_mctp)
+        (tx_state, rx_state, rx_msg_bufs, tx_msg_buf, mock_mctp)
     }};
 }
 
-pub struct MockMctpComponent<A: Alarm<'static> + 'static> {
-    mux_mctp: &'static MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, MCTPI3CBinding<'static>>,
+pub struct MockMctpComponent<
+    A: Alarm<'static> + 'static,
+    M: MCTPTransportBinding<'static> + 'static = MCTPI3CBinding<'static>,
+> {
+    mux_mctp: &'static MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, M>,
 }
 
-impl<A: Alarm<'static>> MockMctpComponent<A> {
-    pub fn new(
-        mux_mctp: &'static MuxMCTPDriver<
-            'static,
-            VirtualMuxAlarm<'static, A>,
-            MCTPI3CBinding<'static>,
-        >,
-    ) -> Self {
+impl<A: Alarm<'static>, M: MCTPTransportBinding<'static>> MockMctpComponent<A, M> {
+    pub fn new(mux_mctp: &'static MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, M>) -> Self {
         Self { mux_mctp }
     }
 }
 
-impl<A: Alarm<'static>> Component for MockMctpComponent<A> {
+impl<A: Alarm<'static>, M: MCTPTransportBinding<'static>> Component for MockMctpComponent<A, M> {
     type StaticInput = (
-        &'static mut MaybeUninit<
-            MCTPTxState<'static, VirtualMuxAlarm<'static, A>, MCTPI3CBinding<'static>>,
-        >,
+        &'static mut MaybeUninit<MCTPTxState<'static, VirtualMuxAlarm<'static, A>, M>>,
         &'static mut MaybeUninit<MCTPRxState<'static>>,
-        &'static mut MaybeUninit<[u8; MCTP_MAX_MESSAGE_SIZE]>,
+        &'static mut MaybeUninit<[[u8; MCTP_MAX_MESSAGE_SIZE]; MCTP_MAX_REASSEMBLY_CONTEXTS]>,
         &'static mut MaybeUninit<[u8; MCTP_MAX_MESSAGE_SIZE]>,
         &'static mut MaybeUninit<MockMctp<'static>>,
     );
     type Output = &'static MockMctp<'static>;
 
     fn finalize(self, static_buffer: Self::StaticInput) -> Self::Output {
-        let rx_msg_buf = static_buffer.2.write([0; MCTP_MAX_MESSAGE_SIZE]);
+        let rx_msg_bufs = static_buffer
+            .2
+            .write([[0; MCTP_MAX_MESSAGE_SIZE]; MCTP_MAX_REASSEMBLY_CONTEXTS]);
         let tx_msg_buf = static_buffer.3.write([0; MCTP_MAX_MESSAGE_SIZE]);
 
         let tx_state = static_buffer.0.write(MCTPTxState::new(self.mux_mctp));
 
-        let rx_state = static_buffer
-            .1
-            .write(MCTPRxState::new(rx_msg_buf, MessageType::TestMsgType));
+        let rx_state = static_buffer.1.write(MCTPRxState::new(
+            rx_msg_bufs.each_mut().map(|buf| buf.as_mut_slice()),
+            MessageType::TestMsgType,
+        ));
 
         let mock_mctp = static_buffer.4.write(MockMctp::new(
             tx_state,
@@ -2,7 +2,9 @@
 
 //! Component for initializing the MCTP mux.
 //!
-//! This provides MCTPMuxComponent, which initializes the MCTP mux.
+//! This provides MCTPMuxComponent, which initializes the MCTP mux over I3C,
+//! MCTPPcieVdmMuxComponent, which initializes the MCTP mux over PCIe VDM,
+//! and MCTPSerialMuxComponent, which initializes the MCTP mux over a UART.
 //!
 //! Usage
 //! -----
@@ -14,17 +16,32 @@
 //!    i3c,
 //!    mux_alarm)
 //! .finalize(mctp_mux_component_static!(InternalTimers, MCTPI3CBinding));
+//!
+//! let mux_mctp = mcu_components::mux_mctp::MCTPPcieVdmMuxComponent::new(
+//!    pcie_vdm,
+//!    mux_alarm)
+//! .finalize(mctp_pcie_vdm_mux_component_static!(InternalTimers));
+//!
+//! let mux_mctp = mcu_components::mux_mctp::MCTPSerialMuxComponent::new(
+//!    uart_mux,
+//!    mux_alarm)
+//! .finalize(mctp_serial_mux_component_static!(InternalTimers));
 //! ```
 //!
 
 use capsules_core::virtualizers::virtual_alarm::{MuxAlarm, VirtualMuxAlarm};
+use capsules_core::virtualizers::virtual_uart::{MuxUart, UartDevice};
 use capsules_runtime::mctp::mux::MuxMCTPDriver;
-use capsules_runtime::mctp::transport_binding::{MCTPI3CBinding, MCTPTransportBinding};
+use capsules_runtime::mctp::transport_binding::{
+    MCTPI3CBinding, MCTPPcieVdmBinding, MCTPSerialBinding, MCTPTransportBinding,
+    MCTP_PCIE_VDM_MAXMTU, MCTP_SERIAL_MAXMTU, MCTP_SERIAL_MAX_FRAME_SIZE,
+};
 use core::mem::MaybeUninit;
 use i3c_driver::core::MAX_READ_WRITE_SIZE;
 use kernel::component::Component;
 use kernel::deferred_call::DeferredCallClient;
 use kernel::hil::time::Alarm;
+use kernel::hil::uart;
 
 // Setup static space for the objects.
 #[macro_export]
@@ -51,6 +68,63 @@ macro_rules! mctp_mux_component_static {
     }};
 }
 
+#[macro_export]
+macro_rules! mctp_pcie_vdm_mux_component_static {
+    ($A:ty $(,)?) => {{
+        use capsules_core::virtualizers::virtual_alarm::VirtualMuxAlarm;
+        use capsules_runtime::mctp::mux::MuxMCTPDriver;
+        use capsules_runtime::mctp::transport_binding::{MCTPPcieVdmBinding, MCTP_PCIE_VDM_MAXMTU};
+
+        let alarm = kernel::static_buf!(VirtualMuxAlarm<'static, $A>);
+        let tx_buffer = kernel::static_buf!([u8; MCTP_PCIE_VDM_MAXMTU]);
+        let rx_buffer = kernel::static_buf!([u8; MCTP_PCIE_VDM_MAXMTU]);
+        let mctp_pcie_vdm_binding = kernel::static_buf!(MCTPPcieVdmBinding<'static>);
+        let mux_mctp_driver = kernel::static_buf!(
+            MuxMCTPDriver<'static, VirtualMuxAlarm<'static, $A>, MCTPPcieVdmBinding<'static>>
+        );
+        (
+            alarm,
+            tx_buffer,
+            rx_buffer,
+            mctp_pcie_vdm_binding,
+            mux_mctp_driver,
+        )
+    }};
+}
+
+#[macro_export]
+macro_rules! mctp_serial_mux_component_static {
+    ($A:ty $(,)?) => {{
+        use capsules_core::virtualizers::virtual_alarm::VirtualMuxAlarm;
+        use capsules_core::virtualizers::virtual_uart::UartDevice;
+        use capsules_runtime::mctp::mux::MuxMCTPDriver;
+        use capsules_runtime::mctp::transport_binding::{
+            MCTPSerialBinding, MCTP_SERIAL_MAXMTU, MCTP_SERIAL_MAX_FRAME_SIZE,
+        };
+
+        let alarm = kernel::static_buf!(VirtualMuxAlarm<'static, $A>);
+        let tx_buffer = kernel::static_buf!([u8; MCTP_SERIAL_MAXMTU]);
+        let rx_buffer = kernel::static_buf!([u8; MCTP_SERIAL_MAXMTU]);
+        let uart_device = kernel::static_buf!(UartDevice<'static>);
+        let frame_buffer = kernel::static_buf!([u8; MCTP_SERIAL_MAX_FRAME_SIZE]);
+        let uart_rx_buffer = kernel::static_buf!([u8; 1]);
+        let mctp_serial_binding = kernel::static_buf!(MCTPSerialBinding<'static>);
+        let mux_mctp_driver = kernel::static_buf!(
+            MuxMCTPDriver<'static, VirtualMuxAlarm<'static, $A>, MCTPSerialBinding<'static>>
+        );
+        (
+            alarm,
+            tx_buffer,
+            rx_buffer,
+            uart_device,
+            frame_buffer,
+            uart_rx_buffer,
+            mctp_serial_binding,
+            mux_mctp_driver,
+        )
+    }};
+}
+
 pub struct MCTPMuxComponent<A: Alarm<'static> + 'static> {
     i3c_target: &'static dyn i3c_driver::hil::I3CTarget<'static>,
     mux_alarm: &'static MuxAlarm<'static, A>,
@@ -106,6 +180,145 @@ impl<A: Alarm<'static>> Component for MCTPMuxComponent<A> {
 
         mctp_device.set_tx_client(mux_mctp_driver);
         mctp_device.set_rx_client(mux_mctp_driver);
+        mux_mctp_alarm.set_alarm_client(mux_mctp_driver);
+
+        mux_mctp_driver.register();
+        mux_mctp_driver
+    }
+}
+
+pub struct MCTPPcieVdmMuxComponent<A: Alarm<'static> + 'static> {
+    pcie_vdm_target: &'static dyn pcie_vdm_transport::hil::PcieVdmTarget<'static>,
+    mux_alarm: &'static MuxAlarm<'static, A>,
+}
+
+impl<A: Alarm<'static>> MCTPPcieVdmMuxComponent<A> {
+    pub fn new(
+        pcie_vdm_target: &'static dyn pcie_vdm_transport::hil::PcieVdmTarget,
+        mux_alarm: &'static MuxAlarm<'static, A>,
+    ) -> Self {
+        Self {
+            pcie_vdm_target,
+            mux_alarm,
+        }
+    }
+}
+
+impl<A: Alarm<'static>> Component for MCTPPcieVdmMuxComponent<A> {
+    type StaticInput = (
+        &'static mut MaybeUninit<VirtualMuxAlarm<'static, A>>,
+        &'static mut MaybeUninit<[u8; MCTP_PCIE_VDM_MAXMTU]>,
+        &'static mut MaybeUninit<[u8; MCTP_PCIE_VDM_MAXMTU]>,
+        &'static mut MaybeUninit<MCTPPcieVdmBinding<'static>>,
+        &'static mut MaybeUninit<
+            MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, MCTPPcieVdmBinding<'static>>,
+        >,
+    );
+    type Output =
+        &'static MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, MCTPPcieVdmBinding<'static>>;
+
+    fn finalize(self, static_buffer: Self::StaticInput) -> Self::Output {
+        let mctp_device = static_buffer
+            .3
+            .write(MCTPPcieVdmBinding::new(self.pcie_vdm_target));
+        mctp_device.setup_mctp_pcie_vdm();
+        self.pcie_vdm_target.set_tx_client(mctp_device);
+        self.pcie_vdm_target.set_rx_client(mctp_device);
+
+        let mtu = mctp_device.get_mtu_size();
+        let tx_pkt_buffer = static_buffer.1.write([0; MCTP_PCIE_VDM_MAXMTU]);
+        let rx_pkt_buffer = static_buffer.2.write([0; MCTP_PCIE_VDM_MAXMTU]);
+        let local_eid = 0; // could be a default value or 0 until dynamically assigned
+
+        let mux_mctp_alarm = static_buffer.0.write(VirtualMuxAlarm::new(self.mux_alarm));
+        mux_mctp_alarm.setup();
+
+        let mux_mctp_driver = static_buffer.4.write(MuxMCTPDriver::new(
+            mctp_device,
+            local_eid,
+            mtu,
+            tx_pkt_buffer,
+            rx_pkt_buffer,
+            mux_mctp_alarm,
+        ));
+
+        mctp_device.set_tx_client(mux_mctp_driver);
+        mctp_device.set_rx_client(mux_mctp_driver);
+        mux_mctp_alarm.set_alarm_client(mux_mctp_driver);
+
+        mux_mctp_driver.register();
+        mux_mctp_driver
+    }
+}
+
+pub struct MCTPSerialMuxComponent<A: Alarm<'static> + 'static> {
+    uart_mux: &'static MuxUart<'static>,
+    mux_alarm: &'static MuxAlarm<'static, A>,
+}
+
+impl<A: Alarm<'static>> MCTPSerialMuxComponent<A> {
+    pub fn new(
+        uart_mux: &'static MuxUart<'static>,
+        mux_alarm: &'static MuxAlarm<'static, A>,
+    ) -> Self {
+        Self {
+            uart_mux,
+            mux_alarm,
+        }
+    }
+}
+
+impl<A: Alarm<'static>> Component for MCTPSerialMuxComponent<A> {
+    type StaticInput = (
+        &'static mut MaybeUninit<VirtualMuxAlarm<'static, A>>,
+        &'static mut MaybeUninit<[u8; MCTP_SERIAL_MAXMTU]>,
+        &'static mut MaybeUninit<[u8; MCTP_SERIAL_MAXMTU]>,
+        &'static mut MaybeUninit<UartDevice<'static>>,
+        &'static mut MaybeUninit<[u8; MCTP_SERIAL_MAX_FRAME_SIZE]>,
+        &'static mut MaybeUninit<[u8; 1]>,
+        &'static mut MaybeUninit<MCTPSerialBinding<'static>>,
+        &'static mut MaybeUninit<
+            MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, MCTPSerialBinding<'static>>,
+        >,
+    );
+    type Output =
+        &'static MuxMCTPDriver<'static, VirtualMuxAlarm<'static, A>, MCTPSerialBinding<'static>>;
+
+    fn finalize(self, static_buffer: Self::StaticInput) -> Self::Output {
+        // The UART is shared with the console through the UART mux
+        let uart_device = static_buffer.3.write(UartDevice::new(self.uart_mux, true));
+        uart_device.setup();
+
+        let frame_buffer = static_buffer.4.write([0; MCTP_SERIAL_MAX_FRAME_SIZE]);
+        let uart_rx_buffer = static_buffer.5.write([0; 1]);
+        let mctp_device = static_buffer.6.write(MCTPSerialBinding::new(
+            uart_device,
+            frame_buffer,
+            uart_rx_buffer,
+        ));
+        uart::Transmit::set_transmit_client(uart_device, mctp_device);
+        uart::Receive::set_receive_client(uart_device, mctp_device);
+
+        let mtu = mctp_device.get_mtu_size();
+        let tx_pkt_buffer = static_buffer.1.write([0; MCTP_SERIAL_MAXMTU]);
+        let rx_pkt_buffer = static_buffer.2.write([0; MCTP_SERIAL_MAXMTU]);
+        let local_eid = 0; // could be a default value or 0 until dynamically assigned
+
+        let mux_mctp_alarm = static_buffer.0.write(VirtualMuxAlarm::new(self.mux_alarm));
+        mux_mctp_alarm.setup();
+
+        let mux_mctp_driver = static_buffer.7.write(MuxMCTPDriver::new(
+            mctp_device,
+            local_eid,
+            mtu,
+            tx_pkt_buffer,
+            rx_pkt_buffer,
+            mux_mctp_alarm,
+        ));
+
+        mctp_device.set_tx_client(mux_mctp_driver);
+        mctp_device.set_rx_client(mux_mctp_driver);
+        mux_mctp_alarm.set_alarm_client(mux_mctp_driver);
 
         mux_mctp_driver.register();
         mux_mctp_driver
@@ -0,0 +1,67 @@
+// Licensed under the Apache-2.0 license
+
+use core::result::Result;
+use kernel::ErrorCode;
+
+/// Provides information about a PCIe VDM target device.
+pub struct PcieVdmTargetInfo {
+    /// PCI ID (bus/device/function) of the device, used as the Requester ID
+    /// of the transmitted VDMs. Absent until it has been assigned by the host.
+    pub pci_id: Option<u16>,
+    /// Maximum length of a VDM (TLP header and data payload) that can be
+    /// received or transmitted.
+    pub max_vdm_len: usize,
+}
+
+pub trait PcieVdmTxClient {
+    /// Called when the VDM has been transmitted.
+    ///
+    /// # Arguments
+    /// * `tx_buffer` - The buffer passed to `transmit()`
+    /// * `result` - Result indicating success or failure of the transmission
+    fn send_done(&self, tx_buffer: &'static mut [u8], result: Result<(), ErrorCode>);
+}
+
+pub trait PcieVdmRxClient {
+    /// Called when a VDM has been received.
+    ///
+    /// # Arguments
+    /// * `rx_buffer` - Buffer containing the received VDM, starting with the TLP header
+    /// * `len` - Length of the received VDM in bytes, a multiple of 4
+    fn receive(&self, rx_buffer: &'static mut [u8], len: usize);
+
+    /// Called when a VDM has arrived and the driver needs a buffer to receive it.
+    /// The client should call set_rx_buffer() to set the buffer.
+    fn write_expected(&self);
+}
+
+pub trait PcieVdmTarget<'a> {
+    /// Set the client that will be called when the VDM is transmitted.
+    fn set_tx_client(&self, client: &'a dyn PcieVdmTxClient);
+
+    /// Set the client that will be called when a VDM is received.
+    fn set_rx_client(&self, client: &'a dyn PcieVdmRxClient);
+
+    /// Set the buffer that will be used for receiving VDMs.
+    fn set_rx_buffer(&self, rx_buf: &'static mut [u8]);
+
+    /// Transmit a VDM. The buffer holds the TLP header followed by the data payload.
+    ///
+    /// # Arguments
+    /// * `tx_buf` - Buffer containing the VDM to be transmitted
+    /// * `len` - Length of the VDM in bytes, a multiple of 4
+    fn transmit(
+        &self,
+        tx_buf: &'static mut [u8],
+        len: usize,
+    ) -> Result<(), (ErrorCode, &'static mut [u8])>;
+
+    /// Enable the PCIe VDM target device.
+    fn enable(&self);
+
+    /// Disable the PCIe VDM target device.
+    fn disable(&self);
+
+    /// Returns information about this PCIe VDM target device.
+    fn get_device_info(&self) -> PcieVdmTargetInfo;
+}
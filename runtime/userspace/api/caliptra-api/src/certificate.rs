@@ -1,14 +1,17 @@
 // Licensed under the Apache-2.0 license
 
+use crate::crypto::asym::MLDSA87_SIGNATURE_SIZE;
+use crate::crypto::hash::SHA384_HASH_SIZE;
 use crate::error::{CaliptraApiError, CaliptraApiResult};
 use crate::mailbox_api::{
     execute_mailbox_cmd, CertificateChainResp, CertifyEcKeyResp, DpeEcResp, DpeResponse,
-    MAX_DPE_RESP_DATA_SIZE,
+    Mldsa87CertResp, MAX_DPE_RESP_DATA_SIZE,
 };
 use caliptra_api::mailbox::{
     CommandId, GetFmcAliasEcc384CertReq, GetIdevCsrReq, GetIdevCsrResp, GetLdevCertResp,
     GetLdevEcc384CertReq, GetRtAliasEcc384CertReq, InvokeDpeReq, MailboxReqHeader,
-    MailboxRespHeader, PopulateIdevEcc384CertReq, Request,
+    MailboxRespHeader, PopulateIdevEcc384CertReq, Request, SignWithRtAliasMldsa87Req,
+    SignWithRtAliasMldsa87Resp,
 };
 use dpe::commands::{
     CertifyKeyCmd, CertifyKeyFlags, Command, CommandHdr, GetCertificateChainCmd, SignCmd, SignFlags,
@@ -21,11 +24,13 @@ use zerocopy::{FromBytes, FromZeros, IntoBytes};
 
 pub const IDEV_ECC_CSR_MAX_SIZE: usize = GetIdevCsrResp::DATA_MAX_SIZE;
 pub const MAX_ECC_CERT_SIZE: usize = GetLdevCertResp::DATA_MAX_SIZE;
+pub use crate::mailbox_api::MAX_MLDSA87_CERT_SIZE;
 pub const MAX_CERT_CHUNK_SIZE: usize = 1024;
 pub const KEY_LABEL_SIZE: usize = DPE_PROFILE.get_hash_size();
 
 pub enum CertType {
     Ecc,
+    Mldsa,
 }
 
 pub struct CertContext {
@@ -131,12 +136,59 @@ impl CertContext {
         Ok(resp.data_size as usize)
     }
 
+    pub async fn get_ldev_mldsa87_cert(&mut self, cert: &mut [u8]) -> CaliptraApiResult<usize> {
+        self.get_mldsa87_cert(CommandId::GET_LDEV_MLDSA87_CERT.into(), cert)
+            .await
+    }
+
+    pub async fn get_fmc_alias_mldsa87_cert(
+        &mut self,
+        cert: &mut [u8],
+    ) -> CaliptraApiResult<usize> {
+        self.get_mldsa87_cert(CommandId::GET_FMC_ALIAS_MLDSA87_CERT.into(), cert)
+            .await
+    }
+
+    pub async fn get_rt_alias_mldsa87_cert(&mut self, cert: &mut [u8]) -> CaliptraApiResult<usize> {
+        self.get_mldsa87_cert(CommandId::GET_RT_ALIAS_MLDSA87_CERT.into(), cert)
+            .await
+    }
+
     pub async fn certify_key(
         &mut self,
         cert: &mut [u8],
         label: Option<&[u8; KEY_LABEL_SIZE]>,
         derived_pubkey_x: Option<&mut [u8]>,
         derived_pubkey_y: Option<&mut [u8]>,
+    ) -> CaliptraApiResult<usize> {
+        self.certify_key_with_format(
+            CertifyKeyCmd::FORMAT_X509,
+            cert,
+            label,
+            derived_pubkey_x,
+            derived_pubkey_y,
+        )
+        .await
+    }
+
+    /// Generates a CSR for the DPE key derived with the given label.
+    /// The CSR is returned as a CMS SignedData structure wrapping the PKCS#10 request.
+    pub async fn certify_key_csr(
+        &mut self,
+        csr: &mut [u8],
+        label: Option<&[u8; KEY_LABEL_SIZE]>,
+    ) -> CaliptraApiResult<usize> {
+        self.certify_key_with_format(CertifyKeyCmd::FORMAT_CSR, csr, label, None, None)
+            .await
+    }
+
+    async fn certify_key_with_format(
+        &mut self,
+        format: u32,
+        cert: &mut [u8],
+        label: Option<&[u8; KEY_LABEL_SIZE]>,
+        derived_pubkey_x: Option<&mut [u8]>,
+        derived_pubkey_y: Option<&mut [u8]>,
     ) -> CaliptraApiResult<usize> {
         if let Some(ref x) = derived_pubkey_x {
             if x.len() != DPE_PROFILE.get_tci_size() {
@@ -152,7 +204,7 @@ impl CertContext {
         let mut dpe_cmd = CertifyKeyCmd {
             handle: ContextHandle::default(),
             flags: CertifyKeyFlags::empty(),
-            format: CertifyKeyCmd::FORMAT_X509,
+            format,
             label: [0; KEY_LABEL_SIZE],
         };
 
@@ -223,6 +275,36 @@ impl CertContext {
         }
     }
 
+    /// Signs the digest with the ML-DSA-87 RT alias key, whose certificate is
+    /// returned by `get_rt_alias_mldsa87_cert`.
+    pub async fn sign_mldsa87(
+        &mut self,
+        digest: &[u8; SHA384_HASH_SIZE],
+        signature: &mut [u8],
+    ) -> CaliptraApiResult<usize> {
+        if signature.len() < MLDSA87_SIGNATURE_SIZE {
+            return Err(CaliptraApiError::InvalidArgument("Invalid signature size"));
+        }
+
+        let mut req = SignWithRtAliasMldsa87Req {
+            hdr: MailboxReqHeader::default(),
+            digest: *digest,
+        };
+        let mut resp = SignWithRtAliasMldsa87Resp::new_zeroed();
+        execute_mailbox_cmd(
+            &self.mbox,
+            SignWithRtAliasMldsa87Req::ID.0,
+            req.as_mut_bytes(),
+            resp.as_mut_bytes(),
+        )
+        .await?;
+
+        // The signature is padded to a multiple of 4 bytes in the response
+        signature[..MLDSA87_SIGNATURE_SIZE]
+            .copy_from_slice(&resp.signature[..MLDSA87_SIGNATURE_SIZE]);
+        Ok(MLDSA87_SIGNATURE_SIZE)
+    }
+
     pub fn max_cert_chain_chunk_size(&mut self) -> usize {
         MAX_CERT_CHUNK_SIZE
     }
@@ -277,6 +359,19 @@ impl CertContext {
         Ok(resp)
     }
 
+    async fn get_mldsa87_cert(&mut self, cmd: u32, cert: &mut [u8]) -> CaliptraApiResult<usize> {
+        let mut req = MailboxReqHeader::default();
+        let mut resp = Mldsa87CertResp::new_zeroed();
+        execute_mailbox_cmd(&self.mbox, cmd, req.as_mut_bytes(), resp.as_mut_bytes()).await?;
+
+        let cert_len = resp.data_size as usize;
+        if cert_len > resp.data.len() || cert_len > cert.len() {
+            return Err(CaliptraApiError::InvalidResponse);
+        }
+        cert[..cert_len].copy_from_slice(&resp.data[..cert_len]);
+        Ok(cert_len)
+    }
+
     async fn execute_dpe_cmd(
         &mut self,
         dpe_cmd: &mut Command<'_>,
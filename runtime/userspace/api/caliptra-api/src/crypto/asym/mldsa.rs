@@ -0,0 +1,53 @@
+// Licensed under the Apache-2.0 license
+
+use crate::crypto::asym::{MLDSA87_PUBLIC_KEY_SIZE, MLDSA87_SIGNATURE_SIZE};
+use crate::error::{CaliptraApiError, CaliptraApiResult};
+use crate::mailbox_api::{execute_mailbox_cmd, Mldsa87VerifyReq, MAX_MLDSA87_VERIFY_MSG_SIZE};
+use caliptra_api::mailbox::{CommandId, MailboxRespHeader};
+use core::mem::size_of;
+use libsyscall_caliptra::mailbox::Mailbox;
+use zerocopy::{FromZeros, IntoBytes};
+
+pub struct Mldsa;
+
+impl Mldsa {
+    /// Verifies a pure ML-DSA-87 signature, with an empty context, over the message.
+    ///
+    /// # Arguments
+    /// * `pub_key` - The ML-DSA-87 public key.
+    /// * `signature` - The ML-DSA-87 signature.
+    /// * `message` - The signed message. Must not exceed `MAX_MLDSA87_VERIFY_MSG_SIZE`.
+    pub async fn mldsa87_verify(
+        pub_key: &[u8; MLDSA87_PUBLIC_KEY_SIZE],
+        signature: &[u8; MLDSA87_SIGNATURE_SIZE],
+        message: &[u8],
+    ) -> CaliptraApiResult<()> {
+        if message.len() > MAX_MLDSA87_VERIFY_MSG_SIZE {
+            Err(CaliptraApiError::InvalidArgument(
+                "Message size exceeds maximum limit",
+            ))?;
+        }
+
+        let mailbox = Mailbox::new();
+
+        let mut req = Mldsa87VerifyReq::new_zeroed();
+        req.pub_key.copy_from_slice(pub_key);
+        req.signature[..MLDSA87_SIGNATURE_SIZE].copy_from_slice(signature);
+        req.message_size = message.len() as u32;
+        req.message[..message.len()].copy_from_slice(message);
+
+        // Only send the message bytes in use, rounded up to a DWORD boundary
+        let req_len = size_of::<Mldsa87VerifyReq>() - MAX_MLDSA87_VERIFY_MSG_SIZE
+            + message.len().next_multiple_of(4);
+
+        let mut rsp = MailboxRespHeader::default();
+        execute_mailbox_cmd(
+            &mailbox,
+            CommandId::MLDSA87_SIGNATURE_VERIFY.into(),
+            &mut req.as_mut_bytes()[..req_len],
+            rsp.as_mut_bytes(),
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -1,27 +1,31 @@
 // Licensed under the Apache-2.0 license
 pub mod ecdh;
 pub mod ecdsa;
+pub mod mldsa;
 
 pub const ECC_P384_SIGNATURE_SIZE: usize = 96;
 pub const ECC_P384_PARAM_X_SIZE: usize = 48;
 pub const ECC_P384_PARAM_Y_SIZE: usize = 48;
+pub const MLDSA87_SIGNATURE_SIZE: usize = 4627;
+pub const MLDSA87_PUBLIC_KEY_SIZE: usize = 2592;
 
 pub enum KeyExchScheme {
     Ecdh,
 }
 
 // Type of Asymmetric Algorithm supported.
-// Currently only ECC P384 is supported.
-// This can be extended to support PQC algorithms in the future.
+// ECC P384 and the post-quantum ML-DSA-87 are supported.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AsymAlgo {
     EccP384,
+    MlDsa87,
 }
 
 impl AsymAlgo {
     pub fn signature_size(&self) -> usize {
         match self {
             AsymAlgo::EccP384 => ECC_P384_SIGNATURE_SIZE,
+            AsymAlgo::MlDsa87 => MLDSA87_SIGNATURE_SIZE,
         }
     }
 }
@@ -0,0 +1,109 @@
+// Licensed under the Apache-2.0 license
+
+use crate::error::{CaliptraApiError, CaliptraApiResult};
+use crate::mailbox_api::execute_mailbox_cmd;
+use caliptra_api::mailbox::{
+    CommandId, MailboxReqHeader, MailboxRespHeader, ProductionAuthDebugUnlockChallenge,
+    ProductionAuthDebugUnlockReq, ProductionAuthDebugUnlockToken,
+};
+use core::mem::size_of;
+use libsyscall_caliptra::mailbox::Mailbox;
+use zerocopy::{FromZeros, IntoBytes};
+
+/// Size of the signed token, excluding the mailbox request header.
+pub const DEBUG_UNLOCK_TOKEN_SIZE: usize =
+    size_of::<ProductionAuthDebugUnlockToken>() - size_of::<MailboxReqHeader>();
+
+// Length in DWORDs of the request, excluding the mailbox request header
+const DEBUG_UNLOCK_REQ_LENGTH: u32 =
+    ((size_of::<ProductionAuthDebugUnlockReq>() - size_of::<MailboxReqHeader>()) / 4) as u32;
+
+pub struct DebugUnlock {
+    mbox: Mailbox,
+}
+
+impl Default for DebugUnlock {
+    fn default() -> Self {
+        DebugUnlock::new()
+    }
+}
+
+impl DebugUnlock {
+    pub fn new() -> Self {
+        DebugUnlock {
+            mbox: Mailbox::new(),
+        }
+    }
+
+    /// Requests production debug unlock through the debug service register. Caliptra ROM
+    /// only handles the request on boot, so the device must be reset afterwards.
+    pub fn request_unlock(&self) -> CaliptraApiResult<()> {
+        self.mbox
+            .request_prod_debug_unlock()
+            .map_err(CaliptraApiError::Syscall)
+    }
+
+    /// Returns true while Caliptra ROM waits for the unlock challenge request and token.
+    pub fn in_progress(&self) -> CaliptraApiResult<bool> {
+        self.mbox
+            .prod_debug_unlock_in_progress()
+            .map_err(CaliptraApiError::Syscall)
+    }
+
+    /// Returns the debug unlock level granted by Caliptra, or 0 if debug is locked.
+    pub fn unlock_level(&self) -> CaliptraApiResult<u8> {
+        self.mbox
+            .prod_debug_unlock_level()
+            .map_err(CaliptraApiError::Syscall)
+    }
+
+    /// Requests the debug unlock challenge at the given level. Caliptra ROM only accepts
+    /// the request while the unlock is in progress.
+    ///
+    /// # Arguments
+    /// * `unlock_level` - The requested debug unlock level.
+    ///
+    /// # Returns
+    /// * `ProductionAuthDebugUnlockChallenge` - The challenge to be signed into the token.
+    pub async fn request_challenge(
+        &self,
+        unlock_level: u8,
+    ) -> CaliptraApiResult<ProductionAuthDebugUnlockChallenge> {
+        let mut req = ProductionAuthDebugUnlockReq {
+            length: DEBUG_UNLOCK_REQ_LENGTH,
+            unlock_level,
+            ..Default::default()
+        };
+        let mut resp = ProductionAuthDebugUnlockChallenge::new_zeroed();
+
+        execute_mailbox_cmd(
+            &self.mbox,
+            CommandId::PRODUCTION_AUTH_DEBUG_UNLOCK_REQ.into(),
+            req.as_mut_bytes(),
+            resp.as_mut_bytes(),
+        )
+        .await?;
+        Ok(resp)
+    }
+
+    /// Sends the signed token to Caliptra for authorization. The level that was granted is
+    /// returned by `unlock_level` once the unlock is no longer in progress.
+    ///
+    /// # Arguments
+    /// * `token` - The signed token. The mailbox request header is populated by this call.
+    pub async fn authorize_token(
+        &self,
+        token: &mut ProductionAuthDebugUnlockToken,
+    ) -> CaliptraApiResult<()> {
+        let mut resp = MailboxRespHeader::default();
+
+        execute_mailbox_cmd(
+            &self.mbox,
+            CommandId::PRODUCTION_AUTH_DEBUG_UNLOCK_TOKEN.into(),
+            token.as_mut_bytes(),
+            resp.as_mut_bytes(),
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+// Licensed under the Apache-2.0 license
+
+use crate::crypto::hash::SHA384_HASH_SIZE;
+use crate::error::{CaliptraApiError, CaliptraApiResult};
+use crate::mailbox_api::execute_mailbox_cmd;
+use caliptra_api::mailbox::{MailboxReqHeader, Request, StashMeasurementReq, StashMeasurementResp};
+use core::cell::RefCell;
+use core::mem::size_of;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex;
+use libsyscall_caliptra::mailbox::Mailbox;
+use zerocopy::IntoBytes;
+
+/// Maximum number of SoC measurements recorded in the stash log.
+pub const MAX_STASHED_MEASUREMENTS: usize = 16;
+
+/// A SoC measurement stashed in Caliptra, which extends it into PCR31.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StashedMeasurement {
+    pub metadata: [u8; 4],
+    pub measurement: [u8; SHA384_HASH_SIZE],
+    pub svn: u32,
+}
+
+struct StashLog {
+    entries: [StashedMeasurement; MAX_STASHED_MEASUREMENTS],
+    count: usize,
+}
+
+static STASH_LOG: Mutex<CriticalSectionRawMutex, RefCell<StashLog>> =
+    Mutex::new(RefCell::new(StashLog {
+        entries: [StashedMeasurement {
+            metadata: [0; 4],
+            measurement: [0; SHA384_HASH_SIZE],
+            svn: 0,
+        }; MAX_STASHED_MEASUREMENTS],
+        count: 0,
+    }));
+
+/// Log of the SoC measurements stashed in Caliptra.
+///
+/// Caliptra does not report the measurements it has been given, so they are
+/// recorded as they are stashed to make the PCR31 extensions verifiable.
+pub struct MeasurementLog;
+
+impl MeasurementLog {
+    /// Stashes a SoC measurement in Caliptra and records it in the log.
+    ///
+    /// # Arguments
+    /// * `metadata` - Metadata of the measured object, such as its firmware ID.
+    /// * `measurement` - SHA-384 digest of the measured object.
+    /// * `context` - Context of the measurement.
+    /// * `svn` - Security version number of the measured object.
+    pub async fn stash_measurement(
+        metadata: [u8; 4],
+        measurement: &[u8; SHA384_HASH_SIZE],
+        context: &[u8; SHA384_HASH_SIZE],
+        svn: u32,
+    ) -> CaliptraApiResult<()> {
+        let entry = StashedMeasurement {
+            metadata,
+            measurement: *measurement,
+            svn,
+        };
+        let log_full = STASH_LOG.lock(|log| log.borrow().count >= MAX_STASHED_MEASUREMENTS);
+        if log_full {
+            Err(CaliptraApiError::InvalidOperation("Measurement log full"))?;
+        }
+
+        let mailbox = Mailbox::new();
+        let mut req = StashMeasurementReq {
+            hdr: MailboxReqHeader::default(),
+            metadata,
+            measurement: *measurement,
+            context: *context,
+            svn,
+        };
+        let mut rsp_bytes = [0u8; size_of::<StashMeasurementResp>()];
+        execute_mailbox_cmd(
+            &mailbox,
+            StashMeasurementReq::ID.0,
+            req.as_mut_bytes(),
+            &mut rsp_bytes,
+        )
+        .await?;
+
+        STASH_LOG.lock(|log| {
+            let mut log = log.borrow_mut();
+            let count = log.count;
+            log.entries[count] = entry;
+            log.count += 1;
+        });
+        Ok(())
+    }
+
+    /// Returns the number of stashed measurements.
+    pub fn count() -> usize {
+        STASH_LOG.lock(|log| log.borrow().count)
+    }
+
+    /// Returns the stashed measurement at the given index, in the order they were stashed.
+    pub fn entry(index: usize) -> Option<StashedMeasurement> {
+        STASH_LOG.lock(|log| {
+            let log = log.borrow();
+            log.entries[..log.count].get(index).copied()
+        })
+    }
+}
@@ -1,5 +1,6 @@
 // Licensed under the Apache-2.0 license
 
 pub mod device_state;
+pub mod measurement_log;
 pub mod ocp_eat_claims;
 pub mod pcr_quote;
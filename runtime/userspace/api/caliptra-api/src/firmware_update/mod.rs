@@ -6,7 +6,9 @@ mod pldm_context;
 mod pldm_fdops;
 
 use crate::firmware_update::pldm_client::pldm_total_component_size;
-use crate::firmware_update::pldm_context::State;
+use crate::firmware_update::pldm_context::{
+    metadata_offset, package_data_offset, State, UpdateMetadata, DOWNLOAD_CTX, PLDM_STATE,
+};
 use crate::mailbox_api::MAX_CRYPTO_MBOX_DATA_SIZE;
 use alloc::boxed::Box;
 use async_trait::async_trait;
@@ -26,6 +28,7 @@ use libsyscall_caliptra::dma::AXIAddr;
 use libsyscall_caliptra::dma::{DMAMapping, DMASource, DMATransaction, DMA as DMASyscall};
 use libsyscall_caliptra::mailbox::Mailbox;
 use libsyscall_caliptra::mailbox::{MailboxError, PayloadStream};
+use libsyscall_caliptra::mci::{mci_reg::RESET_REASON, Mci};
 use libtock_platform::ErrorCode;
 use libtockasync::TockExecutor;
 use pldm_common::message::firmware_update::apply_complete::ApplyResult;
@@ -33,6 +36,7 @@ use pldm_common::message::firmware_update::get_fw_params::FirmwareParameters;
 use pldm_common::message::firmware_update::verify_complete::VerifyResult;
 use pldm_common::protocol::firmware_update::Descriptor;
 use pldm_lib::daemon::PldmService;
+use pldm_lib::firmware_device::fd_ops::PackageDataPolicy;
 use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 use core::fmt::Write;
@@ -43,6 +47,8 @@ use libtock_console::Console;
 use crate::crypto::hash::{HashAlgoType, HashContext};
 
 const MAX_DMA_TRANSFER_SIZE: usize = 128;
+const RESET_REASON_FW_HITLESS_UPD_RESET_MASK: u32 = 0x1;
+const RESET_REASON_FW_BOOT_UPD_RESET_MASK: u32 = 0x2;
 
 pub struct FirmwareUpdater<'a, D: DMAMapping> {
     staging_memory: &'static dyn StagingMemory,
@@ -56,6 +62,9 @@ pub struct FirmwareUpdater<'a, D: DMAMapping> {
 pub struct PldmFirmwareDeviceParams {
     pub descriptors: &'static [Descriptor],
     pub fw_params: &'static FirmwareParameters,
+    /// Requirements on the packages the device accepts. When signed package data is required,
+    /// the package data is the SoC manifest and is verified with Caliptra before the download.
+    pub package_data_policy: PackageDataPolicy,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -81,11 +90,28 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
     }
 
     pub async fn start(&mut self) -> Result<(), ErrorCode> {
+        // The image left pending by an update is activated by the first update started after
+        // the reset, before the PLDM service is running
+        let first_start = PLDM_STATE.lock(|state| *state.borrow() == State::NotRunning);
+        self.load_metadata().await?;
+        if first_start && self.take_pending_activation().await? {
+            match self.activate_pending_image().await {
+                // The caller resets the MCU to boot the activated image
+                Ok(()) => return Ok(()),
+                Err(_) => writeln!(
+                    Console::<DefaultSyscalls>::writer(),
+                    "[FW Upd] Failed to activate the pending image"
+                )
+                .unwrap(),
+            }
+        }
+
         // Download firmware image to staging memory
         pldm_client::initialize_pldm(
             self.spawner,
             self.params.descriptors,
             self.params.fw_params,
+            self.params.package_data_policy,
             self.staging_memory,
         )
         .await?;
@@ -117,6 +143,13 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
         pldm_client::pldm_set_apply_result(ApplyResult::ApplySuccess);
         pldm_client::pldm_wait(State::Activate).await?;
 
+        let activation_deferred = self.is_activation_deferred();
+        self.save_metadata(img_len, activation_deferred).await?;
+        if activation_deferred {
+            // The image stays pending in the staging memory until the next reset, so the MCU
+            // must not be reset here
+            return Ok(());
+        }
         self.set_auth_manifest().await?;
 
         // Update MCU and reboot
@@ -125,6 +158,14 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
         Ok(())
     }
 
+    /// Returns true if the update was applied but the UA did not request self-contained
+    /// activation. In that case the caller must not reset the MCU: the images are activated at
+    /// the next reset, and the PLDM service accepts a new update on the next call to `start`.
+    pub fn is_activation_deferred(&self) -> bool {
+        PLDM_STATE.lock(|state| *state.borrow() == State::Activate)
+            && !DOWNLOAD_CTX.lock(|ctx| ctx.borrow().self_contained_activation)
+    }
+
     pub async fn get_image_toc(
         &self,
         num_images: usize,
@@ -169,6 +210,108 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
         Ok(image_header)
     }
 
+    /// Loads the device metadata persisted in the staging memory. If it was lost after a reset
+    /// for a firmware update, the FD retrieves it from the UA with GetMetaData.
+    async fn load_metadata(&self) -> Result<(), ErrorCode> {
+        let offset = metadata_offset(self.staging_memory).ok_or(ErrorCode::Size)?;
+        let mut metadata = UpdateMetadata::new();
+        self.staging_memory
+            .read(offset, metadata.as_mut_bytes())
+            .await?;
+        let mut restore_pending = false;
+        if !metadata.is_valid() {
+            let reset_reason = Mci::<DefaultSyscalls>::new().read(RESET_REASON, 0)?;
+            restore_pending = reset_reason
+                & (RESET_REASON_FW_HITLESS_UPD_RESET_MASK | RESET_REASON_FW_BOOT_UPD_RESET_MASK)
+                != 0;
+            metadata = UpdateMetadata::new();
+        }
+        DOWNLOAD_CTX.lock(|ctx| {
+            let mut ctx = ctx.borrow_mut();
+            ctx.metadata = metadata;
+            ctx.metadata_restore_pending = restore_pending;
+        });
+        Ok(())
+    }
+
+    /// Records the update being activated in the device metadata.
+    async fn save_metadata(
+        &self,
+        image_size: usize,
+        activation_pending: bool,
+    ) -> Result<(), ErrorCode> {
+        let metadata = DOWNLOAD_CTX.lock(|ctx| {
+            let mut ctx = ctx.borrow_mut();
+            ctx.metadata.update_count = ctx.metadata.update_count.wrapping_add(1);
+            ctx.metadata.image_size = image_size as u32;
+            ctx.metadata.package_data_size = ctx.package_data_len as u32;
+            ctx.metadata.activation_pending = activation_pending as u32;
+            ctx.metadata.populate_checksum();
+            ctx.metadata
+        });
+        self.write_metadata(&metadata).await
+    }
+
+    async fn write_metadata(&self, metadata: &UpdateMetadata) -> Result<(), ErrorCode> {
+        let offset = metadata_offset(self.staging_memory).ok_or(ErrorCode::Size)?;
+        self.staging_memory.write(offset, metadata.as_bytes()).await
+    }
+
+    /// Returns true if an update was applied without being activated before the reset. The
+    /// pending flag is cleared first so that an image that fails to activate is not retried at
+    /// every boot.
+    async fn take_pending_activation(&self) -> Result<bool, ErrorCode> {
+        let metadata = DOWNLOAD_CTX.lock(|ctx| {
+            let mut ctx = ctx.borrow_mut();
+            if ctx.metadata.activation_pending == 0 {
+                return None;
+            }
+            ctx.metadata.activation_pending = 0;
+            ctx.metadata.populate_checksum();
+            // The SoC manifest of the pending image may be the package data of its update
+            ctx.package_data_len = ctx.metadata.package_data_size as usize;
+            Some(ctx.metadata)
+        });
+        let Some(metadata) = metadata else {
+            return Ok(false);
+        };
+        self.write_metadata(&metadata).await?;
+        Ok(true)
+    }
+
+    /// Activates the image left pending in the staging memory. The image is verified again
+    /// since the staging memory is not protected while the image waits for the reset.
+    async fn activate_pending_image(&mut self) -> Result<(), ErrorCode> {
+        writeln!(
+            Console::<DefaultSyscalls>::writer(),
+            "[FW Upd] Activating pending image"
+        )
+        .unwrap();
+        let flash_header = self.verify().await?;
+        self.update_caliptra(&flash_header).await?;
+        self.set_auth_manifest().await?;
+        self.update_mcu(&flash_header).await
+    }
+
+    /// Returns the offset and size of the SoC manifest in the staging memory. The manifest is
+    /// the package data retrieved from the UA if any, otherwise the manifest of the image.
+    async fn get_soc_manifest_toc(
+        &self,
+        flash_header: &FlashHeader,
+    ) -> Result<(usize, usize), ErrorCode> {
+        let package_data_len = DOWNLOAD_CTX.lock(|ctx| ctx.borrow().package_data_len);
+        if package_data_len > 0 {
+            let offset = package_data_offset(self.staging_memory).ok_or(ErrorCode::Size)?;
+            return Ok((offset, package_data_len));
+        }
+        self.get_image_toc(
+            flash_header.image_count as usize,
+            flash_header.image_headers_offset as usize,
+            SOC_MANIFEST_IDENTIFIER,
+        )
+        .await
+    }
+
     async fn set_auth_manifest(&mut self) -> Result<(), ErrorCode> {
         let mut flash_header = [0u8; core::mem::size_of::<FlashHeader>()];
         self.staging_memory
@@ -183,11 +326,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
         )
         .unwrap();
         let (manifest_offset, manifest_len) = self
-            .get_image_toc(
-                flash_header.image_count as usize,
-                flash_header.image_headers_offset as usize,
-                SOC_MANIFEST_IDENTIFIER,
-            )
+            .get_soc_manifest_toc(&flash_header)
             .await
             .map_err(|_| ErrorCode::Fail)?;
 
@@ -268,11 +407,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
         )
         .unwrap();
         let (manifest_offset, manifest_len) = self
-            .get_image_toc(
-                flash_header.image_count as usize,
-                flash_header.image_headers_offset as usize,
-                SOC_MANIFEST_IDENTIFIER,
-            )
+            .get_soc_manifest_toc(&flash_header)
             .await
             .map_err(|_| ErrorCode::Fail)?;
         self.verify_manifest(manifest_offset, manifest_len).await?;
@@ -418,41 +553,7 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
     }
 
     async fn verify_manifest(&mut self, offset: usize, len: usize) -> Result<(), ErrorCode> {
-        let mut req = AuthManifestReqHeader {
-            chksum: 0,
-            manifest_size: len as u32,
-        };
-
-        let mut payload_stream = MailboxPayloadStream::new(self.staging_memory, offset, len);
-
-        // Calculate the mailbox checksum
-        let mut checksum = payload_stream.get_bytesum().await;
-        for b in CommandId::VERIFY_AUTH_MANIFEST.0.to_le_bytes().iter() {
-            checksum = checksum.wrapping_add(u32::from(*b));
-        }
-        for b in req.as_mut_bytes().iter() {
-            checksum = checksum.wrapping_add(u32::from(*b));
-        }
-        req.chksum = 0u32.wrapping_sub(checksum);
-
-        let response_buffer = &mut [0u8; core::mem::size_of::<MailboxRespHeader>()];
-        let header = req.as_mut_bytes();
-        loop {
-            let result = self
-                .mailbox
-                .execute_with_payload_stream(
-                    CommandId::VERIFY_AUTH_MANIFEST.into(),
-                    Some(header),
-                    &mut payload_stream,
-                    response_buffer,
-                )
-                .await;
-            match result {
-                Ok(_) => return Ok(()),
-                Err(MailboxError::ErrorCode(ErrorCode::Busy)) => continue,
-                Err(_) => return Err(ErrorCode::Fail),
-            }
-        }
+        verify_auth_manifest(&self.mailbox, self.staging_memory, offset, len).await
     }
 
     async fn get_dma_image_staging_address(&self, image_id: u32) -> Result<AXIAddr, ErrorCode> {
@@ -648,6 +749,50 @@ impl<'a, D: DMAMapping> FirmwareUpdater<'a, D> {
     }
 }
 
+/// Verifies the SoC manifest in the staging memory with Caliptra. Its signatures are checked
+/// against the vendor and owner keys provisioned in the device.
+pub(crate) async fn verify_auth_manifest(
+    mailbox: &Mailbox,
+    staging_memory: &'static dyn StagingMemory,
+    offset: usize,
+    len: usize,
+) -> Result<(), ErrorCode> {
+    let mut req = AuthManifestReqHeader {
+        chksum: 0,
+        manifest_size: len as u32,
+    };
+
+    let mut payload_stream = MailboxPayloadStream::new(staging_memory, offset, len);
+
+    // Calculate the mailbox checksum
+    let mut checksum = payload_stream.get_bytesum().await;
+    for b in CommandId::VERIFY_AUTH_MANIFEST.0.to_le_bytes().iter() {
+        checksum = checksum.wrapping_add(u32::from(*b));
+    }
+    for b in req.as_mut_bytes().iter() {
+        checksum = checksum.wrapping_add(u32::from(*b));
+    }
+    req.chksum = 0u32.wrapping_sub(checksum);
+
+    let response_buffer = &mut [0u8; core::mem::size_of::<MailboxRespHeader>()];
+    let header = req.as_mut_bytes();
+    loop {
+        let result = mailbox
+            .execute_with_payload_stream(
+                CommandId::VERIFY_AUTH_MANIFEST.into(),
+                Some(header),
+                &mut payload_stream,
+                response_buffer,
+            )
+            .await;
+        match result {
+            Ok(_) => return Ok(()),
+            Err(MailboxError::ErrorCode(ErrorCode::Busy)) => continue,
+            Err(_) => return Err(ErrorCode::Fail),
+        }
+    }
+}
+
 pub struct PldmInstance<'a> {
     pub pldm_service: Option<PldmService<'a>>,
     pub executor: TockExecutor,
@@ -15,7 +15,7 @@ use pldm_common::message::firmware_update::get_fw_params::FirmwareParameters;
 use pldm_common::message::firmware_update::verify_complete::VerifyResult;
 use pldm_common::protocol::firmware_update::Descriptor;
 use pldm_lib::daemon::PldmService;
-use pldm_lib::firmware_device::fd_ops::FdOps;
+use pldm_lib::firmware_device::fd_ops::{FdOps, PackageDataPolicy};
 
 pub static FW_UPDATE_TASK_YIELD: Signal<CriticalSectionRawMutex, ()> = Signal::new();
 pub static PLDM_DAEMON_TASK_YIELD: Signal<CriticalSectionRawMutex, ()> = Signal::new();
@@ -30,6 +30,7 @@ pub async fn initialize_pldm(
     spawner: Spawner,
     descriptors: &'static [Descriptor],
     fw_params: &'static FirmwareParameters,
+    package_data_policy: PackageDataPolicy,
     staging_memory: &'static dyn StagingMemory,
 ) -> Result<(), ErrorCode> {
     let is_initialiazed = PLDM_STATE.lock(|state| {
@@ -41,37 +42,53 @@ pub async fn initialize_pldm(
             true
         }
     });
-    if !is_initialiazed {
-        if descriptors.is_empty() {
-            panic!("PLDM descriptors cannot be empty");
-        }
-        let mut update_fd_ops: UpdateFdOps = UpdateFdOps::new();
-        let static_update_fd_ops: &'static mut UpdateFdOps =
-            unsafe { core::mem::transmute(&mut update_fd_ops) };
-
-        PLDM_STATE.lock(|state| {
-            let mut state = state.borrow_mut();
-            *state = State::DownloadingImage;
-        });
+    if is_initialiazed {
+        // The PLDM service is still running after an update whose activation was deferred,
+        // so it only has to accept the next update
+        start_download(descriptors, fw_params, package_data_policy, staging_memory);
+        return Ok(());
+    }
+    if descriptors.is_empty() {
+        panic!("PLDM descriptors cannot be empty");
+    }
+    let mut update_fd_ops: UpdateFdOps = UpdateFdOps::new();
+    let static_update_fd_ops: &'static mut UpdateFdOps =
+        unsafe { core::mem::transmute(&mut update_fd_ops) };
 
-        DOWNLOAD_CTX.lock(|ctx| {
-            let mut ctx = ctx.borrow_mut();
-            ctx.total_length = 0;
-            ctx.initial_offset = 0;
-            ctx.current_offset = 0;
-            ctx.total_downloaded = 0;
-            ctx.descriptors = Some(descriptors);
-            ctx.fw_params = Some(fw_params);
-            ctx.staging_memory = Some(staging_memory);
-        });
+    start_download(descriptors, fw_params, package_data_policy, staging_memory);
 
-        spawner
-            .spawn(pldm_service_task(static_update_fd_ops, spawner))
-            .unwrap();
-    }
+    spawner
+        .spawn(pldm_service_task(static_update_fd_ops, spawner))
+        .unwrap();
     Ok(())
 }
 
+fn start_download(
+    descriptors: &'static [Descriptor],
+    fw_params: &'static FirmwareParameters,
+    package_data_policy: PackageDataPolicy,
+    staging_memory: &'static dyn StagingMemory,
+) {
+    PLDM_STATE.lock(|state| {
+        let mut state = state.borrow_mut();
+        *state = State::DownloadingImage;
+    });
+
+    DOWNLOAD_CTX.lock(|ctx| {
+        let mut ctx = ctx.borrow_mut();
+        ctx.total_length = 0;
+        ctx.initial_offset = 0;
+        ctx.current_offset = 0;
+        ctx.total_downloaded = 0;
+        ctx.package_data_len = 0;
+        ctx.self_contained_activation = false;
+        ctx.descriptors = Some(descriptors);
+        ctx.fw_params = Some(fw_params);
+        ctx.package_data_policy = package_data_policy;
+        ctx.staging_memory = Some(staging_memory);
+    });
+}
+
 pub async fn pldm_wait(wait_state: State) -> Result<(), ErrorCode> {
     FW_UPDATE_TASK_YIELD.wait().await;
     let state = PLDM_STATE.lock(|state| *state.borrow());
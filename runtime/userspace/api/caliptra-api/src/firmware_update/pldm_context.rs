@@ -2,12 +2,15 @@
 
 use core::cell::RefCell;
 
+use caliptra_auth_man_types::AuthorizationManifest;
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::blocking_mutex::Mutex;
 use pldm_common::message::firmware_update::apply_complete::ApplyResult;
 use pldm_common::message::firmware_update::get_fw_params::FirmwareParameters;
 use pldm_common::message::firmware_update::verify_complete::VerifyResult;
 use pldm_common::protocol::firmware_update::Descriptor;
+use pldm_lib::firmware_device::fd_ops::PackageDataPolicy;
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 use super::StagingMemory;
 
@@ -22,6 +25,81 @@ pub enum State {
     Activate,
 }
 
+/// The package data is the SoC authorization manifest, used instead of the one of the image.
+pub const MAX_PACKAGE_DATA_SIZE: usize = core::mem::size_of::<AuthorizationManifest>();
+
+const METADATA_MAGIC: u32 = 0x444D_5546; // "FUMD"
+
+/// Device metadata of the firmware update, saved by the UA with GetDeviceMetaData and
+/// returned with GetMetaData. It is persisted at the end of the staging memory.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, FromBytes, IntoBytes, Immutable, KnownLayout)]
+pub struct UpdateMetadata {
+    pub magic: u32,
+    /// Number of updates activated on the device.
+    pub update_count: u32,
+    /// Size of the image of the last activated update.
+    pub image_size: u32,
+    /// Size of the package data of the last activated update, or 0 if it had none.
+    pub package_data_size: u32,
+    /// Non-zero if the last update was applied without self-contained activation. The image
+    /// left in the staging memory is then activated at the next boot.
+    pub activation_pending: u32,
+    pub checksum: u32,
+}
+
+impl UpdateMetadata {
+    pub const fn empty() -> Self {
+        Self {
+            magic: METADATA_MAGIC,
+            update_count: 0,
+            image_size: 0,
+            package_data_size: 0,
+            activation_pending: 0,
+            checksum: 0,
+        }
+    }
+
+    /// Creates the metadata of a device that has not activated any update yet.
+    pub fn new() -> Self {
+        let mut metadata = Self::empty();
+        metadata.populate_checksum();
+        metadata
+    }
+
+    pub fn populate_checksum(&mut self) {
+        self.checksum = 0u32.wrapping_sub(self.sum());
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.magic == METADATA_MAGIC && self.sum().wrapping_add(self.checksum) == 0
+    }
+
+    fn sum(&self) -> u32 {
+        self.as_bytes()[..core::mem::offset_of!(UpdateMetadata, checksum)]
+            .iter()
+            .fold(0u32, |sum, b| sum.wrapping_add(u32::from(*b)))
+    }
+}
+
+impl Default for UpdateMetadata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Offset of the device metadata, stored in the last bytes of the staging memory.
+pub fn metadata_offset(staging_memory: &dyn StagingMemory) -> Option<usize> {
+    staging_memory
+        .size()
+        .checked_sub(core::mem::size_of::<UpdateMetadata>())
+}
+
+/// Offset of the package data, stored before the device metadata. Images must end before it.
+pub fn package_data_offset(staging_memory: &dyn StagingMemory) -> Option<usize> {
+    metadata_offset(staging_memory)?.checked_sub(MAX_PACKAGE_DATA_SIZE)
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct DownloadCtx<'a> {
     pub total_length: usize,
@@ -34,6 +112,14 @@ pub struct DownloadCtx<'a> {
     pub descriptors: Option<&'a [Descriptor]>,
     pub fw_params: Option<&'a FirmwareParameters>,
     pub staging_memory: Option<&'a dyn StagingMemory>,
+    /// Length of the package data retrieved from the UA, or 0 if there is none.
+    pub package_data_len: usize,
+    pub metadata: UpdateMetadata,
+    /// Set when the device metadata was lost and must be retrieved from the UA.
+    pub metadata_restore_pending: bool,
+    /// Set when the UA requested self-contained activation in ActivateFirmware.
+    pub self_contained_activation: bool,
+    pub package_data_policy: PackageDataPolicy,
 }
 
 pub static DOWNLOAD_CTX: Mutex<CriticalSectionRawMutex, RefCell<DownloadCtx>> =
@@ -48,6 +134,13 @@ pub static DOWNLOAD_CTX: Mutex<CriticalSectionRawMutex, RefCell<DownloadCtx>> =
         descriptors: None,
         fw_params: None,
         staging_memory: None,
+        package_data_len: 0,
+        metadata: UpdateMetadata::empty(),
+        metadata_restore_pending: false,
+        self_contained_activation: false,
+        package_data_policy: PackageDataPolicy {
+            require_authenticated_package_data: false,
+        },
     }));
 
 pub static PLDM_STATE: Mutex<CriticalSectionRawMutex, RefCell<State>> =
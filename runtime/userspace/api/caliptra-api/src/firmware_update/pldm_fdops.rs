@@ -3,10 +3,16 @@
 extern crate alloc;
 
 use super::pldm_client::{FW_UPDATE_TASK_YIELD, PLDM_DAEMON_TASK_YIELD};
-use super::pldm_context::{State, DOWNLOAD_CTX, PLDM_STATE};
+use super::pldm_context::{
+    metadata_offset, package_data_offset, State, UpdateMetadata, DOWNLOAD_CTX,
+    MAX_PACKAGE_DATA_SIZE, PLDM_STATE,
+};
+use super::verify_auth_manifest;
 use alloc::boxed::Box;
 use async_trait::async_trait;
 use flash_image::{FlashHeader, ImageHeader};
+use libsyscall_caliptra::mailbox::Mailbox;
+use pldm_common::message::firmware_update::activate_fw::SelfContainedActivationRequest;
 use pldm_common::message::firmware_update::apply_complete::ApplyResult;
 use pldm_common::message::firmware_update::get_fw_params::FirmwareParameters;
 use pldm_common::message::firmware_update::get_status::ProgressPercent;
@@ -16,7 +22,8 @@ use pldm_common::protocol::firmware_update::{
     ComponentResponseCode, Descriptor, PLDM_FWUP_BASELINE_TRANSFER_SIZE,
 };
 use pldm_common::util::fw_component::FirmwareComponent;
-use pldm_lib::firmware_device::fd_ops::{ComponentOperation, FdOps, FdOpsError};
+use pldm_lib::firmware_device::fd_ops::{ComponentOperation, FdOps, FdOpsError, PackageDataPolicy};
+use zerocopy::IntoBytes;
 
 const MAX_PLDM_TRANSFER_SIZE: usize = 196; // This should be smaller than I3C MAX_READ_WRITE_SIZE
 const ESTIMATED_ACTIVATION_TIME_SECS: u16 = 600; // 10 minutes estimated activation time including reset
@@ -107,7 +114,9 @@ impl FdOps for UpdateFdOps {
         }
         let staging_memory = DOWNLOAD_CTX.lock(|ctx| ctx.borrow().staging_memory);
         if let Some(staging_area) = staging_memory {
-            if staging_area.size() < component.comp_image_size.unwrap_or(0) as usize {
+            // The end of the staging area holds the package data and the device metadata
+            let image_area_size = package_data_offset(staging_area).unwrap_or(0);
+            if image_area_size < component.comp_image_size.unwrap_or(0) as usize {
                 // Staging area is not large enough for the component
                 return Ok(ComponentResponseCode::CompPrerequisitesNotMet);
             }
@@ -231,10 +240,19 @@ impl FdOps for UpdateFdOps {
 
     fn activate(
         &self,
-        _self_contained_activation: u8,
+        self_contained_activation: SelfContainedActivationRequest,
         estimated_time: &mut u16,
     ) -> Result<u8, FdOpsError> {
-        *estimated_time = ESTIMATED_ACTIVATION_TIME_SECS;
+        let self_contained = self_contained_activation
+            == SelfContainedActivationRequest::ActivateSelfContainedComponents;
+        if self_contained {
+            *estimated_time = ESTIMATED_ACTIVATION_TIME_SECS;
+        }
+        // The update task activates the new images with a reset of the MCU only if self-contained
+        // activation is requested. Otherwise the images stay pending in the staging memory.
+        DOWNLOAD_CTX.lock(|ctx| {
+            ctx.borrow_mut().self_contained_activation = self_contained;
+        });
         PLDM_STATE.lock(|state| {
             let mut state = state.borrow_mut();
             *state = State::Activate;
@@ -242,4 +260,114 @@ impl FdOps for UpdateFdOps {
         FW_UPDATE_TASK_YIELD.signal(());
         Ok(0) // PLDM completion code for success
     }
+
+    fn will_get_package_data(&self, pkg_data_len: usize) -> bool {
+        DOWNLOAD_CTX.lock(|ctx| {
+            let mut ctx = ctx.borrow_mut();
+            let has_room = ctx
+                .staging_memory
+                .is_some_and(|staging_area| package_data_offset(staging_area).is_some());
+            ctx.package_data_len = if has_room && pkg_data_len <= MAX_PACKAGE_DATA_SIZE {
+                pkg_data_len
+            } else {
+                0
+            };
+            ctx.package_data_len != 0
+        })
+    }
+
+    async fn handle_package_data(
+        &self,
+        offset: usize,
+        data: &[u8],
+        is_last: bool,
+    ) -> Result<(), FdOpsError> {
+        let (staging_memory, package_data_len) = DOWNLOAD_CTX.lock(|ctx| {
+            let ctx = ctx.borrow();
+            (ctx.staging_memory, ctx.package_data_len)
+        });
+        let staging_area = staging_memory.ok_or(FdOpsError::PackageDataError)?;
+        let end = offset + data.len();
+        if end > package_data_len || (is_last && end != package_data_len) {
+            return Err(FdOpsError::PackageDataError);
+        }
+        let base = package_data_offset(staging_area).ok_or(FdOpsError::PackageDataError)?;
+        staging_area
+            .write(base + offset, data)
+            .await
+            .map_err(|_| FdOpsError::PackageDataError)
+    }
+
+    fn package_data_policy(&self) -> PackageDataPolicy {
+        DOWNLOAD_CTX.lock(|ctx| ctx.borrow().package_data_policy)
+    }
+
+    async fn verify_package_data(&self) -> Result<(), FdOpsError> {
+        // The package data is the SoC manifest, which must be signed with the keys provisioned
+        // in Caliptra before any image of the package is downloaded
+        let (staging_memory, package_data_len) = DOWNLOAD_CTX.lock(|ctx| {
+            let ctx = ctx.borrow();
+            (ctx.staging_memory, ctx.package_data_len)
+        });
+        let staging_area = staging_memory.ok_or(FdOpsError::PackageDataError)?;
+        let offset = package_data_offset(staging_area).ok_or(FdOpsError::PackageDataError)?;
+        verify_auth_manifest(&Mailbox::new(), staging_area, offset, package_data_len)
+            .await
+            .map_err(|_| FdOpsError::PackageDataError)
+    }
+
+    fn get_device_metadata_len(&self) -> usize {
+        core::mem::size_of::<UpdateMetadata>()
+    }
+
+    fn get_device_metadata(&self, offset: usize, data: &mut [u8]) -> Result<usize, FdOpsError> {
+        let metadata = DOWNLOAD_CTX.lock(|ctx| ctx.borrow().metadata);
+        let metadata = metadata
+            .as_bytes()
+            .get(offset..)
+            .ok_or(FdOpsError::MetaDataError)?;
+        let len = metadata.len().min(data.len());
+        data[..len].copy_from_slice(&metadata[..len]);
+        Ok(len)
+    }
+
+    fn is_metadata_restore_pending(&self) -> bool {
+        DOWNLOAD_CTX.lock(|ctx| ctx.borrow().metadata_restore_pending)
+    }
+
+    async fn restore_metadata(
+        &self,
+        offset: usize,
+        data: &[u8],
+        is_last: bool,
+    ) -> Result<(), FdOpsError> {
+        let metadata = DOWNLOAD_CTX.lock(|ctx| {
+            let mut ctx = ctx.borrow_mut();
+            let bytes = ctx.metadata.as_mut_bytes();
+            let end = offset + data.len();
+            if end > bytes.len() || (is_last && end != bytes.len()) {
+                return Err(FdOpsError::MetaDataError);
+            }
+            bytes[offset..end].copy_from_slice(data);
+            Ok(ctx.metadata)
+        })?;
+        if !is_last {
+            return Ok(());
+        }
+        if !metadata.is_valid() {
+            DOWNLOAD_CTX.lock(|ctx| ctx.borrow_mut().metadata = UpdateMetadata::new());
+            return Err(FdOpsError::MetaDataError);
+        }
+
+        // Persist the restored metadata
+        let staging_memory = DOWNLOAD_CTX.lock(|ctx| ctx.borrow().staging_memory);
+        let staging_area = staging_memory.ok_or(FdOpsError::MetaDataError)?;
+        let offset = metadata_offset(staging_area).ok_or(FdOpsError::MetaDataError)?;
+        staging_area
+            .write(offset, metadata.as_bytes())
+            .await
+            .map_err(|_| FdOpsError::MetaDataError)?;
+        DOWNLOAD_CTX.lock(|ctx| ctx.borrow_mut().metadata_restore_pending = false);
+        Ok(())
+    }
 }
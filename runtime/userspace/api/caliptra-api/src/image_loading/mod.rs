@@ -6,6 +6,8 @@ mod pldm_client;
 mod pldm_context;
 mod pldm_fdops;
 
+use crate::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
+use crate::evidence::measurement_log::MeasurementLog;
 use alloc::boxed::Box;
 use async_trait::async_trait;
 use caliptra_api::mailbox::{
@@ -14,7 +16,7 @@ use caliptra_api::mailbox::{
 };
 use embassy_executor::Spawner;
 use flash_image::{FlashHeader, SOC_MANIFEST_IDENTIFIER};
-use libsyscall_caliptra::dma::DMAMapping;
+use libsyscall_caliptra::dma::{DMAMapping, DMASource, DMATransaction, DMA as DMASyscall};
 use libsyscall_caliptra::flash::SpiFlash as FlashSyscall;
 use libsyscall_caliptra::mailbox::{MailboxError, PayloadStream};
 use libsyscall_caliptra::{dma::AXIAddr, mailbox::Mailbox};
@@ -28,6 +30,9 @@ use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 pub const IMAGE_AUTHORIZED: u32 = 0xDEADC0DE;
 
+/// Size of the image portions read back from the load address to be measured.
+const MEASUREMENT_READ_SIZE: usize = 128;
+
 pub struct PldmInstance<'a> {
     pub pldm_service: Option<PldmService<'a>>,
     pub executor: TockExecutor,
@@ -98,7 +103,7 @@ impl<D: DMAMapping + 'static> ImageLoader for FlashImageLoader<D> {
         )
         .await?;
         authorize_image(&self.mailbox, image_id, size).await?;
-        Ok(())
+        stash_image_measurement(self.dma_mapping, image_id, load_address, size).await
     }
 }
 
@@ -187,7 +192,8 @@ impl<D: DMAMapping + 'static> ImageLoader for PldmImageLoader<'_, D> {
             .await?;
             let (offset, size) = pldm_client::pldm_download_toc(image_info.component_id).await?;
             pldm_client::pldm_download_image(load_address, offset, size).await?;
-            authorize_image(&self.mailbox, image_id, size).await
+            authorize_image(&self.mailbox, image_id, size).await?;
+            stash_image_measurement(self.dma_mapping, image_id, load_address, size).await
         };
         if result.is_err() {
             self.finalize()?;
@@ -249,7 +255,7 @@ async fn authorize_image(mailbox: &Mailbox, image_id: u32, size: u32) -> Result<
     let mut req = AuthorizeAndStashReq {
         hdr: MailboxReqHeader::default(),
         fw_id: image_id.to_le_bytes(),
-        flags: 1, // Skip Stash
+        flags: 1, // Skip Stash, the measurement is stashed by stash_image_measurement
         source: ImageHashSource::LoadAddress as u32,
         image_size: size,
         ..Default::default()
@@ -280,6 +286,63 @@ async fn authorize_image(mailbox: &Mailbox, image_id: u32, size: u32) -> Result<
     Ok(())
 }
 
+/// Stashes the measurement of an authorized image in Caliptra, which extends it into PCR31,
+/// and records it in the measurement log.
+async fn stash_image_measurement(
+    dma_mapping: &impl DMAMapping,
+    image_id: u32,
+    load_address: AXIAddr,
+    size: u32,
+) -> Result<(), ErrorCode> {
+    let measurement = image_digest(dma_mapping, load_address, size as usize).await?;
+    MeasurementLog::stash_measurement(
+        image_id.to_le_bytes(),
+        &measurement,
+        &[0; SHA384_HASH_SIZE],
+        0,
+    )
+    .await
+    .map_err(|_| ErrorCode::Fail)
+}
+
+/// Computes the SHA-384 digest of the image by reading it back from the load address.
+async fn image_digest(
+    dma_mapping: &impl DMAMapping,
+    load_address: AXIAddr,
+    size: usize,
+) -> Result<[u8; SHA384_HASH_SIZE], ErrorCode> {
+    let dma_syscall: DMASyscall = DMASyscall::new();
+    let mut hash_ctx = HashContext::new();
+    hash_ctx
+        .init(HashAlgoType::SHA384, None)
+        .await
+        .map_err(|_| ErrorCode::Fail)?;
+
+    let mut offset = 0;
+    while offset < size {
+        let read_size = (size - offset).min(MEASUREMENT_READ_SIZE);
+        let mut buffer = [0u8; MEASUREMENT_READ_SIZE];
+        let transaction = DMATransaction {
+            byte_count: read_size,
+            source: DMASource::Address(load_address + offset as u64),
+            dest_addr: dma_mapping.mcu_sram_to_mcu_axi(buffer.as_mut_ptr() as u32)?,
+        };
+        dma_syscall.xfer(&transaction).await?;
+        hash_ctx
+            .update(&buffer[..read_size])
+            .await
+            .map_err(|_| ErrorCode::Fail)?;
+        offset += read_size;
+    }
+
+    let mut digest = [0u8; SHA384_HASH_SIZE];
+    hash_ctx
+        .finalize(&mut digest)
+        .await
+        .map_err(|_| ErrorCode::Fail)?;
+    Ok(digest)
+}
+
 pub struct FlashMailboxPayloadStream<'a> {
     pub flash: &'a FlashSyscall,
     pub offset: usize,
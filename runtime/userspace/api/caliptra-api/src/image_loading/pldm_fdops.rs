@@ -8,6 +8,7 @@ use alloc::boxed::Box;
 use async_trait::async_trait;
 use flash_image::{FlashHeader, ImageHeader};
 use libsyscall_caliptra::dma::{AXIAddr, DMAMapping, DMASource, DMATransaction, DMA as DMASyscall};
+use pldm_common::message::firmware_update::activate_fw::SelfContainedActivationRequest;
 use pldm_common::message::firmware_update::apply_complete::ApplyResult;
 use pldm_common::message::firmware_update::get_fw_params::FirmwareParameters;
 use pldm_common::message::firmware_update::get_status::ProgressPercent;
@@ -260,7 +261,7 @@ impl<D: DMAMapping> FdOps for StreamingFdOps<'_, D> {
 
     fn activate(
         &self,
-        _self_contained_activation: u8,
+        _self_contained_activation: SelfContainedActivationRequest,
         estimated_time: &mut u16,
     ) -> Result<u8, FdOpsError> {
         *estimated_time = 0;
@@ -4,6 +4,7 @@
 pub mod certificate;
 pub mod checksum;
 pub mod crypto;
+pub mod debug_unlock;
 pub mod error;
 pub mod evidence;
 pub mod firmware_update;
@@ -13,6 +13,7 @@
 //! - `MAX_ECC_CERT_SIZE`: Maximum size of an ECC certificate.
 //! - `MAX_CERT_CHUNK_SIZE`: Maximum size of a certificate chunk.
 //! - `MAX_RANDOM_NUM_SIZE`: Maximum size of a random number and the rand_stir input.
+//! - `MAX_MLDSA87_VERIFY_MSG_SIZE`: Maximum size of a message verified with an ML-DSA-87 signature.
 //!
 //! # Assertions
 //! - Ensures that the redefined structures do not exceed the size of their original counterparts.
@@ -26,6 +27,9 @@
 //! - `CertificateChainResp`: Represents a response containing a chunk of a certificate chain. Equivalent to `GetCertificateChainResp`.
 //! - `RandomStirReq`: Represents a request to stir the random number generator. Equivalent to `CmRandomStirReq`.
 //! - `RandomGenerateResp`: Represents a response for generating random numbers. Equivalent to `CmRandomGenerateResp`.
+//! - `Mldsa87CertResp`: Represents a response containing an ML-DSA-87 DICE certificate. Equivalent to `GetLdevCertResp`
+//!   with a data buffer large enough for an ML-DSA-87 certificate.
+//! - `Mldsa87VerifyReq`: Represents a request to verify an ML-DSA-87 signature over a message.
 //!
 //! # Enums
 //! - `DpeResponse`: Enum representing various DPE command responses:
@@ -34,6 +38,7 @@
 //! These structures and constants are intended for use in the Caliptra subsystem's mailbox
 //! API, particularly for cryptographic and DPE-related operations.
 
+use crate::crypto::asym::{MLDSA87_PUBLIC_KEY_SIZE, MLDSA87_SIGNATURE_SIZE};
 use crate::error::CaliptraApiError;
 use crate::error::CaliptraApiResult;
 use caliptra_api::mailbox::CmRandomGenerateResp;
@@ -55,8 +60,11 @@ pub const MAX_ECC_CERT_SIZE: usize = 1024;
 pub const MAX_CERT_CHUNK_SIZE: usize = 1024;
 pub const MAX_RANDOM_STIR_SIZE: usize = 48;
 pub const MAX_RANDOM_NUM_SIZE: usize = 48;
+pub const MAX_MLDSA87_CERT_SIZE: usize = 8192;
+pub const MAX_MLDSA87_VERIFY_MSG_SIZE: usize = 4096;
 
 const _: () = assert!(MAX_CRYPTO_MBOX_DATA_SIZE <= MAX_CMB_DATA_SIZE);
+const _: () = assert!(MAX_MLDSA87_VERIFY_MSG_SIZE <= MAX_CMB_DATA_SIZE);
 const _: () = assert!(size_of::<DpeEcResp>() <= size_of::<InvokeDpeResp>());
 const _: () = assert!(size_of::<CertificateChainResp>() <= size_of::<GetCertificateChainResp>());
 const _: () = assert!(size_of::<CertifyEcKeyResp>() <= size_of::<CertifyKeyResp>());
@@ -135,6 +143,27 @@ impl Default for DpeEcResp {
     }
 }
 
+// GET_LDEV_MLDSA87_CERT, GET_FMC_ALIAS_MLDSA87_CERT and GET_RT_ALIAS_MLDSA87_CERT
+#[repr(C)]
+#[derive(Debug, IntoBytes, FromBytes, KnownLayout, Immutable, PartialEq, Eq)]
+pub(crate) struct Mldsa87CertResp {
+    pub hdr: MailboxRespHeader,
+    pub data_size: u32,
+    pub data: [u8; MAX_MLDSA87_CERT_SIZE], // variable length
+}
+
+// MLDSA87_SIGNATURE_VERIFY
+#[repr(C)]
+#[derive(Debug, IntoBytes, FromBytes, KnownLayout, Immutable, PartialEq, Eq)]
+pub(crate) struct Mldsa87VerifyReq {
+    pub hdr: MailboxReqHeader,
+    pub pub_key: [u8; MLDSA87_PUBLIC_KEY_SIZE],
+    // Signature padded to a DWORD boundary
+    pub signature: [u8; MLDSA87_SIGNATURE_SIZE + 1],
+    pub message_size: u32,
+    pub message: [u8; MAX_MLDSA87_VERIFY_MSG_SIZE], // variable length
+}
+
 // DPE Commands
 
 pub(crate) enum DpeResponse {
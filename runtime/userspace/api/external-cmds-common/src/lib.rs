@@ -10,6 +10,12 @@ use zerocopy::{Immutable, IntoBytes};
 
 pub const MAX_FW_VERSION_LEN: usize = 32;
 pub const MAX_UID_LEN: usize = 32;
+pub const DEBUG_UNLOCK_UDI_LEN: usize = 32;
+pub const DEBUG_UNLOCK_CHALLENGE_LEN: usize = 48;
+
+/// IDevID certificate slots, indexed like the CSRs they endorse.
+pub const IDEV_ECC_CERT_SLOT: u32 = 0;
+pub const IDEV_MLDSA_CERT_SLOT: u32 = 1;
 
 /// Common error type for unified commands.
 #[derive(Debug)]
@@ -19,6 +25,7 @@ pub enum CommandError {
     InternalError,
     NotSupported,
     Busy,
+    AlreadyProvisioned,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -59,6 +66,66 @@ pub struct DeviceCapabilities {
     pub reserved: [u8; 4],     // Bytes [28:31]
 }
 
+/// State of the IDevID certificate chain.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CertificateState {
+    /// A valid chain has been provisioned.
+    Provisioned = 0,
+    /// A valid chain has not been provisioned.
+    #[default]
+    NotProvisioned = 1,
+    /// The stored chain is being validated.
+    Validating = 2,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CertificateStatus {
+    pub state: CertificateState,
+    /// Platform-specific error details if chain validation has failed.
+    pub error_details: u32,
+    /// Bit N is set if certificate slot N holds an imported certificate.
+    pub provisioned_slots: u32,
+}
+
+/// Type of the log accessed by the log commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LogType {
+    /// RoT application information and machine state.
+    Debug = 0,
+    /// Measurements extended into the attestation PCRs.
+    Attestation = 1,
+}
+
+impl TryFrom<u32> for LogType {
+    type Error = CommandError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(LogType::Debug),
+            1 => Ok(LogType::Attestation),
+            _ => Err(CommandError::InvalidParams),
+        }
+    }
+}
+
+/// Production debug unlock challenge generated by Caliptra.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugUnlockChallenge {
+    pub unique_device_identifier: [u8; DEBUG_UNLOCK_UDI_LEN],
+    pub challenge: [u8; DEBUG_UNLOCK_CHALLENGE_LEN],
+}
+
+impl Default for DebugUnlockChallenge {
+    fn default() -> Self {
+        Self {
+            unique_device_identifier: [0; DEBUG_UNLOCK_UDI_LEN],
+            challenge: [0; DEBUG_UNLOCK_CHALLENGE_LEN],
+        }
+    }
+}
+
 /// Asynchronous trait for handling commands common to both external MCU mailbox and MCTP VDM protocols.
 ///
 /// Each function represents a protocol-agnostic command handler. Implementors should provide
@@ -109,4 +176,93 @@ pub trait UnifiedCommandHandler {
         &self,
         capabilities: &mut DeviceCapabilities,
     ) -> Result<(), CommandError>;
+
+    /// Exports the IDevID certificate signing request for the given index.
+    ///
+    /// # Arguments
+    /// * `index` - The CSR index, matching the certificate slot it is endorsed into.
+    /// * `csr` - Buffer to store the DER-encoded CSR.
+    ///
+    /// # Returns
+    /// * `Result<usize, CommandError>` - The CSR size on success, or an error.
+    async fn export_idev_csr(&self, index: u32, csr: &mut [u8]) -> Result<usize, CommandError>;
+
+    /// Imports the DER-encoded IDevID certificate endorsed by the CA.
+    ///
+    /// # Arguments
+    /// * `cert` - The DER-encoded certificate.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn import_idev_cert(&self, cert: &[u8]) -> Result<(), CommandError>;
+
+    /// Retrieves the state of the IDevID certificate chain.
+    ///
+    /// # Arguments
+    /// * `status` - Mutable reference to store the certificate status.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn get_cert_state(&self, status: &mut CertificateStatus) -> Result<(), CommandError>;
+
+    /// Reads the log contents starting at the given byte offset.
+    ///
+    /// Both transports serialize the log the same way, so a given offset always refers to
+    /// the same log contents regardless of the transport used to read it.
+    ///
+    /// # Arguments
+    /// * `log_type` - The log to read.
+    /// * `offset` - Offset in bytes into the serialized log.
+    /// * `data` - Buffer to store the log contents.
+    ///
+    /// # Returns
+    /// * `Result<usize, CommandError>` - The number of bytes read, 0 once the end of the log
+    ///   is reached, or an error.
+    async fn get_log(
+        &self,
+        log_type: LogType,
+        offset: usize,
+        data: &mut [u8],
+    ) -> Result<usize, CommandError>;
+
+    /// Clears the log.
+    ///
+    /// # Arguments
+    /// * `log_type` - The log to clear.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn clear_log(&self, log_type: LogType) -> Result<(), CommandError>;
+
+    /// Requests production debug unlock at the given level.
+    ///
+    /// # Arguments
+    /// * `unlock_level` - The requested debug unlock level.
+    /// * `challenge` - Mutable reference to store the challenge to be signed into the token.
+    ///
+    /// # Returns
+    /// * `Result<(), CommandError>` - Ok on success, or an error.
+    async fn request_debug_unlock(
+        &self,
+        unlock_level: u8,
+        challenge: &mut DebugUnlockChallenge,
+    ) -> Result<(), CommandError>;
+
+    /// Receives a chunk of the signed debug unlock token and authorizes the token once
+    /// it is complete.
+    ///
+    /// Chunks are sent in order, and a chunk at offset 0 starts a new token.
+    ///
+    /// # Arguments
+    /// * `offset` - Offset in bytes of the chunk into the token.
+    /// * `data` - The token chunk.
+    ///
+    /// # Returns
+    /// * `Result<Option<u8>, CommandError>` - The granted unlock level once the token is
+    ///   authorized, None while more chunks are expected, or an error.
+    async fn authorize_debug_unlock_token(
+        &self,
+        offset: usize,
+        data: &[u8],
+    ) -> Result<Option<u8>, CommandError>;
 }
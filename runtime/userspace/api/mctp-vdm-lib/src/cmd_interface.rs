@@ -4,17 +4,25 @@ use crate::error::VdmLibError;
 use crate::transport::MctpVdmTransport;
 use core::convert::TryFrom;
 use external_cmds_common::{
-    DeviceCapabilities, DeviceId, DeviceInfo, FirmwareVersion, Uid, UnifiedCommandHandler,
-    MAX_UID_LEN,
+    CertificateStatus, CommandError, DebugUnlockChallenge, DeviceCapabilities, DeviceId,
+    DeviceInfo, FirmwareVersion, LogType, Uid, UnifiedCommandHandler, MAX_UID_LEN,
 };
 use mctp_vdm_common::codec::VdmCodec;
 use mctp_vdm_common::message::{
-    DeviceCapabilitiesResponse, DeviceIdResponse, DeviceInfoRequest, DeviceInfoResponse,
-    FirmwareVersionRequest, FirmwareVersionResponse, DEVICE_CAPS_SIZE,
+    AuthorizeDebugUnlockTokenRequestHeader, AuthorizeDebugUnlockTokenResponse, ClearLogRequest,
+    ClearLogResponse, DeviceCapabilitiesResponse, DeviceIdResponse, DeviceInfoRequest,
+    DeviceInfoResponse, ExportCsrRequest, ExportCsrResponse, FirmwareVersionRequest,
+    FirmwareVersionResponse, GetCertificateStateResponse, GetLogRequest, GetLogResponse,
+    ImportCertificateRequestHeader, ImportCertificateResponse, RequestDebugUnlockRequest,
+    RequestDebugUnlockResponse, DEBUG_UNLOCK_CHALLENGE_SIZE, DEBUG_UNLOCK_UDI_SIZE,
+    DEVICE_CAPS_SIZE, MAX_CERT_SIZE, MAX_CSR_SIZE, MAX_DEBUG_UNLOCK_LEVEL, MAX_LOG_DATA_SIZE,
+    MAX_TOKEN_CHUNK_SIZE, MIN_DEBUG_UNLOCK_LEVEL, REQUEST_DEBUG_UNLOCK_LENGTH,
 };
 use mctp_vdm_common::protocol::{
-    VdmCommand, VdmCompletionCode, VdmFailureResponse, VdmMsgHeader, VDM_MSG_HEADER_LEN,
+    VdmCommand, VdmCompletionCode, VdmFailureResponse, VdmMsgHeader, CALIPTRA_PCI_VENDOR_ID,
+    CALIPTRA_VDM_CMD_SET_VERSION, VDM_MSG_HEADER_LEN,
 };
+use mctp_vdm_common::util::endpoint_uuid::endpoint_uuid;
 use mctp_vdm_common::util::mctp_transport::{
     construct_mctp_vdm_msg, extract_vdm_msg, VDM_MSG_OFFSET,
 };
@@ -38,6 +46,31 @@ impl<'a> CmdInterface<'a> {
         }
     }
 
+    /// Register the endpoint information reported by the MCTP control commands.
+    ///
+    /// The endpoint UUID is derived from the device unique ID, and the Caliptra
+    /// vendor defined message set is advertised as supported.
+    pub async fn register_endpoint_info(&mut self) -> Result<(), VdmLibError> {
+        let mut info = DeviceInfo::Uid(Uid::default());
+        self.unified_handler
+            .get_device_info(0, &mut info)
+            .await
+            .map_err(|_| VdmLibError::CommandHandlerError)?;
+
+        let DeviceInfo::Uid(uid) = &info;
+        let uuid = endpoint_uuid(&uid.unique_chip_id[..uid.len.min(MAX_UID_LEN)]);
+
+        self.transport
+            .set_endpoint_uuid(&uuid)
+            .map_err(|_| VdmLibError::TransportError)?;
+        self.transport
+            .register_vendor_defined_msg_support(
+                CALIPTRA_PCI_VENDOR_ID,
+                CALIPTRA_VDM_CMD_SET_VERSION,
+            )
+            .map_err(|_| VdmLibError::TransportError)
+    }
+
     /// Handle a responder message (receive request, process, send response).
     pub async fn handle_responder_msg(&mut self, msg_buf: &mut [u8]) -> Result<(), VdmLibError> {
         // Receive a request from the transport.
@@ -119,11 +152,23 @@ impl<'a> CmdInterface<'a> {
             }
             VdmCommand::DeviceId => self.handle_device_id(msg_buf, vdm_req_len).await,
             VdmCommand::DeviceInfo => self.handle_device_info(msg_buf, vdm_req_len).await,
-            _ => self.send_error_response(
-                msg_buf,
-                hdr.command_code,
-                VdmCompletionCode::UnsupportedCommand,
-            ),
+            VdmCommand::ExportCsr => self.handle_export_csr(msg_buf, vdm_req_len).await,
+            VdmCommand::ImportCertificate => {
+                self.handle_import_certificate(msg_buf, vdm_req_len).await
+            }
+            VdmCommand::GetCertificateState => {
+                self.handle_get_certificate_state(msg_buf, vdm_req_len)
+                    .await
+            }
+            VdmCommand::GetLog => self.handle_get_log(msg_buf, vdm_req_len).await,
+            VdmCommand::ClearLog => self.handle_clear_log(msg_buf, vdm_req_len).await,
+            VdmCommand::RequestDebugUnlock => {
+                self.handle_request_debug_unlock(msg_buf, vdm_req_len).await
+            }
+            VdmCommand::AuthorizeDebugUnlockToken => {
+                self.handle_authorize_debug_unlock_token(msg_buf, vdm_req_len)
+                    .await
+            }
         }
     }
 
@@ -262,6 +307,285 @@ impl<'a> CmdInterface<'a> {
         self.encode_device_info_response(msg_buf, &resp)
     }
 
+    /// Handle Export CSR command.
+    async fn handle_export_csr(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode the request.
+        let req = ExportCsrRequest::decode(&vdm_msg[..req_len])
+            .map_err(|_| VdmLibError::DecodingError)?;
+
+        // Export the CSR using the unified handler.
+        let mut csr = [0u8; MAX_CSR_SIZE];
+        let index = req.index;
+        let result = self.unified_handler.export_idev_csr(index, &mut csr).await;
+
+        // Build the response.
+        let resp = match result {
+            Ok(len) if len <= MAX_CSR_SIZE => {
+                ExportCsrResponse::new(VdmCompletionCode::Success as u32, &csr[..len])
+            }
+            Ok(_) => ExportCsrResponse::new(VdmCompletionCode::GeneralError as u32, &[]),
+            Err(e) => ExportCsrResponse::new(Self::completion_code(&e) as u32, &[]),
+        };
+
+        // Encode the response into the MCTP payload.
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Import Certificate command.
+    async fn handle_import_certificate(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode the request header. The certificate is used in place rather than copied.
+        let req = ImportCertificateRequestHeader::decode(&vdm_msg[..req_len])
+            .map_err(|_| VdmLibError::DecodingError)?;
+        let header_size = core::mem::size_of::<ImportCertificateRequestHeader>();
+        let cert_size = req.cert_size as usize;
+        if cert_size == 0 || cert_size > MAX_CERT_SIZE || header_size + cert_size > req_len {
+            return self.send_error_response(
+                msg_buf,
+                VdmCommand::ImportCertificate.into(),
+                VdmCompletionCode::InvalidLength,
+            );
+        }
+
+        // Import the certificate using the unified handler.
+        let result = self
+            .unified_handler
+            .import_idev_cert(&vdm_msg[header_size..header_size + cert_size])
+            .await;
+
+        // Build the response.
+        let completion_code = match result {
+            Ok(()) => VdmCompletionCode::Success,
+            Err(e) => Self::completion_code(&e),
+        };
+
+        let resp = ImportCertificateResponse::new(completion_code as u32);
+
+        // Encode the response into the MCTP payload.
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Get Certificate State command.
+    async fn handle_get_certificate_state(
+        &self,
+        msg_buf: &mut [u8],
+        _req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Get the certificate state using the unified handler.
+        let mut status = CertificateStatus::default();
+        let result = self.unified_handler.get_cert_state(&mut status).await;
+
+        // Build the response.
+        let resp = match result {
+            Ok(()) => GetCertificateStateResponse::new(
+                VdmCompletionCode::Success as u32,
+                status.state as u32,
+                status.error_details,
+                status.provisioned_slots,
+            ),
+            Err(e) => GetCertificateStateResponse::new(
+                Self::completion_code(&e) as u32,
+                status.state as u32,
+                0,
+                0,
+            ),
+        };
+
+        // Encode the response into the MCTP payload.
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Get Log command.
+    async fn handle_get_log(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode the request.
+        let req =
+            GetLogRequest::decode(&vdm_msg[..req_len]).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Read the requested chunk of the log using the unified handler.
+        let mut data = [0u8; MAX_LOG_DATA_SIZE];
+        let log_type = req.log_type;
+        let offset = req.offset;
+        let result = match LogType::try_from(log_type) {
+            Ok(log_type) => {
+                self.unified_handler
+                    .get_log(log_type, offset as usize, &mut data)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+
+        // Build the response.
+        let resp = match result {
+            Ok(len) if len <= MAX_LOG_DATA_SIZE => {
+                GetLogResponse::new(VdmCompletionCode::Success as u32, &data[..len])
+            }
+            Ok(_) => GetLogResponse::new(VdmCompletionCode::GeneralError as u32, &[]),
+            Err(e) => GetLogResponse::new(Self::completion_code(&e) as u32, &[]),
+        };
+
+        // Encode the response into the MCTP payload.
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Clear Log command.
+    async fn handle_clear_log(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode the request.
+        let req =
+            ClearLogRequest::decode(&vdm_msg[..req_len]).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Clear the log using the unified handler.
+        let log_type = req.log_type;
+        let result = match LogType::try_from(log_type) {
+            Ok(log_type) => self.unified_handler.clear_log(log_type).await,
+            Err(e) => Err(e),
+        };
+
+        // Build the response.
+        let completion_code = match result {
+            Ok(()) => VdmCompletionCode::Success,
+            Err(e) => Self::completion_code(&e),
+        };
+
+        let resp = ClearLogResponse::new(completion_code as u32);
+
+        // Encode the response into the MCTP payload.
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Request Debug Unlock command.
+    async fn handle_request_debug_unlock(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode the request.
+        let req = RequestDebugUnlockRequest::decode(&vdm_msg[..req_len])
+            .map_err(|_| VdmLibError::DecodingError)?;
+        let length = req.length;
+        if length != REQUEST_DEBUG_UNLOCK_LENGTH {
+            return self.send_error_response(
+                msg_buf,
+                VdmCommand::RequestDebugUnlock.into(),
+                VdmCompletionCode::InvalidLength,
+            );
+        }
+
+        // Request the challenge using the unified handler.
+        let mut challenge = DebugUnlockChallenge::default();
+        let unlock_level = req.unlock_level;
+        let result = if (MIN_DEBUG_UNLOCK_LEVEL..=MAX_DEBUG_UNLOCK_LEVEL).contains(&unlock_level) {
+            self.unified_handler
+                .request_debug_unlock(unlock_level, &mut challenge)
+                .await
+        } else {
+            Err(CommandError::InvalidParams)
+        };
+
+        // Build the response.
+        let resp = match result {
+            Ok(()) => RequestDebugUnlockResponse::new(
+                VdmCompletionCode::Success as u32,
+                &challenge.unique_device_identifier,
+                &challenge.challenge,
+            ),
+            Err(e) => RequestDebugUnlockResponse::new(
+                Self::completion_code(&e) as u32,
+                &[0u8; DEBUG_UNLOCK_UDI_SIZE],
+                &[0u8; DEBUG_UNLOCK_CHALLENGE_SIZE],
+            ),
+        };
+
+        // Encode the response into the MCTP payload.
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Handle Authorize Debug Unlock Token command.
+    async fn handle_authorize_debug_unlock_token(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<usize, VdmLibError> {
+        // Extract VDM message portion.
+        let vdm_msg = extract_vdm_msg(msg_buf).map_err(|_| VdmLibError::DecodingError)?;
+
+        // Decode the request header. The token chunk is used in place rather than copied.
+        let req = AuthorizeDebugUnlockTokenRequestHeader::decode(&vdm_msg[..req_len])
+            .map_err(|_| VdmLibError::DecodingError)?;
+        let header_size = core::mem::size_of::<AuthorizeDebugUnlockTokenRequestHeader>();
+        let data_size = req.data_size as usize;
+        if data_size == 0 || data_size > MAX_TOKEN_CHUNK_SIZE || header_size + data_size > req_len {
+            return self.send_error_response(
+                msg_buf,
+                VdmCommand::AuthorizeDebugUnlockToken.into(),
+                VdmCompletionCode::InvalidLength,
+            );
+        }
+
+        // Pass the token chunk to the unified handler.
+        let offset = req.offset;
+        let result = self
+            .unified_handler
+            .authorize_debug_unlock_token(
+                offset as usize,
+                &vdm_msg[header_size..header_size + data_size],
+            )
+            .await;
+
+        // Build the response.
+        let resp = match result {
+            Ok(unlock_level) => AuthorizeDebugUnlockTokenResponse::new(
+                VdmCompletionCode::Success as u32,
+                unlock_level.unwrap_or(0) as u32,
+            ),
+            Err(e) => AuthorizeDebugUnlockTokenResponse::new(Self::completion_code(&e) as u32, 0),
+        };
+
+        // Encode the response into the MCTP payload.
+        self.encode_response(msg_buf, &resp)
+    }
+
+    /// Map a unified command handler error to a VDM completion code.
+    fn completion_code(err: &CommandError) -> VdmCompletionCode {
+        match err {
+            CommandError::InvalidParams => VdmCompletionCode::InvalidData,
+            CommandError::RespLengthTooLarge => VdmCompletionCode::InvalidLength,
+            CommandError::NotSupported => VdmCompletionCode::UnsupportedCommand,
+            CommandError::Busy => VdmCompletionCode::NotReady,
+            CommandError::InternalError => VdmCompletionCode::GeneralError,
+            CommandError::AlreadyProvisioned => VdmCompletionCode::AlreadyProvisioned,
+        }
+    }
+
     /// Send an error response.
     fn send_error_response(
         &self,
@@ -82,6 +82,15 @@ pub async fn vdm_responder(
     cmd_interface: &'static mut CmdInterface<'static>,
     running: &'static AtomicBool,
 ) {
+    if let Err(e) = cmd_interface.register_endpoint_info().await {
+        writeln!(
+            Console::<DefaultSyscalls>::writer(),
+            "vdm_responder failed to register endpoint info: {:?}",
+            e
+        )
+        .unwrap();
+    }
+
     let mut msg_buffer = [0u8; MAX_VDM_MSG_SIZE];
     while running.load(Ordering::SeqCst) {
         if let Err(e) = cmd_interface.handle_responder_msg(&mut msg_buffer).await {
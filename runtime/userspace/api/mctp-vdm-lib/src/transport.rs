@@ -1,7 +1,7 @@
 // Licensed under the Apache-2.0 license
 
 use crate::error::VdmLibError;
-use libsyscall_caliptra::mctp::{driver_num, Mctp, MessageInfo};
+use libsyscall_caliptra::mctp::{driver_num, Mctp, MessageInfo, MCTP_UUID_LEN};
 use mctp_vdm_common::util::mctp_transport::{
     MctpCommonHeader, MCTP_COMMON_HEADER_OFFSET, MCTP_VDM_MSG_TYPE,
 };
@@ -101,6 +101,25 @@ impl MctpVdmTransport {
             .max_message_size()
             .map_err(|_| TransportError::DriverError)
     }
+
+    /// Set the UUID reported by the MCTP Get Endpoint UUID control command.
+    pub fn set_endpoint_uuid(&self, uuid: &[u8; MCTP_UUID_LEN]) -> Result<(), TransportError> {
+        self.mctp
+            .set_endpoint_uuid(uuid)
+            .map_err(|_| TransportError::DriverError)
+    }
+
+    /// Register a vendor defined message set reported by the
+    /// MCTP Get Vendor Defined Message Support control command.
+    pub fn register_vendor_defined_msg_support(
+        &self,
+        vendor_id: u16,
+        cmd_set_version: u16,
+    ) -> Result<(), TransportError> {
+        self.mctp
+            .register_vendor_defined_msg_support(vendor_id, cmd_set_version)
+            .map_err(|_| TransportError::DriverError)
+    }
 }
 
 impl Default for MctpVdmTransport {
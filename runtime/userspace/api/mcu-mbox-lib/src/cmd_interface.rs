@@ -4,30 +4,31 @@ use crate::transport::McuMboxTransport;
 use caliptra_api::mailbox::{CommandId as CaliptraCommandId, MailboxReqHeader};
 use core::sync::atomic::{AtomicBool, Ordering};
 use external_cmds_common::{
-    DeviceCapabilities, DeviceId, DeviceInfo, FirmwareVersion, UnifiedCommandHandler, MAX_UID_LEN,
+    DeviceCapabilities, DeviceId, DeviceInfo, FirmwareVersion, LogType, UnifiedCommandHandler,
+    MAX_UID_LEN,
 };
 use libapi_caliptra::mailbox_api::execute_mailbox_cmd;
 use libsyscall_caliptra::mailbox::Mailbox;
 use libsyscall_caliptra::mcu_mbox::MbxCmdStatus;
 use mcu_mbox_common::messages::{
-    CommandId, DeviceCapsReq, DeviceCapsResp, DeviceIdReq, DeviceIdResp, DeviceInfoReq,
-    DeviceInfoResp, FirmwareVersionReq, FirmwareVersionResp, MailboxRespHeader,
-    MailboxRespHeaderVarSize, McuAesDecryptInitReq, McuAesDecryptInitResp, McuAesDecryptUpdateReq,
-    McuAesDecryptUpdateResp, McuAesEncryptInitReq, McuAesEncryptInitResp, McuAesEncryptUpdateReq,
-    McuAesEncryptUpdateResp, McuAesGcmDecryptFinalReq, McuAesGcmDecryptFinalResp,
-    McuAesGcmDecryptInitReq, McuAesGcmDecryptInitResp, McuAesGcmDecryptUpdateReq,
-    McuAesGcmDecryptUpdateResp, McuAesGcmEncryptFinalReq, McuAesGcmEncryptFinalResp,
-    McuAesGcmEncryptInitReq, McuAesGcmEncryptInitResp, McuAesGcmEncryptUpdateReq,
-    McuAesGcmEncryptUpdateResp, McuCmDeleteReq, McuCmDeleteResp, McuCmImportReq, McuCmImportResp,
-    McuCmStatusReq, McuCmStatusResp, McuEcdhFinishReq, McuEcdhFinishResp, McuEcdhGenerateReq,
-    McuEcdhGenerateResp, McuEcdsaCmkPublicKeyReq, McuEcdsaCmkPublicKeyResp, McuEcdsaCmkSignReq,
-    McuEcdsaCmkSignResp, McuEcdsaCmkVerifyReq, McuEcdsaCmkVerifyResp, McuFipsSelfTestGetResultsReq,
-    McuFipsSelfTestGetResultsResp, McuFipsSelfTestStartReq, McuFipsSelfTestStartResp,
-    McuHkdfExpandReq, McuHkdfExpandResp, McuHkdfExtractReq, McuHkdfExtractResp,
-    McuHmacKdfCounterReq, McuHmacKdfCounterResp, McuHmacReq, McuHmacResp, McuMailboxResp,
-    McuRandomGenerateReq, McuRandomGenerateResp, McuRandomStirReq, McuRandomStirResp,
-    McuShaFinalReq, McuShaFinalResp, McuShaInitReq, McuShaInitResp, McuShaUpdateReq,
-    DEVICE_CAPS_SIZE, MAX_FW_VERSION_STR_LEN,
+    ClearLogReq, ClearLogResp, CommandId, DeviceCapsReq, DeviceCapsResp, DeviceIdReq, DeviceIdResp,
+    DeviceInfoReq, DeviceInfoResp, FirmwareVersionReq, FirmwareVersionResp, GetLogReq, GetLogResp,
+    MailboxRespHeader, MailboxRespHeaderVarSize, McuAesDecryptInitReq, McuAesDecryptInitResp,
+    McuAesDecryptUpdateReq, McuAesDecryptUpdateResp, McuAesEncryptInitReq, McuAesEncryptInitResp,
+    McuAesEncryptUpdateReq, McuAesEncryptUpdateResp, McuAesGcmDecryptFinalReq,
+    McuAesGcmDecryptFinalResp, McuAesGcmDecryptInitReq, McuAesGcmDecryptInitResp,
+    McuAesGcmDecryptUpdateReq, McuAesGcmDecryptUpdateResp, McuAesGcmEncryptFinalReq,
+    McuAesGcmEncryptFinalResp, McuAesGcmEncryptInitReq, McuAesGcmEncryptInitResp,
+    McuAesGcmEncryptUpdateReq, McuAesGcmEncryptUpdateResp, McuCmDeleteReq, McuCmDeleteResp,
+    McuCmImportReq, McuCmImportResp, McuCmStatusReq, McuCmStatusResp, McuEcdhFinishReq,
+    McuEcdhFinishResp, McuEcdhGenerateReq, McuEcdhGenerateResp, McuEcdsaCmkPublicKeyReq,
+    McuEcdsaCmkPublicKeyResp, McuEcdsaCmkSignReq, McuEcdsaCmkSignResp, McuEcdsaCmkVerifyReq,
+    McuEcdsaCmkVerifyResp, McuFipsSelfTestGetResultsReq, McuFipsSelfTestGetResultsResp,
+    McuFipsSelfTestStartReq, McuFipsSelfTestStartResp, McuHkdfExpandReq, McuHkdfExpandResp,
+    McuHkdfExtractReq, McuHkdfExtractResp, McuHmacKdfCounterReq, McuHmacKdfCounterResp, McuHmacReq,
+    McuHmacResp, McuMailboxResp, McuRandomGenerateReq, McuRandomGenerateResp, McuRandomStirReq,
+    McuRandomStirResp, McuShaFinalReq, McuShaFinalResp, McuShaInitReq, McuShaInitResp,
+    McuShaUpdateReq, DEVICE_CAPS_SIZE, MAX_FW_VERSION_STR_LEN, MAX_RESP_DATA_SIZE,
 };
 #[cfg(feature = "periodic-fips-self-test")]
 use mcu_mbox_common::messages::{
@@ -113,6 +114,8 @@ impl<'a> CmdInterface<'a> {
             CommandId::MC_DEVICE_CAPABILITIES => self.handle_device_caps(msg_buf, req_len).await,
             CommandId::MC_DEVICE_ID => self.handle_device_id(msg_buf, req_len).await,
             CommandId::MC_DEVICE_INFO => self.handle_device_info(msg_buf, req_len).await,
+            CommandId::MC_GET_LOG => self.handle_get_log(msg_buf, req_len).await,
+            CommandId::MC_CLEAR_LOG => self.handle_clear_log(msg_buf, req_len).await,
             CommandId::MC_FIPS_SELF_TEST_START => {
                 let mut resp_bytes = [0u8; core::mem::size_of::<McuFipsSelfTestStartResp>()];
                 self.handle_crypto_passthrough::<McuFipsSelfTestStartReq>(
@@ -621,6 +624,96 @@ impl<'a> CmdInterface<'a> {
         Ok((resp_bytes.len(), mbox_cmd_status))
     }
 
+    async fn handle_get_log(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<(usize, MbxCmdStatus), MsgHandlerError> {
+        // Decode the request
+        let req = GetLogReq::ref_from_bytes(&msg_buf[..req_len])
+            .map_err(|_| MsgHandlerError::InvalidParams)?;
+        let log_type = req.log_type;
+        let offset = req.offset as usize;
+
+        // Read as much of the log as fits in the response.
+        let mut log_resp = GetLogResp::default();
+        let max_len = MAX_RESP_DATA_SIZE.min(
+            msg_buf
+                .len()
+                .saturating_sub(core::mem::size_of::<MailboxRespHeaderVarSize>()),
+        );
+        let ret = match LogType::try_from(log_type) {
+            Ok(log_type) => {
+                self.non_crypto_cmds_handler
+                    .get_log(log_type, offset, &mut log_resp.data[..max_len])
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+
+        let mbox_cmd_status = match ret {
+            Ok(len) if len <= max_len => {
+                log_resp.hdr.data_len = len as u32;
+                MbxCmdStatus::Complete
+            }
+            _ => {
+                log_resp = GetLogResp::default();
+                MbxCmdStatus::Failure
+            }
+        };
+
+        let mut resp = McuMailboxResp::GetLog(log_resp);
+
+        // Populate the checksum for response
+        resp.populate_chksum()
+            .map_err(|_| MsgHandlerError::McuMboxCommon)?;
+
+        // Encode the response and copy to msg_buf.
+        let resp_bytes = resp
+            .as_bytes()
+            .map_err(|_| MsgHandlerError::McuMboxCommon)?;
+
+        msg_buf[..resp_bytes.len()].copy_from_slice(resp_bytes);
+
+        Ok((resp_bytes.len(), mbox_cmd_status))
+    }
+
+    async fn handle_clear_log(
+        &self,
+        msg_buf: &mut [u8],
+        req_len: usize,
+    ) -> Result<(usize, MbxCmdStatus), MsgHandlerError> {
+        // Decode the request
+        let req = ClearLogReq::ref_from_bytes(&msg_buf[..req_len])
+            .map_err(|_| MsgHandlerError::InvalidParams)?;
+
+        let ret = match LogType::try_from(req.log_type) {
+            Ok(log_type) => self.non_crypto_cmds_handler.clear_log(log_type).await,
+            Err(e) => Err(e),
+        };
+
+        let mbox_cmd_status = if ret.is_ok() {
+            MbxCmdStatus::Complete
+        } else {
+            MbxCmdStatus::Failure
+        };
+
+        let mut resp = McuMailboxResp::ClearLog(ClearLogResp::default());
+
+        // Populate the checksum for response
+        resp.populate_chksum()
+            .map_err(|_| MsgHandlerError::McuMboxCommon)?;
+
+        // Encode the response and copy to msg_buf.
+        let resp_bytes = resp
+            .as_bytes()
+            .map_err(|_| MsgHandlerError::McuMboxCommon)?;
+
+        msg_buf[..resp_bytes.len()].copy_from_slice(resp_bytes);
+
+        Ok((resp_bytes.len(), mbox_cmd_status))
+    }
+
     pub async fn handle_crypto_passthrough<T: Default + IntoBytes + FromBytes>(
         &self,
         msg_buf: &mut [u8],
@@ -3,13 +3,19 @@
 use crate::control_context::{ControlContext, CtrlCmdResponder, ProtocolCapability};
 use crate::error::MsgHandlerError;
 use crate::firmware_device::fd_context::FirmwareDeviceContext;
+use crate::fru::fru_context::FruContext;
+use crate::platform::platform_context::PlatformContext;
 use crate::transport::MctpTransport;
 use core::sync::atomic::{AtomicBool, Ordering};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::{Mutex, MutexGuard};
 use pldm_common::codec::PldmCodec;
 use pldm_common::protocol::base::{
     PldmBaseCompletionCode, PldmControlCmd, PldmFailureResponse, PldmMsgHeader, PldmSupportedType,
 };
 use pldm_common::protocol::firmware_update::FwUpdateCmd;
+use pldm_common::protocol::fru::FruCmd;
+use pldm_common::protocol::platform::PlatformCmd;
 use pldm_common::util::mctp_transport::{
     construct_mctp_pldm_msg, extract_pldm_msg, PLDM_MSG_OFFSET,
 };
@@ -32,7 +38,12 @@ pub(crate) fn generate_failure_response(
 pub struct CmdInterface<'a> {
     ctrl_ctx: ControlContext<'a>,
     fd_ctx: FirmwareDeviceContext<'a>,
+    platform_ctx: Option<PlatformContext<'a>>,
+    fru_ctx: Option<FruContext<'a>>,
     busy: AtomicBool,
+    // The MCTP driver waits for a single response at a time, so the requests of the
+    // initiator and the event sender are serialized
+    requester: Mutex<CriticalSectionRawMutex, ()>,
 }
 
 impl<'a> CmdInterface<'a> {
@@ -44,7 +55,39 @@ impl<'a> CmdInterface<'a> {
         Self {
             ctrl_ctx,
             fd_ctx,
+            platform_ctx: None,
+            fru_ctx: None,
             busy: AtomicBool::new(false),
+            requester: Mutex::new(()),
+        }
+    }
+
+    /// Creates a command interface that also handles the PLDM for Platform Monitoring and
+    /// Control commands. `protocol_capabilities` should include the platform type.
+    pub fn new_with_platform(
+        protocol_capabilities: &'a [ProtocolCapability],
+        fd_ctx: FirmwareDeviceContext<'a>,
+        platform_ctx: PlatformContext<'a>,
+    ) -> Self {
+        Self {
+            platform_ctx: Some(platform_ctx),
+            ..Self::new(protocol_capabilities, fd_ctx)
+        }
+    }
+
+    /// Creates a command interface that also handles the PLDM for FRU Data commands, and the
+    /// PLDM for Platform Monitoring and Control commands when `platform_ctx` is provided.
+    /// `protocol_capabilities` should include the FRU type.
+    pub fn new_with_fru(
+        protocol_capabilities: &'a [ProtocolCapability],
+        fd_ctx: FirmwareDeviceContext<'a>,
+        platform_ctx: Option<PlatformContext<'a>>,
+        fru_ctx: FruContext<'a>,
+    ) -> Self {
+        Self {
+            platform_ctx,
+            fru_ctx: Some(fru_ctx),
+            ..Self::new(protocol_capabilities, fd_ctx)
         }
     }
 
@@ -87,6 +130,8 @@ impl<'a> CmdInterface<'a> {
             return Ok(());
         }
 
+        let _guard = self.lock_requester().await;
+
         // Send the request
         transport
             .send_request(ua_eid, &msg_buf[..req_len + reserved_len])
@@ -94,12 +139,14 @@ impl<'a> CmdInterface<'a> {
             .map_err(MsgHandlerError::Transport)?;
 
         // Wait for and process the response
-        transport
+        let rsp_len = transport
             .receive_response(msg_buf)
             .await
             .map_err(MsgHandlerError::Transport)?;
 
-        let payload = extract_pldm_msg(msg_buf).map_err(MsgHandlerError::Util)?;
+        // Bound the payload by the received length, as some responses carry a variable-length
+        // portion of data.
+        let payload = extract_pldm_msg(&mut msg_buf[..rsp_len]).map_err(MsgHandlerError::Util)?;
 
         // Handle the response
         self.fd_ctx.handle_response(payload).await?;
@@ -107,6 +154,60 @@ impl<'a> CmdInterface<'a> {
         Ok(())
     }
 
+    /// Sends the next platform event to the event receiver, if any, and handles its response.
+    pub async fn handle_platform_event(
+        &self,
+        transport: &mut MctpTransport,
+        msg_buf: &mut [u8],
+    ) -> Result<(), MsgHandlerError> {
+        let Some(platform_ctx) = &self.platform_ctx else {
+            return Ok(());
+        };
+
+        // Prepare the request payload
+        let payload = construct_mctp_pldm_msg(msg_buf).map_err(MsgHandlerError::Util)?;
+        let reserved_len = PLDM_MSG_OFFSET;
+
+        // Generate the event message
+        let Some((receiver_eid, req_len)) = platform_ctx
+            .generate_event(self.ctrl_ctx.get_tid(), payload)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let guard = self.lock_requester().await;
+
+        // Send the request
+        transport
+            .send_request(receiver_eid, &msg_buf[..req_len + reserved_len])
+            .await
+            .map_err(MsgHandlerError::Transport)?;
+
+        // Wait for the response, the event is generated again on the next poll if none comes
+        let rsp_len = transport
+            .receive_response_with_timeout(
+                msg_buf,
+                crate::config::PLATFORM_EVENT_RESPONSE_TIMEOUT_MS,
+            )
+            .await
+            .map_err(MsgHandlerError::Transport)?;
+        drop(guard);
+
+        let payload = extract_pldm_msg(&mut msg_buf[..rsp_len]).map_err(MsgHandlerError::Util)?;
+
+        platform_ctx.handle_event_response(payload).await
+    }
+
+    /// Locks the requester side of the MCTP transport for a request/response exchange.
+    pub async fn lock_requester(&self) -> MutexGuard<'_, CriticalSectionRawMutex, ()> {
+        self.requester.lock().await
+    }
+
+    pub fn has_platform(&self) -> bool {
+        self.platform_ctx.is_some()
+    }
+
     pub async fn should_start_initiator_mode(&self) -> bool {
         self.fd_ctx.should_start_initiator_mode().await
     }
@@ -115,6 +216,10 @@ impl<'a> CmdInterface<'a> {
         self.fd_ctx.should_stop_initiator_mode().await
     }
 
+    pub async fn is_downloading(&self) -> bool {
+        self.fd_ctx.is_downloading().await
+    }
+
     /// Check if the current transfer has been cancelled.
     pub fn is_cancelled(&self) -> bool {
         self.fd_ctx.is_cancelled()
@@ -168,6 +273,8 @@ impl<'a> CmdInterface<'a> {
         let resp_len = match pldm_type {
             PldmSupportedType::Base => self.process_control_cmd(cmd_opcode, payload),
             PldmSupportedType::FwUpdate => self.process_fw_update_cmd(cmd_opcode, payload).await,
+            PldmSupportedType::Platform => self.process_platform_cmd(cmd_opcode, payload).await,
+            PldmSupportedType::Fru => self.process_fru_cmd(cmd_opcode, payload),
             _ => {
                 unreachable!()
             }
@@ -213,6 +320,9 @@ impl<'a> CmdInterface<'a> {
                 }
                 FwUpdateCmd::RequestUpdate => self.fd_ctx.request_update_rsp(payload).await,
                 FwUpdateCmd::PassComponentTable => self.fd_ctx.pass_component_rsp(payload).await,
+                FwUpdateCmd::GetDeviceMetaData => {
+                    self.fd_ctx.get_device_metadata_rsp(payload).await
+                }
                 FwUpdateCmd::UpdateComponent => self.fd_ctx.update_component_rsp(payload).await,
 
                 FwUpdateCmd::ActivateFirmware => self.fd_ctx.activate_firmware_rsp(payload).await,
@@ -232,6 +342,65 @@ impl<'a> CmdInterface<'a> {
         }
     }
 
+    async fn process_platform_cmd(
+        &self,
+        cmd_opcode: u8,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        let Some(platform_ctx) = &self.platform_ctx else {
+            return generate_failure_response(
+                payload,
+                PldmBaseCompletionCode::InvalidPldmType as u8,
+            );
+        };
+
+        match PlatformCmd::try_from(cmd_opcode) {
+            Ok(cmd) => match cmd {
+                PlatformCmd::SetEventReceiver => platform_ctx.set_event_receiver_rsp(payload).await,
+                PlatformCmd::GetSensorReading => platform_ctx.get_sensor_reading_rsp(payload).await,
+                PlatformCmd::GetStateSensorReadings => {
+                    platform_ctx.get_state_sensor_readings_rsp(payload).await
+                }
+                PlatformCmd::GetPdrRepositoryInfo => {
+                    platform_ctx.get_pdr_repository_info_rsp(payload).await
+                }
+                PlatformCmd::GetPdr => platform_ctx.get_pdr_rsp(payload).await,
+                _ => generate_failure_response(
+                    payload,
+                    PldmBaseCompletionCode::UnsupportedPldmCmd as u8,
+                ),
+            },
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::UnsupportedPldmCmd as u8)
+            }
+        }
+    }
+
+    fn process_fru_cmd(
+        &self,
+        cmd_opcode: u8,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        let Some(fru_ctx) = &self.fru_ctx else {
+            return generate_failure_response(
+                payload,
+                PldmBaseCompletionCode::InvalidPldmType as u8,
+            );
+        };
+
+        match FruCmd::try_from(cmd_opcode) {
+            Ok(cmd) => match cmd {
+                FruCmd::GetFruRecordTableMetadata => {
+                    fru_ctx.get_fru_record_table_metadata_rsp(payload)
+                }
+                FruCmd::GetFruRecordTable => fru_ctx.get_fru_record_table_rsp(payload),
+            },
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::UnsupportedPldmCmd as u8)
+            }
+        }
+    }
+
     fn preprocess_request(
         &self,
         payload: &[u8],
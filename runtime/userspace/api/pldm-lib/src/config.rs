@@ -4,47 +4,119 @@ use crate::control_context::ProtocolCapability;
 use embassy_sync::lazy_lock::LazyLock;
 use pldm_common::protocol::base::{PldmControlCmd, PldmSupportedType};
 use pldm_common::protocol::firmware_update::{FwUpdateCmd, PldmFdTime};
+use pldm_common::protocol::fru::FruCmd;
+use pldm_common::protocol::platform::PlatformCmd;
 
 pub const PLDM_PROTOCOL_CAP_COUNT: usize = 2;
+pub const PLDM_PLATFORM_PROTOCOL_CAP_COUNT: usize = 3;
+pub const PLDM_FRU_PROTOCOL_CAP_COUNT: usize = 3;
+pub const PLDM_PLATFORM_FRU_PROTOCOL_CAP_COUNT: usize = 4;
 pub const FD_MAX_XFER_SIZE: usize = 512; // Arbitrary limit and change as needed.
+pub const FD_METADATA_MAX_XFER_SIZE: usize = 256; // Maximum metadata in a GetDeviceMetaData response.
 pub const DEFAULT_FD_T1_TIMEOUT: PldmFdTime = 120000; // FD_T1 update mode idle timeout, range is [60s, 120s].
 pub const DEFAULT_FD_T2_RETRY_TIME: PldmFdTime = 5000; // FD_T2 retry request for firmware data, range is [1s, 5s].
 pub const INSTANCE_ID_COUNT: u8 = 32;
 pub const UA_EID: u8 = 8; // Update Agent Endpoint ID for testing.
+pub const PLATFORM_MAX_NUMERIC_SENSORS: usize = 8; // Arbitrary limit, change as needed
+pub const PLATFORM_MAX_STATE_SENSORS: usize = 8; // Arbitrary limit, change as needed
+pub const PLATFORM_MAX_PDR_XFER_SIZE: usize = 256; // Maximum record data in a GetPDR response.
+pub const PLATFORM_EVENT_POLL_INTERVAL_MS: u32 = 1000; // Sensor polling period for event generation.
+pub const PLATFORM_EVENT_RESPONSE_TIMEOUT_MS: u32 = 1000; // Time the event receiver has to respond.
+pub const FRU_MAX_TABLE_SIZE: usize = 512; // Arbitrary limit, change as needed
+pub const FRU_MAX_RECORD_FIELDS: usize = 16; // Arbitrary limit, change as needed
+pub const FRU_MAX_XFER_SIZE: usize = 256; // Maximum table data in a GetFRURecordTable response.
+
+const BASE_PROTOCOL_CAPABILITY: ProtocolCapability<'static> = ProtocolCapability {
+    pldm_type: PldmSupportedType::Base,
+    protocol_version: 0xF1F1F000, //"1.1.0"
+    supported_commands: &[
+        PldmControlCmd::SetTid as u8,
+        PldmControlCmd::GetTid as u8,
+        PldmControlCmd::GetPldmCommands as u8,
+        PldmControlCmd::GetPldmVersion as u8,
+        PldmControlCmd::GetPldmTypes as u8,
+    ],
+};
+
+const FW_UPDATE_PROTOCOL_CAPABILITY: ProtocolCapability<'static> = ProtocolCapability {
+    pldm_type: PldmSupportedType::FwUpdate,
+    protocol_version: 0xF1F3F000, // "1.3.0"
+    supported_commands: &[
+        FwUpdateCmd::QueryDeviceIdentifiers as u8,
+        FwUpdateCmd::GetFirmwareParameters as u8,
+        FwUpdateCmd::RequestUpdate as u8,
+        FwUpdateCmd::PassComponentTable as u8,
+        FwUpdateCmd::UpdateComponent as u8,
+        FwUpdateCmd::RequestFirmwareData as u8,
+        FwUpdateCmd::TransferComplete as u8,
+        FwUpdateCmd::VerifyComplete as u8,
+        FwUpdateCmd::ApplyComplete as u8,
+        FwUpdateCmd::ActivateFirmware as u8,
+        FwUpdateCmd::GetStatus as u8,
+        FwUpdateCmd::CancelUpdateComponent as u8,
+        FwUpdateCmd::CancelUpdate as u8,
+        FwUpdateCmd::GetPackageData as u8,
+        FwUpdateCmd::GetDeviceMetaData as u8,
+        FwUpdateCmd::GetMetaData as u8,
+    ],
+};
+
+const PLATFORM_PROTOCOL_CAPABILITY: ProtocolCapability<'static> = ProtocolCapability {
+    pldm_type: PldmSupportedType::Platform,
+    protocol_version: 0xF1F2F000, // "1.2.0"
+    supported_commands: &[
+        PlatformCmd::SetEventReceiver as u8,
+        PlatformCmd::GetSensorReading as u8,
+        PlatformCmd::GetStateSensorReadings as u8,
+        PlatformCmd::GetPdrRepositoryInfo as u8,
+        PlatformCmd::GetPdr as u8,
+    ],
+};
+
+const FRU_PROTOCOL_CAPABILITY: ProtocolCapability<'static> = ProtocolCapability {
+    pldm_type: PldmSupportedType::Fru,
+    protocol_version: 0xF1F0F000, // "1.0.0"
+    supported_commands: &[
+        FruCmd::GetFruRecordTableMetadata as u8,
+        FruCmd::GetFruRecordTable as u8,
+    ],
+};
 
 pub static PLDM_PROTOCOL_CAPABILITIES: LazyLock<
     [ProtocolCapability<'static>; PLDM_PROTOCOL_CAP_COUNT],
+> = LazyLock::new(|| [BASE_PROTOCOL_CAPABILITY, FW_UPDATE_PROTOCOL_CAPABILITY]);
+
+/// Capabilities of a terminus that also supports PLDM for Platform Monitoring and Control.
+pub static PLDM_PLATFORM_PROTOCOL_CAPABILITIES: LazyLock<
+    [ProtocolCapability<'static>; PLDM_PLATFORM_PROTOCOL_CAP_COUNT],
+> = LazyLock::new(|| {
+    [
+        BASE_PROTOCOL_CAPABILITY,
+        FW_UPDATE_PROTOCOL_CAPABILITY,
+        PLATFORM_PROTOCOL_CAPABILITY,
+    ]
+});
+
+/// Capabilities of a terminus that also supports PLDM for FRU Data.
+pub static PLDM_FRU_PROTOCOL_CAPABILITIES: LazyLock<
+    [ProtocolCapability<'static>; PLDM_FRU_PROTOCOL_CAP_COUNT],
+> = LazyLock::new(|| {
+    [
+        BASE_PROTOCOL_CAPABILITY,
+        FW_UPDATE_PROTOCOL_CAPABILITY,
+        FRU_PROTOCOL_CAPABILITY,
+    ]
+});
+
+/// Capabilities of a terminus that also supports PLDM for Platform Monitoring and Control and
+/// PLDM for FRU Data.
+pub static PLDM_PLATFORM_FRU_PROTOCOL_CAPABILITIES: LazyLock<
+    [ProtocolCapability<'static>; PLDM_PLATFORM_FRU_PROTOCOL_CAP_COUNT],
 > = LazyLock::new(|| {
     [
-        ProtocolCapability {
-            pldm_type: PldmSupportedType::Base,
-            protocol_version: 0xF1F1F000, //"1.1.0"
-            supported_commands: &[
-                PldmControlCmd::SetTid as u8,
-                PldmControlCmd::GetTid as u8,
-                PldmControlCmd::GetPldmCommands as u8,
-                PldmControlCmd::GetPldmVersion as u8,
-                PldmControlCmd::GetPldmTypes as u8,
-            ],
-        },
-        ProtocolCapability {
-            pldm_type: PldmSupportedType::FwUpdate,
-            protocol_version: 0xF1F3F000, // "1.3.0"
-            supported_commands: &[
-                FwUpdateCmd::QueryDeviceIdentifiers as u8,
-                FwUpdateCmd::GetFirmwareParameters as u8,
-                FwUpdateCmd::RequestUpdate as u8,
-                FwUpdateCmd::PassComponentTable as u8,
-                FwUpdateCmd::UpdateComponent as u8,
-                FwUpdateCmd::RequestFirmwareData as u8,
-                FwUpdateCmd::TransferComplete as u8,
-                FwUpdateCmd::VerifyComplete as u8,
-                FwUpdateCmd::ApplyComplete as u8,
-                FwUpdateCmd::ActivateFirmware as u8,
-                FwUpdateCmd::GetStatus as u8,
-                FwUpdateCmd::CancelUpdateComponent as u8,
-                FwUpdateCmd::CancelUpdate as u8,
-            ],
-        },
+        BASE_PROTOCOL_CAPABILITY,
+        FW_UPDATE_PROTOCOL_CAPABILITY,
+        PLATFORM_PROTOCOL_CAPABILITY,
+        FRU_PROTOCOL_CAPABILITY,
     ]
 });
@@ -5,6 +5,8 @@ use crate::config;
 use crate::firmware_device::fd_context::FirmwareDeviceContext;
 use crate::firmware_device::fd_ops::FdOps;
 use crate::firmware_device::transfer_session::TransferSession;
+use crate::fru::fru_context::FruContext;
+use crate::platform::platform_context::PlatformContext;
 use crate::timer::AsyncAlarm;
 use crate::transport::MctpTransport;
 use core::fmt::Write;
@@ -14,6 +16,7 @@ use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::signal::Signal;
 use libsyscall_caliptra::mctp::driver_num;
 use libsyscall_caliptra::DefaultSyscalls;
+use libtock_alarm::Milliseconds;
 use libtock_console::Console;
 use pldm_common::codec::PldmCodec;
 use pldm_common::message::firmware_update::request_fw_data::{
@@ -78,6 +81,66 @@ impl<'a> PldmService<'a> {
         }
     }
 
+    /// Initializes a PLDM service that also responds to the PLDM for Platform Monitoring and
+    /// Control commands and reports sensor events to the event receiver.
+    pub fn init_with_platform(
+        fdops: &'a dyn FdOps,
+        platform_ctx: PlatformContext<'a>,
+        spawner: Spawner,
+    ) -> Self {
+        let cmd_interface = CmdInterface::new_with_platform(
+            config::PLDM_PLATFORM_PROTOCOL_CAPABILITIES.get(),
+            FirmwareDeviceContext::new(fdops),
+            platform_ctx,
+        );
+        Self {
+            spawner,
+            cmd_interface,
+            running: {
+                static RUNNING: AtomicBool = AtomicBool::new(false);
+                &RUNNING
+            },
+            initiator_signal: {
+                static INITIATOR_SIGNAL: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+                &INITIATOR_SIGNAL
+            },
+        }
+    }
+
+    /// Initializes a PLDM service that also responds to the PLDM for FRU Data commands. When
+    /// `platform_ctx` is provided, the service also handles platform monitoring and control as
+    /// with `init_with_platform`.
+    pub fn init_with_fru(
+        fdops: &'a dyn FdOps,
+        platform_ctx: Option<PlatformContext<'a>>,
+        fru_ctx: FruContext<'a>,
+        spawner: Spawner,
+    ) -> Self {
+        let protocol_capabilities: &'static [_] = if platform_ctx.is_some() {
+            config::PLDM_PLATFORM_FRU_PROTOCOL_CAPABILITIES.get()
+        } else {
+            config::PLDM_FRU_PROTOCOL_CAPABILITIES.get()
+        };
+        let cmd_interface = CmdInterface::new_with_fru(
+            protocol_capabilities,
+            FirmwareDeviceContext::new(fdops),
+            platform_ctx,
+            fru_ctx,
+        );
+        Self {
+            spawner,
+            cmd_interface,
+            running: {
+                static RUNNING: AtomicBool = AtomicBool::new(false);
+                &RUNNING
+            },
+            initiator_signal: {
+                static INITIATOR_SIGNAL: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+                &INITIATOR_SIGNAL
+            },
+        }
+    }
+
     pub async fn start(&mut self) -> Result<(), PldmServiceError> {
         if self.running.load(Ordering::SeqCst) {
             return Err(PldmServiceError::StartError);
@@ -103,6 +166,12 @@ impl<'a> PldmService<'a> {
                 self.initiator_signal,
             ))
             .unwrap();
+
+        if cmd_interface.has_platform() {
+            self.spawner
+                .spawn(pldm_event_task(cmd_interface, self.running))
+                .unwrap();
+        }
         Ok(())
     }
 
@@ -129,6 +198,14 @@ pub async fn pldm_responder_task(
     pldm_responder(cmd_interface, running, initiator_signal).await;
 }
 
+#[embassy_executor::task]
+pub async fn pldm_event_task(
+    cmd_interface: &'static CmdInterface<'static>,
+    running: &'static AtomicBool,
+) {
+    pldm_event_sender(cmd_interface, running).await;
+}
+
 pub async fn pldm_initiator(
     cmd_interface: &'static CmdInterface<'static>,
     running: &'static AtomicBool,
@@ -198,7 +275,7 @@ pub async fn pldm_initiator(
                         // After successful handling, check if we should switch to optimized download
                         // The regular handler will have processed the first chunk; now create session
                         // for subsequent chunks if we're still in download phase
-                        if cmd_interface.should_start_initiator_mode().await {
+                        if cmd_interface.is_downloading().await {
                             session = Some(cmd_interface.create_transfer_session().await);
                             counter = 0;
                         }
@@ -246,13 +323,41 @@ pub async fn pldm_responder(
             }
         }
 
-        // When FD state is download state, signal the initiator task
+        // When FD state is download state or the FD needs package data or metadata from the UA,
+        // signal the initiator task
         if cmd_interface.should_start_initiator_mode().await && !initiator_signal.signaled() {
             initiator_signal.signal(());
         }
     }
 }
 
+pub async fn pldm_event_sender(
+    cmd_interface: &'static CmdInterface<'static>,
+    running: &'static AtomicBool,
+) {
+    let mut transport = MctpTransport::new(driver_num::MCTP_PLDM);
+
+    let mut msg_buffer = [0; MAX_MCTP_PLDM_MSG_SIZE];
+    let mut console_writer = Console::<DefaultSyscalls>::writer();
+
+    while running.load(Ordering::SeqCst) {
+        if let Err(e) = cmd_interface
+            .handle_platform_event(&mut transport, &mut msg_buffer)
+            .await
+        {
+            writeln!(
+                console_writer,
+                "PLDM_APP: Error handling platform event: {:?}",
+                e
+            )
+            .unwrap();
+        }
+
+        AsyncAlarm::<DefaultSyscalls>::sleep(Milliseconds(config::PLATFORM_EVENT_POLL_INTERVAL_MS))
+            .await;
+    }
+}
+
 /// Optimized download loop that uses a local TransferSession to minimize mutex acquisitions.
 ///
 /// This function runs the download phase with the session state kept outside the async mutex,
@@ -327,6 +432,8 @@ async fn run_optimized_download(
     // Mark as sent
     session.mark_sent(cmd_interface.now(), FwUpdateCmd::RequestFirmwareData as u8);
 
+    let _guard = cmd_interface.lock_requester().await;
+
     // Send request
     transport
         .send_request(ua_eid, &msg_buffer[..msg_len + PLDM_MSG_OFFSET])
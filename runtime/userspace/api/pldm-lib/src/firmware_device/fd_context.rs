@@ -1,16 +1,26 @@
 // Licensed under the Apache-2.0 license
 
 use crate::cmd_interface::generate_failure_response;
+use crate::config::FD_METADATA_MAX_XFER_SIZE;
 use crate::error::MsgHandlerError;
 use crate::firmware_device::fd_internal::{FdInternal, FdReqState};
-use crate::firmware_device::fd_ops::{ComponentOperation, FdOps};
+use crate::firmware_device::fd_ops::{ComponentOperation, FdOps, FdOpsError};
 use pldm_common::codec::PldmCodec;
 use pldm_common::message::firmware_update::activate_fw::{
-    ActivateFirmwareRequest, ActivateFirmwareResponse,
+    ActivateFirmwareRequest, ActivateFirmwareResponse, SelfContainedActivationRequest,
+};
+use pldm_common::message::firmware_update::get_device_metadata::{
+    GetDeviceMetaDataRequest, GetDeviceMetaDataResponse,
 };
 use pldm_common::message::firmware_update::get_fw_params::{
     FirmwareParameters, GetFirmwareParametersRequest, GetFirmwareParametersResponse,
 };
+use pldm_common::message::firmware_update::get_metadata::{
+    GetMetaDataRequest, GetMetaDataResponseFixed,
+};
+use pldm_common::message::firmware_update::get_package_data::{
+    GetPackageDataRequest, GetPackageDataResponseFixed,
+};
 use pldm_common::message::firmware_update::get_status::ProgressPercent;
 use pldm_common::message::firmware_update::pass_component::{
     PassComponentTableRequest, PassComponentTableResponse,
@@ -23,7 +33,7 @@ use pldm_common::message::firmware_update::request_cancel::{
     CancelUpdateResponse,
 };
 use pldm_common::message::firmware_update::request_update::{
-    RequestUpdateRequest, RequestUpdateResponse,
+    FdWillSendGetPackageData, RequestUpdateRequest, RequestUpdateResponse,
 };
 use pldm_common::message::firmware_update::transfer_complete::{
     TransferCompleteRequest, TransferResult,
@@ -43,18 +53,18 @@ use pldm_common::message::firmware_update::request_fw_data::{
 };
 use pldm_common::message::firmware_update::verify_complete::{VerifyCompleteRequest, VerifyResult};
 use pldm_common::protocol::base::{
-    PldmBaseCompletionCode, PldmMsgHeader, PldmMsgType, TransferRespFlag,
+    PldmBaseCompletionCode, PldmMsgHeader, PldmMsgType, TransferOperationFlag, TransferRespFlag,
 };
 use pldm_common::protocol::firmware_update::{
     ComponentActivationMethods, ComponentCompatibilityResponse, ComponentCompatibilityResponseCode,
     ComponentResponse, ComponentResponseCode, Descriptor, FirmwareDeviceState, FwUpdateCmd,
     FwUpdateCompletionCode, PldmFirmwareString, UpdateOptionFlags, MAX_DESCRIPTORS_COUNT,
-    PLDM_FWUP_BASELINE_TRANSFER_SIZE,
+    PLDM_FWUP_BASELINE_TRANSFER_SIZE, PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN,
 };
 use pldm_common::util::fw_component::FirmwareComponent;
 
 use crate::firmware_device::fd_internal::{
-    ApplyState, DownloadState, InitiatorModeState, VerifyState,
+    ApplyState, DataXferState, DownloadState, InitiatorModeState, VerifyState,
 };
 use crate::firmware_device::transfer_session::CancellationFlag;
 
@@ -116,6 +126,7 @@ impl<'a> FirmwareDeviceContext<'a> {
         self.ops
             .get_firmware_parms(&mut firmware_params)
             .map_err(MsgHandlerError::FdOps)?;
+        self.set_pending_firmware_params(&mut firmware_params).await;
 
         // Construct response
         let resp = GetFirmwareParametersResponse::new(
@@ -164,17 +175,65 @@ impl<'a> FirmwareDeviceContext<'a> {
         // Set transfer size to the internal state
         self.internal.set_xfer_size(fd_transfer_size).await;
 
-        // Construct response, no metadata or package data.
-        let resp = RequestUpdateResponse::new(
-            req.fixed.hdr.instance_id(),
-            PldmBaseCompletionCode::Success as u8,
-            0,
-            0,
-            None,
-        );
+        let fd_meta_data_len = u16::try_from(self.ops.get_device_metadata_len())
+            .map_err(|_| MsgHandlerError::FdOps(FdOpsError::MetaDataError))?;
+        let pkg_data_len = req.fixed.pkg_data_len as usize;
+        let will_get_pkg_data = pkg_data_len > 0 && self.ops.will_get_package_data(pkg_data_len);
+
+        // The policy of the device may only accept packages with authenticated package data
+        if !will_get_pkg_data
+            && self
+                .ops
+                .package_data_policy()
+                .require_authenticated_package_data
+        {
+            return generate_failure_response(
+                payload,
+                FwUpdateCompletionCode::UnableToInitiateUpdate as u8,
+            );
+        }
+
+        // Construct response
+        let resp = if will_get_pkg_data {
+            RequestUpdateResponse::new(
+                req.fixed.hdr.instance_id(),
+                PldmBaseCompletionCode::Success as u8,
+                fd_meta_data_len,
+                FdWillSendGetPackageData::YesWithMaxTransferSize as u8,
+                Some(fd_transfer_size as u32),
+            )
+        } else {
+            RequestUpdateResponse::new(
+                req.fixed.hdr.instance_id(),
+                PldmBaseCompletionCode::Success as u8,
+                fd_meta_data_len,
+                FdWillSendGetPackageData::No as u8,
+                None,
+            )
+        };
 
         match resp.encode(payload) {
             Ok(bytes) => {
+                self.internal
+                    .set_comp_image_set_ver(&req.get_comp_image_set_ver_str())
+                    .await;
+
+                // Retrieve the package data, then the metadata saved before a reset, from the UA
+                if will_get_pkg_data {
+                    self.start_data_xfer(InitiatorModeState::PackageData(DataXferState::default()))
+                        .await;
+                } else if self.ops.is_metadata_restore_pending() {
+                    self.start_data_xfer(InitiatorModeState::MetaData(DataXferState::default()))
+                        .await;
+                } else {
+                    self.internal
+                        .set_initiator_mode(InitiatorModeState::Download(DownloadState::default()))
+                        .await;
+                    self.internal
+                        .set_fd_req(FdReqState::Unused, false, None, None, None, None)
+                        .await;
+                }
+
                 // Move FD state to 'LearnComponents'
                 self.internal
                     .set_fd_state(FirmwareDeviceState::LearnComponents)
@@ -270,6 +329,100 @@ impl<'a> FirmwareDeviceContext<'a> {
         }
     }
 
+    pub async fn get_device_metadata_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        // Check if FD is in 'LearnComponents' state. Otherwise returns 'INVALID_STATE' completion code
+        if self.internal.get_fd_state().await != FirmwareDeviceState::LearnComponents {
+            return generate_failure_response(
+                payload,
+                FwUpdateCompletionCode::InvalidStateForCommand as u8,
+            );
+        }
+
+        // Set timestamp for FD T1 timeout
+        self.set_fd_t1_ts().await;
+
+        // Decode the request message
+        let req = match GetDeviceMetaDataRequest::decode(payload) {
+            Ok(req) => req,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    PldmBaseCompletionCode::InvalidLength as u8,
+                )
+            }
+        };
+
+        let op_flag = match TransferOperationFlag::try_from(req.transfer_operation_flag) {
+            Ok(flag) => flag,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    FwUpdateCompletionCode::InvalidTransferOperationFlag as u8,
+                )
+            }
+        };
+
+        let metadata_len = self.ops.get_device_metadata_len();
+        if metadata_len == 0 {
+            return generate_failure_response(
+                payload,
+                FwUpdateCompletionCode::NoDeviceMetadata as u8,
+            );
+        }
+
+        // The data transfer handle is the offset of the next portion within the metadata
+        let offset = match op_flag {
+            TransferOperationFlag::GetFirstPart => 0,
+            TransferOperationFlag::GetNextPart => {
+                let offset = req.data_transfer_handle as usize;
+                if offset == 0 || offset >= metadata_len {
+                    return generate_failure_response(
+                        payload,
+                        FwUpdateCompletionCode::InvalidTransferHandle as u8,
+                    );
+                }
+                offset
+            }
+        };
+
+        let mut metadata = [0u8; FD_METADATA_MAX_XFER_SIZE];
+        let max_len = (metadata_len - offset).min(FD_METADATA_MAX_XFER_SIZE);
+        let len = self
+            .ops
+            .get_device_metadata(offset, &mut metadata[..max_len])
+            .map_err(MsgHandlerError::FdOps)?;
+        if len == 0 || len > max_len {
+            return Err(MsgHandlerError::FdOps(FdOpsError::MetaDataError));
+        }
+
+        let end = offset + len;
+        let is_last = end == metadata_len;
+        let transfer_flag = match (offset == 0, is_last) {
+            (true, true) => TransferRespFlag::StartAndEnd,
+            (true, false) => TransferRespFlag::Start,
+            (false, false) => TransferRespFlag::Middle,
+            (false, true) => TransferRespFlag::End,
+        };
+
+        let resp = GetDeviceMetaDataResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            if is_last { 0 } else { end as u32 },
+            transfer_flag,
+            &metadata[..len],
+        );
+
+        match resp.encode(payload) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+
     pub async fn update_component_rsp(&self, payload: &mut [u8]) -> Result<usize, MsgHandlerError> {
         // Check if FD is in 'ReadyTransfer' state. Otherwise returns 'INVALID_STATE' completion code
         if self.internal.get_fd_state().await != FirmwareDeviceState::ReadyXfer {
@@ -375,24 +528,21 @@ impl<'a> FirmwareDeviceContext<'a> {
 
         // Decode the request message
         let req = ActivateFirmwareRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
-        let self_contained = req.self_contained_activation_req;
-
-        // Validate self_contained value
-        match self_contained {
-            0 | 1 => {}
-            _ => {
-                return generate_failure_response(
-                    payload,
-                    PldmBaseCompletionCode::InvalidData as u8,
-                )
-            }
-        }
+        let Ok(self_contained) =
+            SelfContainedActivationRequest::try_from(req.self_contained_activation_req)
+        else {
+            return generate_failure_response(payload, PldmBaseCompletionCode::InvalidData as u8);
+        };
 
         let mut estimated_time = 0u16;
         let completion_code = self
             .ops
             .activate(self_contained, &mut estimated_time)
             .map_err(MsgHandlerError::FdOps)?;
+        if self_contained == SelfContainedActivationRequest::NotActivateSelfContainedComponents {
+            // The estimated time only applies to self-contained activation
+            estimated_time = 0;
+        }
 
         // Construct response
         let resp =
@@ -406,11 +556,18 @@ impl<'a> FirmwareDeviceContext<'a> {
                     self.internal
                         .set_fd_state(FirmwareDeviceState::Activate)
                         .await;
-                    if self_contained == 0 {
-                        // If activation is not self-contained, then consider the FW already activated
-                        self.internal
-                            .set_fd_idle(GetStatusReasonCode::ActivateFw)
-                            .await;
+                    match self_contained {
+                        SelfContainedActivationRequest::ActivateSelfContainedComponents => {
+                            // The pending components are activated by the device itself
+                            self.internal.clear_pending_components().await;
+                        }
+                        SelfContainedActivationRequest::NotActivateSelfContainedComponents => {
+                            // The applied components stay pending until they are activated
+                            // by other means, e.g. a reset of the device
+                            self.internal
+                                .set_fd_idle(GetStatusReasonCode::ActivateFw)
+                                .await;
+                        }
                     }
                 }
                 Ok(bytes)
@@ -654,7 +811,7 @@ impl<'a> FirmwareDeviceContext<'a> {
     }
 
     pub async fn should_start_initiator_mode(&self) -> bool {
-        self.internal.get_fd_state().await == FirmwareDeviceState::Download
+        self.is_downloading().await || self.internal.is_fd_data_xfer_pending().await
     }
 
     pub async fn should_stop_initiator_mode(&self) -> bool {
@@ -663,13 +820,21 @@ impl<'a> FirmwareDeviceContext<'a> {
             FirmwareDeviceState::Download
                 | FirmwareDeviceState::Verify
                 | FirmwareDeviceState::Apply
-        )
+        ) && !self.internal.is_fd_data_xfer_pending().await
+    }
+
+    /// Returns true if the FD is downloading a component.
+    pub async fn is_downloading(&self) -> bool {
+        self.internal.get_fd_state().await == FirmwareDeviceState::Download
     }
 
     pub async fn fd_progress(&self, payload: &mut [u8]) -> Result<usize, MsgHandlerError> {
         let fd_state = self.internal.get_fd_state().await;
 
         let result = match fd_state {
+            _ if self.internal.is_fd_data_xfer_pending().await => {
+                self.fd_progress_data_xfer(payload).await
+            }
             FirmwareDeviceState::Download => self.fd_progress_download(payload).await,
             FirmwareDeviceState::Verify => self.pldm_fd_progress_verify(payload).await,
             FirmwareDeviceState::Apply => self.pldm_fd_progress_apply(payload).await,
@@ -717,6 +882,9 @@ impl<'a> FirmwareDeviceContext<'a> {
             Ok(FwUpdateCmd::TransferComplete) => self.process_transfer_complete_rsp(payload).await,
             Ok(FwUpdateCmd::VerifyComplete) => self.process_verify_complete_rsp(payload).await,
             Ok(FwUpdateCmd::ApplyComplete) => self.process_apply_complete_rsp(payload).await,
+            Ok(FwUpdateCmd::GetPackageData) | Ok(FwUpdateCmd::GetMetaData) => {
+                self.process_data_xfer_rsp(payload).await
+            }
             _ => Err(MsgHandlerError::FdInitiatorModeError),
         }
     }
@@ -878,6 +1046,9 @@ impl<'a> FirmwareDeviceContext<'a> {
         }
 
         if fd_req.result == Some(ApplyResult::ApplySuccess as u8) {
+            // The component is reported as pending until it is activated
+            self.internal.add_pending_component().await;
+
             // Switch to Xfer
             self.internal
                 .set_fd_req(FdReqState::Unused, false, None, None, None, None)
@@ -895,6 +1066,194 @@ impl<'a> FirmwareDeviceContext<'a> {
         Ok(())
     }
 
+    async fn process_data_xfer_rsp(&self, payload: &mut [u8]) -> Result<(), MsgHandlerError> {
+        let (cmd, _, offset) = self
+            .internal
+            .get_fd_data_xfer_state()
+            .await
+            .ok_or(MsgHandlerError::FdInitiatorModeError)?;
+
+        // Both responses share the same layout
+        let (completion_code, next_data_transfer_handle, transfer_flag, data_offset) = match cmd {
+            FwUpdateCmd::GetPackageData => {
+                let rsp =
+                    GetPackageDataResponseFixed::decode(payload).map_err(MsgHandlerError::Codec)?;
+                (
+                    rsp.completion_code,
+                    rsp.next_data_transfer_handle,
+                    rsp.transfer_flag,
+                    core::mem::size_of::<GetPackageDataResponseFixed>(),
+                )
+            }
+            _ => {
+                let rsp =
+                    GetMetaDataResponseFixed::decode(payload).map_err(MsgHandlerError::Codec)?;
+                (
+                    rsp.completion_code,
+                    rsp.next_data_transfer_handle,
+                    rsp.transfer_flag,
+                    core::mem::size_of::<GetMetaDataResponseFixed>(),
+                )
+            }
+        };
+
+        let fd_req = self.internal.get_fd_req().await;
+        if completion_code != PldmBaseCompletionCode::Success as u8 {
+            // Wait for UA to cancel
+            self.internal
+                .set_fd_req(
+                    FdReqState::Failed,
+                    true,
+                    Some(completion_code),
+                    fd_req.instance_id,
+                    None,
+                    None,
+                )
+                .await;
+            return Ok(());
+        }
+
+        let is_last = match TransferRespFlag::try_from(transfer_flag) {
+            Ok(TransferRespFlag::Start) if offset == 0 => false,
+            Ok(TransferRespFlag::Middle) if offset != 0 => false,
+            Ok(TransferRespFlag::StartAndEnd) if offset == 0 => true,
+            Ok(TransferRespFlag::End) if offset != 0 => true,
+            _ => return Err(MsgHandlerError::FdInitiatorModeError),
+        };
+
+        let data = &payload[data_offset..];
+        match cmd {
+            FwUpdateCmd::GetPackageData => self
+                .ops
+                .handle_package_data(offset as usize, data, is_last)
+                .await
+                .map_err(MsgHandlerError::FdOps)?,
+            _ => self
+                .ops
+                .restore_metadata(offset as usize, data, is_last)
+                .await
+                .map_err(MsgHandlerError::FdOps)?,
+        }
+
+        if is_last
+            && matches!(cmd, FwUpdateCmd::GetPackageData)
+            && self
+                .ops
+                .package_data_policy()
+                .require_authenticated_package_data
+            && self.ops.verify_package_data().await.is_err()
+        {
+            // Wait for UA to cancel
+            self.internal
+                .set_fd_req(
+                    FdReqState::Failed,
+                    true,
+                    Some(FwUpdateCompletionCode::PackageDataError as u8),
+                    fd_req.instance_id,
+                    None,
+                    None,
+                )
+                .await;
+            return Ok(());
+        }
+
+        if !is_last {
+            // Request the next portion
+            self.internal
+                .set_fd_data_xfer_state(next_data_transfer_handle, offset + data.len() as u32)
+                .await;
+            self.internal
+                .set_fd_req(
+                    FdReqState::Ready,
+                    false,
+                    None,
+                    fd_req.instance_id,
+                    None,
+                    None,
+                )
+                .await;
+        } else if matches!(cmd, FwUpdateCmd::GetPackageData)
+            && self.ops.is_metadata_restore_pending()
+        {
+            self.start_data_xfer(InitiatorModeState::MetaData(DataXferState::default()))
+                .await;
+        } else {
+            // Done, wait for the UA to pass the components
+            self.internal
+                .set_initiator_mode(InitiatorModeState::Download(DownloadState::default()))
+                .await;
+            self.internal
+                .set_fd_req(
+                    FdReqState::Unused,
+                    false,
+                    None,
+                    fd_req.instance_id,
+                    None,
+                    None,
+                )
+                .await;
+        }
+
+        Ok(())
+    }
+
+    async fn start_data_xfer(&self, mode: InitiatorModeState) {
+        let instance_id = self.internal.get_fd_req().await.instance_id;
+        self.internal.set_initiator_mode(mode).await;
+        self.internal
+            .set_fd_req(FdReqState::Ready, false, None, instance_id, None, None)
+            .await;
+    }
+
+    async fn fd_progress_data_xfer(&self, payload: &mut [u8]) -> Result<usize, MsgHandlerError> {
+        if !self.should_send_fd_request().await {
+            return Ok(0);
+        }
+
+        let (cmd, data_transfer_handle, offset) = self
+            .internal
+            .get_fd_data_xfer_state()
+            .await
+            .ok_or(MsgHandlerError::FdInitiatorModeError)?;
+        let op_flag = if offset == 0 {
+            TransferOperationFlag::GetFirstPart
+        } else {
+            TransferOperationFlag::GetNextPart
+        };
+
+        let instance_id = self.internal.alloc_next_instance_id().await.unwrap();
+        let msg_len = match cmd {
+            FwUpdateCmd::GetPackageData => GetPackageDataRequest::new(
+                instance_id,
+                PldmMsgType::Request,
+                data_transfer_handle,
+                op_flag,
+            )
+            .encode(payload),
+            _ => GetMetaDataRequest::new(
+                instance_id,
+                PldmMsgType::Request,
+                data_transfer_handle,
+                op_flag,
+            )
+            .encode(payload),
+        }
+        .map_err(MsgHandlerError::Codec)?;
+
+        self.internal
+            .set_fd_req(
+                FdReqState::Sent,
+                false,
+                None,
+                Some(instance_id),
+                Some(cmd as u8),
+                Some(self.ops.now()),
+            )
+            .await;
+
+        Ok(msg_len)
+    }
+
     async fn fd_progress_download(&self, payload: &mut [u8]) -> Result<usize, MsgHandlerError> {
         // Get offset and length from ops first (this is async but outside the batch)
         // We need to do this before the batch because query_download_offset_and_length
@@ -1073,6 +1432,44 @@ impl<'a> FirmwareDeviceContext<'a> {
         Ok(msg_len)
     }
 
+    // Reports the components applied but not activated yet as the pending images.
+    async fn set_pending_firmware_params(&self, firmware_params: &mut FirmwareParameters) {
+        let pending = self.internal.get_pending_components().await;
+        if pending.is_empty() {
+            return;
+        }
+
+        let image_set_ver = &pending.comp_image_set_ver;
+        firmware_params
+            .params_fixed
+            .pending_comp_image_set_ver_str_type = image_set_ver.str_type;
+        firmware_params
+            .params_fixed
+            .pending_comp_image_set_ver_str_len = image_set_ver.str_len;
+        firmware_params.pending_comp_image_set_ver_str = Some(image_set_ver.str_data);
+
+        let comp_count = firmware_params.params_fixed.comp_count as usize;
+        for entry in firmware_params.comp_param_table.iter_mut().take(comp_count) {
+            let fixed = &mut entry.comp_param_entry_fixed;
+            let Some(comp) = pending.find(
+                fixed.comp_classification,
+                fixed.comp_identifier,
+                fixed.comp_classification_index,
+            ) else {
+                continue;
+            };
+            fixed.pending_comp_comparison_stamp = comp.comp_comparison_stamp;
+            fixed.pending_comp_ver_str_type = comp.comp_version.str_type;
+            fixed.pending_comp_ver_str_len = comp.comp_version.str_len;
+            // The release date of the component is not part of UpdateComponent
+            fixed.pending_comp_release_date = Default::default();
+            let mut ver_str = [0u8; PLDM_FWUP_IMAGE_SET_VER_STR_MAX_LEN];
+            let len = comp.comp_version.str_len as usize;
+            ver_str[..len].copy_from_slice(&comp.comp_version.str_data[..len]);
+            entry.pending_comp_ver_str = Some(ver_str);
+        }
+    }
+
     async fn should_send_fd_request(&self) -> bool {
         let now = self.ops.now();
 
@@ -1126,3 +1523,258 @@ impl<'a> FirmwareDeviceContext<'a> {
         self.ops
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::firmware_device::fd_ops::PackageDataPolicy;
+    use async_trait::async_trait;
+    use core::cell::Cell;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use pldm_common::message::firmware_update::get_package_data::GetPackageDataResponse;
+    use pldm_common::protocol::base::PldmFailureResponse;
+    use pldm_common::protocol::firmware_update::PldmFdTime;
+
+    const PACKAGE_DATA: [u8; 16] = [0xA5; 16];
+    const UA_TRANSFER_SIZE: u32 = 64;
+
+    /// Polls the future once. Panics if the future waits, which only a syscall does.
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        match fut.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future is waiting on a syscall"),
+        }
+    }
+
+    /// Operations of a device that only accepts packages with authenticated package data.
+    struct PackageDataFdOps {
+        package_data_valid: bool,
+        package_data_len: Cell<usize>,
+    }
+
+    impl PackageDataFdOps {
+        fn new(package_data_valid: bool) -> Self {
+            Self {
+                package_data_valid,
+                package_data_len: Cell::new(0),
+            }
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl FdOps for PackageDataFdOps {
+        fn get_device_identifiers(
+            &self,
+            _device_identifiers: &mut [Descriptor],
+        ) -> Result<usize, FdOpsError> {
+            Ok(0)
+        }
+
+        fn get_firmware_parms(
+            &self,
+            _firmware_params: &mut FirmwareParameters,
+        ) -> Result<(), FdOpsError> {
+            Ok(())
+        }
+
+        async fn get_xfer_size(&self, ua_transfer_size: usize) -> Result<usize, FdOpsError> {
+            Ok(ua_transfer_size)
+        }
+
+        fn handle_component(
+            &self,
+            _component: &FirmwareComponent,
+            _fw_params: &FirmwareParameters,
+            _op: ComponentOperation,
+        ) -> Result<ComponentResponseCode, FdOpsError> {
+            Ok(ComponentResponseCode::CompCanBeUpdated)
+        }
+
+        async fn query_download_offset_and_length(
+            &self,
+            _component: &FirmwareComponent,
+        ) -> Result<(usize, usize), FdOpsError> {
+            Err(FdOpsError::FwDownloadError)
+        }
+
+        async fn download_fw_data(
+            &self,
+            _offset: usize,
+            _data: &[u8],
+            _component: &FirmwareComponent,
+        ) -> Result<TransferResult, FdOpsError> {
+            Err(FdOpsError::FwDownloadError)
+        }
+
+        fn is_download_complete(&self, _component: &FirmwareComponent) -> bool {
+            false
+        }
+
+        fn query_download_progress(
+            &self,
+            _component: &FirmwareComponent,
+            _progress_percent: &mut ProgressPercent,
+        ) -> Result<(), FdOpsError> {
+            Ok(())
+        }
+
+        async fn verify(
+            &self,
+            _component: &FirmwareComponent,
+            _progress_percent: &mut ProgressPercent,
+        ) -> Result<VerifyResult, FdOpsError> {
+            Err(FdOpsError::VerifyError)
+        }
+
+        async fn apply(
+            &self,
+            _component: &FirmwareComponent,
+            _progress_percent: &mut ProgressPercent,
+        ) -> Result<ApplyResult, FdOpsError> {
+            Err(FdOpsError::ApplyError)
+        }
+
+        fn activate(
+            &self,
+            _self_contained_activation: SelfContainedActivationRequest,
+            _estimated_time: &mut u16,
+        ) -> Result<u8, FdOpsError> {
+            Err(FdOpsError::ActivateError)
+        }
+
+        fn cancel_update_component(
+            &self,
+            _component: &FirmwareComponent,
+        ) -> Result<(), FdOpsError> {
+            Ok(())
+        }
+
+        fn will_get_package_data(&self, pkg_data_len: usize) -> bool {
+            pkg_data_len <= PACKAGE_DATA.len()
+        }
+
+        async fn handle_package_data(
+            &self,
+            offset: usize,
+            data: &[u8],
+            _is_last: bool,
+        ) -> Result<(), FdOpsError> {
+            self.package_data_len.set(offset + data.len());
+            Ok(())
+        }
+
+        fn package_data_policy(&self) -> PackageDataPolicy {
+            PackageDataPolicy {
+                require_authenticated_package_data: true,
+            }
+        }
+
+        async fn verify_package_data(&self) -> Result<(), FdOpsError> {
+            if self.package_data_valid && self.package_data_len.get() == PACKAGE_DATA.len() {
+                Ok(())
+            } else {
+                Err(FdOpsError::PackageDataError)
+            }
+        }
+
+        fn now(&self) -> PldmFdTime {
+            0
+        }
+    }
+
+    fn request_update(ctx: &FirmwareDeviceContext, pkg_data_len: u16) -> u8 {
+        let image_set_ver = PldmFirmwareString::new("UTF-8", "1.0.0").unwrap();
+        let mut payload = [0u8; 128];
+        RequestUpdateRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            UA_TRANSFER_SIZE,
+            1,
+            1,
+            pkg_data_len,
+            &image_set_ver,
+        )
+        .encode(&mut payload)
+        .unwrap();
+        let len = block_on(ctx.request_update_rsp(&mut payload)).unwrap();
+        PldmFailureResponse::decode(&payload[..len])
+            .unwrap()
+            .completion_code
+    }
+
+    /// Sends the GetPackageData request of the FD and returns the package data to it.
+    fn transfer_package_data(ctx: &FirmwareDeviceContext) {
+        let mut payload = [0u8; 128];
+        let len = block_on(ctx.fd_progress(&mut payload)).unwrap();
+        let req = GetPackageDataRequest::decode(&payload[..len]).unwrap();
+        assert_eq!(
+            req.transfer_operation_flag,
+            TransferOperationFlag::GetFirstPart as u8
+        );
+        let len = GetPackageDataResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            0,
+            TransferRespFlag::StartAndEnd,
+            &PACKAGE_DATA,
+        )
+        .encode(&mut payload)
+        .unwrap();
+        block_on(ctx.handle_response(&mut payload[..len])).unwrap();
+    }
+
+    #[test]
+    fn test_authenticated_package_data_required() {
+        let ops = PackageDataFdOps::new(true);
+        let ctx = FirmwareDeviceContext::new(&ops);
+
+        // A package without package data for the device is rejected
+        assert_eq!(
+            request_update(&ctx, 0),
+            FwUpdateCompletionCode::UnableToInitiateUpdate as u8
+        );
+        assert!(!block_on(ctx.internal.is_update_mode()));
+
+        // So is a package whose package data the device does not retrieve
+        assert_eq!(
+            request_update(&ctx, PACKAGE_DATA.len() as u16 + 1),
+            FwUpdateCompletionCode::UnableToInitiateUpdate as u8
+        );
+
+        assert_eq!(
+            request_update(&ctx, PACKAGE_DATA.len() as u16),
+            PldmBaseCompletionCode::Success as u8
+        );
+        transfer_package_data(&ctx);
+        assert_eq!(ops.package_data_len.get(), PACKAGE_DATA.len());
+
+        // The package data is authenticated, so the FD goes on with the update
+        let fd_req = block_on(ctx.internal.get_fd_req());
+        assert_eq!(fd_req.state, FdReqState::Unused);
+        assert!(!block_on(ctx.internal.is_fd_data_xfer_pending()));
+    }
+
+    #[test]
+    fn test_unauthenticated_package_data() {
+        let ops = PackageDataFdOps::new(false);
+        let ctx = FirmwareDeviceContext::new(&ops);
+
+        assert_eq!(
+            request_update(&ctx, PACKAGE_DATA.len() as u16),
+            PldmBaseCompletionCode::Success as u8
+        );
+        transfer_package_data(&ctx);
+
+        // The update stops with PACKAGE_DATA_ERROR until the UA cancels it
+        let fd_req = block_on(ctx.internal.get_fd_req());
+        assert_eq!(fd_req.state, FdReqState::Failed);
+        assert_eq!(
+            fd_req.result,
+            Some(FwUpdateCompletionCode::PackageDataError as u8)
+        );
+        assert!(!block_on(ctx.internal.is_fd_data_xfer_pending()));
+    }
+}
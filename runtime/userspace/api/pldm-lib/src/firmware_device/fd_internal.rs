@@ -5,7 +5,8 @@ use embassy_sync::blocking_mutex::raw::NoopRawMutex;
 use embassy_sync::mutex::Mutex;
 use pldm_common::message::firmware_update::get_status::GetStatusReasonCode;
 use pldm_common::protocol::firmware_update::{
-    FirmwareDeviceState, PldmFdTime, UpdateOptionFlags, PLDM_FWUP_MAX_PADDING_SIZE,
+    FirmwareDeviceState, FwUpdateCmd, PldmFdTime, PldmFirmwareString, UpdateOptionFlags,
+    MAX_COMPONENT_COUNT, PLDM_FWUP_MAX_PADDING_SIZE,
 };
 use pldm_common::util::fw_component::FirmwareComponent;
 
@@ -49,6 +50,12 @@ pub struct FdInternalInner {
     // Mode-specific data for the requester.
     initiator_mode_state: InitiatorModeState,
 
+    // Component image set version of the current update, set by `RequestUpdate`.
+    comp_image_set_ver: PldmFirmwareString,
+
+    // Components applied but not activated yet, reported by `GetFirmwareParameters`.
+    pending_comps: PendingComponents,
+
     // Address of the Update Agent (UA).
     _ua_address: Option<Tid>,
 
@@ -275,6 +282,68 @@ impl FdInternal {
         inner.initiator_mode_state = mode;
     }
 
+    /// Returns the command, next data transfer handle and received length of the package data
+    /// or metadata transfer, if the FD is retrieving either from the UA.
+    pub async fn get_fd_data_xfer_state(&self) -> Option<(FwUpdateCmd, u32, u32)> {
+        let inner = self.inner.lock().await;
+        match &inner.initiator_mode_state {
+            InitiatorModeState::PackageData(xfer) => Some((
+                FwUpdateCmd::GetPackageData,
+                xfer.data_transfer_handle,
+                xfer.offset,
+            )),
+            InitiatorModeState::MetaData(xfer) => Some((
+                FwUpdateCmd::GetMetaData,
+                xfer.data_transfer_handle,
+                xfer.offset,
+            )),
+            _ => None,
+        }
+    }
+
+    pub async fn set_fd_data_xfer_state(&self, data_transfer_handle: u32, offset: u32) {
+        let mut inner = self.inner.lock().await;
+        if let InitiatorModeState::PackageData(xfer) | InitiatorModeState::MetaData(xfer) =
+            &mut inner.initiator_mode_state
+        {
+            xfer.data_transfer_handle = data_transfer_handle;
+            xfer.offset = offset;
+        }
+    }
+
+    /// Returns true while a package data or metadata transfer has requests left to send.
+    pub async fn is_fd_data_xfer_pending(&self) -> bool {
+        let inner = self.inner.lock().await;
+        matches!(
+            inner.initiator_mode_state,
+            InitiatorModeState::PackageData(_) | InitiatorModeState::MetaData(_)
+        ) && matches!(inner.req.state, FdReqState::Ready | FdReqState::Sent)
+    }
+
+    pub async fn set_comp_image_set_ver(&self, comp_image_set_ver: &PldmFirmwareString) {
+        let mut inner = self.inner.lock().await;
+        inner.comp_image_set_ver = comp_image_set_ver.clone();
+    }
+
+    /// Records the component being updated as pending activation, under the component image set
+    /// version of the current update.
+    pub async fn add_pending_component(&self) {
+        let mut inner = self.inner.lock().await;
+        let inner = &mut *inner;
+        inner.pending_comps.comp_image_set_ver = inner.comp_image_set_ver.clone();
+        inner.pending_comps.add(&inner.update_comp);
+    }
+
+    pub async fn get_pending_components(&self) -> PendingComponents {
+        let inner = self.inner.lock().await;
+        inner.pending_comps.clone()
+    }
+
+    pub async fn clear_pending_components(&self) {
+        let mut inner = self.inner.lock().await;
+        inner.pending_comps = PendingComponents::default();
+    }
+
     pub async fn set_fd_verify_progress(&self, progress: u8) {
         let mut inner = self.inner.lock().await;
         if let InitiatorModeState::Verify(verify) = &mut inner.initiator_mode_state {
@@ -481,6 +550,8 @@ impl FdInternalInner {
             max_xfer_size,
             req: FdReq::new(),
             initiator_mode_state: InitiatorModeState::Download(DownloadState::default()),
+            comp_image_set_ver: PldmFirmwareString::default(),
+            pending_comps: PendingComponents::default(),
             _ua_address: None,
             fd_t1_update_ts: 0,
             fd_t1_timeout,
@@ -544,11 +615,21 @@ impl FdReq {
 
 #[derive(Debug)]
 pub enum InitiatorModeState {
+    PackageData(DataXferState),
+    MetaData(DataXferState),
     Download(DownloadState),
     Verify(VerifyState),
     Apply(ApplyState),
 }
 
+#[derive(Debug, Default)]
+pub struct DataXferState {
+    // Handle of the next portion, as returned by the UA.
+    pub data_transfer_handle: u32,
+    // Number of bytes received so far.
+    pub offset: u32,
+}
+
 #[derive(Debug, Default)]
 pub struct DownloadState {
     pub offset: u32,
@@ -564,3 +645,62 @@ pub struct VerifyState {
 pub struct ApplyState {
     pub progress_percent: u8,
 }
+
+/// Components that were applied but not activated yet.
+#[derive(Clone, Default)]
+pub struct PendingComponents {
+    // Component image set version of the update that applied the components.
+    pub comp_image_set_ver: PldmFirmwareString,
+    comps: [FirmwareComponent; MAX_COMPONENT_COUNT],
+    count: usize,
+}
+
+impl PendingComponents {
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the pending component with the given classification, identifier and
+    /// classification index, if any.
+    pub fn find(
+        &self,
+        comp_classification: u16,
+        comp_identifier: u16,
+        comp_classification_index: u8,
+    ) -> Option<&FirmwareComponent> {
+        self.position(
+            comp_classification,
+            comp_identifier,
+            comp_classification_index,
+        )
+        .map(|index| &self.comps[index])
+    }
+
+    // A component applied again replaces its previous pending image.
+    fn add(&mut self, comp: &FirmwareComponent) {
+        let index = self
+            .position(
+                comp.comp_classification,
+                comp.comp_identifier,
+                comp.comp_classification_index,
+            )
+            .unwrap_or(self.count);
+        if index < MAX_COMPONENT_COUNT {
+            self.comps[index] = comp.clone();
+            self.count = self.count.max(index + 1);
+        }
+    }
+
+    fn position(
+        &self,
+        comp_classification: u16,
+        comp_identifier: u16,
+        comp_classification_index: u8,
+    ) -> Option<usize> {
+        self.comps[..self.count].iter().position(|comp| {
+            comp.comp_classification == comp_classification
+                && comp.comp_identifier == comp_identifier
+                && comp.comp_classification_index == comp_classification_index
+        })
+    }
+}
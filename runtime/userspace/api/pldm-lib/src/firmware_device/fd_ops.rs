@@ -4,6 +4,7 @@ extern crate alloc;
 use alloc::boxed::Box;
 use async_trait::async_trait;
 use libsyscall_caliptra::DefaultSyscalls;
+use pldm_common::message::firmware_update::activate_fw::SelfContainedActivationRequest;
 use pldm_common::message::firmware_update::apply_complete::ApplyResult;
 use pldm_common::message::firmware_update::get_status::ProgressPercent;
 use pldm_common::message::firmware_update::request_cancel::{
@@ -30,6 +31,8 @@ pub enum FdOpsError {
     ApplyError,
     ActivateError,
     CancelUpdateError,
+    PackageDataError,
+    MetaDataError,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -38,6 +41,18 @@ pub enum ComponentOperation {
     UpdateComponent,
 }
 
+/// Requirements of the firmware device on the package data of the update packages it accepts.
+///
+/// The device does not receive the package header, so the signature area of a package is never
+/// checked on the device. The policy only covers the FirmwareDevicePackageData, which the package
+/// vendor signs for the device.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PackageDataPolicy {
+    /// Rejects the update if the package has no package data for the device, or if the package
+    /// data is not authenticated by `FdOps::verify_package_data`.
+    pub require_authenticated_package_data: bool,
+}
+
 /// Trait for firmware device-specific operations.
 ///
 /// This trait defines asynchronous methods for performing various firmware device operations,
@@ -205,9 +220,12 @@ pub trait FdOps {
     ///
     /// # Arguments
     ///
-    /// * `self_contained_activation` - Indicates if self-contained activation is requested.
+    /// * `self_contained_activation` - Indicates if the components that support self-contained
+    ///   activation should be activated now. Otherwise, the applied components stay pending and
+    ///   are reported as such in GetFirmwareParameters until they are activated.
     /// * `estimated_time` - A mutable reference to store the estimated time (in seconds)
-    ///   required to perform self-activation. This may be left as `None` if not needed.
+    ///   required to perform self-activation. It is only reported to the UA when self-contained
+    ///   activation is requested.
     ///
     /// # Returns
     ///
@@ -218,7 +236,7 @@ pub trait FdOps {
     /// have been updated. If not, it should return `PLDM_FWUP_INCOMPLETE_UPDATE`.
     fn activate(
         &self,
-        self_contained_activation: u8,
+        self_contained_activation: SelfContainedActivationRequest,
         estimated_time: &mut u16,
     ) -> Result<u8, FdOpsError>;
 
@@ -258,6 +276,115 @@ pub trait FdOps {
         ))
     }
 
+    /// Indicates whether the device will retrieve the package data advertised by the UA in the
+    /// RequestUpdate request with GetPackageData before learning the components.
+    ///
+    /// # Arguments
+    ///
+    /// * `pkg_data_len` - The length in bytes of the FirmwareDevicePackageData in the package.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Returns `true` if the device will retrieve the package data, otherwise `false`.
+    fn will_get_package_data(&self, _pkg_data_len: usize) -> bool {
+        false
+    }
+
+    /// Handles a portion of the package data received from the UA.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The offset in bytes of the portion within the package data.
+    /// * `data` - A slice of bytes representing the portion of the package data.
+    /// * `is_last` - Indicates if this is the last portion of the package data.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), FdOpsError>` - On success, returns `Ok(())`. On failure, returns an `FdOpsError`.
+    async fn handle_package_data(
+        &self,
+        _offset: usize,
+        _data: &[u8],
+        _is_last: bool,
+    ) -> Result<(), FdOpsError> {
+        Err(FdOpsError::PackageDataError)
+    }
+
+    /// Retrieves the requirements of the device on the package data of the packages it accepts.
+    ///
+    /// # Returns
+    ///
+    /// * `PackageDataPolicy` - The policy checked on RequestUpdate and on the package data.
+    fn package_data_policy(&self) -> PackageDataPolicy {
+        PackageDataPolicy::default()
+    }
+
+    /// Authenticates the package data once it is fully received. Only called when the policy
+    /// of the device requires authenticated package data.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), FdOpsError>` - On success, returns `Ok(())`. On failure, returns an `FdOpsError`
+    ///   and the update is not continued.
+    async fn verify_package_data(&self) -> Result<(), FdOpsError> {
+        Err(FdOpsError::PackageDataError)
+    }
+
+    /// Retrieves the length of the device metadata that the UA should save with GetDeviceMetaData
+    /// and return with GetMetaData after the device is reset to activate new firmware.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The length in bytes of the device metadata, or 0 if the device has none.
+    fn get_device_metadata_len(&self) -> usize {
+        0
+    }
+
+    /// Retrieves a portion of the device metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The offset in bytes of the portion within the device metadata.
+    /// * `data` - A mutable slice of bytes to store the portion of the device metadata.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, FdOpsError>` - On success, returns the number of bytes copied to `data`.
+    ///   On failure, returns an `FdOpsError`.
+    fn get_device_metadata(&self, _offset: usize, _data: &mut [u8]) -> Result<usize, FdOpsError> {
+        Err(FdOpsError::MetaDataError)
+    }
+
+    /// Indicates whether the device was reset during an update and needs the device metadata it
+    /// reported before the reset, which it retrieves from the UA with GetMetaData.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Returns `true` if the device metadata should be retrieved, otherwise `false`.
+    fn is_metadata_restore_pending(&self) -> bool {
+        false
+    }
+
+    /// Handles a portion of the device metadata returned by the UA.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The offset in bytes of the portion within the device metadata.
+    /// * `data` - A slice of bytes representing the portion of the device metadata.
+    /// * `is_last` - Indicates if this is the last portion of the device metadata.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), FdOpsError>` - On success, returns `Ok(())`. On failure, returns an `FdOpsError`.
+    async fn restore_metadata(
+        &self,
+        _offset: usize,
+        _data: &[u8],
+        _is_last: bool,
+    ) -> Result<(), FdOpsError> {
+        Err(FdOpsError::MetaDataError)
+    }
+
     /// Retrieves the current timestamp in milliseconds.
     ///
     /// # Returns
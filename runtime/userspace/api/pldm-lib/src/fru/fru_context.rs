@@ -0,0 +1,226 @@
+// Licensed under the Apache-2.0 license
+
+use crate::cmd_interface::generate_failure_response;
+use crate::config::{FRU_MAX_TABLE_SIZE, FRU_MAX_XFER_SIZE};
+use crate::error::MsgHandlerError;
+use crate::fru::fru_table::FruTable;
+use pldm_common::codec::PldmCodec;
+use pldm_common::message::fru::get_fru_record_table::{
+    GetFruRecordTableRequest, GetFruRecordTableResponse,
+};
+use pldm_common::message::fru::get_fru_record_table_metadata::{
+    GetFruRecordTableMetadataRequest, GetFruRecordTableMetadataResponse,
+};
+use pldm_common::protocol::base::{
+    PldmBaseCompletionCode, TransferOperationFlag, TransferRespFlag,
+};
+use pldm_common::protocol::fru::FruCompletionCode;
+
+/// `FruContext` handles the PLDM for FRU Data (type 4) commands.
+///
+/// It serves a FRU record table built by the platform, typically with
+/// `FruTable::from_device_identity`, so that the BMC inventory can describe the device.
+pub struct FruContext<'a> {
+    table: &'a FruTable,
+}
+
+impl<'a> FruContext<'a> {
+    pub fn new(table: &'a FruTable) -> Self {
+        Self { table }
+    }
+
+    pub fn get_fru_record_table_metadata_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        let req =
+            GetFruRecordTableMetadataRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
+        if self.table.record_count() == 0 {
+            return generate_failure_response(
+                payload,
+                FruCompletionCode::DataStructureTableUnavailable as u8,
+            );
+        }
+
+        let resp = GetFruRecordTableMetadataResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            FRU_MAX_TABLE_SIZE as u32,
+            self.table.data().len() as u32,
+            self.table.record_set_count(),
+            self.table.record_count(),
+            self.table.checksum(),
+        );
+        resp.encode(payload).map_err(MsgHandlerError::Codec)
+    }
+
+    pub fn get_fru_record_table_rsp(&self, payload: &mut [u8]) -> Result<usize, MsgHandlerError> {
+        let req = match GetFruRecordTableRequest::decode(payload) {
+            Ok(req) => req,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    PldmBaseCompletionCode::InvalidLength as u8,
+                )
+            }
+        };
+
+        let op_flag = match TransferOperationFlag::try_from(req.transfer_operation_flag) {
+            Ok(flag) => flag,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    FruCompletionCode::InvalidTransferFlag as u8,
+                )
+            }
+        };
+
+        let data = self.table.data();
+        if data.is_empty() {
+            return generate_failure_response(
+                payload,
+                FruCompletionCode::DataStructureTableUnavailable as u8,
+            );
+        }
+
+        // The data transfer handle is the offset of the next portion within the table
+        let offset = match op_flag {
+            TransferOperationFlag::GetFirstPart => 0,
+            TransferOperationFlag::GetNextPart => {
+                let offset = req.data_transfer_handle as usize;
+                if offset == 0 || offset >= data.len() {
+                    return generate_failure_response(
+                        payload,
+                        FruCompletionCode::InvalidDataTransferHandle as u8,
+                    );
+                }
+                offset
+            }
+        };
+
+        let end = (offset + FRU_MAX_XFER_SIZE).min(data.len());
+        let is_last = end == data.len();
+        let transfer_flag = match (offset == 0, is_last) {
+            (true, true) => TransferRespFlag::StartAndEnd,
+            (true, false) => TransferRespFlag::Start,
+            (false, false) => TransferRespFlag::Middle,
+            (false, true) => TransferRespFlag::End,
+        };
+
+        let resp = GetFruRecordTableResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            if is_last { 0 } else { end as u32 },
+            transfer_flag,
+            &data[offset..end],
+        );
+
+        match resp.encode(payload) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pldm_common::message::fru::get_fru_record_table::GetFruRecordTableResponseFixed;
+    use pldm_common::message::fru::get_fru_record_table_metadata::fru_table_crc32;
+    use pldm_common::protocol::base::{PldmFailureResponse, PldmMsgType};
+    use pldm_common::protocol::fru::{
+        FruField, FruFieldEncoding, FruGeneralFieldType, FruRecordType,
+    };
+
+    #[test]
+    fn test_get_fru_record_table() {
+        let value = [b'x'; 200];
+        let field = FruField::new(FruGeneralFieldType::Description, &value).unwrap();
+        let mut table = FruTable::new();
+        for record_set_id in 1..=2 {
+            table
+                .add_record(
+                    record_set_id,
+                    FruRecordType::General,
+                    FruFieldEncoding::Ascii,
+                    &[field],
+                )
+                .unwrap();
+        }
+        assert!(table.data().len() > FRU_MAX_XFER_SIZE);
+        let ctx = FruContext::new(&table);
+
+        let mut payload = [0u8; 512];
+        GetFruRecordTableMetadataRequest::new(0x01, PldmMsgType::Request)
+            .encode(&mut payload)
+            .unwrap();
+        let len = ctx.get_fru_record_table_metadata_rsp(&mut payload).unwrap();
+        let resp = GetFruRecordTableMetadataResponse::decode(&payload[..len]).unwrap();
+        assert_eq!(resp.completion_code, PldmBaseCompletionCode::Success as u8);
+        assert_eq!({ resp.fru_table_length }, table.data().len() as u32);
+        assert_eq!({ resp.total_record_set_identifiers }, 2);
+        assert_eq!({ resp.total_table_records }, 2);
+
+        // Read the table in multiple portions
+        let mut received = [0u8; FRU_MAX_TABLE_SIZE];
+        let mut received_len = 0;
+        let mut handle = 0;
+        let mut op_flag = TransferOperationFlag::GetFirstPart;
+        loop {
+            GetFruRecordTableRequest::new(0x01, PldmMsgType::Request, handle, op_flag)
+                .encode(&mut payload)
+                .unwrap();
+            let len = ctx.get_fru_record_table_rsp(&mut payload).unwrap();
+            let fixed = GetFruRecordTableResponseFixed::decode(&payload[..len]).unwrap();
+            assert_eq!(fixed.completion_code, PldmBaseCompletionCode::Success as u8);
+            let data = &payload[core::mem::size_of::<GetFruRecordTableResponseFixed>()..len];
+            received[received_len..received_len + data.len()].copy_from_slice(data);
+            received_len += data.len();
+            if fixed.transfer_flag == TransferRespFlag::End as u8 {
+                break;
+            }
+            assert_eq!(fixed.transfer_flag, TransferRespFlag::Start as u8);
+            handle = fixed.next_data_transfer_handle;
+            op_flag = TransferOperationFlag::GetNextPart;
+        }
+        assert_eq!(&received[..received_len], table.data());
+        assert_eq!(fru_table_crc32(&received[..received_len]), {
+            resp.checksum
+        });
+
+        // Invalid data transfer handle
+        GetFruRecordTableRequest::new(
+            0x01,
+            PldmMsgType::Request,
+            table.data().len() as u32,
+            TransferOperationFlag::GetNextPart,
+        )
+        .encode(&mut payload)
+        .unwrap();
+        let len = ctx.get_fru_record_table_rsp(&mut payload).unwrap();
+        let resp = PldmFailureResponse::decode(&payload[..len]).unwrap();
+        assert_eq!(
+            resp.completion_code,
+            FruCompletionCode::InvalidDataTransferHandle as u8
+        );
+    }
+
+    #[test]
+    fn test_fru_table_unavailable() {
+        let table = FruTable::new();
+        let ctx = FruContext::new(&table);
+
+        let mut payload = [0u8; 64];
+        GetFruRecordTableMetadataRequest::new(0x01, PldmMsgType::Request)
+            .encode(&mut payload)
+            .unwrap();
+        let len = ctx.get_fru_record_table_metadata_rsp(&mut payload).unwrap();
+        let resp = PldmFailureResponse::decode(&payload[..len]).unwrap();
+        assert_eq!(
+            resp.completion_code,
+            FruCompletionCode::DataStructureTableUnavailable as u8
+        );
+    }
+}
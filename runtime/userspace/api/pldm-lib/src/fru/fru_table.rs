@@ -0,0 +1,357 @@
+// Licensed under the Apache-2.0 license
+
+use crate::config::{FRU_MAX_RECORD_FIELDS, FRU_MAX_TABLE_SIZE};
+use external_cmds_common::{DeviceId, FirmwareVersion, Uid};
+use pldm_common::codec::PldmCodec;
+use pldm_common::message::fru::get_fru_record_table_metadata::fru_table_crc32;
+use pldm_common::protocol::fru::{
+    FruField, FruFieldEncoding, FruGeneralFieldType, FruRecordHeader, FruRecordSetId,
+    FruRecordType, PLDM_FRU_TABLE_ALIGNMENT,
+};
+
+/// Record set identifier of the General FRU record built from the device identity.
+pub const DEVICE_IDENTITY_RECORD_SET_ID: FruRecordSetId = 1;
+
+// "vvvv:dddd:ssss:ssss"
+const PART_NUMBER_LEN: usize = 19;
+
+#[derive(Debug)]
+pub enum FruTableError {
+    TableFull,
+    TooManyFields,
+    InvalidField,
+}
+
+/// Identity of the device reported in the General FRU record.
+pub struct DeviceIdentity<'b> {
+    /// Product name reported in the Name field.
+    pub name: &'b str,
+    /// PCI-style identifiers reported in the Part Number field.
+    pub device_id: &'b DeviceId,
+    /// Firmware versions in `get_firmware_version` index order, each reported in a Version field.
+    pub firmware_versions: &'b [FirmwareVersion],
+    /// Unique chip ID, reported in hexadecimal in the Serial Number field.
+    pub unique_chip_id: Option<&'b Uid>,
+}
+
+/// The FRU record table of the terminus, returned by `GetFRURecordTable`.
+///
+/// Records are appended back to back. The table is padded with zeros to a multiple of four
+/// bytes, and the pad bytes are covered by the integrity checksum.
+pub struct FruTable {
+    data: [u8; FRU_MAX_TABLE_SIZE],
+    len: usize,
+    record_count: u16,
+    record_set_count: u16,
+}
+
+impl Default for FruTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FruTable {
+    pub fn new() -> Self {
+        Self {
+            data: [0u8; FRU_MAX_TABLE_SIZE],
+            len: 0,
+            record_count: 0,
+            record_set_count: 0,
+        }
+    }
+
+    /// Builds a table with a single General FRU record describing the device.
+    pub fn from_device_identity(identity: &DeviceIdentity) -> Result<Self, FruTableError> {
+        let mut part_number = [0u8; PART_NUMBER_LEN];
+        let ids = [
+            identity.device_id.vendor_id,
+            identity.device_id.device_id,
+            identity.device_id.subsystem_vendor_id,
+            identity.device_id.subsystem_id,
+        ];
+        for (i, id) in ids.iter().enumerate() {
+            let start = i * 5;
+            encode_hex(&id.to_be_bytes(), &mut part_number[start..start + 4]);
+            if i < ids.len() - 1 {
+                part_number[start + 4] = b':';
+            }
+        }
+
+        let mut serial_number = [0u8; 2 * external_cmds_common::MAX_UID_LEN];
+        let serial_number_len = identity.unique_chip_id.map_or(0, |uid| {
+            let uid = &uid.unique_chip_id[..uid.len.min(uid.unique_chip_id.len())];
+            encode_hex(uid, &mut serial_number[..2 * uid.len()]);
+            2 * uid.len()
+        });
+
+        let mut fields = [FruField {
+            field_type: 0,
+            value: &[],
+        }; FRU_MAX_RECORD_FIELDS];
+        let mut count = 0;
+        push_field(
+            &mut fields,
+            &mut count,
+            FruGeneralFieldType::Name,
+            identity.name.as_bytes(),
+        )?;
+        push_field(
+            &mut fields,
+            &mut count,
+            FruGeneralFieldType::PartNumber,
+            &part_number,
+        )?;
+        if serial_number_len > 0 {
+            push_field(
+                &mut fields,
+                &mut count,
+                FruGeneralFieldType::SerialNumber,
+                &serial_number[..serial_number_len],
+            )?;
+        }
+        for version in identity.firmware_versions {
+            push_field(
+                &mut fields,
+                &mut count,
+                FruGeneralFieldType::Version,
+                &version.ver_str[..version.len.min(version.ver_str.len())],
+            )?;
+        }
+
+        let mut table = Self::new();
+        table.add_record(
+            DEVICE_IDENTITY_RECORD_SET_ID,
+            FruRecordType::General,
+            FruFieldEncoding::Ascii,
+            &fields[..count],
+        )?;
+        Ok(table)
+    }
+
+    /// Appends a record to the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `record_set_id` - The FRU record set the record belongs to.
+    /// * `record_type` - The type of the record.
+    /// * `encoding` - The encoding of the string fields of the record.
+    /// * `fields` - The fields of the record.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), FruTableError>` - On success, returns `Ok(())`. Returns
+    ///   `FruTableError::TableFull` if the padded table would exceed `FRU_MAX_TABLE_SIZE`.
+    pub fn add_record(
+        &mut self,
+        record_set_id: FruRecordSetId,
+        record_type: FruRecordType,
+        encoding: FruFieldEncoding,
+        fields: &[FruField],
+    ) -> Result<(), FruTableError> {
+        let field_count = u8::try_from(fields.len()).map_err(|_| FruTableError::TooManyFields)?;
+        let hdr = FruRecordHeader::new(record_set_id, record_type, field_count, encoding);
+        let record_len = core::mem::size_of::<FruRecordHeader>()
+            + fields
+                .iter()
+                .map(|field| field.codec_size_in_bytes())
+                .sum::<usize>();
+        if padded_len(self.len + record_len) > FRU_MAX_TABLE_SIZE {
+            return Err(FruTableError::TableFull);
+        }
+
+        let is_new_record_set = !self.contains_record_set(record_set_id);
+        let mut offset = self.len;
+        offset += hdr
+            .encode(&mut self.data[offset..])
+            .map_err(|_| FruTableError::TableFull)?;
+        for field in fields {
+            offset += field
+                .encode(&mut self.data[offset..])
+                .map_err(|_| FruTableError::TableFull)?;
+        }
+
+        self.len = offset;
+        self.record_count += 1;
+        if is_new_record_set {
+            self.record_set_count += 1;
+        }
+        Ok(())
+    }
+
+    /// Returns the table, including its pad bytes.
+    pub fn data(&self) -> &[u8] {
+        &self.data[..padded_len(self.len)]
+    }
+
+    pub fn record_count(&self) -> u16 {
+        self.record_count
+    }
+
+    pub fn record_set_count(&self) -> u16 {
+        self.record_set_count
+    }
+
+    /// Returns the integrity checksum reported by `GetFRURecordTableMetadata`.
+    pub fn checksum(&self) -> u32 {
+        fru_table_crc32(self.data())
+    }
+
+    fn contains_record_set(&self, record_set_id: FruRecordSetId) -> bool {
+        let mut offset = 0;
+        while offset < self.len {
+            let Ok(hdr) = FruRecordHeader::decode(&self.data[offset..self.len]) else {
+                return false;
+            };
+            if hdr.record_set_id == record_set_id {
+                return true;
+            }
+            offset += core::mem::size_of::<FruRecordHeader>();
+            for _ in 0..hdr.field_count {
+                // Skip the type and length bytes and the value
+                offset += 2 + self.data[offset + 1] as usize;
+            }
+        }
+        false
+    }
+}
+
+fn push_field<'f>(
+    fields: &mut [FruField<'f>],
+    count: &mut usize,
+    field_type: FruGeneralFieldType,
+    value: &'f [u8],
+) -> Result<(), FruTableError> {
+    let field = fields.get_mut(*count).ok_or(FruTableError::TooManyFields)?;
+    *field = FruField::new(field_type, value).map_err(|_| FruTableError::InvalidField)?;
+    *count += 1;
+    Ok(())
+}
+
+fn padded_len(len: usize) -> usize {
+    len.next_multiple_of(PLDM_FRU_TABLE_ALIGNMENT)
+}
+
+// Writes `bytes` as uppercase hexadecimal digits into `out`, which must be twice as long.
+fn encode_hex(bytes: &[u8], out: &mut [u8]) {
+    const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+    for (byte, digits) in bytes.iter().zip(out.chunks_exact_mut(2)) {
+        digits[0] = DIGITS[(byte >> 4) as usize];
+        digits[1] = DIGITS[(byte & 0xF) as usize];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn firmware_version(ver: &str) -> FirmwareVersion {
+        let mut version = FirmwareVersion {
+            len: ver.len(),
+            ..Default::default()
+        };
+        version.ver_str[..ver.len()].copy_from_slice(ver.as_bytes());
+        version
+    }
+
+    #[test]
+    fn test_fru_table_from_device_identity() {
+        let device_id = DeviceId {
+            vendor_id: 0x1414,
+            device_id: 0x0010,
+            subsystem_vendor_id: 0x0001,
+            subsystem_id: 0x0002,
+        };
+        let mut uid = Uid {
+            len: 4,
+            ..Default::default()
+        };
+        uid.unique_chip_id[..4].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        let firmware_versions = [firmware_version("1.0.0"), firmware_version("2.1")];
+
+        let table = FruTable::from_device_identity(&DeviceIdentity {
+            name: "Caliptra",
+            device_id: &device_id,
+            firmware_versions: &firmware_versions,
+            unique_chip_id: Some(&uid),
+        })
+        .unwrap();
+        assert_eq!(table.record_count(), 1);
+        assert_eq!(table.record_set_count(), 1);
+
+        let data = table.data();
+        assert_eq!(data.len() % PLDM_FRU_TABLE_ALIGNMENT, 0);
+        let hdr = FruRecordHeader::decode(data).unwrap();
+        assert_eq!(
+            hdr,
+            FruRecordHeader::new(
+                DEVICE_IDENTITY_RECORD_SET_ID,
+                FruRecordType::General,
+                5,
+                FruFieldEncoding::Ascii
+            )
+        );
+
+        let mut offset = core::mem::size_of::<FruRecordHeader>();
+        let mut next_field = || {
+            let field_type = data[offset];
+            let len = data[offset + 1] as usize;
+            let value = &data[offset + 2..offset + 2 + len];
+            offset += 2 + len;
+            (field_type, value)
+        };
+        assert_eq!(
+            next_field(),
+            (FruGeneralFieldType::Name as u8, &b"Caliptra"[..])
+        );
+        assert_eq!(
+            next_field(),
+            (
+                FruGeneralFieldType::PartNumber as u8,
+                &b"1414:0010:0001:0002"[..]
+            )
+        );
+        assert_eq!(
+            next_field(),
+            (FruGeneralFieldType::SerialNumber as u8, &b"DEADBEEF"[..])
+        );
+        assert_eq!(
+            next_field(),
+            (FruGeneralFieldType::Version as u8, &b"1.0.0"[..])
+        );
+        assert_eq!(
+            next_field(),
+            (FruGeneralFieldType::Version as u8, &b"2.1"[..])
+        );
+        assert!(data[offset..].iter().all(|&pad| pad == 0));
+        assert_eq!(table.checksum(), fru_table_crc32(data));
+    }
+
+    #[test]
+    fn test_fru_table_add_record() {
+        let mut table = FruTable::new();
+        assert!(table.data().is_empty());
+
+        let name = [FruField::new(FruGeneralFieldType::Name, b"a").unwrap()];
+        table
+            .add_record(1, FruRecordType::General, FruFieldEncoding::Ascii, &name)
+            .unwrap();
+        table
+            .add_record(1, FruRecordType::Oem, FruFieldEncoding::Ascii, &[])
+            .unwrap();
+        table
+            .add_record(2, FruRecordType::General, FruFieldEncoding::Ascii, &name)
+            .unwrap();
+        assert_eq!(table.record_count(), 3);
+        assert_eq!(table.record_set_count(), 2);
+        assert_eq!(table.data().len(), 24);
+
+        let value = [b'x'; 255];
+        let big = [FruField::new(FruGeneralFieldType::Description, &value).unwrap(); 4];
+        assert!(matches!(
+            table.add_record(3, FruRecordType::General, FruFieldEncoding::Ascii, &big),
+            Err(FruTableError::TableFull)
+        ));
+        assert_eq!(table.record_count(), 3);
+    }
+}
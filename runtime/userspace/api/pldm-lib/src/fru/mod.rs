@@ -0,0 +1,4 @@
+// Licensed under the Apache-2.0 license
+
+pub mod fru_context;
+pub mod fru_table;
@@ -8,5 +8,7 @@ pub mod control_context;
 pub mod daemon;
 pub mod error;
 pub mod firmware_device;
+pub mod fru;
+pub mod platform;
 pub mod timer;
 pub mod transport;
@@ -0,0 +1,5 @@
+// Licensed under the Apache-2.0 license
+
+pub mod pdr_repo;
+pub mod platform_context;
+pub mod sensor_ops;
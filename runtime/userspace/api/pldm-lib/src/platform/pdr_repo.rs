@@ -0,0 +1,164 @@
+// Licensed under the Apache-2.0 license
+
+use pldm_common::codec::PldmCodec;
+use pldm_common::message::platform::get_pdr_repository_info::PdrRepositoryInfo;
+use pldm_common::protocol::platform::{
+    PdrHeader, PdrRecordHandle, PdrRepositoryState, PLDM_NO_MORE_RECORDS, PLDM_PDR_HEADER_LEN,
+    PLDM_PDR_HEADER_VERSION,
+};
+
+#[derive(Debug)]
+pub enum PdrRepoError {
+    InvalidRecord,
+    InvalidRecordHandle,
+}
+
+/// A PDR record retrieved from the repository.
+pub struct PdrRecord<'a> {
+    /// The encoded record, including the common PDR header.
+    pub data: &'a [u8],
+    pub record_change_number: u16,
+    /// Handle of the next record, or `PLDM_NO_MORE_RECORDS` for the last record.
+    pub next_record_handle: PdrRecordHandle,
+}
+
+/// Trait for the Platform Descriptor Record (PDR) repository of the terminus.
+///
+/// The repository describes the sensors that can be accessed through the platform monitoring
+/// and control commands. Records are retrieved whole; the `GetPDR` handler takes care of
+/// splitting them into multiple parts when needed.
+pub trait PdrRepository {
+    /// Returns the summary reported by `GetPDRRepositoryInfo`.
+    fn info(&self) -> PdrRepositoryInfo;
+
+    /// Retrieves a record.
+    ///
+    /// # Arguments
+    ///
+    /// * `record_handle` - The handle of the record. `0` retrieves the first record.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PdrRecord, PdrRepoError>` - The record on success, or
+    ///   `PdrRepoError::InvalidRecordHandle` if no record has the given handle.
+    fn get_record(&self, record_handle: PdrRecordHandle) -> Result<PdrRecord<'_>, PdrRepoError>;
+}
+
+/// PDR repository backed by a buffer of encoded PDRs stored back to back.
+pub struct StaticPdrRepository<'a> {
+    records: &'a [u8],
+    info: PdrRepositoryInfo,
+}
+
+impl<'a> StaticPdrRepository<'a> {
+    /// Creates a repository from encoded PDRs.
+    ///
+    /// Every record must start with a valid common PDR header whose `data_length` covers the rest of
+    /// the record, and record handles must be non-zero.
+    pub fn new(records: &'a [u8]) -> Result<Self, PdrRepoError> {
+        let mut info = PdrRepositoryInfo::default();
+        let mut offset = 0;
+        while offset < records.len() {
+            let hdr =
+                PdrHeader::decode(&records[offset..]).map_err(|_| PdrRepoError::InvalidRecord)?;
+            let record_len = PLDM_PDR_HEADER_LEN + hdr.data_length as usize;
+            if hdr.pdr_header_version != PLDM_PDR_HEADER_VERSION
+                || hdr.record_handle == PLDM_NO_MORE_RECORDS
+                || offset + record_len > records.len()
+            {
+                return Err(PdrRepoError::InvalidRecord);
+            }
+            info.record_count += 1;
+            info.largest_record_size = info.largest_record_size.max(record_len as u32);
+            offset += record_len;
+        }
+        info.repository_size = records.len() as u32;
+        info.repository_state = PdrRepositoryState::Available;
+
+        Ok(Self { records, info })
+    }
+
+    fn record_at(&self, offset: usize) -> Option<(PdrHeader, &'a [u8])> {
+        let hdr = PdrHeader::decode(self.records.get(offset..)?).ok()?;
+        let record_len = PLDM_PDR_HEADER_LEN + hdr.data_length as usize;
+        Some((hdr, &self.records[offset..offset + record_len]))
+    }
+}
+
+impl PdrRepository for StaticPdrRepository<'_> {
+    fn info(&self) -> PdrRepositoryInfo {
+        self.info
+    }
+
+    fn get_record(&self, record_handle: PdrRecordHandle) -> Result<PdrRecord<'_>, PdrRepoError> {
+        let mut offset = 0;
+        while let Some((hdr, data)) = self.record_at(offset) {
+            offset += data.len();
+            if record_handle == PLDM_NO_MORE_RECORDS || hdr.record_handle == record_handle {
+                let next_record_handle = self
+                    .record_at(offset)
+                    .map_or(PLDM_NO_MORE_RECORDS, |(next, _)| next.record_handle);
+                return Ok(PdrRecord {
+                    data,
+                    record_change_number: hdr.record_change_number,
+                    next_record_handle,
+                });
+            }
+        }
+        Err(PdrRepoError::InvalidRecordHandle)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pldm_common::protocol::platform::{
+        NumericSensorPdr, PdrType, PossibleStates, StateSensorPdr,
+    };
+
+    #[test]
+    fn test_static_pdr_repository() {
+        let mut records = [0u8; 256];
+        let numeric = NumericSensorPdr {
+            hdr: PdrHeader::new(
+                1,
+                PdrType::NumericSensor,
+                (core::mem::size_of::<NumericSensorPdr>() - PLDM_PDR_HEADER_LEN) as u16,
+            ),
+            sensor_id: 1,
+            ..Default::default()
+        };
+        let state = StateSensorPdr::new(
+            2,
+            0,
+            2,
+            0,
+            0,
+            0,
+            &[PossibleStates::new(1, &[1, 2]).unwrap()],
+        )
+        .unwrap();
+        let mut len = numeric.encode(&mut records).unwrap();
+        len += state.encode(&mut records[len..]).unwrap();
+
+        let repo = StaticPdrRepository::new(&records[..len]).unwrap();
+        let info = repo.info();
+        assert_eq!(info.record_count, 2);
+        assert_eq!(info.repository_size, len as u32);
+        assert_eq!(
+            info.largest_record_size,
+            core::mem::size_of::<NumericSensorPdr>() as u32
+        );
+
+        let first = repo.get_record(0).unwrap();
+        assert_eq!(first.data.len(), core::mem::size_of::<NumericSensorPdr>());
+        assert_eq!(first.next_record_handle, 2);
+        let second = repo.get_record(first.next_record_handle).unwrap();
+        assert_eq!(second.data.len(), state.codec_size_in_bytes());
+        assert_eq!(second.next_record_handle, PLDM_NO_MORE_RECORDS);
+        assert!(repo.get_record(3).is_err());
+
+        // Truncated record
+        assert!(StaticPdrRepository::new(&records[..len - 1]).is_err());
+    }
+}
@@ -0,0 +1,627 @@
+// Licensed under the Apache-2.0 license
+
+use crate::cmd_interface::generate_failure_response;
+use crate::config::{
+    INSTANCE_ID_COUNT, PLATFORM_MAX_NUMERIC_SENSORS, PLATFORM_MAX_PDR_XFER_SIZE,
+    PLATFORM_MAX_STATE_SENSORS,
+};
+use crate::control_context::Tid;
+use crate::error::MsgHandlerError;
+use crate::platform::pdr_repo::PdrRepository;
+use crate::platform::sensor_ops::{NumericSensor, SensorOpsError, StateSensor, StateSensorReading};
+use crate::timer::AsyncAlarm;
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_sync::mutex::Mutex;
+use libsyscall_caliptra::DefaultSyscalls;
+use pldm_common::codec::PldmCodec;
+use pldm_common::message::platform::get_pdr::{pdr_crc8, GetPdrRequest, GetPdrResponse};
+use pldm_common::message::platform::get_pdr_repository_info::{
+    GetPdrRepositoryInfoRequest, GetPdrRepositoryInfoResponse,
+};
+use pldm_common::message::platform::get_sensor_reading::{
+    GetSensorReadingRequest, GetSensorReadingResponse, NumericSensorStates,
+};
+use pldm_common::message::platform::get_state_sensor_readings::{
+    GetStateSensorReadingsRequest, GetStateSensorReadingsResponse, StateField,
+};
+use pldm_common::message::platform::platform_event_message::{
+    HeartbeatTimerElapsedEvent, PlatformEventMessageRequest, PlatformEventMessageResponse,
+    SensorEvent, SensorEventData,
+};
+use pldm_common::message::platform::set_event_receiver::{
+    SetEventReceiverRequest, SetEventReceiverResponse,
+};
+use pldm_common::protocol::base::{
+    InstanceId, PldmBaseCompletionCode, PldmMsgType, TransferOperationFlag,
+};
+use pldm_common::protocol::firmware_update::PldmFdTime;
+use pldm_common::protocol::platform::{
+    EventMessageGlobalEnable, GetPdrCompletionCode, NumericSensorState, PdrTransferFlag,
+    PlatformEventClass, SensorCompletionCode, SensorEventMessageEnable,
+    SetEventReceiverCompletionCode, TransportProtocolType, PLDM_MAX_COMPOSITE_SENSOR_COUNT,
+};
+
+/// Present, previous and last reported state of a sensor.
+#[derive(Debug, Clone, Copy, Default)]
+struct SensorStates {
+    present: u8,
+    previous: u8,
+    event: u8,
+}
+
+impl SensorStates {
+    fn update(&mut self, present: u8) {
+        if present != self.present {
+            self.previous = self.present;
+            self.present = present;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct EventReceiver {
+    eid: u8,
+    global_enable: EventMessageGlobalEnable,
+    // Heartbeat period in seconds.
+    heartbeat_timer: u16,
+}
+
+/// The event sent to the event receiver and waiting for its response.
+#[derive(Debug, Clone, Copy)]
+enum PendingEvent {
+    NumericSensor {
+        index: usize,
+        state: u8,
+    },
+    StateSensor {
+        index: usize,
+        offset: usize,
+        state: u8,
+    },
+    Heartbeat,
+}
+
+struct PlatformInternal {
+    event_receiver: Option<EventReceiver>,
+    numeric_states: [SensorStates; PLATFORM_MAX_NUMERIC_SENSORS],
+    state_states: [[SensorStates; PLDM_MAX_COMPOSITE_SENSOR_COUNT]; PLATFORM_MAX_STATE_SENSORS],
+    pending_event: Option<PendingEvent>,
+    instance_id: InstanceId,
+    heartbeat_seq: u8,
+    last_heartbeat: PldmFdTime,
+}
+
+impl PlatformInternal {
+    fn new() -> Self {
+        Self {
+            event_receiver: None,
+            numeric_states: [SensorStates::default(); PLATFORM_MAX_NUMERIC_SENSORS],
+            state_states: [[SensorStates::default(); PLDM_MAX_COMPOSITE_SENSOR_COUNT];
+                PLATFORM_MAX_STATE_SENSORS],
+            pending_event: None,
+            instance_id: 0,
+            heartbeat_seq: 0,
+            last_heartbeat: 0,
+        }
+    }
+
+    fn alloc_instance_id(&mut self) -> InstanceId {
+        let instance_id = self.instance_id;
+        self.instance_id = (self.instance_id + 1) % INSTANCE_ID_COUNT;
+        instance_id
+    }
+
+    fn sensor_event_message_enable(&self) -> SensorEventMessageEnable {
+        match self.event_receiver {
+            Some(_) => SensorEventMessageEnable::EventsEnabled,
+            None => SensorEventMessageEnable::EventsDisabled,
+        }
+    }
+}
+
+/// `PlatformContext` handles the PLDM for Platform Monitoring and Control (type 2) commands.
+///
+/// It serves the PDR repository and the sensors implemented by the platform, and reports sensor
+/// state changes to the event receiver registered by `SetEventReceiver`.
+pub struct PlatformContext<'a> {
+    pdr_repo: &'a dyn PdrRepository,
+    numeric_sensors: &'a [&'a dyn NumericSensor],
+    state_sensors: &'a [&'a dyn StateSensor],
+    internal: Mutex<NoopRawMutex, PlatformInternal>,
+}
+
+impl<'a> PlatformContext<'a> {
+    pub fn new(
+        pdr_repo: &'a dyn PdrRepository,
+        numeric_sensors: &'a [&'a dyn NumericSensor],
+        state_sensors: &'a [&'a dyn StateSensor],
+    ) -> Result<Self, SensorOpsError> {
+        if numeric_sensors.len() > PLATFORM_MAX_NUMERIC_SENSORS
+            || state_sensors.len() > PLATFORM_MAX_STATE_SENSORS
+            || state_sensors
+                .iter()
+                .any(|sensor| sensor.composite_sensor_count() > PLDM_MAX_COMPOSITE_SENSOR_COUNT)
+        {
+            return Err(SensorOpsError::TooManySensors);
+        }
+
+        Ok(Self {
+            pdr_repo,
+            numeric_sensors,
+            state_sensors,
+            internal: Mutex::new(PlatformInternal::new()),
+        })
+    }
+
+    fn now(&self) -> PldmFdTime {
+        AsyncAlarm::<DefaultSyscalls>::get_milliseconds().unwrap_or(0)
+    }
+
+    pub async fn get_pdr_repository_info_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        let req = GetPdrRepositoryInfoRequest::decode(payload).map_err(MsgHandlerError::Codec)?;
+        let resp = GetPdrRepositoryInfoResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            &self.pdr_repo.info(),
+        );
+        resp.encode(payload).map_err(MsgHandlerError::Codec)
+    }
+
+    pub async fn get_pdr_rsp(&self, payload: &mut [u8]) -> Result<usize, MsgHandlerError> {
+        let req = match GetPdrRequest::decode(payload) {
+            Ok(req) => req,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    PldmBaseCompletionCode::InvalidLength as u8,
+                )
+            }
+        };
+
+        let op_flag = match TransferOperationFlag::try_from(req.transfer_operation_flag) {
+            Ok(flag) => flag,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    GetPdrCompletionCode::InvalidTransferOperationFlag as u8,
+                )
+            }
+        };
+
+        if req.request_count == 0 {
+            return generate_failure_response(payload, PldmBaseCompletionCode::InvalidData as u8);
+        }
+
+        let record = match self.pdr_repo.get_record(req.record_handle) {
+            Ok(record) => record,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    GetPdrCompletionCode::InvalidRecordHandle as u8,
+                )
+            }
+        };
+
+        // The data transfer handle is the offset of the next part within the record
+        let offset = match op_flag {
+            TransferOperationFlag::GetFirstPart => 0,
+            TransferOperationFlag::GetNextPart => {
+                if req.record_change_number != record.record_change_number {
+                    return generate_failure_response(
+                        payload,
+                        GetPdrCompletionCode::InvalidRecordChangeNumber as u8,
+                    );
+                }
+                let offset = req.data_transfer_handle as usize;
+                if offset == 0 || offset >= record.data.len() {
+                    return generate_failure_response(
+                        payload,
+                        GetPdrCompletionCode::InvalidDataTransferHandle as u8,
+                    );
+                }
+                offset
+            }
+        };
+
+        let max_len = (req.request_count as usize).min(PLATFORM_MAX_PDR_XFER_SIZE);
+        let end = (offset + max_len).min(record.data.len());
+        let is_last = end == record.data.len();
+        let (transfer_flag, transfer_crc) = match (offset == 0, is_last) {
+            (true, true) => (PdrTransferFlag::StartAndEnd, None),
+            (true, false) => (PdrTransferFlag::Start, None),
+            (false, false) => (PdrTransferFlag::Middle, None),
+            (false, true) => (PdrTransferFlag::End, Some(pdr_crc8(record.data))),
+        };
+
+        let resp = GetPdrResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            record.next_record_handle,
+            if is_last { 0 } else { end as u32 },
+            transfer_flag,
+            &record.data[offset..end],
+            transfer_crc,
+        );
+
+        match resp.encode(payload) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+
+    pub async fn get_sensor_reading_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        let req = match GetSensorReadingRequest::decode(payload) {
+            Ok(req) => req,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    PldmBaseCompletionCode::InvalidLength as u8,
+                )
+            }
+        };
+
+        let sensor_id = req.sensor_id;
+        let Some((index, sensor)) = self
+            .numeric_sensors
+            .iter()
+            .enumerate()
+            .find(|(_, sensor)| sensor.sensor_id() == sensor_id)
+        else {
+            return generate_failure_response(payload, SensorCompletionCode::InvalidSensorId as u8);
+        };
+
+        let reading = match sensor.read().await {
+            Ok(reading) => reading,
+            Err(_) => {
+                return generate_failure_response(payload, PldmBaseCompletionCode::Error as u8)
+            }
+        };
+
+        let (states, event_message_enable) = {
+            let mut internal = self.internal.lock().await;
+            let states = &mut internal.numeric_states[index];
+            states.update(reading.present_state as u8);
+            if req.rearm_event_state != 0 {
+                states.event = NumericSensorState::Unknown as u8;
+            }
+            (*states, internal.sensor_event_message_enable())
+        };
+
+        let resp = GetSensorReadingResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            reading.operational_state,
+            event_message_enable,
+            NumericSensorStates {
+                present_state: reading.present_state,
+                previous_state: NumericSensorState::try_from(states.previous)
+                    .unwrap_or(NumericSensorState::Unknown),
+                event_state: NumericSensorState::try_from(states.event)
+                    .unwrap_or(NumericSensorState::Unknown),
+            },
+            reading.value,
+        );
+
+        match resp.encode(payload) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+
+    pub async fn get_state_sensor_readings_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        let req = match GetStateSensorReadingsRequest::decode(payload) {
+            Ok(req) => req,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    PldmBaseCompletionCode::InvalidLength as u8,
+                )
+            }
+        };
+
+        let sensor_id = req.sensor_id;
+        let Some((index, sensor)) = self
+            .state_sensors
+            .iter()
+            .enumerate()
+            .find(|(_, sensor)| sensor.sensor_id() == sensor_id)
+        else {
+            return generate_failure_response(payload, SensorCompletionCode::InvalidSensorId as u8);
+        };
+
+        let count = sensor.composite_sensor_count();
+        let mut readings = [StateSensorReading::default(); PLDM_MAX_COMPOSITE_SENSOR_COUNT];
+        if sensor.read(&mut readings[..count]).await.is_err() {
+            return generate_failure_response(payload, PldmBaseCompletionCode::Error as u8);
+        }
+
+        let mut fields = [StateField::default(); PLDM_MAX_COMPOSITE_SENSOR_COUNT];
+        {
+            let mut internal = self.internal.lock().await;
+            for (offset, reading) in readings[..count].iter().enumerate() {
+                let states = &mut internal.state_states[index][offset];
+                states.update(reading.present_state);
+                if req.sensor_rearm & (1 << offset) != 0 {
+                    states.event = 0;
+                }
+                fields[offset] = StateField {
+                    sensor_operational_state: reading.operational_state as u8,
+                    present_state: states.present,
+                    previous_state: states.previous,
+                    event_state: states.event,
+                };
+            }
+        }
+
+        let resp = match GetStateSensorReadingsResponse::new(
+            req.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+            &fields[..count],
+        ) {
+            Ok(resp) => resp,
+            Err(_) => {
+                return generate_failure_response(payload, PldmBaseCompletionCode::Error as u8)
+            }
+        };
+
+        match resp.encode(payload) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => {
+                generate_failure_response(payload, PldmBaseCompletionCode::InvalidLength as u8)
+            }
+        }
+    }
+
+    pub async fn set_event_receiver_rsp(
+        &self,
+        payload: &mut [u8],
+    ) -> Result<usize, MsgHandlerError> {
+        let req = match SetEventReceiverRequest::decode(payload) {
+            Ok(req) => req,
+            Err(_) => {
+                return generate_failure_response(
+                    payload,
+                    PldmBaseCompletionCode::InvalidLength as u8,
+                )
+            }
+        };
+
+        if TransportProtocolType::try_from(req.fixed.transport_protocol_type).is_err() {
+            return generate_failure_response(
+                payload,
+                SetEventReceiverCompletionCode::InvalidProtocolType as u8,
+            );
+        }
+
+        let global_enable =
+            match EventMessageGlobalEnable::try_from(req.fixed.event_message_global_enable) {
+                Ok(global_enable) => global_enable,
+                Err(_) => {
+                    return generate_failure_response(
+                        payload,
+                        PldmBaseCompletionCode::InvalidData as u8,
+                    )
+                }
+            };
+
+        let event_receiver = match global_enable {
+            EventMessageGlobalEnable::Disable => None,
+            // Events are only delivered asynchronously; PollForPlatformEventMessage is not supported
+            EventMessageGlobalEnable::EnablePolling => {
+                return generate_failure_response(
+                    payload,
+                    SetEventReceiverCompletionCode::EnableMethodNotSupported as u8,
+                )
+            }
+            EventMessageGlobalEnable::EnableAsync
+            | EventMessageGlobalEnable::EnableAsyncKeepAlive => {
+                let eid = req.fixed.event_receiver_address_info;
+                if eid == 0 || eid == 0xFF {
+                    return generate_failure_response(
+                        payload,
+                        PldmBaseCompletionCode::InvalidData as u8,
+                    );
+                }
+                let heartbeat_timer = req.heartbeat_timer.unwrap_or(0);
+                if global_enable == EventMessageGlobalEnable::EnableAsyncKeepAlive
+                    && heartbeat_timer == 0
+                {
+                    return generate_failure_response(
+                        payload,
+                        SetEventReceiverCompletionCode::HeartbeatFrequencyTooHigh as u8,
+                    );
+                }
+                Some(EventReceiver {
+                    eid,
+                    global_enable,
+                    heartbeat_timer,
+                })
+            }
+        };
+
+        let now = self.now();
+        {
+            let mut internal = self.internal.lock().await;
+            internal.event_receiver = event_receiver;
+            internal.pending_event = None;
+            internal.last_heartbeat = now;
+        }
+
+        let resp = SetEventReceiverResponse::new(
+            req.fixed.hdr.instance_id(),
+            PldmBaseCompletionCode::Success as u8,
+        );
+        resp.encode(payload).map_err(MsgHandlerError::Codec)
+    }
+
+    /// Generates the next `PlatformEventMessage` request for the event receiver, if any.
+    ///
+    /// The sensors are read and the first state change that has not been reported yet is encoded
+    /// into `payload`. When the heartbeat is enabled, a heartbeat event is generated once the
+    /// heartbeat period has elapsed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some((eid, len)))` - The EID of the event receiver and the length of the request.
+    /// * `Ok(None)` - No event receiver is registered or there is no event to report.
+    pub async fn generate_event(
+        &self,
+        tid: Tid,
+        payload: &mut [u8],
+    ) -> Result<Option<(u8, usize)>, MsgHandlerError> {
+        let Some(receiver) = self.internal.lock().await.event_receiver else {
+            return Ok(None);
+        };
+
+        if receiver.global_enable == EventMessageGlobalEnable::EnableAsyncKeepAlive {
+            let now = self.now();
+            let mut internal = self.internal.lock().await;
+            if now.saturating_sub(internal.last_heartbeat)
+                >= receiver.heartbeat_timer as PldmFdTime * 1000
+            {
+                let mut event_data = [0u8; core::mem::size_of::<HeartbeatTimerElapsedEvent>()];
+                HeartbeatTimerElapsedEvent::new(internal.heartbeat_seq)
+                    .encode(&mut event_data)
+                    .map_err(MsgHandlerError::Codec)?;
+                internal.pending_event = Some(PendingEvent::Heartbeat);
+                let req = PlatformEventMessageRequest::new(
+                    internal.alloc_instance_id(),
+                    PldmMsgType::Request,
+                    tid,
+                    PlatformEventClass::HeartbeatTimerElapsed,
+                    &event_data,
+                )
+                .map_err(MsgHandlerError::PldmCommon)?;
+                let len = req.encode(payload).map_err(MsgHandlerError::Codec)?;
+                return Ok(Some((receiver.eid, len)));
+            }
+        }
+
+        for (index, sensor) in self.numeric_sensors.iter().enumerate() {
+            let Ok(reading) = sensor.read().await else {
+                continue;
+            };
+
+            let mut internal = self.internal.lock().await;
+            let states = &mut internal.numeric_states[index];
+            states.update(reading.present_state as u8);
+            if states.present == states.event {
+                continue;
+            }
+
+            let event = SensorEvent {
+                sensor_id: sensor.sensor_id(),
+                data: SensorEventData::NumericSensorState {
+                    event_state: states.present,
+                    previous_event_state: states.event,
+                    present_reading: reading.value,
+                },
+            };
+            internal.pending_event = Some(PendingEvent::NumericSensor {
+                index,
+                state: reading.present_state as u8,
+            });
+            let req = PlatformEventMessageRequest::new_sensor_event(
+                internal.alloc_instance_id(),
+                tid,
+                &event,
+            )
+            .map_err(MsgHandlerError::PldmCommon)?;
+            let len = req.encode(payload).map_err(MsgHandlerError::Codec)?;
+            return Ok(Some((receiver.eid, len)));
+        }
+
+        for (index, sensor) in self.state_sensors.iter().enumerate() {
+            let count = sensor.composite_sensor_count();
+            let mut readings = [StateSensorReading::default(); PLDM_MAX_COMPOSITE_SENSOR_COUNT];
+            if sensor.read(&mut readings[..count]).await.is_err() {
+                continue;
+            }
+
+            let mut internal = self.internal.lock().await;
+            let mut changed = None;
+            for (offset, reading) in readings[..count].iter().enumerate() {
+                let states = &mut internal.state_states[index][offset];
+                states.update(reading.present_state);
+                if changed.is_none() && states.present != states.event {
+                    changed = Some((offset, *states));
+                }
+            }
+            let Some((offset, states)) = changed else {
+                continue;
+            };
+
+            let event = SensorEvent {
+                sensor_id: sensor.sensor_id(),
+                data: SensorEventData::StateSensorState {
+                    sensor_offset: offset as u8,
+                    event_state: states.present,
+                    previous_event_state: states.event,
+                },
+            };
+            internal.pending_event = Some(PendingEvent::StateSensor {
+                index,
+                offset,
+                state: states.present,
+            });
+            let req = PlatformEventMessageRequest::new_sensor_event(
+                internal.alloc_instance_id(),
+                tid,
+                &event,
+            )
+            .map_err(MsgHandlerError::PldmCommon)?;
+            let len = req.encode(payload).map_err(MsgHandlerError::Codec)?;
+            return Ok(Some((receiver.eid, len)));
+        }
+
+        Ok(None)
+    }
+
+    /// Handles the response of the event receiver to the last `PlatformEventMessage` request.
+    ///
+    /// The event is marked as reported when accepted, and is otherwise generated again on the next
+    /// call to `generate_event`.
+    pub async fn handle_event_response(&self, payload: &mut [u8]) -> Result<(), MsgHandlerError> {
+        let resp = PlatformEventMessageResponse::decode(payload).map_err(MsgHandlerError::Codec)?;
+        let now = self.now();
+
+        let mut internal = self.internal.lock().await;
+        let Some(pending) = internal.pending_event.take() else {
+            return Ok(());
+        };
+        if resp.completion_code != PldmBaseCompletionCode::Success as u8 {
+            return Ok(());
+        }
+
+        match pending {
+            PendingEvent::NumericSensor { index, state } => {
+                internal.numeric_states[index].event = state;
+            }
+            PendingEvent::StateSensor {
+                index,
+                offset,
+                state,
+            } => {
+                internal.state_states[index][offset].event = state;
+            }
+            PendingEvent::Heartbeat => {
+                internal.last_heartbeat = now;
+                internal.heartbeat_seq = internal.heartbeat_seq.wrapping_add(1);
+            }
+        }
+        Ok(())
+    }
+}
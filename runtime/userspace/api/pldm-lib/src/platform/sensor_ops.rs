@@ -0,0 +1,86 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use pldm_common::protocol::platform::{
+    NumericSensorState, SensorId, SensorOperationalState, SensorValue,
+};
+
+#[derive(Debug)]
+pub enum SensorOpsError {
+    ReadError,
+    TooManySensors,
+}
+
+/// A reading of a numeric sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericSensorReading {
+    pub operational_state: SensorOperationalState,
+    /// The state of the reading with respect to the thresholds of the sensor.
+    pub present_state: NumericSensorState,
+    pub value: SensorValue,
+}
+
+/// The reading of one composite sensor of a state sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateSensorReading {
+    pub operational_state: SensorOperationalState,
+    /// A state value of the state set of the composite sensor.
+    pub present_state: u8,
+}
+
+impl Default for StateSensorReading {
+    fn default() -> Self {
+        Self {
+            operational_state: SensorOperationalState::StatusUnknown,
+            present_state: 0,
+        }
+    }
+}
+
+/// Trait for a numeric sensor exposed through PLDM for Platform Monitoring and Control,
+/// such as the fill level of a log.
+///
+/// The sensor must be described by a Numeric Sensor PDR in the PDR repository, and its readings
+/// must use the data size advertised by that PDR.
+#[async_trait(?Send)]
+pub trait NumericSensor {
+    /// Returns the sensor ID, matching the `sensor_id` field of the sensor's PDR.
+    fn sensor_id(&self) -> SensorId;
+
+    /// Asynchronously reads the sensor.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<NumericSensorReading, SensorOpsError>` - The present reading on success.
+    ///   On failure, returns a `SensorOpsError`.
+    async fn read(&self) -> Result<NumericSensorReading, SensorOpsError>;
+}
+
+/// Trait for a state sensor exposed through PLDM for Platform Monitoring and Control,
+/// such as the boot status or the FIPS self-test state.
+///
+/// The sensor must be described by a State Sensor PDR in the PDR repository, with one possible
+/// states entry per composite sensor.
+#[async_trait(?Send)]
+pub trait StateSensor {
+    /// Returns the sensor ID, matching the `sensor_id` field of the sensor's PDR.
+    fn sensor_id(&self) -> SensorId;
+
+    /// Returns the number of composite sensors, at most `PLDM_MAX_COMPOSITE_SENSOR_COUNT`.
+    fn composite_sensor_count(&self) -> usize;
+
+    /// Asynchronously reads the sensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `readings` - A mutable slice of `composite_sensor_count()` entries to store the reading
+    ///   of each composite sensor.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), SensorOpsError>` - On success, returns `Ok(())`. On failure, returns a
+    ///   `SensorOpsError`.
+    async fn read(&self, readings: &mut [StateSensorReading]) -> Result<(), SensorOpsError>;
+}
@@ -1,6 +1,12 @@
 // Licensed under the Apache-2.0 license
 
+use crate::timer::AsyncAlarm;
+use core::future::{poll_fn, Future};
+use core::pin::pin;
+use core::task::Poll;
 use libsyscall_caliptra::mctp::{Mctp, MessageInfo};
+use libsyscall_caliptra::DefaultSyscalls;
+use libtock_alarm::Milliseconds;
 use pldm_common::util::mctp_transport::{
     MctpCommonHeader, MCTP_COMMON_HEADER_OFFSET, MCTP_PLDM_MSG_TYPE,
 };
@@ -18,8 +24,12 @@ pub enum TransportError {
     SendError,
     ResponseNotExpected,
     NoRequestInFlight,
+    Timeout,
 }
 
+// Period at which a response is checked for while waiting with a timeout
+const RESPONSE_POLL_INTERVAL_MS: u32 = 10;
+
 pub struct MctpTransport {
     mctp: Mctp,
     cur_resp_ctx: Option<MessageInfo>,
@@ -52,7 +62,8 @@ impl MctpTransport {
         Ok(())
     }
 
-    pub async fn receive_response(&mut self, rsp: &mut [u8]) -> Result<(), TransportError> {
+    /// Receives the response to the last request sent and returns its length in bytes.
+    pub async fn receive_response(&mut self, rsp: &mut [u8]) -> Result<usize, TransportError> {
         // Reset msg buffer
         rsp.fill(0);
         let (rsp_len, _msg_info) = if let Some(msg_info) = &self.cur_req_ctx {
@@ -68,6 +79,54 @@ impl MctpTransport {
             Err(TransportError::BufferTooSmall)?;
         }
 
+        self.check_response(rsp, rsp_len as usize)
+    }
+
+    /// Receives the response to the last request sent, giving up after `timeout_ms`.
+    ///
+    /// A pending receive cannot be dropped, so on timeout it is cancelled in the MCTP driver
+    /// and the request is no longer in flight.
+    pub async fn receive_response_with_timeout(
+        &mut self,
+        rsp: &mut [u8],
+        timeout_ms: u32,
+    ) -> Result<usize, TransportError> {
+        let Some(msg_info) = self.cur_req_ctx.clone() else {
+            Err(TransportError::ResponseNotExpected)?
+        };
+
+        // Reset msg buffer
+        rsp.fill(0);
+        let mut waited_ms = 0;
+
+        let result = {
+            let mctp = &self.mctp;
+            let mut rx = pin!(mctp.receive_response(rsp, msg_info.tag, msg_info.eid));
+            loop {
+                if let Poll::Ready(result) = poll_fn(|cx| Poll::Ready(rx.as_mut().poll(cx))).await {
+                    break result;
+                }
+
+                if waited_ms >= timeout_ms {
+                    // The response may still complete the receive before the cancellation
+                    let _ = mctp.cancel_receive_response();
+                    break rx.await;
+                }
+                AsyncAlarm::<DefaultSyscalls>::sleep(Milliseconds(RESPONSE_POLL_INTERVAL_MS)).await;
+                waited_ms += RESPONSE_POLL_INTERVAL_MS;
+            }
+        };
+        let (rsp_len, _msg_info) = result.map_err(|_| TransportError::ReceiveError)?;
+
+        if rsp_len == 0 {
+            self.cur_req_ctx = None;
+            Err(TransportError::Timeout)?;
+        }
+
+        self.check_response(rsp, rsp_len as usize)
+    }
+
+    fn check_response(&mut self, rsp: &[u8], rsp_len: usize) -> Result<usize, TransportError> {
         // Check common header
         let mctp_hdr = MctpCommonHeader(rsp[MCTP_COMMON_HEADER_OFFSET]);
         if mctp_hdr.ic() != 0 || mctp_hdr.msg_type() != MCTP_PLDM_MSG_TYPE {
@@ -75,7 +134,7 @@ impl MctpTransport {
         }
 
         self.cur_req_ctx = None;
-        Ok(())
+        Ok(rsp_len)
     }
 
     pub async fn receive_request(&mut self, req: &mut [u8]) -> Result<(), TransportError> {
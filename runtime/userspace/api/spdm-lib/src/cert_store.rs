@@ -6,12 +6,12 @@ use crate::error::{SpdmError, SpdmResult};
 use crate::protocol::*;
 use alloc::boxed::Box;
 use async_trait::async_trait;
-use libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
+use libapi_caliptra::crypto::asym::AsymAlgo;
 use libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
 use libapi_caliptra::error::CaliptraApiError;
 use zerocopy::IntoBytes;
 
-pub const MAX_CERT_SLOTS_SUPPORTED: u8 = 2;
+pub const MAX_CERT_SLOTS_SUPPORTED: u8 = 8;
 
 #[derive(Debug, PartialEq)]
 pub enum CertStoreError {
@@ -24,6 +24,9 @@ pub enum CertStoreError {
     BufferTooSmall,
     InvalidOffset,
     CertReadError,
+    CertWriteError,
+    ReadOnlySlot,
+    UnsupportedOperation,
     CaliptraApi(CaliptraApiError),
 }
 pub type CertStoreResult<T> = Result<T, CertStoreError>;
@@ -37,14 +40,15 @@ pub trait SpdmCertStore {
     /// * `u8` - The number of supported certificate slots.
     fn slot_count(&self) -> u8;
 
-    /// Check if the slot is provisioned.
+    /// Check if the slot is provisioned with a certificate chain of the asymmetric algorithm.
     ///
     /// # Arguments
     /// * `slot_id` - The slot ID of the certificate chain.
+    /// * `asym_algo` - The asymmetric algorithm to indicate the type of certificate chain.
     ///
     /// # Returns
     /// * `bool` - True if the slot is provisioned, false otherwise.
-    async fn is_provisioned(&self, slot_id: u8) -> bool;
+    async fn is_provisioned(&self, slot_id: u8, asym_algo: AsymAlgo) -> bool;
 
     /// Get the length of the certificate chain in bytes.
     /// The certificate chain is in ASN.1 DER-encoded X.509 v3 format.
@@ -102,7 +106,7 @@ pub trait SpdmCertStore {
     /// * `slot_id` - The slot ID of the certificate chain.
     /// * `asym_algo` - Asymmetric algorithm to sign with.
     /// * `hash` - The hash to sign.
-    /// * `signature` - The output buffer to store the ECC384 signature.
+    /// * `signature` - The output buffer to store the signature, of `asym_algo.signature_size()` bytes.
     ///
     /// # Returns
     /// * `()` - Ok if successful, error otherwise.
@@ -111,7 +115,7 @@ pub trait SpdmCertStore {
         slot_id: u8,
         asym_algo: AsymAlgo,
         hash: &'a [u8; SHA384_HASH_SIZE],
-        signature: &'a mut [u8; ECC_P384_SIGNATURE_SIZE],
+        signature: &'a mut [u8],
     ) -> CertStoreResult<()>;
 
     /// Get the KeyPairID associated with the certificate chain if SPDM responder supports
@@ -144,6 +148,80 @@ pub trait SpdmCertStore {
     /// # Returns
     /// * `KeyUsageMask` - The KeyUsageMask associated with the certificate chain or None if not supported or not found.
     async fn key_usage_mask(&self, slot_id: u8) -> Option<KeyUsageMask>;
+
+    /// Generate a PKCS#10 certificate signing request (CSR) for the device key used by the
+    /// writable certificate slots. Required if the responder advertises CSR_CAP.
+    /// The `csr` module helps build the CSR.
+    ///
+    /// # Arguments
+    /// * `asym_algo` - Asymmetric algorithm of the key to generate the CSR for.
+    /// * `requester_info` - DER-encoded CertificationRequestInfo supplied by the requester,
+    ///   already checked to be well-formed. Its subject and attributes must be applied to
+    ///   the CSR. May be empty.
+    /// * `csr` - The output buffer to store the DER-encoded CSR.
+    ///
+    /// # Returns
+    /// * `usize` - The length of the CSR in bytes or error.
+    async fn get_csr<'a>(
+        &self,
+        _asym_algo: AsymAlgo,
+        _requester_info: &'a [u8],
+        _csr: &'a mut [u8],
+    ) -> CertStoreResult<usize> {
+        Err(CertStoreError::UnsupportedOperation)
+    }
+
+    /// Get the public key of the device key certified by the chains installed with
+    /// SET_CERTIFICATE. Required if the responder advertises SET_CERT_CAP.
+    /// ECC P-384 keys are returned as the affine coordinates X || Y.
+    ///
+    /// # Arguments
+    /// * `asym_algo` - Asymmetric algorithm of the device key.
+    /// * `public_key` - The output buffer to store the public key.
+    ///
+    /// # Returns
+    /// * `usize` - The length of the public key in bytes or error.
+    async fn device_public_key<'a>(
+        &self,
+        _asym_algo: AsymAlgo,
+        _public_key: &'a mut [u8],
+    ) -> CertStoreResult<usize> {
+        Err(CertStoreError::UnsupportedOperation)
+    }
+
+    /// Install a certificate chain into the slot and persist it.
+    /// Required if the responder advertises SET_CERT_CAP.
+    /// The chain has already been checked to be well-formed and anchored by `root_hash`, and
+    /// its leaf certificate to certify the key returned by `device_public_key`.
+    ///
+    /// # Arguments
+    /// * `slot_id` - The slot ID of the certificate chain.
+    /// * `asym_algo` - The asymmetric algorithm to indicate the type of Certificate chain.
+    /// * `root_hash` - The hash of the root certificate in the certificate chain.
+    /// * `cert_chain` - The ASN.1 DER-encoded X.509 v3 certificates, root certificate first.
+    ///
+    /// # Returns
+    /// * `()` - Ok if successful, error otherwise.
+    async fn set_cert_chain<'a>(
+        &self,
+        _slot_id: u8,
+        _asym_algo: AsymAlgo,
+        _root_hash: &'a [u8; SHA384_HASH_SIZE],
+        _cert_chain: &'a [u8],
+    ) -> CertStoreResult<()> {
+        Err(CertStoreError::UnsupportedOperation)
+    }
+
+    /// Erase the certificate chain installed in the slot.
+    ///
+    /// # Arguments
+    /// * `slot_id` - The slot ID of the certificate chain.
+    ///
+    /// # Returns
+    /// * `()` - Ok if successful, error otherwise.
+    async fn erase_cert_chain(&self, _slot_id: u8) -> CertStoreResult<()> {
+        Err(CertStoreError::UnsupportedOperation)
+    }
 }
 
 pub(crate) fn validate_cert_store(cert_store: &dyn SpdmCertStore) -> SpdmResult<()> {
@@ -154,13 +232,16 @@ pub(crate) fn validate_cert_store(cert_store: &dyn SpdmCertStore) -> SpdmResult<
     Ok(())
 }
 
-pub(crate) async fn cert_slot_mask(cert_store: &dyn SpdmCertStore) -> (u8, u8) {
+pub(crate) async fn cert_slot_mask(
+    cert_store: &dyn SpdmCertStore,
+    asym_algo: AsymAlgo,
+) -> (u8, u8) {
     let slot_count = cert_store.slot_count().min(MAX_CERT_SLOTS_SUPPORTED);
-    let supported_slot_mask = (1 << slot_count) - 1;
+    let supported_slot_mask = ((1u16 << slot_count) - 1) as u8;
 
     let mut provisioned_slot_mask = 0;
     for i in 0..slot_count {
-        if cert_store.is_provisioned(i).await {
+        if cert_store.is_provisioned(i, asym_algo).await {
             provisioned_slot_mask |= 1 << i;
         }
     }
@@ -1,6 +1,7 @@
 // Licensed under the Apache-2.0 license
 
 use crate::commands::certificate_rsp::CertificateResponse;
+use crate::commands::measurement_extension_log_rsp::MelResponse;
 use crate::commands::measurements_rsp::MeasurementsResponse;
 use crate::commands::vendor_defined_rsp::VendorLargeResponse;
 
@@ -16,6 +17,12 @@ pub enum ChunkError {
     InvalidChunkSeqNum,
     /// Invalid message offset provided
     InvalidMessageOffset,
+    /// No large request is currently in progress
+    NoLargeRequestInProgress,
+    /// Chunk received over a different session than the first chunk
+    InvalidChunkSession,
+    /// Chunk size exceeds the remaining size of the large request
+    InvalidChunkSize,
 }
 
 /// Stores state and metadata for managing ongoing large message requests and responses.
@@ -51,7 +58,10 @@ pub type ChunkResult<T> = Result<T, ChunkError>;
 pub(crate) enum LargeResponse {
     Certificate(CertificateResponse),
     Measurements(MeasurementsResponse),
+    MeasurementExtensionLog(MelResponse),
     Vdm(VendorLargeResponse),
+    /// Response generated in the large response buffer, starting at the given offset
+    Buffered(usize),
 }
 
 /// Manages the context for ongoing large message responses
@@ -169,3 +179,142 @@ impl LargeResponseCtx {
         self.chunk_state.bytes_transferred
     }
 }
+
+/// Manages the context for the reassembly of an ongoing large request (CHUNK_SEND)
+#[derive(Default)]
+pub(crate) struct LargeRequestCtx {
+    chunk_state: ChunkState,
+    /// Session over which the first chunk was received
+    session_id: Option<u32>,
+}
+
+impl LargeRequestCtx {
+    /// Reset the context, discarding any partially received large request
+    pub(crate) fn reset(&mut self) {
+        self.chunk_state.reset();
+        self.session_id = None;
+    }
+
+    /// Initialize the context for a large request on receipt of its first chunk
+    ///
+    /// # Arguments
+    /// * `large_req_size` - The size of the large request message
+    /// * `handle` - The handle chosen by the Requester for this large request
+    /// * `session_id` - The session over which the chunk was received, if any
+    pub fn init(&mut self, large_req_size: usize, handle: u8, session_id: Option<u32>) {
+        self.chunk_state.init(large_req_size, handle);
+        self.session_id = session_id;
+    }
+
+    /// Is large message request in progress
+    ///
+    /// # Returns
+    /// Returns `true` if a large request is currently being received, otherwise `false`
+    pub fn in_progress(&self) -> bool {
+        self.chunk_state.in_use
+    }
+
+    /// Validates that the chunk continues the large request in progress
+    ///
+    /// # Arguments
+    /// * `handle` - The chunk handle to validate
+    /// * `chunk_seq_num` - The sequence number to validate
+    /// * `session_id` - The session over which the chunk was received, if any
+    ///
+    /// # Returns
+    /// `Ok(())` if valid, or a specific `ChunkError` if validation fails
+    pub fn validate_chunk(
+        &self,
+        handle: u8,
+        chunk_seq_num: u16,
+        session_id: Option<u32>,
+    ) -> ChunkResult<()> {
+        if !self.chunk_state.in_use {
+            return Err(ChunkError::NoLargeRequestInProgress);
+        }
+        if self.chunk_state.handle != handle {
+            return Err(ChunkError::InvalidChunkHandle);
+        }
+        if self.chunk_state.seq_num != chunk_seq_num {
+            return Err(ChunkError::InvalidChunkSeqNum);
+        }
+        if self.session_id != session_id {
+            return Err(ChunkError::InvalidChunkSession);
+        }
+        Ok(())
+    }
+
+    /// Returns the total size of the large request being received
+    pub fn large_request_size(&self) -> usize {
+        self.chunk_state.large_msg_size
+    }
+
+    pub fn bytes_received(&self) -> usize {
+        self.chunk_state.bytes_transferred
+    }
+
+    /// Records that a chunk has been received and updates internal state
+    ///
+    /// # Arguments
+    /// * `chunk_size` - The size of the chunk that was received
+    ///
+    /// # Returns
+    /// `Ok(true)` if the large request is now complete, `Ok(false)` if more chunks are expected
+    pub fn next_chunk_received(&mut self, chunk_size: usize) -> ChunkResult<bool> {
+        if !self.chunk_state.in_use {
+            return Err(ChunkError::NoLargeRequestInProgress);
+        }
+        let rem_len = self.chunk_state.large_msg_size - self.chunk_state.bytes_transferred;
+        if chunk_size == 0 || chunk_size > rem_len {
+            return Err(ChunkError::InvalidChunkSize);
+        }
+        self.chunk_state.bytes_transferred += chunk_size;
+        self.chunk_state.seq_num = self.chunk_state.seq_num.wrapping_add(1);
+        Ok(self.chunk_state.bytes_transferred == self.chunk_state.large_msg_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_large_request_reassembly() {
+        let mut ctx = LargeRequestCtx::default();
+        assert!(!ctx.in_progress());
+        assert_eq!(
+            ctx.validate_chunk(1, 0, None),
+            Err(ChunkError::NoLargeRequestInProgress)
+        );
+
+        ctx.init(100, 7, Some(0xFFFE_FFFE));
+        assert!(ctx.in_progress());
+        assert_eq!(ctx.validate_chunk(7, 0, Some(0xFFFE_FFFE)), Ok(()));
+        assert_eq!(ctx.next_chunk_received(60), Ok(false));
+
+        assert_eq!(
+            ctx.validate_chunk(8, 1, Some(0xFFFE_FFFE)),
+            Err(ChunkError::InvalidChunkHandle)
+        );
+        assert_eq!(
+            ctx.validate_chunk(7, 0, Some(0xFFFE_FFFE)),
+            Err(ChunkError::InvalidChunkSeqNum)
+        );
+        assert_eq!(
+            ctx.validate_chunk(7, 1, None),
+            Err(ChunkError::InvalidChunkSession)
+        );
+        assert_eq!(ctx.validate_chunk(7, 1, Some(0xFFFE_FFFE)), Ok(()));
+
+        // A chunk must not overrun the announced size of the large request
+        assert_eq!(
+            ctx.next_chunk_received(41),
+            Err(ChunkError::InvalidChunkSize)
+        );
+        assert_eq!(ctx.next_chunk_received(40), Ok(true));
+        assert_eq!(ctx.bytes_received(), 100);
+
+        ctx.reset();
+        assert!(!ctx.in_progress());
+    }
+}
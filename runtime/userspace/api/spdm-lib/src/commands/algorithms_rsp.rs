@@ -26,7 +26,8 @@ struct NegotiateAlgorithmsReq {
     other_param_support: OtherParamSupport,
     base_asym_algo: BaseAsymAlgo,
     base_hash_algo: BaseHashAlgo,
-    reserved_1: [u8; 12],
+    pqc_asym_algo: PqcAsymAlgo,
+    reserved_1: [u8; 8],
     ext_asyn_count: u8,
     ext_hash_count: u8,
     reserved_2: u8,
@@ -81,7 +82,8 @@ struct AlgorithmsResp {
     measurement_hash_algo: MeasurementHashAlgo,
     base_asym_sel: BaseAsymAlgo,
     base_hash_sel: BaseHashAlgo,
-    reserved_2: [u8; 11],
+    pqc_asym_sel: PqcAsymAlgo,
+    reserved_2: [u8; 7],
     mel_specification_sel: MelSpecification,
     ext_asym_sel_count: u8,
     ext_hash_sel_count: u8,
@@ -106,7 +108,20 @@ enum AlgType {
     AeadCipherSuite = 3,
     ReqBaseAsymAlg = 4,
     KeySchedule = 5,
+    ReqPqcAsymAlg = 6,
+    Kem = 7,
 }
+
+impl AlgType {
+    // Size of the AlgSupported field of the algorithm structure
+    fn fixed_alg_count(&self) -> u8 {
+        match self {
+            AlgType::ReqPqcAsymAlg | AlgType::Kem => 4,
+            _ => 2,
+        }
+    }
+}
+
 impl TryFrom<u8> for AlgType {
     type Error = SpdmError;
 
@@ -116,6 +131,8 @@ impl TryFrom<u8> for AlgType {
             3 => Ok(AlgType::AeadCipherSuite),
             4 => Ok(AlgType::ReqBaseAsymAlg),
             5 => Ok(AlgType::KeySchedule),
+            6 => Ok(AlgType::ReqPqcAsymAlg),
+            7 => Ok(AlgType::Kem),
             _ => Err(SpdmError::InvalidParam),
         }
     }
@@ -156,6 +173,28 @@ pub(crate) fn selected_measurement_specification(ctx: &SpdmContext) -> Measureme
     measurement_specification_sel
 }
 
+/// Returns the selected BaseAsymSel and PQCAsymSel. At most one of them is non-zero,
+/// a common PQC asymmetric algorithm being preferred over a traditional one.
+pub(crate) fn selected_asym_algorithms(ctx: &SpdmContext) -> (BaseAsymAlgo, PqcAsymAlgo) {
+    let local_algorithms = &ctx.local_algorithms.device_algorithms;
+    let peer_algorithms = ctx.state.connection_info.peer_algorithms();
+    let algorithm_priority_table = &ctx.local_algorithms.algorithm_priority_table;
+
+    let pqc_asym_sel = PqcAsymAlgo(local_algorithms.pqc_asym_algo.0.prioritize(
+        &peer_algorithms.pqc_asym_algo.0,
+        algorithm_priority_table.pqc_asym_algo,
+    ));
+    if pqc_asym_sel.0 != 0 {
+        return (BaseAsymAlgo::default(), pqc_asym_sel);
+    }
+
+    let base_asym_sel = BaseAsymAlgo(local_algorithms.base_asym_algo.0.prioritize(
+        &peer_algorithms.base_asym_algo.0,
+        algorithm_priority_table.base_asym_algo,
+    ));
+    (base_asym_sel, PqcAsymAlgo::default())
+}
+
 async fn process_negotiate_algorithms_request<'a>(
     ctx: &mut SpdmContext<'a>,
     spdm_hdr: SpdmMsgHdr,
@@ -171,7 +210,12 @@ async fn process_negotiate_algorithms_request<'a>(
     })?;
 
     // Reserved fields check
-    if req.param2 != 0 || req.reserved_1 != [0; 12] || req.reserved_2 != 0 {
+    if req.param2 != 0 || req.reserved_1 != [0; 8] || req.reserved_2 != 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // PQCAsymAlgo is reserved prior to v1.4
+    if connection_version < SpdmVersion::V14 && req.pqc_asym_algo.0 != 0 {
         Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
     }
 
@@ -209,6 +253,11 @@ async fn process_negotiate_algorithms_request<'a>(
             ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
         })?;
 
+        // The PQC algorithm types are only defined from v1.4
+        if connection_version < SpdmVersion::V14 && alg_type.fixed_alg_count() != 2 {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+
         // AlgType shall monotonically increase
         if i > 0 && prev_alg_type > alg_struct.alg_type() {
             Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
@@ -230,15 +279,24 @@ async fn process_negotiate_algorithms_request<'a>(
                 req_base_asym_algo = ReqBaseAsymAlg(alg_struct.alg_supported())
             }
             AlgType::KeySchedule => key_schedule = KeySchedule(alg_struct.alg_supported()),
+            // Requester PQC signing and KEM algorithms are not supported
+            AlgType::ReqPqcAsymAlg | AlgType::Kem => {}
         }
 
         let ext_alg_count = alg_struct.ext_alg_count();
         total_ext_alg_count += ext_alg_count;
 
         let fixed_alg_count = alg_struct.fixed_alg_count();
-        if fixed_alg_count != 2 {
+        if fixed_alg_count != alg_type.fixed_alg_count() {
             Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
         }
+
+        // Skip the remainder of a 4-byte AlgSupported field
+        req_payload
+            .pull_data(fixed_alg_count as usize - 2)
+            .map_err(|_| {
+                ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+            })?;
     }
 
     // Total number of extended algorithms check
@@ -264,6 +322,7 @@ async fn process_negotiate_algorithms_request<'a>(
         other_param_support: req.other_param_support,
         measurement_hash_algo,
         base_asym_algo: req.base_asym_algo,
+        pqc_asym_algo: req.pqc_asym_algo,
         base_hash_algo: req.base_hash_algo,
         mel_specification: req.mel_specification,
         dhe_group,
@@ -325,11 +384,8 @@ async fn generate_algorithms_response<'a>(
         measurement_hash_algo = local_algorithms.measurement_hash_algo;
     }
 
-    // BaseAsymSel
-    let base_asym_sel = BaseAsymAlgo(local_algorithms.base_asym_algo.0.prioritize(
-        &peer_algorithms.base_asym_algo.0,
-        algorithm_priority_table.base_asym_algo,
-    ));
+    // BaseAsymSel and PQCAsymSel
+    let (base_asym_sel, pqc_asym_sel) = selected_asym_algorithms(ctx);
 
     // BaseHashSel
     let base_hash_sel = local_algorithms.base_hash_algo.prioritize(
@@ -352,7 +408,8 @@ async fn generate_algorithms_response<'a>(
         measurement_hash_algo,
         base_asym_sel,
         base_hash_sel,
-        reserved_2: [0; 11],
+        pqc_asym_sel,
+        reserved_2: [0; 7],
         mel_specification_sel,
         ext_asym_sel_count: 0,
         ext_hash_sel_count: 0,
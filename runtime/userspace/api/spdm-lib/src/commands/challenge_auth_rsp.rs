@@ -4,6 +4,7 @@ use crate::codec::{Codec, CommonCodec, MessageBuf};
 use crate::commands::algorithms_rsp::selected_measurement_specification;
 use crate::commands::error_rsp::ErrorCode;
 use crate::context::SpdmContext;
+use crate::encap_ctx::EncapFlow;
 use crate::error::{CommandError, CommandResult};
 use crate::protocol::*;
 use crate::state::ConnectionState;
@@ -51,7 +52,8 @@ bitfield! {
     impl Debug;
     u8;
     pub slot_id, set_slot_id: 3, 0;
-    reserved, _: 7, 4;
+    reserved, _: 6, 4;
+    pub basic_mut_auth_req, set_basic_mut_auth_req: 7, 7;
 }
 
 async fn process_challenge<'a>(
@@ -84,10 +86,11 @@ async fn process_challenge<'a>(
     }
 
     // Note: Pubkey of the responder will not be pre-provisioned to Requester. So slot ID 0xFF is invalid.
+    let asym_algo = ctx.validate_negotiated_base_asym_algo(req_payload)?;
     if challenge_req.slot_id >= MAX_CERT_SLOTS_SUPPORTED
         || !ctx
             .device_certs_store
-            .is_provisioned(challenge_req.slot_id)
+            .is_provisioned(challenge_req.slot_id, asym_algo)
             .await
     {
         Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
@@ -163,21 +166,17 @@ async fn encode_m1_signature<'a>(
         m1_transcript_hash
     };
 
-    let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
-
-    ctx.device_certs_store
-        .sign_hash(slot_id, asym_algo, &tbs, &mut signature)
-        .await
-        .map_err(|e| (false, CommandError::CertStore(e)))?;
-
-    // Encode the signature
+    // Sign directly into the response
     let sig_len = asym_algo.signature_size();
     rsp.put_data(sig_len)
         .map_err(|e| (false, CommandError::Codec(e)))?;
     let sig_buf = rsp
         .data_mut(sig_len)
         .map_err(|e| (false, CommandError::Codec(e)))?;
-    sig_buf.copy_from_slice(&signature[..sig_len]);
+    ctx.device_certs_store
+        .sign_hash(slot_id, asym_algo, &tbs, sig_buf)
+        .await
+        .map_err(|e| (false, CommandError::CertStore(e)))?;
     rsp.pull_data(sig_len)
         .map_err(|e| (false, CommandError::Codec(e)))?;
 
@@ -188,9 +187,15 @@ async fn encode_challenge_auth_rsp_base<'a>(
     ctx: &mut SpdmContext<'a>,
     slot_id: u8,
     asym_algo: AsymAlgo,
+    basic_mut_auth_req: bool,
     rsp: &mut MessageBuf<'a>,
 ) -> CommandResult<usize> {
     let mut challenge_auth_rsp = ChallengeAuthRspBase::new(slot_id);
+    if basic_mut_auth_req {
+        challenge_auth_rsp
+            .challenge_auth_attr
+            .set_basic_mut_auth_req(1);
+    }
 
     // Get the certificate chain hash
     spdm_cert_chain_hash(
@@ -242,6 +247,7 @@ async fn generate_challenge_auth_response<'a>(
     slot_id: u8,
     meas_summary_hash_type: u8,
     requester_context: Option<RequesterContext>,
+    basic_mut_auth_req: bool,
     rsp: &mut MessageBuf<'a>,
 ) -> CommandResult<()> {
     // Get the selected asymmetric algorithm
@@ -256,7 +262,8 @@ async fn generate_challenge_auth_response<'a>(
         .map_err(|e| (false, CommandError::Codec(e)))?;
 
     // Encode the CHALLENGE_AUTH response fixed fields
-    payload_len += encode_challenge_auth_rsp_base(ctx, slot_id, asym_algo, rsp).await?;
+    payload_len +=
+        encode_challenge_auth_rsp_base(ctx, slot_id, asym_algo, basic_mut_auth_req, rsp).await?;
 
     // Get the measurement summary hash
     if meas_summary_hash_type != 0 {
@@ -307,21 +314,50 @@ pub(crate) async fn handle_challenge<'a>(
     let (slot_id, meas_summary_hash_type, req_context) =
         process_challenge(ctx, spdm_hdr, req_payload).await?;
 
+    // Basic mutual authentication is only defined in SPDM 1.1. The Requester is then
+    // authenticated with encapsulated requests once CHALLENGE_AUTH is received.
+    let basic_mut_auth_req =
+        ctx.state.connection_info.version_number() == SpdmVersion::V11 && ctx.mut_auth_supported();
+
     // Generate CHALLENGE_AUTH response
-    ctx.prepare_response_buffer(req_payload)?;
-    generate_challenge_auth_response(
-        ctx,
-        slot_id,
-        meas_summary_hash_type,
-        req_context,
-        req_payload,
-    )
-    .await?;
+    let asym_algo = ctx.validate_negotiated_base_asym_algo(req_payload)?;
+    if ctx.signed_response_in_large_buffer(asym_algo) {
+        let mut large_rsp = ctx.take_large_response_buffer(req_payload)?;
+        let result = generate_challenge_auth_response(
+            ctx,
+            slot_id,
+            meas_summary_hash_type,
+            req_context,
+            basic_mut_auth_req,
+            &mut large_rsp,
+        )
+        .await;
+        ctx.complete_large_response(large_rsp, result, req_payload)?;
+    } else {
+        ctx.prepare_response_buffer(req_payload)?;
+        generate_challenge_auth_response(
+            ctx,
+            slot_id,
+            meas_summary_hash_type,
+            req_context,
+            basic_mut_auth_req,
+            req_payload,
+        )
+        .await?;
+    }
 
     // Change the connection state to Authenticated
     ctx.state
         .connection_info
         .set_state(ConnectionState::Authenticated);
 
+    // A new CHALLENGE restarts the authentication of the Requester
+    ctx.state.connection_info.set_requester_auth_slot_id(None);
+    if basic_mut_auth_req {
+        ctx.shared_transcript
+            .reset_context(TranscriptContext::MutM1);
+        ctx.encap_context.init(EncapFlow::Basic);
+    }
+
     Ok(())
 }
@@ -187,6 +187,31 @@ async fn encode_chunk_data(
                 rsp.pull_data(chunk_size)
                     .map_err(|e| (false, CommandError::Codec(e)))?;
             }
+            LargeResponse::MeasurementExtensionLog(mel_rsp) => {
+                // Get the chunk data from the measurement extension log response
+                let measurement_log = ctx
+                    .measurement_log
+                    .ok_or((false, CommandError::InvalidChunkContext))?;
+                mel_rsp
+                    .get_chunk(measurement_log, offset, chunk_buf)
+                    .await?;
+                rsp.pull_data(chunk_size)
+                    .map_err(|e| (false, CommandError::Codec(e)))?;
+            }
+            LargeResponse::Buffered(rsp_offset) => {
+                // Copy the chunk data from the large response buffer
+                let large_rsp_buf = ctx
+                    .large_rsp_buffer
+                    .as_ref()
+                    .ok_or((false, CommandError::InvalidChunkContext))?;
+                let start = rsp_offset + offset;
+                let chunk_data = large_rsp_buf
+                    .get(start..start + chunk_size)
+                    .ok_or((false, CommandError::InvalidChunkContext))?;
+                chunk_buf.copy_from_slice(chunk_data);
+                rsp.pull_data(chunk_size)
+                    .map_err(|e| (false, CommandError::Codec(e)))?;
+            }
             LargeResponse::Vdm(_vdm_rsp) => {
                 todo!("implement chunking logic for VDM response")
             }
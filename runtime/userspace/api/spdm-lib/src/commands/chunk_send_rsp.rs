@@ -0,0 +1,334 @@
+// Licensed under the Apache-2.0 license
+use crate::chunk_ctx::LargeResponse;
+use crate::codec::{encode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::{encode_error_response, ErrorCode};
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use bitfield::bitfield;
+use core::mem::size_of;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+// Size of an ERROR message with up to one byte of extended error data (LargeResponse handle)
+const ERROR_MSG_SIZE: usize = 5;
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct ChunkSendReqFixed {
+    chunk_sender_attr: ChunkSenderAttr,
+    handle: u8,
+    chunk_seq_num: u16,
+    reserved: u16,
+    chunk_size: u32,
+}
+impl CommonCodec for ChunkSendReqFixed {}
+
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable)]
+    #[repr(C)]
+    struct ChunkSenderAttr(u8);
+    impl Debug;
+    u8;
+    pub last_chunk, set_last_chunk: 0, 0;
+    reserved, _: 7, 1;
+}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct LargeMessageSize(u32);
+impl CommonCodec for LargeMessageSize {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct ChunkSendAckFixed {
+    chunk_receiver_attr: ChunkReceiverAttr,
+    handle: u8,
+    chunk_seq_num: u16,
+}
+impl CommonCodec for ChunkSendAckFixed {}
+
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable)]
+    #[repr(C)]
+    struct ChunkReceiverAttr(u8);
+    impl Debug;
+    u8;
+    pub early_error_detected, set_early_error_detected: 0, 0;
+    reserved, _: 7, 1;
+}
+
+enum ChunkSendStatus {
+    /// More chunks of the large request are expected
+    InProgress,
+    /// All chunks of the large request have been received
+    Complete,
+    /// The large request is rejected before all chunks have been received
+    EarlyError(ErrorCode),
+}
+
+/// Returns the maximum size of a large request the responder can reassemble.
+fn max_large_request_size(ctx: &SpdmContext) -> usize {
+    let buf_len = ctx.large_req_buffer.as_ref().map_or(0, |buf| buf.len());
+    (ctx.local_capabilities.max_spdm_msg_size as usize).min(buf_len)
+}
+
+// Encodes an ERROR message to be carried in ResponseToLargeRequest
+fn encode_error_msg(
+    ctx: &SpdmContext,
+    error_code: ErrorCode,
+    extended_data: Option<&[u8]>,
+    buf: &mut [u8; ERROR_MSG_SIZE],
+) -> CommandResult<usize> {
+    let spdm_version = ctx.state.connection_info.version_number();
+    let mut error_msg = MessageBuf::new(buf);
+    match encode_error_response(&mut error_msg, spdm_version, error_code, 0, extended_data) {
+        (true, _) => Ok(error_msg.data_len()),
+        (false, e) => Err((false, e)),
+    }
+}
+
+fn process_chunk_send<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<(u8, u16, ChunkSendStatus)> {
+    // Check that the spdm version valid and is >= SPDM_VERSION_1_2
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    if connection_version < SpdmVersion::V12 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Decode the request payload
+    let req = ChunkSendReqFixed::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+    let handle = req.handle;
+    let chunk_seq_num = req.chunk_seq_num;
+    let chunk_size = req.chunk_size as usize;
+    let session_id = ctx.session_mgr.active_session_id();
+
+    if chunk_seq_num == 0 {
+        // The first chunk starts a new large request, discarding any partial one
+        ctx.large_req_context.reset();
+
+        let large_msg_size = LargeMessageSize::decode(req_payload).map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+        let large_msg_size = large_msg_size.0 as usize;
+
+        // A request that fits in a single transfer must not be chunked
+        if large_msg_size <= ctx.min_data_transfer_size() {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+
+        if large_msg_size > max_large_request_size(ctx) {
+            return Ok((
+                handle,
+                chunk_seq_num,
+                ChunkSendStatus::EarlyError(ErrorCode::RequestTooLarge),
+            ));
+        }
+
+        ctx.large_req_context
+            .init(large_msg_size, handle, session_id);
+    } else if ctx
+        .large_req_context
+        .validate_chunk(handle, chunk_seq_num, session_id)
+        .is_err()
+    {
+        ctx.large_req_context.reset();
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    let offset = ctx.large_req_context.bytes_received();
+    let complete = if req_payload.data_len() < chunk_size {
+        None
+    } else {
+        ctx.large_req_context.next_chunk_received(chunk_size).ok()
+    };
+
+    // The LastChunk bit must be set on the chunk that completes the large request only
+    let last_chunk = req.chunk_sender_attr.last_chunk() == 1;
+    if complete != Some(last_chunk) {
+        ctx.large_req_context.reset();
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // Append the chunk to the large request
+    let chunk = req_payload
+        .data(chunk_size)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    let large_req_buf = ctx
+        .large_req_buffer
+        .as_mut()
+        .ok_or((false, CommandError::InvalidChunkContext))?;
+    large_req_buf[offset..offset + chunk_size].copy_from_slice(chunk);
+
+    let status = if last_chunk {
+        ChunkSendStatus::Complete
+    } else {
+        ChunkSendStatus::InProgress
+    };
+    Ok((handle, chunk_seq_num, status))
+}
+
+fn generate_chunk_send_ack(
+    ctx: &mut SpdmContext<'_>,
+    handle: u8,
+    chunk_seq_num: u16,
+    early_error: bool,
+    response_to_large_req: Option<&[u8]>,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Spdm Header first
+    let spdm_hdr = SpdmMsgHdr::new(
+        ctx.state.connection_info.version_number(),
+        ReqRespCode::ChunkSendAck,
+    );
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let mut chunk_receiver_attr = ChunkReceiverAttr(0);
+    chunk_receiver_attr.set_early_error_detected(early_error as u8);
+    let ack_fixed = ChunkSendAckFixed {
+        chunk_receiver_attr,
+        handle,
+        chunk_seq_num,
+    };
+    payload_len += ack_fixed
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    // ResponseToLargeRequest is only present with the last chunk or an early error
+    if let Some(response) = response_to_large_req {
+        payload_len +=
+            encode_u8_slice(response, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
+    }
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+/// Copies the response to a large request into the large response buffer, to be sent
+/// with CHUNK_GET. Returns the handle of the large response, or None if the response
+/// cannot be buffered.
+fn buffer_large_response(ctx: &mut SpdmContext<'_>, large_rsp: &[u8]) -> Option<u8> {
+    if !ctx.support_large_msg_chunking() {
+        return None;
+    }
+    let large_rsp_buf = ctx.large_rsp_buffer.as_mut()?;
+    large_rsp_buf
+        .get_mut(..large_rsp.len())?
+        .copy_from_slice(large_rsp);
+    Some(
+        ctx.large_resp_context
+            .init(LargeResponse::Buffered(0), large_rsp.len()),
+    )
+}
+
+/// Generates the CHUNK_SEND_ACK response for the last chunk of a large request,
+/// carrying the response to the reassembled large request.
+///
+/// # Arguments
+/// * `handle` - The handle of the large request
+/// * `chunk_seq_num` - The sequence number of the last chunk
+/// * `large_rsp` - The message buffer holding the response to the large request
+/// * `rsp` - The message buffer for the CHUNK_SEND_ACK response
+pub(crate) fn generate_large_request_ack(
+    ctx: &mut SpdmContext<'_>,
+    handle: u8,
+    chunk_seq_num: u16,
+    large_rsp: &MessageBuf<'_>,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    ctx.prepare_response_buffer(rsp)?;
+
+    let large_rsp_len = large_rsp.data_len();
+    let ack_size = size_of::<SpdmMsgHdr>() + size_of::<ChunkSendAckFixed>();
+    if ack_size + large_rsp_len <= ctx.min_data_transfer_size() {
+        let response = large_rsp
+            .data(large_rsp_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        return generate_chunk_send_ack(ctx, handle, chunk_seq_num, false, Some(response), rsp);
+    }
+
+    // The response does not fit in the CHUNK_SEND_ACK. It is retrieved with CHUNK_GET
+    // using the handle in ERROR(LargeResponse), if it can be buffered.
+    let large_rsp = large_rsp
+        .data(large_rsp_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    let mut error_msg = [0u8; ERROR_MSG_SIZE];
+    let error_msg_len = match buffer_large_response(ctx, large_rsp) {
+        Some(large_rsp_handle) => encode_error_msg(
+            ctx,
+            ErrorCode::LargeResponse,
+            Some(&[large_rsp_handle]),
+            &mut error_msg,
+        )?,
+        None => encode_error_msg(ctx, ErrorCode::ResponseTooLarge, None, &mut error_msg)?,
+    };
+    generate_chunk_send_ack(
+        ctx,
+        handle,
+        chunk_seq_num,
+        false,
+        Some(&error_msg[..error_msg_len]),
+        rsp,
+    )
+}
+
+/// Handles a CHUNK_SEND request.
+///
+/// # Returns
+/// `Some((handle, chunk_seq_num))` once the last chunk of the large request has been received.
+/// The caller processes the large request and generates the CHUNK_SEND_ACK response with
+/// `generate_large_request_ack`. Otherwise the CHUNK_SEND_ACK response is generated here.
+pub(crate) fn handle_chunk_send<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<Option<(u8, u16)>> {
+    // Perform all checks and send a error response if any fail
+    // 1. Check CHUNK_SEND is sent after CAPABILITIES
+    // 2. Check if chunk capabilities are enabled
+    if ctx.state.connection_info.state() < ConnectionState::AfterCapabilities
+        || ctx.local_capabilities.flags.chunk_cap() == 0
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // Large requests can only be received with a reassembly buffer
+    if ctx.large_req_buffer.is_none() {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Process CHUNK_SEND request
+    let (handle, chunk_seq_num, status) = process_chunk_send(ctx, spdm_hdr, req_payload)?;
+
+    // Generate CHUNK_SEND_ACK response
+    match status {
+        ChunkSendStatus::Complete => Ok(Some((handle, chunk_seq_num))),
+        ChunkSendStatus::InProgress => {
+            ctx.prepare_response_buffer(req_payload)?;
+            generate_chunk_send_ack(ctx, handle, chunk_seq_num, false, None, req_payload)?;
+            Ok(None)
+        }
+        ChunkSendStatus::EarlyError(error_code) => {
+            let mut error_msg = [0u8; ERROR_MSG_SIZE];
+            let error_msg_len = encode_error_msg(ctx, error_code, None, &mut error_msg)?;
+            ctx.prepare_response_buffer(req_payload)?;
+            generate_chunk_send_ack(
+                ctx,
+                handle,
+                chunk_seq_num,
+                true,
+                Some(&error_msg[..error_msg_len]),
+                req_payload,
+            )?;
+            Ok(None)
+        }
+    }
+}
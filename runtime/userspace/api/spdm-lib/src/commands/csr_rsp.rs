@@ -0,0 +1,189 @@
+// Licensed under the Apache-2.0 license
+
+use crate::cert_store::CertStoreError;
+use crate::codec::{encode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::{SpdmContext, MAX_SPDM_RESPONDER_BUF_SIZE};
+use crate::csr::RequesterInfo;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::opaque_data::OPAQUE_DATA_LEN_MAX_SIZE;
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use bitfield::bitfield;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use libapi_caliptra::crypto::asym::AsymAlgo;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+// Largest CSR that can be returned without chunking
+const MAX_CSR_SIZE: usize = MAX_SPDM_RESPONDER_BUF_SIZE;
+
+// The CSR is too large for the responder task stack, so it is generated in place
+static CSR_BUFFER: Mutex<CriticalSectionRawMutex, [u8; MAX_CSR_SIZE]> =
+    Mutex::new([0; MAX_CSR_SIZE]);
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct GetCsrReq {
+    key_pair_id: u8,
+    param2: CsrReqAttributes,
+    requester_info_len: u16,
+    opaque_data_len: u16,
+}
+
+impl CommonCodec for GetCsrReq {}
+
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable)]
+    #[repr(C)]
+    struct CsrReqAttributes(u8);
+    impl Debug;
+    u8;
+    pub csr_tracking_tag, _: 2, 0;
+    pub overwrite, _: 3, 3;
+    pub csr_cert_model, _: 6, 4;
+    reserved, _: 7, 7;
+}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct CsrRspBase {
+    param1: u8,
+    param2: u8,
+    csr_len: u16,
+    reserved: u16,
+}
+
+impl CommonCodec for CsrRspBase {}
+
+/// Generates the CSR for the device key. Returns the SPDM error to report to the
+/// Requester on failure.
+async fn generate_csr(
+    ctx: &SpdmContext<'_>,
+    asym_algo: AsymAlgo,
+    req_payload: &MessageBuf<'_>,
+    requester_info_len: usize,
+    csr: &mut [u8],
+) -> Result<usize, ErrorCode> {
+    let requester_info = req_payload
+        .data(requester_info_len)
+        .map_err(|_| ErrorCode::InvalidRequest)?;
+
+    // RequesterInfo is a CertificationRequestInfo whose subject and attributes are
+    // applied to the CSR
+    if !requester_info.is_empty() && RequesterInfo::parse(requester_info).is_err() {
+        Err(ErrorCode::InvalidRequest)?;
+    }
+
+    ctx.device_certs_store
+        .get_csr(asym_algo, requester_info, csr)
+        .await
+        .map_err(|e| match e {
+            CertStoreError::BufferTooSmall => ErrorCode::ResponseTooLarge,
+            CertStoreError::UnsupportedOperation => ErrorCode::UnsupportedRequest,
+            _ => ErrorCode::OperationFailed,
+        })
+}
+
+async fn process_get_csr<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+    csr: &mut [u8],
+) -> CommandResult<usize> {
+    // Validate the version
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let get_csr_req = GetCsrReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    let requester_info_len = get_csr_req.requester_info_len as usize;
+    let opaque_data_len = get_csr_req.opaque_data_len as usize;
+    if opaque_data_len > OPAQUE_DATA_LEN_MAX_SIZE
+        || req_payload.data_len() < requester_info_len + opaque_data_len
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    if connection_version >= SpdmVersion::V13 {
+        // KeyPairID must be 0 without MULTI_KEY_CAP. CSR tracking and overwrite
+        // require CERT_INSTALL_RESET_CAP which is not supported.
+        let attributes = &get_csr_req.param2;
+        if get_csr_req.key_pair_id != 0
+            || attributes.csr_tracking_tag() != 0
+            || attributes.overwrite() != 0
+        {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+    }
+
+    let asym_algo = ctx.validate_negotiated_base_asym_algo(req_payload)?;
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::GetCsr);
+
+    // The opaque data carries no element the responder acts on, so it is only length-checked.
+    match generate_csr(ctx, asym_algo, req_payload, requester_info_len, csr).await {
+        Ok(csr_len) => Ok(csr_len),
+        Err(error_code) => Err(ctx.generate_error_response(req_payload, error_code, 0, None)),
+    }
+}
+
+fn generate_csr_response(
+    ctx: &mut SpdmContext<'_>,
+    csr: &[u8],
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    if size_of::<SpdmMsgHdr>() + size_of::<CsrRspBase>() + csr.len() > ctx.min_data_transfer_size()
+    {
+        Err(ctx.generate_error_response(rsp, ErrorCode::ResponseTooLarge, 0, None))?;
+    }
+
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::Csr);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let csr_rsp_base = CsrRspBase {
+        param1: 0,
+        param2: 0,
+        csr_len: csr.len() as u16,
+        reserved: 0,
+    };
+    payload_len += csr_rsp_base
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    payload_len += encode_u8_slice(csr, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
+
+    rsp.push_data(payload_len)
+        .map_err(|_| (false, CommandError::BufferTooSmall))
+}
+
+pub(crate) async fn handle_get_csr<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // GET_CSR is supported from v1.2 and requires CSR_CAP
+    if ctx.state.connection_info.version_number() < SpdmVersion::V12
+        || ctx.local_capabilities.flags.csr_cap() == 0
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Process GET_CSR request
+    let mut csr = CSR_BUFFER.lock().await;
+    let csr_len = process_get_csr(ctx, spdm_hdr, req_payload, &mut csr[..]).await?;
+
+    // Generate CSR response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_csr_response(ctx, &csr[..csr_len], req_payload)
+}
@@ -1,6 +1,8 @@
 // Licensed under the Apache-2.0 license
 
-use crate::cert_store::{cert_slot_mask, spdm_cert_chain_hash, SpdmCertStore};
+use crate::cert_store::{
+    cert_slot_mask, spdm_cert_chain_hash, SpdmCertStore, MAX_CERT_SLOTS_SUPPORTED,
+};
 use crate::codec::{Codec, CommonCodec, MessageBuf};
 use crate::commands::error_rsp::ErrorCode;
 use crate::context::SpdmContext;
@@ -31,6 +33,13 @@ pub struct GetDigestsRespCommon {
 
 impl CommonCodec for GetDigestsRespCommon {}
 
+/// Iterates over the slot IDs set in the provisioned slot mask, in ascending order.
+/// Provisioned slots need not be contiguous once slots have been set with SET_CERTIFICATE.
+fn provisioned_slots(provisioned_slot_mask: u8) -> impl Iterator<Item = u8> {
+    (0..MAX_CERT_SLOTS_SUPPORTED)
+        .filter(move |slot_id| provisioned_slot_mask & (1 << *slot_id) != 0)
+}
+
 async fn encode_cert_chain_digest(
     slot_id: u8,
     cert_store: &dyn SpdmCertStore,
@@ -63,7 +72,8 @@ async fn generate_digests_response<'a>(
     let asym_algo = ctx.validate_negotiated_base_asym_algo(rsp)?;
 
     // Get the supported and provisioned slot masks.
-    let (supported_slot_mask, provisioned_slot_mask) = cert_slot_mask(ctx.device_certs_store).await;
+    let (supported_slot_mask, provisioned_slot_mask) =
+        cert_slot_mask(ctx.device_certs_store, asym_algo).await;
 
     // No slots provisioned with certificates
     let slot_cnt = provisioned_slot_mask.count_ones() as usize;
@@ -90,11 +100,10 @@ async fn generate_digests_response<'a>(
         .map_err(|_| (false, CommandError::BufferTooSmall))?;
 
     // Encode the certificate chain digests for each provisioned slot
-    for slot_id in 0..slot_cnt {
-        payload_len +=
-            encode_cert_chain_digest(slot_id as u8, ctx.device_certs_store, asym_algo, rsp)
-                .await
-                .map_err(|_| ctx.generate_error_response(rsp, ErrorCode::Unspecified, 0, None))?;
+    for slot_id in provisioned_slots(provisioned_slot_mask) {
+        payload_len += encode_cert_chain_digest(slot_id, ctx.device_certs_store, asym_algo, rsp)
+            .await
+            .map_err(|_| ctx.generate_error_response(rsp, ErrorCode::Unspecified, 0, None))?;
     }
 
     // Fill the multi-key connection response data if applicable
@@ -141,20 +150,20 @@ async fn encode_multi_key_conn_rsp_data(
     let mut key_usage_offset = 0;
     let mut cert_info_offset = 0;
 
-    for slot_id in 0..slot_cnt {
+    for slot_id in provisioned_slots(provisioned_slot_mask) {
         let key_pair_id = ctx
             .device_certs_store
-            .key_pair_id(slot_id as u8)
+            .key_pair_id(slot_id)
             .await
             .unwrap_or_default();
         let cert_info = ctx
             .device_certs_store
-            .cert_info(slot_id as u8)
+            .cert_info(slot_id)
             .await
             .unwrap_or_default();
         let key_usage_mask = ctx
             .device_certs_store
-            .key_usage_mask(slot_id as u8)
+            .key_usage_mask(slot_id)
             .await
             .unwrap_or_default();
 
@@ -0,0 +1,584 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{decode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::encap_ctx::{EncapFlow, EncapRequest};
+use crate::error::{CommandError, CommandResult};
+use crate::peer_cert::{peer_cert_chain_hash, verify_peer_cert_chain};
+use crate::protocol::*;
+use crate::session::RequesterAuth;
+use crate::state::ConnectionState;
+use crate::transcript::TranscriptContext;
+use libapi_caliptra::crypto::asym::ECC_P384_SIGNATURE_SIZE;
+use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use libapi_caliptra::crypto::rng::Rng;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+// Maximum portion of the Requester certificate chain requested by each encapsulated GET_CERTIFICATE
+const ENCAP_CERT_PORTION_LEN: u16 = 512;
+
+/// PayloadType of ENCAPSULATED_RESPONSE_ACK
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EncapPayloadType {
+    Absent = 0,
+    Present = 1,
+    ReqSlotNumber = 2,
+}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct GetEncapsulatedRequestReq {
+    _reserved0: u8,
+    _reserved1: u8,
+}
+impl CommonCodec for GetEncapsulatedRequestReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapsulatedRequestRspBase {
+    request_id: u8,
+    _reserved: u8,
+}
+impl CommonCodec for EncapsulatedRequestRspBase {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct DeliverEncapsulatedResponseReq {
+    request_id: u8,
+    _reserved: u8,
+}
+impl CommonCodec for DeliverEncapsulatedResponseReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapsulatedResponseAckBase {
+    request_id: u8,
+    payload_type: u8,
+}
+impl CommonCodec for EncapsulatedResponseAckBase {}
+
+// Fields added to ENCAPSULATED_RESPONSE_ACK in v1.2
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapsulatedResponseAckExt {
+    ack_request_id: u8,
+    _reserved: [u8; 3],
+}
+impl CommonCodec for EncapsulatedResponseAckExt {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapGetDigestsReq {
+    _reserved0: u8,
+    _reserved1: u8,
+}
+impl CommonCodec for EncapGetDigestsReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapGetCertificateReq {
+    slot_id: u8,
+    param2: u8,
+    offset: u16,
+    length: u16,
+}
+impl CommonCodec for EncapGetCertificateReq {}
+
+// CHALLENGE sent to a SPDM 1.1 Requester, without measurement summary hash
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapChallengeReq {
+    slot_id: u8,
+    meas_summary_hash_type: u8,
+    nonce: [u8; SPDM_NONCE_LEN],
+}
+impl CommonCodec for EncapChallengeReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapChallengeAuthRspBase {
+    challenge_auth_attr: u8,
+    _slot_mask: u8,
+    cert_chain_hash: [u8; SHA384_HASH_SIZE],
+    _nonce: [u8; SPDM_NONCE_LEN],
+    opaque_length: u16,
+}
+impl CommonCodec for EncapChallengeAuthRspBase {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapDigestsRspBase {
+    _supported_slot_mask: u8,
+    provisioned_slot_mask: u8,
+}
+impl CommonCodec for EncapDigestsRspBase {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct EncapCertificateRspBase {
+    slot_id: u8,
+    _param2: u8,
+    portion_length: u16,
+    remainder_length: u16,
+}
+impl CommonCodec for EncapCertificateRspBase {}
+
+/// Returns the flow the encapsulated request belongs to: the session whose handshake
+/// requested mutual authentication, or basic mutual authentication outside of a session.
+fn current_encap_flow(ctx: &SpdmContext) -> EncapFlow {
+    let session_id = if ctx.state.connection_info.handshake_in_the_clear() {
+        ctx.session_mgr.handshake_phase_session_id()
+    } else {
+        ctx.session_mgr.active_session_id()
+    };
+    match session_id {
+        Some(session_id) => EncapFlow::Session(session_id),
+        None => EncapFlow::Basic,
+    }
+}
+
+fn validate_encap_request(ctx: &SpdmContext, req_payload: &mut MessageBuf) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // Encapsulated requests are not supported in v1.0
+    if ctx.state.connection_info.version_number() < SpdmVersion::V11 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Encapsulated requests are only used for mutual authentication
+    if ctx.local_capabilities.flags.encap_cap() == 0
+        || ctx.local_capabilities.flags.mut_auth_cap() == 0
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    Ok(())
+}
+
+async fn encode_encapsulated_request(
+    ctx: &mut SpdmContext<'_>,
+    flow: EncapFlow,
+    request: EncapRequest,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<usize> {
+    let connection_version = ctx.state.connection_info.version_number();
+    let request_start = rsp.data_offset();
+    let len = match request {
+        EncapRequest::GetDigests => {
+            let mut len = SpdmMsgHdr::new(connection_version, ReqRespCode::GetDigests)
+                .encode(rsp)
+                .map_err(|e| (false, CommandError::Codec(e)))?;
+            len += EncapGetDigestsReq {
+                _reserved0: 0,
+                _reserved1: 0,
+            }
+            .encode(rsp)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+            len
+        }
+        EncapRequest::GetCertificate { slot_id, offset } => {
+            let mut len = SpdmMsgHdr::new(connection_version, ReqRespCode::GetCertificate)
+                .encode(rsp)
+                .map_err(|e| (false, CommandError::Codec(e)))?;
+            len += EncapGetCertificateReq {
+                slot_id,
+                param2: 0,
+                offset,
+                length: ENCAP_CERT_PORTION_LEN,
+            }
+            .encode(rsp)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+            len
+        }
+        EncapRequest::Challenge { slot_id } => {
+            let mut challenge_req = EncapChallengeReq {
+                slot_id,
+                meas_summary_hash_type: 0,
+                nonce: [0; SPDM_NONCE_LEN],
+            };
+            Rng::generate_random_number(&mut challenge_req.nonce)
+                .await
+                .map_err(|e| (false, CommandError::CaliptraApi(e)))?;
+
+            let mut len = SpdmMsgHdr::new(connection_version, ReqRespCode::Challenge)
+                .encode(rsp)
+                .map_err(|e| (false, CommandError::Codec(e)))?;
+            len += challenge_req
+                .encode(rsp)
+                .map_err(|e| (false, CommandError::Codec(e)))?;
+            len
+        }
+    };
+
+    // In basic mutual authentication the encapsulated requests are part of MutM1
+    if flow == EncapFlow::Basic {
+        let request_end = rsp.data_offset();
+        let msg = rsp
+            .message_slice(request_end)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        let encap_request = &msg[msg.len() - (request_end - request_start)..];
+        ctx.append_slice_to_transcript(encap_request, TranscriptContext::MutM1, None)
+            .await?;
+    }
+
+    Ok(len)
+}
+
+async fn process_encap_digests(
+    ctx: &mut SpdmContext<'_>,
+    rsp_payload: &mut MessageBuf<'_>,
+) -> CommandResult<Option<EncapRequest>> {
+    let digests = EncapDigestsRspBase::decode(rsp_payload).map_err(|_| {
+        ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None)
+    })?;
+
+    // Retrieve the certificate chain of the lowest provisioned slot. The digests
+    // themselves are not needed as the chain is anchored by its RootHash.
+    if digests.provisioned_slot_mask == 0 {
+        Err(ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None))?;
+    }
+    let slot_id = digests.provisioned_slot_mask.trailing_zeros() as u8;
+    ctx.encap_context.set_req_slot_id(slot_id);
+
+    Ok(Some(EncapRequest::GetCertificate { slot_id, offset: 0 }))
+}
+
+async fn process_encap_certificate(
+    ctx: &mut SpdmContext<'_>,
+    flow: EncapFlow,
+    slot_id: u8,
+    offset: u16,
+    rsp_payload: &mut MessageBuf<'_>,
+) -> CommandResult<Option<EncapRequest>> {
+    let cert_rsp = EncapCertificateRspBase::decode(rsp_payload).map_err(|_| {
+        ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None)
+    })?;
+
+    let portion_length = cert_rsp.portion_length;
+    let remainder_length = cert_rsp.remainder_length;
+    if cert_rsp.slot_id & 0x0F != slot_id
+        || portion_length == 0
+        || portion_length > ENCAP_CERT_PORTION_LEN
+        || ctx.encap_context.cert_chain_len() != offset as usize
+    {
+        Err(ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None))?;
+    }
+
+    if rsp_payload.data_len() < portion_length as usize {
+        Err(ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None))?;
+    }
+    let portion = rsp_payload
+        .data(portion_length as usize)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    if ctx.encap_context.append_cert_chain(portion).is_err() {
+        Err(ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None))?;
+    }
+    rsp_payload
+        .pull_data(portion_length as usize)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    if remainder_length > 0 {
+        return Ok(Some(EncapRequest::GetCertificate {
+            slot_id,
+            offset: ctx.encap_context.cert_chain_len() as u16,
+        }));
+    }
+
+    // The whole chain has been received. Validate it against the provisioned roots.
+    let public_key =
+        verify_peer_cert_chain(ctx.encap_context.cert_chain(), ctx.requester_root_hashes)
+            .await
+            .map_err(|_| {
+                ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None)
+            })?;
+
+    let mut cert_chain_hash = [0u8; SHA384_HASH_SIZE];
+    peer_cert_chain_hash(ctx.encap_context.cert_chain(), &mut cert_chain_hash)
+        .await
+        .map_err(|e| (false, CommandError::PeerCert(e)))?;
+
+    let requester_auth = RequesterAuth {
+        slot_id,
+        cert_chain_hash,
+        public_key,
+    };
+
+    match flow {
+        // The Requester proves possession of the key with the FINISH signature
+        EncapFlow::Session(session_id) => {
+            let session_info = ctx
+                .session_mgr
+                .session_info_mut(session_id)
+                .map_err(|e| (false, CommandError::Session(e)))?;
+            session_info.requester_auth = Some(requester_auth);
+            Ok(None)
+        }
+        // The Requester proves possession of the key by answering CHALLENGE
+        EncapFlow::Basic => {
+            ctx.encap_context.set_requester_auth(requester_auth);
+            Ok(Some(EncapRequest::Challenge { slot_id }))
+        }
+    }
+}
+
+async fn process_encap_challenge_auth(
+    ctx: &mut SpdmContext<'_>,
+    slot_id: u8,
+    rsp_payload: &mut MessageBuf<'_>,
+) -> CommandResult<Option<EncapRequest>> {
+    let requester_auth = ctx.encap_context.requester_auth().ok_or_else(|| {
+        ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None)
+    })?;
+
+    let challenge_auth = EncapChallengeAuthRspBase::decode(rsp_payload).map_err(|_| {
+        ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None)
+    })?;
+
+    // The Requester must answer with the certificate chain that was retrieved
+    if challenge_auth.challenge_auth_attr & 0x0F != slot_id
+        || challenge_auth.cert_chain_hash != requester_auth.cert_chain_hash
+    {
+        Err(ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None))?;
+    }
+
+    // OpaqueData followed by the signature
+    let opaque_length = challenge_auth.opaque_length as usize;
+    if rsp_payload.data_len() != opaque_length + ECC_P384_SIGNATURE_SIZE {
+        Err(ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None))?;
+    }
+    rsp_payload
+        .pull_data(opaque_length)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
+    decode_u8_slice(rsp_payload, &mut signature).map_err(|e| (false, CommandError::Codec(e)))?;
+
+    // SPDM 1.1 signs the MutM1 transcript hash directly
+    let m1_hash = ctx
+        .transcript_hash(TranscriptContext::MutM1, None, true)
+        .await?;
+    if requester_auth
+        .public_key
+        .verify(&m1_hash, &signature)
+        .await
+        .is_err()
+    {
+        Err(ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None))?;
+    }
+
+    ctx.state
+        .connection_info
+        .set_requester_auth_slot_id(Some(slot_id));
+    Ok(None)
+}
+
+async fn process_encapsulated_response(
+    ctx: &mut SpdmContext<'_>,
+    flow: EncapFlow,
+    pending: EncapRequest,
+    rsp_payload: &mut MessageBuf<'_>,
+) -> CommandResult<Option<EncapRequest>> {
+    // In basic mutual authentication the encapsulated responses are part of MutM1,
+    // CHALLENGE_AUTH excluding its signature
+    if flow == EncapFlow::Basic {
+        let signature_len = match pending {
+            EncapRequest::Challenge { .. } => ECC_P384_SIGNATURE_SIZE,
+            _ => 0,
+        };
+        let encap_rsp_len = rsp_payload
+            .data_len()
+            .checked_sub(signature_len)
+            .ok_or_else(|| {
+                ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None)
+            })?;
+        let encap_rsp = rsp_payload
+            .data(encap_rsp_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        ctx.append_slice_to_transcript(encap_rsp, TranscriptContext::MutM1, None)
+            .await?;
+    }
+
+    let encap_hdr = SpdmMsgHdr::decode(rsp_payload).map_err(|_| {
+        ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None)
+    })?;
+
+    // The encapsulated response must use the negotiated version
+    let connection_version = ctx.state.connection_info.version_number();
+    if encap_hdr.version().ok() != Some(connection_version) {
+        Err(ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None))?;
+    }
+
+    match (pending, encap_hdr.req_resp_code()) {
+        (EncapRequest::GetDigests, Ok(ReqRespCode::Digests)) => {
+            process_encap_digests(ctx, rsp_payload).await
+        }
+        (EncapRequest::GetCertificate { slot_id, offset }, Ok(ReqRespCode::Certificate)) => {
+            process_encap_certificate(ctx, flow, slot_id, offset, rsp_payload).await
+        }
+        (EncapRequest::Challenge { slot_id }, Ok(ReqRespCode::ChallengeAuth)) => {
+            process_encap_challenge_auth(ctx, slot_id, rsp_payload).await
+        }
+        // An ERROR or any other response aborts mutual authentication
+        _ => Err(ctx.generate_error_response(rsp_payload, ErrorCode::InvalidResponseCode, 0, None)),
+    }
+}
+
+async fn generate_encapsulated_request(
+    ctx: &mut SpdmContext<'_>,
+    flow: EncapFlow,
+    request: EncapRequest,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::EncapsulatedRequest);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    payload_len += EncapsulatedRequestRspBase {
+        request_id: ctx.encap_context.next_request_id(),
+        _reserved: 0,
+    }
+    .encode(rsp)
+    .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    payload_len += encode_encapsulated_request(ctx, flow, request, rsp).await?;
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+async fn generate_encapsulated_response_ack(
+    ctx: &mut SpdmContext<'_>,
+    flow: EncapFlow,
+    next_request: Option<EncapRequest>,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    let connection_version = ctx.state.connection_info.version_number();
+    let ack_request_id = ctx.encap_context.request_id();
+
+    let (request_id, payload_type) = match next_request {
+        Some(_) => (
+            ctx.encap_context.next_request_id(),
+            EncapPayloadType::Present,
+        ),
+        // The selected Requester slot is reported with the final acknowledgement from v1.2
+        None if connection_version >= SpdmVersion::V12 => (0, EncapPayloadType::ReqSlotNumber),
+        None => (0, EncapPayloadType::Absent),
+    };
+
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::EncapsulatedResponseAck);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    payload_len += EncapsulatedResponseAckBase {
+        request_id,
+        payload_type: payload_type as u8,
+    }
+    .encode(rsp)
+    .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    if connection_version >= SpdmVersion::V12 {
+        payload_len += EncapsulatedResponseAckExt {
+            ack_request_id,
+            _reserved: [0; 3],
+        }
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    }
+
+    match (next_request, payload_type) {
+        (Some(request), _) => {
+            payload_len += encode_encapsulated_request(ctx, flow, request, rsp).await?;
+        }
+        (None, EncapPayloadType::ReqSlotNumber) => {
+            payload_len += ctx
+                .encap_context
+                .req_slot_id()
+                .encode(rsp)
+                .map_err(|e| (false, CommandError::Codec(e)))?;
+        }
+        _ => {}
+    }
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_get_encapsulated_request<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    validate_encap_request(ctx, req_payload)?;
+
+    // Validate the version
+    let _ = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let _get_encap_req = GetEncapsulatedRequestReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::GetEncapsulatedRequest);
+
+    // The encapsulated flow starts with GET_DIGESTS after KEY_EXCHANGE_RSP or
+    // CHALLENGE_AUTH requested mutual authentication
+    let flow = current_encap_flow(ctx);
+    let request = ctx
+        .encap_context
+        .pending_request(flow)
+        .filter(|request| *request == EncapRequest::GetDigests)
+        .ok_or_else(|| {
+            ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None)
+        })?;
+
+    // Generate ENCAPSULATED_REQUEST response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_encapsulated_request(ctx, flow, request, req_payload).await
+}
+
+pub(crate) async fn handle_deliver_encapsulated_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    validate_encap_request(ctx, req_payload)?;
+
+    // Validate the version
+    let _ = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let deliver_req = DeliverEncapsulatedResponseReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::DeliverEncapsulatedResponse);
+
+    let flow = current_encap_flow(ctx);
+    let pending = ctx.encap_context.pending_request(flow).ok_or_else(|| {
+        ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None)
+    })?;
+
+    if deliver_req.request_id != ctx.encap_context.request_id() {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // Process the encapsulated response. Any failure aborts the encapsulated flow,
+    // leaving the session without an authenticated Requester.
+    let next_request = match process_encapsulated_response(ctx, flow, pending, req_payload).await {
+        Ok(next_request) => next_request,
+        Err(e) => {
+            ctx.encap_context.reset();
+            return Err(e);
+        }
+    };
+
+    ctx.encap_context.set_pending(next_request);
+
+    // Generate ENCAPSULATED_RESPONSE_ACK response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_encapsulated_response_ack(ctx, flow, next_request, req_payload).await
+}
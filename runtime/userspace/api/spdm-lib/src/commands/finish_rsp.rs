@@ -7,11 +7,12 @@ use crate::commands::error_rsp::ErrorCode;
 use crate::context::SpdmContext;
 use crate::error::{CommandError, CommandResult};
 use crate::protocol::*;
-use crate::session::{SessionKeyType, SessionState};
+use crate::session::{RequesterAuth, SessionKeyType, SessionState};
 use crate::state::ConnectionState;
 use crate::transcript::TranscriptContext;
 use bitfield::bitfield;
 use constant_time_eq::constant_time_eq;
+use libapi_caliptra::crypto::asym::ECC_P384_SIGNATURE_SIZE;
 use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use zerocopy::{FromBytes, Immutable, IntoBytes};
 
@@ -83,6 +84,76 @@ async fn verify_requester_verify_data(
     Ok(())
 }
 
+/// Returns the Requester certificate to verify the FINISH signature with if mutual
+/// authentication was requested in KEY_EXCHANGE_RSP.
+fn requester_auth_for_finish(
+    ctx: &SpdmContext<'_>,
+    session_id: u32,
+    finish_req_base: &FinishReqBase,
+    req_payload: &mut MessageBuf<'_>,
+) -> CommandResult<Option<RequesterAuth>> {
+    let session_info = ctx
+        .session_mgr
+        .session_info(session_id)
+        .map_err(|e| (false, CommandError::Session(e)))?;
+    let signature_present = finish_req_base.req_signature_present & 0x01 != 0;
+
+    if !session_info.mut_auth_requested {
+        if signature_present {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+        }
+        return Ok(None);
+    }
+
+    // The Requester certificate chain must have been retrieved with encapsulated requests
+    let Some(requester_auth) = session_info.requester_auth else {
+        return Err(ctx.generate_error_response(
+            req_payload,
+            ErrorCode::UnexpectedRequest,
+            0,
+            None,
+        ));
+    };
+
+    if !signature_present || finish_req_base.req_slot_id != requester_auth.slot_id {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    Ok(Some(requester_auth))
+}
+
+async fn verify_requester_signature(
+    ctx: &mut SpdmContext<'_>,
+    session_id: u32,
+    requester_auth: &RequesterAuth,
+    req_payload: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
+    decode_u8_slice(req_payload, &mut signature).map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let spdm_version = ctx.state.connection_info.version_number();
+    let transcript_hash = ctx
+        .transcript_hash(TranscriptContext::Th, Some(session_id), false)
+        .await?;
+
+    let tbs = get_tbs_via_response_code(spdm_version, ReqRespCode::Finish, transcript_hash)
+        .await
+        .map_err(|e| (false, CommandError::SignCtx(e)))?;
+
+    if requester_auth
+        .public_key
+        .verify(&tbs, &signature)
+        .await
+        .is_err()
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::DecryptError, 0, None))?;
+    }
+
+    // Add the signature to the transcript
+    ctx.append_slice_to_transcript(&signature, TranscriptContext::Th, Some(session_id))
+        .await
+}
+
 async fn process_finish<'a>(
     ctx: &mut SpdmContext<'a>,
     session_id: u32,
@@ -92,16 +163,33 @@ async fn process_finish<'a>(
     // Validate the version
     let _ = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
 
-    // Decode the FINISH request payload (currently no Session-based mutual authentication is supported)
-    let _finish_req_base =
+    // Decode the FINISH request payload
+    let finish_req_base =
         FinishReqBase::decode(req_payload).map_err(|e| (false, CommandError::Codec(e)))?;
 
     ctx.reset_transcript_via_req_code(ReqRespCode::Finish);
 
-    // Append FINISH req (excluding RequesterVerifyData) to TH transcript.
+    let requester_auth = requester_auth_for_finish(ctx, session_id, &finish_req_base, req_payload)?;
+
+    // With mutual authentication, the hash of the Requester cert chain precedes FINISH in TH
+    if let Some(requester_auth) = &requester_auth {
+        ctx.append_slice_to_transcript(
+            &requester_auth.cert_chain_hash,
+            TranscriptContext::Th,
+            Some(session_id),
+        )
+        .await?;
+    }
+
+    // Append FINISH req (excluding Signature and RequesterVerifyData) to TH transcript.
     ctx.append_message_to_transcript(req_payload, TranscriptContext::Th, Some(session_id))
         .await?;
 
+    // Verify the Requester signature and add it to the transcript
+    if let Some(requester_auth) = &requester_auth {
+        verify_requester_signature(ctx, session_id, requester_auth, req_payload).await?;
+    }
+
     // Verify HMAC of the RequesterVerifyData
     let mut requester_verify_data = [0u8; SHA384_HASH_SIZE];
     decode_u8_slice(req_payload, &mut requester_verify_data)
@@ -237,6 +325,16 @@ pub(crate) async fn handle_finish<'a>(
     }
     .ok_or_else(|| ctx.generate_error_response(req_payload, ErrorCode::SessionRequired, 0, None))?;
 
+    // Sessions established with PSK_EXCHANGE are completed with PSK_FINISH
+    let use_psk = ctx
+        .session_mgr
+        .session_info(session_id)
+        .map(|info| info.use_psk)
+        .map_err(|e| (false, CommandError::Session(e)))?;
+    if use_psk {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
     // Verify the negotiated Hash algorithm is SHA384
     ctx.validate_negotiated_hash_algo(req_payload)?;
 
@@ -247,13 +345,10 @@ pub(crate) async fn handle_finish<'a>(
     ctx.prepare_response_buffer(req_payload)?;
     generate_finish_response(ctx, session_id, req_payload).await?;
 
-    // Set the session state to Establishing
+    // Set the session state to Establishing. This ends the handshake phase of the session.
     ctx.session_mgr
         .set_session_state(session_id, SessionState::Establishing)
         .map_err(|e| (false, CommandError::Session(e)))?;
 
-    // Reset handshake phase session ID
-    ctx.session_mgr.reset_handshake_phase_session_id();
-
     Ok(())
 }
@@ -0,0 +1,110 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(Debug, FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct HeartbeatReq {
+    reserved1: u8,
+    reserved2: u8,
+}
+
+impl CommonCodec for HeartbeatReq {}
+
+#[derive(Debug, FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct HeartbeatAck {
+    reserved1: u8,
+    reserved2: u8,
+}
+
+impl CommonCodec for HeartbeatAck {}
+
+fn process_heartbeat(
+    ctx: &mut SpdmContext<'_>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Validate the version
+    let _ = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let _heartbeat_req = HeartbeatReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::Heartbeat);
+
+    Ok(())
+}
+
+fn generate_heartbeat_response(
+    ctx: &mut SpdmContext<'_>,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Prepare the response message
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::HeartbeatAck);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let heartbeat_ack = HeartbeatAck {
+        reserved1: 0,
+        reserved2: 0,
+    };
+    payload_len += heartbeat_ack
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    rsp.push_data(payload_len)
+        .map_err(|_| (false, CommandError::BufferTooSmall))
+}
+
+pub(crate) async fn handle_heartbeat<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // HEARTBEAT is not supported in v1.0
+    if ctx.state.connection_info.version_number() < SpdmVersion::V11 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Both the responder and the requester must support HBEAT_CAP
+    if ctx.local_capabilities.flags.hbeat_cap() == 0
+        || ctx
+            .state
+            .connection_info
+            .peer_capabilities()
+            .flags
+            .hbeat_cap()
+            == 0
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // HEARTBEAT is only valid inside a session. The session liveness has already
+    // been refreshed when the secured message was decoded.
+    if ctx.session_mgr.active_session_id().is_none() {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::SessionRequired, 0, None))?;
+    }
+
+    // Process HEARTBEAT request
+    process_heartbeat(ctx, spdm_hdr, req_payload)?;
+
+    // Generate HEARTBEAT_ACK response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_heartbeat_response(ctx, req_payload)
+}
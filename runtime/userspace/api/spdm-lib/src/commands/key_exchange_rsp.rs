@@ -8,6 +8,7 @@ use crate::commands::algorithms_rsp::selected_measurement_specification;
 use crate::commands::challenge_auth_rsp::encode_measurement_summary_hash;
 use crate::commands::error_rsp::ErrorCode;
 use crate::context::SpdmContext;
+use crate::encap_ctx::EncapFlow;
 use crate::error::{CommandError, CommandResult};
 use crate::opaque_element::secure_message::{
     sm_select_version_from_list, sm_selected_version_opaque_data, SmVersion,
@@ -19,7 +20,7 @@ use crate::state::ConnectionState;
 use crate::transcript::TranscriptContext;
 use bitfield::bitfield;
 use libapi_caliptra::crypto::asym::ecdh::CMB_ECDH_EXCHANGE_DATA_MAX_SIZE;
-use libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_SIGNATURE_SIZE};
+use libapi_caliptra::crypto::asym::{AsymAlgo, MLDSA87_SIGNATURE_SIZE};
 use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 use libapi_caliptra::crypto::rng::Rng;
 use zerocopy::{FromBytes, Immutable, IntoBytes};
@@ -87,18 +88,29 @@ struct KeyExchRspContext {
     selected_sm_version: SmVersion,
     resp_session_id: u16,
     session_id: u32,
+    heartbeat_period: u8,
+    mut_auth_requested: bool,
 }
 
-fn init_session(
+pub(crate) fn init_session(
     session_info: &mut SessionInfo,
     local_capabilities_flags: CapabilityFlags,
     connection_info: &ConnectionInfo,
     session_policy: SessionPolicy,
     asym_algo: AsymAlgo,
+    heartbeat_period: u8,
 ) {
     // let local_capabilities_flags = ctx.local_capabilities.flags;
     let peer_capabilities = connection_info.peer_capabilities().flags;
 
+    // Heartbeat is only negotiated if both sides support HBEAT_CAP
+    let heartbeat_period =
+        if local_capabilities_flags.hbeat_cap() != 0 && peer_capabilities.hbeat_cap() != 0 {
+            heartbeat_period
+        } else {
+            0
+        };
+
     let mac_cap = local_capabilities_flags.mac_cap() != 0 && peer_capabilities.mac_cap() != 0;
     let encrypt_cap =
         local_capabilities_flags.encrypt_cap() != 0 && peer_capabilities.encrypt_cap() != 0;
@@ -114,6 +126,7 @@ fn init_session(
         session_type,
         connection_info.version_number(),
         asym_algo,
+        heartbeat_period,
     );
 }
 
@@ -147,7 +160,7 @@ async fn process_key_exchange<'a>(
         || ctx.local_capabilities.flags.cert_cap() == 0
         || !ctx
             .device_certs_store
-            .is_provisioned(exch_req.slot_id)
+            .is_provisioned(exch_req.slot_id, asym_algo)
             .await
     {
         Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
@@ -185,6 +198,13 @@ async fn process_key_exchange<'a>(
         .connection_info
         .set_sec_msg_version(selected_sm_version);
 
+    // The FINISH request of a handshake in the clear is not carried in the session. It can
+    // only be matched to its session while no other handshake in the clear is in progress.
+    let handshake_in_the_clear = ctx.state.connection_info.handshake_in_the_clear();
+    if handshake_in_the_clear && ctx.session_mgr.handshake_phase_session_id().is_some() {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
     // Create session
     let (session_id, resp_session_id) =
         ctx.session_mgr.generate_session_id(exch_req.req_session_id);
@@ -194,7 +214,11 @@ async fn process_key_exchange<'a>(
         ctx.generate_error_response(req_payload, ErrorCode::SessionLimitExceeded, 0, None)
     })?;
 
-    ctx.session_mgr.set_handshake_phase_session_id(session_id);
+    let heartbeat_period = ctx.session_mgr.heartbeat_period();
+
+    // Request mutual authentication if the Requester certificate chain can be retrieved
+    // with encapsulated requests and validated against a provisioned root.
+    let mut_auth_requested = ctx.mut_auth_supported();
 
     let session_info = ctx
         .session_mgr
@@ -207,7 +231,11 @@ async fn process_key_exchange<'a>(
         &ctx.state.connection_info,
         exch_req.session_policy,
         asym_algo,
+        heartbeat_period,
     );
+    let heartbeat_period = session_info.heartbeat_period;
+    session_info.mut_auth_requested = mut_auth_requested;
+    session_info.handshake_in_the_clear = handshake_in_the_clear;
 
     let resp_exch_data = session_info
         .compute_dhe_secret(&exch_req.exchange_data)
@@ -243,16 +271,26 @@ async fn process_key_exchange<'a>(
         selected_sm_version,
         resp_session_id,
         session_id,
+        heartbeat_period,
+        mut_auth_requested,
     })
 }
 
 async fn encode_key_exchange_rsp_base(
+    heartbeat_period: u8,
     resp_session_id: u16,
+    mut_auth_requested: bool,
     resp_exchange_data: [u8; CMB_ECDH_EXCHANGE_DATA_MAX_SIZE],
     rsp: &mut MessageBuf<'_>,
 ) -> CommandResult<usize> {
     let mut key_exch_rsp = KeyExchangeRspBase::new();
+    key_exch_rsp.heartbeat_period = heartbeat_period;
     key_exch_rsp.rsp_session_id = resp_session_id;
+    if mut_auth_requested {
+        // The Requester certificate is retrieved with GET_ENCAPSULATED_REQUEST
+        // before FINISH. SlotIDParam is left 0 as it only applies to implicit GET_DIGESTS.
+        key_exch_rsp.mut_auth_requested.set_encaps_request_flow(1);
+    }
     key_exch_rsp
         .exchange_data
         .copy_from_slice(&resp_exchange_data);
@@ -273,7 +311,7 @@ async fn th1_signature(
     session_id: u32,
     slot_id: u8,
     asym_algo: AsymAlgo,
-) -> CommandResult<[u8; ECC_P384_SIGNATURE_SIZE]> {
+) -> CommandResult<[u8; MLDSA87_SIGNATURE_SIZE]> {
     let spdm_version = ctx.state.connection_info.version_number();
     let th1_transcript_hash = ctx
         .transcript_hash(
@@ -291,9 +329,14 @@ async fn th1_signature(
     .await
     .map_err(|e| (false, CommandError::SignCtx(e)))?;
 
-    let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
+    let mut signature = [0u8; MLDSA87_SIGNATURE_SIZE];
     ctx.device_certs_store
-        .sign_hash(slot_id, asym_algo, &tbs, &mut signature)
+        .sign_hash(
+            slot_id,
+            asym_algo,
+            &tbs,
+            &mut signature[..asym_algo.signature_size()],
+        )
         .await
         .map_err(|e| (false, CommandError::CertStore(e)))?;
     Ok(signature)
@@ -316,7 +359,9 @@ async fn generate_key_exchange_response<'a>(
 
     // Encode the KEY_EXCHANGE response fixed fields
     payload_len += encode_key_exchange_rsp_base(
+        key_exch_rsp_ctx.heartbeat_period,
         key_exch_rsp_ctx.resp_session_id,
+        key_exch_rsp_ctx.mut_auth_requested,
         key_exch_rsp_ctx.resp_exch_data,
         rsp,
     )
@@ -354,11 +399,12 @@ async fn generate_key_exchange_response<'a>(
     )
     .await?;
 
-    payload_len += encode_u8_slice(&th1_sig, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
+    let th1_sig = &th1_sig[..asym_algo.signature_size()];
+    payload_len += encode_u8_slice(th1_sig, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
 
     // Update the session transcript with the KEY_EXCHANGE_RSP signature
     ctx.append_slice_to_transcript(
-        &th1_sig,
+        th1_sig,
         TranscriptContext::Th,
         Some(key_exch_rsp_ctx.session_id),
     )
@@ -449,37 +495,50 @@ pub(crate) async fn handle_key_exchange<'a>(
     let key_exch_rsp_ctx = match process_key_exchange(ctx, asym_algo, spdm_hdr, req_payload).await {
         Ok(result) => result,
         Err(e) => {
-            if ctx.session_mgr.handshake_phase_session_id().is_some() {
-                let session_id = ctx.session_mgr.handshake_phase_session_id().unwrap();
-                let _ = ctx.session_mgr.delete_session(session_id);
-            }
+            // Only tear down the session created by this request. Other sessions
+            // in the table, including ones still in their handshake, are left intact.
+            ctx.session_mgr.delete_unstarted_session();
             return Err(e);
         }
     };
 
-    // Generate KEY_EXCHANGE response
-    ctx.prepare_response_buffer(req_payload)?;
-
     let session_id = key_exch_rsp_ctx.session_id;
+    let mut_auth_requested = key_exch_rsp_ctx.mut_auth_requested;
 
-    // Generate response with automatic cleanup on error
-    if let Err(e) =
+    // Generate KEY_EXCHANGE response
+    let result = if ctx.signed_response_in_large_buffer(asym_algo) {
+        match ctx.take_large_response_buffer(req_payload) {
+            Ok(mut large_rsp) => {
+                let result = generate_key_exchange_response(
+                    ctx,
+                    asym_algo,
+                    key_exch_rsp_ctx,
+                    &mut large_rsp,
+                )
+                .await;
+                ctx.complete_large_response(large_rsp, result, req_payload)
+            }
+            Err(e) => Err(e),
+        }
+    } else {
+        ctx.prepare_response_buffer(req_payload)?;
         generate_key_exchange_response(ctx, asym_algo, key_exch_rsp_ctx, req_payload).await
-    {
+    };
+
+    if let Err(e) = result {
         // Clean up session on error
-        if ctx.session_mgr.handshake_phase_session_id().is_some() {
-            let _ = ctx.session_mgr.delete_session(session_id); // Ignore cleanup errors
-        }
+        let _ = ctx.session_mgr.delete_session(session_id); // Ignore cleanup errors
         return Err(e);
     }
 
-    if !ctx.state.connection_info.handshake_in_the_clear() {
-        ctx.session_mgr.set_handshake_phase_session_id(session_id);
-    }
-
     ctx.session_mgr
         .set_session_state(session_id, SessionState::HandshakeInProgress)
         .map_err(|e| (false, CommandError::Session(e)))?;
 
+    // Start retrieving the Requester certificate chain with encapsulated requests
+    if mut_auth_requested {
+        ctx.encap_context.init(EncapFlow::Session(session_id));
+    }
+
     Ok(())
 }
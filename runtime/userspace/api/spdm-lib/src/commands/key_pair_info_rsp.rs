@@ -0,0 +1,287 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{encode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::key_pair_store::{
+    KeyPairAsymAlgo, KeyPairCapabilities, KeyPairInfo, KeyPairStoreError, SpdmKeyPairStore,
+    MAX_PUBLIC_KEY_INFO_SIZE,
+};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct GetKeyPairInfoReq {
+    param1: u8,
+    param2: u8,
+    key_pair_id: u8,
+}
+
+impl CommonCodec for GetKeyPairInfoReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct KeyPairInfoRspBase {
+    param1: u8,
+    param2: u8,
+    total_key_pairs: u8,
+    key_pair_id: u8,
+    capabilities: KeyPairCapabilities,
+    key_usage_capabilities: KeyUsageMask,
+    current_key_usage: KeyUsageMask,
+    asym_algo_capabilities: KeyPairAsymAlgo,
+    current_asym_algo: KeyPairAsymAlgo,
+    public_key_info_len: u16,
+    assoc_cert_slot_mask: u8,
+}
+
+impl CommonCodec for KeyPairInfoRspBase {}
+
+// PQC asymmetric algorithms of the key pair, following PublicKeyInfo from v1.4
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct KeyPairInfoRspPqcAlgo {
+    pqc_asym_algo_cap_len: u8,
+    pqc_asym_algo_capabilities: PqcAsymAlgo,
+    current_pqc_asym_algo_len: u8,
+    current_pqc_asym_algo: PqcAsymAlgo,
+}
+
+impl CommonCodec for KeyPairInfoRspPqcAlgo {}
+
+/// Returns the key pair store if the responder supports the key pair info requests.
+pub(crate) fn key_pair_store<'a>(
+    ctx: &SpdmContext<'a>,
+    req_payload: &mut MessageBuf,
+) -> CommandResult<&'a dyn SpdmKeyPairStore> {
+    let connection_version = ctx.state.connection_info.version_number();
+    match ctx.key_pair_store {
+        Some(store) if connection_version >= SpdmVersion::V13 => Ok(store),
+        _ => Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None)),
+    }
+}
+
+/// Returns true if the KeyPairID identifies one of the key pairs of the responder.
+pub(crate) fn valid_key_pair_id(store: &dyn SpdmKeyPairStore, key_pair_id: u8) -> bool {
+    key_pair_id != 0 && key_pair_id <= store.total_key_pairs()
+}
+
+fn process_get_key_pair_info<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+    store: &dyn SpdmKeyPairStore,
+) -> CommandResult<u8> {
+    // Validate the version
+    let _ = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let req = GetKeyPairInfoReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    if !valid_key_pair_id(store, req.key_pair_id) {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::GetKeyPairInfo);
+
+    Ok(req.key_pair_id)
+}
+
+fn generate_key_pair_info_response(
+    ctx: &mut SpdmContext<'_>,
+    store: &dyn SpdmKeyPairStore,
+    key_pair_id: u8,
+    info: &KeyPairInfo,
+    public_key_info: &[u8],
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::KeyPairInfo);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let rsp_base = KeyPairInfoRspBase {
+        param1: 0,
+        param2: 0,
+        total_key_pairs: store.total_key_pairs(),
+        key_pair_id,
+        capabilities: info.capabilities,
+        key_usage_capabilities: info.key_usage_capabilities,
+        current_key_usage: info.current_key_usage,
+        asym_algo_capabilities: info.asym_algo_capabilities,
+        current_asym_algo: info.current_asym_algo,
+        public_key_info_len: public_key_info.len() as u16,
+        assoc_cert_slot_mask: info.assoc_cert_slot_mask,
+    };
+    payload_len += rsp_base
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    payload_len +=
+        encode_u8_slice(public_key_info, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
+
+    if connection_version >= SpdmVersion::V14 {
+        let pqc_algo = KeyPairInfoRspPqcAlgo {
+            pqc_asym_algo_cap_len: size_of::<PqcAsymAlgo>() as u8,
+            pqc_asym_algo_capabilities: info.pqc_asym_algo_capabilities,
+            current_pqc_asym_algo_len: size_of::<PqcAsymAlgo>() as u8,
+            current_pqc_asym_algo: info.current_pqc_asym_algo,
+        };
+        payload_len += pqc_algo
+            .encode(rsp)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+    }
+
+    rsp.push_data(payload_len)
+        .map_err(|_| (false, CommandError::BufferTooSmall))
+}
+
+pub(crate) async fn handle_get_key_pair_info<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // GET_KEY_PAIR_INFO is supported from v1.3 and requires GET_KEY_PAIR_INFO_CAP
+    if ctx.local_capabilities.flags.get_key_pair_info_cap() == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+    let store = key_pair_store(ctx, req_payload)?;
+
+    // Process GET_KEY_PAIR_INFO request
+    let key_pair_id = process_get_key_pair_info(ctx, spdm_hdr, req_payload, store)?;
+
+    let mut public_key_info = [0u8; MAX_PUBLIC_KEY_INFO_SIZE];
+    let (info, public_key_info_len) = store
+        .key_pair_info(key_pair_id, &mut public_key_info)
+        .await
+        .map_err(|e| match e {
+            KeyPairStoreError::InvalidKeyPairId => {
+                ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+            }
+            _ => ctx.generate_error_response(req_payload, ErrorCode::Unspecified, 0, None),
+        })?;
+    if public_key_info_len > MAX_PUBLIC_KEY_INFO_SIZE {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::Unspecified, 0, None))?;
+    }
+
+    // Generate KEY_PAIR_INFO response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_key_pair_info_response(
+        ctx,
+        store,
+        key_pair_id,
+        &info,
+        &public_key_info[..public_key_info_len],
+        req_payload,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::key_pair_store::{ECC_P384_PUBLIC_KEY_INFO, MLDSA87_PUBLIC_KEY_INFO};
+    use crate::test_util::*;
+
+    fn key_pair_info_capability_flags() -> CapabilityFlags {
+        let mut flags = CapabilityFlags::default();
+        flags.set_get_key_pair_info_cap(1);
+        flags
+    }
+
+    #[test]
+    fn test_key_pair_info_ecc384() {
+        let mut transport = TestTransport;
+        let mut meas_value = TestMeasurementValue;
+        let store = TestKeyPairStore::new();
+        let mut buf = [0u8; 128];
+        let mut ctx = negotiated_context(
+            SpdmVersion::V13,
+            key_pair_info_capability_flags(),
+            &mut transport,
+            &TestCertStore,
+            &mut meas_value,
+        );
+        ctx.set_key_pair_store(&store);
+
+        let (spdm_hdr, mut req) = request(&mut buf, &[0x13, 0xFC, 0, 0, 1]);
+        block_on(handle_get_key_pair_info(&mut ctx, spdm_hdr, &mut req)).unwrap();
+
+        let rsp = response(&req);
+        assert_eq!(rsp.len(), 23 + ECC_P384_PUBLIC_KEY_INFO.len());
+        assert_eq!(&rsp[..6], &[0x13, 0x7C, 0, 0, 2, 1]);
+        // Current key usage and asymmetric algorithm
+        assert_eq!(&rsp[10..12], &[0x03, 0x00]);
+        assert_eq!(&rsp[12..16], &[0x10, 0, 0, 0]);
+        assert_eq!(&rsp[16..20], &[0x10, 0, 0, 0]);
+        // PublicKeyInfoLen, AssocCertSlotMask and PublicKeyInfo
+        assert_eq!(rsp[20] as usize, ECC_P384_PUBLIC_KEY_INFO.len());
+        assert_eq!(rsp[22], 0x01);
+        assert_eq!(&rsp[23..], ECC_P384_PUBLIC_KEY_INFO);
+    }
+
+    #[test]
+    fn test_key_pair_info_mldsa87_algo_reported_from_v14() {
+        let mut transport = TestTransport;
+        let mut meas_value = TestMeasurementValue;
+        let store = TestKeyPairStore::new();
+        let mut buf = [0u8; 128];
+        let mut ctx = negotiated_context(
+            SpdmVersion::V14,
+            key_pair_info_capability_flags(),
+            &mut transport,
+            &TestCertStore,
+            &mut meas_value,
+        );
+        ctx.set_key_pair_store(&store);
+
+        let (spdm_hdr, mut req) = request(&mut buf, &[0x14, 0xFC, 0, 0, 2]);
+        block_on(handle_get_key_pair_info(&mut ctx, spdm_hdr, &mut req)).unwrap();
+
+        let rsp = response(&req);
+        let pqc_algo_offset = 23 + MLDSA87_PUBLIC_KEY_INFO.len();
+        assert_eq!(rsp.len(), pqc_algo_offset + 10);
+        // No traditional asymmetric algorithm
+        assert_eq!(&rsp[12..20], &[0; 8]);
+        assert_eq!(&rsp[23..pqc_algo_offset], MLDSA87_PUBLIC_KEY_INFO);
+        assert_eq!(
+            &rsp[pqc_algo_offset..],
+            &[4, 0x04, 0, 0, 0, 4, 0x04, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_key_pair_info_invalid_key_pair_id() {
+        let mut transport = TestTransport;
+        let mut meas_value = TestMeasurementValue;
+        let store = TestKeyPairStore::new();
+        let mut bufs = [[0u8; 128]; 2];
+        let mut ctx = negotiated_context(
+            SpdmVersion::V13,
+            key_pair_info_capability_flags(),
+            &mut transport,
+            &TestCertStore,
+            &mut meas_value,
+        );
+        ctx.set_key_pair_store(&store);
+
+        for (key_pair_id, buf) in [0, 3].into_iter().zip(bufs.iter_mut()) {
+            let (spdm_hdr, mut req) = request(buf, &[0x13, 0xFC, 0, 0, key_pair_id]);
+            assert_eq!(
+                block_on(handle_get_key_pair_info(&mut ctx, spdm_hdr, &mut req)),
+                Err((true, CommandError::ErrorCode(ErrorCode::InvalidRequest)))
+            );
+            assert_eq!(response(&req), &[0x13, 0x7F, 0x01, 0x00]);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::session::{KeyUpdateOp, SessionError};
+use crate::state::ConnectionState;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct KeyUpdateReq {
+    key_operation: u8,
+    tag: u8,
+}
+
+impl CommonCodec for KeyUpdateReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct KeyUpdateAck {
+    key_operation: u8,
+    tag: u8,
+}
+
+impl CommonCodec for KeyUpdateAck {}
+
+async fn process_key_update<'a>(
+    ctx: &mut SpdmContext<'a>,
+    session_id: u32,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<(KeyUpdateOp, u8)> {
+    // Validate the version
+    let _ = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let key_update_req = KeyUpdateReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    let op = KeyUpdateOp::try_from(key_update_req.key_operation).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::KeyUpdate);
+
+    match ctx
+        .session_mgr
+        .key_update(session_id, op, key_update_req.tag)
+        .await
+    {
+        Ok(()) => {}
+        Err(SessionError::InvalidKeyUpdate) => {
+            Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?
+        }
+        Err(e) => Err((false, CommandError::Session(e)))?,
+    }
+
+    Ok((op, key_update_req.tag))
+}
+
+fn generate_key_update_response(
+    ctx: &mut SpdmContext<'_>,
+    op: KeyUpdateOp,
+    tag: u8,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Prepare the response message
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::KeyUpdateAck);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let key_update_ack = KeyUpdateAck {
+        key_operation: op as u8,
+        tag,
+    };
+    payload_len += key_update_ack
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    rsp.push_data(payload_len)
+        .map_err(|_| (false, CommandError::BufferTooSmall))
+}
+
+pub(crate) async fn handle_key_update<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // KEY_UPDATE is not supported in v1.0
+    if ctx.state.connection_info.version_number() < SpdmVersion::V11 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Both the responder and the requester must support KEY_UPD_CAP
+    if ctx.local_capabilities.flags.key_upd_cap() == 0
+        || ctx
+            .state
+            .connection_info
+            .peer_capabilities()
+            .flags
+            .key_upd_cap()
+            == 0
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // KEY_UPDATE is only valid inside an established session
+    let session_id = ctx.session_mgr.active_session_id().ok_or_else(|| {
+        ctx.generate_error_response(req_payload, ErrorCode::SessionRequired, 0, None)
+    })?;
+
+    // Process KEY_UPDATE request
+    let (op, tag) = process_key_update(ctx, session_id, spdm_hdr, req_payload).await?;
+
+    // Generate KEY_UPDATE_ACK response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_key_update_response(ctx, op, tag, req_payload)
+}
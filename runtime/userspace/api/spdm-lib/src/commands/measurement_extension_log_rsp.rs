@@ -0,0 +1,470 @@
+// Licensed under the Apache-2.0 license
+
+use crate::chunk_ctx::LargeResponse;
+use crate::codec::{Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::measurement_log::{MeasurementLogError, SpdmMeasurementLog};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use core::mem::size_of;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+const MEL_RESP_HEADER_SIZE: usize = size_of::<MeasurementExtensionLogRspHdr>();
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct GetMeasurementExtensionLogReq {
+    param1: u8,
+    param2: u8,
+    offset: u32,
+    length: u32,
+}
+
+impl CommonCodec for GetMeasurementExtensionLogReq {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct MeasurementExtensionLogRspHdr {
+    spdm_hdr: SpdmMsgHdr,
+    param1: u8,
+    param2: u8,
+    portion_length: u32,
+    remainder_length: u32,
+}
+
+impl CommonCodec for MeasurementExtensionLogRspHdr {}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MelResponse {
+    spdm_version: SpdmVersion,
+    offset: u32,
+    portion_len: u32,
+    remainder_len: u32,
+}
+
+impl MelResponse {
+    fn rsp_hdr(&self) -> MeasurementExtensionLogRspHdr {
+        MeasurementExtensionLogRspHdr {
+            spdm_hdr: SpdmMsgHdr::new(self.spdm_version, ReqRespCode::MeasurementExtensionLog),
+            param1: 0,
+            param2: 0,
+            portion_length: self.portion_len,
+            remainder_length: self.remainder_len,
+        }
+    }
+
+    /// Returns a chunk of the MEASUREMENT_EXTENSION_LOG response starting at `rsp_offset`.
+    /// The chunk must not extend past the end of the response.
+    pub(crate) async fn get_chunk(
+        &self,
+        measurement_log: &dyn SpdmMeasurementLog,
+        rsp_offset: usize,
+        chunk: &mut [u8],
+    ) -> CommandResult<usize> {
+        let mut chunk_data_len = 0;
+        if rsp_offset < MEL_RESP_HEADER_SIZE {
+            // Read from the response header
+            let rsp_hdr = self.rsp_hdr();
+            let hdr_bytes = &rsp_hdr.as_bytes()[rsp_offset..];
+            chunk_data_len = hdr_bytes.len().min(chunk.len());
+            chunk[..chunk_data_len].copy_from_slice(&hdr_bytes[..chunk_data_len]);
+        }
+
+        if chunk_data_len < chunk.len() {
+            let mel_offset = self.offset as usize
+                + (rsp_offset + chunk_data_len).saturating_sub(MEL_RESP_HEADER_SIZE);
+            read_log_portion(measurement_log, mel_offset, &mut chunk[chunk_data_len..]).await?;
+            chunk_data_len = chunk.len();
+        }
+
+        Ok(chunk_data_len)
+    }
+}
+
+// Fills the buffer with the MEL starting at the offset
+async fn read_log_portion(
+    measurement_log: &dyn SpdmMeasurementLog,
+    offset: usize,
+    log_portion: &mut [u8],
+) -> CommandResult<()> {
+    let mut pos = 0;
+    while pos < log_portion.len() {
+        let len = measurement_log
+            .read_log(offset + pos, &mut log_portion[pos..])
+            .await
+            .map_err(|e| (false, CommandError::MeasurementLog(e)))?;
+        if len == 0 {
+            Err((
+                false,
+                CommandError::MeasurementLog(MeasurementLogError::InvalidOffset),
+            ))?;
+        }
+        pos += len;
+    }
+    Ok(())
+}
+
+fn measurement_log<'a>(
+    ctx: &SpdmContext<'a>,
+    req_payload: &mut MessageBuf,
+) -> CommandResult<&'a dyn SpdmMeasurementLog> {
+    let connection_version = ctx.state.connection_info.version_number();
+    match ctx.measurement_log {
+        Some(measurement_log) if connection_version >= SpdmVersion::V13 => Ok(measurement_log),
+        _ => Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None)),
+    }
+}
+
+async fn process_get_measurement_extension_log<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+    measurement_log: &dyn SpdmMeasurementLog,
+) -> CommandResult<MelResponse> {
+    // Validate the version
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let req = GetMeasurementExtensionLogReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+    let offset = req.offset;
+    let length = req.length;
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::GetMeasurementExtensionLog);
+
+    // A retrieval starts at offset 0. The following requests read the same snapshot.
+    if offset == 0 {
+        measurement_log.snapshot_log().await.map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::Unspecified, 0, None)
+        })?;
+    }
+
+    let mel_len = measurement_log
+        .log_size()
+        .await
+        .map_err(|_| ctx.generate_error_response(req_payload, ErrorCode::Unspecified, 0, None))?;
+    if offset as usize >= mel_len {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    let remainder_len = (mel_len - offset as usize) as u32;
+    let portion_len = if ctx.support_large_msg_chunking() {
+        // When chunking is supported, use the full requested length
+        length.min(remainder_len)
+    } else {
+        // Otherwise, limit the portion to what fits in a single response
+        let max_portion_len = ctx
+            .min_data_transfer_size()
+            .saturating_sub(MEL_RESP_HEADER_SIZE) as u32;
+        length.min(max_portion_len).min(remainder_len)
+    };
+
+    Ok(MelResponse {
+        spdm_version: connection_version,
+        offset,
+        portion_len,
+        remainder_len: remainder_len - portion_len,
+    })
+}
+
+async fn generate_measurement_extension_log_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    rsp_ctx: MelResponse,
+    measurement_log: &dyn SpdmMeasurementLog,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    let rsp_len = MEL_RESP_HEADER_SIZE + rsp_ctx.portion_len as usize;
+    if rsp_len > ctx.min_data_transfer_size() {
+        let large_rsp = LargeResponse::MeasurementExtensionLog(rsp_ctx);
+        let handle = ctx.large_resp_context.init(large_rsp, rsp_len);
+        Err(ctx.generate_error_response(rsp, ErrorCode::LargeResponse, 0, Some(&[handle])))?;
+    }
+
+    let mut payload_len = rsp_ctx
+        .rsp_hdr()
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let portion_len = rsp_ctx.portion_len as usize;
+    if portion_len > 0 {
+        rsp.put_data(portion_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        let log_portion = rsp
+            .data_mut(portion_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        read_log_portion(measurement_log, rsp_ctx.offset as usize, log_portion).await?;
+        rsp.pull_data(portion_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        payload_len += portion_len;
+    }
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_get_measurement_extension_log<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // GET_MEASUREMENT_EXTENSION_LOG is supported from v1.3 and requires MEL_CAP
+    if ctx.local_capabilities.flags.mel_cap() == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+    let measurement_log = measurement_log(ctx, req_payload)?;
+
+    // Process GET_MEASUREMENT_EXTENSION_LOG request
+    let rsp_ctx =
+        process_get_measurement_extension_log(ctx, spdm_hdr, req_payload, measurement_log).await?;
+
+    // Generate MEASUREMENT_EXTENSION_LOG response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_measurement_extension_log_response(ctx, rsp_ctx, measurement_log, req_payload).await
+}
+
+#[cfg(test)]
+mod test {
+    extern crate alloc;
+
+    use super::*;
+    use crate::commands::chunk_get_rsp::handle_chunk_get;
+    use crate::measurement_log::{DmtfMelBuilder, MeasurementLogResult};
+    use crate::test_util::*;
+    use alloc::boxed::Box;
+    use async_trait::async_trait;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    const MEASUREMENT_SIZE: usize = 48;
+    const MEL_ENTRY_SIZE: usize = DMTF_MEL_ENTRY_HEADER_SIZE + MEASUREMENT_SIZE;
+    const MAX_MEL_SIZE: usize = DMTF_MEL_HEADER_SIZE + 8 * MEL_ENTRY_SIZE;
+
+    // Smallest DataTransferSize, so that the log does not fit in a single response
+    const DATA_TRANSFER_SIZE: u32 = 42;
+
+    /// Log of the measurements [1; 48], [2; 48], ... in the order they are extended.
+    struct TestMeasurementLog {
+        extensions: AtomicUsize,
+        snapshot: AtomicUsize,
+    }
+
+    impl TestMeasurementLog {
+        fn new(extensions: usize) -> Self {
+            Self {
+                extensions: AtomicUsize::new(extensions),
+                snapshot: AtomicUsize::new(0),
+            }
+        }
+
+        fn extend(&self) {
+            self.extensions.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn build(extensions: usize, mel: &mut [u8]) -> usize {
+            let mut builder = DmtfMelBuilder::new(mel).unwrap();
+            for i in 0..extensions {
+                builder
+                    .add_entry(
+                        SPDM_MEASUREMENT_MANIFEST_INDEX,
+                        MeasurementValueType::MutableFirmware,
+                        true,
+                        &[i as u8 + 1; MEASUREMENT_SIZE],
+                    )
+                    .unwrap();
+            }
+            builder.finish()
+        }
+    }
+
+    #[async_trait]
+    impl SpdmMeasurementLog for TestMeasurementLog {
+        async fn snapshot_log(&self) -> MeasurementLogResult<()> {
+            self.snapshot
+                .store(self.extensions.load(Ordering::Relaxed), Ordering::Relaxed);
+            Ok(())
+        }
+
+        async fn log_size(&self) -> MeasurementLogResult<usize> {
+            Ok(DMTF_MEL_HEADER_SIZE + self.snapshot.load(Ordering::Relaxed) * MEL_ENTRY_SIZE)
+        }
+
+        async fn read_log<'a>(
+            &self,
+            offset: usize,
+            log_portion: &'a mut [u8],
+        ) -> MeasurementLogResult<usize> {
+            let mut mel = [0u8; MAX_MEL_SIZE];
+            let mel_len = Self::build(self.snapshot.load(Ordering::Relaxed), &mut mel);
+            if offset >= mel_len {
+                return Err(MeasurementLogError::InvalidOffset);
+            }
+            let len = log_portion.len().min(mel_len - offset);
+            log_portion[..len].copy_from_slice(&mel[offset..offset + len]);
+            Ok(len)
+        }
+    }
+
+    fn mel_capability_flags() -> CapabilityFlags {
+        let mut flags = CapabilityFlags::default();
+        flags.set_mel_cap(1);
+        flags
+    }
+
+    fn get_mel_request(offset: u32, length: u32) -> [u8; 12] {
+        let mut msg = [0u8; 12];
+        msg[..4].copy_from_slice(&[0x13, 0xEF, 0, 0]);
+        msg[4..8].copy_from_slice(&offset.to_le_bytes());
+        msg[8..].copy_from_slice(&length.to_le_bytes());
+        msg
+    }
+
+    #[test]
+    fn test_mel_retrieved_in_portions_from_snapshot() {
+        let mut transport = TestTransport;
+        let mut meas_value = TestMeasurementValue;
+        let log = TestMeasurementLog::new(2);
+        let mut bufs = [[0u8; 64]; 8];
+        let mut bufs = bufs.iter_mut();
+        let mut ctx = negotiated_context(
+            SpdmVersion::V13,
+            mel_capability_flags(),
+            &mut transport,
+            &TestCertStore,
+            &mut meas_value,
+        );
+        ctx.local_capabilities.data_transfer_size = DATA_TRANSFER_SIZE;
+        ctx.set_measurement_log(&log);
+
+        let mut expected_mel = [0u8; MAX_MEL_SIZE];
+        let mel_len = TestMeasurementLog::build(2, &mut expected_mel);
+        let max_portion_len = DATA_TRANSFER_SIZE as usize - MEL_RESP_HEADER_SIZE;
+
+        let mut mel = [0u8; MAX_MEL_SIZE];
+        let mut offset = 0;
+        loop {
+            let (spdm_hdr, mut req) = request(
+                bufs.next().unwrap(),
+                &get_mel_request(offset as u32, u32::MAX),
+            );
+            block_on(handle_get_measurement_extension_log(
+                &mut ctx, spdm_hdr, &mut req,
+            ))
+            .unwrap();
+
+            let rsp = response(&req);
+            assert_eq!(&rsp[..4], &[0x13, 0x6F, 0, 0]);
+            let portion_len = u32::from_le_bytes(rsp[4..8].try_into().unwrap()) as usize;
+            let remainder_len = u32::from_le_bytes(rsp[8..12].try_into().unwrap()) as usize;
+            assert_eq!(portion_len, max_portion_len.min(mel_len - offset));
+            assert_eq!(remainder_len, mel_len - offset - portion_len);
+            mel[offset..offset + portion_len].copy_from_slice(&rsp[12..]);
+            offset += portion_len;
+
+            // A measurement extended during the retrieval is not part of its snapshot
+            log.extend();
+            if remainder_len == 0 {
+                break;
+            }
+        }
+        assert_eq!(offset, mel_len);
+        assert_eq!(&mel[..mel_len], &expected_mel[..mel_len]);
+
+        // The next retrieval reports the measurements extended in the meantime
+        let (spdm_hdr, mut req) = request(bufs.next().unwrap(), &get_mel_request(0, 12));
+        block_on(handle_get_measurement_extension_log(
+            &mut ctx, spdm_hdr, &mut req,
+        ))
+        .unwrap();
+        let rsp = response(&req);
+        let extensions = log.extensions.load(Ordering::Relaxed);
+        assert_eq!(&rsp[12..16], &(extensions as u32).to_le_bytes());
+        assert_eq!(
+            &rsp[8..12],
+            &((DMTF_MEL_HEADER_SIZE + extensions * MEL_ENTRY_SIZE - 12) as u32).to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn test_mel_retrieved_with_chunk_get() {
+        let mut transport = TestTransport;
+        let mut meas_value = TestMeasurementValue;
+        let log = TestMeasurementLog::new(2);
+        let mut bufs = [[0u8; 64]; 8];
+        let mut bufs = bufs.iter_mut();
+        let mut flags = mel_capability_flags();
+        flags.set_chunk_cap(1);
+        let mut ctx = negotiated_context(
+            SpdmVersion::V13,
+            flags,
+            &mut transport,
+            &TestCertStore,
+            &mut meas_value,
+        );
+        ctx.local_capabilities.data_transfer_size = DATA_TRANSFER_SIZE;
+        ctx.set_measurement_log(&log);
+
+        let mut expected_mel = [0u8; MAX_MEL_SIZE];
+        let mel_len = TestMeasurementLog::build(2, &mut expected_mel);
+
+        // The whole log is requested, which exceeds DataTransferSize
+        let (spdm_hdr, mut req) = request(bufs.next().unwrap(), &get_mel_request(0, u32::MAX));
+        let result = block_on(handle_get_measurement_extension_log(
+            &mut ctx, spdm_hdr, &mut req,
+        ));
+        assert_eq!(
+            result,
+            Err((true, CommandError::ErrorCode(ErrorCode::LargeResponse)))
+        );
+        let rsp = response(&req);
+        assert_eq!(&rsp[..4], &[0x13, 0x7F, 0x0F, 0x00]);
+        let handle = rsp[4];
+
+        // The response is retrieved with CHUNK_GET. The first chunk carries LargeMessageSize.
+        let mut large_rsp = [0u8; MEL_RESP_HEADER_SIZE + MAX_MEL_SIZE];
+        let mut large_rsp_len = 0;
+        let mut chunk_seq_num = 0u16;
+        loop {
+            let seq = chunk_seq_num.to_le_bytes();
+            let (spdm_hdr, mut req) = request(
+                bufs.next().unwrap(),
+                &[0x13, 0x86, 0, handle, seq[0], seq[1]],
+            );
+            block_on(handle_chunk_get(&mut ctx, spdm_hdr, &mut req)).unwrap();
+
+            let chunk = response(&req);
+            assert!(chunk.len() <= DATA_TRANSFER_SIZE as usize);
+            assert_eq!(&chunk[..6], &[0x13, 0x06, chunk[2], handle, seq[0], seq[1]]);
+            let chunk_size = u32::from_le_bytes(chunk[8..12].try_into().unwrap()) as usize;
+            let chunk_data = if chunk_seq_num == 0 {
+                assert_eq!(
+                    &chunk[12..16],
+                    &((MEL_RESP_HEADER_SIZE + mel_len) as u32).to_le_bytes()
+                );
+                &chunk[16..]
+            } else {
+                &chunk[12..]
+            };
+            assert_eq!(chunk_data.len(), chunk_size);
+            large_rsp[large_rsp_len..large_rsp_len + chunk_size].copy_from_slice(chunk_data);
+            large_rsp_len += chunk_size;
+
+            if chunk[2] & 0x01 != 0 {
+                break;
+            }
+            chunk_seq_num += 1;
+        }
+        assert!(!ctx.large_resp_context.in_progress());
+
+        assert_eq!(large_rsp_len, MEL_RESP_HEADER_SIZE + mel_len);
+        assert_eq!(&large_rsp[..4], &[0x13, 0x6F, 0, 0]);
+        assert_eq!(&large_rsp[4..8], &(mel_len as u32).to_le_bytes());
+        assert_eq!(&large_rsp[8..12], &0u32.to_le_bytes());
+        assert_eq!(&large_rsp[12..large_rsp_len], &expected_mel[..mel_len]);
+    }
+}
@@ -186,8 +186,9 @@ impl MeasurementsResponse {
             let signature = self
                 .l1_signature(self.asym_algo, shared_transcript, session_info, cert_store)
                 .await?;
+            let sig_len = self.asym_algo.signature_size();
             let sig_offset = (offset + copied) - signature_start;
-            let copy_len = (signature.len() - sig_offset).min(rem_len);
+            let copy_len = (sig_len - sig_offset).min(rem_len);
             chunk_buf[copied..copied + copy_len]
                 .copy_from_slice(&signature[sig_offset..sig_offset + copy_len]);
             copied += copy_len;
@@ -300,8 +301,8 @@ impl MeasurementsResponse {
         transcript: &mut Transcript,
         session_info: Option<&mut SessionInfo>,
         cert_store: &dyn SpdmCertStore,
-    ) -> CommandResult<[u8; ECC_P384_SIGNATURE_SIZE]> {
-        let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
+    ) -> CommandResult<[u8; MLDSA87_SIGNATURE_SIZE]> {
+        let mut signature = [0u8; MLDSA87_SIGNATURE_SIZE];
         let mut signature_buf = MessageBuf::new(&mut signature);
         let _ = self
             .encode_l1_signature(
@@ -351,22 +352,20 @@ impl MeasurementsResponse {
             CommandError::Measurement(MeasurementsError::InvalidSlotId),
         ))?;
 
-        let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
-        cert_store
-            .sign_hash(slot_id, asym_algo, &tbs, &mut signature)
-            .await
-            .map_err(|e| (false, CommandError::CertStore(e)))?;
-
-        buf.put_data(signature.len())
+        let sig_len = asym_algo.signature_size();
+        buf.put_data(sig_len)
             .map_err(|e| (false, CommandError::Codec(e)))?;
         let signature_buf = buf
-            .data_mut(signature.len())
+            .data_mut(sig_len)
             .map_err(|e| (false, CommandError::Codec(e)))?;
-        signature_buf.copy_from_slice(&signature);
-        buf.pull_data(signature.len())
+        cert_store
+            .sign_hash(slot_id, asym_algo, &tbs, signature_buf)
+            .await
+            .map_err(|e| (false, CommandError::CertStore(e)))?;
+        buf.pull_data(sig_len)
             .map_err(|e| (false, CommandError::Codec(e)))?;
 
-        Ok(signature.len())
+        Ok(sig_len)
     }
 
     async fn response_size(&self, measurements: &mut SpdmMeasurements<'_>) -> CommandResult<usize> {
@@ -482,43 +481,52 @@ pub(crate) async fn generate_measurements_response<'a>(
         let handle = ctx.large_resp_context.init(large_rsp, rsp_len);
         Err(ctx.generate_error_response(rsp, ErrorCode::LargeResponse, 0, Some(&[handle])))?
     } else {
-        let session_info = match ctx.session_mgr.active_session_id() {
-            Some(session_id) => match ctx.session_mgr.session_info_mut(session_id) {
-                Ok(info) => Some(info),
-                Err(e) => Err((false, CommandError::Session(e)))?,
-            },
-            None => None,
-        };
-
         // If the response fits in a single message, prepare it directly
-        // Encode the response fixed fields
-        rsp.put_data(rsp_len)
-            .map_err(|e| (false, CommandError::Codec(e)))?;
-        let rsp_buf = rsp
-            .data_mut(rsp_len)
-            .map_err(|e| (false, CommandError::Codec(e)))?;
-        let payload_len = rsp_ctx
-            .get_chunk(
-                &mut ctx.measurements,
-                &mut ctx.shared_transcript,
-                ctx.device_certs_store,
-                0,
-                rsp_buf,
-                session_info,
-            )
-            .await?;
-        if rsp_len != payload_len {
-            Err((
-                false,
-                CommandError::Measurement(MeasurementsError::InvalidBuffer),
-            ))?;
-        }
-        rsp.pull_data(payload_len)
-            .map_err(|e| (false, CommandError::Codec(e)))?;
+        encode_measurements_response(ctx, &rsp_ctx, rsp_len, rsp).await
+    }
+}
+
+// Encodes the whole MEASUREMENTS response of `rsp_len` bytes in the response buffer
+async fn encode_measurements_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    rsp_ctx: &MeasurementsResponse,
+    rsp_len: usize,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    let session_info = match ctx.session_mgr.active_session_id() {
+        Some(session_id) => match ctx.session_mgr.session_info_mut(session_id) {
+            Ok(info) => Some(info),
+            Err(e) => Err((false, CommandError::Session(e)))?,
+        },
+        None => None,
+    };
 
-        rsp.push_data(payload_len)
-            .map_err(|e| (false, CommandError::Codec(e)))
+    rsp.put_data(rsp_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    let rsp_buf = rsp
+        .data_mut(rsp_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+    let payload_len = rsp_ctx
+        .get_chunk(
+            &mut ctx.measurements,
+            &mut ctx.shared_transcript,
+            ctx.device_certs_store,
+            0,
+            rsp_buf,
+            session_info,
+        )
+        .await?;
+    if rsp_len != payload_len {
+        Err((
+            false,
+            CommandError::Measurement(MeasurementsError::InvalidBuffer),
+        ))?;
     }
+    rsp.pull_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
 }
 
 pub(crate) async fn handle_get_measurements<'a>(
@@ -551,8 +559,18 @@ pub(crate) async fn handle_get_measurements<'a>(
     // Process GET_MEASUREMENTS request
     let rsp_ctx = process_get_measurements(ctx, spdm_hdr, req_payload).await?;
 
-    // Generate MEASUREMENTS response
-    ctx.prepare_response_buffer(req_payload)?;
-    generate_measurements_response(ctx, rsp_ctx, req_payload).await?;
+    // Generate MEASUREMENTS response. A signed response that does not fit in a single transfer
+    // is generated at once in the large response buffer, so that it is only signed once.
+    if rsp_ctx.req_attr.signature_requested() == 1
+        && ctx.signed_response_in_large_buffer(rsp_ctx.asym_algo)
+    {
+        let rsp_len = rsp_ctx.response_size(&mut ctx.measurements).await?;
+        let mut large_rsp = ctx.take_large_response_buffer(req_payload)?;
+        let result = encode_measurements_response(ctx, &rsp_ctx, rsp_len, &mut large_rsp).await;
+        ctx.complete_large_response(large_rsp, result, req_payload)?;
+    } else {
+        ctx.prepare_response_buffer(req_payload)?;
+        generate_measurements_response(ctx, rsp_ctx, req_payload).await?;
+    }
     Ok(())
 }
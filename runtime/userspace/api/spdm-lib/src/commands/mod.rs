@@ -5,11 +5,22 @@ pub mod capabilities_rsp;
 pub mod certificate_rsp;
 pub mod challenge_auth_rsp;
 pub mod chunk_get_rsp;
+pub mod chunk_send_rsp;
+pub mod csr_rsp;
 pub mod digests_rsp;
+pub mod encapsulated_rsp;
 pub mod end_session_ack_rsp;
 pub mod error_rsp;
 pub mod finish_rsp;
+pub mod heartbeat_rsp;
 pub mod key_exchange_rsp;
+pub mod key_pair_info_rsp;
+pub mod key_update_rsp;
+pub mod measurement_extension_log_rsp;
 pub mod measurements_rsp;
+pub mod psk_exchange_rsp;
+pub mod psk_finish_rsp;
+pub mod set_certificate_rsp;
+pub mod set_key_pair_info_rsp;
 pub mod vendor_defined_rsp;
 pub mod version_rsp;
@@ -0,0 +1,397 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{decode_u8_slice, encode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::algorithms_rsp::selected_measurement_specification;
+use crate::commands::challenge_auth_rsp::encode_measurement_summary_hash;
+use crate::commands::error_rsp::ErrorCode;
+use crate::commands::key_exchange_rsp::init_session;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::opaque_element::secure_message::{
+    sm_select_version_from_list, sm_selected_version_opaque_data, SmVersion,
+};
+use crate::protocol::*;
+use crate::psk_store::MAX_PSK_HINT_SIZE;
+use crate::session::{SessionKeyType, SessionPolicy, SessionState};
+use crate::state::ConnectionState;
+use crate::transcript::TranscriptContext;
+use libapi_caliptra::crypto::asym::AsymAlgo;
+use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use libapi_caliptra::crypto::rng::Rng;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+// Size of the ResponderContext returned when PSK_CAP indicates session key derivation with context
+pub const RESPONDER_CONTEXT_LEN: usize = SHA384_HASH_SIZE;
+// Maximum RequesterContext size accepted in PSK_EXCHANGE
+pub const MAX_REQUESTER_CONTEXT_LEN: usize = 64;
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct PskExchangeReqBase {
+    meas_summary_hash_type: u8,
+    session_policy: SessionPolicy,
+    req_session_id: u16,
+    psk_hint_len: u16,
+    requester_context_len: u16,
+    opaque_data_len: u16,
+}
+
+impl CommonCodec for PskExchangeReqBase {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct PskExchangeRspBase {
+    heartbeat_period: u8,
+    _reserved0: u8,
+    rsp_session_id: u16,
+    _reserved1: u16,
+    responder_context_len: u16,
+    opaque_data_len: u16,
+}
+
+impl CommonCodec for PskExchangeRspBase {}
+
+struct PskExchReqContext {
+    meas_summary_hash_type: u8,
+    session_policy: SessionPolicy,
+    req_session_id: u16,
+    psk_hint: [u8; MAX_PSK_HINT_SIZE],
+    psk_hint_len: usize,
+    selected_sm_version: SmVersion,
+}
+
+struct PskExchRspContext {
+    meas_summary_hash_type: u8,
+    selected_sm_version: SmVersion,
+    resp_session_id: u16,
+    session_id: u32,
+    heartbeat_period: u8,
+}
+
+fn psk_with_context(ctx: &SpdmContext) -> bool {
+    ctx.local_capabilities.flags.psk_cap() == PskCapability::PskWithContext as u8
+}
+
+fn process_psk_exchange<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<PskExchReqContext> {
+    // Validate the version
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    // Decode the PSK_EXCHANGE request fixed fields
+    let psk_exch_req = PskExchangeReqBase::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    // Validate measurement summary hash type and DMTF spec
+    match psk_exch_req.meas_summary_hash_type {
+        0 => {} // No measurement summary hash requested
+        1 | 0xFF => {
+            if selected_measurement_specification(ctx).dmtf_measurement_spec() != 1 {
+                Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+            }
+        }
+        _ => Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?,
+    }
+
+    // Session policy is only defined from v1.3
+    let session_policy = if connection_version >= SpdmVersion::V13 {
+        psk_exch_req.session_policy
+    } else {
+        SessionPolicy::default()
+    };
+
+    // If session policy with event_all_policy is set, verify that the responder supports event capability
+    if session_policy.event_all_policy() != 0 && ctx.local_capabilities.flags.event_cap() == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    let psk_hint_len = psk_exch_req.psk_hint_len as usize;
+    let requester_context_len = psk_exch_req.requester_context_len as usize;
+    let opaque_data_len = psk_exch_req.opaque_data_len as usize;
+    if psk_hint_len > MAX_PSK_HINT_SIZE
+        || requester_context_len == 0
+        || requester_context_len > MAX_REQUESTER_CONTEXT_LEN
+        || opaque_data_len > OPAQUE_DATA_LEN_MAX_SIZE
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // PSKHint
+    let mut psk_hint = [0u8; MAX_PSK_HINT_SIZE];
+    decode_u8_slice(req_payload, &mut psk_hint[..psk_hint_len]).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    // RequesterContext is only consumed as part of the transcript
+    let mut requester_context = [0u8; MAX_REQUESTER_CONTEXT_LEN];
+    decode_u8_slice(req_payload, &mut requester_context[..requester_context_len]).map_err(
+        |_| ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None),
+    )?;
+
+    // Decode the OpaqueData and select the secure version from list
+    let mut req_opaque_data = OpaqueData {
+        len: opaque_data_len as u16,
+        ..Default::default()
+    };
+    decode_u8_slice(req_payload, &mut req_opaque_data.data[..opaque_data_len]).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    let selected_sm_version =
+        sm_select_version_from_list(req_opaque_data, ctx.supported_secure_versions).map_err(
+            |_| ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None),
+        )?;
+
+    Ok(PskExchReqContext {
+        meas_summary_hash_type: psk_exch_req.meas_summary_hash_type,
+        session_policy,
+        req_session_id: psk_exch_req.req_session_id,
+        psk_hint,
+        psk_hint_len,
+        selected_sm_version,
+    })
+}
+
+async fn init_psk_session<'a>(
+    ctx: &mut SpdmContext<'a>,
+    session_id: u32,
+    psk_exch_req_ctx: &PskExchReqContext,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<u8> {
+    let psk_store = ctx
+        .psk_store
+        .ok_or_else(|| ctx.generate_error_response(req_payload, ErrorCode::Unspecified, 0, None))?;
+
+    // Look up the PSK matching the hint
+    let psk = psk_store
+        .psk(&psk_exch_req_ctx.psk_hint[..psk_exch_req_ctx.psk_hint_len])
+        .await
+        .map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+
+    let heartbeat_period = ctx.session_mgr.heartbeat_period();
+    let session_info = ctx
+        .session_mgr
+        .session_info_mut(session_id)
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    // PSK sessions are not bound to an asymmetric key; the algorithm is unused
+    init_session(
+        session_info,
+        ctx.local_capabilities.flags,
+        &ctx.state.connection_info,
+        psk_exch_req_ctx.session_policy,
+        AsymAlgo::EccP384,
+        heartbeat_period,
+    );
+    session_info.set_psk(psk);
+    let heartbeat_period = session_info.heartbeat_period;
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::PskExchange);
+
+    // Update transcript with the PSK_EXCHANGE request
+    ctx.append_message_to_transcript(req_payload, TranscriptContext::Th, Some(session_id))
+        .await?;
+
+    Ok(heartbeat_period)
+}
+
+async fn generate_psk_exchange_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    psk_exch_rsp_ctx: PskExchRspContext,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    let session_id = psk_exch_rsp_ctx.session_id;
+
+    // Prepare the response buffer
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::PskExchangeRsp);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let responder_context_len = if psk_with_context(ctx) {
+        RESPONDER_CONTEXT_LEN
+    } else {
+        0
+    };
+
+    let opaque_data = sm_selected_version_opaque_data(psk_exch_rsp_ctx.selected_sm_version)
+        .map_err(|e| (false, CommandError::OpaqueData(e)))?;
+
+    // Encode the PSK_EXCHANGE response fixed fields
+    let psk_exch_rsp = PskExchangeRspBase {
+        heartbeat_period: psk_exch_rsp_ctx.heartbeat_period,
+        _reserved0: 0,
+        rsp_session_id: psk_exch_rsp_ctx.resp_session_id,
+        _reserved1: 0,
+        responder_context_len: responder_context_len as u16,
+        opaque_data_len: opaque_data.len,
+    };
+    payload_len += psk_exch_rsp
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    // Get the measurement summary hash
+    if psk_exch_rsp_ctx.meas_summary_hash_type != 0 {
+        payload_len +=
+            encode_measurement_summary_hash(ctx, psk_exch_rsp_ctx.meas_summary_hash_type, rsp)
+                .await?;
+    }
+
+    // ResponderContext
+    if responder_context_len > 0 {
+        let mut responder_context = [0u8; RESPONDER_CONTEXT_LEN];
+        Rng::generate_random_number(&mut responder_context)
+            .await
+            .map_err(|e| (false, CommandError::CaliptraApi(e)))?;
+        payload_len += encode_u8_slice(&responder_context, rsp)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+    }
+
+    // OpaqueData with version selection. The length is carried in the fixed fields.
+    payload_len += encode_u8_slice(&opaque_data.data[..opaque_data.len as usize], rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    // Append the response (excluding ResponderVerifyData) to Th transcript
+    ctx.append_message_to_transcript(rsp, TranscriptContext::Th, Some(session_id))
+        .await?;
+
+    // Compute TH1 transcript hash for generating the session handshake key
+    let th1_transcript_hash = ctx
+        .transcript_hash(TranscriptContext::Th, Some(session_id), false)
+        .await?;
+
+    let session_info = ctx
+        .session_mgr
+        .session_info_mut(session_id)
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    session_info
+        .generate_session_handshake_key(&th1_transcript_hash)
+        .await
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    // ResponderVerifyData is always present in PSK_EXCHANGE_RSP
+    let responder_verify_data = session_info
+        .compute_hmac(SessionKeyType::ResponseFinishedKey, &th1_transcript_hash)
+        .await
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    payload_len += encode_u8_slice(&responder_verify_data, rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    ctx.append_slice_to_transcript(
+        &responder_verify_data,
+        TranscriptContext::Th,
+        Some(session_id),
+    )
+    .await?;
+
+    // Without a ResponderContext there is no PSK_FINISH, so the data keys are
+    // derived right away from TH2 = TH1 + ResponderVerifyData.
+    if responder_context_len == 0 {
+        let th2_transcript_hash = ctx
+            .transcript_hash(TranscriptContext::Th, Some(session_id), true)
+            .await?;
+
+        ctx.session_mgr
+            .session_info_mut(session_id)
+            .map_err(|e| (false, CommandError::Session(e)))?
+            .generate_session_data_key(&th2_transcript_hash)
+            .await
+            .map_err(|e| (false, CommandError::Session(e)))?;
+    }
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_psk_exchange<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // PSK_EXCHANGE is not supported in v1.0
+    if ctx.state.connection_info.version_number() < SpdmVersion::V11 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Check if PSK_CAP is supported
+    if ctx.local_capabilities.flags.psk_cap() == PskCapability::NoPsk as u8 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // DSP0277 specifies that secure messaging requires at least MAC_CAP to be set.
+    if ctx.local_capabilities.flags.mac_cap() == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // Check negotiated algorithms are valid
+    ctx.validate_negotiated_hash_algo(req_payload)?;
+
+    // Process PSK_EXCHANGE request
+    let psk_exch_req_ctx = process_psk_exchange(ctx, spdm_hdr, req_payload)?;
+
+    ctx.state
+        .connection_info
+        .set_sec_msg_version(psk_exch_req_ctx.selected_sm_version);
+
+    // Create session
+    let (session_id, resp_session_id) = ctx
+        .session_mgr
+        .generate_session_id(psk_exch_req_ctx.req_session_id);
+
+    ctx.session_mgr.create_session(session_id).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::SessionLimitExceeded, 0, None)
+    })?;
+
+    let heartbeat_period =
+        match init_psk_session(ctx, session_id, &psk_exch_req_ctx, req_payload).await {
+            Ok(heartbeat_period) => heartbeat_period,
+            Err(e) => {
+                let _ = ctx.session_mgr.delete_session(session_id); // Ignore cleanup errors
+                return Err(e);
+            }
+        };
+
+    let psk_exch_rsp_ctx = PskExchRspContext {
+        meas_summary_hash_type: psk_exch_req_ctx.meas_summary_hash_type,
+        selected_sm_version: psk_exch_req_ctx.selected_sm_version,
+        resp_session_id,
+        session_id,
+        heartbeat_period,
+    };
+
+    // Generate PSK_EXCHANGE response
+    ctx.prepare_response_buffer(req_payload)?;
+
+    // Generate response with automatic cleanup on error
+    if let Err(e) = generate_psk_exchange_response(ctx, psk_exch_rsp_ctx, req_payload).await {
+        let _ = ctx.session_mgr.delete_session(session_id); // Ignore cleanup errors
+        return Err(e);
+    }
+
+    // With a ResponderContext the session waits for PSK_FINISH, otherwise it is ready for use
+    let session_state = if psk_with_context(ctx) {
+        SessionState::HandshakeInProgress
+    } else {
+        SessionState::Established
+    };
+
+    ctx.session_mgr
+        .set_session_state(session_id, session_state)
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{decode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::protocol::*;
+use crate::session::{SessionKeyType, SessionState};
+use crate::state::ConnectionState;
+use crate::transcript::TranscriptContext;
+use constant_time_eq::constant_time_eq;
+use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct PskFinishReqBase {
+    _reserved0: u8,
+    _reserved1: u8,
+}
+impl CommonCodec for PskFinishReqBase {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct PskFinishRspBase {
+    _reserved0: u8,
+    _reserved1: u8,
+}
+impl CommonCodec for PskFinishRspBase {}
+
+async fn process_psk_finish<'a>(
+    ctx: &mut SpdmContext<'a>,
+    session_id: u32,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Validate the version
+    let _ = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let _psk_finish_req = PskFinishReqBase::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::PskFinish);
+
+    // Append PSK_FINISH req (excluding RequesterVerifyData) to TH transcript.
+    ctx.append_message_to_transcript(req_payload, TranscriptContext::Th, Some(session_id))
+        .await?;
+
+    let mut requester_verify_data = [0u8; SHA384_HASH_SIZE];
+    decode_u8_slice(req_payload, &mut requester_verify_data).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    // Verify the RequesterVerifyData
+    let hmac_transcript_hash = ctx
+        .transcript_hash(TranscriptContext::Th, Some(session_id), false)
+        .await?;
+
+    let computed_hmac = ctx
+        .session_mgr
+        .session_info_mut(session_id)
+        .map_err(|e| (false, CommandError::Session(e)))?
+        .compute_hmac(SessionKeyType::RequestFinishedKey, &hmac_transcript_hash)
+        .await
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    if !constant_time_eq(&computed_hmac, &requester_verify_data) {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::DecryptError, 0, None))?;
+    }
+
+    // Add the RequesterVerifyData to the transcript
+    ctx.append_slice_to_transcript(
+        &requester_verify_data,
+        TranscriptContext::Th,
+        Some(session_id),
+    )
+    .await
+}
+
+async fn generate_psk_finish_response<'a>(
+    ctx: &mut SpdmContext<'a>,
+    session_id: u32,
+    rsp: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Prepare the response buffer
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::PskFinishRsp);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    payload_len += PskFinishRspBase {
+        _reserved0: 0,
+        _reserved1: 0,
+    }
+    .encode(rsp)
+    .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    ctx.append_message_to_transcript(rsp, TranscriptContext::Th, Some(session_id))
+        .await?;
+
+    // Generate session data key
+    let th2_transcript_hash = ctx
+        .transcript_hash(TranscriptContext::Th, Some(session_id), true)
+        .await?;
+
+    ctx.session_mgr
+        .session_info_mut(session_id)
+        .map_err(|e| (false, CommandError::Session(e)))?
+        .generate_session_data_key(&th2_transcript_hash)
+        .await
+        .map_err(|e| (false, CommandError::Session(e)))?;
+
+    rsp.push_data(payload_len)
+        .map_err(|e| (false, CommandError::Codec(e)))
+}
+
+pub(crate) async fn handle_psk_finish<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // PSK_FINISH is not supported in v1.0
+    if ctx.state.connection_info.version_number() < SpdmVersion::V11 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // PSK_FINISH is only used when the Responder provides a ResponderContext
+    if ctx.local_capabilities.flags.psk_cap() != PskCapability::PskWithContext as u8 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // PSK_FINISH is always sent within the session being established
+    let session_id = ctx.session_mgr.active_session_id().ok_or_else(|| {
+        ctx.generate_error_response(req_payload, ErrorCode::SessionRequired, 0, None)
+    })?;
+
+    let use_psk = ctx
+        .session_mgr
+        .session_info(session_id)
+        .map(|info| info.use_psk)
+        .map_err(|e| (false, CommandError::Session(e)))?;
+    if !use_psk {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // Verify the negotiated Hash algorithm is SHA384
+    ctx.validate_negotiated_hash_algo(req_payload)?;
+
+    // Process PSK_FINISH request
+    process_psk_finish(ctx, session_id, spdm_hdr, req_payload).await?;
+
+    // Generate PSK_FINISH response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_psk_finish_response(ctx, session_id, req_payload).await?;
+
+    // The response is still protected with the handshake keys. The session moves
+    // to Established once it has been encoded.
+    ctx.session_mgr
+        .set_session_state(session_id, SessionState::Establishing)
+        .map_err(|e| (false, CommandError::Session(e)))
+}
@@ -0,0 +1,214 @@
+// Licensed under the Apache-2.0 license
+
+use crate::cert_store::CertStoreError;
+use crate::codec::{Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::peer_cert::{verify_peer_cert_chain, PeerCertError};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use bitfield::bitfield;
+use libapi_caliptra::crypto::asym::{AsymAlgo, ECC_P384_PARAM_X_SIZE, ECC_P384_PARAM_Y_SIZE};
+use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct SetCertificateReq {
+    param1: SetCertificateAttributes,
+    key_pair_id: u8,
+}
+
+impl CommonCodec for SetCertificateReq {}
+
+bitfield! {
+    #[derive(FromBytes, IntoBytes, Immutable)]
+    #[repr(C)]
+    struct SetCertificateAttributes(u8);
+    impl Debug;
+    u8;
+    pub slot_id, _: 3, 0;
+    pub cert_model, _: 6, 4;
+    pub erase, _: 7, 7;
+}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct SetCertificateRsp {
+    slot_id: u8,
+    param2: u8,
+}
+
+impl CommonCodec for SetCertificateRsp {}
+
+fn store_error_code(e: CertStoreError) -> ErrorCode {
+    match e {
+        CertStoreError::InvalidSlotId | CertStoreError::ReadOnlySlot => ErrorCode::InvalidRequest,
+        CertStoreError::UnsupportedOperation | CertStoreError::UnsupportedAsymAlgo => {
+            ErrorCode::UnsupportedRequest
+        }
+        _ => ErrorCode::OperationFailed,
+    }
+}
+
+/// Validates the SPDM certificate chain in the request and installs it into the slot.
+/// Returns the SPDM error to report to the Requester on failure.
+///
+/// Only chains for ECC P-384 device keys can be installed.
+async fn install_cert_chain(
+    ctx: &SpdmContext<'_>,
+    slot_id: u8,
+    asym_algo: AsymAlgo,
+    req_payload: &MessageBuf<'_>,
+) -> Result<(), ErrorCode> {
+    if asym_algo != AsymAlgo::EccP384 {
+        Err(ErrorCode::UnsupportedRequest)?;
+    }
+
+    // The leaf certificate must certify the device key that signs for the slot
+    let mut device_key = [0u8; ECC_P384_PARAM_X_SIZE + ECC_P384_PARAM_Y_SIZE];
+    let device_key_len = ctx
+        .device_certs_store
+        .device_public_key(asym_algo, &mut device_key)
+        .await
+        .map_err(store_error_code)?;
+    if device_key_len != device_key.len() {
+        Err(ErrorCode::OperationFailed)?;
+    }
+
+    // Length (2 bytes) | Reserved (2 bytes) | RootHash | Certificates
+    let header = req_payload
+        .data(SPDM_CERT_CHAIN_METADATA_LEN)
+        .map_err(|_| ErrorCode::InvalidRequest)?;
+    let cert_chain_len = u16::from_le_bytes([header[0], header[1]]) as usize;
+    if cert_chain_len <= SPDM_CERT_CHAIN_METADATA_LEN {
+        Err(ErrorCode::InvalidRequest)?;
+    }
+
+    let cert_chain = req_payload
+        .data(cert_chain_len)
+        .map_err(|_| ErrorCode::InvalidRequest)?;
+    let mut root_hash = [0u8; SHA384_HASH_SIZE];
+    root_hash.copy_from_slice(
+        &cert_chain[SPDM_CERT_CHAIN_METADATA_LEN - SHA384_HASH_SIZE..SPDM_CERT_CHAIN_METADATA_LEN],
+    );
+
+    // The chain must be anchored by the root certificate it carries and every
+    // certificate must be issued by its predecessor, which must be a CA certificate.
+    let leaf_key = verify_peer_cert_chain(cert_chain, &[root_hash])
+        .await
+        .map_err(|e| match e {
+            PeerCertError::CaliptraApi(_) => ErrorCode::OperationFailed,
+            _ => ErrorCode::InvalidRequest,
+        })?;
+    if leaf_key.x != device_key[..ECC_P384_PARAM_X_SIZE]
+        || leaf_key.y != device_key[ECC_P384_PARAM_X_SIZE..]
+    {
+        Err(ErrorCode::InvalidRequest)?;
+    }
+
+    ctx.device_certs_store
+        .set_cert_chain(
+            slot_id,
+            asym_algo,
+            &root_hash,
+            &cert_chain[SPDM_CERT_CHAIN_METADATA_LEN..],
+        )
+        .await
+        .map_err(store_error_code)
+}
+
+async fn process_set_certificate<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<u8> {
+    // Validate the version
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let set_cert_req = SetCertificateReq::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    let slot_id = set_cert_req.param1.slot_id();
+    if slot_id >= ctx.device_certs_store.slot_count() {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    // Erase and KeyPairID are defined from v1.3. KeyPairID must be 0 without MULTI_KEY_CAP.
+    let erase = connection_version >= SpdmVersion::V13 && set_cert_req.param1.erase() == 1;
+    if connection_version >= SpdmVersion::V13 && set_cert_req.key_pair_id != 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    let asym_algo = ctx.validate_negotiated_base_asym_algo(req_payload)?;
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::SetCertificate);
+
+    let result = if erase {
+        ctx.device_certs_store
+            .erase_cert_chain(slot_id)
+            .await
+            .map_err(store_error_code)
+    } else {
+        install_cert_chain(ctx, slot_id, asym_algo, req_payload).await
+    };
+
+    match result {
+        Ok(()) => Ok(slot_id),
+        Err(error_code) => Err(ctx.generate_error_response(req_payload, error_code, 0, None)),
+    }
+}
+
+fn generate_set_certificate_response(
+    ctx: &mut SpdmContext<'_>,
+    slot_id: u8,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::SetCertificateRsp);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let set_cert_rsp = SetCertificateRsp { slot_id, param2: 0 };
+    payload_len += set_cert_rsp
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    rsp.push_data(payload_len)
+        .map_err(|_| (false, CommandError::BufferTooSmall))
+}
+
+pub(crate) async fn handle_set_certificate<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // SET_CERTIFICATE is supported from v1.2 and requires SET_CERT_CAP
+    if ctx.state.connection_info.version_number() < SpdmVersion::V12
+        || ctx.local_capabilities.flags.set_certificate_cap() == 0
+    {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+
+    // Slots are only provisioned over a secure session so the chain cannot be
+    // replaced by an unauthenticated Requester.
+    if ctx.session_mgr.active_session_id().is_none() {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::SessionRequired, 0, None))?;
+    }
+
+    // Process SET_CERTIFICATE request
+    let slot_id = process_set_certificate(ctx, spdm_hdr, req_payload).await?;
+
+    // Generate SET_CERTIFICATE_RSP response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_set_certificate_response(ctx, slot_id, req_payload)
+}
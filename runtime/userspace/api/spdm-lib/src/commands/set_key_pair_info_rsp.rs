@@ -0,0 +1,423 @@
+// Licensed under the Apache-2.0 license
+
+use crate::codec::{decode_u8_slice, Codec, CommonCodec, MessageBuf};
+use crate::commands::error_rsp::ErrorCode;
+use crate::commands::key_pair_info_rsp::{key_pair_store, valid_key_pair_id};
+use crate::context::SpdmContext;
+use crate::error::{CommandError, CommandResult};
+use crate::key_pair_store::{
+    KeyPairAsymAlgo, KeyPairInfo, KeyPairStoreError, KeyPairUpdate, SetKeyPairInfoOperation,
+    SpdmKeyPairStore, MAX_PUBLIC_KEY_INFO_SIZE,
+};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct SetKeyPairInfoReqBase {
+    operation: u8,
+    param2: u8,
+    reserved: u8,
+    key_pair_id: u8,
+}
+
+impl CommonCodec for SetKeyPairInfoReqBase {}
+
+// Desired key pair properties. Not present for the erase operation.
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C, packed)]
+struct SetKeyPairInfoDesired {
+    reserved: u8,
+    key_usage: KeyUsageMask,
+    asym_algo: KeyPairAsymAlgo,
+    assoc_cert_slot_mask: u8,
+}
+
+impl CommonCodec for SetKeyPairInfoDesired {}
+
+#[derive(FromBytes, IntoBytes, Immutable)]
+#[repr(C)]
+struct SetKeyPairInfoAck {
+    param1: u8,
+    param2: u8,
+}
+
+impl CommonCodec for SetKeyPairInfoAck {}
+
+// Traditional and PQC asymmetric algorithms of a key pair as a single mask
+fn algo_mask(asym_algo: KeyPairAsymAlgo, pqc_asym_algo: PqcAsymAlgo) -> u64 {
+    asym_algo.0 as u64 | (pqc_asym_algo.0 as u64) << 32
+}
+
+/// Decodes DesiredPqcAsymAlgoLen and DesiredPqcAsymAlgo, present from v1.4.
+fn decode_desired_pqc_asym_algo(req_payload: &mut MessageBuf) -> Option<PqcAsymAlgo> {
+    let len = u8::decode(req_payload).ok()? as usize;
+    let mut pqc_asym_algo = [0u8; size_of::<PqcAsymAlgo>()];
+    decode_u8_slice(req_payload, pqc_asym_algo.get_mut(..len)?).ok()?;
+    Some(PqcAsymAlgo(u32::from_le_bytes(pqc_asym_algo)))
+}
+
+/// Checks the requested operation against the capabilities of the key pair.
+fn operation_allowed(
+    operation: SetKeyPairInfoOperation,
+    info: &KeyPairInfo,
+    update: &KeyPairUpdate,
+) -> bool {
+    let caps = info.capabilities;
+    let desired_algo = algo_mask(update.asym_algo, update.pqc_asym_algo);
+    let algo_capabilities = algo_mask(info.asym_algo_capabilities, info.pqc_asym_algo_capabilities);
+    let current_algo = algo_mask(info.current_asym_algo, info.current_pqc_asym_algo);
+    let algo_supported = desired_algo.count_ones() <= 1 && desired_algo & !algo_capabilities == 0;
+    let usage_supported = update.key_usage.0 & !info.key_usage_capabilities.0 == 0;
+
+    match operation {
+        SetKeyPairInfoOperation::Erase => caps.erasable_cap() == 1,
+        SetKeyPairInfoOperation::Generate => {
+            caps.gen_key_cap() == 1 && desired_algo.count_ones() == 1 && algo_supported
+        }
+        SetKeyPairInfoOperation::Change => {
+            let usage_ok = update.key_usage.0 == info.current_key_usage.0
+                || (caps.key_usage_cap() == 1 && usage_supported);
+            let algo_ok =
+                desired_algo == current_algo || (caps.asym_algo_cap() == 1 && algo_supported);
+            let assoc_ok = update.assoc_cert_slot_mask == info.assoc_cert_slot_mask
+                || caps.cert_assoc_cap() == 1;
+            usage_ok && algo_ok && assoc_ok
+        }
+    }
+}
+
+/// Validates the request against the key pair and applies it.
+/// Returns the SPDM error to report to the Requester on failure.
+async fn apply_key_pair_info(
+    store: &dyn SpdmKeyPairStore,
+    key_pair_id: u8,
+    operation: SetKeyPairInfoOperation,
+    update: KeyPairUpdate,
+) -> Result<(), ErrorCode> {
+    let mut public_key_info = [0u8; MAX_PUBLIC_KEY_INFO_SIZE];
+    let (info, _) = store
+        .key_pair_info(key_pair_id, &mut public_key_info)
+        .await
+        .map_err(|_| ErrorCode::Unspecified)?;
+
+    if !operation_allowed(operation, &info, &update) {
+        Err(ErrorCode::InvalidRequest)?;
+    }
+
+    store
+        .set_key_pair_info(key_pair_id, operation, update)
+        .await
+        .map_err(|e| match e {
+            KeyPairStoreError::InvalidKeyPairId
+            | KeyPairStoreError::InvalidParam
+            | KeyPairStoreError::UnsupportedOperation => ErrorCode::InvalidRequest,
+            _ => ErrorCode::OperationFailed,
+        })
+}
+
+async fn process_set_key_pair_info<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+    store: &dyn SpdmKeyPairStore,
+) -> CommandResult<()> {
+    // Validate the version
+    let connection_version = ctx.validate_spdm_version(&spdm_hdr, req_payload)?;
+
+    let req_base = SetKeyPairInfoReqBase::decode(req_payload).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    let operation = SetKeyPairInfoOperation::try_from(req_base.operation & 0x0F).map_err(|_| {
+        ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+    })?;
+
+    if !valid_key_pair_id(store, req_base.key_pair_id) {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None))?;
+    }
+
+    let update = if operation == SetKeyPairInfoOperation::Erase {
+        KeyPairUpdate {
+            key_usage: KeyUsageMask::default(),
+            asym_algo: KeyPairAsymAlgo::default(),
+            pqc_asym_algo: PqcAsymAlgo::default(),
+            assoc_cert_slot_mask: 0,
+        }
+    } else {
+        let desired = SetKeyPairInfoDesired::decode(req_payload).map_err(|_| {
+            ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+        })?;
+        let pqc_asym_algo = if connection_version >= SpdmVersion::V14 {
+            decode_desired_pqc_asym_algo(req_payload).ok_or_else(|| {
+                ctx.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)
+            })?
+        } else {
+            PqcAsymAlgo::default()
+        };
+        KeyPairUpdate {
+            key_usage: desired.key_usage,
+            asym_algo: desired.asym_algo,
+            pqc_asym_algo,
+            assoc_cert_slot_mask: desired.assoc_cert_slot_mask,
+        }
+    };
+
+    ctx.reset_transcript_via_req_code(ReqRespCode::SetKeyPairInfo);
+
+    apply_key_pair_info(store, req_base.key_pair_id, operation, update)
+        .await
+        .map_err(|error_code| ctx.generate_error_response(req_payload, error_code, 0, None))
+}
+
+fn generate_set_key_pair_info_ack(
+    ctx: &mut SpdmContext<'_>,
+    rsp: &mut MessageBuf<'_>,
+) -> CommandResult<()> {
+    // Spdm Header first
+    let connection_version = ctx.state.connection_info.version_number();
+    let spdm_hdr = SpdmMsgHdr::new(connection_version, ReqRespCode::SetKeyPairInfoAck);
+    let mut payload_len = spdm_hdr
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    let ack = SetKeyPairInfoAck {
+        param1: 0,
+        param2: 0,
+    };
+    payload_len += ack
+        .encode(rsp)
+        .map_err(|e| (false, CommandError::Codec(e)))?;
+
+    rsp.push_data(payload_len)
+        .map_err(|_| (false, CommandError::BufferTooSmall))
+}
+
+pub(crate) async fn handle_set_key_pair_info<'a>(
+    ctx: &mut SpdmContext<'a>,
+    spdm_hdr: SpdmMsgHdr,
+    req_payload: &mut MessageBuf<'a>,
+) -> CommandResult<()> {
+    // Check if the connection state is valid
+    if ctx.state.connection_info.state() < ConnectionState::AlgorithmsNegotiated {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnexpectedRequest, 0, None))?;
+    }
+
+    // SET_KEY_PAIR_INFO is supported from v1.3 and requires SET_KEY_PAIR_INFO_CAP
+    if ctx.local_capabilities.flags.set_key_pair_info_cap() == 0 {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::UnsupportedRequest, 0, None))?;
+    }
+    let store = key_pair_store(ctx, req_payload)?;
+
+    // Key pairs are only reconfigured over a secure session, as for SET_CERTIFICATE
+    if ctx.session_mgr.active_session_id().is_none() {
+        Err(ctx.generate_error_response(req_payload, ErrorCode::SessionRequired, 0, None))?;
+    }
+
+    // Process SET_KEY_PAIR_INFO request
+    process_set_key_pair_info(ctx, spdm_hdr, req_payload, store).await?;
+
+    // Generate SET_KEY_PAIR_INFO_ACK response
+    ctx.prepare_response_buffer(req_payload)?;
+    generate_set_key_pair_info_ack(ctx, req_payload)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::key_pair_store::KeyPairCapabilities;
+    use crate::test_util::*;
+    use core::sync::atomic::Ordering;
+
+    fn ecc384_key_pair(capabilities: KeyPairCapabilities) -> KeyPairInfo {
+        let mut key_usage = KeyUsageMask::default();
+        key_usage.set_challenge_usage(1);
+        key_usage.set_key_exch_usage(1);
+        let mut asym_algo = KeyPairAsymAlgo::default();
+        asym_algo.set_ecc384(1);
+        KeyPairInfo {
+            capabilities,
+            key_usage_capabilities: key_usage,
+            current_key_usage: key_usage,
+            asym_algo_capabilities: asym_algo,
+            current_asym_algo: asym_algo,
+            pqc_asym_algo_capabilities: PqcAsymAlgo::default(),
+            current_pqc_asym_algo: PqcAsymAlgo::default(),
+            assoc_cert_slot_mask: 0x01,
+        }
+    }
+
+    #[test]
+    fn test_change_key_usage_requires_capability() {
+        let mut update = KeyPairUpdate {
+            key_usage: KeyUsageMask::default(),
+            asym_algo: ecc384_key_pair(KeyPairCapabilities::default()).current_asym_algo,
+            pqc_asym_algo: PqcAsymAlgo::default(),
+            assoc_cert_slot_mask: 0x01,
+        };
+        update.key_usage.set_challenge_usage(1);
+
+        let info = ecc384_key_pair(KeyPairCapabilities::default());
+        assert!(!operation_allowed(
+            SetKeyPairInfoOperation::Change,
+            &info,
+            &update
+        ));
+
+        let mut caps = KeyPairCapabilities::default();
+        caps.set_key_usage_cap(1);
+        let info = ecc384_key_pair(caps);
+        assert!(operation_allowed(
+            SetKeyPairInfoOperation::Change,
+            &info,
+            &update
+        ));
+
+        // Usages outside of KeyUsageCapabilities are rejected
+        update.key_usage.set_measurement_usage(1);
+        assert!(!operation_allowed(
+            SetKeyPairInfoOperation::Change,
+            &info,
+            &update
+        ));
+    }
+
+    #[test]
+    fn test_generate_requires_single_supported_algo() {
+        let mut caps = KeyPairCapabilities::default();
+        caps.set_gen_key_cap(1);
+        let info = ecc384_key_pair(caps);
+
+        let mut update = KeyPairUpdate {
+            key_usage: info.current_key_usage,
+            asym_algo: KeyPairAsymAlgo::default(),
+            pqc_asym_algo: PqcAsymAlgo::default(),
+            assoc_cert_slot_mask: 0,
+        };
+        assert!(!operation_allowed(
+            SetKeyPairInfoOperation::Generate,
+            &info,
+            &update
+        ));
+
+        update.asym_algo.set_ecc384(1);
+        assert!(operation_allowed(
+            SetKeyPairInfoOperation::Generate,
+            &info,
+            &update
+        ));
+
+        update.asym_algo.set_ecc256(1);
+        assert!(!operation_allowed(
+            SetKeyPairInfoOperation::Generate,
+            &info,
+            &update
+        ));
+
+        // A key pair has a single algorithm, traditional or PQC
+        update.asym_algo.set_ecc256(0);
+        update.pqc_asym_algo.set_ml_dsa_87(1);
+        assert!(!operation_allowed(
+            SetKeyPairInfoOperation::Generate,
+            &info,
+            &update
+        ));
+        assert!(!operation_allowed(
+            SetKeyPairInfoOperation::Erase,
+            &info,
+            &update
+        ));
+    }
+
+    fn set_key_pair_info_capability_flags() -> CapabilityFlags {
+        let mut flags = CapabilityFlags::default();
+        flags.set_set_key_pair_info_cap(1);
+        flags
+    }
+
+    // SET_KEY_PAIR_INFO changing the key usage of the ECC P-384 key pair to CHALLENGE
+    const CHANGE_KEY_USAGE_REQ: [u8; 14] = [
+        0x13, 0xFD, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x10, 0x00, 0x00, 0x00, 0x01,
+    ];
+
+    #[test]
+    fn test_set_key_pair_info_change_key_usage() {
+        let mut transport = TestTransport;
+        let mut meas_value = TestMeasurementValue;
+        let store = TestKeyPairStore::new();
+        let mut buf = [0u8; 64];
+        let mut ctx = negotiated_context(
+            SpdmVersion::V13,
+            set_key_pair_info_capability_flags(),
+            &mut transport,
+            &TestCertStore,
+            &mut meas_value,
+        );
+        ctx.set_key_pair_store(&store);
+        ctx.session_mgr
+            .create_session_at(0xFFFE_FFFE, None)
+            .unwrap();
+        ctx.session_mgr.set_active_session_id(0xFFFE_FFFE);
+
+        let (spdm_hdr, mut req) = request(&mut buf, &CHANGE_KEY_USAGE_REQ);
+        block_on(handle_set_key_pair_info(&mut ctx, spdm_hdr, &mut req)).unwrap();
+
+        assert_eq!(response(&req), &[0x13, 0x7D, 0x00, 0x00]);
+        assert_eq!(store.ecc384_key_usage.load(Ordering::Relaxed), 0x02);
+    }
+
+    #[test]
+    fn test_set_key_pair_info_rejected_requests() {
+        let mut transport = TestTransport;
+        let mut meas_value = TestMeasurementValue;
+        let store = TestKeyPairStore::new();
+        let mut bufs = [[0u8; 64]; 3];
+        let mut ctx = negotiated_context(
+            SpdmVersion::V13,
+            set_key_pair_info_capability_flags(),
+            &mut transport,
+            &TestCertStore,
+            &mut meas_value,
+        );
+        ctx.set_key_pair_store(&store);
+        let [outside_session_buf, no_reserved_buf, mldsa_buf] = &mut bufs;
+
+        // Key pairs are only reconfigured over a secure session
+        let (spdm_hdr, mut req) = request(outside_session_buf, &CHANGE_KEY_USAGE_REQ);
+        assert_eq!(
+            block_on(handle_set_key_pair_info(&mut ctx, spdm_hdr, &mut req)),
+            Err((true, CommandError::ErrorCode(ErrorCode::SessionRequired)))
+        );
+
+        ctx.session_mgr
+            .create_session_at(0xFFFE_FFFE, None)
+            .unwrap();
+        ctx.session_mgr.set_active_session_id(0xFFFE_FFFE);
+
+        // The Reserved byte before DesiredKeyUsage is missing
+        let mut no_reserved_req = [0u8; 13];
+        no_reserved_req[..6].copy_from_slice(&CHANGE_KEY_USAGE_REQ[..6]);
+        no_reserved_req[6..].copy_from_slice(&CHANGE_KEY_USAGE_REQ[7..]);
+        let (spdm_hdr, mut req) = request(no_reserved_buf, &no_reserved_req);
+        assert_eq!(
+            block_on(handle_set_key_pair_info(&mut ctx, spdm_hdr, &mut req)),
+            Err((true, CommandError::ErrorCode(ErrorCode::InvalidRequest)))
+        );
+
+        // The key usage of the ML-DSA-87 key pair cannot be changed
+        let mut mldsa_req = CHANGE_KEY_USAGE_REQ;
+        mldsa_req[5] = 2;
+        mldsa_req[9] = 0;
+        let (spdm_hdr, mut req) = request(mldsa_buf, &mldsa_req);
+        assert_eq!(
+            block_on(handle_set_key_pair_info(&mut ctx, spdm_hdr, &mut req)),
+            Err((true, CommandError::ErrorCode(ErrorCode::InvalidRequest)))
+        );
+        assert_eq!(response(&req), &[0x13, 0x7F, 0x01, 0x00]);
+        assert_eq!(
+            store.ecc384_key_usage.load(Ordering::Relaxed),
+            TestKeyPairStore::key_usage_capabilities().0
+        );
+    }
+}
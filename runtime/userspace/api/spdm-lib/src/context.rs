@@ -1,25 +1,34 @@
 // Licensed under the Apache-2.0 license
 
 use crate::cert_store::*;
-use crate::chunk_ctx::LargeResponseCtx;
+use crate::chunk_ctx::{LargeRequestCtx, LargeResponse, LargeResponseCtx};
 use crate::codec::{encode_u8_slice, Codec, MessageBuf};
 use crate::commands::error_rsp::{encode_error_response, ErrorCode};
 use crate::commands::{
     algorithms_rsp, capabilities_rsp, certificate_rsp, challenge_auth_rsp, chunk_get_rsp,
-    digests_rsp, end_session_ack_rsp, finish_rsp, key_exchange_rsp, measurements_rsp,
-    vendor_defined_rsp, version_rsp,
+    chunk_send_rsp, csr_rsp, digests_rsp, encapsulated_rsp, end_session_ack_rsp, finish_rsp,
+    heartbeat_rsp, key_exchange_rsp, key_pair_info_rsp, key_update_rsp,
+    measurement_extension_log_rsp, measurements_rsp, psk_exchange_rsp, psk_finish_rsp,
+    set_certificate_rsp, set_key_pair_info_rsp, vendor_defined_rsp, version_rsp,
 };
+use crate::encap_ctx::EncapContext;
 use crate::error::*;
+use crate::key_pair_store::SpdmKeyPairStore;
+use crate::measurement_log::SpdmMeasurementLog;
 use crate::measurements::SpdmMeasurements;
 use crate::protocol::algorithms::*;
 use crate::protocol::common::{ReqRespCode, SpdmMsgHdr};
 use crate::protocol::version::*;
 use crate::protocol::DeviceCapabilities;
-use crate::session::{SessionManager, SessionState};
+use crate::psk_store::SpdmPskStore;
+use crate::session::{SessionExpirySignal, SessionManager, SessionState, MAX_NUM_SESSIONS};
 use crate::state::{ConnectionState, State};
 use crate::transcript::{Transcript, TranscriptContext};
 use crate::transport::common::SpdmTransport;
 use crate::vdm_handler::VdmHandler;
+use core::future::{poll_fn, Future};
+use core::pin::pin;
+use core::task::Poll;
 use libapi_caliptra::crypto::asym::*;
 use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
 
@@ -39,9 +48,18 @@ pub struct SpdmContext<'a> {
     pub(crate) local_capabilities: DeviceCapabilities,
     pub(crate) local_algorithms: LocalDeviceAlgorithms<'a>,
     pub(crate) device_certs_store: &'a dyn SpdmCertStore,
+    pub(crate) psk_store: Option<&'a dyn SpdmPskStore>,
+    pub(crate) key_pair_store: Option<&'a dyn SpdmKeyPairStore>,
     pub(crate) measurements: SpdmMeasurements<'a>,
+    pub(crate) measurement_log: Option<&'a dyn SpdmMeasurementLog>,
     pub(crate) large_resp_context: LargeResponseCtx,
+    pub(crate) large_req_context: LargeRequestCtx,
+    pub(crate) large_req_buffer: Option<&'a mut [u8]>,
+    pub(crate) large_rsp_buffer: Option<&'a mut [u8]>,
+    pub(crate) encap_context: EncapContext,
+    pub(crate) requester_root_hashes: &'a [[u8; SHA384_HASH_SIZE]],
     pub(crate) session_mgr: SessionManager,
+    session_expiry_signal: Option<&'a SessionExpirySignal>,
     pub(crate) vdm_handlers: Option<&'a mut [&'a mut dyn VdmHandler]>,
 }
 
@@ -54,6 +72,7 @@ impl<'a> SpdmContext<'a> {
         local_capabilities: DeviceCapabilities,
         local_algorithms: LocalDeviceAlgorithms<'a>,
         device_certs_store: &'a dyn SpdmCertStore,
+        psk_store: Option<&'a dyn SpdmPskStore>,
         measurements: SpdmMeasurements<'a>,
         vdm_handlers: Option<&'a mut [&'a mut dyn VdmHandler]>,
     ) -> SpdmResult<Self> {
@@ -61,6 +80,11 @@ impl<'a> SpdmContext<'a> {
 
         validate_cert_store(device_certs_store)?;
 
+        // PSK_CAP requires a PSK store to look up the pre-shared keys
+        if local_capabilities.flags.psk_cap() != 0 && psk_store.is_none() {
+            Err(SpdmError::InvalidParam)?;
+        }
+
         Ok(Self {
             supported_versions,
             supported_secure_versions,
@@ -70,23 +94,31 @@ impl<'a> SpdmContext<'a> {
             local_capabilities,
             local_algorithms,
             device_certs_store,
+            psk_store,
+            key_pair_store: None,
             measurements,
+            measurement_log: None,
             large_resp_context: LargeResponseCtx::default(),
+            large_req_context: LargeRequestCtx::default(),
+            large_req_buffer: None,
+            large_rsp_buffer: None,
+            encap_context: EncapContext::default(),
+            requester_root_hashes: &[],
             session_mgr: SessionManager::new(),
+            session_expiry_signal: None,
             vdm_handlers,
         })
     }
 
     pub async fn process_message(&mut self, msg_buf: &mut MessageBuf<'a>) -> SpdmResult<()> {
-        let secure = self
-            .transport
-            .receive_request(msg_buf)
-            .await
-            .map_err(SpdmError::Transport)?;
+        let secure = self.receive_request(msg_buf).await?;
 
         // Reset active session_id
         self.session_mgr.reset_active_session_id();
 
+        // Tear down sessions whose peer has stopped sending heartbeats
+        self.session_mgr.expire_sessions();
+
         if secure {
             // Create a temporary buffer for decrypted application data
             let mut app_data = [0u8; MAX_SPDM_RESPONDER_BUF_SIZE];
@@ -122,6 +154,27 @@ impl<'a> SpdmContext<'a> {
         Ok(())
     }
 
+    /// Waits for the next request. Sessions whose heartbeat window expires in the meantime
+    /// are torn down each time the session expiry signal is raised.
+    async fn receive_request(&mut self, msg_buf: &mut MessageBuf<'a>) -> SpdmResult<bool> {
+        let session_mgr = &mut self.session_mgr;
+        let expiry_signal = self.session_expiry_signal;
+        let mut request = self.transport.receive_request(msg_buf);
+
+        poll_fn(|cx| {
+            if let Poll::Ready(result) = request.as_mut().poll(cx) {
+                return Poll::Ready(result.map_err(SpdmError::Transport));
+            }
+            if let Some(expiry_signal) = expiry_signal {
+                while pin!(expiry_signal.wait()).poll(cx).is_ready() {
+                    session_mgr.expire_sessions();
+                }
+            }
+            Poll::Pending
+        })
+        .await
+    }
+
     async fn handle_request(&mut self, buf: &mut MessageBuf<'a>) -> CommandResult<()> {
         let req = buf;
 
@@ -137,6 +190,89 @@ impl<'a> SpdmContext<'a> {
             self.large_resp_context.reset();
         }
 
+        if req_code == ReqRespCode::ChunkSend {
+            return self.handle_chunk_send(req_msg_header, req).await;
+        }
+
+        if self.large_req_context.in_progress() {
+            // Discard the partially received large request if the request is not a CHUNK_SEND
+            self.large_req_context.reset();
+        }
+
+        self.dispatch_request(req_code, req_msg_header, req).await
+    }
+
+    async fn handle_chunk_send(
+        &mut self,
+        spdm_hdr: SpdmMsgHdr,
+        req: &mut MessageBuf<'a>,
+    ) -> CommandResult<()> {
+        let Some((handle, chunk_seq_num)) = chunk_send_rsp::handle_chunk_send(self, spdm_hdr, req)?
+        else {
+            return Ok(());
+        };
+
+        // All chunks received. Process the large request in the reassembly buffer.
+        let large_req_len = self.large_req_context.large_request_size();
+        self.large_req_context.reset();
+        let large_req_buf = self
+            .large_req_buffer
+            .take()
+            .ok_or((false, CommandError::InvalidChunkContext))?;
+        let mut large_req = MessageBuf::new(large_req_buf);
+
+        let result = self
+            .handle_large_request(&mut large_req, large_req_len)
+            .await;
+
+        // The response to the large request is carried in the CHUNK_SEND_ACK
+        let ack_result = match result {
+            Ok(()) | Err((true, _)) => chunk_send_rsp::generate_large_request_ack(
+                self,
+                handle,
+                chunk_seq_num,
+                &large_req,
+                req,
+            ),
+            Err(_) => Ok(()),
+        };
+        self.large_req_buffer = Some(large_req.into_inner());
+
+        ack_result?;
+        result
+    }
+
+    async fn handle_large_request(
+        &mut self,
+        large_req: &mut MessageBuf<'a>,
+        large_req_len: usize,
+    ) -> CommandResult<()> {
+        large_req
+            .put_data(large_req_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+
+        let req_msg_header: SpdmMsgHdr =
+            SpdmMsgHdr::decode(large_req).map_err(|e| (false, CommandError::Codec(e)))?;
+
+        let req_code = req_msg_header
+            .req_resp_code()
+            .map_err(|_| (false, CommandError::UnsupportedRequest))?;
+
+        // Chunk transfer requests cannot be sent as a large request
+        if matches!(req_code, ReqRespCode::ChunkSend | ReqRespCode::ChunkGet) {
+            Err(self.generate_error_response(large_req, ErrorCode::InvalidRequest, 0, None))?;
+        }
+
+        self.dispatch_request(req_code, req_msg_header, large_req)
+            .await
+    }
+
+    async fn dispatch_request(
+        &mut self,
+        req_code: ReqRespCode,
+        req_msg_header: SpdmMsgHdr,
+        req: &mut MessageBuf<'a>,
+    ) -> CommandResult<()> {
         // Check for requests prohibited within session
         self.validate_request_in_session_context(req_code, req)?;
 
@@ -162,6 +298,14 @@ impl<'a> SpdmContext<'a> {
             ReqRespCode::GetMeasurements => {
                 measurements_rsp::handle_get_measurements(self, req_msg_header, req).await?
             }
+            ReqRespCode::GetMeasurementExtensionLog => {
+                measurement_extension_log_rsp::handle_get_measurement_extension_log(
+                    self,
+                    req_msg_header,
+                    req,
+                )
+                .await?
+            }
             ReqRespCode::ChunkGet => {
                 chunk_get_rsp::handle_chunk_get(self, req_msg_header, req).await?
             }
@@ -169,6 +313,35 @@ impl<'a> SpdmContext<'a> {
                 key_exchange_rsp::handle_key_exchange(self, req_msg_header, req).await?
             }
             ReqRespCode::Finish => finish_rsp::handle_finish(self, req_msg_header, req).await?,
+            ReqRespCode::PskExchange => {
+                psk_exchange_rsp::handle_psk_exchange(self, req_msg_header, req).await?
+            }
+            ReqRespCode::PskFinish => {
+                psk_finish_rsp::handle_psk_finish(self, req_msg_header, req).await?
+            }
+            ReqRespCode::Heartbeat => {
+                heartbeat_rsp::handle_heartbeat(self, req_msg_header, req).await?
+            }
+            ReqRespCode::KeyUpdate => {
+                key_update_rsp::handle_key_update(self, req_msg_header, req).await?
+            }
+            ReqRespCode::GetEncapsulatedRequest => {
+                encapsulated_rsp::handle_get_encapsulated_request(self, req_msg_header, req).await?
+            }
+            ReqRespCode::DeliverEncapsulatedResponse => {
+                encapsulated_rsp::handle_deliver_encapsulated_response(self, req_msg_header, req)
+                    .await?
+            }
+            ReqRespCode::GetCsr => csr_rsp::handle_get_csr(self, req_msg_header, req).await?,
+            ReqRespCode::SetCertificate => {
+                set_certificate_rsp::handle_set_certificate(self, req_msg_header, req).await?
+            }
+            ReqRespCode::GetKeyPairInfo => {
+                key_pair_info_rsp::handle_get_key_pair_info(self, req_msg_header, req).await?
+            }
+            ReqRespCode::SetKeyPairInfo => {
+                set_key_pair_info_rsp::handle_set_key_pair_info(self, req_msg_header, req).await?
+            }
             ReqRespCode::EndSession => {
                 end_session_ack_rsp::handle_end_session(self, req_msg_header, req).await?
             }
@@ -206,9 +379,66 @@ impl<'a> SpdmContext<'a> {
         }
     }
 
+    /// Sets the heartbeat period (in seconds) offered in KEY_EXCHANGE_RSP when both
+    /// sides support HBEAT_CAP. A value of 0 disables heartbeat.
+    pub fn set_heartbeat_period(&mut self, heartbeat_period: u8) {
+        self.session_mgr.set_heartbeat_period(heartbeat_period);
+    }
+
+    /// Sets the maximum number of concurrent secure sessions on this transport.
+    /// Must be between 1 and `MAX_NUM_SESSIONS`.
+    pub fn set_max_sessions(&mut self, max_sessions: usize) -> SpdmResult<()> {
+        if max_sessions == 0 || max_sessions > MAX_NUM_SESSIONS {
+            return Err(SpdmError::InvalidParam);
+        }
+        self.session_mgr.set_max_sessions(max_sessions);
+        Ok(())
+    }
+
+    /// Sets the signal the platform raises periodically to tear down sessions whose
+    /// heartbeat window has expired while no request is received. Without it, expired
+    /// sessions are only torn down when the next request is received.
+    pub fn set_session_expiry_signal(&mut self, expiry_signal: &'a SessionExpirySignal) {
+        self.session_expiry_signal = Some(expiry_signal);
+    }
+
+    /// Sets the SHA-384 hashes of the root certificates trusted to anchor a Requester
+    /// certificate chain. Mutual authentication is only requested in KEY_EXCHANGE_RSP
+    /// when at least one root hash is provisioned.
+    pub fn set_requester_root_hashes(&mut self, root_hashes: &'a [[u8; SHA384_HASH_SIZE]]) {
+        self.requester_root_hashes = root_hashes;
+    }
+
+    /// Sets the buffer used to reassemble large requests received in chunks with CHUNK_SEND.
+    /// Large requests up to the smaller of the buffer size and the local `max_spdm_msg_size`
+    /// are accepted. CHUNK_SEND is not supported until a buffer is set.
+    pub fn set_large_request_buffer(&mut self, large_req_buffer: &'a mut [u8]) {
+        self.large_req_buffer = Some(large_req_buffer);
+    }
+
+    /// Sets the buffer used to generate responses signed with an asymmetric algorithm whose
+    /// signature does not fit in a single transfer, such as ML-DSA-87. These responses are
+    /// sent in chunks with CHUNK_GET and are rejected with ResponseTooLarge until a buffer is set.
+    pub fn set_large_response_buffer(&mut self, large_rsp_buffer: &'a mut [u8]) {
+        self.large_rsp_buffer = Some(large_rsp_buffer);
+    }
+
+    /// Sets the store describing the asymmetric key pairs that back the certificate slots.
+    /// Required to serve GET_KEY_PAIR_INFO and SET_KEY_PAIR_INFO.
+    pub fn set_key_pair_store(&mut self, key_pair_store: &'a dyn SpdmKeyPairStore) {
+        self.key_pair_store = Some(key_pair_store);
+    }
+
+    /// Sets the measurement extension log (MEL) of the device.
+    /// Required to serve GET_MEASUREMENT_EXTENSION_LOG when MEL_CAP is set.
+    pub fn set_measurement_log(&mut self, measurement_log: &'a dyn SpdmMeasurementLog) {
+        self.measurement_log = Some(measurement_log);
+    }
+
     pub(crate) fn reset(&mut self) {
         self.state.reset();
         self.session_mgr.reset();
+        self.encap_context.reset();
     }
 
     pub(crate) fn prepare_response_buffer(&self, rsp_buf: &mut MessageBuf) -> CommandResult<()> {
@@ -258,6 +488,100 @@ impl<'a> SpdmContext<'a> {
                 == 1
     }
 
+    /// Returns true if responses signed with the asymmetric algorithm are generated in the
+    /// large response buffer, as the signature alone may not fit in a single transfer.
+    pub(crate) fn signed_response_in_large_buffer(&self, asym_algo: AsymAlgo) -> bool {
+        asym_algo.signature_size() > ECC_P384_SIGNATURE_SIZE
+    }
+
+    /// Takes the large response buffer to generate a response in.
+    /// The buffer is restored by `complete_large_response`.
+    pub(crate) fn take_large_response_buffer(
+        &mut self,
+        rsp: &mut MessageBuf,
+    ) -> CommandResult<MessageBuf<'a>> {
+        match self.large_rsp_buffer.take() {
+            Some(large_rsp_buf) if self.support_large_msg_chunking() => {
+                Ok(MessageBuf::new(large_rsp_buf))
+            }
+            large_rsp_buf => {
+                self.large_rsp_buffer = large_rsp_buf;
+                Err(self.generate_error_response(rsp, ErrorCode::ResponseTooLarge, 0, None))
+            }
+        }
+    }
+
+    /// Restores the large response buffer once the response has been generated in it.
+    /// A response that fits in a single transfer, or an error response, is copied to `rsp`.
+    /// Otherwise `rsp` is set to ERROR(LargeResponse) and the response is sent with CHUNK_GET.
+    pub(crate) fn complete_large_response(
+        &mut self,
+        large_rsp: MessageBuf<'a>,
+        result: CommandResult<()>,
+        rsp: &mut MessageBuf,
+    ) -> CommandResult<()> {
+        let large_rsp_len = large_rsp.data_len();
+        let large_rsp_offset = large_rsp.data_offset();
+
+        let copy_result = match result {
+            Ok(()) if large_rsp_len > self.min_data_transfer_size() => None,
+            Ok(()) | Err((true, _)) => Some(self.copy_response(&large_rsp, rsp)),
+            Err(_) => None,
+        };
+        self.large_rsp_buffer = Some(large_rsp.into_inner());
+
+        if let Some(copy_result) = copy_result {
+            copy_result?;
+            return result;
+        }
+        result?;
+
+        let large_rsp = LargeResponse::Buffered(large_rsp_offset);
+        let handle = self.large_resp_context.init(large_rsp, large_rsp_len);
+        let _ = self.generate_error_response(rsp, ErrorCode::LargeResponse, 0, Some(&[handle]));
+        Ok(())
+    }
+
+    fn copy_response(&self, src: &MessageBuf, rsp: &mut MessageBuf) -> CommandResult<()> {
+        let src_len = src.data_len();
+        let msg = src
+            .data(src_len)
+            .map_err(|e| (false, CommandError::Codec(e)))?;
+        self.prepare_response_buffer(rsp)?;
+        encode_u8_slice(msg, rsp).map_err(|e| (false, CommandError::Codec(e)))?;
+        rsp.push_data(src_len)
+            .map_err(|e| (false, CommandError::Codec(e)))
+    }
+
+    /// Returns true if mutual authentication with the encapsulated request flow can
+    /// be requested from the Requester.
+    pub(crate) fn mut_auth_supported(&self) -> bool {
+        let peer_flags = self.state.connection_info.peer_capabilities().flags;
+        self.local_capabilities.flags.mut_auth_cap() == 1
+            && self.local_capabilities.flags.encap_cap() == 1
+            && peer_flags.mut_auth_cap() == 1
+            && peer_flags.cert_cap() == 1
+            && !self.requester_root_hashes.is_empty()
+            && self
+                .selected_req_base_asym_algo()
+                .tpm_alg_ecdsa_ecc_nist_p384()
+                == 1
+    }
+
+    /// Returns the signing algorithm of the Requester selected in ALGORITHMS.
+    pub(crate) fn selected_req_base_asym_algo(&self) -> ReqBaseAsymAlg {
+        let peer_algorithms = self.state.connection_info.peer_algorithms();
+        let local_algorithms = &self.local_algorithms.device_algorithms;
+        ReqBaseAsymAlg(
+            local_algorithms.req_base_asym_algo.0.prioritize(
+                &peer_algorithms.req_base_asym_algo.0,
+                self.local_algorithms
+                    .algorithm_priority_table
+                    .req_base_asym_algo,
+            ),
+        )
+    }
+
     pub(crate) fn reset_transcript_via_req_code(&mut self, req_code: ReqRespCode) {
         // Any request other than GET_MEASUREMENTS resets the L1 transcript context.
         if req_code != ReqRespCode::GetMeasurements {
@@ -270,6 +594,8 @@ impl<'a> SpdmContext<'a> {
             ReqRespCode::GetMeasurements
             | ReqRespCode::KeyExchange
             | ReqRespCode::Finish
+            | ReqRespCode::PskExchange
+            | ReqRespCode::PskFinish
             | ReqRespCode::EndSession => {
                 if self.state.connection_info.state() < ConnectionState::Authenticated {
                     self.shared_transcript.reset_context(TranscriptContext::M1);
@@ -391,26 +717,14 @@ impl<'a> SpdmContext<'a> {
         &self,
         req_payload: &mut MessageBuf,
     ) -> CommandResult<AsymAlgo> {
-        let peer_algorithms = self.state.connection_info.peer_algorithms();
-        let local_algorithms = &self.local_algorithms.device_algorithms;
-        let algorithm_priority_table = &self.local_algorithms.algorithm_priority_table;
-
-        let base_asym_sel = BaseAsymAlgo(local_algorithms.base_asym_algo.0.prioritize(
-            &peer_algorithms.base_asym_algo.0,
-            algorithm_priority_table.base_asym_algo,
-        ));
+        let (base_asym_sel, pqc_asym_sel) = algorithms_rsp::selected_asym_algorithms(self);
 
-        // Ensure AsymAlgoSel has exactly one bit set and it is ECC P-384
-        if base_asym_sel.0.count_ones() != 1 || base_asym_sel.tpm_alg_ecdsa_ecc_nist_p384() != 1 {
-            return Err(self.generate_error_response(
-                req_payload,
-                ErrorCode::InvalidRequest,
-                0,
-                None,
-            ));
+        // Ensure exactly one asymmetric algorithm is selected and it is ECC P-384 or ML-DSA-87
+        match (base_asym_sel.0.count_ones(), pqc_asym_sel.0.count_ones()) {
+            (1, 0) if base_asym_sel.tpm_alg_ecdsa_ecc_nist_p384() == 1 => Ok(AsymAlgo::EccP384),
+            (0, 1) if pqc_asym_sel.ml_dsa_87() == 1 => Ok(AsymAlgo::MlDsa87),
+            _ => Err(self.generate_error_response(req_payload, ErrorCode::InvalidRequest, 0, None)),
         }
-
-        Ok(AsymAlgo::EccP384)
     }
 
     pub(crate) fn validate_negotiated_dhe_group(&self, rsp: &mut MessageBuf) -> CommandResult<()> {
@@ -453,7 +767,8 @@ impl<'a> SpdmContext<'a> {
             | ReqRespCode::GetCapabilities
             | ReqRespCode::NegotiateAlgorithms
             | ReqRespCode::Challenge
-            | ReqRespCode::KeyExchange => {
+            | ReqRespCode::KeyExchange
+            | ReqRespCode::PskExchange => {
                 Err(self.generate_error_response(req, ErrorCode::UnexpectedRequest, 0, None))
             }
 
@@ -461,6 +776,13 @@ impl<'a> SpdmContext<'a> {
             ReqRespCode::GetDigests
             | ReqRespCode::GetCertificate
             | ReqRespCode::GetMeasurements
+            | ReqRespCode::GetMeasurementExtensionLog
+            | ReqRespCode::Heartbeat
+            | ReqRespCode::KeyUpdate
+            | ReqRespCode::GetCsr
+            | ReqRespCode::SetCertificate
+            | ReqRespCode::GetKeyPairInfo
+            | ReqRespCode::SetKeyPairInfo
             | ReqRespCode::EndSession => {
                 if session_info.session_state == SessionState::Established {
                     Ok(())
@@ -469,8 +791,12 @@ impl<'a> SpdmContext<'a> {
                 }
             }
 
-            // FINISH requires handshake in progress state (Session Handshake phase)
-            ReqRespCode::Finish => {
+            // FINISH/PSK_FINISH and the encapsulated requests require handshake in progress state
+            // (Session Handshake phase)
+            ReqRespCode::Finish
+            | ReqRespCode::PskFinish
+            | ReqRespCode::GetEncapsulatedRequest
+            | ReqRespCode::DeliverEncapsulatedResponse => {
                 if session_info.session_state == SessionState::HandshakeInProgress {
                     Ok(())
                 } else {
@@ -483,3 +809,107 @@ impl<'a> SpdmContext<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::key_pair_store::ECC_P384_PUBLIC_KEY_INFO;
+    use crate::protocol::CapabilityFlags;
+    use crate::test_util::*;
+
+    // Smallest DataTransferSize, so that KEY_PAIR_INFO does not fit in the CHUNK_SEND_ACK
+    const DATA_TRANSFER_SIZE: u32 = 42;
+
+    #[test]
+    fn test_chunk_send_with_chunked_response() {
+        let mut transport = TestTransport;
+        let mut meas_value = TestMeasurementValue;
+        let store = TestKeyPairStore::new();
+        let mut large_req_buf = [0u8; 128];
+        let mut large_rsp_buf = [0u8; 128];
+        let mut bufs = [[0u8; 64]; 4];
+        let [chunk_send_0, chunk_send_1, chunk_get_0, chunk_get_1] = &mut bufs;
+
+        let mut flags = CapabilityFlags::default();
+        flags.set_chunk_cap(1);
+        flags.set_get_key_pair_info_cap(1);
+        let mut ctx = negotiated_context(
+            SpdmVersion::V13,
+            flags,
+            &mut transport,
+            &TestCertStore,
+            &mut meas_value,
+        );
+        ctx.local_capabilities.data_transfer_size = DATA_TRANSFER_SIZE;
+        ctx.set_key_pair_store(&store);
+        ctx.set_large_request_buffer(&mut large_req_buf);
+        ctx.set_large_response_buffer(&mut large_rsp_buf);
+
+        // GET_KEY_PAIR_INFO, padded so that it is sent in two chunks
+        let mut large_req = [0u8; 48];
+        large_req[..5].copy_from_slice(&[0x13, 0xFC, 0, 0, 1]);
+
+        // The first chunk carries LargeMessageSize
+        let mut msg = [0u8; 16 + 26];
+        msg[..16].copy_from_slice(&[0x13, 0x85, 0, 7, 0, 0, 0, 0, 26, 0, 0, 0, 48, 0, 0, 0]);
+        msg[16..].copy_from_slice(&large_req[..26]);
+        let mut req = request_buf(chunk_send_0, &msg);
+        block_on(ctx.handle_request(&mut req)).unwrap();
+        assert_eq!(response(&req), &[0x13, 0x05, 0, 7, 0, 0]);
+
+        let mut msg = [0u8; 12 + 22];
+        msg[..12].copy_from_slice(&[0x13, 0x85, 1, 7, 1, 0, 0, 0, 22, 0, 0, 0]);
+        msg[12..].copy_from_slice(&large_req[26..]);
+        let mut req = request_buf(chunk_send_1, &msg);
+        block_on(ctx.handle_request(&mut req)).unwrap();
+
+        // ResponseToLargeRequest is ERROR(LargeResponse) with the handle of the response
+        let ack = response(&req);
+        assert_eq!(ack.len(), 6 + 5);
+        assert_eq!(
+            &ack[..10],
+            &[0x13, 0x05, 0, 7, 1, 0, 0x13, 0x7F, 0x0F, 0x00]
+        );
+        let handle = ack[10];
+
+        // KEY_PAIR_INFO is retrieved with CHUNK_GET. The first chunk carries LargeMessageSize.
+        let large_rsp_len = 23 + ECC_P384_PUBLIC_KEY_INFO.len();
+        let mut large_rsp = [0u8; 64];
+        let mut req = request_buf(chunk_get_0, &[0x13, 0x86, 0, handle, 0, 0]);
+        block_on(ctx.handle_request(&mut req)).unwrap();
+        let chunk = response(&req);
+        assert_eq!(
+            &chunk[..12],
+            &[0x13, 0x06, 0, handle, 0, 0, 0, 0, 26, 0, 0, 0]
+        );
+        assert_eq!(&chunk[12..16], &(large_rsp_len as u32).to_le_bytes());
+        large_rsp[..26].copy_from_slice(&chunk[16..]);
+
+        let mut req = request_buf(chunk_get_1, &[0x13, 0x86, 0, handle, 1, 0]);
+        block_on(ctx.handle_request(&mut req)).unwrap();
+        let chunk = response(&req);
+        let last_chunk_len = large_rsp_len - 26;
+        assert_eq!(
+            &chunk[..12],
+            &[
+                0x13,
+                0x06,
+                1,
+                handle,
+                1,
+                0,
+                0,
+                0,
+                last_chunk_len as u8,
+                0,
+                0,
+                0
+            ]
+        );
+        large_rsp[26..large_rsp_len].copy_from_slice(&chunk[12..]);
+        assert!(!ctx.large_resp_context.in_progress());
+
+        assert_eq!(&large_rsp[..6], &[0x13, 0x7C, 0, 0, 2, 1]);
+        assert_eq!(&large_rsp[23..large_rsp_len], ECC_P384_PUBLIC_KEY_INFO);
+    }
+}
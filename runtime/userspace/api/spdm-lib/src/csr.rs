@@ -0,0 +1,351 @@
+// Licensed under the Apache-2.0 license
+
+//! PKCS#10 certificate signing requests (RFC 2986) returned by GET_CSR.
+//!
+//! The RequesterInfo of GET_CSR is a DER CertificationRequestInfo. Its subject and
+//! attributes are applied to the CSR, while its public key is replaced by the device key.
+//! These helpers let a `SpdmCertStore` build the CSR:
+//! CertificationRequest ::= SEQUENCE { certificationRequestInfo, signatureAlgorithm, signature }
+
+use core::ops::Range;
+use libapi_caliptra::crypto::asym::ECC_P384_SIGNATURE_SIZE;
+use x509_common::{
+    header_len, unsigned_integer_len, DerError, DerReader, DerWriter, DER_TAG_BIT_STRING,
+    DER_TAG_CONTEXT_0, DER_TAG_INTEGER, DER_TAG_OCTET_STRING, DER_TAG_OID, DER_TAG_SEQUENCE,
+    DER_TAG_SET, ECDSA_WITH_SHA384_ALG_ID,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum CsrError {
+    InvalidFormat,
+    BufferTooSmall,
+}
+
+pub type CsrResult<T> = Result<T, CsrError>;
+
+impl From<DerError> for CsrError {
+    fn from(e: DerError) -> Self {
+        match e {
+            DerError::InvalidEncoding => CsrError::InvalidFormat,
+            DerError::BufferTooSmall => CsrError::BufferTooSmall,
+        }
+    }
+}
+
+// id-signedData (1.2.840.113549.1.7.2)
+const ID_SIGNED_DATA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02];
+
+// DER encoding of the CertificationRequestInfo version v1(0)
+const CSR_VERSION_V1: &[u8] = &[DER_TAG_INTEGER, 0x01, 0x00];
+
+/// Subject and attributes requested in the RequesterInfo of GET_CSR
+#[derive(Debug, PartialEq)]
+pub struct RequesterInfo<'a> {
+    // DER encoding of the subject Name
+    subject: &'a [u8],
+    // DER encoding of the [0] Attributes
+    attributes: &'a [u8],
+}
+
+impl<'a> RequesterInfo<'a> {
+    /// Parses the RequesterInfo of GET_CSR.
+    ///
+    /// CertificationRequestInfo ::= SEQUENCE { version INTEGER { v1(0) }, subject Name,
+    ///     subjectPKInfo SubjectPublicKeyInfo, attributes [0] Attributes }
+    pub fn parse(requester_info: &'a [u8]) -> CsrResult<Self> {
+        let mut outer = DerReader::new(requester_info);
+        let mut info = DerReader::new(outer.expect(DER_TAG_SEQUENCE)?);
+        if !outer.is_empty() || info.expect(DER_TAG_INTEGER)? != [0] {
+            Err(CsrError::InvalidFormat)?;
+        }
+
+        let subject = info.expect_tlv(DER_TAG_SEQUENCE)?;
+        info.expect(DER_TAG_SEQUENCE)?; // subjectPKInfo
+        let attributes = info.expect_tlv(DER_TAG_CONTEXT_0)?;
+        if !info.is_empty() {
+            Err(CsrError::InvalidFormat)?;
+        }
+
+        Ok(Self {
+            subject,
+            attributes,
+        })
+    }
+}
+
+/// Returns the range of the CSR encapsulated in a CMS SignedData, such as the CSR
+/// generated by DPE.
+///
+/// ContentInfo ::= SEQUENCE { contentType, content [0] EXPLICIT SignedData }
+/// SignedData ::= SEQUENCE { version, digestAlgorithms, encapContentInfo, ... }
+/// EncapsulatedContentInfo ::= SEQUENCE { eContentType, eContent [0] EXPLICIT OCTET STRING }
+pub fn signed_data_content(cms: &[u8]) -> CsrResult<Range<usize>> {
+    let mut outer = DerReader::new(cms);
+    let mut content_info = DerReader::new(outer.expect(DER_TAG_SEQUENCE)?);
+    if content_info.expect(DER_TAG_OID)? != ID_SIGNED_DATA {
+        Err(CsrError::InvalidFormat)?;
+    }
+
+    let mut content = DerReader::new(content_info.expect(DER_TAG_CONTEXT_0)?);
+    let mut signed_data = DerReader::new(content.expect(DER_TAG_SEQUENCE)?);
+    signed_data.expect(DER_TAG_INTEGER)?; // version
+    signed_data.expect(DER_TAG_SET)?; // digestAlgorithms
+
+    let mut encap_content_info = DerReader::new(signed_data.expect(DER_TAG_SEQUENCE)?);
+    encap_content_info.expect(DER_TAG_OID)?; // eContentType
+    let mut e_content = DerReader::new(encap_content_info.expect(DER_TAG_CONTEXT_0)?);
+    let csr = e_content.expect(DER_TAG_OCTET_STRING)?;
+
+    // The content must be a single CertificationRequest
+    let mut csr_reader = DerReader::new(csr);
+    csr_reader.expect(DER_TAG_SEQUENCE)?;
+    if !csr_reader.is_empty() {
+        Err(CsrError::InvalidFormat)?;
+    }
+
+    let start = csr.as_ptr() as usize - cms.as_ptr() as usize;
+    Ok(start..start + csr.len())
+}
+
+/// Returns the DER encoding of the SubjectPublicKeyInfo of a CSR.
+pub fn csr_subject_public_key_info(csr: &[u8]) -> CsrResult<&[u8]> {
+    let mut outer = DerReader::new(csr);
+    let mut csr = DerReader::new(outer.expect(DER_TAG_SEQUENCE)?);
+    let mut info = DerReader::new(csr.expect(DER_TAG_SEQUENCE)?);
+    info.expect(DER_TAG_INTEGER)?; // version
+    info.expect(DER_TAG_SEQUENCE)?; // subject
+    Ok(info.expect_tlv(DER_TAG_SEQUENCE)?)
+}
+
+/// Encodes the CertificationRequestInfo of a CSR for the device key with the subject and
+/// attributes requested by the Requester.
+///
+/// # Arguments
+/// * `requester_info` - The subject and attributes requested by the Requester.
+/// * `spki` - DER encoding of the SubjectPublicKeyInfo of the device key.
+/// * `out` - Output buffer for the CertificationRequestInfo.
+///
+/// # Returns
+/// * `usize` - The size of the CertificationRequestInfo, to be signed with the device key.
+pub fn encode_certification_request_info(
+    requester_info: &RequesterInfo,
+    spki: &[u8],
+    out: &mut [u8],
+) -> CsrResult<usize> {
+    let info_len = CSR_VERSION_V1.len()
+        + requester_info.subject.len()
+        + spki.len()
+        + requester_info.attributes.len();
+
+    let mut writer = DerWriter::new(out);
+    writer.write_header(DER_TAG_SEQUENCE, info_len)?;
+    writer.write_bytes(CSR_VERSION_V1)?;
+    writer.write_bytes(requester_info.subject)?;
+    writer.write_bytes(spki)?;
+    writer.write_bytes(requester_info.attributes)?;
+    Ok(writer.len())
+}
+
+/// Completes in place a CSR signed with ecdsa-with-SHA384. The CertificationRequestInfo
+/// at the start of the buffer is wrapped into the CertificationRequest with its signature.
+///
+/// # Arguments
+/// * `buf` - Buffer holding the CertificationRequestInfo. Receives the CSR.
+/// * `info_len` - The size of the CertificationRequestInfo.
+/// * `signature` - The signature of the CertificationRequestInfo in raw r || s format.
+///
+/// # Returns
+/// * `usize` - The size of the CSR.
+pub fn encode_ecdsa_p384_certification_request(
+    buf: &mut [u8],
+    info_len: usize,
+    signature: &[u8; ECC_P384_SIGNATURE_SIZE],
+) -> CsrResult<usize> {
+    // Ecdsa-Sig-Value ::= SEQUENCE { r INTEGER, s INTEGER }
+    let (r, s) = signature.split_at(ECC_P384_SIGNATURE_SIZE / 2);
+    let sig_value_len = unsigned_integer_len(r) + unsigned_integer_len(s);
+    let bit_string_len = 1 + header_len(sig_value_len) + sig_value_len;
+
+    let alg_id_len = header_len(ECDSA_WITH_SHA384_ALG_ID.len()) + ECDSA_WITH_SHA384_ALG_ID.len();
+    let csr_len = info_len + alg_id_len + header_len(bit_string_len) + bit_string_len;
+    let csr_hdr_len = header_len(csr_len);
+    if info_len > buf.len() || csr_hdr_len + csr_len > buf.len() {
+        Err(CsrError::BufferTooSmall)?;
+    }
+
+    buf.copy_within(..info_len, csr_hdr_len);
+    let (hdr, rest) = buf.split_at_mut(csr_hdr_len);
+    DerWriter::new(hdr).write_header(DER_TAG_SEQUENCE, csr_len)?;
+
+    let mut writer = DerWriter::new(&mut rest[info_len..]);
+    writer.write_header(DER_TAG_SEQUENCE, ECDSA_WITH_SHA384_ALG_ID.len())?;
+    writer.write_bytes(ECDSA_WITH_SHA384_ALG_ID)?;
+    writer.write_header(DER_TAG_BIT_STRING, bit_string_len)?;
+    writer.write_bytes(&[0])?; // No unused bits
+    writer.write_header(DER_TAG_SEQUENCE, sig_value_len)?;
+    writer.write_unsigned_integer(r)?;
+    writer.write_unsigned_integer(s)?;
+
+    Ok(csr_hdr_len + csr_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrayvec::ArrayVec;
+
+    // Name with a single CN=Test
+    const SUBJECT: &[u8] = &[
+        0x30, 0x0F, 0x31, 0x0D, 0x30, 0x0B, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0C, 0x04, 0x54, 0x65,
+        0x73, 0x74,
+    ];
+    // SubjectPublicKeyInfo with an empty algorithm and a 2-byte key
+    const SPKI: &[u8] = &[0x30, 0x06, 0x30, 0x00, 0x03, 0x02, 0x00, 0xAB];
+    // Attributes with an empty challengePassword
+    const ATTRIBUTES: &[u8] = &[
+        0xA0, 0x0F, 0x30, 0x0D, 0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x07,
+        0x31, 0x00,
+    ];
+
+    fn tlv(tag: u8, value: &[u8]) -> ArrayVec<u8, 256> {
+        let mut buf = [0u8; 256];
+        let mut writer = DerWriter::new(&mut buf);
+        writer.write_header(tag, value.len()).unwrap();
+        writer.write_bytes(value).unwrap();
+        let len = writer.len();
+        buf[..len].iter().copied().collect()
+    }
+
+    fn requester_info(version: u8, trailing: &[u8]) -> ArrayVec<u8, 256> {
+        let mut body = ArrayVec::<u8, 256>::new();
+        body.try_extend_from_slice(&[DER_TAG_INTEGER, 0x01, version])
+            .unwrap();
+        body.try_extend_from_slice(SUBJECT).unwrap();
+        body.try_extend_from_slice(SPKI).unwrap();
+        body.try_extend_from_slice(ATTRIBUTES).unwrap();
+        body.try_extend_from_slice(trailing).unwrap();
+        tlv(DER_TAG_SEQUENCE, &body)
+    }
+
+    #[test]
+    fn test_requester_info_parse() {
+        let info = requester_info(0, &[]);
+        let parsed = RequesterInfo::parse(&info).unwrap();
+        assert_eq!(parsed.subject, SUBJECT);
+        assert_eq!(parsed.attributes, ATTRIBUTES);
+
+        // Only v1 is defined
+        let info = requester_info(1, &[]);
+        assert_eq!(RequesterInfo::parse(&info), Err(CsrError::InvalidFormat));
+
+        // Nothing may follow the attributes
+        let info = requester_info(0, &[0x05, 0x00]);
+        assert_eq!(RequesterInfo::parse(&info), Err(CsrError::InvalidFormat));
+
+        // Truncated
+        let info = requester_info(0, &[]);
+        assert_eq!(
+            RequesterInfo::parse(&info[..info.len() - 1]),
+            Err(CsrError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn test_signed_data_content() {
+        let csr = tlv(DER_TAG_SEQUENCE, &[DER_TAG_INTEGER, 0x01, 0x00]);
+
+        let mut encap_content_info = ArrayVec::<u8, 256>::new();
+        encap_content_info
+            .try_extend_from_slice(&tlv(DER_TAG_OID, &[0x2A, 0x03]))
+            .unwrap();
+        encap_content_info
+            .try_extend_from_slice(&tlv(DER_TAG_CONTEXT_0, &tlv(DER_TAG_OCTET_STRING, &csr)))
+            .unwrap();
+
+        let mut signed_data = ArrayVec::<u8, 256>::new();
+        signed_data
+            .try_extend_from_slice(&[DER_TAG_INTEGER, 0x01, 0x03])
+            .unwrap();
+        signed_data
+            .try_extend_from_slice(&[DER_TAG_SET, 0x00])
+            .unwrap();
+        signed_data
+            .try_extend_from_slice(&tlv(DER_TAG_SEQUENCE, &encap_content_info))
+            .unwrap();
+        signed_data
+            .try_extend_from_slice(&[DER_TAG_SET, 0x00])
+            .unwrap();
+
+        let mut content_info = ArrayVec::<u8, 256>::new();
+        content_info
+            .try_extend_from_slice(&tlv(DER_TAG_OID, ID_SIGNED_DATA))
+            .unwrap();
+        content_info
+            .try_extend_from_slice(&tlv(
+                DER_TAG_CONTEXT_0,
+                &tlv(DER_TAG_SEQUENCE, &signed_data),
+            ))
+            .unwrap();
+        let cms = tlv(DER_TAG_SEQUENCE, &content_info);
+
+        let range = signed_data_content(&cms).unwrap();
+        assert_eq!(&cms[range], csr.as_slice());
+
+        // A CSR is not a CMS SignedData
+        assert_eq!(signed_data_content(&csr), Err(CsrError::InvalidFormat));
+    }
+
+    #[test]
+    fn test_encode_ecdsa_p384_certification_request() {
+        let info = requester_info(0, &[]);
+        let requester_info = RequesterInfo::parse(&info).unwrap();
+
+        let mut buf = [0u8; 512];
+        let info_len = encode_certification_request_info(&requester_info, SPKI, &mut buf).unwrap();
+        let mut expected_info = [0u8; 512];
+        expected_info[..info_len].copy_from_slice(&buf[..info_len]);
+
+        // r with the high bit set, s with leading zeros
+        let mut signature = [0u8; ECC_P384_SIGNATURE_SIZE];
+        signature[..48].fill(0x80);
+        signature[50..].fill(0x11);
+        let csr_len =
+            encode_ecdsa_p384_certification_request(&mut buf, info_len, &signature).unwrap();
+
+        let mut outer = DerReader::new(&buf[..csr_len]);
+        let mut csr = DerReader::new(outer.expect(DER_TAG_SEQUENCE).unwrap());
+        assert!(outer.is_empty());
+        assert_eq!(
+            csr.expect_tlv(DER_TAG_SEQUENCE).unwrap(),
+            &expected_info[..info_len]
+        );
+        assert_eq!(
+            csr.expect(DER_TAG_SEQUENCE).unwrap(),
+            ECDSA_WITH_SHA384_ALG_ID
+        );
+        let sig_bits = csr.expect(DER_TAG_BIT_STRING).unwrap();
+        assert!(csr.is_empty());
+        assert_eq!(sig_bits[0], 0);
+
+        let mut sig_outer = DerReader::new(&sig_bits[1..]);
+        let mut sig_value = DerReader::new(sig_outer.expect(DER_TAG_SEQUENCE).unwrap());
+        let r = sig_value.expect(DER_TAG_INTEGER).unwrap();
+        assert_eq!(r.len(), 49);
+        assert_eq!(r[0], 0);
+        assert_eq!(sig_value.expect(DER_TAG_INTEGER).unwrap(), &[0x11; 46]);
+
+        assert_eq!(csr_subject_public_key_info(&buf[..csr_len]).unwrap(), SPKI);
+
+        // The buffer cannot hold the signature
+        let mut small = [0u8; 512];
+        small[..info_len].copy_from_slice(&expected_info[..info_len]);
+        assert_eq!(
+            encode_ecdsa_p384_certification_request(
+                &mut small[..info_len + 8],
+                info_len,
+                &signature
+            ),
+            Err(CsrError::BufferTooSmall)
+        );
+    }
+}
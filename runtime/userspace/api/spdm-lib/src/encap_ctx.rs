@@ -0,0 +1,188 @@
+// Licensed under the Apache-2.0 license
+
+use crate::peer_cert::MAX_PEER_CERT_CHAIN_SIZE;
+use crate::session::RequesterAuth;
+
+#[derive(Debug, PartialEq)]
+pub enum EncapError {
+    /// The peer certificate chain does not fit in the buffer
+    CertChainTooLarge,
+}
+
+pub type EncapResult<T> = Result<T, EncapError>;
+
+/// Encapsulated request currently outstanding toward the Requester
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EncapRequest {
+    GetDigests,
+    GetCertificate { slot_id: u8, offset: u16 },
+    Challenge { slot_id: u8 },
+}
+
+/// Handshake in which the encapsulated request flow authenticates the Requester
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EncapFlow {
+    /// Mutual authentication requested in KEY_EXCHANGE_RSP. The Requester proves
+    /// possession of its key with the FINISH signature.
+    Session(u32),
+    /// Basic mutual authentication requested in CHALLENGE_AUTH (SPDM 1.1). The Requester
+    /// proves possession of its key by answering an encapsulated CHALLENGE.
+    Basic,
+}
+
+/// Tracks the encapsulated request flow used to retrieve the Requester's
+/// certificate chain for mutual authentication.
+pub(crate) struct EncapContext {
+    flow: Option<EncapFlow>,
+    request_id: u8,
+    pending: Option<EncapRequest>,
+    req_slot_id: u8,
+    cert_chain: [u8; MAX_PEER_CERT_CHAIN_SIZE],
+    cert_chain_len: usize,
+    // Requester certificate awaiting the encapsulated CHALLENGE_AUTH in basic mutual authentication
+    requester_auth: Option<RequesterAuth>,
+}
+
+impl Default for EncapContext {
+    fn default() -> Self {
+        Self {
+            flow: None,
+            request_id: 0,
+            pending: None,
+            req_slot_id: 0,
+            cert_chain: [0; MAX_PEER_CERT_CHAIN_SIZE],
+            cert_chain_len: 0,
+            requester_auth: None,
+        }
+    }
+}
+
+impl EncapContext {
+    /// Starts a new encapsulated request flow.
+    /// The first request sent to the Requester is always GET_DIGESTS.
+    pub(crate) fn init(&mut self, flow: EncapFlow) {
+        self.reset();
+        self.flow = Some(flow);
+        self.pending = Some(EncapRequest::GetDigests);
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.flow = None;
+        self.pending = None;
+        self.req_slot_id = 0;
+        self.cert_chain_len = 0;
+        self.requester_auth = None;
+    }
+
+    /// Returns the outstanding request of the flow, if any
+    pub(crate) fn pending_request(&self, flow: EncapFlow) -> Option<EncapRequest> {
+        if self.flow == Some(flow) {
+            self.pending
+        } else {
+            None
+        }
+    }
+
+    /// Allocates the RequestID for the next encapsulated request. RequestID 0 is
+    /// reserved to signal that no further requests follow.
+    pub(crate) fn next_request_id(&mut self) -> u8 {
+        self.request_id = self.request_id.wrapping_add(1).max(1);
+        self.request_id
+    }
+
+    pub(crate) fn request_id(&self) -> u8 {
+        self.request_id
+    }
+
+    pub(crate) fn set_pending(&mut self, request: Option<EncapRequest>) {
+        self.pending = request;
+    }
+
+    pub(crate) fn req_slot_id(&self) -> u8 {
+        self.req_slot_id
+    }
+
+    pub(crate) fn set_req_slot_id(&mut self, slot_id: u8) {
+        self.req_slot_id = slot_id;
+    }
+
+    /// Appends a portion of the Requester certificate chain
+    pub(crate) fn append_cert_chain(&mut self, portion: &[u8]) -> EncapResult<()> {
+        let end = self.cert_chain_len + portion.len();
+        if end > MAX_PEER_CERT_CHAIN_SIZE {
+            return Err(EncapError::CertChainTooLarge);
+        }
+        self.cert_chain[self.cert_chain_len..end].copy_from_slice(portion);
+        self.cert_chain_len = end;
+        Ok(())
+    }
+
+    pub(crate) fn cert_chain_len(&self) -> usize {
+        self.cert_chain_len
+    }
+
+    pub(crate) fn cert_chain(&self) -> &[u8] {
+        &self.cert_chain[..self.cert_chain_len]
+    }
+
+    pub(crate) fn requester_auth(&self) -> Option<RequesterAuth> {
+        self.requester_auth
+    }
+
+    pub(crate) fn set_requester_auth(&mut self, requester_auth: RequesterAuth) {
+        self.requester_auth = Some(requester_auth);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_request_per_flow() {
+        let mut encap_ctx = EncapContext::default();
+        encap_ctx.init(EncapFlow::Session(0xFFFE_0001));
+        assert_eq!(
+            encap_ctx.pending_request(EncapFlow::Session(0xFFFE_0001)),
+            Some(EncapRequest::GetDigests)
+        );
+        assert_eq!(
+            encap_ctx.pending_request(EncapFlow::Session(0xFFFD_0002)),
+            None
+        );
+        assert_eq!(encap_ctx.pending_request(EncapFlow::Basic), None);
+
+        // A new flow drops the state of the previous one
+        encap_ctx.append_cert_chain(&[0xA5; 16]).unwrap();
+        encap_ctx.init(EncapFlow::Basic);
+        assert_eq!(encap_ctx.cert_chain_len(), 0);
+        assert_eq!(
+            encap_ctx.pending_request(EncapFlow::Basic),
+            Some(EncapRequest::GetDigests)
+        );
+        assert_eq!(
+            encap_ctx.pending_request(EncapFlow::Session(0xFFFE_0001)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_request_id_skips_zero() {
+        let mut encap_ctx = EncapContext::default();
+        encap_ctx.request_id = u8::MAX;
+        assert_eq!(encap_ctx.next_request_id(), 1);
+        assert_eq!(encap_ctx.request_id(), 1);
+    }
+
+    #[test]
+    fn test_cert_chain_too_large() {
+        let mut encap_ctx = EncapContext::default();
+        encap_ctx
+            .append_cert_chain(&[0; MAX_PEER_CERT_CHAIN_SIZE])
+            .unwrap();
+        assert_eq!(
+            encap_ctx.append_cert_chain(&[0]),
+            Err(EncapError::CertChainTooLarge)
+        );
+    }
+}
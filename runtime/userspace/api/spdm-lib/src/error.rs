@@ -4,9 +4,12 @@ use crate::cert_store::CertStoreError;
 use crate::chunk_ctx::ChunkError;
 use crate::codec::CodecError;
 use crate::commands::error_rsp::ErrorCode;
+use crate::measurement_log::MeasurementLogError;
 use crate::measurements::MeasurementsError;
+use crate::peer_cert::PeerCertError;
 use crate::protocol::opaque_data::OpaqueDataError;
 use crate::protocol::SignCtxError;
+use crate::psk_store::PskStoreError;
 use crate::session::SessionError;
 use crate::transcript::TranscriptError;
 use crate::transport::common::TransportError;
@@ -47,9 +50,12 @@ pub enum CommandError {
     MissingVdmHandler,
     Chunk(ChunkError),
     CertStore(CertStoreError),
+    PskStore(PskStoreError),
+    PeerCert(PeerCertError),
     CaliptraApi(CaliptraApiError),
     Transcript(TranscriptError),
     Measurement(MeasurementsError),
+    MeasurementLog(MeasurementLogError),
     Session(SessionError),
     OpaqueData(OpaqueDataError),
     Vdm(VdmError),
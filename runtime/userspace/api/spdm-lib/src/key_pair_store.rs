@@ -0,0 +1,158 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+
+use crate::protocol::{KeyUsageMask, PqcAsymAlgo};
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use bitfield::bitfield;
+use libapi_caliptra::error::CaliptraApiError;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+// Maximum size of the PublicKeyInfo reported in KEY_PAIR_INFO
+pub const MAX_PUBLIC_KEY_INFO_SIZE: usize = 64;
+
+// DER-encoded SubjectPublicKeyInfo.algorithm of an ECC P-384 key
+// (id-ecPublicKey with secp384r1)
+pub const ECC_P384_PUBLIC_KEY_INFO: &[u8] = &[
+    0x30, 0x10, 0x06, 0x07, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01, 0x06, 0x05, 0x2B, 0x81, 0x04,
+    0x00, 0x22,
+];
+
+// DER-encoded SubjectPublicKeyInfo.algorithm of an ML-DSA-87 key (id-ml-dsa-87)
+pub const MLDSA87_PUBLIC_KEY_INFO: &[u8] = &[
+    0x30, 0x0B, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x03, 0x13,
+];
+
+#[derive(Debug, PartialEq)]
+pub enum KeyPairStoreError {
+    InvalidKeyPairId,
+    InvalidParam,
+    BufferTooSmall,
+    UnsupportedOperation,
+    OperationFailed,
+    CaliptraApi(CaliptraApiError),
+}
+pub type KeyPairStoreResult<T> = Result<T, KeyPairStoreError>;
+
+// SPDM KEY_PAIR_INFO Capabilities fields
+bitfield! {
+#[derive(FromBytes, IntoBytes, Immutable, Default, Clone, Copy)]
+#[repr(C)]
+pub struct KeyPairCapabilities(u16);
+impl Debug;
+u16;
+pub gen_key_cap, set_gen_key_cap: 0,0;
+pub erasable_cap, set_erasable_cap: 1,1;
+pub cert_assoc_cap, set_cert_assoc_cap: 2,2;
+pub key_usage_cap, set_key_usage_cap: 3,3;
+pub asym_algo_cap, set_asym_algo_cap: 4,4;
+pub shareable_cap, set_shareable_cap: 5,5;
+reserved, _: 15,6;
+}
+
+// SPDM KEY_PAIR_INFO AsymAlgoCapabilities and CurrentAsymAlgo fields
+bitfield! {
+#[derive(FromBytes, IntoBytes, Immutable, Default, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct KeyPairAsymAlgo(u32);
+impl Debug;
+u8;
+pub rsa2048, set_rsa2048: 0,0;
+pub rsa3072, set_rsa3072: 1,1;
+pub rsa4096, set_rsa4096: 2,2;
+pub ecc256, set_ecc256: 3,3;
+pub ecc384, set_ecc384: 4,4;
+pub ecc521, set_ecc521: 5,5;
+pub sm2, set_sm2: 6,6;
+pub ed25519, set_ed25519: 7,7;
+pub ed448, set_ed448: 8,8;
+reserved, _: 31,9;
+}
+
+/// Operation requested with SET_KEY_PAIR_INFO
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetKeyPairInfoOperation {
+    Change = 0,
+    Erase = 1,
+    Generate = 2,
+}
+
+impl TryFrom<u8> for SetKeyPairInfoOperation {
+    type Error = KeyPairStoreError;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SetKeyPairInfoOperation::Change),
+            1 => Ok(SetKeyPairInfoOperation::Erase),
+            2 => Ok(SetKeyPairInfoOperation::Generate),
+            _ => Err(KeyPairStoreError::InvalidParam),
+        }
+    }
+}
+
+/// Properties of an asymmetric key pair as reported in KEY_PAIR_INFO.
+/// A key pair has a single algorithm, either traditional or PQC. The PQC algorithms
+/// (ML-DSA) are only reported to a Requester that negotiated SPDM 1.4 or later.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeyPairInfo {
+    pub capabilities: KeyPairCapabilities,
+    pub key_usage_capabilities: KeyUsageMask,
+    pub current_key_usage: KeyUsageMask,
+    pub asym_algo_capabilities: KeyPairAsymAlgo,
+    pub current_asym_algo: KeyPairAsymAlgo,
+    pub pqc_asym_algo_capabilities: PqcAsymAlgo,
+    pub current_pqc_asym_algo: PqcAsymAlgo,
+    pub assoc_cert_slot_mask: u8,
+}
+
+/// Key pair properties requested with SET_KEY_PAIR_INFO
+#[derive(Debug, Clone, Copy)]
+pub struct KeyPairUpdate {
+    pub key_usage: KeyUsageMask,
+    pub asym_algo: KeyPairAsymAlgo,
+    pub pqc_asym_algo: PqcAsymAlgo,
+    pub assoc_cert_slot_mask: u8,
+}
+
+#[async_trait]
+pub trait SpdmKeyPairStore {
+    /// Get the number of key pairs of the responder.
+    /// The key pairs are identified by consecutive KeyPairIDs from 1 to total_key_pairs.
+    ///
+    /// # Returns
+    /// * `u8` - The number of key pairs.
+    fn total_key_pairs(&self) -> u8;
+
+    /// Get the properties of the key pair.
+    ///
+    /// # Arguments
+    /// * `key_pair_id` - The KeyPairID of the key pair.
+    /// * `public_key_info` - The output buffer to store the DER-encoded SubjectPublicKeyInfo.algorithm of the key pair.
+    ///
+    /// # Returns
+    /// * `(KeyPairInfo, usize)` - The key pair properties and the length of the PublicKeyInfo, or error.
+    async fn key_pair_info<'a>(
+        &self,
+        key_pair_id: u8,
+        public_key_info: &'a mut [u8],
+    ) -> KeyPairStoreResult<(KeyPairInfo, usize)>;
+
+    /// Apply a SET_KEY_PAIR_INFO operation to the key pair.
+    /// The request has already been checked against the capabilities reported by `key_pair_info`.
+    ///
+    /// # Arguments
+    /// * `key_pair_id` - The KeyPairID of the key pair.
+    /// * `operation` - The operation to perform.
+    /// * `update` - The desired key usage, asymmetric algorithm and certificate slot association.
+    ///
+    /// # Returns
+    /// * `()` - Ok if successful, error otherwise.
+    async fn set_key_pair_info(
+        &self,
+        _key_pair_id: u8,
+        _operation: SetKeyPairInfoOperation,
+        _update: KeyPairUpdate,
+    ) -> KeyPairStoreResult<()> {
+        Err(KeyPairStoreError::UnsupportedOperation)
+    }
+}
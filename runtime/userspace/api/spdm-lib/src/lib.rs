@@ -24,15 +24,33 @@ pub mod transport;
 // Device certificate management
 pub mod cert_store;
 
+// Peer certificate chain validation
+pub mod peer_cert;
+
+// PKCS#10 certificate signing requests
+pub mod csr;
+
+// Pre-shared key management
+pub mod psk_store;
+
+// Asymmetric key pair management
+pub mod key_pair_store;
+
 // Transcript management
 pub mod transcript;
 
 // Spdm measurements management
 pub mod measurements;
 
+// Measurement extension log management
+pub mod measurement_log;
+
 // Chunking context for large messages
 pub mod chunk_ctx;
 
+// Encapsulated request context for mutual authentication
+pub mod encap_ctx;
+
 // Secure session management
 pub mod session;
 
@@ -41,3 +59,6 @@ pub mod vdm_handler;
 
 // Opaque Element
 pub mod opaque_element;
+
+#[cfg(test)]
+mod test_util;
@@ -0,0 +1,171 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+
+use crate::protocol::{
+    DmtfMelEntryHeader, DmtfMelHeader, MeasurementValueType, DMTF_MEL_ENTRY_HEADER_SIZE,
+    DMTF_MEL_HEADER_SIZE,
+};
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use libapi_caliptra::error::CaliptraApiError;
+use zerocopy::IntoBytes;
+
+#[derive(Debug, PartialEq)]
+pub enum MeasurementLogError {
+    InvalidOffset,
+    BufferTooSmall,
+    CaliptraApi(CaliptraApiError),
+}
+pub type MeasurementLogResult<T> = Result<T, MeasurementLogError>;
+
+#[async_trait]
+pub trait SpdmMeasurementLog {
+    /// Take a snapshot of the measurement extension log (MEL).
+    /// Called when a requester starts retrieving the MEL from offset 0. `log_size` and
+    /// `read_log` report the snapshot until the next one is taken, so that the MEL does
+    /// not change between the requests of a retrieval.
+    ///
+    /// # Returns
+    /// * `()` - Ok if successful, error otherwise.
+    async fn snapshot_log(&self) -> MeasurementLogResult<()>;
+
+    /// Get the size of the measurement extension log (MEL) snapshot.
+    ///
+    /// # Returns
+    /// * `usize` - The size of the MEL in bytes, or error.
+    async fn log_size(&self) -> MeasurementLogResult<usize>;
+
+    /// Read a portion of the measurement extension log snapshot.
+    /// The MEL must be in the DMTF format, which can be built with `DmtfMelBuilder`.
+    ///
+    /// # Arguments
+    /// * `offset` - The offset in bytes from the start of the MEL.
+    /// * `log_portion` - The output buffer to store the portion of the MEL.
+    ///
+    /// # Returns
+    /// * `usize` - The number of bytes read, or error.
+    async fn read_log<'a>(
+        &self,
+        offset: usize,
+        log_portion: &'a mut [u8],
+    ) -> MeasurementLogResult<usize>;
+}
+
+/// Builds a measurement extension log in the DMTF format.
+/// Entries are numbered in the order they are added.
+pub struct DmtfMelBuilder<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+    number_of_entries: u32,
+}
+
+impl<'a> DmtfMelBuilder<'a> {
+    pub fn new(buf: &'a mut [u8]) -> MeasurementLogResult<Self> {
+        if buf.len() < DMTF_MEL_HEADER_SIZE {
+            return Err(MeasurementLogError::BufferTooSmall);
+        }
+        Ok(Self {
+            buf,
+            len: DMTF_MEL_HEADER_SIZE,
+            number_of_entries: 0,
+        })
+    }
+
+    /// Appends the extension of a measurement to the log.
+    ///
+    /// # Arguments
+    /// * `meas_index` - The index of the measurement block that the entry extends.
+    /// * `value_type` - The DMTFSpecMeasurementValueType of the extension.
+    /// * `is_dgst` - True if the value is a digest, false if it is a raw bit stream.
+    /// * `value` - The value the measurement is extended with.
+    pub fn add_entry(
+        &mut self,
+        meas_index: u8,
+        value_type: MeasurementValueType,
+        is_dgst: bool,
+        value: &[u8],
+    ) -> MeasurementLogResult<()> {
+        let value_size =
+            u16::try_from(value.len()).map_err(|_| MeasurementLogError::BufferTooSmall)?;
+        let entry_len = DMTF_MEL_ENTRY_HEADER_SIZE + value.len();
+        if self.buf.len() - self.len < entry_len {
+            return Err(MeasurementLogError::BufferTooSmall);
+        }
+
+        let entry_hdr = DmtfMelEntryHeader::new(
+            self.number_of_entries,
+            meas_index,
+            value_size,
+            is_dgst,
+            value_type,
+        );
+        let value_offset = self.len + DMTF_MEL_ENTRY_HEADER_SIZE;
+        self.buf[self.len..value_offset].copy_from_slice(entry_hdr.as_bytes());
+        self.buf[value_offset..value_offset + value.len()].copy_from_slice(value);
+
+        self.len += entry_len;
+        self.number_of_entries += 1;
+        Ok(())
+    }
+
+    /// Completes the log header.
+    ///
+    /// # Returns
+    /// The size of the measurement extension log.
+    pub fn finish(self) -> usize {
+        let mel_entries_len = (self.len - DMTF_MEL_HEADER_SIZE) as u32;
+        let header = DmtfMelHeader::new(self.number_of_entries, mel_entries_len);
+        self.buf[..DMTF_MEL_HEADER_SIZE].copy_from_slice(header.as_bytes());
+        self.len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dmtf_mel_builder() {
+        let mut buf = [0u8; 64];
+        let mut builder = DmtfMelBuilder::new(&mut buf).unwrap();
+        builder
+            .add_entry(
+                0xFD,
+                MeasurementValueType::MutableFirmware,
+                true,
+                &[0xAA; 4],
+            )
+            .unwrap();
+        builder
+            .add_entry(
+                0xFD,
+                MeasurementValueType::HashExtendedMeasurement,
+                false,
+                &[0xBB; 2],
+            )
+            .unwrap();
+        // The third entry does not fit in the buffer
+        assert_eq!(
+            builder.add_entry(1, MeasurementValueType::MutableFirmware, true, &[0; 48]),
+            Err(MeasurementLogError::BufferTooSmall)
+        );
+        let len = builder.finish();
+
+        assert_eq!(len, 12 + 2 * 15 + 4 + 2);
+        // NumberOfEntries and MeasurementExtensionLogEntriesLength
+        assert_eq!(&buf[0..4], &2u32.to_le_bytes());
+        assert_eq!(&buf[4..8], &(len as u32 - 12).to_le_bytes());
+
+        // First entry: MEIndex 0, MeasurementIndex 0xFD, digest of type 1
+        assert_eq!(&buf[12..16], &0u32.to_le_bytes());
+        assert_eq!(&buf[16..20], &0xFDu32.to_le_bytes());
+        assert_eq!(&buf[24..27], &[0x01, 0x04, 0x00]);
+        assert_eq!(&buf[27..31], &[0xAA; 4]);
+
+        // Second entry: MEIndex 1, raw bit stream of type 8
+        assert_eq!(&buf[31..35], &1u32.to_le_bytes());
+        assert_eq!(&buf[43..46], &[0x88, 0x02, 0x00]);
+        assert_eq!(&buf[46..48], &[0xBB; 2]);
+    }
+}
@@ -0,0 +1,432 @@
+// Licensed under the Apache-2.0 license
+
+//! Validation of certificate chains received from the peer: the Requester chain
+//! retrieved during mutual authentication, and the chains installed with SET_CERTIFICATE.
+//!
+//! The chain is expected in the SPDM certificate chain format:
+//! Length (2 bytes) | Reserved (2 bytes) | RootHash (H bytes) | Certificates (DER)
+//! Certificates may carry ECC P-384 keys, signed with ecdsa-with-SHA384 or
+//! ecdsa-with-SHA512, or ML-DSA-87 keys. The leaf certificate must carry an ECC P-384 key,
+//! the only algorithm of Requester keys and of device keys certified with SET_CERTIFICATE.
+
+use libapi_caliptra::crypto::asym::ecdsa::Ecdsa;
+use libapi_caliptra::crypto::asym::mldsa::Mldsa;
+use libapi_caliptra::crypto::asym::{
+    AsymAlgo, ECC_P384_PARAM_X_SIZE, ECC_P384_PARAM_Y_SIZE, ECC_P384_SIGNATURE_SIZE,
+    MLDSA87_PUBLIC_KEY_SIZE, MLDSA87_SIGNATURE_SIZE,
+};
+use libapi_caliptra::crypto::hash::{HashAlgoType, HashContext, SHA384_HASH_SIZE};
+use libapi_caliptra::error::CaliptraApiError;
+use x509_common::{
+    parse_ecc_p384_public_key, parse_ecdsa_signature, DerError, DerReader, X509Cert,
+    DER_TAG_SEQUENCE, ECDSA_WITH_SHA384_ALG_ID, ECDSA_WITH_SHA512_ALG_ID,
+    EC_P384_PUBLIC_KEY_ALG_ID, ML_DSA_87_ALG_ID,
+};
+
+// Maximum size of the peer certificate chain buffered during mutual authentication.
+// The large buffer holds chains with ML-DSA-87 CA certificates.
+#[cfg(feature = "large-buffer")]
+pub const MAX_PEER_CERT_CHAIN_SIZE: usize = 16384;
+
+#[cfg(not(feature = "large-buffer"))]
+pub const MAX_PEER_CERT_CHAIN_SIZE: usize = 2048;
+
+const SPDM_CERT_CHAIN_HDR_SIZE: usize = 4;
+
+#[derive(Debug, PartialEq)]
+pub enum PeerCertError {
+    InvalidCertChain,
+    InvalidCertificate,
+    UntrustedRoot,
+    UnsupportedAlgorithm,
+    InvalidSignature,
+    CaliptraApi(CaliptraApiError),
+}
+
+pub type PeerCertResult<T> = Result<T, PeerCertError>;
+
+impl From<DerError> for PeerCertError {
+    fn from(_: DerError) -> Self {
+        PeerCertError::InvalidCertificate
+    }
+}
+
+/// ECC P-384 public key of the peer's leaf certificate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PeerPublicKey {
+    pub x: [u8; ECC_P384_PARAM_X_SIZE],
+    pub y: [u8; ECC_P384_PARAM_Y_SIZE],
+}
+
+impl PeerPublicKey {
+    /// Verifies an ECDSA P-384 signature over the given SHA-384 hash with Caliptra.
+    ///
+    /// # Arguments
+    /// * `hash` - The message hash that was signed.
+    /// * `signature` - The signature in raw r || s format.
+    pub async fn verify(
+        &self,
+        hash: &[u8; SHA384_HASH_SIZE],
+        signature: &[u8; ECC_P384_SIGNATURE_SIZE],
+    ) -> PeerCertResult<()> {
+        Ecdsa::ecdsa_verify(self.x, self.y, signature, *hash)
+            .await
+            .map_err(|_| PeerCertError::InvalidSignature)
+    }
+}
+
+/// Validates a peer certificate chain in SPDM format and returns the public key of the leaf certificate.
+///
+/// The RootHash of the chain must match one of the provisioned root hashes and the
+/// hash of the first (root) certificate. Every subsequent certificate must name its predecessor
+/// as its issuer and be signed by it, and every certificate but the leaf must be a CA certificate.
+///
+/// # Arguments
+/// * `cert_chain` - The certificate chain in SPDM certificate chain format.
+/// * `trusted_root_hashes` - The provisioned SHA-384 hashes of the trusted root certificates.
+///
+/// # Returns
+/// * `PeerPublicKey` - The public key of the leaf certificate.
+pub(crate) async fn verify_peer_cert_chain(
+    cert_chain: &[u8],
+    trusted_root_hashes: &[[u8; SHA384_HASH_SIZE]],
+) -> PeerCertResult<PeerPublicKey> {
+    match verify_chain(cert_chain, trusted_root_hashes).await? {
+        SubjectPublicKey::EccP384(public_key) => Ok(public_key),
+        SubjectPublicKey::MlDsa87(_) => Err(PeerCertError::UnsupportedAlgorithm),
+    }
+}
+
+// Validates the chain and returns the public key of the leaf certificate
+async fn verify_chain<'a>(
+    cert_chain: &'a [u8],
+    trusted_root_hashes: &[[u8; SHA384_HASH_SIZE]],
+) -> PeerCertResult<SubjectPublicKey<'a>> {
+    if cert_chain.len() <= SPDM_CERT_CHAIN_HDR_SIZE + SHA384_HASH_SIZE {
+        Err(PeerCertError::InvalidCertChain)?;
+    }
+
+    let chain_len = u16::from_le_bytes([cert_chain[0], cert_chain[1]]) as usize;
+    if chain_len != cert_chain.len() {
+        Err(PeerCertError::InvalidCertChain)?;
+    }
+
+    let root_hash =
+        &cert_chain[SPDM_CERT_CHAIN_HDR_SIZE..SPDM_CERT_CHAIN_HDR_SIZE + SHA384_HASH_SIZE];
+    if !trusted_root_hashes
+        .iter()
+        .any(|trusted| trusted.as_slice() == root_hash)
+    {
+        Err(PeerCertError::UntrustedRoot)?;
+    }
+
+    let mut certs = DerReader::new(&cert_chain[SPDM_CERT_CHAIN_HDR_SIZE + SHA384_HASH_SIZE..]);
+    let mut issuer: Option<PeerCert> = None;
+
+    while !certs.is_empty() {
+        let (tag, _, cert_der) = certs.read_tlv()?;
+        if tag != DER_TAG_SEQUENCE {
+            Err(PeerCertError::InvalidCertificate)?;
+        }
+        let cert = PeerCert::parse(cert_der)?;
+
+        match &issuer {
+            // The root certificate is trusted by its hash
+            None => {
+                let mut cert_hash = [0u8; SHA384_HASH_SIZE];
+                sha384(cert_der, &mut cert_hash).await?;
+                if cert_hash.as_slice() != root_hash {
+                    Err(PeerCertError::UntrustedRoot)?;
+                }
+            }
+            Some(issuer) => {
+                cert.check_issuer(issuer)?;
+                cert.verify_signature(&issuer.public_key).await?;
+            }
+        }
+
+        issuer = Some(cert);
+    }
+
+    issuer
+        .map(|leaf| leaf.public_key)
+        .ok_or(PeerCertError::InvalidCertChain)
+}
+
+/// Computes the hash of a peer certificate chain in SPDM format, as appended to the session transcript.
+///
+/// # Arguments
+/// * `cert_chain` - The certificate chain in SPDM certificate chain format.
+/// * `hash` - Output buffer for the SHA-384 hash.
+pub(crate) async fn peer_cert_chain_hash(
+    cert_chain: &[u8],
+    hash: &mut [u8; SHA384_HASH_SIZE],
+) -> PeerCertResult<()> {
+    sha384(cert_chain, hash).await
+}
+
+async fn sha384(data: &[u8], hash: &mut [u8; SHA384_HASH_SIZE]) -> PeerCertResult<()> {
+    digest(HashAlgoType::SHA384, data, hash).await
+}
+
+async fn digest(hash_algo: HashAlgoType, data: &[u8], hash: &mut [u8]) -> PeerCertResult<()> {
+    let mut hash_ctx = HashContext::new();
+    hash_ctx
+        .init(hash_algo, None)
+        .await
+        .map_err(PeerCertError::CaliptraApi)?;
+    hash_ctx
+        .update(data)
+        .await
+        .map_err(PeerCertError::CaliptraApi)?;
+    hash_ctx
+        .finalize(hash)
+        .await
+        .map_err(PeerCertError::CaliptraApi)
+}
+
+/// Public key carried by a certificate of the chain
+#[derive(Clone, Copy)]
+enum SubjectPublicKey<'a> {
+    EccP384(PeerPublicKey),
+    MlDsa87(&'a [u8; MLDSA87_PUBLIC_KEY_SIZE]),
+}
+
+impl<'a> SubjectPublicKey<'a> {
+    // Parses the subjectPublicKey of the SubjectPublicKeyInfo with the given algorithm
+    fn parse(alg_id: &[u8], key_bits: &'a [u8]) -> PeerCertResult<Self> {
+        match alg_id {
+            EC_P384_PUBLIC_KEY_ALG_ID => {
+                let (x, y) = parse_ecc_p384_public_key(key_bits)?;
+                Ok(SubjectPublicKey::EccP384(PeerPublicKey { x, y }))
+            }
+            ML_DSA_87_ALG_ID => key_bits
+                .try_into()
+                .map(SubjectPublicKey::MlDsa87)
+                .map_err(|_| PeerCertError::InvalidCertificate),
+            _ => Err(PeerCertError::UnsupportedAlgorithm),
+        }
+    }
+
+    #[cfg(test)]
+    fn asym_algo(&self) -> AsymAlgo {
+        match self {
+            SubjectPublicKey::EccP384(_) => AsymAlgo::EccP384,
+            SubjectPublicKey::MlDsa87(_) => AsymAlgo::MlDsa87,
+        }
+    }
+}
+
+/// Signature algorithm of a certificate
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CertSignatureAlgo {
+    EcdsaWithSha384,
+    EcdsaWithSha512,
+    MlDsa87,
+}
+
+impl CertSignatureAlgo {
+    fn from_alg_id(alg_id: &[u8]) -> PeerCertResult<Self> {
+        match alg_id {
+            ECDSA_WITH_SHA384_ALG_ID => Ok(Self::EcdsaWithSha384),
+            ECDSA_WITH_SHA512_ALG_ID => Ok(Self::EcdsaWithSha512),
+            ML_DSA_87_ALG_ID => Ok(Self::MlDsa87),
+            _ => Err(PeerCertError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+/// Certificate of the chain with its signature algorithm and public key
+struct PeerCert<'a> {
+    cert: X509Cert<'a>,
+    signature_algo: CertSignatureAlgo,
+    public_key: SubjectPublicKey<'a>,
+}
+
+impl<'a> PeerCert<'a> {
+    fn parse(cert_der: &'a [u8]) -> PeerCertResult<Self> {
+        let cert = X509Cert::parse(cert_der)?;
+        let signature_algo = CertSignatureAlgo::from_alg_id(cert.signature_alg_id)?;
+        let public_key = SubjectPublicKey::parse(cert.public_key_alg_id, cert.public_key)?;
+
+        Ok(Self {
+            cert,
+            signature_algo,
+            public_key,
+        })
+    }
+
+    /// Checks that the certificate names `issuer` as its issuer, and that `issuer` is a CA
+    /// certificate allowed to sign certificates.
+    fn check_issuer(&self, issuer: &PeerCert<'_>) -> PeerCertResult<()> {
+        if self.cert.issuer != issuer.cert.subject || !issuer.cert.is_ca()? {
+            Err(PeerCertError::InvalidCertChain)?;
+        }
+        Ok(())
+    }
+
+    /// Verifies the certificate signature with the public key of its issuer.
+    async fn verify_signature(&self, issuer_key: &SubjectPublicKey<'_>) -> PeerCertResult<()> {
+        let tbs = self.cert.tbs;
+        match (issuer_key, self.signature_algo) {
+            (SubjectPublicKey::EccP384(key), CertSignatureAlgo::EcdsaWithSha384) => {
+                let signature = parse_ecdsa_signature(self.cert.signature)?;
+                let mut tbs_hash = [0u8; SHA384_HASH_SIZE];
+                sha384(tbs, &mut tbs_hash).await?;
+                key.verify(&tbs_hash, &signature).await
+            }
+            (SubjectPublicKey::EccP384(key), CertSignatureAlgo::EcdsaWithSha512) => {
+                let signature = parse_ecdsa_signature(self.cert.signature)?;
+                let mut digest_buf = [0u8; 64];
+                digest(HashAlgoType::SHA512, tbs, &mut digest_buf).await?;
+
+                // ECDSA P-384 uses the leftmost 384 bits of a longer digest
+                let mut tbs_hash = [0u8; SHA384_HASH_SIZE];
+                tbs_hash.copy_from_slice(&digest_buf[..SHA384_HASH_SIZE]);
+                key.verify(&tbs_hash, &signature).await
+            }
+            (SubjectPublicKey::MlDsa87(key), CertSignatureAlgo::MlDsa87) => {
+                let signature: &[u8; MLDSA87_SIGNATURE_SIZE] = self
+                    .cert
+                    .signature
+                    .try_into()
+                    .map_err(|_| PeerCertError::InvalidCertificate)?;
+                Mldsa::mldsa87_verify(key, signature, tbs)
+                    .await
+                    .map_err(|_| PeerCertError::InvalidSignature)
+            }
+            // The signature algorithm must match the key of the issuer
+            _ => Err(PeerCertError::InvalidCertificate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x509_common::EC_POINT_UNCOMPRESSED;
+
+    #[test]
+    fn test_cert_signature_algo() {
+        assert_eq!(
+            CertSignatureAlgo::from_alg_id(ECDSA_WITH_SHA384_ALG_ID),
+            Ok(CertSignatureAlgo::EcdsaWithSha384)
+        );
+        assert_eq!(
+            CertSignatureAlgo::from_alg_id(ECDSA_WITH_SHA512_ALG_ID),
+            Ok(CertSignatureAlgo::EcdsaWithSha512)
+        );
+        assert_eq!(
+            CertSignatureAlgo::from_alg_id(ML_DSA_87_ALG_ID),
+            Ok(CertSignatureAlgo::MlDsa87)
+        );
+        // ecdsa-with-SHA256
+        assert_eq!(
+            CertSignatureAlgo::from_alg_id(&[
+                0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02
+            ]),
+            Err(PeerCertError::UnsupportedAlgorithm)
+        );
+    }
+
+    // Names with a single CN=Root and CN=Leaf
+    const ROOT_NAME: &[u8] = &[
+        0x30, 0x0F, 0x31, 0x0D, 0x30, 0x0B, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0C, 0x04, 0x52, 0x6F,
+        0x6F, 0x74,
+    ];
+    const LEAF_NAME: &[u8] = &[
+        0x30, 0x0F, 0x31, 0x0D, 0x30, 0x0B, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0C, 0x04, 0x4C, 0x65,
+        0x61, 0x66,
+    ];
+    // basicConstraints extension with cA TRUE
+    const CA_EXTENSIONS: &[u8] = &[
+        0x30, 0x0C, 0x06, 0x03, 0x55, 0x1D, 0x13, 0x04, 0x05, 0x30, 0x03, 0x01, 0x01, 0xFF,
+    ];
+
+    fn peer_cert<'a>(issuer: &'a [u8], subject: &'a [u8], extensions: &'a [u8]) -> PeerCert<'a> {
+        PeerCert {
+            cert: X509Cert {
+                tbs: &[],
+                signature_alg_id: ECDSA_WITH_SHA384_ALG_ID,
+                signature: &[],
+                issuer,
+                subject,
+                public_key_info: &[],
+                public_key_alg_id: EC_P384_PUBLIC_KEY_ALG_ID,
+                public_key: &[],
+                extensions,
+            },
+            signature_algo: CertSignatureAlgo::EcdsaWithSha384,
+            public_key: SubjectPublicKey::EccP384(PeerPublicKey {
+                x: [0; ECC_P384_PARAM_X_SIZE],
+                y: [0; ECC_P384_PARAM_Y_SIZE],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_check_issuer() {
+        let root = peer_cert(ROOT_NAME, ROOT_NAME, CA_EXTENSIONS);
+        let leaf = peer_cert(ROOT_NAME, LEAF_NAME, &[]);
+        assert_eq!(leaf.check_issuer(&root), Ok(()));
+
+        // Issuer name is not the subject of the previous certificate
+        let leaf = peer_cert(LEAF_NAME, LEAF_NAME, &[]);
+        assert_eq!(
+            leaf.check_issuer(&root),
+            Err(PeerCertError::InvalidCertChain)
+        );
+
+        // Previous certificate is not a CA
+        let root = peer_cert(ROOT_NAME, ROOT_NAME, &[]);
+        let leaf = peer_cert(ROOT_NAME, LEAF_NAME, &[]);
+        assert_eq!(
+            leaf.check_issuer(&root),
+            Err(PeerCertError::InvalidCertChain)
+        );
+    }
+
+    #[test]
+    fn test_parse_subject_public_key() {
+        let mut ecc_key = [0u8; 1 + ECC_P384_PARAM_X_SIZE + ECC_P384_PARAM_Y_SIZE];
+        ecc_key[0] = EC_POINT_UNCOMPRESSED;
+        ecc_key[1..1 + ECC_P384_PARAM_X_SIZE].fill(0x11);
+        ecc_key[1 + ECC_P384_PARAM_X_SIZE..].fill(0x22);
+        match SubjectPublicKey::parse(EC_P384_PUBLIC_KEY_ALG_ID, &ecc_key) {
+            Ok(SubjectPublicKey::EccP384(key)) => {
+                assert_eq!(key.x, [0x11; ECC_P384_PARAM_X_SIZE]);
+                assert_eq!(key.y, [0x22; ECC_P384_PARAM_Y_SIZE]);
+            }
+            _ => panic!("expected an ECC P-384 key"),
+        }
+        assert_eq!(
+            SubjectPublicKey::parse(EC_P384_PUBLIC_KEY_ALG_ID, &ecc_key)
+                .unwrap()
+                .asym_algo(),
+            AsymAlgo::EccP384
+        );
+
+        let mldsa_key = [0x33u8; MLDSA87_PUBLIC_KEY_SIZE];
+        match SubjectPublicKey::parse(ML_DSA_87_ALG_ID, &mldsa_key) {
+            Ok(SubjectPublicKey::MlDsa87(key)) => assert_eq!(key, &mldsa_key),
+            _ => panic!("expected an ML-DSA-87 key"),
+        }
+        assert_eq!(
+            SubjectPublicKey::parse(ML_DSA_87_ALG_ID, &mldsa_key)
+                .unwrap()
+                .asym_algo(),
+            AsymAlgo::MlDsa87
+        );
+
+        // Truncated ML-DSA-87 key
+        assert!(matches!(
+            SubjectPublicKey::parse(ML_DSA_87_ALG_ID, &mldsa_key[1..]),
+            Err(PeerCertError::InvalidCertificate)
+        ));
+        // Signature algorithm in place of a key algorithm
+        assert!(matches!(
+            SubjectPublicKey::parse(ECDSA_WITH_SHA384_ALG_ID, &ecc_key),
+            Err(PeerCertError::UnsupportedAlgorithm)
+        ));
+    }
+}
@@ -226,6 +226,36 @@ pub enum BaseAsymAlgoType {
     EddsaEd448,
 }
 
+// PQC Asymmetric Algorithm field
+bitfield! {
+#[derive(FromBytes, IntoBytes, Immutable, Default, Clone, Copy)]
+#[repr(C)]
+pub struct PqcAsymAlgo(u32);
+impl Debug;
+u8;
+pub ml_dsa_44, set_ml_dsa_44: 0,0;
+pub ml_dsa_65, set_ml_dsa_65: 1,1;
+pub ml_dsa_87, set_ml_dsa_87: 2,2;
+reserved, _: 31,3;
+}
+
+impl From<PqcAsymAlgoType> for u32 {
+    fn from(pqc_asym_algo_type: PqcAsymAlgoType) -> u32 {
+        match pqc_asym_algo_type {
+            PqcAsymAlgoType::MlDsa44 => PqcAsymAlgo(1 << 0).0,
+            PqcAsymAlgoType::MlDsa65 => PqcAsymAlgo(1 << 1).0,
+            PqcAsymAlgoType::MlDsa87 => PqcAsymAlgo(1 << 2).0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PqcAsymAlgoType {
+    MlDsa44,
+    MlDsa65,
+    MlDsa87,
+}
+
 // Base Hash Algorithm field
 bitfield! {
 #[derive(FromBytes, IntoBytes, Immutable, Default, Clone, Copy)]
@@ -530,6 +560,7 @@ pub struct DeviceAlgorithms {
     pub other_param_support: OtherParamSupport,
     pub measurement_hash_algo: MeasurementHashAlgo,
     pub base_asym_algo: BaseAsymAlgo,
+    pub pqc_asym_algo: PqcAsymAlgo,
     pub base_hash_algo: BaseHashAlgo,
     pub mel_specification: MelSpecification,
     pub dhe_group: DheNamedGroup,
@@ -551,6 +582,8 @@ impl Default for DeviceAlgorithms {
         let mut base_asym_algo = BaseAsymAlgo::default();
         base_asym_algo.set_tpm_alg_ecdsa_ecc_nist_p384(1);
 
+        let pqc_asym_algo = PqcAsymAlgo::default();
+
         let mut base_hash_algo = BaseHashAlgo::default();
         base_hash_algo.set_tpm_alg_sha_384(1);
 
@@ -567,6 +600,7 @@ impl Default for DeviceAlgorithms {
             other_param_support,
             measurement_hash_algo,
             base_asym_algo,
+            pqc_asym_algo,
             base_hash_algo,
             mel_specification,
             dhe_group,
@@ -595,6 +629,12 @@ impl DeviceAlgorithms {
         num
     }
 
+    pub fn set_pqc_asym_algo(&mut self) {
+        let mut pqc_asym_algo = PqcAsymAlgo::default();
+        pqc_asym_algo.set_ml_dsa_87(1);
+        self.pqc_asym_algo = pqc_asym_algo;
+    }
+
     pub fn set_dhe_group(&mut self) {
         let mut dhe_named_group = DheNamedGroup::default();
         dhe_named_group.set_secp384r1(1);
@@ -607,6 +647,12 @@ impl DeviceAlgorithms {
         self.aead_cipher_suite = aead_cipher_suite;
     }
 
+    pub fn set_req_base_asym_algo(&mut self) {
+        let mut req_base_asym_algo = ReqBaseAsymAlg::default();
+        req_base_asym_algo.set_tpm_alg_ecdsa_ecc_nist_p384(1);
+        self.req_base_asym_algo = req_base_asym_algo;
+    }
+
     pub fn set_spdm_key_schedule(&mut self) {
         let mut key_schedule = KeySchedule::default();
         key_schedule.set_spdm_key_schedule(1);
@@ -626,6 +672,7 @@ pub struct AlgorithmPriorityTable<'a> {
     pub measurement_specification: Option<&'a [MeasurementSpecificationType]>,
     pub opaque_data_format: Option<&'a [OpaqueDataFormatType]>,
     pub base_asym_algo: Option<&'a [BaseAsymAlgoType]>,
+    pub pqc_asym_algo: Option<&'a [PqcAsymAlgoType]>,
     pub base_hash_algo: Option<&'a [BaseHashAlgoType]>,
     pub mel_specification: Option<&'a [MelSpecificationType]>,
     pub dhe_group: Option<&'a [DheGroupType]>,
@@ -647,6 +694,7 @@ impl Default for LocalDeviceAlgorithms<'_> {
                 measurement_specification: None,
                 opaque_data_format: None,
                 base_asym_algo: None,
+                pqc_asym_algo: None,
                 base_hash_algo: Some(HASH_PRIORITY_TABLE),
                 mel_specification: None,
                 dhe_group: None,
@@ -666,6 +714,7 @@ impl LocalDeviceAlgorithms<'_> {
                 measurement_specification: None,
                 opaque_data_format: None,
                 base_asym_algo: None,
+                pqc_asym_algo: None,
                 base_hash_algo: Some(HASH_PRIORITY_TABLE),
                 mel_specification: None,
                 dhe_group: None,
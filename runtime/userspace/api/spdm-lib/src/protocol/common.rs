@@ -25,12 +25,36 @@ pub(crate) enum ReqRespCode {
     Measurements = 0x60,
     ChunkGet = 0x86,
     ChunkResponse = 0x06,
+    ChunkSend = 0x85,
+    ChunkSendAck = 0x05,
     KeyExchange = 0xE4,
     KeyExchangeRsp = 0x64,
     Finish = 0xE5,
     FinishRsp = 0x65,
+    PskExchange = 0xE6,
+    PskExchangeRsp = 0x66,
+    PskFinish = 0xE7,
+    PskFinishRsp = 0x67,
+    Heartbeat = 0xE8,
+    HeartbeatAck = 0x68,
+    KeyUpdate = 0xE9,
+    KeyUpdateAck = 0x69,
+    GetEncapsulatedRequest = 0xEA,
+    EncapsulatedRequest = 0x6A,
+    DeliverEncapsulatedResponse = 0xEB,
+    EncapsulatedResponseAck = 0x6B,
     EndSession = 0xEC,
     EndSessionAck = 0x6C,
+    GetCsr = 0xED,
+    Csr = 0x6D,
+    SetCertificate = 0xEE,
+    SetCertificateRsp = 0x6E,
+    GetMeasurementExtensionLog = 0xEF,
+    MeasurementExtensionLog = 0x6F,
+    GetKeyPairInfo = 0xFC,
+    KeyPairInfo = 0x7C,
+    SetKeyPairInfo = 0xFD,
+    SetKeyPairInfoAck = 0x7D,
     VendorDefinedRequest = 0xFE,
     VendorDefinedResponse = 0x7E,
     Error = 0x7F,
@@ -56,12 +80,36 @@ impl TryFrom<u8> for ReqRespCode {
             0x60 => Ok(ReqRespCode::Measurements),
             0x86 => Ok(ReqRespCode::ChunkGet),
             0x06 => Ok(ReqRespCode::ChunkResponse),
+            0x85 => Ok(ReqRespCode::ChunkSend),
+            0x05 => Ok(ReqRespCode::ChunkSendAck),
             0x7F => Ok(ReqRespCode::Error),
             0xE4 => Ok(ReqRespCode::KeyExchange),
             0xE5 => Ok(ReqRespCode::Finish),
             0x65 => Ok(ReqRespCode::FinishRsp),
+            0xE6 => Ok(ReqRespCode::PskExchange),
+            0x66 => Ok(ReqRespCode::PskExchangeRsp),
+            0xE7 => Ok(ReqRespCode::PskFinish),
+            0x67 => Ok(ReqRespCode::PskFinishRsp),
+            0xE8 => Ok(ReqRespCode::Heartbeat),
+            0x68 => Ok(ReqRespCode::HeartbeatAck),
+            0xE9 => Ok(ReqRespCode::KeyUpdate),
+            0x69 => Ok(ReqRespCode::KeyUpdateAck),
+            0xEA => Ok(ReqRespCode::GetEncapsulatedRequest),
+            0x6A => Ok(ReqRespCode::EncapsulatedRequest),
+            0xEB => Ok(ReqRespCode::DeliverEncapsulatedResponse),
+            0x6B => Ok(ReqRespCode::EncapsulatedResponseAck),
             0xEC => Ok(ReqRespCode::EndSession),
             0x6C => Ok(ReqRespCode::EndSessionAck),
+            0xED => Ok(ReqRespCode::GetCsr),
+            0x6D => Ok(ReqRespCode::Csr),
+            0xEE => Ok(ReqRespCode::SetCertificate),
+            0x6E => Ok(ReqRespCode::SetCertificateRsp),
+            0xEF => Ok(ReqRespCode::GetMeasurementExtensionLog),
+            0x6F => Ok(ReqRespCode::MeasurementExtensionLog),
+            0xFC => Ok(ReqRespCode::GetKeyPairInfo),
+            0x7C => Ok(ReqRespCode::KeyPairInfo),
+            0xFD => Ok(ReqRespCode::SetKeyPairInfo),
+            0x7D => Ok(ReqRespCode::SetKeyPairInfoAck),
             0xFE => Ok(ReqRespCode::VendorDefinedRequest),
             0x7E => Ok(ReqRespCode::VendorDefinedResponse),
             _ => Err(SpdmError::UnsupportedRequest),
@@ -82,6 +130,7 @@ impl ReqRespCode {
             ReqRespCode::ChallengeAuth => "responder-challenge_auth signing",
             ReqRespCode::Measurements => "responder-measurements signing",
             ReqRespCode::KeyExchangeRsp => "responder-key_exchange_rsp signing",
+            ReqRespCode::Finish => "requester-finish signing",
             _ => return Err(SpdmError::UnsupportedRequest),
         };
 
@@ -8,6 +8,8 @@ pub const SPDM_MAX_MEASUREMENT_RECORD_SIZE: u32 = 0xFFFFFF;
 pub const SPDM_MEASUREMENT_MANIFEST_INDEX: u8 = 0xFD;
 pub const SPDM_DEVICE_MODE_INDEX: u8 = 0xFE;
 pub const MEAS_BLOCK_METADATA_SIZE: usize = size_of::<DmtfMeasurementBlockMetadata>();
+pub const DMTF_MEL_HEADER_SIZE: usize = size_of::<DmtfMelHeader>();
+pub const DMTF_MEL_ENTRY_HEADER_SIZE: usize = size_of::<DmtfMelEntryHeader>();
 
 bitfield! {
 #[derive(IntoBytes, FromBytes, Immutable, Default)]
@@ -75,6 +77,63 @@ impl DmtfMeasurementBlockMetadata {
     }
 }
 
+// Measurement extension log in the DMTF format (MELspecification DMTF)
+#[derive(IntoBytes, FromBytes, Immutable, Default)]
+#[repr(C)]
+pub struct DmtfMelHeader {
+    pub number_of_entries: u32,
+    pub mel_entries_len: u32,
+    reserved: u32,
+}
+
+impl DmtfMelHeader {
+    pub fn new(number_of_entries: u32, mel_entries_len: u32) -> Self {
+        DmtfMelHeader {
+            number_of_entries,
+            mel_entries_len,
+            reserved: 0,
+        }
+    }
+}
+
+// Header of a DMTF measurement extension log entry, followed by the measurement extension
+#[derive(IntoBytes, FromBytes, Immutable, Default)]
+#[repr(C, packed)]
+pub struct DmtfMelEntryHeader {
+    mel_index: u32,
+    meas_index: u32,
+    reserved: u32,
+    meas_val_hdr: DmtfSpecMeasurementValueHeader,
+}
+
+impl DmtfMelEntryHeader {
+    pub fn new(
+        mel_index: u32,
+        meas_index: u8,
+        meas_value_size: u16,
+        meas_value_dgst: bool,
+        meas_value_type: MeasurementValueType,
+    ) -> Self {
+        let mut entry_hdr = DmtfMelEntryHeader {
+            mel_index,
+            meas_index: meas_index as u32,
+            ..Default::default()
+        };
+
+        // If digest, repr = 0, raw bit stream = 1
+        entry_hdr
+            .meas_val_hdr
+            .value_type
+            .set_meas_val_repr(u8::from(!meas_value_dgst));
+        entry_hdr
+            .meas_val_hdr
+            .value_type
+            .set_meas_val_type(meas_value_type as u8);
+        entry_hdr.meas_val_hdr.value_size = meas_value_size;
+        entry_hdr
+    }
+}
+
 pub enum MeasurementChangeStatus {
     NoDetection = 0,
     ChangeDetected = 1,
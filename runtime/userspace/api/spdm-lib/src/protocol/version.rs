@@ -2,9 +2,17 @@
 
 use crate::error::{SpdmError, SpdmResult};
 
+#[cfg(not(feature = "spdm-v14"))]
 const MAX_NUM_SUPPORTED_SPDM_VERSIONS: usize = 4;
+#[cfg(not(feature = "spdm-v14"))]
 const MAX_SUPPORTED_VERSION: SpdmVersion = SpdmVersion::V13;
 
+// SPDM 1.4 support is not complete and is only enabled for the PQC algorithms
+#[cfg(feature = "spdm-v14")]
+const MAX_NUM_SUPPORTED_SPDM_VERSIONS: usize = 5;
+#[cfg(feature = "spdm-v14")]
+const MAX_SUPPORTED_VERSION: SpdmVersion = SpdmVersion::V14;
+
 #[derive(Debug, Default, PartialEq, Clone, Copy, PartialOrd)]
 pub enum SpdmVersion {
     #[default]
@@ -12,6 +20,7 @@ pub enum SpdmVersion {
     V11,
     V12,
     V13,
+    V14,
 }
 
 impl SpdmVersion {
@@ -21,6 +30,7 @@ impl SpdmVersion {
             SpdmVersion::V11 => "1.1.*",
             SpdmVersion::V12 => "1.2.*",
             SpdmVersion::V13 => "1.3.*",
+            SpdmVersion::V14 => "1.4.*",
         }
     }
 }
@@ -33,6 +43,7 @@ impl TryFrom<u8> for SpdmVersion {
             0x11 => Ok(SpdmVersion::V11),
             0x12 => Ok(SpdmVersion::V12),
             0x13 => Ok(SpdmVersion::V13),
+            0x14 => Ok(SpdmVersion::V14),
             _ => Err(SpdmError::UnsupportedVersion),
         }
     }
@@ -56,6 +67,7 @@ impl SpdmVersion {
             SpdmVersion::V11 => 0x11,
             SpdmVersion::V12 => 0x12,
             SpdmVersion::V13 => 0x13,
+            SpdmVersion::V14 => 0x14,
         }
     }
 
@@ -0,0 +1,39 @@
+// Licensed under the Apache-2.0 license
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use caliptra_api::mailbox::Cmk;
+use libapi_caliptra::error::CaliptraApiError;
+
+// Maximum PSKHint length accepted in PSK_EXCHANGE
+pub const MAX_PSK_HINT_SIZE: usize = 16;
+
+#[derive(Debug, PartialEq)]
+pub enum PskStoreError {
+    NotProvisioned,
+    UnknownPskHint,
+    CaliptraApi(CaliptraApiError),
+}
+pub type PskStoreResult<T> = Result<T, PskStoreError>;
+
+#[async_trait]
+pub trait SpdmPskStore {
+    /// Check if at least one pre-shared key is provisioned.
+    ///
+    /// # Returns
+    /// * `bool` - True if a PSK is available, false otherwise.
+    fn is_provisioned(&self) -> bool;
+
+    /// Get the pre-shared key identified by the PSKHint sent by the requester.
+    /// The key is returned as a Caliptra cryptographic mailbox key handle usable for HMAC,
+    /// e.g. one obtained by importing the raw PSK with `Import::import(CmKeyUsage::Hmac, ..)`.
+    ///
+    /// # Arguments
+    /// * `psk_hint` - The PSKHint from the PSK_EXCHANGE request. Empty if the requester sent none.
+    ///
+    /// # Returns
+    /// * `Cmk` - The key handle of the PSK or error if no PSK matches the hint.
+    async fn psk(&self, psk_hint: &[u8]) -> PskStoreResult<Cmk>;
+}
@@ -0,0 +1,48 @@
+// Licensed under the Apache-2.0 license
+
+/// Extends the 32-bit alarm tick count to a 64-bit time that does not wrap around.
+///
+/// The tick count must be sampled at least once per wrap period of the alarm. This is
+/// guaranteed while the session expiry signal is raised periodically.
+#[derive(Debug, Default)]
+pub(crate) struct SessionClock {
+    last_ticks: Option<u32>,
+    elapsed_ticks: u64,
+}
+
+impl SessionClock {
+    /// Records the current alarm tick count.
+    ///
+    /// # Returns
+    /// The time in 64-bit ticks since the first sample.
+    pub fn update(&mut self, ticks: u32) -> u64 {
+        if let Some(last_ticks) = self.last_ticks {
+            self.elapsed_ticks += u64::from(ticks.wrapping_sub(last_ticks));
+        }
+        self.last_ticks = Some(ticks);
+        self.elapsed_ticks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_is_monotonic() {
+        let mut clock = SessionClock::default();
+        assert_eq!(clock.update(1000), 0);
+        assert_eq!(clock.update(1500), 500);
+        assert_eq!(clock.update(1500), 500);
+    }
+
+    #[test]
+    fn test_clock_extends_past_wrap() {
+        let mut clock = SessionClock::default();
+        clock.update(u32::MAX - 9);
+        // The tick count wraps around, the time keeps going forward
+        assert_eq!(clock.update(10), 20);
+        assert_eq!(clock.update(u32::MAX), u64::from(u32::MAX) + 10);
+        assert_eq!(clock.update(5), u64::from(u32::MAX) + 16);
+    }
+}
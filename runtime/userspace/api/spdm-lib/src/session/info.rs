@@ -1,9 +1,11 @@
 // Licensed under the Apache-2.0 license
 
 use super::{KeySchedule, SessionError, SessionKeyType, SessionResult};
+use crate::peer_cert::PeerPublicKey;
 use crate::protocol::SpdmVersion;
 use crate::transcript::SessionTranscript;
 use bitfield::bitfield;
+use caliptra_api::mailbox::Cmk;
 use libapi_caliptra::crypto::aes_gcm::Aes256GcmTag;
 use libapi_caliptra::crypto::asym::ecdh::CMB_ECDH_EXCHANGE_DATA_MAX_SIZE;
 use libapi_caliptra::crypto::asym::AsymAlgo;
@@ -30,6 +32,34 @@ pub(crate) enum SessionState {
     Terminating,         // When END_SESSION is received
 }
 
+/// Key operations carried in KEY_UPDATE Param1
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum KeyUpdateOp {
+    UpdateKey = 1,
+    UpdateAllKeys = 2,
+    VerifyNewKey = 3,
+}
+
+impl TryFrom<u8> for KeyUpdateOp {
+    type Error = SessionError;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(KeyUpdateOp::UpdateKey),
+            2 => Ok(KeyUpdateOp::UpdateAllKeys),
+            3 => Ok(KeyUpdateOp::VerifyNewKey),
+            _ => Err(SessionError::InvalidKeyUpdate),
+        }
+    }
+}
+
+/// Requester certificate retrieved and validated through the encapsulated request flow
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequesterAuth {
+    pub(crate) slot_id: u8,
+    pub(crate) cert_chain_hash: [u8; SHA384_HASH_SIZE],
+    pub(crate) public_key: PeerPublicKey,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum SessionType {
     None,
@@ -47,6 +77,20 @@ pub(crate) struct SessionInfo {
     pub(crate) asym_algo: AsymAlgo, // Asymmetric algorithm negotiated for this session
     key_schedule_ctx: KeySchedule,  // Key schedule context for this session
     pub(crate) session_transcript: SessionTranscript,
+    // Last UpdateKey/UpdateAllKeys request (operation, tag) awaiting VerifyNewKey
+    pending_key_update: Option<(KeyUpdateOp, u8)>,
+    // Whether the session was established with PSK_EXCHANGE instead of KEY_EXCHANGE
+    pub(crate) use_psk: bool,
+    // Whether the handshake messages are sent in the clear instead of in the session
+    pub(crate) handshake_in_the_clear: bool,
+    // Whether mutual authentication was requested in KEY_EXCHANGE_RSP
+    pub(crate) mut_auth_requested: bool,
+    // Requester certificate used to verify the FINISH signature when mutual authentication is requested
+    pub(crate) requester_auth: Option<RequesterAuth>,
+    // Heartbeat period in seconds negotiated in KEY_EXCHANGE_RSP/PSK_EXCHANGE_RSP (0 = disabled)
+    pub(crate) heartbeat_period: u8,
+    // Time in 64-bit alarm ticks at which the last message was received in this session
+    last_activity_ticks: u64,
 }
 
 impl SessionInfo {
@@ -59,6 +103,13 @@ impl SessionInfo {
             asym_algo: AsymAlgo::EccP384, // Default to ECC P384
             key_schedule_ctx: KeySchedule::default(),
             session_transcript: SessionTranscript::new(),
+            pending_key_update: None,
+            use_psk: false,
+            handshake_in_the_clear: false,
+            mut_auth_requested: false,
+            requester_auth: None,
+            heartbeat_period: 0,
+            last_activity_ticks: 0,
         }
     }
 
@@ -68,12 +119,39 @@ impl SessionInfo {
         session_type: SessionType,
         spdm_version: SpdmVersion,
         asym_algo: AsymAlgo,
+        heartbeat_period: u8,
     ) {
         self.session_policy = session_policy;
         self.session_state = SessionState::HandshakeNotStarted;
         self.session_type = session_type;
         self.key_schedule_ctx.set_spdm_version(spdm_version);
         self.asym_algo = asym_algo;
+        self.heartbeat_period = heartbeat_period;
+        self.mut_auth_requested = false;
+        self.requester_auth = None;
+    }
+
+    /// Records activity on the session, restarting the heartbeat window
+    ///
+    /// # Arguments
+    /// `now_ticks` is the current time in 64-bit alarm ticks.
+    pub fn refresh_heartbeat(&mut self, now_ticks: u64) {
+        self.last_activity_ticks = now_ticks;
+    }
+
+    /// Checks whether the heartbeat window of the session has expired.
+    /// Per DSP0274, the Responder terminates the session if no message is
+    /// received within twice the heartbeat period.
+    ///
+    /// # Arguments
+    /// `now_ticks` is the current time in 64-bit alarm ticks.
+    /// `freq_hz` is the alarm frequency.
+    pub fn heartbeat_expired(&self, now_ticks: u64, freq_hz: u32) -> bool {
+        if self.heartbeat_period == 0 {
+            return false;
+        }
+        let window_ticks = 2 * u64::from(self.heartbeat_period) * u64::from(freq_hz);
+        now_ticks.saturating_sub(self.last_activity_ticks) > window_ticks
     }
 
     /// Sets the session state
@@ -101,6 +179,16 @@ impl SessionInfo {
             .map_err(SessionError::KeySchedule)
     }
 
+    /// Sets the pre-shared key for a PSK session. The PSK replaces the DHE
+    /// secret as input to the handshake secret derivation.
+    ///
+    /// # Arguments
+    /// `psk` is the Caliptra key handle of the pre-shared key.
+    pub fn set_psk(&mut self, psk: Cmk) {
+        self.use_psk = true;
+        self.key_schedule_ctx.set_psk(psk);
+    }
+
     pub async fn generate_session_handshake_key(
         &mut self,
         th1_transcript_hash: &[u8; SHA384_HASH_SIZE],
@@ -187,4 +275,58 @@ impl SessionInfo {
             .await
             .map_err(SessionError::KeySchedule)
     }
+
+    /// Performs the session key operation requested by KEY_UPDATE.
+    ///
+    /// UpdateKey rotates the request direction data key. UpdateAllKeys additionally
+    /// rotates the response direction data key, which takes effect immediately so that
+    /// KEY_UPDATE_ACK is protected with the new key. The new request direction key is
+    /// only committed once VerifyNewKey is received.
+    ///
+    /// # Arguments
+    /// `op` is the requested key operation.
+    /// `tag` is the tag of the KEY_UPDATE request, used to detect retries.
+    pub async fn key_update(&mut self, op: KeyUpdateOp, tag: u8) -> SessionResult<()> {
+        match op {
+            KeyUpdateOp::UpdateKey | KeyUpdateOp::UpdateAllKeys => {
+                if let Some(pending) = self.pending_key_update {
+                    // A retried request was already applied; just acknowledge it again.
+                    if pending == (op, tag) {
+                        return Ok(());
+                    }
+                    return Err(SessionError::InvalidKeyUpdate);
+                }
+
+                self.key_schedule_ctx
+                    .update_data_key(SessionKeyType::RequestDataEncDecKey)
+                    .await
+                    .map_err(SessionError::KeySchedule)?;
+
+                if op == KeyUpdateOp::UpdateAllKeys {
+                    self.key_schedule_ctx
+                        .update_data_key(SessionKeyType::ResponseDataEncDecKey)
+                        .await
+                        .map_err(SessionError::KeySchedule)?;
+                    self.key_schedule_ctx
+                        .activate_data_key(SessionKeyType::ResponseDataEncDecKey, true)
+                        .map_err(SessionError::KeySchedule)?;
+                }
+
+                self.pending_key_update = Some((op, tag));
+            }
+            KeyUpdateOp::VerifyNewKey => {
+                if self.pending_key_update.is_none() {
+                    return Err(SessionError::InvalidKeyUpdate);
+                }
+
+                self.key_schedule_ctx
+                    .activate_data_key(SessionKeyType::RequestDataEncDecKey, true)
+                    .map_err(SessionError::KeySchedule)?;
+
+                self.pending_key_update = None;
+            }
+        }
+
+        Ok(())
+    }
 }
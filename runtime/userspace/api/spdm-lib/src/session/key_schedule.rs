@@ -22,6 +22,7 @@ pub enum KeyScheduleError {
     HandshakeSecretNotFound,
     MasterSecretNotFound,
     DataSecretNotFound,
+    KeyUpdateNotPending,
     CaliptraApi(CaliptraApiError),
 }
 
@@ -77,6 +78,14 @@ impl KeySchedule {
         Ok(self_exch_data)
     }
 
+    /// Sets the pre-shared key used in place of the DHE secret for PSK sessions
+    ///
+    /// # Arguments
+    /// `psk` is the Caliptra key handle of the pre-shared key.
+    pub fn set_psk(&mut self, psk: Cmk) {
+        self.master_secret_ctx.psk = Some(psk);
+    }
+
     pub async fn generate_session_handshake_key(
         &mut self,
         th1_transcript_hash: &[u8],
@@ -172,7 +181,7 @@ impl KeySchedule {
 
         let mut aes_gcm = AesGcm::new();
 
-        let decrypted_size = aes_gcm
+        let result = aes_gcm
             .spdm_message_decrypt(
                 major_secret,
                 self.spdm_version.into(),
@@ -183,13 +192,128 @@ impl KeySchedule {
                 tag,
                 plaintext_msg,
             )
-            .await
-            .map_err(KeyScheduleError::CaliptraApi)?;
+            .await;
 
-        // Increment the sequence number after decryption
-        self.increment_sequence_number(session_key_type)?;
+        match result {
+            Ok(decrypted_size) => {
+                // Increment the sequence number after decryption
+                self.increment_sequence_number(session_key_type)?;
+                Ok(decrypted_size)
+            }
+            Err(e) => {
+                // Until the new request direction key is verified, the requester may still be
+                // using the previous one (e.g. when retrying a KEY_UPDATE whose ACK was lost).
+                let backup = match session_key_type {
+                    SessionKeyType::RequestDataEncDecKey => {
+                        self.data_secret_ctx.request_data_secret_backup.as_mut()
+                    }
+                    _ => None,
+                };
+                let Some(backup) = backup else {
+                    return Err(KeyScheduleError::CaliptraApi(e));
+                };
+
+                let decrypted_size = aes_gcm
+                    .spdm_message_decrypt(
+                        backup.secret.clone(),
+                        self.spdm_version.into(),
+                        backup.sequence_num.to_le_bytes(),
+                        true,
+                        aad_data,
+                        encrypted_msg,
+                        tag,
+                        plaintext_msg,
+                    )
+                    .await
+                    .map_err(KeyScheduleError::CaliptraApi)?;
+
+                backup.sequence_num += 1;
+                Ok(decrypted_size)
+            }
+        }
+    }
 
-        Ok(decrypted_size)
+    /// Derives the next generation of the data secret for the given direction.
+    ///
+    /// The current secret and sequence number are retained as a backup until
+    /// `activate_data_key` is called for the same direction.
+    ///
+    /// # Arguments
+    /// `session_key_type` is either `RequestDataEncDecKey` or `ResponseDataEncDecKey`.
+    pub async fn update_data_key(
+        &mut self,
+        session_key_type: SessionKeyType,
+    ) -> KeyScheduleResult<()> {
+        let bin_str9 = self.bin_concat(SpdmBinStr::BinStr9, SHA384_HASH_SIZE as u16, None)?;
+
+        let (secret, sequence_num, backup) = match session_key_type {
+            SessionKeyType::RequestDataEncDecKey => (
+                &mut self.data_secret_ctx.request_data_secret,
+                &mut self.data_secret_ctx.request_sequence_num,
+                &mut self.data_secret_ctx.request_data_secret_backup,
+            ),
+            SessionKeyType::ResponseDataEncDecKey => (
+                &mut self.data_secret_ctx.response_data_secret,
+                &mut self.data_secret_ctx.response_sequence_num,
+                &mut self.data_secret_ctx.response_data_secret_backup,
+            ),
+            _ => Err(KeyScheduleError::InvalidSessionKeyType)?,
+        };
+
+        let cur_secret = secret.clone().ok_or(KeyScheduleError::DataSecretNotFound)?;
+
+        // Updated-Data-Secret = HKDF-Expand(Current-Data-Secret, bin_str9, Hash.Length)
+        let expand_rsp = Hmac::hkdf_expand(
+            &cur_secret,
+            CmKeyUsage::Hmac,
+            SHA384_HASH_SIZE as u32,
+            bin_str9.as_slice(),
+        )
+        .await
+        .map_err(KeyScheduleError::CaliptraApi)?;
+
+        *backup = Some(DataSecretBackup {
+            secret: cur_secret,
+            sequence_num: *sequence_num,
+        });
+        *secret = Some(expand_rsp.okm);
+        // Sequence number restarts from zero for every new key
+        *sequence_num = 0;
+
+        Ok(())
+    }
+
+    /// Commits or rolls back a pending data key update for the given direction.
+    ///
+    /// # Arguments
+    /// `session_key_type` is either `RequestDataEncDecKey` or `ResponseDataEncDecKey`.
+    /// `use_new_key` discards the backup if true, otherwise restores the previous key.
+    pub fn activate_data_key(
+        &mut self,
+        session_key_type: SessionKeyType,
+        use_new_key: bool,
+    ) -> KeyScheduleResult<()> {
+        let (secret, sequence_num, backup) = match session_key_type {
+            SessionKeyType::RequestDataEncDecKey => (
+                &mut self.data_secret_ctx.request_data_secret,
+                &mut self.data_secret_ctx.request_sequence_num,
+                &mut self.data_secret_ctx.request_data_secret_backup,
+            ),
+            SessionKeyType::ResponseDataEncDecKey => (
+                &mut self.data_secret_ctx.response_data_secret,
+                &mut self.data_secret_ctx.response_sequence_num,
+                &mut self.data_secret_ctx.response_data_secret_backup,
+            ),
+            _ => Err(KeyScheduleError::InvalidSessionKeyType)?,
+        };
+
+        let prev = backup.take().ok_or(KeyScheduleError::KeyUpdateNotPending)?;
+        if !use_new_key {
+            *secret = Some(prev.secret);
+            *sequence_num = prev.sequence_num;
+        }
+
+        Ok(())
     }
 
     fn get_sequence_number(&self, session_key_type: SessionKeyType) -> KeyScheduleResult<u64> {
@@ -255,13 +379,20 @@ impl KeySchedule {
         Ok(())
     }
 
-    // Generates the handshake secret using the DHE Secret and Salt_0
+    // Generates the handshake secret using the DHE Secret (or the PSK) and Salt_0
     async fn generate_handshake_secret(&mut self) -> KeyScheduleResult<()> {
         let salt_0 = [0u8; SHA384_HASH_SIZE];
 
         // Handshake-Secret = HKDF-Extract(Salt_0, DHE-Secret)
-        if let Some(dhe_secret) = &self.master_secret_ctx.dhe_secret {
-            let extract = Hmac::hkdf_extract(HkdfSalt::Data(&salt_0), dhe_secret)
+        // For PSK sessions: Handshake-Secret = HKDF-Extract(Salt_0, PSK)
+        let shared_secret = self
+            .master_secret_ctx
+            .dhe_secret
+            .as_ref()
+            .or(self.master_secret_ctx.psk.as_ref());
+
+        if let Some(shared_secret) = shared_secret {
+            let extract = Hmac::hkdf_extract(HkdfSalt::Data(&salt_0), shared_secret)
                 .await
                 .map_err(KeyScheduleError::CaliptraApi)?;
 
@@ -502,6 +633,7 @@ impl KeySchedule {
             SpdmVersion::V11 => "spdm1.1 ",
             SpdmVersion::V12 => "spdm1.2 ",
             SpdmVersion::V13 => "spdm1.3 ",
+            SpdmVersion::V14 => "spdm1.4 ",
         }
     }
 }
@@ -510,6 +642,8 @@ impl KeySchedule {
 struct MasterSecretCtx {
     // DHE secret
     dhe_secret: Option<Cmk>,
+    // Pre-shared key (PSK sessions only)
+    psk: Option<Cmk>,
     // Handshake secret
     handshake_secret: Option<Cmk>,
     // Master secret
@@ -542,6 +676,15 @@ struct DataSecretCtx {
     request_sequence_num: u64,
     // Response direction sequence number
     response_sequence_num: u64,
+    // Previous request direction data secret, kept until the updated key is activated
+    request_data_secret_backup: Option<DataSecretBackup>,
+    // Previous response direction data secret, kept until the updated key is activated
+    response_data_secret_backup: Option<DataSecretBackup>,
+}
+
+struct DataSecretBackup {
+    secret: Cmk,
+    sequence_num: u64,
 }
 
 #[allow(dead_code)]
@@ -6,17 +6,29 @@ use crate::codec::{encode_u8_slice, Codec, CodecError, MessageBuf};
 use crate::context::MAX_SPDM_RESPONDER_BUF_SIZE;
 use crate::transport::common::SpdmTransport;
 use core::mem::size_of;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
 use libapi_caliptra::crypto::aes_gcm::Aes256GcmTag;
 use libapi_caliptra::error::CaliptraApiError;
+use libsyscall_caliptra::DefaultSyscalls;
+use libtock_alarm::Alarm;
 
+pub mod clock;
 pub mod info;
 pub mod key_schedule;
 
 // Re-export main types
-pub(crate) use info::{SessionInfo, SessionPolicy, SessionState, SessionType};
+pub(crate) use clock::SessionClock;
+pub(crate) use info::{
+    KeyUpdateOp, RequesterAuth, SessionInfo, SessionPolicy, SessionState, SessionType,
+};
 pub(crate) use key_schedule::{KeySchedule, KeyScheduleError, SessionKeyType};
 
-pub const MAX_NUM_SESSIONS: usize = 1;
+// Capacity of the session table. The number of sessions actually allowed can be
+// lowered at runtime with `SpdmContext::set_max_sessions`.
+pub const MAX_NUM_SESSIONS: usize = 4;
+// Default heartbeat period in seconds offered when both sides support HBEAT_CAP
+pub const DEFAULT_HEARTBEAT_PERIOD: u8 = 30;
 const MAX_SPDM_AEAD_ASSOCIATED_DATA_SIZE: usize = 16; // Size of the associated data for AEAD
 
 #[derive(Debug, PartialEq)]
@@ -29,6 +41,7 @@ pub enum SessionError {
     BufferTooSmall,
     EncodeAeadError,
     DecodeAeadError,
+    InvalidKeyUpdate,
     KeySchedule(KeyScheduleError),
     CaliptraApi(CaliptraApiError),
     Codec(CodecError),
@@ -36,36 +49,54 @@ pub enum SessionError {
 
 pub type SessionResult<T> = Result<T, SessionError>;
 
-#[derive(Default)]
+/// Signal raised periodically by the platform so that sessions whose heartbeat window
+/// has expired are torn down while the responder is waiting for the next request.
+/// Set with `SpdmContext::set_session_expiry_signal`.
+pub type SessionExpirySignal = Signal<CriticalSectionRawMutex, ()>;
+
+/// Session table of an SPDM responder.
+///
+/// Each `SpdmContext` owns the session table for its transport, so sessions are
+/// keyed by (transport, session ID). Every entry carries its own key schedule,
+/// transcript and handshake state, allowing multiple secured sessions to be
+/// established and used concurrently.
 pub(crate) struct SessionManager {
     active_session_id: Option<u32>,
-    handshake_phase_session_id: Option<u32>,
     sessions: [Option<SessionInfo>; MAX_NUM_SESSIONS],
+    max_sessions: usize,
     cur_responder_session_id: u16,
+    heartbeat_period: u8,
+    clock: SessionClock,
 }
 
 impl SessionManager {
     pub fn new() -> Self {
         Self {
             active_session_id: None,
-            handshake_phase_session_id: None,
-            sessions: [None; MAX_NUM_SESSIONS],
+            sessions: [const { None }; MAX_NUM_SESSIONS],
+            max_sessions: MAX_NUM_SESSIONS,
             cur_responder_session_id: 0,
+            heartbeat_period: DEFAULT_HEARTBEAT_PERIOD,
+            clock: SessionClock::default(),
         }
     }
 
     pub fn reset(&mut self) {
         self.active_session_id = None;
-        self.handshake_phase_session_id = None;
-        self.sessions = [None; MAX_NUM_SESSIONS];
+        self.sessions = [const { None }; MAX_NUM_SESSIONS];
         self.cur_responder_session_id = 0;
     }
 
     pub fn generate_session_id(&mut self, requester_session_id: u16) -> (u32, u16) {
-        let rsp_session_id = self.cur_responder_session_id;
-        let session_id = (u32::from(rsp_session_id) << 16) | u32::from(requester_session_id);
-        self.cur_responder_session_id = self.cur_responder_session_id.wrapping_add(1);
-        (session_id, rsp_session_id)
+        loop {
+            let rsp_session_id = self.cur_responder_session_id;
+            let session_id = (u32::from(rsp_session_id) << 16) | u32::from(requester_session_id);
+            self.cur_responder_session_id = self.cur_responder_session_id.wrapping_add(1);
+            // Skip IDs still held by a live session after the counter wraps around
+            if self.session_info(session_id).is_err() {
+                return (session_id, rsp_session_id);
+            }
+        }
     }
 
     pub fn set_active_session_id(&mut self, session_id: u32) {
@@ -80,22 +111,67 @@ impl SessionManager {
         self.active_session_id
     }
 
+    /// Returns the ID of the session whose handshake is in progress in the clear.
+    /// The handshake messages of such a session are not carried in the session, so
+    /// at most one of them is allowed at a time.
     pub fn handshake_phase_session_id(&self) -> Option<u32> {
-        self.handshake_phase_session_id
+        self.sessions
+            .iter()
+            .flatten()
+            .find(|info| {
+                info.handshake_in_the_clear
+                    && info.session_state == SessionState::HandshakeInProgress
+            })
+            .map(|info| info.session_id)
+    }
+
+    /// Tears down the session whose handshake has not started. This is the session
+    /// created by the KEY_EXCHANGE or PSK_EXCHANGE request being processed, as every
+    /// other session has moved on to a later state.
+    pub fn delete_unstarted_session(&mut self) {
+        for session in self.sessions.iter_mut() {
+            if session
+                .as_ref()
+                .is_some_and(|info| info.session_state == SessionState::HandshakeNotStarted)
+            {
+                *session = None;
+            }
+        }
+    }
+
+    pub fn heartbeat_period(&self) -> u8 {
+        self.heartbeat_period
     }
 
-    pub fn set_handshake_phase_session_id(&mut self, session_id: u32) {
-        self.handshake_phase_session_id = Some(session_id);
+    pub fn set_heartbeat_period(&mut self, heartbeat_period: u8) {
+        self.heartbeat_period = heartbeat_period;
     }
 
-    pub fn reset_handshake_phase_session_id(&mut self) {
-        self.handshake_phase_session_id = None;
+    pub fn set_max_sessions(&mut self, max_sessions: usize) {
+        self.max_sessions = max_sessions.clamp(1, MAX_NUM_SESSIONS);
+    }
+
+    pub fn num_sessions(&self) -> usize {
+        self.sessions.iter().filter(|s| s.is_some()).count()
     }
 
     pub fn create_session(&mut self, session_id: u32) -> SessionResult<()> {
+        let now = self.now();
+        self.create_session_at(session_id, now)
+    }
+
+    /// Creates a session whose heartbeat window starts at `now` (64-bit alarm ticks).
+    pub fn create_session_at(&mut self, session_id: u32, now: Option<u64>) -> SessionResult<()> {
+        if self.num_sessions() >= self.max_sessions {
+            return Err(SessionError::SessionsLimitReached);
+        }
+
         for i in 0..MAX_NUM_SESSIONS {
             if self.sessions[i].is_none() {
-                let session_info = SessionInfo::new(session_id);
+                let mut session_info = SessionInfo::new(session_id);
+                if let Some(now) = now {
+                    session_info.refresh_heartbeat(now);
+                }
                 self.sessions[i] = Some(session_info);
                 return Ok(());
             }
@@ -103,6 +179,35 @@ impl SessionManager {
         Err(SessionError::SessionsLimitReached)
     }
 
+    /// Tears down all sessions whose heartbeat window has expired,
+    /// releasing their slots for new sessions.
+    pub fn expire_sessions(&mut self) {
+        let (Some(now), Ok(freq)) = (self.now(), Alarm::<DefaultSyscalls>::get_frequency()) else {
+            return;
+        };
+        self.expire_sessions_at(now, freq.0);
+    }
+
+    /// Tears down all sessions whose heartbeat window has expired at `now` (64-bit alarm ticks).
+    pub fn expire_sessions_at(&mut self, now: u64, freq_hz: u32) {
+        for i in 0..MAX_NUM_SESSIONS {
+            let expired_session_id = self.sessions[i]
+                .as_ref()
+                .filter(|info| info.heartbeat_expired(now, freq_hz))
+                .map(|info| info.session_id);
+
+            if let Some(session_id) = expired_session_id {
+                let _ = self.delete_session(session_id);
+            }
+        }
+    }
+
+    /// Returns the current time in 64-bit alarm ticks, or None if the alarm is unavailable.
+    fn now(&mut self) -> Option<u64> {
+        let ticks = Alarm::<DefaultSyscalls>::get_ticks().ok()?;
+        Some(self.clock.update(ticks))
+    }
+
     pub fn set_session_state(&mut self, session_id: u32, state: SessionState) -> SessionResult<()> {
         let session_info = self
             .sessions
@@ -129,13 +234,18 @@ impl SessionManager {
         if self.active_session_id == Some(session_id) {
             self.reset_active_session_id();
         }
-        if self.handshake_phase_session_id == Some(session_id) {
-            self.reset_handshake_phase_session_id();
-        }
         Ok(())
     }
 
-    #[allow(dead_code)]
+    pub async fn key_update(
+        &mut self,
+        session_id: u32,
+        op: KeyUpdateOp,
+        tag: u8,
+    ) -> SessionResult<()> {
+        self.session_info_mut(session_id)?.key_update(op, tag).await
+    }
+
     pub fn session_info(&self, session_id: u32) -> SessionResult<&SessionInfo> {
         self.sessions
             .iter()
@@ -292,6 +402,189 @@ impl SessionManager {
 
         self.set_active_session_id(session_id);
         app_data_buffer[..app_data_len].copy_from_slice(app_data);
+
+        // Any successfully authenticated message keeps the session alive
+        if let Some(now) = self.now() {
+            self.session_info_mut(session_id)?.refresh_heartbeat(now);
+        }
         Ok(app_data_len)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::SpdmVersion;
+    use libapi_caliptra::crypto::asym::AsymAlgo;
+
+    const FREQ_HZ: u32 = 1000;
+
+    fn start_session(session_mgr: &mut SessionManager, session_id: u32, now: u64) {
+        session_mgr
+            .create_session_at(session_id, Some(now))
+            .unwrap();
+        session_mgr.session_info_mut(session_id).unwrap().init(
+            SessionPolicy::default(),
+            SessionType::MacAndEncrypt,
+            SpdmVersion::V12,
+            AsymAlgo::EccP384,
+            DEFAULT_HEARTBEAT_PERIOD,
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_expiry() {
+        let window = 2 * u64::from(DEFAULT_HEARTBEAT_PERIOD) * u64::from(FREQ_HZ);
+        let mut session_mgr = SessionManager::new();
+        start_session(&mut session_mgr, 0x0000_0001, 0);
+        start_session(&mut session_mgr, 0x0001_0002, window / 2);
+
+        // Sessions stay alive within twice the heartbeat period
+        session_mgr.expire_sessions_at(window, FREQ_HZ);
+        assert_eq!(session_mgr.num_sessions(), 2);
+
+        // Only the session without activity in the window is torn down
+        session_mgr.expire_sessions_at(window + 1, FREQ_HZ);
+        assert_eq!(session_mgr.num_sessions(), 1);
+        assert!(session_mgr.session_info(0x0000_0001).is_err());
+
+        // Activity restarts the window
+        session_mgr
+            .session_info_mut(0x0001_0002)
+            .unwrap()
+            .refresh_heartbeat(window + 1);
+        session_mgr.expire_sessions_at(2 * window, FREQ_HZ);
+        assert!(session_mgr.session_info(0x0001_0002).is_ok());
+        session_mgr.expire_sessions_at(2 * window + 2, FREQ_HZ);
+        assert_eq!(session_mgr.num_sessions(), 0);
+    }
+
+    #[test]
+    fn test_heartbeat_expiry_past_tick_wrap() {
+        let mut clock = SessionClock::default();
+        let mut session_mgr = SessionManager::new();
+        start_session(
+            &mut session_mgr,
+            0x0000_0001,
+            clock.update(u32::MAX - FREQ_HZ),
+        );
+
+        // The 32-bit tick count wraps around within the heartbeat window
+        session_mgr.expire_sessions_at(clock.update(FREQ_HZ), FREQ_HZ);
+        assert_eq!(session_mgr.num_sessions(), 1);
+
+        // A full wrap period later the session has expired, although the 32-bit
+        // tick count is back close to its value when the session was created
+        for ticks in [u32::MAX / 2, u32::MAX - FREQ_HZ, 0] {
+            clock.update(ticks);
+        }
+        session_mgr.expire_sessions_at(clock.update(FREQ_HZ), FREQ_HZ);
+        assert_eq!(session_mgr.num_sessions(), 0);
+    }
+
+    #[test]
+    fn test_concurrent_handshakes() {
+        let mut session_mgr = SessionManager::new();
+        let (session_a, _) = session_mgr.generate_session_id(0xFFFE);
+        let (session_b, _) = session_mgr.generate_session_id(0xFFFE);
+        assert_ne!(session_a, session_b);
+
+        // Both handshakes are in progress at the same time
+        for session_id in [session_a, session_b] {
+            start_session(&mut session_mgr, session_id, 0);
+            session_mgr
+                .set_session_state(session_id, SessionState::HandshakeInProgress)
+                .unwrap();
+        }
+        assert_eq!(session_mgr.num_sessions(), 2);
+        assert_eq!(session_mgr.handshake_phase_session_id(), None);
+
+        // A failed KEY_EXCHANGE only tears down the session it created
+        let (session_c, _) = session_mgr.generate_session_id(0xFFFE);
+        session_mgr.create_session_at(session_c, Some(0)).unwrap();
+        session_mgr.delete_unstarted_session();
+        assert!(session_mgr.session_info(session_c).is_err());
+        assert_eq!(session_mgr.num_sessions(), 2);
+
+        // Completing the second handshake leaves the first one in progress
+        session_mgr
+            .set_session_state(session_b, SessionState::Establishing)
+            .unwrap();
+        let state = |mgr: &SessionManager, id| mgr.session_info(id).unwrap().session_state;
+        assert_eq!(
+            state(&session_mgr, session_a),
+            SessionState::HandshakeInProgress
+        );
+        assert_eq!(state(&session_mgr, session_b), SessionState::Establishing);
+
+        // Ending one session leaves the other usable
+        session_mgr.set_active_session_id(session_b);
+        session_mgr.delete_session(session_b).unwrap();
+        assert_eq!(session_mgr.active_session_id(), None);
+        assert_eq!(
+            state(&session_mgr, session_a),
+            SessionState::HandshakeInProgress
+        );
+    }
+
+    #[test]
+    fn test_handshake_in_the_clear_tracked_per_session() {
+        let mut session_mgr = SessionManager::new();
+        start_session(&mut session_mgr, 0x0000_0001, 0);
+        start_session(&mut session_mgr, 0x0001_0002, 0);
+        session_mgr
+            .session_info_mut(0x0001_0002)
+            .unwrap()
+            .handshake_in_the_clear = true;
+        for session_id in [0x0000_0001, 0x0001_0002] {
+            session_mgr
+                .set_session_state(session_id, SessionState::HandshakeInProgress)
+                .unwrap();
+        }
+        assert_eq!(session_mgr.handshake_phase_session_id(), Some(0x0001_0002));
+
+        // The handshake phase ends with FINISH
+        session_mgr
+            .set_session_state(0x0001_0002, SessionState::Establishing)
+            .unwrap();
+        assert_eq!(session_mgr.handshake_phase_session_id(), None);
+    }
+
+    #[test]
+    fn test_session_limit() {
+        let mut session_mgr = SessionManager::new();
+        for i in 0..MAX_NUM_SESSIONS as u32 {
+            session_mgr.create_session_at(i, None).unwrap();
+        }
+        assert_eq!(
+            session_mgr.create_session_at(MAX_NUM_SESSIONS as u32, None),
+            Err(SessionError::SessionsLimitReached)
+        );
+
+        // A lower limit applies to new sessions only
+        session_mgr.set_max_sessions(2);
+        session_mgr.delete_session(0).unwrap();
+        assert_eq!(
+            session_mgr.create_session_at(0, None),
+            Err(SessionError::SessionsLimitReached)
+        );
+        session_mgr.reset();
+        assert_eq!(session_mgr.num_sessions(), 0);
+        session_mgr.create_session_at(0, None).unwrap();
+        session_mgr.create_session_at(1, None).unwrap();
+        assert_eq!(
+            session_mgr.create_session_at(2, None),
+            Err(SessionError::SessionsLimitReached)
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_disabled() {
+        let mut session_mgr = SessionManager::new();
+        session_mgr.create_session_at(0x0000_0001, Some(0)).unwrap();
+
+        // Sessions without a heartbeat period never expire
+        session_mgr.expire_sessions_at(u64::MAX, FREQ_HZ);
+        assert_eq!(session_mgr.num_sessions(), 1);
+    }
+}
@@ -33,6 +33,8 @@ pub(crate) struct ConnectionInfo {
     peer_algorithms: DeviceAlgorithms,
     multi_key_conn_rsp: bool,
     handshake_in_the_clear: bool,
+    // Slot of the Requester certificate authenticated with basic mutual authentication
+    requester_auth_slot_id: Option<u8>,
 }
 
 impl Default for ConnectionInfo {
@@ -45,6 +47,7 @@ impl Default for ConnectionInfo {
             peer_algorithms: DeviceAlgorithms::default(),
             multi_key_conn_rsp: false,
             handshake_in_the_clear: false,
+            requester_auth_slot_id: None,
         }
     }
 }
@@ -104,11 +107,22 @@ impl ConnectionInfo {
         self.multi_key_conn_rsp
     }
 
+    /// Returns the slot of the Requester certificate if the Requester was authenticated
+    /// with basic mutual authentication.
+    pub fn requester_auth_slot_id(&self) -> Option<u8> {
+        self.requester_auth_slot_id
+    }
+
+    pub fn set_requester_auth_slot_id(&mut self, slot_id: Option<u8>) {
+        self.requester_auth_slot_id = slot_id;
+    }
+
     fn reset(&mut self) {
         self.version_number = SpdmVersion::default();
         self.state = ConnectionState::NotStarted;
         self.peer_capabilities = DeviceCapabilities::default();
         self.peer_algorithms = DeviceAlgorithms::default();
+        self.requester_auth_slot_id = None;
     }
 }
 
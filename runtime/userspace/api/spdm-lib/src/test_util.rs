@@ -0,0 +1,294 @@
+// Licensed under the Apache-2.0 license
+
+//! Host test support for driving the command handlers without a transport or Caliptra.
+//!
+//! Handlers are called directly with a context whose connection has already been
+//! negotiated. Only handlers that complete without a Caliptra syscall can be driven
+//! this way.
+
+extern crate alloc;
+
+use crate::cert_store::{CertStoreError, CertStoreResult, SpdmCertStore};
+use crate::codec::{Codec, MessageBuf};
+use crate::context::{SpdmContext, MAX_SPDM_RESPONDER_BUF_SIZE};
+use crate::key_pair_store::{
+    KeyPairInfo, KeyPairStoreError, KeyPairStoreResult, KeyPairUpdate, SetKeyPairInfoOperation,
+    SpdmKeyPairStore, ECC_P384_PUBLIC_KEY_INFO, MLDSA87_PUBLIC_KEY_INFO,
+};
+use crate::measurements::{
+    MeasurementsError, MeasurementsResult, SpdmMeasurementValue, SpdmMeasurements,
+};
+use crate::protocol::*;
+use crate::state::ConnectionState;
+use crate::transport::common::{SpdmTransport, TransportError, TransportResult};
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use core::future::Future;
+use core::pin::pin;
+use core::sync::atomic::{AtomicU16, Ordering};
+use core::task::{Context, Poll, Waker};
+use libapi_caliptra::crypto::asym::AsymAlgo;
+use libapi_caliptra::crypto::hash::SHA384_HASH_SIZE;
+
+const SUPPORTED_VERSIONS: &[SpdmVersion] = &[SpdmVersion::V12, SpdmVersion::V13];
+
+/// Polls the future once. Panics if the future waits, which only a syscall does.
+pub(crate) fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    match fut.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future is waiting on a syscall"),
+    }
+}
+
+/// Transport whose messages are exchanged by the test itself.
+pub(crate) struct TestTransport;
+
+#[async_trait]
+impl SpdmTransport for TestTransport {
+    async fn send_request<'a>(
+        &mut self,
+        _dest_eid: u8,
+        _req: &mut MessageBuf<'a>,
+        _secure: Option<bool>,
+    ) -> TransportResult<()> {
+        Err(TransportError::OperationNotSupported)
+    }
+
+    async fn receive_response<'a>(&mut self, _rsp: &mut MessageBuf<'a>) -> TransportResult<bool> {
+        Err(TransportError::OperationNotSupported)
+    }
+
+    async fn receive_request<'a>(&mut self, _req: &mut MessageBuf<'a>) -> TransportResult<bool> {
+        Err(TransportError::OperationNotSupported)
+    }
+
+    async fn send_response<'a>(
+        &mut self,
+        _resp: &mut MessageBuf<'a>,
+        _secure: bool,
+    ) -> TransportResult<()> {
+        Err(TransportError::OperationNotSupported)
+    }
+
+    fn max_message_size(&self) -> TransportResult<usize> {
+        Ok(MAX_SPDM_RESPONDER_BUF_SIZE)
+    }
+
+    fn header_size(&self) -> usize {
+        0
+    }
+}
+
+/// Certificate store with a single unprovisioned slot.
+pub(crate) struct TestCertStore;
+
+#[async_trait]
+impl SpdmCertStore for TestCertStore {
+    fn slot_count(&self) -> u8 {
+        1
+    }
+
+    async fn is_provisioned(&self, _slot_id: u8, _asym_algo: AsymAlgo) -> bool {
+        false
+    }
+
+    async fn cert_chain_len(&self, _asym_algo: AsymAlgo, _slot_id: u8) -> CertStoreResult<usize> {
+        Err(CertStoreError::UnprovisionedSlot)
+    }
+
+    async fn get_cert_chain<'a>(
+        &self,
+        _slot_id: u8,
+        _asym_algo: AsymAlgo,
+        _offset: usize,
+        _cert_portion: &'a mut [u8],
+    ) -> CertStoreResult<usize> {
+        Err(CertStoreError::UnprovisionedSlot)
+    }
+
+    async fn root_cert_hash<'a>(
+        &self,
+        _slot_id: u8,
+        _asym_algo: AsymAlgo,
+        _cert_hash: &'a mut [u8; SHA384_HASH_SIZE],
+    ) -> CertStoreResult<()> {
+        Err(CertStoreError::UnprovisionedSlot)
+    }
+
+    async fn sign_hash<'a>(
+        &self,
+        _slot_id: u8,
+        _asym_algo: AsymAlgo,
+        _hash: &'a [u8; SHA384_HASH_SIZE],
+        _signature: &'a mut [u8],
+    ) -> CertStoreResult<()> {
+        Err(CertStoreError::UnprovisionedSlot)
+    }
+
+    async fn key_pair_id(&self, _slot_id: u8) -> Option<u8> {
+        None
+    }
+
+    async fn cert_info(&self, _slot_id: u8) -> Option<CertificateInfo> {
+        None
+    }
+
+    async fn key_usage_mask(&self, _slot_id: u8) -> Option<KeyUsageMask> {
+        None
+    }
+}
+
+/// Device without measurements.
+pub(crate) struct TestMeasurementValue;
+
+#[async_trait]
+impl SpdmMeasurementValue for TestMeasurementValue {
+    async fn get_measurement_value(
+        &mut self,
+        _index: u8,
+        _nonce: &[u8],
+        _asym_algo: AsymAlgo,
+        _measurement: &mut [u8],
+    ) -> MeasurementsResult<usize> {
+        Err(MeasurementsError::InvalidIndex)
+    }
+}
+
+/// Key pair store with an ECC P-384 key pair (KeyPairID 1) whose key usage can be
+/// changed, associated with slot 0, and an ML-DSA-87 key pair (KeyPairID 2) with
+/// fixed properties.
+pub(crate) struct TestKeyPairStore {
+    pub ecc384_key_usage: AtomicU16,
+}
+
+impl TestKeyPairStore {
+    pub fn new() -> Self {
+        Self {
+            ecc384_key_usage: AtomicU16::new(Self::key_usage_capabilities().0),
+        }
+    }
+
+    pub fn key_usage_capabilities() -> KeyUsageMask {
+        let mut key_usage = KeyUsageMask::default();
+        key_usage.set_challenge_usage(1);
+        key_usage.set_key_exch_usage(1);
+        key_usage
+    }
+}
+
+#[async_trait]
+impl SpdmKeyPairStore for TestKeyPairStore {
+    fn total_key_pairs(&self) -> u8 {
+        2
+    }
+
+    async fn key_pair_info<'a>(
+        &self,
+        key_pair_id: u8,
+        public_key_info: &'a mut [u8],
+    ) -> KeyPairStoreResult<(KeyPairInfo, usize)> {
+        let key_usage = Self::key_usage_capabilities();
+        let mut info = KeyPairInfo {
+            key_usage_capabilities: key_usage,
+            current_key_usage: key_usage,
+            assoc_cert_slot_mask: 0x01,
+            ..Default::default()
+        };
+        let key_info = match key_pair_id {
+            1 => {
+                info.capabilities.set_key_usage_cap(1);
+                info.current_key_usage =
+                    KeyUsageMask(self.ecc384_key_usage.load(Ordering::Relaxed));
+                info.asym_algo_capabilities.set_ecc384(1);
+                info.current_asym_algo.set_ecc384(1);
+                ECC_P384_PUBLIC_KEY_INFO
+            }
+            2 => {
+                info.pqc_asym_algo_capabilities.set_ml_dsa_87(1);
+                info.current_pqc_asym_algo.set_ml_dsa_87(1);
+                MLDSA87_PUBLIC_KEY_INFO
+            }
+            _ => Err(KeyPairStoreError::InvalidKeyPairId)?,
+        };
+        public_key_info[..key_info.len()].copy_from_slice(key_info);
+        Ok((info, key_info.len()))
+    }
+
+    async fn set_key_pair_info(
+        &self,
+        key_pair_id: u8,
+        operation: SetKeyPairInfoOperation,
+        update: KeyPairUpdate,
+    ) -> KeyPairStoreResult<()> {
+        if key_pair_id != 1 || operation != SetKeyPairInfoOperation::Change {
+            Err(KeyPairStoreError::UnsupportedOperation)?;
+        }
+        self.ecc384_key_usage
+            .store(update.key_usage.0, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Creates a context whose connection negotiated `version`, with the local capabilities
+/// and the ECC P-384 and SHA-384 algorithms.
+pub(crate) fn negotiated_context<'a>(
+    version: SpdmVersion,
+    flags: CapabilityFlags,
+    transport: &'a mut TestTransport,
+    cert_store: &'a TestCertStore,
+    meas_value: &'a mut TestMeasurementValue,
+) -> SpdmContext<'a> {
+    let capabilities = DeviceCapabilities {
+        ct_exponent: 0,
+        flags,
+        data_transfer_size: MAX_SPDM_RESPONDER_BUF_SIZE as u32,
+        max_spdm_msg_size: MAX_SPDM_RESPONDER_BUF_SIZE as u32,
+    };
+
+    let mut ctx = SpdmContext::new(
+        SUPPORTED_VERSIONS,
+        SUPPORTED_VERSIONS,
+        transport,
+        capabilities,
+        LocalDeviceAlgorithms::default(),
+        cert_store,
+        None,
+        SpdmMeasurements::new(&[], meas_value),
+        None,
+    )
+    .unwrap();
+
+    let mut peer_algorithms = DeviceAlgorithms::default();
+    peer_algorithms
+        .base_asym_algo
+        .set_tpm_alg_ecdsa_ecc_nist_p384(1);
+    peer_algorithms.base_hash_algo.set_tpm_alg_sha_384(1);
+
+    let connection_info = &mut ctx.state.connection_info;
+    connection_info.set_version_number(version);
+    connection_info.set_peer_capabilities(capabilities);
+    connection_info.set_peer_algorithms(peer_algorithms);
+    connection_info.set_state(ConnectionState::AlgorithmsNegotiated);
+    ctx
+}
+
+/// Loads the request into the buffer, as received from the transport.
+pub(crate) fn request_buf<'a>(buf: &'a mut [u8], msg: &[u8]) -> MessageBuf<'a> {
+    buf[..msg.len()].copy_from_slice(msg);
+    let mut req = MessageBuf::new(buf);
+    req.put_data(msg.len()).unwrap();
+    req
+}
+
+/// Loads the request into the buffer and decodes its SPDM header.
+pub(crate) fn request<'a>(buf: &'a mut [u8], msg: &[u8]) -> (SpdmMsgHdr, MessageBuf<'a>) {
+    let mut req = request_buf(buf, msg);
+    let spdm_hdr = SpdmMsgHdr::decode(&mut req).unwrap();
+    (spdm_hdr, req)
+}
+
+/// Returns the response generated in the request buffer.
+pub(crate) fn response<'a>(rsp: &'a MessageBuf) -> &'a [u8] {
+    rsp.data(rsp.data_len()).unwrap()
+}
@@ -19,9 +19,8 @@ pub type TranscriptResult<T> = Result<T, TranscriptError>;
 
 // Buffer size constants
 const VCA_BUFFER_SIZE: usize = 256;
-const DIGESTS_BUFFER_SIZE: usize = 4
-    + (SHA384_HASH_SIZE + MAX_CERT_SLOTS_SUPPORTED as usize)
-    + 4 * MAX_CERT_SLOTS_SUPPORTED as usize;
+// DIGESTS header followed by, for each slot, the digest, KeyPairID, CertificateInfo and KeyUsageMask
+const DIGESTS_BUFFER_SIZE: usize = 4 + (SHA384_HASH_SIZE + 4) * MAX_CERT_SLOTS_SUPPORTED as usize;
 
 // Type aliases for buffer types
 type VcaBuffer = ArrayVec<u8, VCA_BUFFER_SIZE>;
@@ -31,6 +30,7 @@ pub enum TranscriptContext {
     Vca,
     Digests,
     M1,
+    MutM1,
     L1,
     Th,
 }
@@ -50,6 +50,10 @@ pub(crate) struct Transcript {
     // B = Concatenate (GET_DIGESTS, DIGESTS, GET_CERTIFICATE, CERTIFICATE)
     // C = Concatenate (CHALLENGE, CHALLENGE_AUTH excluding signature)
     hash_ctx_m1: Option<HashContext>,
+    // Hash context for the `M1` of the Requester in basic mutual authentication
+    // MutM1 = Concatenate(A, B', C')
+    // where B' and C' are the encapsulated equivalents of B and C
+    hash_ctx_mut_m1: Option<HashContext>,
     // Hash Context for `L1`
     // L1 = Concatenate(A, M) if SPDM_VERSION >= 1.2 or L1 = Concatenate(M) if SPDM_VERSION < 1.2
     // where
@@ -64,6 +68,7 @@ impl Transcript {
             vca_buf: VcaBuffer::new(),
             digests_buf: None,
             hash_ctx_m1: None,
+            hash_ctx_mut_m1: None,
             hash_ctx_l1: None,
         }
     }
@@ -85,6 +90,7 @@ impl Transcript {
         self.vca_buf.clear();
         self.digests_buf = None;
         self.hash_ctx_m1 = None;
+        self.hash_ctx_mut_m1 = None;
         self.hash_ctx_l1 = None;
     }
 
@@ -97,6 +103,7 @@ impl Transcript {
             TranscriptContext::Vca => self.vca_buf.clear(),
             TranscriptContext::Digests => self.digests_buf = None,
             TranscriptContext::M1 => self.hash_ctx_m1 = None,
+            TranscriptContext::MutM1 => self.hash_ctx_mut_m1 = None,
             TranscriptContext::L1 => self.hash_ctx_l1 = None,
             _ => {}
         }
@@ -121,6 +128,7 @@ impl Transcript {
             TranscriptContext::Vca => self.append_vca(data),
             TranscriptContext::Digests => self.append_digests(data),
             TranscriptContext::M1 => self.append_m1(data).await,
+            TranscriptContext::MutM1 => self.append_mut_m1(data).await,
             TranscriptContext::L1 => self.append_l1(self.spdm_version, session_info, data).await,
             TranscriptContext::Th => {
                 if let Some(session) = session_info {
@@ -164,6 +172,19 @@ impl Transcript {
                     Err(TranscriptError::InvalidState)
                 }
             }
+            TranscriptContext::MutM1 => {
+                if let Some(ctx) = &mut self.hash_ctx_mut_m1 {
+                    ctx.finalize(hash)
+                        .await
+                        .map_err(TranscriptError::CaliptraApi)?;
+                    if finish_hash {
+                        self.hash_ctx_mut_m1 = None;
+                    }
+                    Ok(())
+                } else {
+                    Err(TranscriptError::InvalidState)
+                }
+            }
             TranscriptContext::L1 => {
                 match session_info {
                     Some(session) => {
@@ -254,6 +275,23 @@ impl Transcript {
         }
     }
 
+    async fn append_mut_m1(&mut self, data: &[u8]) -> TranscriptResult<()> {
+        if let Some(ctx) = &mut self.hash_ctx_mut_m1 {
+            ctx.update(data).await.map_err(TranscriptError::CaliptraApi)
+        } else {
+            let vca_data = self.vca_buf.as_slice();
+            let mut ctx = HashContext::new();
+            ctx.init(HashAlgoType::SHA384, Some(vca_data))
+                .await
+                .map_err(TranscriptError::CaliptraApi)?;
+            ctx.update(data)
+                .await
+                .map_err(TranscriptError::CaliptraApi)?;
+            self.hash_ctx_mut_m1 = Some(ctx);
+            Ok(())
+        }
+    }
+
     async fn append_l1(
         &mut self,
         spdm_version: SpdmVersion,
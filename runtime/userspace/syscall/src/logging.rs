@@ -48,6 +48,17 @@ impl<S: Syscalls> LoggingSyscall<S> {
             .map(|x: u32| x as usize)
     }
 
+    /// Gets the number of bytes of the logging storage used by the entries of the log.
+    ///
+    /// # Returns
+    /// - `Ok(used)` - The used size in bytes.
+    /// - `Err(ErrorCode)` - An error code if the operation fails.
+    pub fn get_used_size(&self) -> Result<usize, ErrorCode> {
+        S::command(self.driver_num, logging_cmd::GET_USED, 0, 0)
+            .to_result()
+            .map(|x: u32| x as usize)
+    }
+
     /// Appends an entry to the log asynchronously.
     ///
     /// # Arguments
@@ -190,4 +201,5 @@ mod logging_cmd {
     pub const SYNC: u32 = 4;
     pub const ERASE: u32 = 5;
     pub const GET_CAP: u32 = 6;
+    pub const GET_USED: u32 = 7;
 }
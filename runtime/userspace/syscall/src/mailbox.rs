@@ -229,6 +229,36 @@ impl<S: Syscalls> Mailbox<S> {
         .await
         .map_err(MailboxError::ErrorCode)
     }
+
+    /// Requests production debug unlock through the debug service register. Caliptra ROM
+    /// handles the request after the next reset.
+    ///
+    /// # Returns
+    /// - `Err(ErrorCode::NoSupport)` if the device is not in the production lifecycle with
+    ///   DEBUG_INTENT set.
+    pub fn request_prod_debug_unlock(&self) -> Result<(), ErrorCode> {
+        S::command(
+            self.driver_num,
+            mailbox_cmd::REQUEST_PROD_DEBUG_UNLOCK,
+            0,
+            0,
+        )
+        .to_result::<(), ErrorCode>()
+    }
+
+    /// Returns true while Caliptra ROM waits for the production debug unlock request and token.
+    pub fn prod_debug_unlock_in_progress(&self) -> Result<bool, ErrorCode> {
+        S::command(self.driver_num, mailbox_cmd::PROD_DEBUG_UNLOCK_STATUS, 0, 0)
+            .to_result::<u32, ErrorCode>()
+            .map(|in_progress| in_progress != 0)
+    }
+
+    /// Returns the production debug unlock level granted by Caliptra, or 0 if none was granted.
+    pub fn prod_debug_unlock_level(&self) -> Result<u8, ErrorCode> {
+        S::command(self.driver_num, mailbox_cmd::PROD_DEBUG_UNLOCK_LEVEL, 0, 0)
+            .to_result::<u32, ErrorCode>()
+            .map(|level| level as u8)
+    }
 }
 #[async_trait(?Send)]
 pub trait PayloadStream {
@@ -253,6 +283,9 @@ mod mailbox_cmd {
     pub const START_CHUNKED_REQUEST: u32 = 2;
     pub const NEXT_PAYLOAD_CHUNK: u32 = 3;
     pub const EXECUTE_CHUNKED_REQUEST: u32 = 4;
+    pub const REQUEST_PROD_DEBUG_UNLOCK: u32 = 5;
+    pub const PROD_DEBUG_UNLOCK_STATUS: u32 = 6;
+    pub const PROD_DEBUG_UNLOCK_LEVEL: u32 = 7;
 }
 
 /// Buffer IDs for mailbox read operations.
@@ -3,9 +3,12 @@
 use crate::DefaultSyscalls;
 use core::marker::PhantomData;
 use libtock_platform::share;
-use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
+use libtock_platform::{AllowRo, DefaultConfig, ErrorCode, Syscalls};
 use libtockasync::TockSubscribe;
 
+/// Length of the endpoint UUID
+pub const MCTP_UUID_LEN: usize = 16;
+
 type EndpointId = u8;
 type Tag = u8;
 
@@ -229,10 +232,72 @@ impl<S: Syscalls> Mctp<S> {
         Ok((recv_len, info.into()))
     }
 
+    /// Cancel the pending `receive_response` call.
+    /// The pending call completes with a response length of 0, so that its future can be dropped.
+    ///
+    /// # Returns
+    /// * `()` - On success
+    /// * `ErrorCode` - The error code on failure
+    pub fn cancel_receive_response(&self) -> Result<(), ErrorCode> {
+        S::command(self.driver_num, command::CANCEL_RECEIVE_RESPONSE, 0, 0).to_result()
+    }
+
     pub fn max_message_size(&self) -> Result<u32, ErrorCode> {
         S::command(self.driver_num, command::GET_MAX_MESSAGE_SIZE, 0, 0).to_result()
     }
 
+    /// Set the UUID reported by the Get Endpoint UUID control command.
+    /// Only the vendor defined message driver is allowed to set the endpoint UUID.
+    ///
+    /// # Arguments
+    /// * `uuid` - The endpoint UUID
+    ///
+    /// # Returns
+    /// * `()` - On success
+    /// * `ErrorCode` - The error code on failure
+    pub fn set_endpoint_uuid(&self, uuid: &[u8; MCTP_UUID_LEN]) -> Result<(), ErrorCode> {
+        if self.driver_num != driver_num::MCTP_CALIPTRA {
+            Err(ErrorCode::Invalid)?;
+        }
+
+        share::scope::<AllowRo<_, { driver_num::MCTP_CALIPTRA }, { allow_ro::MESSAGE_WRITE }>, _, _>(
+            |handle| {
+                S::allow_ro::<
+                    DefaultConfig,
+                    { driver_num::MCTP_CALIPTRA },
+                    { allow_ro::MESSAGE_WRITE },
+                >(handle, uuid)?;
+
+                S::command(self.driver_num, command::SET_ENDPOINT_UUID, 0, 0)
+                    .to_result::<(), ErrorCode>()
+            },
+        )
+    }
+
+    /// Register a vendor defined message set reported by the
+    /// Get Vendor Defined Message Support control command.
+    ///
+    /// # Arguments
+    /// * `vendor_id` - The PCI vendor ID of the message set
+    /// * `cmd_set_version` - The version of the vendor defined command set
+    ///
+    /// # Returns
+    /// * `()` - On success
+    /// * `ErrorCode` - The error code on failure
+    pub fn register_vendor_defined_msg_support(
+        &self,
+        vendor_id: u16,
+        cmd_set_version: u16,
+    ) -> Result<(), ErrorCode> {
+        S::command(
+            self.driver_num,
+            command::REGISTER_VDM_SUPPORT,
+            vendor_id as u32,
+            cmd_set_version as u32,
+        )
+        .to_result()
+    }
+
     pub fn msg_type(&self) -> Result<u8, ErrorCode> {
         match self.driver_num {
             driver_num::MCTP_SPDM => Ok(5),
@@ -262,6 +327,9 @@ pub mod driver_num {
 /// - `3` - Send MCTP request
 /// - `4` - Send MCTP response
 /// - `5` - Get maximum message size supported by the MCTP driver
+/// - `6` - Set the endpoint UUID
+/// - `7` - Register vendor defined message support
+/// - `8` - Cancel receive MCTP response
 mod command {
     pub const EXISTS: u32 = 0;
     pub const RECEIVE_REQUEST: u32 = 1;
@@ -269,6 +337,9 @@ mod command {
     pub const SEND_REQUEST: u32 = 3;
     pub const SEND_RESPONSE: u32 = 4;
     pub const GET_MAX_MESSAGE_SIZE: u32 = 5;
+    pub const SET_ENDPOINT_UUID: u32 = 6;
+    pub const REGISTER_VDM_SUPPORT: u32 = 7;
+    pub const CANCEL_RECEIVE_RESPONSE: u32 = 8;
 }
 
 mod subscribe {
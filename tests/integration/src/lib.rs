@@ -31,6 +31,7 @@ mod test {
     use mcu_builder::flash_image::build_flash_image_bytes;
     use mcu_builder::{CaliptraBuilder, EmulatorBinaries, FirmwareBinaries, ImageCfg, TARGET};
     use mcu_hw_model::{DefaultHwModel, Fuses, InitParams, McuHwModel};
+    use mcu_rom_common::LifecycleControllerState;
     use mcu_testing_common::{DeviceLifecycle, MCU_RUNNING};
     use random_port::PortPicker;
     use std::sync::atomic::{AtomicU32, Ordering};
@@ -68,6 +69,11 @@ mod test {
         pub flash_boot: bool,
         /// ROM feature flag. If set, compiles a ROM with this feature enabled.
         pub rom_feature: Option<&'a str>,
+        /// Lifecycle state of the device. The Dev state boots Caliptra in the manufacturing
+        /// lifecycle, in which it generates the IDevID CSR.
+        pub lifecycle_controller_state: Option<LifecycleControllerState>,
+        /// Sets the DEBUG_INTENT strap, without which production debug unlock is not requested.
+        pub debug_intent: bool,
     }
 
     static PROJECT_ROOT: LazyLock<PathBuf> = LazyLock::new(|| {
@@ -360,6 +366,8 @@ mod test {
             otp_memory: otp_memory.as_deref(),
             primary_flash_initial_contents: flash_image,
             flash_boot: params.flash_boot,
+            lifecycle_controller_state: params.lifecycle_controller_state,
+            debug_intent: params.debug_intent,
             ..Default::default()
         })
         .unwrap()
@@ -408,6 +416,12 @@ mod test {
             i3c_port.clone(),
         ];
 
+        // MCTP over serial is exchanged over the UART socket
+        if feature == "test-mctp-serial" {
+            let uart_port = PortPicker::new().random(true).pick().unwrap();
+            emulator_args.extend(["--uart-port".to_string(), uart_port.to_string()]);
+        }
+
         // map the memory map to the emulator
         emulator_args.extend([
             "--rom-offset".to_string(),
@@ -787,13 +801,23 @@ mod test {
     run_test!(test_log_flash_linear);
     run_test!(test_log_flash_circular);
     run_test!(test_log_flash_usermode, example_app);
+    run_test!(test_mctp_bridge);
+    run_test!(test_mctp_serial);
     run_test!(test_mctp_ctrl_cmds);
+    run_test!(test_mctp_pcie_vdm);
     // run_test!(test_mctp_user_loopback, example_app);
     run_test!(test_pldm_discovery);
     run_test!(test_pldm_fw_update);
+    run_test!(test_pldm_platform);
+    run_test!(test_pldm_fru);
     run_test!(test_mctp_spdm_responder_conformance, nightly);
     run_test!(test_doe_spdm_responder_conformance, nightly);
     run_test!(test_doe_spdm_tdisp_ide_validator, nightly);
+    run_test!(test_doe_spdm_pqc_requester, nightly);
+    run_test!(test_doe_spdm_key_update, nightly);
+    run_test!(test_doe_spdm_psk, nightly);
+    run_test!(test_doe_spdm_mut_auth, nightly);
+    run_test!(test_doe_spdm_set_cert, nightly);
     run_test!(test_mci, example_app);
     run_test!(test_mcu_mbox_driver);
     run_test!(test_mcu_mbox_soc_requester_loopback, example_app);
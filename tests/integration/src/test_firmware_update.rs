@@ -46,6 +46,8 @@ mod test {
         update_soc_images_paths: Vec<PathBuf>,
         update_runtime_firmware: Option<PathBuf>,
         pldm_fw_pkg_path: Option<PathBuf>,
+        // Package data of the firmware device ID record, used by the device as the SoC manifest
+        update_package_data: Option<Vec<u8>>,
         partition_table: Option<PartitionTable>,
         builder: Option<CaliptraBuilder>,
         flash_offset: usize,
@@ -326,8 +328,10 @@ mod test {
             std::fs::read(update_flash_image_path.clone()).expect("Failed to read flash image");
         // Retain the first 1024 bytes flash_image in the PLDM package.
         let truncated_flash_image = &flash_image[..1024];
-        let pldm_manifest =
+        let mut pldm_manifest =
             get_streaming_boot_pldm_fw_manifest(&get_device_uuid(), truncated_flash_image);
+        pldm_manifest.firmware_device_id_records[0].firmware_device_package_data =
+            new_opts.update_package_data.clone();
         let pldm_fw_pkg_path = create_pldm_fw_package(&pldm_manifest);
         new_opts.pldm_fw_pkg_path = Some(pldm_fw_pkg_path);
         let secondary_flash_image_path = tempfile::NamedTempFile::new()
@@ -442,6 +446,50 @@ mod test {
         assert_ne!(0, test);
     }
 
+    /// The device uses the package data as the SoC manifest, instead of the invalid manifest of
+    /// the image.
+    fn test_package_data_manifest(opts: &TestOptions) {
+        let mut opts = opts.clone();
+
+        let invalid_manifest_path = tempfile::NamedTempFile::new()
+            .expect("Failed to create temp file")
+            .path()
+            .to_path_buf();
+        std::fs::write(
+            &invalid_manifest_path,
+            [0xde, 0xad, 0xbe, 0xef].repeat(1024),
+        )
+        .expect("Failed to write invalid manifest");
+
+        let (_, update_flash_image_path) = create_flash_image(
+            opts.update_caliptra_fw.clone(),
+            Some(invalid_manifest_path),
+            opts.update_runtime_firmware.clone(),
+            None,
+            0,
+            opts.update_soc_images_paths.clone(),
+        );
+
+        opts.update_flash_image_path = Some(update_flash_image_path);
+        opts.update_package_data = Some(
+            std::fs::read(opts.update_soc_manifest.as_ref().unwrap())
+                .expect("Failed to read SOC manifest"),
+        );
+        let opts = fast_update_options(&opts);
+        let test = run_runtime_with_options(&opts);
+        assert_eq!(0, test);
+    }
+
+    /// The device rejects the update when the package data is not a valid SoC manifest, even
+    /// if the manifest of the image is valid.
+    fn test_invalid_package_data(opts: &TestOptions) {
+        let mut opts = opts.clone();
+        opts.update_package_data = Some([0xde, 0xad, 0xbe, 0xef].repeat(256));
+        let opts = fast_update_options(&opts);
+        let test = run_runtime_with_options(&opts);
+        assert_ne!(0, test);
+    }
+
     fn test_invalid_mcu_image(opts: &TestOptions) {
         let mut opts = opts.clone();
 
@@ -705,6 +753,7 @@ mod test {
             update_runtime_firmware: Some(test_runtime),
             update_soc_images_paths: soc_images_paths,
             pldm_fw_pkg_path: Some(pldm_fw_pkg_path),
+            update_package_data: None,
             partition_table: Some(partition_table),
             builder: Some(builder),
             flash_offset,
@@ -845,6 +894,7 @@ mod test {
             update_runtime_firmware: Some(update_runtime_firmware),
             update_soc_images_paths,
             pldm_fw_pkg_path: Some(pldm_fw_pkg_path),
+            update_package_data: None,
             partition_table: None,
             builder: Some(builder.clone()),
             flash_offset: 0,
@@ -892,6 +942,22 @@ mod test {
         lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
 
+    #[test]
+    fn test_firmware_update_flash_package_data_manifest() {
+        let lock = TEST_LOCK.lock().unwrap();
+        let opts = create_firmware_update_test_options(true);
+        test_package_data_manifest(&opts);
+        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    #[test]
+    fn test_firmware_update_flash_invalid_package_data() {
+        let lock = TEST_LOCK.lock().unwrap();
+        let opts = create_firmware_update_test_options(true);
+        test_invalid_package_data(&opts);
+        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
     #[test]
     fn test_firmware_update_flash_invalid_mcu_image() {
         let lock = TEST_LOCK.lock().unwrap();
@@ -908,10 +974,10 @@ mod test {
         lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     }
 
-    /// FPGA-specific streaming firmware update test that uses cached PLDM_FW_PKG artifacts
+    /// Sets PLDM_FW_PKG to the package built for the FPGA if it is not set. Returns false if
+    /// no package is available.
     #[cfg(feature = "fpga_realtime")]
-    #[test]
-    fn test_firmware_update_streaming_fpga() {
+    fn set_fpga_pldm_fw_pkg(test_name: &str) -> bool {
         if env::var("PLDM_FW_PKG").is_err() {
             if let Ok(binaries) = mcu_builder::FirmwareBinaries::from_env() {
                 // If PLDM_FW_PKG is not specified, we will use the PLDM firmware package
@@ -930,13 +996,53 @@ mod test {
                     test_pldm_pkg_path.to_string_lossy().to_string(),
                 );
             } else {
-                println!("Skipping test_firmware_update_streaming_fpga as PLDM_FW_PKG is not set");
-                return;
+                println!("Skipping {} as PLDM_FW_PKG is not set", test_name);
+                return false;
             }
         }
+        true
+    }
+
+    /// FPGA-specific streaming firmware update test that uses cached PLDM_FW_PKG artifacts
+    #[cfg(feature = "fpga_realtime")]
+    #[test]
+    fn test_firmware_update_streaming_fpga() {
+        if !set_fpga_pldm_fw_pkg("test_firmware_update_streaming_fpga") {
+            return;
+        }
         crate::test_pldm_fw_update::test::start_pldm_test(
             "test_firmware_update_streaming",
             log::LevelFilter::Info,
         );
     }
+
+    /// Updates the device through the Firmware Update API without self-contained activation,
+    /// then checks that the device is not reset and reports the image as pending.
+    #[cfg(feature = "fpga_realtime")]
+    #[test]
+    fn test_firmware_update_streaming_fpga_pending_image() {
+        if !set_fpga_pldm_fw_pkg("test_firmware_update_streaming_fpga_pending_image") {
+            return;
+        }
+        crate::test_pldm_fw_update::test::start_pldm_test_with(
+            "test_firmware_update_streaming",
+            log::LevelFilter::Info,
+            crate::test_pldm_fw_update::test::PldmFwUpdateTest::test_fw_update_pending_image,
+        );
+    }
+
+    /// Leaves an update pending as above, then resets the device and checks that the reset
+    /// activates the pending image: the updated firmware runs its test and exits.
+    #[cfg(feature = "fpga_realtime")]
+    #[test]
+    fn test_firmware_update_streaming_fpga_pending_image_reset() {
+        if !set_fpga_pldm_fw_pkg("test_firmware_update_streaming_fpga_pending_image_reset") {
+            return;
+        }
+        crate::test_pldm_fw_update::test::start_pldm_test_with_reset(
+            "test_firmware_update_streaming",
+            log::LevelFilter::Info,
+            crate::test_pldm_fw_update::test::PldmFwUpdateTest::test_fw_update_pending_image,
+        );
+    }
 }
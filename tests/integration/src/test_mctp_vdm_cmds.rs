@@ -10,21 +10,50 @@ pub mod test {
     use crate::test::{finish_runtime_hw_model, start_runtime_hw_model, TestParams, TEST_LOCK};
     use log::{info, LevelFilter};
     use mctp_vdm_common::codec::VdmCodec;
+    use mctp_vdm_common::message::authorize_debug_unlock_token::{
+        AuthorizeDebugUnlockTokenRequest, AuthorizeDebugUnlockTokenRequestHeader,
+        AuthorizeDebugUnlockTokenResponse, DEBUG_UNLOCK_TOKEN_SIZE, MAX_TOKEN_CHUNK_SIZE,
+    };
+    use mctp_vdm_common::message::clear_log::{ClearLogRequest, ClearLogResponse};
     use mctp_vdm_common::message::device_capabilities::{
         DeviceCapabilitiesRequest, DeviceCapabilitiesResponse,
     };
     use mctp_vdm_common::message::device_id::{DeviceIdRequest, DeviceIdResponse};
     use mctp_vdm_common::message::device_info::{DeviceInfoRequest, DeviceInfoResponse};
+    use mctp_vdm_common::message::export_csr::{CsrIndex, ExportCsrRequest, ExportCsrResponse};
     use mctp_vdm_common::message::firmware_version::{
         FirmwareVersionRequest, FirmwareVersionResponse,
     };
-    use mctp_vdm_common::protocol::header::VdmCompletionCode;
+    use mctp_vdm_common::message::get_certificate_state::{
+        CertificateState, GetCertificateStateRequest, GetCertificateStateResponse,
+    };
+    use mctp_vdm_common::message::get_log::{GetLogRequest, GetLogResponse, LogType};
+    use mctp_vdm_common::message::import_certificate::{
+        ImportCertificateRequest, ImportCertificateRequestHeader, ImportCertificateResponse,
+        MAX_CERT_SIZE,
+    };
+    use mctp_vdm_common::message::request_debug_unlock::{
+        RequestDebugUnlockRequest, MAX_DEBUG_UNLOCK_LEVEL, MIN_DEBUG_UNLOCK_LEVEL,
+    };
+    use mctp_vdm_common::protocol::header::{
+        VdmCompletionCode, CALIPTRA_PCI_VENDOR_ID, CALIPTRA_VDM_CMD_SET_VERSION,
+    };
+    use mctp_vdm_common::util::endpoint_uuid::endpoint_uuid;
     use mcu_hw_model::McuHwModel;
     use mcu_mbox_common::config;
+    use mcu_rom_common::LifecycleControllerState;
+    use mcu_testing_common::mctp_util::ctrl_protocol::{CmdCompletionCode, MCTPCtrlCmd};
     use mcu_testing_common::mctp_vdm_transport::{
         MctpVdmSocket, MctpVdmTransport, VdmClient, VdmTransportError,
     };
     use mcu_testing_common::{wait_for_runtime_start, MCU_RUNNING};
+    use openssl::asn1::{Asn1Integer, Asn1Time};
+    use openssl::bn::BigNum;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::hash::MessageDigest;
+    use openssl::nid::Nid;
+    use openssl::pkey::{HasPublic, PKey, PKeyRef, Private};
+    use openssl::x509::{X509Builder, X509NameBuilder, X509Req, X509ReqBuilder, X509};
     use random_port::PortPicker;
     use simple_logger::SimpleLogger;
     use std::process::exit;
@@ -33,9 +62,84 @@ pub mod test {
     /// Maximum buffer size for encoding VDM requests.
     const MAX_REQUEST_BUF_SIZE: usize = 1024;
 
+    /// Certificate slot of the IDevID ECC certificate.
+    const IDEV_ECC_CERT_SLOT: u32 = 0;
+
+    /// Size of an attestation log entry: metadata, SHA-384 measurement and SVN.
+    const ATTESTATION_LOG_ENTRY_SIZE: usize = 4 + 48 + 4;
+
+    /// Log type that is neither the debug log nor the attestation log.
+    const INVALID_LOG_TYPE: u32 = 2;
+
+    /// Creates a certificate for `public_key` with short names, so that a chain of two
+    /// certificates fits in a single Import Certificate request.
+    fn test_cert<T: HasPublic>(
+        public_key: &PKeyRef<T>,
+        subject: &str,
+        issuer_key: &PKeyRef<Private>,
+        issuer: &str,
+        serial: u32,
+    ) -> Vec<u8> {
+        let name = |common_name: &str| {
+            let mut name = X509NameBuilder::new().unwrap();
+            name.append_entry_by_nid(Nid::COMMONNAME, common_name)
+                .unwrap();
+            name.build()
+        };
+        let serial = Asn1Integer::from_bn(&BigNum::from_u32(serial).unwrap()).unwrap();
+
+        let mut cert = X509Builder::new().unwrap();
+        cert.set_version(2).unwrap();
+        cert.set_serial_number(&serial).unwrap();
+        cert.set_subject_name(&name(subject)).unwrap();
+        cert.set_issuer_name(&name(issuer)).unwrap();
+        cert.set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        cert.set_not_after(&Asn1Time::days_from_now(365).unwrap())
+            .unwrap();
+        cert.set_pubkey(public_key).unwrap();
+        cert.sign(issuer_key, MessageDigest::sha384()).unwrap();
+        cert.build().to_der().unwrap()
+    }
+
+    /// Generates an ECC P-384 key.
+    fn test_key() -> PKey<Private> {
+        let group = EcGroup::from_curve_name(Nid::SECP384R1).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    /// Endorses the key of the IDevID CSR with a test CA, and returns the IDevID certificate
+    /// followed by the CA certificate.
+    fn endorse_idev_csr(csr: &[u8]) -> Vec<u8> {
+        let ca_key = test_key();
+        idev_chain(csr, &ca_key, "Test CA", &ca_key)
+    }
+
+    /// Returns an IDevID certificate of `issuer` signed with `signer_key`, followed by the
+    /// certificate of the test CA with `ca_key`.
+    fn idev_chain(
+        csr: &[u8],
+        signer_key: &PKeyRef<Private>,
+        issuer: &str,
+        ca_key: &PKeyRef<Private>,
+    ) -> Vec<u8> {
+        let csr = X509Req::from_der(csr).unwrap();
+        let idev_cert = test_cert(
+            &csr.public_key().unwrap(),
+            "Test IDevID",
+            signer_key,
+            issuer,
+            2,
+        );
+        let ca_cert = test_cert(ca_key, "Test CA", ca_key, "Test CA", 1);
+        [idev_cert, ca_cert].concat()
+    }
+
     /// Test runner for VDM command tests.
     pub struct VdmCmdTest {
         client: VdmClient,
+        /// IDevID CSR exported by the Export CSR test
+        idev_csr: Vec<u8>,
     }
 
     impl VdmCmdTest {
@@ -43,6 +147,7 @@ pub mod test {
         pub fn new(socket: MctpVdmSocket) -> Self {
             Self {
                 client: VdmClient::new(socket),
+                idev_csr: Vec::new(),
             }
         }
 
@@ -229,6 +334,505 @@ pub mod test {
             Ok(())
         }
 
+        /// Test the MCTP Get Endpoint UUID control command.
+        fn test_get_endpoint_uuid(&mut self) -> Result<(), VdmTransportError> {
+            info!("Testing Get Endpoint UUID control command...");
+
+            let response = self
+                .client
+                .send_ctrl_request(MCTPCtrlCmd::GetEndpointUUID, &[])?;
+            // The UUID is derived from the unique ID registered by the VDM service
+            let mut expected = vec![CmdCompletionCode::Success as u8];
+            expected.extend_from_slice(&endpoint_uuid(&config::TEST_UID));
+            Self::assert_eq(&response, &expected, "Get Endpoint UUID response")?;
+            info!("  UUID: {:02x?} (matches expected)", &response[1..]);
+
+            Ok(())
+        }
+
+        /// Test the MCTP Get Vendor Defined Message Support control command.
+        fn test_get_vdm_support(&mut self) -> Result<(), VdmTransportError> {
+            info!("Testing Get Vendor Defined Message Support control command...");
+
+            // The Caliptra command set is the only one, so no more sets follow
+            let response = self
+                .client
+                .send_ctrl_request(MCTPCtrlCmd::GetVendorDefinedMsgSupport, &[0])?;
+            let mut expected = vec![CmdCompletionCode::Success as u8, 0xFF, 0x00];
+            expected.extend_from_slice(&CALIPTRA_PCI_VENDOR_ID.to_be_bytes());
+            expected.extend_from_slice(&CALIPTRA_VDM_CMD_SET_VERSION.to_be_bytes());
+            Self::assert_eq(&response, &expected, "Vendor ID set 0")?;
+            info!("  Vendor ID set 0: {:02x?} (matches expected)", response);
+
+            // Test invalid vendor ID set selector
+            let response = self
+                .client
+                .send_ctrl_request(MCTPCtrlCmd::GetVendorDefinedMsgSupport, &[1])?;
+            Self::assert_eq(
+                &response.first().copied(),
+                &Some(CmdCompletionCode::ErrorInvalidData as u8),
+                "Vendor ID set 1 completion code",
+            )?;
+            info!("  Invalid selector correctly returns ErrorInvalidData");
+
+            Ok(())
+        }
+
+        /// Test Export CSR command.
+        fn test_export_csr(&mut self) -> Result<(), VdmTransportError> {
+            info!("Testing Export CSR command...");
+
+            // Caliptra generates the CSR when booted in the manufacturing lifecycle
+            let request = ExportCsrRequest::new(CsrIndex::IdevEcc as u32);
+            let response: ExportCsrResponse = self.send_request_expect_success(&request)?;
+            let csr = response.data().to_vec();
+            let valid = X509Req::from_der(&csr)
+                .and_then(|req| req.verify(&req.public_key()?))
+                .unwrap_or(false);
+            if !valid {
+                info!("Invalid CSR: {:?}", csr);
+                return Err(VdmTransportError::InvalidResponse);
+            }
+            info!("  ECC CSR of {} bytes", csr.len());
+            self.idev_csr = csr;
+
+            // The MLDSA CSR cannot be exported
+            let request = ExportCsrRequest::new(CsrIndex::IdevMldsa as u32);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidData)?;
+            info!("  MLDSA index correctly returns InvalidData");
+
+            // Test invalid index
+            let request = ExportCsrRequest::new(99);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidData)?;
+            info!("  Invalid index correctly returns InvalidData");
+
+            Ok(())
+        }
+
+        /// Test Import Certificate and Get Certificate State commands.
+        fn test_import_certificate(&mut self) -> Result<(), VdmTransportError> {
+            info!("Testing Import Certificate and Get Certificate State commands...");
+
+            // Nothing is provisioned on a fresh flash
+            let response: GetCertificateStateResponse =
+                self.send_request_expect_success(&GetCertificateStateRequest::new())?;
+            let state = response.state;
+            Self::assert_eq(
+                &state,
+                &(CertificateState::NotProvisioned as u32),
+                "State before import",
+            )?;
+            Self::assert_eq(
+                &response.is_slot_provisioned(IDEV_ECC_CERT_SLOT),
+                &false,
+                "ECC slot before import",
+            )?;
+
+            // Empty and oversized certificates are rejected
+            let request = ImportCertificateRequestHeader::new(0);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidLength)?;
+            let request = ImportCertificateRequestHeader::new(MAX_CERT_SIZE as u32 + 1);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidLength)?;
+            info!("  Invalid certificate sizes correctly return InvalidLength");
+
+            // Chains that do not parse, or that endorse another key, are rejected
+            let request = ImportCertificateRequest::new(&[0x30, 0x03, 0x02, 0x01, 0x00]);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidData)?;
+            let other_key = test_key();
+            let other_csr = {
+                let mut req = X509ReqBuilder::new().unwrap();
+                req.set_pubkey(&other_key).unwrap();
+                req.sign(&other_key, MessageDigest::sha384()).unwrap();
+                req.build().to_der().unwrap()
+            };
+            let request = ImportCertificateRequest::new(&endorse_idev_csr(&other_csr));
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidData)?;
+
+            // Chains whose IDevID certificate is not issued by the next certificate are
+            // rejected: signed with another key, or naming another issuer
+            let ca_key = test_key();
+            let chain = idev_chain(&self.idev_csr, &other_key, "Test CA", &ca_key);
+            let request = ImportCertificateRequest::new(&chain);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidData)?;
+            let chain = idev_chain(&self.idev_csr, &ca_key, "Other CA", &ca_key);
+            let request = ImportCertificateRequest::new(&chain);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidData)?;
+
+            // Chains that do not end in a self-signed root are rejected
+            let chain = endorse_idev_csr(&self.idev_csr);
+            let idev_cert = X509::from_der(&chain).unwrap().to_der().unwrap();
+            let request = ImportCertificateRequest::new(&idev_cert);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidData)?;
+            let response: GetCertificateStateResponse =
+                self.send_request_expect_success(&GetCertificateStateRequest::new())?;
+            Self::assert_eq(
+                &response.is_slot_provisioned(IDEV_ECC_CERT_SLOT),
+                &false,
+                "ECC slot after rejected imports",
+            )?;
+            info!("  Invalid chains correctly return InvalidData");
+
+            let chain = endorse_idev_csr(&self.idev_csr);
+            let request = ImportCertificateRequest::new(&chain);
+            Self::assert_eq(&request.cert_size(), &chain.len(), "Chain size")?;
+            let _: ImportCertificateResponse = self.send_request_expect_success(&request)?;
+            info!("  Imported a chain of {} bytes", chain.len());
+
+            let response: GetCertificateStateResponse =
+                self.send_request_expect_success(&GetCertificateStateRequest::new())?;
+            let state = response.state;
+            let error_details = response.error_details;
+            Self::assert_eq(
+                &state,
+                &(CertificateState::Provisioned as u32),
+                "State after import",
+            )?;
+            Self::assert_eq(&error_details, &0, "Error details after import")?;
+            Self::assert_eq(
+                &response.is_slot_provisioned(IDEV_ECC_CERT_SLOT),
+                &true,
+                "ECC slot after import",
+            )?;
+            let provisioned_slots = response.provisioned_slots;
+            info!("  Provisioned slots: {:#x}", provisioned_slots);
+
+            // The provisioned chain cannot be replaced
+            let request = ImportCertificateRequest::new(&endorse_idev_csr(&self.idev_csr));
+            self.send_request_expect_error(&request, VdmCompletionCode::AlreadyProvisioned)?;
+            let response: GetCertificateStateResponse =
+                self.send_request_expect_success(&GetCertificateStateRequest::new())?;
+            let state = response.state;
+            Self::assert_eq(
+                &state,
+                &(CertificateState::Provisioned as u32),
+                "State after re-import",
+            )?;
+            info!("  Re-import of a provisioned slot correctly returns AlreadyProvisioned");
+
+            Ok(())
+        }
+
+        /// Read a whole log with chunked Get Log requests.
+        fn read_log(&mut self, log_type: LogType) -> Result<Vec<u8>, VdmTransportError> {
+            let mut log = Vec::new();
+            loop {
+                let request = GetLogRequest::new(log_type as u32, log.len() as u32);
+                let response: GetLogResponse = self.send_request_expect_success(&request)?;
+                if response.data_size() == 0 {
+                    return Ok(log);
+                }
+                log.extend_from_slice(response.data());
+            }
+        }
+
+        /// Split a log into the entries of its length-prefixed records.
+        fn log_entries(mut log: &[u8]) -> Result<Vec<Vec<u8>>, VdmTransportError> {
+            let mut entries = Vec::new();
+            while !log.is_empty() {
+                let (len, rest) = log
+                    .split_first_chunk::<4>()
+                    .ok_or(VdmTransportError::InvalidResponse)?;
+                let len = u32::from_le_bytes(*len) as usize;
+                if rest.len() < len {
+                    info!("Truncated log record of {} bytes", len);
+                    return Err(VdmTransportError::InvalidResponse);
+                }
+                entries.push(rest[..len].to_vec());
+                log = &rest[len..];
+            }
+            Ok(entries)
+        }
+
+        /// Test Get Log command.
+        fn test_get_log(&mut self) -> Result<(), VdmTransportError> {
+            info!("Testing Get Log command...");
+
+            // The debug log holds the entries recorded at boot and spans several chunks
+            let log = self.read_log(LogType::Debug)?;
+            let entries = Self::log_entries(&log)?;
+            let expected: Vec<Vec<u8>> = (0..config::TEST_DEBUG_LOG_ENTRY_COUNT)
+                .map(|index| config::test_debug_log_entry(index).to_vec())
+                .collect();
+            Self::assert_eq(&entries, &expected, "Debug log entries")?;
+            info!(
+                "  Read {} debug log entries ({} bytes)",
+                entries.len(),
+                log.len()
+            );
+
+            // A read from the middle of the log returns the same contents
+            let offset = log.len() / 2;
+            let request = GetLogRequest::new(LogType::Debug as u32, offset as u32);
+            let response: GetLogResponse = self.send_request_expect_success(&request)?;
+            Self::assert_eq(
+                &response.data(),
+                &&log[offset..],
+                "Debug log read from the middle",
+            )?;
+
+            // The attestation log holds one record per stashed measurement
+            let log = self.read_log(LogType::Attestation)?;
+            let entries = Self::log_entries(&log)?;
+            for entry in &entries {
+                Self::assert_eq(
+                    &entry.len(),
+                    &ATTESTATION_LOG_ENTRY_SIZE,
+                    "Attestation log entry size",
+                )?;
+            }
+            info!("  Read {} attestation log entries", entries.len());
+
+            // The measurement stashed by the user app at boot is the last one
+            let mut expected = config::TEST_ATTESTATION_LOG_METADATA.to_vec();
+            expected.extend_from_slice(&config::TEST_ATTESTATION_LOG_MEASUREMENT);
+            expected.extend_from_slice(&config::TEST_ATTESTATION_LOG_SVN.to_le_bytes());
+            Self::assert_eq(
+                &entries.last(),
+                &Some(&expected),
+                "Stashed attestation log entry",
+            )?;
+
+            let request = GetLogRequest::new(INVALID_LOG_TYPE, 0);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidData)?;
+            info!("  Invalid log type correctly returns InvalidData");
+
+            Ok(())
+        }
+
+        /// Test Clear Log command.
+        fn test_clear_log(&mut self) -> Result<(), VdmTransportError> {
+            info!("Testing Clear Log command...");
+
+            // The attestation log must match the measurements extended into PCR31
+            let request = ClearLogRequest::new(LogType::Attestation as u32);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidData)?;
+            let request = ClearLogRequest::new(INVALID_LOG_TYPE);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidData)?;
+            info!("  Attestation and invalid log types correctly return InvalidData");
+
+            let request = ClearLogRequest::new(LogType::Debug as u32);
+            let _: ClearLogResponse = self.send_request_expect_success(&request)?;
+            let log = self.read_log(LogType::Debug)?;
+            Self::assert_eq(&log.len(), &0, "Debug log size after clear")?;
+            info!("  Debug log cleared");
+
+            Ok(())
+        }
+
+        /// Send a token chunk and return the completion code and unlock level of the response.
+        fn send_token_chunk(
+            &mut self,
+            offset: usize,
+            chunk: &[u8],
+        ) -> Result<(VdmCompletionCode, u32), VdmTransportError> {
+            let request = AuthorizeDebugUnlockTokenRequest::new(offset as u32, chunk);
+            let mut request_buf = [0u8; MAX_REQUEST_BUF_SIZE];
+            let size = request
+                .encode(&mut request_buf)
+                .map_err(|_| VdmTransportError::CodecError)?;
+            let response_bytes = self.client.send_raw(&request_buf[..size])?;
+            let code = VdmClient::parse_completion_code(&response_bytes)?;
+            let response = AuthorizeDebugUnlockTokenResponse::decode(&response_bytes)
+                .map_err(|_| VdmTransportError::CodecError)?;
+            Ok((code, response.unlock_level))
+        }
+
+        /// Test Request Debug Unlock and Authorize Debug Unlock Token commands.
+        fn test_debug_unlock(&mut self) -> Result<(), VdmTransportError> {
+            info!("Testing Request Debug Unlock and Authorize Debug Unlock Token commands...");
+
+            // Unlock levels outside 1-8 are rejected
+            for unlock_level in [MIN_DEBUG_UNLOCK_LEVEL - 1, MAX_DEBUG_UNLOCK_LEVEL + 1] {
+                let request = RequestDebugUnlockRequest::new(unlock_level);
+                self.send_request_expect_error(&request, VdmCompletionCode::InvalidData)?;
+            }
+            info!("  Invalid unlock levels correctly return InvalidData");
+
+            let mut request = RequestDebugUnlockRequest::new(MIN_DEBUG_UNLOCK_LEVEL);
+            request.length += 1;
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidLength)?;
+            info!("  Invalid request length correctly returns InvalidLength");
+
+            // Empty and oversized chunks are rejected
+            let request = AuthorizeDebugUnlockTokenRequestHeader::new(0, 0);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidLength)?;
+            let request =
+                AuthorizeDebugUnlockTokenRequestHeader::new(0, MAX_TOKEN_CHUNK_SIZE as u32 + 1);
+            self.send_request_expect_error(&request, VdmCompletionCode::InvalidLength)?;
+            info!("  Invalid chunk sizes correctly return InvalidLength");
+
+            // Chunks must be sent in order
+            let (code, _) = self.send_token_chunk(MAX_TOKEN_CHUNK_SIZE, &[0u8; 16])?;
+            Self::assert_eq(&code, &VdmCompletionCode::InvalidData, "Out of order chunk")?;
+            info!("  Out of order chunk correctly returns InvalidData");
+
+            // The unlock request is only latched in the production lifecycle with DEBUG_INTENT
+            let request = RequestDebugUnlockRequest::new(MIN_DEBUG_UNLOCK_LEVEL);
+            self.send_request_expect_error(&request, VdmCompletionCode::UnsupportedCommand)?;
+            info!("  Debug unlock without DEBUG_INTENT correctly returns UnsupportedCommand");
+
+            // A token is rejected when no challenge was issued
+            let (code, unlock_level) = self.send_token(&[0u8; DEBUG_UNLOCK_TOKEN_SIZE])?;
+            Self::assert_eq(
+                &code,
+                &VdmCompletionCode::InvalidData,
+                "Token without challenge",
+            )?;
+            Self::assert_eq(&unlock_level, &0, "Unlock level without challenge")?;
+            info!("  Token without a challenge correctly returns InvalidData");
+
+            Ok(())
+        }
+
+        /// Send the token in chunks and return the completion code and unlock level of the
+        /// response to the last chunk.
+        fn send_token(
+            &mut self,
+            token: &[u8],
+        ) -> Result<(VdmCompletionCode, u32), VdmTransportError> {
+            let chunks: Vec<&[u8]> = token.chunks(MAX_TOKEN_CHUNK_SIZE).collect();
+            let (last_chunk, chunks) = chunks.split_last().unwrap();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let (code, unlock_level) =
+                    self.send_token_chunk(i * MAX_TOKEN_CHUNK_SIZE, chunk)?;
+                Self::assert_eq(&code, &VdmCompletionCode::Success, "Token chunk")?;
+                Self::assert_eq(&unlock_level, &0, "Unlock level before last chunk")?;
+            }
+            self.send_token_chunk(chunks.len() * MAX_TOKEN_CHUNK_SIZE, last_chunk)
+        }
+
+        /// Request the debug unlock challenge. The first request completes with NotReady and
+        /// resets the device into ROM, so the request is repeated until Caliptra returns the
+        /// challenge.
+        #[cfg(feature = "fpga_realtime")]
+        fn request_debug_unlock_challenge(
+            &mut self,
+            unlock_level: u8,
+        ) -> Result<caliptra_api::mailbox::ProductionAuthDebugUnlockChallenge, VdmTransportError>
+        {
+            use mctp_vdm_common::message::request_debug_unlock::{
+                RequestDebugUnlockResponse, DEBUG_UNLOCK_CHALLENGE_LENGTH,
+            };
+
+            const MAX_ATTEMPTS: usize = 10;
+
+            let request = RequestDebugUnlockRequest::new(unlock_level);
+            let mut request_buf = [0u8; MAX_REQUEST_BUF_SIZE];
+            let size = request
+                .encode(&mut request_buf)
+                .map_err(|_| VdmTransportError::CodecError)?;
+            for _ in 0..MAX_ATTEMPTS {
+                let response_bytes = match self.client.send_raw(&request_buf[..size]) {
+                    Ok(response_bytes) => response_bytes,
+                    // Requests sent while the device resets get no response
+                    Err(VdmTransportError::Timeout) => continue,
+                    Err(err) => return Err(err),
+                };
+                match VdmClient::parse_completion_code(&response_bytes)? {
+                    VdmCompletionCode::Success => {
+                        let response = RequestDebugUnlockResponse::decode(&response_bytes)
+                            .map_err(|_| VdmTransportError::CodecError)?;
+                        let length = response.length;
+                        Self::assert_eq(
+                            &length,
+                            &DEBUG_UNLOCK_CHALLENGE_LENGTH,
+                            "Challenge length",
+                        )?;
+                        return Ok(caliptra_api::mailbox::ProductionAuthDebugUnlockChallenge {
+                            unique_device_identifier: response.unique_device_identifier,
+                            challenge: response.challenge,
+                            ..Default::default()
+                        });
+                    }
+                    VdmCompletionCode::NotReady => continue,
+                    code => {
+                        info!("Expected Success or NotReady, got {:?}", code);
+                        return Err(VdmTransportError::InvalidResponse);
+                    }
+                }
+            }
+            info!("No debug unlock challenge after {} attempts", MAX_ATTEMPTS);
+            Err(VdmTransportError::Timeout)
+        }
+
+        /// Test production debug unlock with a token signed with the vendor test keys. The
+        /// device must be in the production lifecycle with DEBUG_INTENT set.
+        #[cfg(feature = "fpga_realtime")]
+        pub fn test_prod_debug_unlock(&mut self) -> Result<(), VdmTransportError> {
+            use caliptra_image_fake_keys::{
+                VENDOR_ECC_KEY_0_PRIVATE, VENDOR_ECC_KEY_0_PUBLIC, VENDOR_MLDSA_KEY_0_PRIVATE,
+                VENDOR_MLDSA_KEY_0_PUBLIC,
+            };
+            use fips204::ml_dsa_87::PrivateKey as MldsaPrivateKey;
+            use fips204::traits::SerDes;
+            use mcu_hw_model::debug_unlock::prod_debug_unlock_gen_signed_token;
+            use p384::SecretKey as EcdsaSecretKey;
+            use zerocopy::IntoBytes;
+
+            info!("Testing production debug unlock with a signed token...");
+
+            let mut ecc_private_key = [0u8; 48];
+            for (i, word) in VENDOR_ECC_KEY_0_PRIVATE.iter().enumerate() {
+                ecc_private_key[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+            }
+            let ecc_private_key = EcdsaSecretKey::from_slice(&ecc_private_key).unwrap();
+            let mut ecc_public_key = [0u32; 24];
+            ecc_public_key[..12].copy_from_slice(&VENDOR_ECC_KEY_0_PUBLIC.x);
+            ecc_public_key[12..].copy_from_slice(&VENDOR_ECC_KEY_0_PUBLIC.y);
+            let mldsa_private_key = MldsaPrivateKey::try_from_bytes(
+                VENDOR_MLDSA_KEY_0_PRIVATE.0.as_bytes().try_into().unwrap(),
+            )
+            .unwrap();
+
+            // A token signed over an old challenge is rejected
+            let challenge = self.request_debug_unlock_challenge(MIN_DEBUG_UNLOCK_LEVEL)?;
+            let mut stale_challenge = caliptra_api::mailbox::ProductionAuthDebugUnlockChallenge {
+                unique_device_identifier: challenge.unique_device_identifier,
+                challenge: challenge.challenge,
+                ..Default::default()
+            };
+            stale_challenge.challenge[0] ^= 0xff;
+            let token = prod_debug_unlock_gen_signed_token(
+                &stale_challenge,
+                MIN_DEBUG_UNLOCK_LEVEL,
+                &ecc_private_key,
+                &mldsa_private_key,
+                &ecc_public_key,
+                &VENDOR_MLDSA_KEY_0_PUBLIC.0,
+            )
+            .unwrap();
+            let (code, unlock_level) = self.send_token(&token.as_bytes()[4..])?;
+            Self::assert_eq(
+                &code,
+                &VdmCompletionCode::InvalidData,
+                "Token of old challenge",
+            )?;
+            Self::assert_eq(&unlock_level, &0, "Unlock level of rejected token")?;
+            info!("  Token of an old challenge correctly returns InvalidData");
+
+            // A token signed over the challenge is granted at its level
+            let challenge = self.request_debug_unlock_challenge(MIN_DEBUG_UNLOCK_LEVEL)?;
+            let token = prod_debug_unlock_gen_signed_token(
+                &challenge,
+                MIN_DEBUG_UNLOCK_LEVEL,
+                &ecc_private_key,
+                &mldsa_private_key,
+                &ecc_public_key,
+                &VENDOR_MLDSA_KEY_0_PUBLIC.0,
+            )
+            .unwrap();
+            let (code, unlock_level) = self.send_token(&token.as_bytes()[4..])?;
+            Self::assert_eq(&code, &VdmCompletionCode::Success, "Signed token")?;
+            Self::assert_eq(
+                &unlock_level,
+                &(MIN_DEBUG_UNLOCK_LEVEL as u32),
+                "Granted unlock level",
+            )?;
+            info!("  Signed token correctly unlocks debug at the requested level");
+
+            Ok(())
+        }
+
         /// Test unsupported command.
         fn test_unsupported_command(&mut self) -> Result<(), VdmTransportError> {
             info!("Testing unsupported command handling...");
@@ -254,12 +858,29 @@ pub mod test {
             self.test_get_device_id()?;
             self.test_get_device_info()?;
             self.test_get_device_capabilities()?;
+            self.test_get_endpoint_uuid()?;
+            self.test_get_vdm_support()?;
+            self.test_get_log()?;
+            self.test_clear_log()?;
+            self.test_debug_unlock()?;
             self.test_unsupported_command()?;
             Ok(())
         }
 
+        /// Run the certificate provisioning tests, which need the IDevID CSR generated in
+        /// the manufacturing lifecycle.
+        pub fn run_cert_provisioning_tests(&mut self) -> Result<(), VdmTransportError> {
+            self.test_export_csr()?;
+            self.test_import_certificate()?;
+            Ok(())
+        }
+
         /// Spawn test thread and run tests.
-        pub fn run(socket: MctpVdmSocket, debug_level: LevelFilter) {
+        pub fn run(
+            socket: MctpVdmSocket,
+            debug_level: LevelFilter,
+            tests: fn(&mut VdmCmdTest) -> Result<(), VdmTransportError>,
+        ) {
             std::thread::spawn(move || {
                 wait_for_runtime_start();
                 if !MCU_RUNNING.load(Ordering::Relaxed) {
@@ -272,7 +893,7 @@ pub mod test {
                 info!("Running MCTP VDM Command Tests");
                 let mut test = VdmCmdTest::new(socket);
 
-                if let Err(e) = test.run_all_tests() {
+                if let Err(e) = tests(&mut test) {
                     info!("VDM test failed: {:?}", e);
                     exit(-1);
                 } else {
@@ -286,6 +907,16 @@ pub mod test {
 
     /// Start VDM command test with the given feature.
     pub fn start_vdm_test(feature: &str, debug_level: LevelFilter) {
+        start_vdm_test_with(feature, None, debug_level, VdmCmdTest::run_all_tests);
+    }
+
+    /// Start the given VDM command tests with the given feature and lifecycle state.
+    pub fn start_vdm_test_with(
+        feature: &str,
+        lifecycle_controller_state: Option<LifecycleControllerState>,
+        debug_level: LevelFilter,
+        tests: fn(&mut VdmCmdTest) -> Result<(), VdmTransportError>,
+    ) {
         let lock = TEST_LOCK.lock().unwrap();
         lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
@@ -293,6 +924,12 @@ pub mod test {
         let mut hw = start_runtime_hw_model(TestParams {
             feature: Some(&feature),
             i3c_port: Some(PortPicker::new().random(true).pick().unwrap()),
+            lifecycle_controller_state,
+            // Production debug unlock is only tested in the production lifecycle
+            debug_intent: matches!(
+                lifecycle_controller_state,
+                Some(LifecycleControllerState::Prod)
+            ),
             ..Default::default()
         });
 
@@ -301,7 +938,7 @@ pub mod test {
         let vdm_transport =
             MctpVdmTransport::new(hw.i3c_port().unwrap(), hw.i3c_address().unwrap().into());
         let vdm_socket = vdm_transport.create_socket().unwrap();
-        VdmCmdTest::run(vdm_socket, debug_level);
+        VdmCmdTest::run(vdm_socket, debug_level, tests);
 
         let test = finish_runtime_hw_model(&mut hw);
 
@@ -316,4 +953,25 @@ pub mod test {
     fn test_mctp_vdm_cmds() {
         start_vdm_test("test-mctp-vdm-cmds", LevelFilter::Info);
     }
+
+    #[cfg(feature = "fpga_realtime")]
+    #[test]
+    fn test_mctp_vdm_prod_debug_unlock() {
+        start_vdm_test_with(
+            "test-mctp-vdm-cmds",
+            Some(LifecycleControllerState::Prod),
+            LevelFilter::Info,
+            VdmCmdTest::test_prod_debug_unlock,
+        );
+    }
+
+    #[test]
+    fn test_mctp_vdm_cert_provisioning() {
+        start_vdm_test_with(
+            "test-mctp-vdm-cmds",
+            Some(LifecycleControllerState::Dev),
+            LevelFilter::Info,
+            VdmCmdTest::run_cert_provisioning_tests,
+        );
+    }
 }
@@ -13,7 +13,7 @@ pub mod test {
     };
     use mcu_hw_model::McuHwModel;
     use mcu_mbox_common::messages::{
-        CmAesDecryptInitReq, CmAesDecryptUpdateReq, CmAesEncryptInitReq,
+        ClearLogReq, CmAesDecryptInitReq, CmAesDecryptUpdateReq, CmAesEncryptInitReq,
         CmAesEncryptInitRespHeader, CmAesEncryptUpdateReq, CmAesGcmDecryptFinalReq,
         CmAesGcmDecryptFinalRespHeader, CmAesGcmDecryptInitReq, CmAesGcmDecryptUpdateReq,
         CmAesGcmDecryptUpdateRespHeader, CmAesGcmEncryptFinalReq, CmAesGcmEncryptFinalRespHeader,
@@ -23,21 +23,22 @@ pub mod test {
         CmHkdfExtractReq, CmHmacKdfCounterReq, CmHmacReq, CmImportReq, CmKeyUsage,
         CmRandomGenerateReq, CmRandomStirReq, CmShaFinalReq, CmShaFinalResp, CmShaInitReq,
         CmShaUpdateReq, Cmk, DeviceCapsReq, DeviceCapsResp, DeviceIdReq, DeviceIdResp,
-        DeviceInfoReq, DeviceInfoResp, FirmwareVersionReq, FirmwareVersionResp, MailboxReqHeader,
-        MailboxRespHeader, MailboxRespHeaderVarSize, McuAesDecryptInitReq, McuAesDecryptUpdateReq,
-        McuAesEncryptInitReq, McuAesEncryptUpdateReq, McuAesGcmDecryptFinalReq,
-        McuAesGcmDecryptInitReq, McuAesGcmDecryptUpdateReq, McuAesGcmEncryptFinalReq,
-        McuAesGcmEncryptInitReq, McuAesGcmEncryptUpdateReq, McuCmDeleteReq, McuCmImportReq,
-        McuCmImportResp, McuCmStatusReq, McuCmStatusResp, McuEcdhFinishReq, McuEcdhFinishResp,
-        McuEcdhGenerateReq, McuEcdhGenerateResp, McuEcdsaCmkPublicKeyReq, McuEcdsaCmkPublicKeyResp,
-        McuEcdsaCmkSignReq, McuEcdsaCmkSignResp, McuEcdsaCmkVerifyReq, McuEcdsaCmkVerifyResp,
-        McuFipsPeriodicEnableReq, McuFipsPeriodicStatusReq, McuFipsPeriodicStatusResp,
-        McuFipsSelfTestGetResultsReq, McuFipsSelfTestStartReq, McuFipsSelfTestStartResp,
-        McuHkdfExpandReq, McuHkdfExpandResp, McuHkdfExtractReq, McuHkdfExtractResp,
-        McuHmacKdfCounterReq, McuHmacKdfCounterResp, McuHmacReq, McuMailboxReq, McuMailboxResp,
-        McuRandomGenerateReq, McuRandomStirReq, McuShaFinalReq, McuShaFinalResp, McuShaInitReq,
-        McuShaInitResp, McuShaUpdateReq, CMB_AES_GCM_ENCRYPTED_CONTEXT_SIZE,
-        CMB_ECDH_EXCHANGE_DATA_MAX_SIZE, DEVICE_CAPS_SIZE, MAX_CMB_DATA_SIZE,
+        DeviceInfoReq, DeviceInfoResp, FirmwareVersionReq, FirmwareVersionResp, GetLogReq, LogType,
+        MailboxReqHeader, MailboxRespHeader, MailboxRespHeaderVarSize, McuAesDecryptInitReq,
+        McuAesDecryptUpdateReq, McuAesEncryptInitReq, McuAesEncryptUpdateReq,
+        McuAesGcmDecryptFinalReq, McuAesGcmDecryptInitReq, McuAesGcmDecryptUpdateReq,
+        McuAesGcmEncryptFinalReq, McuAesGcmEncryptInitReq, McuAesGcmEncryptUpdateReq,
+        McuCmDeleteReq, McuCmImportReq, McuCmImportResp, McuCmStatusReq, McuCmStatusResp,
+        McuEcdhFinishReq, McuEcdhFinishResp, McuEcdhGenerateReq, McuEcdhGenerateResp,
+        McuEcdsaCmkPublicKeyReq, McuEcdsaCmkPublicKeyResp, McuEcdsaCmkSignReq, McuEcdsaCmkSignResp,
+        McuEcdsaCmkVerifyReq, McuEcdsaCmkVerifyResp, McuFipsPeriodicEnableReq,
+        McuFipsPeriodicStatusReq, McuFipsPeriodicStatusResp, McuFipsSelfTestGetResultsReq,
+        McuFipsSelfTestStartReq, McuFipsSelfTestStartResp, McuHkdfExpandReq, McuHkdfExpandResp,
+        McuHkdfExtractReq, McuHkdfExtractResp, McuHmacKdfCounterReq, McuHmacKdfCounterResp,
+        McuHmacReq, McuMailboxReq, McuMailboxResp, McuRandomGenerateReq, McuRandomStirReq,
+        McuShaFinalReq, McuShaFinalResp, McuShaInitReq, McuShaInitResp, McuShaUpdateReq,
+        CMB_AES_GCM_ENCRYPTED_CONTEXT_SIZE, CMB_ECDH_EXCHANGE_DATA_MAX_SIZE, DEVICE_CAPS_SIZE,
+        MAX_CMB_DATA_SIZE,
     };
     use mcu_testing_common::{
         emulator_ticks_elapsed, get_emulator_ticks, sleep_emulator_ticks, wait_for_runtime_start,
@@ -273,6 +274,7 @@ pub mod test {
                 self.add_hmac_tests()?;
                 self.add_hmac_kdf_counter_tests()?;
                 self.add_hkdf_tests()?;
+                self.add_log_tests()?;
                 Ok(())
             } else if feature == "test-mcu-mbox-fips-self-test" {
                 self.add_fips_self_test_tests()?;
@@ -2333,6 +2335,72 @@ pub mod test {
 
             Ok(expand_resp.0.okm)
         }
+
+        fn add_log_tests(&mut self) -> Result<(), ()> {
+            // The debug log holds the entries recorded at boot
+            let log = self.read_log(LogType::DebugLog)?;
+            let mut entries = Vec::new();
+            let mut records = log.as_slice();
+            while let Some((len, rest)) = records.split_first_chunk::<4>() {
+                let len = u32::from_le_bytes(*len) as usize;
+                assert!(rest.len() >= len, "Truncated debug log record");
+                entries.push(rest[..len].to_vec());
+                records = &rest[len..];
+            }
+            assert!(records.is_empty(), "Truncated debug log record header");
+            let expected: Vec<Vec<u8>> = (0..mcu_mbox_common::config::TEST_DEBUG_LOG_ENTRY_COUNT)
+                .map(|index| mcu_mbox_common::config::test_debug_log_entry(index).to_vec())
+                .collect();
+            assert_eq!(entries, expected);
+
+            // The attestation log cannot be cleared, the debug log can
+            let resp = self.clear_log(LogType::AttestationLog)?;
+            assert_eq!(resp.status_code, MbxCmdStatus::Failure as u32);
+            let resp = self.clear_log(LogType::DebugLog)?;
+            assert_eq!(resp.status_code, MbxCmdStatus::Complete as u32);
+            assert!(self.read_log(LogType::DebugLog)?.is_empty());
+            Ok(())
+        }
+
+        /// Read a whole log with chunked MC_GET_LOG requests.
+        fn read_log(&mut self, log_type: LogType) -> Result<Vec<u8>, ()> {
+            const VAR_HEADER_SIZE: usize = std::mem::size_of::<MailboxRespHeaderVarSize>();
+            let mut log = Vec::new();
+            loop {
+                let mut get_log_req = McuMailboxReq::GetLog(GetLogReq {
+                    hdr: MailboxReqHeader::default(),
+                    log_type: log_type as u32,
+                    offset: log.len() as u32,
+                });
+                get_log_req.populate_chksum().unwrap();
+
+                let resp = self
+                    .process_message(get_log_req.cmd_code().0, get_log_req.as_bytes().unwrap())
+                    .map_err(|_| ())?;
+                assert_eq!(resp.status_code, MbxCmdStatus::Complete as u32);
+                let resp_hdr =
+                    MailboxRespHeaderVarSize::read_from_bytes(&resp.data[..VAR_HEADER_SIZE])
+                        .map_err(|_| ())?;
+                let data_len = resp_hdr.data_len as usize;
+                if data_len == 0 {
+                    return Ok(log);
+                }
+                log.extend_from_slice(&resp.data[VAR_HEADER_SIZE..VAR_HEADER_SIZE + data_len]);
+            }
+        }
+
+        fn clear_log(&mut self, log_type: LogType) -> Result<McuMailboxResponse, ()> {
+            let mut clear_log_req = McuMailboxReq::ClearLog(ClearLogReq {
+                hdr: MailboxReqHeader::default(),
+                log_type: log_type as u32,
+            });
+            clear_log_req.populate_chksum().unwrap();
+            self.process_message(
+                clear_log_req.cmd_code().0,
+                clear_log_req.as_bytes().unwrap(),
+            )
+            .map_err(|_| ())
+        }
     }
 
     /// Helper function to perform ECDSA signing using RustCrypto.
@@ -16,6 +16,8 @@ pub mod test {
         emulator_ticks_elapsed, get_emulator_ticks, sleep_emulator_ticks, wait_for_runtime_start,
         MCU_RUNNING,
     };
+    use pldm_common::message::firmware_update::activate_fw::ActivateFirmwareResponse;
+    use pldm_common::message::firmware_update::get_fw_params::GetFirmwareParametersResponse;
     use pldm_common::protocol::firmware_update::*;
     use pldm_fw_pkg::{
         manifest::{
@@ -27,14 +29,65 @@ pub mod test {
     use pldm_ua::daemon::Options;
     use pldm_ua::daemon::PldmDaemon;
     use pldm_ua::transport::{EndpointId, PldmSocket, PldmTransport};
+    use pldm_ua::update_sm::StateMachineActions;
     use pldm_ua::{discovery_sm, update_sm};
     use random_port::PortPicker;
     use simple_logger::SimpleLogger;
     use std::process::exit;
     use std::sync::atomic::Ordering;
+    use std::sync::{Arc, Mutex};
     use uuid::Uuid;
 
+    type PldmFwUpdateTestFn = fn(&mut PldmFwUpdateTest, LevelFilter) -> Result<(), ()>;
+
     pub fn start_pldm_test(feature: &str, debug_level: LevelFilter) {
+        start_pldm_test_with(feature, debug_level, PldmFwUpdateTest::test_fw_update);
+    }
+
+    pub fn start_pldm_test_with(
+        feature: &str,
+        debug_level: LevelFilter,
+        test_fn: PldmFwUpdateTestFn,
+    ) {
+        let lock = TEST_LOCK.lock().unwrap();
+        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+        let feature = feature.replace("_", "-");
+        let mut hw = start_runtime_hw_model(TestParams {
+            feature: Some(&feature),
+            i3c_port: Some(PortPicker::new().random(true).pick().unwrap()),
+            ..Default::default()
+        });
+
+        hw.start_i3c_controller();
+
+        let pldm_transport =
+            MctpTransport::new(hw.i3c_port().unwrap(), hw.i3c_address().unwrap().into());
+        let pldm_socket = pldm_transport
+            .create_socket(EndpointId(8), EndpointId(0))
+            .unwrap();
+        PldmFwUpdateTest::run(pldm_socket, debug_level, test_fn);
+
+        let mci_ptr = hw.base.mmio.mci().unwrap().ptr as u64;
+        run_imaginary_flash_controller_service(mci_ptr);
+
+        let test = finish_runtime_hw_model(&mut hw);
+
+        assert_eq!(0, test);
+        MCU_RUNNING.store(false, Ordering::Relaxed);
+
+        // force the compiler to keep the lock
+        lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Runs `test_fn` without exiting once it passes, then resets the device and waits for the
+    /// firmware to exit. The firmware running before the reset keeps serving PLDM after an update
+    /// left pending, so the exit shows that the reset booted the pending image.
+    pub fn start_pldm_test_with_reset(
+        feature: &str,
+        debug_level: LevelFilter,
+        test_fn: PldmFwUpdateTestFn,
+    ) {
         let lock = TEST_LOCK.lock().unwrap();
         lock.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
@@ -52,11 +105,16 @@ pub mod test {
         let pldm_socket = pldm_transport
             .create_socket(EndpointId(8), EndpointId(0))
             .unwrap();
-        PldmFwUpdateTest::run(pldm_socket, debug_level);
+        let test_thread = PldmFwUpdateTest::spawn(pldm_socket, debug_level, test_fn);
 
         let mci_ptr = hw.base.mmio.mci().unwrap().ptr as u64;
         run_imaginary_flash_controller_service(mci_ptr);
 
+        hw.step_until(|_| test_thread.is_finished());
+        assert!(test_thread.join().unwrap().is_ok());
+
+        println!("Emulator: Resetting the device to activate the pending image");
+        hw.warm_reset();
         let test = finish_runtime_hw_model(&mut hw);
 
         assert_eq!(0, test);
@@ -71,6 +129,15 @@ pub mod test {
         start_pldm_test("test-pldm-fw-update-e2e", LevelFilter::Debug);
     }
 
+    #[test]
+    fn test_fw_update_e2e_pending_image() {
+        start_pldm_test_with(
+            "test-pldm-fw-update-e2e",
+            LevelFilter::Debug,
+            PldmFwUpdateTest::test_fw_update_pending_image,
+        );
+    }
+
     pub const DEVICE_UUID: [u8; 16] = [
         0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
         0x10,
@@ -130,11 +197,37 @@ pub mod test {
         };
     }
 
+    /// Update SM actions that record what the device reports, then proceed as the default actions.
+    #[derive(Clone, Default)]
+    struct UpdateSmRecorder {
+        fw_params: Arc<Mutex<Option<GetFirmwareParametersResponse>>>,
+        estimated_activation_time: Arc<Mutex<Option<u16>>>,
+    }
+
+    impl update_sm::StateMachineActions for UpdateSmRecorder {
+        fn on_get_firmware_parameters_response(
+            &mut self,
+            ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+            response: GetFirmwareParametersResponse,
+        ) -> Result<(), ()> {
+            *self.fw_params.lock().unwrap() = Some(response.clone());
+            update_sm::DefaultActions {}.on_get_firmware_parameters_response(ctx, response)
+        }
+
+        fn on_activate_firmware_response(
+            &mut self,
+            ctx: &mut update_sm::InnerContext<impl PldmSocket + Send + 'static>,
+            response: ActivateFirmwareResponse,
+        ) -> Result<(), ()> {
+            *self.estimated_activation_time.lock().unwrap() =
+                Some(response.estimated_time_activation);
+            update_sm::DefaultActions {}.on_activate_firmware_response(ctx, response)
+        }
+    }
+
     pub struct PldmFwUpdateTest {
         socket: MctpPldmSocket,
-        daemon: Option<
-            PldmDaemon<MctpPldmSocket, discovery_sm::DefaultActions, update_sm::DefaultActions>,
-        >,
+        daemon: Option<PldmDaemon<MctpPldmSocket, discovery_sm::DefaultActions, UpdateSmRecorder>>,
     }
 
     impl PldmFwUpdateTest {
@@ -177,35 +270,55 @@ pub mod test {
             }
         }
 
-        #[allow(clippy::result_unit_err)]
-        pub fn test_fw_update(&mut self, debug_level: LevelFilter) -> Result<(), ()> {
-            // Initialize log level to info (only once)
-            let _ = SimpleLogger::new().with_level(debug_level).init();
-
-            let pldm_fw_pkg = if let Ok(pldm_fw_pkg_path) = std::env::var("PLDM_FW_PKG") {
-                FirmwareManifest::decode_firmware_package(&pldm_fw_pkg_path, None).map_err(|e| {
-                    error!(
-                        "Failed to decode PLDM FW package from {}: {:?}",
-                        pldm_fw_pkg_path, e
-                    );
-                })?
-            } else {
-                PLDM_FW_PKG.clone()
-            };
-
-            // Run the PLDM daemon
+        fn start_update(
+            &mut self,
+            pldm_fw_pkg: FirmwareManifest,
+            recorder: &UpdateSmRecorder,
+        ) -> Result<(), ()> {
             self.daemon = Some(
                 PldmDaemon::run(
                     self.socket.clone(),
                     Options {
                         pldm_fw_pkg: Some(pldm_fw_pkg),
                         discovery_sm_actions: discovery_sm::DefaultActions {},
-                        update_sm_actions: update_sm::DefaultActions {},
+                        update_sm_actions: recorder.clone(),
                         fd_tid: 0x01,
                     },
                 )
                 .map_err(|_| ())?,
             );
+            Ok(())
+        }
+
+        /// Returns the package given by PLDM_FW_PKG if set, otherwise the default package, and
+        /// whether the default package is used.
+        fn pldm_fw_pkg() -> Result<(FirmwareManifest, bool), ()> {
+            if let Ok(pldm_fw_pkg_path) = std::env::var("PLDM_FW_PKG") {
+                let pldm_fw_pkg =
+                    FirmwareManifest::decode_firmware_package(&pldm_fw_pkg_path, None).map_err(
+                        |e| {
+                            error!(
+                                "Failed to decode PLDM FW package from {}: {:?}",
+                                pldm_fw_pkg_path, e
+                            );
+                        },
+                    )?;
+                Ok((pldm_fw_pkg, false))
+            } else {
+                Ok((PLDM_FW_PKG.clone(), true))
+            }
+        }
+
+        #[allow(clippy::result_unit_err)]
+        pub fn test_fw_update(&mut self, debug_level: LevelFilter) -> Result<(), ()> {
+            // Initialize log level to info (only once)
+            let _ = SimpleLogger::new().with_level(debug_level).init();
+
+            let (pldm_fw_pkg, is_default_pkg) = Self::pldm_fw_pkg()?;
+
+            // Run the PLDM daemon
+            let recorder = UpdateSmRecorder::default();
+            self.start_update(pldm_fw_pkg, &recorder)?;
 
             // Modify the expected state to the one that the test will reach.
             // Note that the UA state machine will not progress if it receives an unexpected response from the device.
@@ -213,10 +326,93 @@ pub mod test {
 
             self.daemon.as_mut().unwrap().stop();
 
-            res
+            res?;
+
+            // The package requests self-contained activation, so the device reports the time it needs
+            if is_default_pkg {
+                let estimated_time = *recorder.estimated_activation_time.lock().unwrap();
+                if !estimated_time.is_some_and(|time| time > 0) {
+                    error!("Unexpected estimated activation time {:?}", estimated_time);
+                    return Err(());
+                }
+            }
+            Ok(())
+        }
+
+        /// Updates the device without self-contained activation, then checks that a new update
+        /// session reads the applied image as pending in GetFirmwareParameters.
+        #[allow(clippy::result_unit_err)]
+        pub fn test_fw_update_pending_image(&mut self, debug_level: LevelFilter) -> Result<(), ()> {
+            let _ = SimpleLogger::new().with_level(debug_level).init();
+
+            let (mut pldm_fw_pkg, _) = Self::pldm_fw_pkg()?;
+            pldm_fw_pkg.component_image_information[0].requested_activation_method = 0;
+            let component = pldm_fw_pkg.component_image_information[0].clone();
+            let expected_image_set_ver = pldm_fw_pkg.firmware_device_id_records[0]
+                .component_image_set_version_string
+                .clone()
+                .unwrap_or_default();
+
+            let recorder = UpdateSmRecorder::default();
+            self.start_update(pldm_fw_pkg.clone(), &recorder)?;
+            let res = self.wait_for_state_transition(update_sm::States::Done);
+            self.daemon.as_mut().unwrap().stop();
+            res?;
+
+            // Start a new update session and stop it once the firmware parameters are read
+            let recorder = UpdateSmRecorder::default();
+            self.start_update(pldm_fw_pkg, &recorder)?;
+            let timeout_ticks: u64 = 1_800_000_000;
+            let start_ticks = get_emulator_ticks();
+            while recorder.fw_params.lock().unwrap().is_none()
+                && !emulator_ticks_elapsed(start_ticks, timeout_ticks)
+            {
+                sleep_emulator_ticks(100_000);
+            }
+            self.daemon.as_mut().unwrap().stop();
+
+            let Some(fw_params) = recorder.fw_params.lock().unwrap().take() else {
+                error!("Timed out waiting for the firmware parameters");
+                return Err(());
+            };
+            let params = &fw_params.parms;
+            let image_set_ver = params.pending_comp_image_set_ver_str.ok_or(())?;
+            let len = params.params_fixed.pending_comp_image_set_ver_str_len as usize;
+            if image_set_ver[..len] != *expected_image_set_ver.as_bytes() {
+                error!("Unexpected pending image set version");
+                return Err(());
+            }
+
+            let entry = &params.comp_param_table[0];
+            let fixed = &entry.comp_param_entry_fixed;
+            let ver_str = entry.pending_comp_ver_str.ok_or(())?;
+            let len = fixed.pending_comp_ver_str_len as usize;
+            if { fixed.pending_comp_comparison_stamp } != component.comparison_stamp.unwrap()
+                || ver_str[..len] != *component.version_string.unwrap().as_bytes()
+            {
+                error!("Unexpected pending component version");
+                return Err(());
+            }
+            Ok(())
+        }
+
+        /// Runs `test_fn` in a thread and returns its result without exiting the process.
+        pub fn spawn(
+            socket: MctpPldmSocket,
+            debug_level: LevelFilter,
+            test_fn: PldmFwUpdateTestFn,
+        ) -> std::thread::JoinHandle<Result<(), ()>> {
+            std::thread::spawn(move || {
+                wait_for_runtime_start();
+                if !MCU_RUNNING.load(Ordering::Relaxed) {
+                    return Err(());
+                }
+                let mut test = PldmFwUpdateTest::new(socket);
+                test_fn(&mut test, debug_level)
+            })
         }
 
-        pub fn run(socket: MctpPldmSocket, debug_level: LevelFilter) {
+        pub fn run(socket: MctpPldmSocket, debug_level: LevelFilter, test_fn: PldmFwUpdateTestFn) {
             std::thread::spawn(move || {
                 wait_for_runtime_start();
                 if !MCU_RUNNING.load(Ordering::Relaxed) {
@@ -224,7 +420,7 @@ pub mod test {
                 }
                 print!("Emulator: Running PLDM Loopback Test: ",);
                 let mut test = PldmFwUpdateTest::new(socket);
-                if test.test_fw_update(debug_level).is_err() {
+                if test_fn(&mut test, debug_level).is_err() {
                     println!("Failed");
                     exit(-1);
                 } else {